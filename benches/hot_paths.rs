@@ -0,0 +1,109 @@
+// Hot Path Benchmarks - The Data Path Between a Tick and a Signal
+// Every tick is folded into a candle, every candle re-evaluates the conditions of every
+// hypothesis watching the symbol, every test result re-scores a pattern's Sharpe, and every
+// price moves through the resting orders of the paper venue's book. A regression in any of
+// these turns into signal latency. `cargo bench` runs them; criterion keeps the last run under
+// target/criterion and reports what changed against it
+
+use std::hint::black_box;
+use chrono::{DateTime, Duration, Utc};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rust_decimal_macros::dec;
+use tokio::runtime::Runtime;
+
+use v26meme::core::discovery_engine::{test_sharpe, Hypothesis, TestResult};
+use v26meme::core::evaluator::{self, Candle, TradeTick};
+use v26meme::core::exchange::{Exchange, OrderRequest, OrderType, PaperExchange, Side};
+use v26meme::core::Decimal;
+
+/// One trading day of DOGE-like prints, twenty a minute
+fn ticks() -> Vec<TradeTick> {
+    let start: DateTime<Utc> = "2026-03-14T00:00:00Z".parse().unwrap();
+    (0..1440 * 20).map(|i| TradeTick {
+        timestamp: start + Duration::seconds(i * 3),
+        price: 0.08 * (1.0 + 0.02 * (i as f64 / 700.0).sin() + 0.001 * (i as f64 / 3.0).cos()),
+        volume: 1_000.0 + (i % 37) as f64 * 150.0,
+    }).collect()
+}
+
+/// The pump fixture's hypothesis: a price and a volume entry condition, a price exit
+fn hypothesis() -> Hypothesis {
+    let fixture: serde_json::Value = serde_json::from_str(include_str!("../tests/fixtures/evaluation/doge_pump.json")).unwrap();
+    serde_json::from_value(fixture["hypothesis"].clone()).unwrap()
+}
+
+fn candle_aggregation(c: &mut Criterion) {
+    let ticks = ticks();
+    let mut group = c.benchmark_group("candle_aggregation");
+    group.throughput(Throughput::Elements(ticks.len() as u64));
+    group.bench_function("one_day_of_ticks", |b| b.iter(|| evaluator::aggregate(black_box(&ticks))));
+    group.finish();
+}
+
+fn condition_evaluation(c: &mut Criterion) {
+    let candles: Vec<Candle> = evaluator::aggregate(&ticks());
+    let hypothesis = hypothesis();
+    let last = candles.len() - 1;
+
+    let mut group = c.benchmark_group("condition_evaluation");
+    group.bench_function("entry_conditions_one_minute", |b| b.iter(|| {
+        hypothesis.entry_conditions.iter()
+            .all(|condition| evaluator::evaluate_condition(condition, black_box(&candles), last).holds)
+    }));
+    group.throughput(Throughput::Elements(candles.len() as u64));
+    group.bench_function("hypothesis_over_one_day", |b| b.iter(|| evaluator::evaluate(&hypothesis, black_box(&candles))));
+    group.finish();
+}
+
+fn sharpe(c: &mut Criterion) {
+    let results: Vec<TestResult> = (0..1_000).map(|i| {
+        let profit = Decimal::new((i * 7919) % 200, 2) - dec!(0.9);
+        TestResult {
+            capital: dec!(5),
+            profitable: profit > Decimal::ZERO,
+            profit,
+            entry_price: dec!(0.08),
+            exit_price: dec!(0.08) * (Decimal::ONE + profit / dec!(5)),
+            duration_seconds: 300,
+            fees: dec!(0.03),
+            slippage: dec!(0.01),
+        }
+    }).collect();
+
+    let mut group = c.benchmark_group("sharpe");
+    group.throughput(Throughput::Elements(results.len() as u64));
+    group.bench_function("test_sharpe_1000_results", |b| b.iter(|| test_sharpe(black_box(&results))));
+    group.finish();
+}
+
+/// Ticks through the paper venue's book of resting limit buys; none crosses, so it stays the same size
+fn order_book_updates(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("order_book_updates");
+    for resting in [10, 100, 1_000] {
+        let paper = PaperExchange::new("paper", 0.006).with_balance("USD", dec!(1_000_000_000));
+        paper.update_price("DOGE-USD", 0.08);
+        runtime.block_on(async {
+            for i in 0..resting {
+                paper.submit_order(&OrderRequest {
+                    client_order_id: format!("bench-{}", i),
+                    symbol: "DOGE-USD".to_string(),
+                    side: Side::Buy,
+                    size: Decimal::TEN,
+                    order_type: OrderType::Limit(dec!(0.07) - Decimal::new(i, 5)),
+                    reduce_only: false,
+                }).await.unwrap();
+            }
+        });
+        // The price moves every tick without ever reaching the highest bid at 0.07
+        let mut step = 0u32;
+        group.bench_function(format!("tick_over_{}_resting_orders", resting), |b| b.iter(|| {
+            step = step.wrapping_add(1);
+            paper.update_price("DOGE-USD", black_box(0.075 + (step % 1_000) as f64 * 1e-6));
+        }));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, candle_aggregation, condition_evaluation, sharpe, order_book_updates);
+criterion_main!(benches);
@@ -0,0 +1,78 @@
+use v26meme::core::interchange::{self, BundleFormat, PatternBundle};
+use v26meme::core::secrets;
+
+const USAGE: &str = "Usage:
+  interchange export <file.json|file.toml> [--active-only]
+  interchange import <file.json|file.toml> [--keep-stats]
+  interchange validate <file.json|file.toml>";
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    secrets::init().await.expect("Failed to load secrets");
+    
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (command, path) = match (args.first(), args.get(1)) {
+        (Some(command), Some(path)) => (command.as_str(), path.as_str()),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+    let format = BundleFormat::from_path(path);
+    
+    // Validation is offline - no database needed
+    if command == "validate" {
+        let text = std::fs::read_to_string(path).expect("Failed to read bundle");
+        match PatternBundle::parse(&text, format) {
+            Ok(bundle) => println!("✅ {} hypotheses, {} patterns, format v{}",
+                bundle.hypotheses.len(), bundle.patterns.len(), bundle.version),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    
+    let database_url = secrets::var("DATABASE_URL")
+        .expect("DATABASE_URL must be set");
+    let db_pool = sqlx::PgPool::connect(&database_url)
+        .await
+        .expect("Failed to connect to database");
+    
+    match command {
+        "export" => {
+            let active_only = args.iter().any(|a| a == "--active-only");
+            let bundle = interchange::export_bundle(&db_pool, active_only)
+                .await
+                .expect("Failed to export patterns");
+            let text = bundle.to_text(format).expect("Failed to serialize bundle");
+            std::fs::write(path, text).expect("Failed to write bundle");
+            
+            println!("📦 Exported {} hypotheses and {} patterns to {}",
+                bundle.hypotheses.len(), bundle.patterns.len(), path);
+        }
+        "import" => {
+            let text = std::fs::read_to_string(path).expect("Failed to read bundle");
+            let bundle = match PatternBundle::parse(&text, format) {
+                Ok(bundle) => bundle,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let keep_stats = args.iter().any(|a| a == "--keep-stats");
+            let imported = interchange::import_bundle(&db_pool, &bundle, keep_stats)
+                .await
+                .expect("Failed to import bundle");
+            
+            println!("📥 Imported {} new hypotheses ({} already known)",
+                imported, (bundle.hypotheses.len() + bundle.patterns.len()) as u64 - imported);
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use v26meme::core::secrets;
+use v26meme::intelligence::PromptStore;
+
+const USAGE: &str = "Usage:
+  prompts list [task]
+  prompts show <task> <version>
+  prompts add <task> <file> [notes]
+  prompts activate <task> <version>";
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    secrets::init().await.expect("Failed to load secrets");
+    
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(command) = args.first() else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+    let task = args.get(1).map(String::as_str);
+    let version = args.get(2).and_then(|v| v.parse::<i32>().ok());
+    
+    let database_url = secrets::var("DATABASE_URL")
+        .expect("DATABASE_URL must be set");
+    let db_pool = sqlx::PgPool::connect(&database_url)
+        .await
+        .expect("Failed to connect to database");
+    let store = PromptStore::new(db_pool);
+    
+    match (command.as_str(), task) {
+        ("list", _) => {
+            let versions = store.versions(task).await.expect("Failed to load prompt versions");
+            let mut current_task = String::new();
+            let mut stats = Vec::new();
+            for v in versions {
+                if v.task != current_task {
+                    stats = store.version_stats(&v.task).await.expect("Failed to load prompt stats");
+                    current_task = v.task.clone();
+                    println!("📝 {}", current_task);
+                }
+                let s = stats.iter().find(|s| s.version == v.version).cloned().unwrap_or_default();
+                let seeded = match s.seeded_win_rate {
+                    Some(win_rate) => format!(", seeded {} tests at {:.1}% win", s.seeded_tests, win_rate * 100.0),
+                    None => String::new(),
+                };
+                println!("  {} v{:<3} {:<8} {} calls, {:.0}% ok, {:.0} tokens avg, ${:.4}{}{}",
+                    if v.is_active { "*" } else { " " }, v.version, v.source.as_str(),
+                    s.calls, s.success_rate * 100.0, s.avg_tokens, s.cost_usd, seeded,
+                    v.notes.map(|n| format!(" - {}", n)).unwrap_or_default());
+            }
+        }
+        ("show", Some(task)) => {
+            let Some(version) = version else {
+                eprintln!("{}", USAGE);
+                std::process::exit(2);
+            };
+            let versions = store.versions(Some(task)).await.expect("Failed to load prompt versions");
+            match versions.into_iter().find(|v| v.version == version) {
+                Some(v) => println!("{}", v.system_prompt),
+                None => {
+                    eprintln!("❌ {} has no v{}", task, version);
+                    std::process::exit(1);
+                }
+            }
+        }
+        ("add", Some(task)) => {
+            let Some(path) = args.get(2) else {
+                eprintln!("{}", USAGE);
+                std::process::exit(2);
+            };
+            let text = std::fs::read_to_string(path).expect("Failed to read prompt file");
+            let notes = args.get(3).map(String::as_str);
+            let version = store.add_version(task, text.trim(), notes)
+                .await
+                .expect("Failed to add prompt version");
+            
+            println!("📝 {} v{} stored (inactive until `prompts activate {} {}`)", task, version, task, version);
+        }
+        ("activate", Some(task)) => {
+            let Some(version) = version else {
+                eprintln!("{}", USAGE);
+                std::process::exit(2);
+            };
+            if store.activate(task, version).await.expect("Failed to activate prompt version") {
+                println!("✅ {} v{} is live from the next call", task, version);
+            } else {
+                eprintln!("❌ {} has no v{}", task, version);
+                std::process::exit(1);
+            }
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use tracing::{info, warn, error};
+use std::sync::Arc;
+use v26meme::core::{alerts::AlertManager, incidents::IncidentManager, risk_manager::RiskManager, secrets, telemetry, Decimal};
+use v26meme::config::AppConfig;
+
+#[tokio::main]
+async fn main() {
+    secrets::init().await.expect("Failed to load secrets");
+    let _telemetry = telemetry::init();
+    info!("🛡️ Starting V26MEME Risk Manager");
+    
+    let risk_config = match AppConfig::load() {
+        Ok(config) => config.risk,
+        Err(e) => {
+            error!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+    let mut risk_manager = RiskManager::with_config(Decimal::from(200), risk_config); // Starting with $200
+    risk_manager.set_alert_manager(Arc::new(AlertManager::from_env()));
+    risk_manager.set_incident_manager(Arc::new(IncidentManager::from_env()));
+    
+    // Keep the risk manager running and monitoring
+    loop {
+        if !risk_manager.check_risk_limits() {
+            warn!("⚠️ Risk limits triggered, waiting...");
+        }
+        
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+    }
+}
@@ -0,0 +1,48 @@
+use v26meme::core::embedding::PatternIndex;
+use v26meme::core::secrets;
+
+const USAGE: &str = "Usage:
+  similar_patterns <pattern_hash> [k]
+  similar_patterns --backfill";
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    secrets::init().await.expect("Failed to load secrets");
+    
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(target) = args.first() else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+    let k: i64 = args.get(1).and_then(|v| v.parse().ok()).unwrap_or(10);
+    
+    let database_url = secrets::var("DATABASE_URL")
+        .expect("DATABASE_URL must be set");
+    let db_pool = sqlx::PgPool::connect(&database_url)
+        .await
+        .expect("Failed to connect to database");
+    let index = PatternIndex::new(db_pool);
+    
+    if target == "--backfill" {
+        let embedded = index.backfill(i64::MAX).await.expect("Failed to backfill embeddings");
+        println!("🧭 Embedded {} patterns", embedded);
+        return;
+    }
+    
+    let neighbors = match index.similar_to(target, k).await.expect("Failed to query embeddings") {
+        Some(neighbors) => neighbors,
+        None => {
+            eprintln!("❌ Unknown pattern {}", target);
+            std::process::exit(1);
+        }
+    };
+    
+    println!("🧭 {} patterns nearest {}", neighbors.len(), target);
+    println!("{:<18} {:>9} {:>7} {:>8} {:>7}  active", "pattern", "distance", "tests", "win", "sharpe");
+    for n in neighbors {
+        println!("{:<18} {:>9.4} {:>7} {:>7.1}% {:>7.2}  {}",
+            n.pattern_hash, n.distance, n.test_count, n.win_rate * 100.0, n.sharpe_ratio,
+            if n.is_active { "yes" } else { "no" });
+    }
+}
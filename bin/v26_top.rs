@@ -0,0 +1,295 @@
+// v26-top - Terminal Dashboard
+// For operators on a box without a browser. Live state (capital, open positions, fills and
+// new patterns) comes from the websocket feed; breakers, drawdown and the pattern leaderboard
+// are polled from the control API every few seconds. Read-only: it never changes anything
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use futures_util::StreamExt;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Row, Table};
+use ratatui::Frame;
+use serde::de::DeserializeOwned;
+use tokio_tungstenite::tungstenite::Message;
+
+use v26meme::core::control_api::Status;
+use v26meme::core::equity::EquityAnalytics;
+use v26meme::core::live_feed::{LiveEvent, Snapshot, TradeKind};
+use v26meme::core::money;
+use v26meme::core::patterns::PatternListing;
+use v26meme::core::secrets;
+
+const POLL_EVERY: Duration = Duration::from_secs(5);
+const RECONNECT_AFTER: Duration = Duration::from_secs(3);
+const LEADERBOARD: usize = 15;
+
+#[derive(Default)]
+struct Dashboard {
+    live: Snapshot,
+    feed_connected: bool,
+    feed_error: Option<String>,
+    status: Option<Status>,
+    patterns: Vec<PatternListing>,
+    equity: Option<EquityAnalytics>,
+    control_error: Option<String>,
+}
+
+type Shared = Arc<Mutex<Dashboard>>;
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    dotenv::dotenv().ok();
+    secrets::init().await.map_err(std::io::Error::other)?;
+    
+    let feed_port = std::env::var("LIVE_FEED_PORT").unwrap_or_else(|_| "8082".to_string());
+    let mut feed_url = std::env::var("LIVE_FEED_URL")
+        .unwrap_or_else(|_| format!("ws://127.0.0.1:{}/ws", feed_port));
+    if let Some(token) = secrets::var("LIVE_FEED_TOKEN").ok().filter(|t| !t.trim().is_empty()) {
+        feed_url = format!("{}?token={}", feed_url, token.trim());
+    }
+    let control_port = std::env::var("CONTROL_PORT").unwrap_or_else(|_| "8081".to_string());
+    let control_url = std::env::var("CONTROL_URL")
+        .unwrap_or_else(|_| format!("http://127.0.0.1:{}", control_port));
+    // A viewer key is all the dashboard needs; the admin token still works
+    let control_token = ["CONTROL_VIEWER_KEY", "CONTROL_API_TOKEN"].iter()
+        .find_map(|name| std::env::var(name).ok().filter(|t| !t.trim().is_empty()));
+    
+    let dashboard: Shared = Arc::new(Mutex::new(Dashboard::default()));
+    tokio::spawn(follow_feed(feed_url, dashboard.clone()));
+    match control_token {
+        Some(token) => {
+            tokio::spawn(poll_control(control_url, token.trim().to_string(), dashboard.clone()));
+        }
+        None => {
+            dashboard.lock().unwrap().control_error = Some("CONTROL_VIEWER_KEY not set".to_string());
+        }
+    }
+    
+    // Drawing and key handling block, so they get a thread of their own
+    tokio::task::spawn_blocking(move || run_ui(dashboard))
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+async fn follow_feed(url: String, dashboard: Shared) {
+    loop {
+        match tokio_tungstenite::connect_async(url.as_str()).await {
+            Ok((mut socket, _)) => {
+                {
+                    let mut d = dashboard.lock().unwrap();
+                    d.feed_connected = true;
+                    d.feed_error = None;
+                }
+                while let Some(message) = socket.next().await {
+                    match message {
+                        Ok(Message::Text(text)) => match serde_json::from_str::<LiveEvent>(&text) {
+                            Ok(event) => dashboard.lock().unwrap().live.apply(&event),
+                            Err(e) => dashboard.lock().unwrap().feed_error = Some(format!("bad event: {}", e)),
+                        },
+                        Ok(Message::Close(_)) => break,
+                        Ok(_) => {}
+                        Err(e) => {
+                            dashboard.lock().unwrap().feed_error = Some(e.to_string());
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => dashboard.lock().unwrap().feed_error = Some(e.to_string()),
+        }
+        dashboard.lock().unwrap().feed_connected = false;
+        tokio::time::sleep(RECONNECT_AFTER).await;
+    }
+}
+
+async fn fetch<T: DeserializeOwned>(client: &reqwest::Client, base: &str, token: &str, path: &str) -> Result<T, String> {
+    let response = client.get(format!("{}{}", base, path))
+        .bearer_auth(token)
+        .timeout(POLL_EVERY)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", path, response.status()));
+    }
+    response.json().await.map_err(|e| e.to_string())
+}
+
+async fn poll_control(base: String, token: String, dashboard: Shared) {
+    let client = reqwest::Client::new();
+    loop {
+        let status = fetch::<Status>(&client, &base, &token, "/api/status").await;
+        let patterns = fetch::<Vec<PatternListing>>(&client, &base, &token, "/api/patterns").await;
+        let equity = fetch::<EquityAnalytics>(&client, &base, &token, "/api/equity?days=30").await;
+        
+        {
+            let mut d = dashboard.lock().unwrap();
+            d.control_error = None;
+            match status {
+                Ok(status) => d.status = Some(status),
+                Err(e) => d.control_error = Some(e),
+            }
+            match patterns {
+                Ok(patterns) => d.patterns = patterns,
+                Err(e) => d.control_error = d.control_error.take().or(Some(e)),
+            }
+            match equity {
+                Ok(equity) => d.equity = Some(equity),
+                Err(e) => d.control_error = d.control_error.take().or(Some(e)),
+            }
+        }
+        tokio::time::sleep(POLL_EVERY).await;
+    }
+}
+
+fn run_ui(dashboard: Shared) -> std::io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = (|| loop {
+        terminal.draw(|frame| draw(frame, &dashboard.lock().unwrap()))?;
+        if event::poll(Duration::from_millis(250))? {
+            if let Event::Key(key) = event::read()? {
+                let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if key.kind == KeyEventKind::Press && (matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) || ctrl_c) {
+                    return Ok(());
+                }
+            }
+        }
+    })();
+    ratatui::restore();
+    result
+}
+
+fn flag(name: &str, tripped: bool) -> Line<'static> {
+    let (state, color) = if tripped { ("TRIPPED", Color::Red) } else { ("ok", Color::Green) };
+    Line::from(vec![Span::raw(format!("{:<16}", name)), Span::styled(state, Style::new().fg(color).bold())])
+}
+
+fn signed(value: f64) -> Style {
+    Style::new().fg(if value >= 0.0 { Color::Green } else { Color::Red })
+}
+
+fn draw(frame: &mut Frame, d: &Dashboard) {
+    let [header, state, tables, fills, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(8),
+        Constraint::Min(8),
+        Constraint::Length(12),
+        Constraint::Length(1),
+    ]).areas(frame.area());
+    
+    // Capital and today's drawdown against its limit
+    let capital = d.live.capital().or(d.status.as_ref().map(|s| money::to_f64(s.capital)));
+    let mut summary = vec![Span::styled(
+        format!(" Capital ${:.2} ", capital.unwrap_or(0.0)),
+        Style::new().add_modifier(Modifier::BOLD),
+    )];
+    if let (Some(status), Some(capital)) = (&d.status, capital) {
+        let pnl = capital - money::to_f64(status.starting_capital);
+        summary.push(Span::styled(format!("({:+.2} since start) ", pnl), signed(pnl)));
+        let near_limit = status.drawdown_pct >= status.max_daily_drawdown_pct * 0.75;
+        summary.push(Span::styled(
+            format!(" Drawdown {:.2}% of {:.1}% limit (high ${:.2}) ",
+                    status.drawdown_pct * 100.0, status.max_daily_drawdown_pct * 100.0, status.daily_high),
+            Style::new().fg(if near_limit { Color::Red } else { Color::Reset }),
+        ));
+    }
+    summary.push(Span::styled(
+        if d.feed_connected { " feed ● " } else { " feed ○ " },
+        Style::new().fg(if d.feed_connected { Color::Green } else { Color::Red }),
+    ));
+    frame.render_widget(Paragraph::new(Line::from(summary)).block(Block::bordered().title(" v26-top ")), header);
+    
+    // Breakers beside the longer-run equity picture
+    let [breakers, analytics] = Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(state);
+    let breaker_lines = match &d.status {
+        Some(status) => vec![
+            flag("emergency stop", status.breakers.emergency_stop),
+            flag("15-minute loss", status.breakers.loss_15min),
+            flag("1-hour loss", status.breakers.loss_1hr),
+            flag("VaR", status.breakers.var),
+            match &status.breakers.reduce_only {
+                Some(reason) => Line::from(vec![Span::raw(format!("{:<16}", "reduce-only")), Span::styled(format!("{:?}", reason), Style::new().fg(Color::Yellow))]),
+                None => flag("reduce-only", false),
+            },
+            Line::from(format!("{:<16}{}", "discovery", if status.discovery_paused { "paused" } else { "running" })),
+        ],
+        None => vec![Line::from("waiting for the control API")],
+    };
+    frame.render_widget(Paragraph::new(breaker_lines).block(Block::bordered().title(" Circuit breakers ")), breakers);
+    
+    let analytics_lines = match &d.equity {
+        Some(e) => vec![
+            Line::from(format!("Max drawdown (30d)  {}", e.max_drawdown.as_ref()
+                .map(|dd| format!("{:.2}% ({})", dd.depth * 100.0, if dd.recovered_at.is_some() { "recovered" } else { "open" }))
+                .unwrap_or_else(|| "none".to_string()))),
+            Line::from(format!("Underwater          {}m (longest {}m)", e.underwater_secs / 60, e.longest_underwater_secs / 60)),
+            Line::from(format!("Sharpe (30d)        {:.2}", e.sharpe_30d)),
+            Line::from(format!("CAGR                {}", e.cagr.map(|c| format!("{:.1}%", c * 100.0)).unwrap_or_else(|| "-".to_string()))),
+            Line::from(format!("Open positions      {}", d.live.positions.len())),
+        ],
+        None => vec![Line::from("waiting for the control API")],
+    };
+    frame.render_widget(Paragraph::new(analytics_lines).block(Block::bordered().title(" Equity ")), analytics);
+    
+    // Pattern leaderboard and the open book
+    let [leaderboard, positions] = Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(tables);
+    let pattern_rows = d.patterns.iter().take(LEADERBOARD).map(|p| Row::new(vec![
+        p.pattern_hash.chars().take(12).collect::<String>(),
+        p.symbol.clone().unwrap_or_else(|| "*".to_string()),
+        format!("{:.2}", p.sharpe_ratio),
+        format!("{:.1}%", p.win_rate * 100.0),
+        p.test_count.to_string(),
+        format!("{:.2}", p.total_profit),
+    ]));
+    frame.render_widget(
+        Table::new(pattern_rows, [Constraint::Length(13), Constraint::Length(11), Constraint::Length(7), Constraint::Length(7), Constraint::Length(6), Constraint::Min(8)])
+            .header(Row::new(vec!["pattern", "symbol", "sharpe", "win", "tests", "profit"]).bold())
+            .block(Block::bordered().title(format!(" Pattern leaderboard ({} active) ", d.patterns.len()))),
+        leaderboard,
+    );
+    
+    let position_rows = d.live.positions.iter().map(|p| Row::new(vec![
+        p.position.symbol.clone(),
+        p.position.exchange.clone(),
+        if p.position.is_long() { "long" } else { "short" }.to_string(),
+        format!("{:.2}", p.position.size),
+        format!("{:.6}", p.position.entry_price),
+        format!("{:.6}", p.position.stop_loss),
+    ]));
+    frame.render_widget(
+        Table::new(position_rows, [Constraint::Length(11), Constraint::Length(9), Constraint::Length(6), Constraint::Length(9), Constraint::Length(11), Constraint::Min(10)])
+            .header(Row::new(vec!["symbol", "venue", "side", "size", "entry", "stop"]).bold())
+            .block(Block::bordered().title(" Open positions ")),
+        positions,
+    );
+    
+    // Most recent fills first
+    let fill_rows = d.live.recent_trades.iter().rev().map(|t| {
+        let pnl = t.pnl.map(|p| format!("{:+.2}", p)).unwrap_or_default();
+        Row::new(vec![
+            t.at.format("%H:%M:%S").to_string(),
+            match t.kind { TradeKind::Entry => "entry", TradeKind::Exit => "exit" }.to_string(),
+            t.symbol.clone(),
+            format!("{:.2}", t.size),
+            format!("{:.6}", t.price),
+            pnl,
+            t.reason.clone().unwrap_or_default(),
+        ]).style(t.pnl.map(|pnl| signed(money::to_f64(pnl))).unwrap_or_default())
+    });
+    frame.render_widget(
+        Table::new(fill_rows, [Constraint::Length(9), Constraint::Length(6), Constraint::Length(11), Constraint::Length(9), Constraint::Length(11), Constraint::Length(9), Constraint::Min(10)])
+            .header(Row::new(vec!["time", "kind", "symbol", "size", "price", "pnl", "reason"]).bold())
+            .block(Block::bordered().title(" Recent fills ")),
+        fills,
+    );
+    
+    let problems: Vec<String> = [d.feed_error.as_ref().map(|e| format!("feed: {}", e)), d.control_error.as_ref().map(|e| format!("control: {}", e))]
+        .into_iter()
+        .flatten()
+        .collect();
+    let footer_text = if problems.is_empty() { "q to quit".to_string() } else { format!("q to quit | {}", problems.join(" | ")) };
+    frame.render_widget(Paragraph::new(footer_text).fg(if problems.is_empty() { Color::DarkGray } else { Color::Yellow }), footer);
+}
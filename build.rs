@@ -0,0 +1,13 @@
+// Generates the strategy plugin gRPC service from proto/plugin.proto with a vendored protoc,
+// so building needs no system protobuf install
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+    tonic_prost_build::configure()
+        .build_client(true)
+        .build_server(true)
+        .compile_protos(&["proto/plugin.proto"], &["proto"])?;
+    Ok(())
+}
@@ -0,0 +1,799 @@
+// App Configuration - Defaults, Then config/v26meme.toml, Then Env Vars
+// One validated AppConfig holds the numbers that used to be scattered through main.rs and the
+// discovery engine: starting capital, the dead-man and shutdown timers, discovery's activation
+// bar and test budget, the symbol universe, alert routing and how often each phase runs. Any
+// field can be overridden from the environment as V26MEME__<SECTION>__<FIELD>, and the older
+// INITIAL_CAPITAL, DEAD_MAN_SECS, SHUTDOWN_TIMEOUT_SECS and SYMBOL_UNIVERSE still work. Risk limits keep their own file and are loaded alongside,
+// so a bad value anywhere stops startup before anything trades. A profile (`--profile degen`)
+// is a named overlay from config/profiles/ that sits between the files and the env vars.
+// `--phases discovery,monitor` (or enabled_phases) starts only part of the pipeline
+
+use std::sync::OnceLock;
+use chrono::NaiveTime;
+use serde::{Serialize, Deserialize};
+use toml::Value;
+
+use crate::core::accounts::{self, AccountConfig};
+use crate::core::alerts::{AlertConfig, Severity};
+use crate::core::autoscale::AutoscalePolicy;
+use crate::core::clock::Calendar;
+use crate::core::market_data::DropPolicy;
+use crate::core::money::Decimal;
+use crate::core::test_budget::{BudgetPolicy, TestBudget};
+use crate::core::tuning::{Tuning, TuningUpdate};
+use crate::risk::RiskConfig;
+
+pub const ENV_PREFIX: &str = "V26MEME__";
+
+pub const DEFAULT_PATH: &str = "config/v26meme.toml";
+
+pub const PROFILE_DIR: &str = "config/profiles";
+
+/// Set once from `--profile`; V26MEME_PROFILE otherwise
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Set once from `--phases`; V26MEME_PHASES otherwise
+static PHASES: OnceLock<String> = OnceLock::new();
+
+/// Env vars that predate the config file, and the field each one sets
+const LEGACY_ENV: [(&str, &str); 4] = [
+    ("INITIAL_CAPITAL", "initial_capital"),
+    ("DEAD_MAN_SECS", "dead_man_secs"),
+    ("SHUTDOWN_TIMEOUT_SECS", "shutdown_timeout_secs"),
+    ("SYMBOL_UNIVERSE", "symbol_universe"),  // comma-separated
+];
+const CHANNELS: [&str; 3] = ["discord", "telegram", "email"];
+
+/// The five long-running parts of `v26meme run`; the control API, health checks, plugins,
+/// retention and the live feed start whichever are enabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Discovery,     // hypothesis tests, plus the news and social feeds they read
+    Intelligence,  // the LLM strategist and LLM sentiment scoring
+    Execution,     // exchanges, the order queue, stops and reconciliation
+    Evolution,     // breeding and retiring patterns
+    Monitor,       // risk checks, equity snapshots and reports
+}
+
+impl Phase {
+    pub const ALL: [Phase; 5] = [Phase::Discovery, Phase::Intelligence, Phase::Execution, Phase::Evolution, Phase::Monitor];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::Discovery => "discovery",
+            Phase::Intelligence => "intelligence",
+            Phase::Execution => "execution",
+            Phase::Evolution => "evolution",
+            Phase::Monitor => "monitor",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Phase::ALL.into_iter().find(|phase| phase.as_str() == s)
+    }
+
+    /// A comma-separated list such as `discovery,monitor`
+    pub fn parse_list(list: &str) -> Result<Vec<Self>, String> {
+        let mut phases = list
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| Phase::parse(&s.to_lowercase()).ok_or_else(|| format!("unknown phase {:?} (expected one of discovery, intelligence, execution, evolution, monitor)", s)))
+            .collect::<Result<Vec<_>, _>>()?;
+        phases.sort();
+        phases.dedup();
+        Ok(phases)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,     // config/profiles/<name>.toml, if one is selected
+    pub initial_capital: f64,        // $200
+    pub dead_man_secs: u64,          // 180s without a risk or discovery beat halts execution
+    pub shutdown_timeout_secs: u64,  // 30s per shutdown step
+    pub symbol_universe: Vec<String>,  // new positions outside it are refused
+    pub enabled_phases: Vec<Phase>,    // all five; `--phases` replaces the list
+    pub alerts: AlertSettings,
+    pub clock: ClockSettings,
+    pub discovery: DiscoveryConfig,
+    pub phases: PhaseIntervals,
+    pub database: DatabaseSettings,
+    pub market_data: MarketDataSettings,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<AccountConfig>,  // exchange accounts and their capital; empty is one per venue
+    #[serde(skip_deserializing)]
+    pub risk: RiskConfig,            // config/risk.toml (or RISK_CONFIG) and its own env vars
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiscoveryConfig {
+    pub hypotheses_per_hour: Option<u32>,   // None follows the equity-scaled test budget
+    pub min_win_rate: f64,                  // 0.55 to activate
+    pub min_tests_required: u32,            // 100 before validation
+    pub evolution_interval_hours: u32,      // 24, the regular cycle
+    pub evolution_min_interval_hours: u32,  // 4 between early (regime change) cycles
+    pub test_venue: Option<String>,         // None uses the first venue of the run
+    pub test_workers: usize,                // 4 hypotheses tested at once
+    pub budget: BudgetPolicy,               // test size and rate as equity grows
+    pub autoscale: AutoscalePolicy,         // the rate against backlog and venue headroom
+}
+
+/// Alert routing over the built-in defaults; ALERT_ROUTE_* and friends still win. Channel
+/// credentials stay in the environment
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertSettings {
+    pub info: Option<Vec<String>>,      // channel names: discord, telegram, email
+    pub warning: Option<Vec<String>>,
+    pub critical: Option<Vec<String>>,
+    pub dedup_minutes: Option<i64>,
+    pub max_per_hour: Option<usize>,    // per channel; critical alerts are never limited
+}
+
+/// The Postgres pool every component shares; DATABASE_URL stays in the environment
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSettings {
+    pub max_connections: u32,          // 20: discovery workers, writers, the control API and reports
+    pub min_connections: u32,          // 2 kept open, so a quiet spell doesn't pay for a reconnect
+    pub acquire_timeout_secs: u64,     // 5 waiting for a free connection before the query fails
+    pub idle_timeout_secs: u64,        // 600 before an idle connection above the minimum closes; 0 never
+    pub max_lifetime_secs: u64,        // 1800 before any connection is recycled; 0 never
+    pub statement_cache_capacity: usize,  // 200 prepared statements per connection; 0 disables
+}
+
+/// The tick channel between the venues and the stop engine, the paper book and the writers
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarketDataSettings {
+    pub channel_capacity: usize,   // 4096 ticks waiting per subscriber, allocated up front
+    pub poll_batch: i64,           // 5000 market_data rows read per poll at most
+    pub drop_policy: DropPolicy,   // oldest: a subscriber that falls behind skips ahead
+}
+
+/// Where trading days begin and when scheduled evolution runs, in local time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClockSettings {
+    pub timezone: String,              // IANA name, "UTC"
+    pub day_start: String,             // "00:00" local; daily reports close each day here
+    pub evolution_at: Option<String>,  // "HH:MM" local; None runs every evolution_interval_hours
+}
+
+/// How often each background phase wakes up
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PhaseIntervals {
+    pub intelligence_secs: u64,          // 1800, one sentiment read per cycle
+    pub template_refresh_cycles: u64,    // 12 intelligence cycles between template proposals
+    pub sentiment_secs: u64,             // 600
+    pub social_secs: u64,                // 300
+    pub correlation_secs: u64,           // 3600, also relearns liquidity profiles
+    pub reconciliation_secs: u64,        // 300
+    pub monitoring_secs: u64,            // 60, risk checks and equity snapshots
+    pub market_data_poll_ms: u64,        // 1000
+    pub market_data_stale_secs: u64,     // 60 without a tick degrades to reduce-only
+    pub market_data_ready_secs: u64,     // 30 execution waits at startup for the first tick; then reduce-only until it comes
+    pub retention_first_pass_secs: u64,  // 600 after startup, then every RETENTION_INTERVAL_HOURS
+    pub config_watch_secs: u64,          // 10 between checks for edited config files; 0 disables
+    pub flag_refresh_secs: u64,          // 30 between reads of feature_flags
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            profile: None,
+            initial_capital: 200.0,
+            dead_man_secs: 180,
+            shutdown_timeout_secs: 30,
+            symbol_universe: ["BTC-USD", "ETH-USD", "SOL-USD", "DOGE-USD"].map(String::from).to_vec(),
+            enabled_phases: Phase::ALL.to_vec(),
+            alerts: AlertSettings::default(),
+            clock: ClockSettings::default(),
+            discovery: DiscoveryConfig::default(),
+            phases: PhaseIntervals::default(),
+            database: DatabaseSettings::default(),
+            market_data: MarketDataSettings::default(),
+            accounts: Vec::new(),
+            risk: RiskConfig::default(),
+        }
+    }
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        let tuning = Tuning::default();
+        DiscoveryConfig {
+            hypotheses_per_hour: tuning.hypotheses_per_hour,
+            min_win_rate: tuning.min_win_rate,
+            min_tests_required: tuning.min_tests_required,
+            evolution_interval_hours: tuning.evolution_interval_hours,
+            evolution_min_interval_hours: tuning.evolution_min_interval_hours,
+            test_venue: None,
+            test_workers: 4,
+            budget: BudgetPolicy::default(),
+            autoscale: AutoscalePolicy::default(),
+        }
+    }
+}
+
+impl Default for PhaseIntervals {
+    fn default() -> Self {
+        PhaseIntervals {
+            intelligence_secs: 1800,
+            template_refresh_cycles: 12,
+            sentiment_secs: 600,
+            social_secs: 300,
+            correlation_secs: 3600,
+            reconciliation_secs: 300,
+            monitoring_secs: 60,
+            market_data_poll_ms: 1000,
+            market_data_stale_secs: 60,
+            market_data_ready_secs: 30,
+            retention_first_pass_secs: 600,
+            config_watch_secs: 10,
+            flag_refresh_secs: 30,
+        }
+    }
+}
+
+impl DiscoveryConfig {
+    /// Where the control API's runtime tuning starts from
+    pub fn tuning(&self) -> Tuning {
+        Tuning {
+            hypotheses_per_hour: self.hypotheses_per_hour,
+            min_win_rate: self.min_win_rate,
+            min_tests_required: self.min_tests_required,
+            evolution_interval_hours: self.evolution_interval_hours,
+            evolution_min_interval_hours: self.evolution_min_interval_hours,
+        }
+    }
+
+    /// The test budget before evolution first rebalances it
+    pub fn starting_budget(&self, capital: Decimal) -> TestBudget {
+        self.budget.budget_for(capital)
+    }
+
+    fn validate(&self) -> Vec<String> {
+        // The same bounds the control API enforces at runtime
+        let update = TuningUpdate {
+            hypotheses_per_hour: Some(self.hypotheses_per_hour.unwrap_or(0)),
+            min_win_rate: Some(self.min_win_rate),
+            min_tests_required: Some(self.min_tests_required),
+            evolution_interval_hours: Some(self.evolution_interval_hours),
+            evolution_min_interval_hours: Some(self.evolution_min_interval_hours),
+        };
+        let mut errors = Tuning::default().with_update(&update).err().unwrap_or_default();
+
+        if self.hypotheses_per_hour == Some(0) {
+            errors.push("hypotheses_per_hour must be at least 1; leave it unset to follow the test budget".to_string());
+        }
+        if self.test_venue.as_deref().is_some_and(|v| v.trim().is_empty()) {
+            errors.push("test_venue cannot be empty".to_string());
+        }
+        if !(1..=64).contains(&self.test_workers) {
+            errors.push(format!("test_workers must be 1-64, got {}", self.test_workers));
+        }
+        let budget = &self.budget;
+        if !(budget.min_test_capital > Decimal::ZERO && budget.min_test_capital <= budget.max_test_capital) {
+            errors.push(format!("budget.min_test_capital must be positive and at most max_test_capital, got {} and {}",
+                                budget.min_test_capital, budget.max_test_capital));
+        }
+        for (name, value) in [("budget.test_capital_pct", budget.test_capital_pct), ("budget.hourly_budget_pct", budget.hourly_budget_pct)] {
+            if !(value > 0.0 && value <= 1.0) {
+                errors.push(format!("{} must be in (0, 1], got {}", name, value));
+            }
+        }
+        if budget.min_hypotheses_per_hour == 0 || budget.min_hypotheses_per_hour > budget.max_hypotheses_per_hour {
+            errors.push(format!("budget hypotheses per hour must be at least 1 with min <= max, got {}-{}",
+                                budget.min_hypotheses_per_hour, budget.max_hypotheses_per_hour));
+        }
+        let autoscale = &self.autoscale;
+        if autoscale.interval_secs == 0 {
+            errors.push("autoscale.interval_secs must be at least 1".to_string());
+        }
+        if !(autoscale.max_step_pct > 0.0 && autoscale.max_step_pct <= 1.0) {
+            errors.push(format!("autoscale.max_step_pct must be in (0, 1], got {}", autoscale.max_step_pct));
+        }
+        if !(0.0..1.0).contains(&autoscale.min_headroom) {
+            errors.push(format!("autoscale.min_headroom must be in [0, 1), got {}", autoscale.min_headroom));
+        }
+        if autoscale.backlog_boost.is_nan() || autoscale.backlog_boost < 1.0 {
+            errors.push(format!("autoscale.backlog_boost must be at least 1, got {}", autoscale.backlog_boost));
+        }
+        errors
+    }
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        DatabaseSettings {
+            max_connections: 20,
+            min_connections: 2,
+            acquire_timeout_secs: 5,
+            idle_timeout_secs: 600,
+            max_lifetime_secs: 1800,
+            statement_cache_capacity: 200,
+        }
+    }
+}
+
+impl DatabaseSettings {
+    fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.max_connections == 0 || self.min_connections > self.max_connections {
+            errors.push(format!("max_connections must be at least 1 and min_connections at most that, got {} and {}",
+                                self.min_connections, self.max_connections));
+        }
+        if self.acquire_timeout_secs == 0 {
+            errors.push("acquire_timeout_secs must be at least 1".to_string());
+        }
+        if self.max_lifetime_secs > 0 && self.idle_timeout_secs > self.max_lifetime_secs {
+            errors.push(format!("idle_timeout_secs must not exceed max_lifetime_secs, got {} and {}",
+                                self.idle_timeout_secs, self.max_lifetime_secs));
+        }
+        errors
+    }
+}
+
+impl Default for MarketDataSettings {
+    fn default() -> Self {
+        MarketDataSettings { channel_capacity: 4096, poll_batch: 5000, drop_policy: DropPolicy::Oldest }
+    }
+}
+
+impl MarketDataSettings {
+    fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.channel_capacity == 0 {
+            errors.push("channel_capacity must be at least 1".to_string());
+        }
+        if self.poll_batch < 1 {
+            errors.push(format!("poll_batch must be at least 1, got {}", self.poll_batch));
+        }
+        errors
+    }
+}
+
+impl Default for ClockSettings {
+    fn default() -> Self {
+        ClockSettings { timezone: "UTC".to_string(), day_start: "00:00".to_string(), evolution_at: None }
+    }
+}
+
+impl ClockSettings {
+    /// UTC midnight days if the settings don't parse; validate refuses those at startup
+    pub fn calendar(&self) -> Calendar {
+        let default = Calendar::default();
+        Calendar {
+            timezone: self.timezone.parse().unwrap_or(default.timezone),
+            day_start: parse_time(&self.day_start).unwrap_or(default.day_start),
+        }
+    }
+
+    pub fn evolution_at(&self) -> Option<NaiveTime> {
+        self.evolution_at.as_deref().and_then(parse_time)
+    }
+
+    fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.timezone.parse::<chrono_tz::Tz>().is_err() {
+            errors.push(format!("timezone {:?} is not an IANA zone such as UTC or America/New_York", self.timezone));
+        }
+        if parse_time(&self.day_start).is_none() {
+            errors.push(format!("day_start must be HH:MM, got {:?}", self.day_start));
+        }
+        if let Some(at) = self.evolution_at.as_deref().filter(|at| parse_time(at).is_none()) {
+            errors.push(format!("evolution_at must be HH:MM, got {:?}", at));
+        }
+        errors
+    }
+}
+
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M").ok()
+}
+
+impl AlertSettings {
+    /// The built-in routes with these settings, then the ALERT_* env vars, on top
+    pub fn alert_config(&self) -> AlertConfig {
+        let mut config = AlertConfig::default();
+        for (severity, route) in [(Severity::Info, &self.info), (Severity::Warning, &self.warning), (Severity::Critical, &self.critical)] {
+            if let Some(route) = route {
+                config.routes.insert(severity, route.clone());
+            }
+        }
+        if let Some(minutes) = self.dedup_minutes {
+            config.dedup_window = chrono::Duration::minutes(minutes);
+        }
+        if let Some(max) = self.max_per_hour {
+            config.max_per_channel_per_hour = max;
+        }
+        config.with_env()
+    }
+
+    fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (name, route) in [("info", &self.info), ("warning", &self.warning), ("critical", &self.critical)] {
+            for channel in route.iter().flatten().filter(|c| !CHANNELS.contains(&c.as_str())) {
+                errors.push(format!("{} routes to unknown channel {} (discord, telegram or email)", name, channel));
+            }
+        }
+        if self.dedup_minutes.is_some_and(|m| m < 0) {
+            errors.push("dedup_minutes cannot be negative".to_string());
+        }
+        if self.max_per_hour == Some(0) {
+            errors.push("max_per_hour must be at least 1".to_string());
+        }
+        errors
+    }
+}
+
+impl PhaseIntervals {
+    fn validate(&self) -> Vec<String> {
+        [
+            ("intelligence_secs", self.intelligence_secs),
+            ("template_refresh_cycles", self.template_refresh_cycles),
+            ("sentiment_secs", self.sentiment_secs),
+            ("social_secs", self.social_secs),
+            ("correlation_secs", self.correlation_secs),
+            ("reconciliation_secs", self.reconciliation_secs),
+            ("monitoring_secs", self.monitoring_secs),
+            ("market_data_poll_ms", self.market_data_poll_ms),
+            ("market_data_stale_secs", self.market_data_stale_secs),
+            ("flag_refresh_secs", self.flag_refresh_secs),
+        ]
+        .into_iter()
+        .filter(|(_, value)| *value == 0)
+        .map(|(name, _)| format!("{} must be at least 1", name))
+        .collect()
+    }
+}
+
+impl AppConfig {
+    /// Defaults, then the TOML file at APP_CONFIG (or config/v26meme.toml if present), then the
+    /// selected profile, then env vars, then `--phases`; risk limits come from RiskConfig::load_with
+    pub fn load() -> Result<Self, String> {
+        let path = Self::path();
+        let profile = profile_name().map(|name| Profile::load(&name)).transpose()?;
+
+        let mut config = match std::fs::read_to_string(&path) {
+            Ok(text) => Self::from_toml(&text).map_err(|e| format!("{}: {}", path, e))?,
+            Err(_) if std::env::var("APP_CONFIG").is_err() => AppConfig::default(),
+            Err(e) => return Err(format!("cannot read {}: {}", path, e)),
+        };
+
+        if let Some(profile) = &profile {
+            config.apply_profile(profile)?;
+        }
+        config.apply_env(std::env::vars())?;
+        if let Some(list) = phase_list() {
+            config.enabled_phases = Phase::parse_list(&list).map_err(|e| format!("--phases: {}", e))?;
+        }
+        config.risk = RiskConfig::load_with(profile.as_ref().map_or(&toml::Table::new(), |p| &p.risk))?;
+
+        let errors = config.validate();
+        if !errors.is_empty() {
+            return Err(format!("invalid config: {}", errors.join("; ")));
+        }
+
+        Ok(config)
+    }
+
+    /// APP_CONFIG, else config/v26meme.toml
+    pub fn path() -> String {
+        std::env::var("APP_CONFIG").unwrap_or_else(|_| DEFAULT_PATH.to_string())
+    }
+
+    /// The selected profile's fields over this config, [risk] included
+    pub fn apply_profile(&mut self, profile: &Profile) -> Result<(), String> {
+        let mut tree = self.tree()?;
+        if let Value::Table(table) = &mut tree {
+            merge(table, &profile.app);
+        }
+        self.rebuild(tree).map_err(|e| format!("profile {}: {}", profile.name, e))?;
+        self.risk.apply_overlay(&profile.risk).map_err(|e| format!("profile {} [risk]: {}", profile.name, e))?;
+        self.profile = Some(profile.name.clone());
+        Ok(())
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let table: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+        if table.contains_key("risk") {
+            return Err("risk limits belong in config/risk.toml (or RISK_CONFIG), not a [risk] section".to_string());
+        }
+        Value::Table(table).try_into().map_err(|e: toml::de::Error| e.to_string())
+    }
+
+    /// Legacy names first, so V26MEME__ overrides win when both are set. Values are read as
+    /// TOML (numbers, booleans) and fall back to plain strings
+    pub fn apply_env(&mut self, vars: impl IntoIterator<Item = (String, String)>) -> Result<(), String> {
+        let mut legacy = Vec::new();
+        let mut prefixed = Vec::new();
+        for (key, value) in vars {
+            if let Some((_, field)) = LEGACY_ENV.iter().find(|(name, _)| *name == key) {
+                let value = match *field {
+                    "symbol_universe" => Value::Array(value.split(',').map(str::trim).filter(|s| !s.is_empty()).map(|s| Value::String(s.to_string())).collect()),
+                    _ => env_value(&value),
+                };
+                legacy.push((key, vec![field.to_string()], value));
+            } else if let Some(path) = key.strip_prefix(ENV_PREFIX) {
+                let path = path.split("__").map(str::to_lowercase).collect();
+                prefixed.push((key, path, env_value(&value)));
+            }
+        }
+        if legacy.is_empty() && prefixed.is_empty() {
+            return Ok(());
+        }
+
+        let mut tree = self.tree()?;
+        for (key, path, value) in legacy.into_iter().chain(prefixed) {
+            set(&mut tree, &path, value).map_err(|e| format!("{}: {}", key, e))?;
+        }
+        self.rebuild(tree).map_err(|e| format!("env override: {}", e))
+    }
+
+    /// The fields a file, profile or env var may set, as TOML
+    fn tree(&self) -> Result<Value, String> {
+        let mut tree = Value::try_from(self).map_err(|e| e.to_string())?;
+        if let Value::Table(table) = &mut tree {
+            table.remove("risk");
+            table.remove("profile");
+        }
+        Ok(tree)
+    }
+
+    /// Back from `tree`, keeping the risk limits and profile name it left out
+    fn rebuild(&mut self, tree: Value) -> Result<(), String> {
+        let risk = std::mem::take(&mut self.risk);
+        let profile = self.profile.take();
+        *self = tree.try_into().map_err(|e: toml::de::Error| e.to_string())?;
+        self.risk = risk;
+        self.profile = profile;
+        Ok(())
+    }
+
+    /// Sanity checks - a config that fails any of these is refused outright
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !(self.initial_capital.is_finite() && self.initial_capital > 0.0) {
+            errors.push(format!("initial_capital must be positive, got {}", self.initial_capital));
+        }
+        if self.dead_man_secs == 0 {
+            errors.push("dead_man_secs must be at least 1".to_string());
+        }
+        if self.shutdown_timeout_secs == 0 {
+            errors.push("shutdown_timeout_secs must be at least 1".to_string());
+        }
+        if self.symbol_universe.is_empty() || self.symbol_universe.iter().any(|s| s.trim().is_empty()) {
+            errors.push("symbol_universe needs at least one symbol and no blank entries".to_string());
+        }
+        errors.extend(phase_errors(&self.enabled_phases).into_iter().map(|e| format!("enabled_phases: {}", e)));
+        errors.extend(self.alerts.validate().into_iter().map(|e| format!("alerts.{}", e)));
+        errors.extend(self.clock.validate().into_iter().map(|e| format!("clock.{}", e)));
+        errors.extend(self.discovery.validate().into_iter().map(|e| format!("discovery.{}", e)));
+        errors.extend(self.phases.validate().into_iter().map(|e| format!("phases.{}", e)));
+        errors.extend(self.database.validate().into_iter().map(|e| format!("database.{}", e)));
+        errors.extend(self.market_data.validate().into_iter().map(|e| format!("market_data.{}", e)));
+        errors.extend(accounts::validate(&self.accounts).into_iter().map(|e| format!("accounts: {}", e)));
+        errors.extend(self.risk.validate().into_iter().map(|e| format!("risk.{}", e)));
+        errors
+    }
+
+    pub fn runs(&self, phase: Phase) -> bool {
+        self.enabled_phases.contains(&phase)
+    }
+
+    /// The effective config as TOML, risk limits included, for `v26meme config`
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+}
+
+/// A named overlay from config/profiles/<name>.toml: any AppConfig field, plus a [risk] section
+/// laid over config/risk.toml
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub name: String,
+    app: toml::Table,
+    risk: toml::Table,
+}
+
+impl Profile {
+    pub fn path(name: &str) -> String {
+        format!("{}/{}.toml", PROFILE_DIR, name)
+    }
+
+    pub fn load(name: &str) -> Result<Self, String> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(format!("profile name {:?} may only use letters, digits, - and _", name));
+        }
+        let path = Self::path(name);
+        let text = std::fs::read_to_string(&path).map_err(|e| format!("profile {}: cannot read {}: {}", name, path, e))?;
+        Self::from_toml(name, &text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn from_toml(name: &str, text: &str) -> Result<Self, String> {
+        let mut app: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+        let risk = match app.remove("risk") {
+            Some(Value::Table(risk)) => risk,
+            Some(_) => return Err("risk must be a section".to_string()),
+            None => toml::Table::new(),
+        };
+        Ok(Profile { name: name.to_string(), app, risk })
+    }
+}
+
+/// Choose the profile every later `load` applies; the first call wins
+pub fn select_profile(name: &str) {
+    let _ = PROFILE.set(name.to_string());
+}
+
+/// `--profile`, else V26MEME_PROFILE, else none
+pub fn profile_name() -> Option<String> {
+    PROFILE
+        .get()
+        .cloned()
+        .or_else(|| std::env::var("V26MEME_PROFILE").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// Choose the phases every later `load` starts; the first call wins
+pub fn select_phases(list: &str) {
+    let _ = PHASES.set(list.to_string());
+}
+
+/// `--phases`, else V26MEME_PHASES, else none (the config's enabled_phases)
+pub fn phase_list() -> Option<String> {
+    PHASES
+        .get()
+        .cloned()
+        .or_else(|| std::env::var("V26MEME_PHASES").ok())
+        .filter(|list| !list.trim().is_empty())
+}
+
+/// Evolution breeds into discovery's queue, and execution trades only while the monitor's risk
+/// checks keep beating the dead-man's switch
+fn phase_errors(phases: &[Phase]) -> Vec<String> {
+    let mut errors = Vec::new();
+    if phases.is_empty() {
+        errors.push("at least one phase must run".to_string());
+    }
+    let requires = [(Phase::Evolution, Phase::Discovery), (Phase::Execution, Phase::Monitor)];
+    for (phase, needed) in requires {
+        if phases.contains(&phase) && !phases.contains(&needed) {
+            errors.push(format!("{} needs {}", phase.as_str(), needed.as_str()));
+        }
+    }
+    errors
+}
+
+/// Tables merge key by key; anything else in `overlay` replaces what `base` had
+pub(crate) fn merge(base: &mut toml::Table, overlay: &toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(key), value) {
+            (Some(Value::Table(inner)), Value::Table(over)) => merge(inner, over),
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn set(tree: &mut Value, path: &[String], value: Value) -> Result<(), String> {
+    let (field, sections) = path.split_last().filter(|(field, _)| !field.is_empty()).ok_or("no field named")?;
+    let mut node = tree;
+    for section in sections {
+        node = node
+            .as_table_mut()
+            .ok_or_else(|| format!("{} is not a section", section))?
+            .entry(section.clone())
+            .or_insert_with(|| Value::Table(toml::Table::new()));
+    }
+    node.as_table_mut().ok_or_else(|| format!("{} is not a section", field))?.insert(field.clone(), value);
+    Ok(())
+}
+
+fn env_value(raw: &str) -> Value {
+    toml::from_str::<toml::Table>(&format!("value = {}", raw.trim()))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use crate::core::money;
+
+    #[test]
+    fn test_file_then_env_layering_and_validation() {
+        let shipped = AppConfig::from_toml(include_str!("config/v26meme.toml")).unwrap();
+        assert_eq!(shipped, AppConfig::default());
+        assert!(shipped.validate().is_empty());
+
+        let mut config = AppConfig::from_toml("initial_capital = 500\n[phases]\nsentiment_secs = 120").unwrap();
+        assert_eq!((config.initial_capital, config.phases.sentiment_secs, config.phases.social_secs), (500.0, 120, 300));
+
+        let env = [
+            ("INITIAL_CAPITAL", "1000"),
+            ("V26MEME__INITIAL_CAPITAL", "750.5"),
+            ("V26MEME__DISCOVERY__TEST_VENUE", "kraken"),
+            ("V26MEME__DISCOVERY__BUDGET__MAX_TEST_CAPITAL", "100"),
+            ("UNRELATED", "x"),
+        ];
+        config.apply_env(env.iter().map(|(k, v)| (k.to_string(), v.to_string()))).unwrap();
+        assert_eq!(config.initial_capital, 750.5);
+        assert_eq!(config.discovery.test_venue.as_deref(), Some("kraken"));
+        assert_eq!(config.discovery.budget.max_test_capital, dec!(100));
+        assert_eq!(config.phases.sentiment_secs, 120);
+
+        let typo = [("V26MEME__PHASES__SENTIMENT".to_string(), "60".to_string())];
+        assert!(config.clone().apply_env(typo).unwrap_err().contains("sentiment"));
+        assert!(AppConfig::from_toml("[risk]\nkelly_fraction = 0.1").is_err());
+
+        config.discovery.min_win_rate = 0.3;
+        config.discovery.test_workers = 0;
+        config.phases.monitoring_secs = 0;
+        let errors = config.validate();
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].starts_with("discovery.min_win_rate"));
+        assert!(errors[1].starts_with("discovery.test_workers"));
+        assert!(errors[2].starts_with("phases.monitoring_secs"));
+        let crowded = AppConfig { database: DatabaseSettings { min_connections: 30, ..DatabaseSettings::default() }, ..AppConfig::default() };
+        assert!(crowded.validate()[0].starts_with("database.max_connections"));
+        let unbounded = AppConfig::from_toml("[market_data]\ndrop_policy = \"newest\"\npoll_batch = 0").unwrap();
+        assert_eq!(unbounded.market_data.drop_policy, DropPolicy::Newest);
+        assert_eq!(unbounded.validate(), vec!["market_data.poll_batch must be at least 1, got 0".to_string()]);
+        let shrinking = AppConfig::from_toml("[discovery.autoscale]\nbacklog_boost = 0.5").unwrap();
+        assert_eq!(shrinking.validate(), vec!["discovery.autoscale.backlog_boost must be at least 1, got 0.5".to_string()]);
+
+        let research = Phase::parse_list("monitor, Discovery,monitor").unwrap();
+        assert_eq!(research, vec![Phase::Discovery, Phase::Monitor]);
+        assert!(Phase::parse_list("discovery,trading").unwrap_err().contains("trading"));
+        let mut partial = AppConfig { enabled_phases: research, ..AppConfig::default() };
+        assert!(partial.validate().is_empty() && !partial.runs(Phase::Execution));
+        partial.enabled_phases = vec![Phase::Execution, Phase::Evolution];
+        assert_eq!(partial.validate(), vec!["enabled_phases: evolution needs discovery", "enabled_phases: execution needs monitor"]);
+    }
+
+    #[test]
+    fn test_profiles_overlay_the_files_and_yield_to_env() {
+        let profile = |name: &str, text: &str| {
+            let mut config = AppConfig::default();
+            config.apply_profile(&Profile::from_toml(name, text).unwrap()).unwrap();
+            assert!(config.validate().is_empty(), "{}: {:?}", name, config.validate());
+            config
+        };
+        let standard = profile("standard", include_str!("config/profiles/standard.toml"));
+        let conservative = profile("conservative", include_str!("config/profiles/conservative.toml"));
+        let degen = profile("degen", include_str!("config/profiles/degen.toml"));
+        assert_eq!(AppConfig { profile: None, ..standard.clone() }, AppConfig::default());
+        assert_eq!(degen.profile.as_deref(), Some("degen"));
+
+        // Every sizing knob grows from conservative through standard to degen
+        let sizing = |c: &AppConfig| [c.risk.max_position_size_pct, c.risk.kelly_fraction, c.risk.max_daily_drawdown_pct, money::to_f64(c.discovery.budget.max_test_capital)];
+        let ordered = |a: [f64; 4], b: [f64; 4]| a.iter().zip(&b).all(|(x, y)| x < y);
+        assert!(ordered(sizing(&conservative), sizing(&standard)) && ordered(sizing(&standard), sizing(&degen)));
+        assert!(conservative.discovery.min_win_rate > degen.discovery.min_win_rate);
+        assert_eq!(conservative.risk.capital_tiers.len(), 2);
+        assert_eq!(degen.risk.capital_tiers, RiskConfig::default().capital_tiers);
+
+        let mut overridden = degen.clone();
+        overridden.apply_env([("V26MEME__DISCOVERY__MIN_TESTS_REQUIRED".to_string(), "80".to_string())]).unwrap();
+        assert_eq!((overridden.discovery.min_tests_required, overridden.discovery.min_win_rate), (80, 0.52));
+        assert_eq!(overridden.profile.as_deref(), Some("degen"));
+
+        let typo = Profile::from_toml("typo", "[risk]
+kelly = 0.1").unwrap();
+        assert!(AppConfig::default().apply_profile(&typo).unwrap_err().contains("kelly"));
+        assert!(Profile::load("../secrets").is_err());
+    }
+}
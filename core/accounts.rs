@@ -0,0 +1,208 @@
+// Accounts - Exchange Accounts With Their Own Keys and Capital
+// With [[accounts]] in the config, each account is its own exchange connection: its own API
+// keys (<PREFIX>_API_KEY, <PREFIX>_SECRET, <PREFIX>_PASSPHRASE), a fixed share of starting
+// capital, and a balance that moves only with its own fills. Orders and positions name the
+// account they trade on, and an order that would hold more than the account's balance is
+// refused even if the portfolio as a whole has room. The discovery account's balance sizes the
+// test budget, so test trades never draw on the capital live patterns trade with. Without
+// [[accounts]], each venue of the run is one account and capital is split evenly, as before
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Mutex;
+use secrecy::SecretString;
+use serde::{Serialize, Deserialize};
+
+use crate::core::money::{self, Decimal};
+use crate::core::secrets;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountRole {
+    Discovery,  // test trades for hypotheses
+    Live,       // activated patterns
+}
+
+impl AccountRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountRole::Discovery => "discovery",
+            AccountRole::Live => "live",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "discovery" => Some(AccountRole::Discovery),
+            "live" => Some(AccountRole::Live),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountConfig {
+    pub name: String,         // the exchange name orders and positions carry
+    pub venue: String,        // whose fee schedule and venue exposure cap apply
+    pub role: AccountRole,
+    pub capital_pct: f64,     // share of initial capital; all accounts together hold all of it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<String>,  // env prefix of its keys; the venue in upper case if unset
+}
+
+/// One account's keys, zeroized on drop
+#[derive(Debug)]
+pub struct Credentials {
+    pub api_key: SecretString,
+    pub secret: SecretString,
+    pub passphrase: Option<SecretString>,
+}
+
+impl AccountConfig {
+    /// One live account per venue, sharing capital evenly; what a run without [[accounts]] trades on
+    pub fn per_venue(venues: &[String]) -> Vec<Self> {
+        let share = 1.0 / venues.len().max(1) as f64;
+        venues
+            .iter()
+            .map(|venue| AccountConfig {
+                name: venue.clone(),
+                venue: venue.clone(),
+                role: AccountRole::Live,
+                capital_pct: share,
+                credentials: None,
+            })
+            .collect()
+    }
+
+    pub fn credential_prefix(&self) -> String {
+        self.credentials.clone().unwrap_or_else(|| self.venue.to_uppercase()).replace('-', "_")
+    }
+
+    /// <PREFIX>_API_KEY and <PREFIX>_SECRET from the secrets source, None unless both are set
+    pub fn credentials(&self) -> Option<Credentials> {
+        let prefix = self.credential_prefix();
+        let var = |suffix: &str| {
+            secrets::var(&format!("{}_{}", prefix, suffix))
+                .ok()
+                .filter(|v| !v.trim().is_empty() && !v.contains("xxxx"))
+                .map(SecretString::from)
+        };
+        Some(Credentials { api_key: var("API_KEY")?, secret: var("SECRET")?, passphrase: var("PASSPHRASE") })
+    }
+}
+
+/// Unique names, shares that add up to all of the capital, at least one live account and at
+/// most one for discovery
+pub fn validate(accounts: &[AccountConfig]) -> Vec<String> {
+    let mut errors = Vec::new();
+    if accounts.is_empty() {
+        return errors;
+    }
+    let mut names = HashSet::new();
+    for account in accounts {
+        if account.name.trim().is_empty() || account.venue.trim().is_empty() {
+            errors.push("every account needs a name and a venue".to_string());
+        }
+        if !names.insert(account.name.as_str()) {
+            errors.push(format!("account {} is listed twice", account.name));
+        }
+        if !(account.capital_pct > 0.0 && account.capital_pct <= 1.0) {
+            errors.push(format!("{}.capital_pct must be in (0, 1], got {}", account.name, account.capital_pct));
+        }
+    }
+    let total: f64 = accounts.iter().map(|a| a.capital_pct).sum();
+    if (total - 1.0).abs() > 1e-6 {
+        errors.push(format!("capital_pct must add up to 1 across accounts, got {}", total));
+    }
+    if !accounts.iter().any(|a| a.role == AccountRole::Live) {
+        errors.push("at least one account needs role = \"live\"".to_string());
+    }
+    if accounts.iter().filter(|a| a.role == AccountRole::Discovery).count() > 1 {
+        errors.push("only one account can have role = \"discovery\"".to_string());
+    }
+    errors
+}
+
+/// Each account's capital partition and what its fills have made or lost since
+#[derive(Debug)]
+pub struct AccountBook {
+    accounts: Vec<AccountConfig>,
+    balances: Mutex<BTreeMap<String, Decimal>>,
+}
+
+impl AccountBook {
+    pub fn new(accounts: Vec<AccountConfig>, capital: Decimal) -> Self {
+        let balances = accounts.iter().map(|a| (a.name.clone(), money::share(capital, a.capital_pct))).collect();
+        AccountBook { accounts, balances: Mutex::new(balances) }
+    }
+
+    pub fn accounts(&self) -> &[AccountConfig] {
+        &self.accounts
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AccountConfig> {
+        self.accounts.iter().find(|a| a.name == name)
+    }
+
+    /// The venue behind an account; a name that is not an account is taken as a venue
+    pub fn venue_of<'a>(&'a self, name: &'a str) -> &'a str {
+        self.get(name).map_or(name, |a| a.venue.as_str())
+    }
+
+    pub fn discovery(&self) -> Option<&AccountConfig> {
+        self.accounts.iter().find(|a| a.role == AccountRole::Discovery)
+    }
+
+    pub fn balance(&self, name: &str) -> Option<Decimal> {
+        self.balances.lock().unwrap().get(name).copied()
+    }
+
+    /// Fees and realized PnL from a fill on `name`; fills elsewhere are ignored
+    pub fn apply_pnl(&self, name: &str, pnl: Decimal) {
+        if let Some(balance) = self.balances.lock().unwrap().get_mut(name) {
+            *balance += pnl;
+        }
+    }
+
+    pub fn balances(&self) -> BTreeMap<String, Decimal> {
+        self.balances.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn account(name: &str, venue: &str, role: AccountRole, capital_pct: f64) -> AccountConfig {
+        AccountConfig { name: name.to_string(), venue: venue.to_string(), role, capital_pct, credentials: None }
+    }
+
+    #[test]
+    fn test_partitions_validate_and_track_balances_apart() {
+        let accounts = vec![
+            account("coinbase-tests", "coinbase", AccountRole::Discovery, 0.2),
+            account("coinbase-live", "coinbase", AccountRole::Live, 0.5),
+            account("kraken-live", "kraken", AccountRole::Live, 0.3),
+        ];
+        assert!(validate(&accounts).is_empty());
+        assert!(validate(&[]).is_empty());
+        assert_eq!(validate(&accounts[..2]).len(), 1);  // 0.7 of the capital
+        let twice = vec![account("a", "coinbase", AccountRole::Discovery, 0.5), account("a", "kraken", AccountRole::Discovery, 0.5)];
+        assert_eq!(validate(&twice).len(), 3);  // duplicate, no live account, two discovery accounts
+
+        let book = AccountBook::new(accounts, dec!(1000));
+        assert_eq!(book.discovery().map(|a| a.name.as_str()), Some("coinbase-tests"));
+        assert_eq!(book.venue_of("kraken-live"), "kraken");
+        assert_eq!(book.venue_of("gemini"), "gemini");
+        book.apply_pnl("coinbase-live", dec!(-25));
+        book.apply_pnl("gemini", dec!(10));
+        assert_eq!(book.balance("coinbase-live"), Some(dec!(475)));
+        assert_eq!(book.balance("coinbase-tests"), Some(dec!(200)));
+        assert_eq!(book.balances().values().sum::<Decimal>(), dec!(975));
+
+        let legacy = AccountConfig::per_venue(&["coinbase".to_string(), "kraken".to_string()]);
+        assert!(validate(&legacy).is_empty());
+        assert_eq!(legacy[1].credential_prefix(), "KRAKEN");
+    }
+}
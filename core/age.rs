@@ -0,0 +1,110 @@
+// Age - Reading an age-Encrypted Secrets File
+// A thin wrapper over the `age` crate for the secrets loader: parse an X25519 identity and
+// decrypt a binary or ASCII-armored file encrypted to it
+
+use std::io::Read;
+use std::str::FromStr;
+use age::armor::ArmoredReader;
+use zeroize::Zeroizing;
+
+#[derive(Debug)]
+pub enum AgeError {
+    Identity(String),
+    Header(String),
+    NoMatchingRecipient,  // none of the stanzas is for this identity
+    Decrypt(String),
+}
+
+impl std::fmt::Display for AgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AgeError::Identity(e) => write!(f, "bad identity: {}", e),
+            AgeError::Header(e) => write!(f, "bad header: {}", e),
+            AgeError::NoMatchingRecipient => write!(f, "file is not encrypted to this identity"),
+            AgeError::Decrypt(e) => write!(f, "decryption failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AgeError {}
+
+impl From<age::DecryptError> for AgeError {
+    fn from(e: age::DecryptError) -> Self {
+        match e {
+            age::DecryptError::NoMatchingKeys => AgeError::NoMatchingRecipient,
+            age::DecryptError::InvalidHeader | age::DecryptError::UnknownFormat | age::DecryptError::InvalidMac => {
+                AgeError::Header(e.to_string())
+            }
+            e => AgeError::Decrypt(e.to_string()),
+        }
+    }
+}
+
+/// An X25519 identity; the key is zeroized on drop
+pub struct Identity(age::x25519::Identity);
+
+impl Identity {
+    /// The first AGE-SECRET-KEY-1 line of an identity file; comments and blank lines are skipped
+    pub fn parse(text: &str) -> Result<Self, AgeError> {
+        let line = text
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty() && !l.starts_with('#'))
+            .ok_or_else(|| AgeError::Identity("no key found".to_string()))?;
+        age::x25519::Identity::from_str(line).map(Identity).map_err(|e| AgeError::Identity(e.to_string()))
+    }
+}
+
+/// Plaintext of an age file encrypted to `identity`
+pub fn decrypt(identity: &Identity, file: &[u8]) -> Result<Zeroizing<Vec<u8>>, AgeError> {
+    let decryptor = age::Decryptor::new(ArmoredReader::new(file))?;
+    let mut reader = decryptor.decrypt(std::iter::once(&identity.0 as &dyn age::Identity))?;
+    let mut plaintext = Zeroizing::new(Vec::new());
+    reader.read_to_end(&mut plaintext).map_err(|e| AgeError::Decrypt(e.to_string()))?;
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    // Encrypted to the identity below with the stock `age` tool
+    const IDENTITY: &str = "# created: 2025-01-01\nAGE-SECRET-KEY-1QYPQXPQ9QCRSSZG2PVXQ6RS0ZQG3YYC5Z5TPWXQERGD3C8G7RUSQGPQYEE\n";
+    const ARMORED: &str = "-----BEGIN AGE ENCRYPTED FILE-----
+YWdlLWVuY3J5cHRpb24ub3JnL3YxCi0+IFgyNTUxOSBFNzVQNnVyeUJNZjlNMWo4
+bkFCeUdJSFJkQ2VCS0NKK3huVHpmMy9wZTIwCjBrMTlsMXdCaUFaQXlGTVc2TUlj
+YkVFNWg4cUhFQk50UTZkZTRwNVVraHcKLS0tIDdTQkxKUWI0NjFVVytGSW1kN3No
+UHRlaG1oL1lRbjR2UGZEUS9mUVdYTkEKyMnKy8zNzs/Q0dLT1NXW17rUFgWXAowJ
+2YvWJO2/u2Zzwq/dSOqgWr0xvcu4MdyfLi8G8ExzlVjW/7cuz35ForP08AJY7mDE
+Y2A9xNeG6EG5p62MwV3qkwlSVYxgAzm2On66Pl2JWxoyXyje+y1695LmOGELkWXr
+ymzbQF9klzQU6bl80oxImFR55pIxprBqV2+1NhOIGZyTijLPLBKC77MfY3hQQEqH
+qI22PRt9sxERyA8lQowhmyzCapKirr/YNYRKjwyze+CmOtdh6UjSDzp3
+-----END AGE ENCRYPTED FILE-----
+";
+
+    #[test]
+    fn test_decrypts_armored_and_binary_files() {
+        let identity = Identity::parse(IDENTITY).unwrap();
+        let plaintext = decrypt(&identity, ARMORED.as_bytes()).unwrap();
+        let text = std::str::from_utf8(&plaintext).unwrap();
+        assert!(text.contains("OPENAI_API_KEY=sk-test-123\n"));
+        assert!(text.contains("postgresql://v26meme:hunter2@db:5432/v26meme"));
+
+        let recipient = identity.0.to_public();
+        let encryptor = age::Encryptor::with_recipients(std::iter::once(&recipient as &dyn age::Recipient)).unwrap();
+        let mut binary = Vec::new();
+        let mut writer = encryptor.wrap_output(&mut binary).unwrap();
+        writer.write_all(&plaintext).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(*decrypt(&identity, &binary).unwrap(), *plaintext);
+
+        // A flipped payload byte fails authentication; a different key is not a recipient
+        let mut tampered = binary.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(decrypt(&identity, &tampered), Err(AgeError::Decrypt(_))));
+        let stranger = Identity(age::x25519::Identity::generate());
+        assert!(matches!(decrypt(&stranger, &binary), Err(AgeError::NoMatchingRecipient)));
+        assert!(Identity::parse("age1notasecretkey").is_err());
+    }
+}
@@ -0,0 +1,465 @@
+// Alerts - Getting a Human's Attention
+// Emergency stops, breakers and stuck flattens are sent to whichever channels are configured:
+// a Discord webhook, a Telegram bot, SMTP email. Each severity routes to its own set of
+// channels. A repeat of the same alert inside the dedup window is dropped, and each channel
+// is rate limited so a flapping breaker cannot bury the one message that matters; critical
+// alerts are never rate limited
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+use tracing::{error, info, warn};
+
+use crate::core::secrets;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "info" => Some(Severity::Info),
+            "warning" => Some(Severity::Warning),
+            "critical" => Some(Severity::Critical),
+            _ => None,
+        }
+    }
+
+    fn emoji(&self) -> &'static str {
+        match self {
+            Severity::Info => "ℹ️",
+            Severity::Warning => "⚠️",
+            Severity::Critical => "🚨",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub severity: Severity,
+    pub key: String,  // alerts sharing a key are duplicates of each other
+    pub title: String,
+    pub body: String,
+    pub raised_at: DateTime<Utc>,
+}
+
+impl Alert {
+    pub fn new(severity: Severity, key: &str, title: impl Into<String>) -> Self {
+        Alert {
+            severity,
+            key: key.to_string(),
+            title: title.into(),
+            body: String::new(),
+            raised_at: Utc::now(),
+        }
+    }
+
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// One plain-text rendering shared by every channel
+    pub fn text(&self) -> String {
+        let mut text = format!("{} [{}] {}", self.severity.emoji(), self.severity.as_str().to_uppercase(), self.title);
+        if !self.body.is_empty() {
+            text.push('\n');
+            text.push_str(&self.body);
+        }
+        text
+    }
+}
+
+#[derive(Debug)]
+pub enum AlertError {
+    Http(String),
+    Smtp(String),
+    Config(String),
+}
+
+impl std::fmt::Display for AlertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertError::Http(e) => write!(f, "request failed: {}", e),
+            AlertError::Smtp(e) => write!(f, "smtp failed: {}", e),
+            AlertError::Config(e) => write!(f, "misconfigured: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AlertError {}
+
+impl From<reqwest::Error> for AlertError {
+    fn from(e: reqwest::Error) -> Self {
+        AlertError::Http(e.to_string())
+    }
+}
+
+#[async_trait]
+pub trait AlertChannel: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn send(&self, alert: &Alert) -> Result<(), AlertError>;
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .unwrap_or_default()
+}
+
+pub struct DiscordChannel {
+    webhook_url: String,
+    http: reqwest::Client,
+}
+
+impl DiscordChannel {
+    pub fn new(webhook_url: String) -> Self {
+        DiscordChannel { webhook_url, http: http_client() }
+    }
+}
+
+#[async_trait]
+impl AlertChannel for DiscordChannel {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    async fn send(&self, alert: &Alert) -> Result<(), AlertError> {
+        // Discord rejects messages over 2000 characters
+        let content: String = alert.text().chars().take(2000).collect();
+        self.http.post(&self.webhook_url)
+            .json(&json!({ "content": content }))
+            .send().await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+pub struct TelegramChannel {
+    bot_token: String,
+    chat_id: String,
+    http: reqwest::Client,
+}
+
+impl TelegramChannel {
+    pub fn new(bot_token: String, chat_id: String) -> Self {
+        TelegramChannel { bot_token, chat_id, http: http_client() }
+    }
+}
+
+#[async_trait]
+impl AlertChannel for TelegramChannel {
+    fn name(&self) -> &str {
+        "telegram"
+    }
+
+    async fn send(&self, alert: &Alert) -> Result<(), AlertError> {
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", self.bot_token);
+        self.http.post(&url)
+            .json(&json!({ "chat_id": self.chat_id, "text": alert.text(), "disable_web_page_preview": true }))
+            .send().await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+pub struct EmailChannel {
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl EmailChannel {
+    /// STARTTLS relay on `port`; credentials are optional for relays that trust the host
+    pub fn new(host: &str, port: u16, credentials: Option<(String, String)>, from: &str, to: &[String]) -> Result<Self, AlertError> {
+        let parse = |address: &str| address.trim().parse::<Mailbox>()
+            .map_err(|e| AlertError::Config(format!("bad address {}: {}", address, e)));
+
+        let mut transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .map_err(|e| AlertError::Smtp(e.to_string()))?
+            .port(port);
+        if let Some((username, password)) = credentials {
+            transport = transport.credentials(Credentials::new(username, password));
+        }
+        let to = to.iter().map(|a| parse(a)).collect::<Result<Vec<_>, _>>()?;
+        if to.is_empty() {
+            return Err(AlertError::Config("no recipients".to_string()));
+        }
+
+        Ok(EmailChannel { from: parse(from)?, to, transport: transport.build() })
+    }
+}
+
+#[async_trait]
+impl AlertChannel for EmailChannel {
+    fn name(&self) -> &str {
+        "email"
+    }
+
+    async fn send(&self, alert: &Alert) -> Result<(), AlertError> {
+        let mut message = Message::builder()
+            .from(self.from.clone())
+            .subject(format!("[v26meme {}] {}", alert.severity.as_str(), alert.title));
+        for to in &self.to {
+            message = message.to(to.clone());
+        }
+        let message = message.body(alert.text()).map_err(|e| AlertError::Smtp(e.to_string()))?;
+        self.transport.send(message).await.map_err(|e| AlertError::Smtp(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AlertConfig {
+    pub routes: HashMap<Severity, Vec<String>>,  // channel names per severity
+    pub dedup_window: Duration,
+    pub max_per_channel_per_hour: usize,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        AlertConfig {
+            routes: HashMap::from([
+                (Severity::Info, vec!["discord".to_string()]),
+                (Severity::Warning, vec!["discord".to_string(), "telegram".to_string()]),
+                (Severity::Critical, vec!["discord".to_string(), "telegram".to_string(), "email".to_string()]),
+            ]),
+            dedup_window: Duration::minutes(15),
+            max_per_channel_per_hour: 20,
+        }
+    }
+}
+
+impl AlertConfig {
+    /// ALERT_ROUTE_<SEVERITY>, ALERT_DEDUP_MINUTES and ALERT_MAX_PER_HOUR over these settings
+    pub fn with_env(mut self) -> Self {
+        let var = |name: &str| std::env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        for severity in [Severity::Info, Severity::Warning, Severity::Critical] {
+            if let Some(route) = var(&format!("ALERT_ROUTE_{}", severity.as_str().to_uppercase())) {
+                self.routes.insert(severity, list(&route));
+            }
+        }
+        if let Some(minutes) = var("ALERT_DEDUP_MINUTES").and_then(|v| v.parse().ok()) {
+            self.dedup_window = Duration::minutes(minutes);
+        }
+        if let Some(max) = var("ALERT_MAX_PER_HOUR").and_then(|v| v.parse().ok()) {
+            self.max_per_channel_per_hour = max;
+        }
+        self
+    }
+}
+
+fn list(value: &str) -> Vec<String> {
+    value.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+}
+
+/// Where one alert went
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Delivery {
+    pub sent: Vec<String>,
+    pub rate_limited: Vec<String>,
+    pub failed: Vec<String>,
+    pub duplicate: bool,
+}
+
+pub struct AlertManager {
+    config: Mutex<AlertConfig>,  // swapped by config hot reload
+    channels: Vec<Box<dyn AlertChannel>>,
+    last_raised: Mutex<HashMap<String, DateTime<Utc>>>,  // by alert key
+    sent_at: Mutex<HashMap<String, VecDeque<DateTime<Utc>>>>,  // by channel, last hour
+}
+
+impl AlertManager {
+    pub fn new(config: AlertConfig) -> Self {
+        AlertManager {
+            config: Mutex::new(config),
+            channels: Vec::new(),
+            last_raised: Mutex::new(HashMap::new()),
+            sent_at: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Channels and routing from the environment; a channel is added only when its
+    /// credentials are set, so an unconfigured deployment still logs every alert
+    pub fn from_env() -> Self {
+        Self::from_env_with(AlertConfig::default().with_env())
+    }
+
+    /// Channels from the environment with routing decided by the caller
+    pub fn from_env_with(config: AlertConfig) -> Self {
+        let var = |name: &str| secrets::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+        let mut manager = AlertManager::new(config);
+        if let Some(webhook) = var("DISCORD_WEBHOOK").filter(|w| !w.ends_with("...")) {
+            manager.add_channel(Box::new(DiscordChannel::new(webhook)));
+        }
+        if let (Some(token), Some(chat_id)) = (var("TELEGRAM_BOT_TOKEN"), var("TELEGRAM_CHAT_ID")) {
+            manager.add_channel(Box::new(TelegramChannel::new(token, chat_id)));
+        }
+        if let (Some(host), Some(from), Some(to)) = (var("SMTP_HOST"), var("ALERT_EMAIL_FROM"), var("ALERT_EMAIL_TO")) {
+            let port = var("SMTP_PORT").and_then(|p| p.parse().ok()).unwrap_or(587);
+            let credentials = var("SMTP_USERNAME").zip(var("SMTP_PASSWORD"));
+            match EmailChannel::new(&host, port, credentials, &from, &list(&to)) {
+                Ok(channel) => manager.add_channel(Box::new(channel)),
+                Err(e) => error!("❌ Email alerts disabled: {}", e),
+            }
+        }
+        manager
+    }
+
+    pub fn add_channel(&mut self, channel: Box<dyn AlertChannel>) {
+        self.channels.push(channel);
+    }
+
+    pub fn channel_names(&self) -> Vec<&str> {
+        self.channels.iter().map(|c| c.name()).collect()
+    }
+
+    pub fn config(&self) -> AlertConfig {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// New routing, dedup window and rate limit; alerts already in flight keep the old ones
+    pub fn set_config(&self, config: AlertConfig) {
+        *self.config.lock().unwrap() = config;
+    }
+
+    /// False if an alert with the same key was raised inside the dedup window
+    pub fn admit(&self, alert: &Alert) -> bool {
+        let mut last_raised = self.last_raised.lock().unwrap();
+        let cutoff = alert.raised_at - self.config.lock().unwrap().dedup_window;
+        last_raised.retain(|_, at| *at > cutoff);
+        if last_raised.contains_key(&alert.key) {
+            return false;
+        }
+        last_raised.insert(alert.key.clone(), alert.raised_at);
+        true
+    }
+
+    /// Take a slot in the channel's hourly budget; critical alerts always get one
+    fn take_slot(&self, channel: &str, alert: &Alert) -> bool {
+        let mut sent_at = self.sent_at.lock().unwrap();
+        let history = sent_at.entry(channel.to_string()).or_default();
+        while history.front().is_some_and(|at| *at <= alert.raised_at - Duration::hours(1)) {
+            history.pop_front();
+        }
+        if alert.severity < Severity::Critical && history.len() >= self.config.lock().unwrap().max_per_channel_per_hour {
+            return false;
+        }
+        history.push_back(alert.raised_at);
+        true
+    }
+
+    /// Log the alert, then deliver it to every channel its severity routes to
+    pub async fn send(&self, alert: &Alert) -> Delivery {
+        match alert.severity {
+            Severity::Critical => error!("{}", alert.text()),
+            Severity::Warning => warn!("{}", alert.text()),
+            Severity::Info => info!("{}", alert.text()),
+        }
+
+        let mut delivery = Delivery::default();
+        if !self.admit(alert) {
+            delivery.duplicate = true;
+            return delivery;
+        }
+
+        let route = self.config.lock().unwrap().routes.get(&alert.severity).cloned().unwrap_or_default();
+        for channel in self.channels.iter().filter(|c| route.iter().any(|r| r == c.name())) {
+            if !self.take_slot(channel.name(), alert) {
+                delivery.rate_limited.push(channel.name().to_string());
+                continue;
+            }
+            match channel.send(alert).await {
+                Ok(()) => delivery.sent.push(channel.name().to_string()),
+                Err(e) => {
+                    error!("❌ {} alert via {} failed: {}", alert.key, channel.name(), e);
+                    delivery.failed.push(channel.name().to_string());
+                }
+            }
+        }
+
+        if !delivery.rate_limited.is_empty() {
+            warn!("⚠️ {} alert rate limited on {:?}", alert.key, delivery.rate_limited);
+        }
+        delivery
+    }
+
+    /// Deliver in the background, for synchronous paths such as the risk checks
+    pub fn raise(self: &Arc<Self>, alert: Alert) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("⚠️ No runtime to deliver alert {}: {}", alert.key, alert.title);
+            return;
+        };
+        let manager = self.clone();
+        runtime.spawn(async move {
+            manager.send(&alert).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Recorder {
+        name: &'static str,
+        sent: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl AlertChannel for Recorder {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn send(&self, alert: &Alert) -> Result<(), AlertError> {
+            self.sent.lock().unwrap().push(format!("{}:{}", self.name, alert.key));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_routing_dedup_and_rate_limit() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let config = AlertConfig { max_per_channel_per_hour: 2, ..AlertConfig::default() };
+        let mut manager = AlertManager::new(config);
+        for name in ["discord", "email"] {
+            manager.add_channel(Box::new(Recorder { name, sent: sent.clone() }));
+        }
+
+        // Warnings skip email; a repeat inside the window is dropped
+        let delivery = manager.send(&Alert::new(Severity::Warning, "var", "VaR breaker")).await;
+        assert_eq!(delivery.sent, vec!["discord"]);
+        assert!(manager.send(&Alert::new(Severity::Warning, "var", "VaR breaker")).await.duplicate);
+
+        // Discord has one slot left this hour; critical alerts are never held back
+        manager.send(&Alert::new(Severity::Info, "a", "first")).await;
+        let limited = manager.send(&Alert::new(Severity::Info, "b", "second")).await;
+        assert_eq!(limited.rate_limited, vec!["discord"]);
+        let critical = manager.send(&Alert::new(Severity::Critical, "stop", "Emergency stop")).await;
+        assert_eq!(critical.sent, vec!["discord", "email"]);
+
+        assert_eq!(*sent.lock().unwrap(), vec!["discord:var", "discord:a", "discord:stop", "email:stop"]);
+    }
+}
@@ -0,0 +1,239 @@
+// Anomaly Detector - Is the Machine Itself Healthy?
+// Circuit breakers watch the market and the book; this watches the system's own behavior.
+// Components count what they do (hypotheses generated, orders finished filled or empty,
+// failed database writes) and the monitoring loop compares each window against thresholds:
+// hypothesis generation stalled, fill rate collapsed, database writes failing. Anomalies are
+// alerted on and logged, and never touch trading state - that stays the breakers' job
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use tracing::{info, warn};
+
+use crate::core::alerts::{Alert, AlertManager, Severity};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    HypothesisGenerated,
+    OrderFilled,    // finished with some size filled
+    OrderUnfilled,  // finished (cancelled, rejected, expired) with nothing filled
+    DbWriteError,
+}
+
+static HYPOTHESES: AtomicU64 = AtomicU64::new(0);
+static ORDERS_FILLED: AtomicU64 = AtomicU64::new(0);
+static ORDERS_UNFILLED: AtomicU64 = AtomicU64::new(0);
+static DB_WRITE_ERRORS: AtomicU64 = AtomicU64::new(0);
+
+/// Count one event; cheap enough for any code path
+pub fn note(event: Event) {
+    let counter = match event {
+        Event::HypothesisGenerated => &HYPOTHESES,
+        Event::OrderFilled => &ORDERS_FILLED,
+        Event::OrderUnfilled => &ORDERS_UNFILLED,
+        Event::DbWriteError => &DB_WRITE_ERRORS,
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Running totals since process start
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Counts {
+    pub hypotheses: u64,
+    pub orders_filled: u64,
+    pub orders_unfilled: u64,
+    pub db_write_errors: u64,
+}
+
+impl Counts {
+    pub fn current() -> Self {
+        Counts {
+            hypotheses: HYPOTHESES.load(Ordering::Relaxed),
+            orders_filled: ORDERS_FILLED.load(Ordering::Relaxed),
+            orders_unfilled: ORDERS_UNFILLED.load(Ordering::Relaxed),
+            db_write_errors: DB_WRITE_ERRORS.load(Ordering::Relaxed),
+        }
+    }
+
+    fn since(&self, earlier: &Counts) -> Counts {
+        Counts {
+            hypotheses: self.hypotheses.saturating_sub(earlier.hypotheses),
+            orders_filled: self.orders_filled.saturating_sub(earlier.orders_filled),
+            orders_unfilled: self.orders_unfilled.saturating_sub(earlier.orders_unfilled),
+            db_write_errors: self.db_write_errors.saturating_sub(earlier.db_write_errors),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Anomaly {
+    HypothesisStall { minutes: i64 },
+    FillRateCollapse { filled: u64, finished: u64 },
+    DbWriteErrors { errors: u64, minutes: i64 },
+}
+
+impl Anomaly {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Anomaly::HypothesisStall { .. } => "hypothesis_stall",
+            Anomaly::FillRateCollapse { .. } => "fill_rate_collapse",
+            Anomaly::DbWriteErrors { .. } => "db_write_errors",
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Anomaly::HypothesisStall { minutes } => format!("No hypotheses generated in {} minutes", minutes),
+            Anomaly::FillRateCollapse { filled, finished } => format!(
+                "Only {} of the last {} finished orders filled ({:.0}%)",
+                filled, finished, *filled as f64 / *finished as f64 * 100.0,
+            ),
+            Anomaly::DbWriteErrors { errors, minutes } => format!("{} failed database writes in {} minutes", errors, minutes),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AnomalyConfig {
+    pub hypothesis_stall: Duration,  // no hypotheses for this long is a stall
+    pub fill_window: Duration,
+    pub min_fill_rate: f64,
+    pub min_finished_orders: u64,    // fewer finished orders than this is too few to judge
+    pub db_error_window: Duration,
+    pub max_db_errors: u64,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        AnomalyConfig {
+            hypothesis_stall: Duration::minutes(30),
+            fill_window: Duration::hours(1),
+            min_fill_rate: 0.25,
+            min_finished_orders: 8,
+            db_error_window: Duration::minutes(10),
+            max_db_errors: 5,
+        }
+    }
+}
+
+pub struct AnomalyDetector {
+    pub config: AnomalyConfig,
+    started_at: DateTime<Utc>,
+    samples: VecDeque<(DateTime<Utc>, Counts)>,
+    active: HashSet<&'static str>,
+    alerts: Option<Arc<AlertManager>>,
+}
+
+impl AnomalyDetector {
+    pub fn new(config: AnomalyConfig, now: DateTime<Utc>) -> Self {
+        AnomalyDetector {
+            config,
+            started_at: now,
+            samples: VecDeque::from([(now, Counts::current())]),
+            active: HashSet::new(),
+            alerts: None,
+        }
+    }
+
+    pub fn set_alert_manager(&mut self, alerts: Arc<AlertManager>) {
+        self.alerts = Some(alerts);
+    }
+
+    /// Totals as they stood at the latest sample no later than `at`
+    fn counts_at(&self, at: DateTime<Utc>) -> Counts {
+        self.samples.iter().rev().find(|(t, _)| *t <= at).or(self.samples.front()).map(|(_, c)| *c).unwrap_or_default()
+    }
+
+    /// Evaluate every window ending at `now` against totals `counts`
+    pub fn evaluate(&mut self, now: DateTime<Utc>, counts: Counts) -> Vec<Anomaly> {
+        self.samples.push_back((now, counts));
+        let longest = self.config.hypothesis_stall.max(self.config.fill_window).max(self.config.db_error_window);
+        while self.samples.len() > 2 && self.samples[1].0 <= now - longest {
+            self.samples.pop_front();
+        }
+
+        let mut anomalies = Vec::new();
+
+        let stall = self.config.hypothesis_stall;
+        if now - self.started_at >= stall && counts.since(&self.counts_at(now - stall)).hypotheses == 0 {
+            anomalies.push(Anomaly::HypothesisStall { minutes: stall.num_minutes() });
+        }
+
+        let fills = counts.since(&self.counts_at(now - self.config.fill_window));
+        let finished = fills.orders_filled + fills.orders_unfilled;
+        if finished >= self.config.min_finished_orders
+            && (fills.orders_filled as f64) < self.config.min_fill_rate * finished as f64 {
+            anomalies.push(Anomaly::FillRateCollapse { filled: fills.orders_filled, finished });
+        }
+
+        let errors = counts.since(&self.counts_at(now - self.config.db_error_window)).db_write_errors;
+        if errors > self.config.max_db_errors {
+            anomalies.push(Anomaly::DbWriteErrors { errors, minutes: self.config.db_error_window.num_minutes() });
+        }
+
+        anomalies
+    }
+
+    /// Sample the live counters; alert once when an anomaly appears and again when it clears
+    pub fn check(&mut self, now: DateTime<Utc>) -> Vec<Anomaly> {
+        let anomalies = self.evaluate(now, Counts::current());
+        let codes: HashSet<&'static str> = anomalies.iter().map(|a| a.code()).collect();
+
+        for anomaly in anomalies.iter().filter(|a| !self.active.contains(a.code())) {
+            warn!("🩻 System anomaly: {}", anomaly.describe());
+            self.alert(Alert::new(Severity::Warning, &format!("anomaly:{}", anomaly.code()), format!("System anomaly: {}", anomaly.code()))
+                .with_body(anomaly.describe()));
+        }
+        for cleared in self.active.difference(&codes) {
+            info!("✅ System anomaly cleared: {}", cleared);
+            self.alert(Alert::new(Severity::Info, &format!("anomaly_cleared:{}", cleared), format!("System anomaly cleared: {}", cleared)));
+        }
+
+        self.active = codes;
+        anomalies
+    }
+
+    fn alert(&self, alert: Alert) {
+        if let Some(alerts) = &self.alerts {
+            alerts.raise(alert);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_flag_stalls_fill_collapse_and_db_errors() {
+        let start = Utc::now();
+        let mut detector = AnomalyDetector::new(AnomalyConfig::default(), start);
+        let base = detector.samples[0].1;
+        let at = |minutes: i64| start + Duration::minutes(minutes);
+        let counts = |hypotheses: u64, filled: u64, unfilled: u64, errors: u64| Counts {
+            hypotheses: base.hypotheses + hypotheses,
+            orders_filled: base.orders_filled + filled,
+            orders_unfilled: base.orders_unfilled + unfilled,
+            db_write_errors: base.db_write_errors + errors,
+        };
+
+        // Healthy: steady hypotheses, most orders filling, a couple of DB errors
+        assert!(detector.evaluate(at(10), counts(5, 6, 2, 2)).is_empty());
+        assert!(detector.evaluate(at(20), counts(9, 8, 2, 2)).is_empty());
+
+        // Then generation stops, orders stop filling and writes start failing
+        assert!(detector.evaluate(at(40), counts(9, 8, 12, 4)).is_empty());
+        let anomalies = detector.evaluate(at(55), counts(9, 8, 30, 10));
+        assert_eq!(anomalies, vec![
+            Anomaly::HypothesisStall { minutes: 30 },
+            Anomaly::FillRateCollapse { filled: 8, finished: 38 },
+            Anomaly::DbWriteErrors { errors: 6, minutes: 10 },
+        ]);
+
+        // Generation resumes and writes recover; fills are still down over the hour
+        let codes: Vec<&str> = detector.evaluate(at(66), counts(10, 8, 30, 10)).iter().map(|a| a.code()).collect();
+        assert_eq!(codes, vec!["fill_rate_collapse"]);
+    }
+}
@@ -0,0 +1,283 @@
+// Archive - Snappy Parquet Files of Pruned Rows, Locally or in S3
+// Retention deletes old ticks and test results from Postgres; before it does, each day's rows
+// are written to a compressed Parquet file under RETENTION_ARCHIVE_DIR and, when
+// ARCHIVE_S3_BUCKET is set, uploaded with a SigV4-signed PUT (AWS or any S3-compatible store
+// via ARCHIVE_S3_ENDPOINT) and removed locally once the upload succeeds
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use arrow_array::{ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use chrono::{DateTime, NaiveDate, Utc};
+use hmac::{Hmac, Mac};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::core::batch_writer::TestResultRow;
+use crate::core::market_data::PriceTick;
+use crate::core::money;
+use crate::core::secrets;
+
+#[derive(Debug)]
+pub enum ArchiveError {
+    Io(std::io::Error),
+    Parquet(ParquetError),
+    Upload(String),
+}
+
+impl std::fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArchiveError::Io(e) => write!(f, "io: {}", e),
+            ArchiveError::Parquet(e) => write!(f, "parquet: {}", e),
+            ArchiveError::Upload(e) => write!(f, "s3 upload: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl From<std::io::Error> for ArchiveError {
+    fn from(e: std::io::Error) -> Self {
+        ArchiveError::Io(e)
+    }
+}
+
+impl From<ParquetError> for ArchiveError {
+    fn from(e: ParquetError) -> Self {
+        ArchiveError::Parquet(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct S3Bucket {
+    pub bucket: String,
+    pub region: String,
+    pub endpoint: String,  // path-style base URL, e.g. https://s3.us-east-1.amazonaws.com or a MinIO host
+    pub prefix: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl S3Bucket {
+    /// None unless ARCHIVE_S3_BUCKET and both AWS credentials are set
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| secrets::var(name).ok().filter(|v| !v.trim().is_empty());
+        let bucket = var("ARCHIVE_S3_BUCKET")?;
+        let (access_key, secret_key) = match (var("AWS_ACCESS_KEY_ID"), var("AWS_SECRET_ACCESS_KEY")) {
+            (Some(access), Some(secret)) => (access, secret),
+            _ => {
+                warn!("⚠️ ARCHIVE_S3_BUCKET is set without AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY - archiving locally only");
+                return None;
+            }
+        };
+        let region = var("AWS_REGION").unwrap_or_else(|| "us-east-1".to_string());
+        let endpoint = var("ARCHIVE_S3_ENDPOINT").unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", region));
+        Some(S3Bucket {
+            bucket,
+            region,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            prefix: var("ARCHIVE_S3_PREFIX").unwrap_or_else(|| "v26meme".to_string()),
+            access_key,
+            secret_key,
+        })
+    }
+
+    /// PUT one object; keys are built from table names and dates, so they never need escaping
+    pub async fn put(&self, http: &reqwest::Client, key: &str, body: Vec<u8>) -> Result<(), ArchiveError> {
+        let path = format!("/{}/{}/{}", self.bucket, self.prefix, key);
+        let host = self.endpoint.split("://").nth(1).unwrap_or(&self.endpoint).to_string();
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let payload_hash = hex(&Sha256::digest(&body));
+        let authorization = self.authorization("PUT", &path, &host, &payload_hash, now);
+
+        let response = http.put(format!("{}{}", self.endpoint, path))
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("authorization", authorization)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ArchiveError::Upload(e.to_string()))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(ArchiveError::Upload(format!("{} {}", status, detail.chars().take(200).collect::<String>())));
+        }
+        Ok(())
+    }
+
+    fn authorization(&self, method: &str, path: &str, host: &str, payload_hash: &str, at: DateTime<Utc>) -> String {
+        let amz_date = at.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = at.format("%Y%m%d").to_string();
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method, path, host, payload_hash, amz_date, signed_headers, payload_hash
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}", amz_date, scope, hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+        let key = signing_key(&self.secret_key, &date, &self.region, "s3");
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key, scope, signed_headers, hex(&hmac(&key, string_to_sign.as_bytes()))
+        )
+    }
+}
+
+/// The SigV4 key for one day, region and service
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let key = hmac(&key, region.as_bytes());
+    let key = hmac(&key, service.as_bytes());
+    hmac(&key, b"aws4_request")
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Where archived days go: always a local file first, then S3 when configured
+pub struct Archive {
+    dir: PathBuf,
+    s3: Option<S3Bucket>,
+    http: reqwest::Client,
+}
+
+impl Archive {
+    pub fn new(dir: PathBuf, s3: Option<S3Bucket>) -> Self {
+        Archive { dir, s3, http: reqwest::Client::new() }
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.s3 {
+            Some(s3) => format!("s3://{}/{} (staged in {})", s3.bucket, s3.prefix, self.dir.display()),
+            None => self.dir.display().to_string(),
+        }
+    }
+
+    /// Store one table's rows for one day; returns where they ended up. A day archived twice
+    /// (rows that arrived late) gets a second file rather than overwriting the first
+    pub async fn store(&self, table: &str, day: NaiveDate, parquet: Vec<u8>) -> Result<String, ArchiveError> {
+        let key = format!("{}/{}.{}.parquet", table, day.format("%Y-%m-%d"), Utc::now().format("%Y%m%dT%H%M%S"));
+        let path = self.dir.join(&key);
+        write_file(&path, &parquet).await?;
+
+        match &self.s3 {
+            Some(s3) => {
+                s3.put(&self.http, &key, parquet).await?;
+                tokio::fs::remove_file(&path).await?;
+                Ok(format!("s3://{}/{}/{}", s3.bucket, s3.prefix, key))
+            }
+            None => Ok(path.display().to_string()),
+        }
+    }
+}
+
+async fn write_file(path: &Path, bytes: &[u8]) -> Result<(), std::io::Error> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, bytes).await
+}
+
+fn timestamps(values: impl Iterator<Item = DateTime<Utc>>) -> ArrayRef {
+    Arc::new(TimestampMicrosecondArray::from(values.map(|at| at.timestamp_micros()).collect::<Vec<_>>()).with_timezone("UTC"))
+}
+
+fn snappy_parquet(schema: Arc<Schema>, columns: Vec<ArrayRef>) -> Result<Vec<u8>, ArchiveError> {
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(|e| ParquetError::ArrowError(e.to_string()))?;
+    let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut out = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut out, schema, Some(properties))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(out)
+}
+
+pub fn ticks_parquet(ticks: &[PriceTick]) -> Result<Vec<u8>, ArchiveError> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("timestamp", DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())), false),
+        Field::new("symbol", DataType::Utf8, false),
+        Field::new("exchange", DataType::Utf8, false),
+        Field::new("price", DataType::Float64, false),
+    ]));
+    snappy_parquet(schema, vec![
+        timestamps(ticks.iter().map(|t| t.timestamp)),
+        Arc::new(ticks.iter().map(|t| Some(t.symbol.as_str())).collect::<StringArray>()),
+        Arc::new(ticks.iter().map(|t| Some(t.exchange.as_str())).collect::<StringArray>()),
+        Arc::new(ticks.iter().map(|t| Some(t.price)).collect::<Float64Array>()),
+    ])
+}
+
+pub fn test_results_parquet(rows: &[TestResultRow]) -> Result<Vec<u8>, ArchiveError> {
+    let number = |name: &str| Field::new(name, DataType::Float64, false);
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("timestamp", DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())), false),
+        Field::new("pattern_hash", DataType::Utf8, false),
+        Field::new("run_id", DataType::Utf8, true),
+        Field::new("profitable", DataType::Boolean, false),
+        number("profit"),
+        number("entry_price"),
+        number("exit_price"),
+        Field::new("duration_seconds", DataType::Int64, false),
+        number("fees"),
+        number("slippage"),
+        number("capital"),
+    ]));
+    let numbers = |f: fn(&TestResultRow) -> f64| -> ArrayRef {
+        Arc::new(rows.iter().map(|r| Some(f(r))).collect::<Float64Array>())
+    };
+    snappy_parquet(schema, vec![
+        timestamps(rows.iter().map(|r| r.timestamp)),
+        Arc::new(rows.iter().map(|r| Some(r.pattern_hash.as_str())).collect::<StringArray>()),
+        Arc::new(rows.iter().map(|r| r.run_id.map(|id| id.to_string())).collect::<StringArray>()),
+        Arc::new(rows.iter().map(|r| Some(r.result.profitable)).collect::<BooleanArray>()),
+        numbers(|r| money::to_f64(r.result.profit)),
+        numbers(|r| money::to_f64(r.result.entry_price)),
+        numbers(|r| money::to_f64(r.result.exit_price)),
+        Arc::new(rows.iter().map(|r| Some(r.result.duration_seconds as i64)).collect::<Int64Array>()),
+        numbers(|r| money::to_f64(r.result.fees)),
+        numbers(|r| money::to_f64(r.result.slippage)),
+        numbers(|r| money::to_f64(r.result.capital)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    #[test]
+    fn test_signing_key_and_snappy_archive() {
+        // Worked example from the AWS Signature Version 4 documentation
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        assert_eq!(hex(&key), "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+
+        let ticks: Vec<PriceTick> = (0..1000).map(|i| PriceTick {
+            symbol: "DOGE-USD".into(),
+            exchange: "coinbase".into(),
+            price: 0.1 + i as f64 * 1e-6,
+            timestamp: DateTime::from_timestamp(1_700_000_000 + i, 0).unwrap(),
+        }).collect();
+        let path = std::env::temp_dir().join(format!("archive-{}.parquet", uuid::Uuid::new_v4()));
+        std::fs::write(&path, ticks_parquet(&ticks).unwrap()).unwrap();
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 1000);
+        assert_eq!(reader.metadata().row_group(0).column(1).compression(), Compression::SNAPPY);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,273 @@
+// Audit Log - Every Decision, Append-Only
+// Discovery, risk and execution record each approval, rejection and activation here with a
+// short reason code and the numbers behind it. Rows are never updated or deleted, so a
+// post-mortem can replay why the system did what it did long after stdout is gone. Entries
+// recorded from a decision path go through a WriteQueue to one writer task, which inserts
+// whatever has queued up in a single statement
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::core::anomaly::{self, Event};
+use crate::core::write_queue::{WriteBatches, WriteQueue, WRITE_QUEUE_BATCH, WRITE_QUEUE_CAPACITY, WRITE_QUEUE_LIMIT};
+
+// A batch of entries, each with the run it was recorded under, in one round trip
+const AUDIT_INSERT: &str = "
+    INSERT INTO audit_log (occurred_at, run_id, component, action, subject, reason_code, detail)
+    SELECT occurred_at, run_id, component, action, subject, reason_code, detail
+    FROM jsonb_to_recordset($1) AS r(occurred_at timestamptz, run_id uuid, component text, action text,
+        subject text, reason_code text, detail jsonb)
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Component {
+    Discovery,
+    Risk,
+    Execution,
+    Control,  // an operator, through the control API
+}
+
+impl Component {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Component::Discovery => "discovery",
+            Component::Risk => "risk",
+            Component::Execution => "execution",
+            Component::Control => "control",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "discovery" => Some(Component::Discovery),
+            "risk" => Some(Component::Risk),
+            "execution" => Some(Component::Execution),
+            "control" => Some(Component::Control),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    Approved,
+    Rejected,
+    Activated,  // a pattern promoted to live trading
+    Skipped,    // not worth acting on, e.g. a near-duplicate hypothesis
+    Halted,     // a breaker, reduce-only or the dead-man's switch engaged
+    Resumed,
+    Retired,    // a pattern taken out of live trading by an operator
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Approved => "approved",
+            AuditAction::Rejected => "rejected",
+            AuditAction::Activated => "activated",
+            AuditAction::Skipped => "skipped",
+            AuditAction::Halted => "halted",
+            AuditAction::Resumed => "resumed",
+            AuditAction::Retired => "retired",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "approved" => Some(AuditAction::Approved),
+            "rejected" => Some(AuditAction::Rejected),
+            "activated" => Some(AuditAction::Activated),
+            "skipped" => Some(AuditAction::Skipped),
+            "halted" => Some(AuditAction::Halted),
+            "resumed" => Some(AuditAction::Resumed),
+            "retired" => Some(AuditAction::Retired),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub component: Component,
+    pub action: AuditAction,
+    pub subject: String,      // pattern hash, position id, or "system"
+    pub reason_code: String,  // snake_case, stable enough to group by
+    pub detail: Value,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl AuditEntry {
+    pub fn new(component: Component, action: AuditAction, subject: &str, reason_code: &str) -> Self {
+        AuditEntry {
+            component,
+            action,
+            subject: subject.to_string(),
+            reason_code: reason_code.to_string(),
+            detail: Value::Null,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    pub fn with_detail(mut self, detail: Value) -> Self {
+        self.detail = detail;
+        self
+    }
+}
+
+#[derive(Clone)]
+pub struct AuditLogger {
+    pub run_id: Option<Uuid>,
+    db_pool: PgPool,
+    queue: WriteQueue<(Option<Uuid>, AuditEntry)>,  // entries from record(), shared by every clone
+}
+
+impl AuditLogger {
+    /// Starts the writer when called inside a tokio runtime; outside one, record() only warns
+    pub fn new(db_pool: PgPool, run_id: Option<Uuid>) -> Self {
+        let (queue, batches) = WriteQueue::channel("Audit", WRITE_QUEUE_CAPACITY, WRITE_QUEUE_LIMIT, WRITE_QUEUE_BATCH);
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(write_batches(db_pool.clone(), batches));
+        }
+        AuditLogger { run_id, db_pool, queue }
+    }
+
+    pub async fn write(&self, entry: &AuditEntry) -> Result<(), sqlx::Error> {
+        sqlx::query("
+            INSERT INTO audit_log (occurred_at, run_id, component, action, subject, reason_code, detail)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ")
+            .bind(entry.occurred_at)
+            .bind(self.run_id)
+            .bind(entry.component.as_str())
+            .bind(entry.action.as_str())
+            .bind(&entry.subject)
+            .bind(&entry.reason_code)
+            .bind(&entry.detail)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Queue for the writer, for synchronous decision paths; a slow or failed write is logged,
+    /// never allowed to block or fail the decision itself
+    pub fn record(&self, entry: AuditEntry) {
+        if let Err((_, entry)) = self.queue.push((self.run_id, entry)) {
+            warn!("⚠️ No audit writer running, dropped {} {} {}", entry.component.as_str(), entry.action.as_str(), entry.subject);
+        }
+    }
+
+    /// Wait for every recorded entry to be written; returns how many are still queued
+    pub async fn flush(&self, timeout: std::time::Duration) -> usize {
+        self.queue.flush(timeout).await
+    }
+
+    /// Everything recorded about one subject, oldest first
+    pub async fn history(&self, subject: &str, limit: i64) -> Result<Vec<AuditEntry>, sqlx::Error> {
+        let rows = sqlx::query("
+            SELECT component, action, subject, reason_code, detail, occurred_at
+            FROM (
+                SELECT * FROM audit_log WHERE subject = $1 ORDER BY occurred_at DESC, id DESC LIMIT $2
+            ) recent
+            ORDER BY occurred_at, id
+        ")
+            .bind(subject)
+            .bind(limit)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        Ok(rows.iter().filter_map(entry_from_row).collect())
+    }
+}
+
+/// The writer: everything queued since the last round trip goes in one insert
+async fn write_batches(db_pool: PgPool, mut batches: WriteBatches<(Option<Uuid>, AuditEntry)>) {
+    while let Some(batch) = batches.next().await {
+        let rows: Vec<Value> = batch.iter().map(|(run_id, entry)| json!({
+            "occurred_at": entry.occurred_at,
+            "run_id": run_id,
+            "component": entry.component.as_str(),
+            "action": entry.action.as_str(),
+            "subject": entry.subject,
+            "reason_code": entry.reason_code,
+            "detail": entry.detail,
+        })).collect();
+        if let Err(e) = sqlx::query(AUDIT_INSERT).persistent(true).bind(Value::Array(rows)).execute(&db_pool).await {
+            let (run_id, first) = &batch[0];
+            error!("❌ Failed to write {} audit entries from {} {} {} (run {:?}): {}",
+                batch.len(), first.component.as_str(), first.action.as_str(), first.subject, run_id, e);
+            anomaly::note(Event::DbWriteError);
+        }
+        batches.written(batch.len());
+    }
+}
+
+fn entry_from_row(row: &PgRow) -> Option<AuditEntry> {
+    Some(AuditEntry {
+        component: Component::parse(row.get("component"))?,
+        action: AuditAction::parse(row.get("action"))?,
+        subject: row.get("subject"),
+        reason_code: row.get("reason_code"),
+        detail: row.get("detail"),
+        occurred_at: row.get("occurred_at"),
+    })
+}
+
+/// Entries about a pattern and the positions it opened, in [from, to), oldest first
+pub async fn load_for_pattern(db_pool: &PgPool, pattern_hash: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<AuditEntry>, sqlx::Error> {
+    let rows = sqlx::query("
+        SELECT component, action, subject, reason_code, detail, occurred_at
+        FROM audit_log
+        WHERE (subject = $1 OR subject IN (SELECT position_id FROM positions WHERE pattern_hash = $1))
+          AND occurred_at >= $2 AND occurred_at < $3
+        ORDER BY occurred_at, id
+    ")
+        .bind(pattern_hash)
+        .bind(from)
+        .bind(to)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().filter_map(entry_from_row).collect())
+}
+
+/// Which entries to replay; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub run_id: Option<Uuid>,
+    pub subject: Option<String>,
+    pub component: Option<Component>,
+    pub since: Option<DateTime<Utc>>,
+    pub limit: i64,
+}
+
+/// Entries matching `filter`, oldest first; the most recent `limit` when there are more
+pub async fn load(db_pool: &PgPool, filter: &AuditFilter) -> Result<Vec<AuditEntry>, sqlx::Error> {
+    let rows = sqlx::query("
+        SELECT component, action, subject, reason_code, detail, occurred_at
+        FROM (
+            SELECT * FROM audit_log
+            WHERE ($1::uuid IS NULL OR run_id = $1)
+              AND ($2::text IS NULL OR subject = $2)
+              AND ($3::text IS NULL OR component = $3)
+              AND ($4::timestamptz IS NULL OR occurred_at >= $4)
+            ORDER BY occurred_at DESC, id DESC
+            LIMIT $5
+        ) recent
+        ORDER BY occurred_at, id
+    ")
+        .bind(filter.run_id)
+        .bind(&filter.subject)
+        .bind(filter.component.map(|c| c.as_str()))
+        .bind(filter.since)
+        .bind(filter.limit)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().filter_map(entry_from_row).collect())
+}
@@ -0,0 +1,277 @@
+// Autoscale - The Hypothesis Rate Follows What the System Can Absorb
+// Without an operator override, discovery ran at the equity-scaled test budget's rate whatever
+// else was going on. Now the loop revisits the rate every `interval_secs`. It settles on the
+// budget's rate; while `backlog_threshold` bred or submitted candidates wait for a test it may
+// rise to `backlog_boost` times that, never past the budget policy's maximum; and when less than
+// `min_headroom` of the test venue's per-minute request limit is left it falls, so live orders
+// keep their share. Each adjustment moves the rate by at most `max_step_pct` and is logged with
+// its reason and the readings behind it
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use crate::core::exchange::{Exchange, ExchangeError, OrderRequest, OrderStatusReport, Quote};
+use crate::core::money::Decimal;
+use crate::core::tuning::HYPOTHESES_PER_HOUR_BOUNDS;
+
+/// How far back the meter counts requests against a venue's per-minute limit
+pub const REQUEST_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoscalePolicy {
+    pub enabled: bool,
+    pub interval_secs: u64,              // between adjustments
+    pub max_step_pct: f64,               // of the current rate, per adjustment
+    pub venue_requests_per_minute: u32,  // the test venue's rate limit
+    pub min_headroom: f64,               // share of that limit kept free for live orders
+    pub backlog_threshold: usize,        // waiting candidates that justify a faster search
+    pub backlog_boost: f64,              // multiple of the budget's rate while they wait
+}
+
+impl Default for AutoscalePolicy {
+    fn default() -> Self {
+        AutoscalePolicy {
+            enabled: true,
+            interval_secs: 300,
+            max_step_pct: 0.25,
+            venue_requests_per_minute: 600,
+            min_headroom: 0.2,
+            backlog_threshold: 50,
+            backlog_boost: 1.5,
+        }
+    }
+}
+
+/// What the rate is adjusted against
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pressure {
+    pub budget_rate: u32,  // the equity-scaled test budget's hypotheses per hour
+    pub headroom: f64,     // share of the test venue's request limit unused over the last minute
+    pub backlog: usize,    // offspring and plugin submissions waiting for a test
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaleReason {
+    TestBudget,  // settling back to the budget's rate
+    Backlog,     // candidates waiting
+    RateLimit,   // the venue is close to its limit
+}
+
+impl ScaleReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScaleReason::TestBudget => "test_budget",
+            ScaleReason::Backlog => "backlog",
+            ScaleReason::RateLimit => "rate_limit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adjustment {
+    pub from: u32,
+    pub to: u32,
+    pub target: u32,  // where the rate is heading; `to` is one step toward it
+    pub reason: ScaleReason,
+}
+
+impl AutoscalePolicy {
+    /// The rate `pressure` calls for, and which signal decided it
+    pub fn target(&self, current: u32, ceiling: u32, pressure: &Pressure) -> (u32, ScaleReason) {
+        let mut target = pressure.budget_rate as f64;
+        let mut reason = ScaleReason::TestBudget;
+        if pressure.backlog >= self.backlog_threshold {
+            target = (target * self.backlog_boost).min(ceiling as f64).max(target);
+            reason = ScaleReason::Backlog;
+        }
+        if pressure.headroom < self.min_headroom {
+            // The current rate scaled so the venue's load would leave min_headroom free
+            let used = (1.0 - pressure.headroom).max(1e-6);
+            let allowed = current as f64 * (1.0 - self.min_headroom) / used;
+            if allowed < target {
+                target = allowed;
+                reason = ScaleReason::RateLimit;
+            }
+        }
+        let (min, max) = HYPOTHESES_PER_HOUR_BOUNDS;
+        ((target.round() as u32).clamp(min, max), reason)
+    }
+
+    /// One step from `current` toward the target, or None when it is already there
+    pub fn adjust(&self, current: u32, ceiling: u32, pressure: &Pressure) -> Option<Adjustment> {
+        let (target, reason) = self.target(current, ceiling, pressure);
+        if target == current {
+            return None;
+        }
+        let step = ((current as f64 * self.max_step_pct).round() as u32).max(1);
+        let to = if target > current { target.min(current + step) } else { target.max(current.saturating_sub(step)) };
+        Some(Adjustment { from: current, to, target, reason })
+    }
+}
+
+/// The policy and when it last looked; owned by the discovery loop
+#[derive(Debug, Clone, Default)]
+pub struct Autoscaler {
+    pub policy: AutoscalePolicy,
+    pub ceiling: u32,  // the budget policy's max_hypotheses_per_hour
+    checked: Option<DateTime<Utc>>,
+}
+
+impl Autoscaler {
+    pub fn new(policy: AutoscalePolicy, ceiling: u32) -> Self {
+        Autoscaler { policy, ceiling, checked: None }
+    }
+
+    /// An adjustment once every interval_secs; None between them, when disabled, or when the rate is right
+    pub fn tick(&mut self, now: DateTime<Utc>, current: u32, pressure: &Pressure) -> Option<Adjustment> {
+        if !self.policy.enabled {
+            return None;
+        }
+        let interval = chrono::Duration::seconds(self.policy.interval_secs as i64);
+        if self.checked.is_some_and(|at| now - at < interval) {
+            return None;
+        }
+        self.checked = Some(now);
+        self.policy.adjust(current, self.ceiling.max(pressure.budget_rate), pressure)
+    }
+}
+
+/// Requests sent to each venue over the last minute; cheap to clone, every clone counts together
+#[derive(Clone, Default)]
+pub struct RequestMeter {
+    sent: Arc<Mutex<HashMap<String, VecDeque<Instant>>>>,
+}
+
+impl RequestMeter {
+    pub fn record(&self, venue: &str, requests: u32) {
+        let now = Instant::now();
+        let mut sent = self.sent.lock().unwrap();
+        let times = sent.entry(venue.to_string()).or_default();
+        prune(times, now);
+        times.extend(std::iter::repeat_n(now, requests as usize));
+    }
+
+    pub fn last_minute(&self, venue: &str) -> u32 {
+        let mut sent = self.sent.lock().unwrap();
+        sent.get_mut(venue).map(|times| {
+            prune(times, Instant::now());
+            times.len() as u32
+        }).unwrap_or(0)
+    }
+
+    /// Share of `limit` requests per minute still unused on `venue`; 1.0 when there is no limit
+    pub fn headroom(&self, venue: &str, limit: u32) -> f64 {
+        if limit == 0 {
+            return 1.0;
+        }
+        (1.0 - self.last_minute(venue) as f64 / limit as f64).max(0.0)
+    }
+}
+
+fn prune(times: &mut VecDeque<Instant>, now: Instant) {
+    while times.front().is_some_and(|at| now.duration_since(*at) >= REQUEST_WINDOW) {
+        times.pop_front();
+    }
+}
+
+/// A venue whose every call is counted against its rate limit
+pub struct MeteredExchange {
+    inner: Arc<dyn Exchange>,
+    meter: RequestMeter,
+}
+
+impl MeteredExchange {
+    pub fn new(inner: Arc<dyn Exchange>, meter: RequestMeter) -> Self {
+        MeteredExchange { inner, meter }
+    }
+
+    fn count(&self) {
+        self.meter.record(self.inner.name(), 1);
+    }
+}
+
+#[async_trait]
+impl Exchange for MeteredExchange {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<Quote, ExchangeError> {
+        self.count();
+        self.inner.quote(symbol).await
+    }
+
+    async fn submit_order(&self, order: &OrderRequest) -> Result<OrderStatusReport, ExchangeError> {
+        self.count();
+        self.inner.submit_order(order).await
+    }
+
+    async fn find_order(&self, client_order_id: &str) -> Result<Option<OrderStatusReport>, ExchangeError> {
+        self.count();
+        self.inner.find_order(client_order_id).await
+    }
+
+    async fn cancel_order(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError> {
+        self.count();
+        self.inner.cancel_order(exchange_order_id).await
+    }
+
+    async fn order_status(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError> {
+        self.count();
+        self.inner.order_status(exchange_order_id).await
+    }
+
+    async fn balances(&self) -> Result<HashMap<String, Decimal>, ExchangeError> {
+        self.count();
+        self.inner.balances().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::exchange::PaperExchange;
+
+    #[tokio::test]
+    async fn test_rate_follows_budget_backlog_and_venue_headroom() {
+        let policy = AutoscalePolicy::default();
+        let calm = Pressure { budget_rate: 100, headroom: 1.0, backlog: 0 };
+        assert_eq!(policy.adjust(100, 200, &calm), None);
+
+        // Waiting candidates lift the rate a step at a time, up to the budget policy's maximum
+        let waiting = Pressure { backlog: 80, ..calm };
+        assert_eq!(policy.target(100, 120, &waiting), (120, ScaleReason::Backlog));
+        let up = policy.adjust(100, 200, &waiting).unwrap();
+        assert_eq!((up.to, up.target, up.reason), (125, 150, ScaleReason::Backlog));
+
+        // A venue 90% used is brought back to 80%, even with a backlog
+        let limited = Pressure { headroom: 0.1, ..waiting };
+        let down = policy.adjust(150, 200, &limited).unwrap();
+        assert_eq!((down.to, down.target, down.reason), (133, 133, ScaleReason::RateLimit));
+
+        // Once the pressure is gone it settles back on the budget
+        let back = policy.adjust(133, 200, &calm).unwrap();
+        assert_eq!((back.to, back.reason), (100, ScaleReason::TestBudget));
+
+        // The loop adjusts once per interval
+        let mut scaler = Autoscaler::new(policy, 200);
+        let now = Utc::now();
+        assert!(scaler.tick(now, 100, &waiting).is_some());
+        assert!(scaler.tick(now + chrono::Duration::seconds(60), 125, &waiting).is_none());
+        assert!(scaler.tick(now + chrono::Duration::seconds(300), 125, &waiting).is_some());
+
+        // Every call to a metered venue counts against its limit
+        let meter = RequestMeter::default();
+        let venue = MeteredExchange::new(Arc::new(PaperExchange::new("coinbase", 0.006)), meter.clone());
+        venue.balances().await.unwrap();
+        meter.record("coinbase", 299);
+        assert_eq!(meter.last_minute("coinbase"), 300);
+        assert!((meter.headroom("coinbase", 600) - 0.5).abs() < 1e-9);
+        assert_eq!(meter.headroom("kraken", 600), 1.0);
+    }
+}
@@ -0,0 +1,149 @@
+// Backtest - Re-scoring Patterns From Their Recorded Tests
+// Every test trade a hypothesis ever made is kept in test_results. Re-running validation over
+// any window of them answers "would this pattern still activate?" and "what if costs were
+// higher than we assumed?" without touching a venue: win rate, net profit, Sharpe and the
+// worst run of cumulative test P&L, under today's activation thresholds
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+
+use crate::core::discovery_engine::{test_sharpe, TestResult, LEGACY_TEST_CAPITAL};
+use crate::core::money::{self, Decimal};
+
+// A pattern's whole history gets its own statement instead of one with optional bounds that the
+// planner can't narrow. Both are served in order by idx_test_results_pattern_time
+const TEST_RESULTS_FOR_PATTERN: &str = "
+    SELECT profitable, profit, entry_price, exit_price, duration_seconds::int8 AS duration_seconds, fees, slippage,
+           COALESCE(capital, $2) AS capital
+    FROM test_results
+    WHERE pattern_hash = $1
+    ORDER BY timestamp
+";
+
+const TEST_RESULTS_IN_WINDOW: &str = "
+    SELECT profitable, profit, entry_price, exit_price, duration_seconds::int8 AS duration_seconds, fees, slippage,
+           COALESCE(capital, $2) AS capital
+    FROM test_results
+    WHERE pattern_hash = $1
+      AND timestamp >= COALESCE($3::timestamptz, '-infinity')
+      AND timestamp < COALESCE($4::timestamptz, 'infinity')
+    ORDER BY timestamp
+";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub pattern_hash: String,
+    pub tests: usize,
+    pub wins: usize,
+    pub win_rate: f64,
+    pub net_profit: Decimal,
+    pub costs: Decimal,       // fees plus slippage, after the multiplier
+    pub sharpe_ratio: f64,    // same annualization discovery validates with
+    pub max_drawdown: Decimal,  // deepest fall in cumulative test P&L, in dollars
+    pub would_activate: bool,
+}
+
+impl BacktestReport {
+    /// Score `results` (oldest first) with costs scaled by `cost_multiplier`
+    pub fn run(pattern_hash: &str, results: &[TestResult], cost_multiplier: f64, min_tests: u32, min_win_rate: f64) -> Self {
+        let rescored: Vec<TestResult> = results.iter().map(|r| {
+            let extra = money::share(r.fees + r.slippage, cost_multiplier - 1.0);
+            TestResult {
+                profit: r.profit - extra,
+                profitable: r.profit - extra > Decimal::ZERO,
+                fees: money::share(r.fees, cost_multiplier),
+                slippage: money::share(r.slippage, cost_multiplier),
+                ..r.clone()
+            }
+        }).collect();
+
+        let wins = rescored.iter().filter(|r| r.profitable).count();
+        let win_rate = if rescored.is_empty() { 0.0 } else { wins as f64 / rescored.len() as f64 };
+
+        let mut cumulative = Decimal::ZERO;
+        let mut peak = Decimal::ZERO;
+        let mut max_drawdown = Decimal::ZERO;
+        for r in &rescored {
+            cumulative += r.profit;
+            peak = peak.max(cumulative);
+            max_drawdown = max_drawdown.max(peak - cumulative);
+        }
+
+        BacktestReport {
+            pattern_hash: pattern_hash.to_string(),
+            tests: rescored.len(),
+            wins,
+            win_rate,
+            net_profit: cumulative,
+            costs: rescored.iter().map(|r| r.fees + r.slippage).sum(),
+            sharpe_ratio: test_sharpe(&rescored),
+            max_drawdown,
+            would_activate: rescored.len() >= min_tests as usize && win_rate >= min_win_rate,
+        }
+    }
+}
+
+/// A pattern's test trades recorded in [from, to), oldest first
+pub async fn load_test_results(
+    db_pool: &PgPool,
+    pattern_hash: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<Vec<TestResult>, sqlx::Error> {
+    let query = match (from, to) {
+        (None, None) => sqlx::query(TEST_RESULTS_FOR_PATTERN).bind(pattern_hash).bind(LEGACY_TEST_CAPITAL),
+        _ => sqlx::query(TEST_RESULTS_IN_WINDOW).bind(pattern_hash).bind(LEGACY_TEST_CAPITAL).bind(from).bind(to),
+    };
+    let rows = query.persistent(true).fetch_all(db_pool).await?;
+
+    Ok(rows.iter().map(|row| TestResult {
+        capital: row.get("capital"),
+        profitable: row.get("profitable"),
+        profit: row.get("profit"),
+        entry_price: row.get("entry_price"),
+        exit_price: row.get("exit_price"),
+        duration_seconds: row.get::<i64, _>("duration_seconds") as u64,
+        fees: row.get("fees"),
+        slippage: row.get("slippage"),
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn result(profit: Decimal, costs: Decimal) -> TestResult {
+        TestResult {
+            capital: dec!(5),
+            profitable: profit > Decimal::ZERO,
+            profit,
+            entry_price: Decimal::ONE,
+            exit_price: Decimal::ONE,
+            duration_seconds: 600,
+            fees: costs,
+            slippage: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_higher_costs_can_flip_a_pattern_out_of_activation() {
+        let results: Vec<TestResult> = [dec!(0.5), dec!(0.15), dec!(-0.4), dec!(0.6), dec!(-0.2)].into_iter()
+            .map(|profit| result(profit, dec!(0.1)))
+            .collect();
+
+        let as_recorded = BacktestReport::run("abc", &results, 1.0, 5, 0.55);
+        assert_eq!(as_recorded.wins, 3);
+        assert!(as_recorded.would_activate);
+        assert_eq!(as_recorded.net_profit, dec!(0.65));
+        assert_eq!(as_recorded.max_drawdown, dec!(0.4));
+
+        // Tripling costs takes $0.20 more from every test; the thin winner becomes a loser
+        let stressed = BacktestReport::run("abc", &results, 3.0, 5, 0.55);
+        assert_eq!(stressed.wins, 2);
+        assert!(!stressed.would_activate);
+        assert_eq!(stressed.net_profit, dec!(-0.35));
+        assert_eq!(stressed.costs, dec!(1.5));
+    }
+}
@@ -0,0 +1,229 @@
+// Backtest Pool - The Candidate Queue Over History, on Every Core
+// Evolution breeds candidates far faster than discovery can paper-trade them. `v26meme backtest
+// --queue` replays the ones still waiting for activation over stored ticks instead. Candidates
+// are partitioned by symbol: each symbol's candles are loaded once, a few symbols at a time on
+// tokio, and the candidates watching it are walked by the evaluator on a rayon pool with one
+// thread per core. A candidate that trades any symbol is walked on each symbol of the universe.
+// The walks on a symbol share one SeriesCache, so each metric is computed once per minute for
+// all of them. Its signals become round trips paying the taker fee both ways, scored as
+// `backtest` scores recorded tests. Progress is counted as candidates finish, so an overnight run can be watched
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use chrono::{DateTime, Utc};
+use futures_util::{StreamExt, TryStreamExt};
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use sqlx::PgPool;
+
+use crate::core::backtest::BacktestReport;
+use crate::core::discovery_engine::{Hypothesis, TestResult};
+use crate::core::evaluator::{self, Candle, Signal, SignalKind};
+use crate::core::metric_cache::{MetricCache, SeriesCache};
+use crate::core::money::{self, Decimal};
+use crate::core::pattern_replay::{self, PATTERN_COLUMNS};
+
+/// Symbols whose candles are held in memory at once
+pub const SYMBOL_LOADS: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct PoolSettings {
+    pub threads: usize,     // 0 is one per core
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub capital: Decimal,   // committed to each simulated trade
+    pub fee_rate: f64,      // taker, paid on entry and on exit
+    pub min_tests: u32,
+    pub min_win_rate: f64,
+}
+
+/// One candidate's score on one symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandidateReport {
+    pub symbol: String,
+    pub report: BacktestReport,
+}
+
+/// Candidate walks finished out of the total; shared with whatever reports on the run
+pub struct Progress {
+    total: AtomicUsize,
+    done: AtomicUsize,
+    started: Instant,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Progress { total: AtomicUsize::new(0), done: AtomicUsize::new(0), started: Instant::now() }
+    }
+}
+
+impl Progress {
+    pub fn total(&self) -> usize {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    pub fn done(&self) -> usize {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    pub fn describe(&self) -> String {
+        let (done, total) = (self.done(), self.total());
+        let rate = done as f64 / self.started.elapsed().as_secs_f64().max(1e-3);
+        let eta = if rate > 0.0 { format!(", ~{:.0}s left", total.saturating_sub(done) as f64 / rate) } else { String::new() };
+        format!("{}/{} candidate walks ({:.0}/s{})", done, total, rate, eta)
+    }
+}
+
+/// Candidates not yet activated, newest first
+pub async fn load_queue(db_pool: &PgPool, limit: i64) -> Result<Vec<Hypothesis>, sqlx::Error> {
+    let query = format!("
+        SELECT {} FROM discovered_patterns
+        WHERE NOT COALESCE(is_active, false)
+        ORDER BY created_at DESC
+        LIMIT $1
+    ", PATTERN_COLUMNS);
+    let rows = sqlx::query(&query).bind(limit).fetch_all(db_pool).await?;
+    Ok(rows.iter().map(|row| pattern_replay::hypothesis(row).0).collect())
+}
+
+/// Candidates by the symbol they are walked on; one that trades any symbol goes under each of `universe`
+pub fn partition(candidates: Vec<Hypothesis>, universe: &[String]) -> BTreeMap<String, Vec<Hypothesis>> {
+    let mut by_symbol: BTreeMap<String, Vec<Hypothesis>> = BTreeMap::new();
+    for h in candidates {
+        match &h.symbol {
+            Some(symbol) => by_symbol.entry(symbol.clone()).or_default().push(h),
+            None => {
+                for symbol in universe {
+                    by_symbol.entry(symbol.clone()).or_default().push(h.clone());
+                }
+            }
+        }
+    }
+    by_symbol
+}
+
+/// Round trips from a walk's signals; a position still open at the end is left out
+pub fn trades(signals: &[Signal], capital: Decimal, fee_rate: f64) -> Vec<TestResult> {
+    let mut results = Vec::new();
+    let mut entry: Option<&Signal> = None;
+    for signal in signals {
+        match (signal.kind, entry) {
+            (SignalKind::Entry, _) => entry = Some(signal),
+            (SignalKind::Exit | SignalKind::Timeout, Some(open)) => {
+                let (entry_price, exit_price) = (money::from_f64(open.price), money::from_f64(signal.price));
+                let fees = money::share(capital, 2.0 * fee_rate);
+                let profit = (capital * exit_price).checked_div(entry_price).map_or(Decimal::ZERO, |value| value - capital) - fees;
+                results.push(TestResult {
+                    capital,
+                    profitable: profit > Decimal::ZERO,
+                    profit,
+                    entry_price,
+                    exit_price,
+                    duration_seconds: (signal.at - open.at).num_seconds().max(0) as u64,
+                    fees,
+                    slippage: Decimal::ZERO,
+                });
+                entry = None;
+            }
+            _ => {}
+        }
+    }
+    results
+}
+
+/// Walk every candidate over one symbol's candles, spread across `pool`
+pub fn replay_symbol(pool: &ThreadPool, symbol: &str, candidates: &[Hypothesis], candles: &[Candle], metrics: &SeriesCache, settings: &PoolSettings, progress: &Progress) -> Vec<CandidateReport> {
+    pool.install(|| candidates.par_iter().map(|h| {
+        let results = trades(&evaluator::evaluate_cached(h, candles, metrics), settings.capital, settings.fee_rate);
+        progress.done.fetch_add(1, Ordering::Relaxed);
+        CandidateReport {
+            symbol: symbol.to_string(),
+            report: BacktestReport::run(&h.hash, &results, 1.0, settings.min_tests, settings.min_win_rate),
+        }
+    }).collect())
+}
+
+/// Replay `candidates` over [from, to); reports come back grouped by symbol
+pub async fn run(db_pool: &PgPool, candidates: Vec<Hypothesis>, universe: &[String], settings: PoolSettings, progress: Arc<Progress>) -> Result<Vec<CandidateReport>, Box<dyn std::error::Error + Send + Sync>> {
+    let by_symbol = partition(candidates, universe);
+    progress.total.store(by_symbol.values().map(Vec::len).sum(), Ordering::Relaxed);
+
+    let pool = Arc::new(rayon::ThreadPoolBuilder::new().num_threads(settings.threads).build()?);
+    let settings = Arc::new(settings);
+    let metrics = Arc::new(MetricCache::default());
+    let reports: Vec<Vec<CandidateReport>> = futures_util::stream::iter(by_symbol)
+        .map(|(symbol, candidates)| {
+            let (pool, settings, progress, metrics) = (pool.clone(), settings.clone(), progress.clone(), metrics.clone());
+            async move {
+                let candles = evaluator::load_candles(db_pool, &symbol, settings.from, settings.to).await?;
+                let reports = tokio::task::spawn_blocking(move || {
+                    let reports = replay_symbol(&pool, &symbol, &candidates, &candles, &metrics.series(&symbol, evaluator::CANDLE_MINUTES), &settings, &progress);
+                    metrics.forget(&symbol);
+                    reports
+                }).await?;
+                Ok::<_, Box<dyn std::error::Error + Send + Sync>>(reports)
+            }
+        })
+        .buffered(SYMBOL_LOADS)
+        .try_collect()
+        .await?;
+    Ok(reports.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use crate::core::discovery_engine::Condition;
+
+    fn candidate(hash: &str, symbol: Option<&str>, threshold: f64) -> Hypothesis {
+        let condition = |operator: &str, value: f64| Condition { metric: "price_delta_1m".to_string(), operator: operator.to_string(), value, weight: 0.5 };
+        Hypothesis {
+            hash: hash.to_string(),
+            entry_conditions: vec![condition(">", threshold)],
+            exit_conditions: vec![condition("<", 0.0)],
+            timeframe: 30,
+            created_at: 0,
+            symbol: symbol.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_candidates_run_in_parallel_per_symbol_and_score_their_round_trips() {
+        let universe = ["DOGE-USD".to_string(), "PEPE-USD".to_string()];
+        let by_symbol = partition(vec![candidate("any", None, 1.0), candidate("doge", Some("DOGE-USD"), 1.0)], &universe);
+        assert_eq!(by_symbol["DOGE-USD"].len(), 2);
+        assert_eq!(by_symbol["PEPE-USD"].iter().map(|h| h.hash.as_str()).collect::<Vec<_>>(), ["any"]);
+
+        // Two pumps of 3% that each fade a minute later
+        let start = "2026-03-14T14:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let closes = [1.0, 1.0, 1.03, 1.04, 1.01, 1.01, 1.0403, 1.05, 1.02];
+        let candles: Vec<Candle> = closes.iter().enumerate().map(|(i, close)| Candle {
+            start: start + Duration::minutes(i as i64),
+            open: *close, high: *close, low: *close, close: *close, volume: 100.0, trades: 5,
+        }).collect();
+
+        let candidates: Vec<Hypothesis> = (0..64).map(|i| candidate(&format!("c{}", i), None, if i % 2 == 0 { 2.0 } else { 5.0 })).collect();
+        let settings = PoolSettings {
+            threads: 4,
+            from: start,
+            to: start + Duration::minutes(9),
+            capital: Decimal::TEN,
+            fee_rate: 0.001,
+            min_tests: 2,
+            min_win_rate: 0.5,
+        };
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(settings.threads).build().unwrap();
+        let progress = Progress::default();
+        let reports = replay_symbol(&pool, "DOGE-USD", &candidates, &candles, &SeriesCache::default(), &settings, &progress);
+
+        assert_eq!((reports.len(), progress.done()), (64, 64));
+        // Entering at 1.03 and 1.0403 and leaving at 1.01 and 1.02 loses both times
+        let fired = &reports[0].report;
+        assert_eq!((fired.pattern_hash.as_str(), fired.tests, fired.wins), ("c0", 2, 0));
+        assert_eq!(fired.costs, Decimal::new(4, 2));
+        assert!(reports.iter().skip(1).step_by(2).all(|r| r.report.tests == 0 && !r.report.would_activate));
+    }
+}
@@ -0,0 +1,364 @@
+// Batch Writer - Buffered Test Result and Tick Inserts with an Outbox
+// One INSERT round trip per test result or tick will not keep up once market data ingestion and
+// 100 tests/hour are both live. Rows are buffered in memory and written as one multi-row INSERT
+// per table every WRITE_FLUSH_MS, or sooner once WRITE_BATCH_SIZE rows are waiting. A batch the
+// table rejects is parked in write_outbox and retried on later flushes; if even the outbox is
+// unreachable the rows stay buffered, oldest dropped first past WRITE_BUFFER_LIMIT
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+use tokio::sync::Notify;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::core::anomaly::{self, Event};
+use crate::core::discovery_engine::TestResult;
+use crate::core::error::Result;
+use crate::core::market_data::PriceTick;
+
+// jsonb_to_recordset turns a whole batch into rows in one statement, and the same statement
+// replays a parked batch from the outbox
+const TEST_RESULTS_INSERT: &str = "
+    INSERT INTO test_results
+    (pattern_hash, profitable, profit, entry_price, exit_price, duration_seconds, fees, slippage, capital, run_id, timestamp)
+    SELECT pattern_hash, profitable, profit, entry_price, exit_price, duration_seconds, fees, slippage, capital, run_id, timestamp
+    FROM jsonb_to_recordset($1) AS r(pattern_hash text, profitable boolean, profit numeric, entry_price numeric,
+        exit_price numeric, duration_seconds integer, fees numeric, slippage numeric, capital numeric, run_id uuid,
+        timestamp timestamptz)
+";
+
+const MARKET_DATA_INSERT: &str = "
+    INSERT INTO market_data (timestamp, symbol, exchange, price)
+    SELECT timestamp, symbol, exchange, price
+    FROM jsonb_to_recordset($1) AS r(timestamp timestamptz, symbol text, exchange text, price numeric)
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    TestResults,
+    MarketData,
+}
+
+impl Target {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Target::TestResults => "test_results",
+            Target::MarketData => "market_data",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "test_results" => Some(Target::TestResults),
+            "market_data" => Some(Target::MarketData),
+            _ => None,
+        }
+    }
+
+    fn insert_sql(&self) -> &'static str {
+        match self {
+            Target::TestResults => TEST_RESULTS_INSERT,
+            Target::MarketData => MARKET_DATA_INSERT,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    pub batch_size: usize,        // flush early once this many rows are waiting
+    pub flush_interval: Duration,
+    pub buffer_limit: usize,      // rows kept per table while the database is unreachable
+    pub max_attempts: i32,        // outbox retries before a batch is left for an operator
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        BatchConfig {
+            batch_size: 500,
+            flush_interval: Duration::from_millis(1000),
+            buffer_limit: 50_000,
+            max_attempts: 10,
+        }
+    }
+}
+
+impl BatchConfig {
+    pub fn from_env() -> Self {
+        let defaults = BatchConfig::default();
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok()).filter(|v| *v > 0);
+        BatchConfig {
+            batch_size: var("WRITE_BATCH_SIZE").map_or(defaults.batch_size, |v| v as usize),
+            flush_interval: var("WRITE_FLUSH_MS").map_or(defaults.flush_interval, Duration::from_millis),
+            buffer_limit: var("WRITE_BUFFER_LIMIT").map_or(defaults.buffer_limit, |v| v as usize),
+            max_attempts: defaults.max_attempts,
+        }
+    }
+}
+
+/// A test_results row as buffered; serializes to the column names the insert expects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestResultRow {
+    pub pattern_hash: String,
+    #[serde(flatten)]
+    pub result: TestResult,
+    pub run_id: Option<Uuid>,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Default)]
+struct Buffers {
+    test_results: Vec<TestResultRow>,
+    ticks: Vec<PriceTick>,
+}
+
+#[derive(Clone)]
+pub struct BatchWriter {
+    db_pool: PgPool,
+    config: BatchConfig,
+    buffers: Arc<Mutex<Buffers>>,
+    flushing: Arc<tokio::sync::Mutex<()>>,  // held for a whole flush, so readers never see a batch in neither place
+    full: Arc<Notify>,
+}
+
+impl BatchWriter {
+    pub fn new(db_pool: PgPool, config: BatchConfig) -> Self {
+        BatchWriter {
+            db_pool,
+            config,
+            buffers: Arc::new(Mutex::new(Buffers::default())),
+            flushing: Arc::new(tokio::sync::Mutex::new(())),
+            full: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Spawn the flush loop; needs a running tokio runtime
+    pub fn start(db_pool: PgPool, config: BatchConfig) -> Self {
+        let writer = Self::new(db_pool, config);
+        let flusher = writer.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(flusher.config.flush_interval);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = flusher.full.notified() => {}
+                }
+                flusher.flush_now().await;
+            }
+        });
+        writer
+    }
+
+    pub fn record_test_result(&self, pattern_hash: &str, result: &TestResult, run_id: Option<Uuid>) {
+        let mut buffers = self.buffers.lock().unwrap();
+        buffers.test_results.push(TestResultRow {
+            pattern_hash: pattern_hash.to_string(),
+            result: result.clone(),
+            run_id,
+            timestamp: Utc::now(),
+        });
+        if buffers.test_results.len() >= self.config.batch_size {
+            self.full.notify_one();
+        }
+    }
+
+    /// For venue streams writing their own ticks; the market_data poller reads that table, so
+    /// it must not write back what it reads
+    pub fn record_tick(&self, tick: &PriceTick) {
+        let mut buffers = self.buffers.lock().unwrap();
+        buffers.ticks.push(tick.clone());
+        if buffers.ticks.len() >= self.config.batch_size {
+            self.full.notify_one();
+        }
+    }
+
+    pub fn buffered(&self) -> usize {
+        let buffers = self.buffers.lock().unwrap();
+        buffers.test_results.len() + buffers.ticks.len()
+    }
+
+    /// Write everything buffered, then retry parked batches; returns how many rows are still buffered
+    pub async fn flush_now(&self) -> usize {
+        let _flushing = self.flushing.lock().await;
+        let (test_results, ticks) = {
+            let mut buffers = self.buffers.lock().unwrap();
+            (std::mem::take(&mut buffers.test_results), std::mem::take(&mut buffers.ticks))
+        };
+
+        let mut reachable = true;
+        if !test_results.is_empty() && !self.write_or_park(Target::TestResults, &test_results).await {
+            reachable = false;
+            let dropped = restore(&mut self.buffers.lock().unwrap().test_results, test_results, self.config.buffer_limit);
+            if dropped > 0 {
+                error!("🚨 Dropped {} buffered test results past WRITE_BUFFER_LIMIT", dropped);
+            }
+        }
+        if !ticks.is_empty() && !self.write_or_park(Target::MarketData, &ticks).await {
+            reachable = false;
+            let dropped = restore(&mut self.buffers.lock().unwrap().ticks, ticks, self.config.buffer_limit);
+            if dropped > 0 {
+                error!("🚨 Dropped {} buffered ticks past WRITE_BUFFER_LIMIT", dropped);
+            }
+        }
+
+        if reachable {
+            if let Err(e) = self.replay_outbox().await {
+                warn!("⚠️ Outbox replay failed ({}): {}", e.code(), e);
+            }
+        }
+        self.buffered()
+    }
+
+    /// Flush for shutdown; returns how many rows never made it to the database or the outbox
+    pub async fn flush(&self, timeout: Duration) -> usize {
+        match tokio::time::timeout(timeout, self.flush_now()).await {
+            Ok(left) => left,
+            Err(_) => self.buffered(),
+        }
+    }
+
+    /// True once the rows are in their table or parked in the outbox, or can never be written
+    async fn write_or_park<T: Serialize>(&self, target: Target, batch: &[T]) -> bool {
+        let count = batch.len();
+        let rows = match rows_json(batch) {
+            Ok(rows) => rows,
+            Err(e) => {
+                // Buffering rows that won't serialize would only fail the same way next flush
+                error!("❌ Dropped {} {} rows that won't serialize: {}", count, target.as_str(), e);
+                anomaly::note(Event::DbWriteError);
+                return true;
+            }
+        };
+        let e = match insert(&self.db_pool, target, &rows).await {
+            Ok(()) => return true,
+            Err(e) => e,
+        };
+
+        warn!("⚠️ Batch of {} {} rows failed, parking it in the outbox: {}", count, target.as_str(), e);
+        let parked = sqlx::query("INSERT INTO write_outbox (target, rows, last_error) VALUES ($1, $2, $3)")
+            .bind(target.as_str())
+            .bind(&rows)
+            .bind(e.to_string())
+            .execute(&self.db_pool)
+            .await;
+        match parked {
+            Ok(_) => true,
+            Err(e) => {
+                error!("❌ Could not park {} {} rows, keeping them buffered: {}", count, target.as_str(), e);
+                anomaly::note(Event::DbWriteError);
+                false
+            }
+        }
+    }
+
+    /// Retry the oldest parked batches; a batch that keeps failing stays put for an operator
+    async fn replay_outbox(&self) -> Result<()> {
+        let parked = sqlx::query("
+            SELECT id, target, rows FROM write_outbox
+            WHERE attempts < $1
+            ORDER BY id
+            LIMIT 20
+        ")
+            .bind(self.config.max_attempts)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        for row in parked {
+            let id: i64 = row.get("id");
+            let rows: Value = row.get("rows");
+            let Some(target) = Target::parse(row.get("target")) else { continue };
+
+            match insert(&self.db_pool, target, &rows).await {
+                Ok(()) => {
+                    sqlx::query("DELETE FROM write_outbox WHERE id = $1").bind(id).execute(&self.db_pool).await?;
+                    info!("📤 Replayed parked batch {} into {}", id, target.as_str());
+                }
+                Err(e) => {
+                    let attempts: i32 = sqlx::query_scalar("
+                        UPDATE write_outbox SET attempts = attempts + 1, last_error = $2, last_attempt_at = NOW()
+                        WHERE id = $1
+                        RETURNING attempts
+                    ")
+                        .bind(id)
+                        .bind(e.to_string())
+                        .fetch_one(&self.db_pool)
+                        .await?;
+                    if attempts >= self.config.max_attempts {
+                        error!("🚨 Parked {} batch {} failed {} times, leaving it in write_outbox: {}",
+                               target.as_str(), id, attempts, e);
+                        anomaly::note(Event::DbWriteError);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn insert(db_pool: &PgPool, target: Target, rows: &Value) -> Result<()> {
+    sqlx::query(target.insert_sql()).bind(rows).execute(db_pool).await?;
+    Ok(())
+}
+
+fn rows_json<T: Serialize>(rows: &[T]) -> Result<Value> {
+    Ok(serde_json::to_value(rows)?)
+}
+
+/// Put a failed batch back ahead of anything recorded since, dropping the oldest rows past
+/// `limit`; returns how many were dropped
+fn restore<T>(buffer: &mut Vec<T>, mut failed: Vec<T>, limit: usize) -> usize {
+    failed.append(buffer);
+    let dropped = failed.len().saturating_sub(limit);
+    failed.drain(..dropped);
+    *buffer = failed;
+    dropped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    /// Column names in a statement's `AS r(...)` definition
+    fn recordset_columns(sql: &str) -> Vec<String> {
+        let definition = sql.split("AS r(").nth(1).unwrap();
+        definition.split(',')
+            .map(|column| column.split_whitespace().next().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_rows_match_their_insert_and_failed_batches_go_back_first() {
+        let result = TestResult {
+            capital: dec!(5),
+            profitable: true,
+            profit: dec!(0.42),
+            entry_price: dec!(100),
+            exit_price: dec!(110),
+            duration_seconds: 600,
+            fees: dec!(0.06),
+            slippage: dec!(0.02),
+        };
+        let row = TestResultRow { pattern_hash: "abc123".to_string(), result, run_id: None, timestamp: Utc::now() };
+        let tick = PriceTick { symbol: "DOGE-USD".into(), exchange: "coinbase".into(), price: 0.1, timestamp: Utc::now() };
+
+        // Every key a buffered row serializes to is a column the insert reads, and vice versa
+        for (json, target) in [(rows_json(&[row]).unwrap(), Target::TestResults), (rows_json(&[tick]).unwrap(), Target::MarketData)] {
+            let mut keys: Vec<String> = json[0].as_object().unwrap().keys().cloned().collect();
+            let mut columns = recordset_columns(target.insert_sql());
+            keys.sort();
+            columns.sort();
+            assert_eq!(keys, columns, "{}", target.as_str());
+            assert_eq!(Target::parse(target.as_str()), Some(target));
+        }
+
+        let mut buffer = vec![4, 5];
+        assert_eq!(restore(&mut buffer, vec![1, 2, 3], 10), 0);
+        assert_eq!(buffer, vec![1, 2, 3, 4, 5]);
+        assert_eq!(restore(&mut buffer, vec![0], 4), 2);
+        assert_eq!(buffer, vec![2, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,286 @@
+// Message Bus - Fills, Risk State and Order Intents over Redis Pub/Sub
+// With MESSAGE_BUS=redis every live event (fills, equity, the open book, activations) is
+// also published to Redis, so a dashboard or another worker can run as its own process or
+// host and see the same stream with sub-millisecond fan-out. Order intents published by other
+// processes reach the local execution engine only with BUS_ACCEPT_ORDERS=true, and still go
+// through the risk manager. Postgres stays the record; the bus is best-effort and drops
+// messages rather than slowing a fill when Redis is away
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::core::execution_engine::OrderIntent;
+use crate::core::live_feed::{LiveEvent, LiveFeed};
+use crate::core::secrets;
+use crate::core::redis::{RedisConnection, RedisError, RedisUrl, Reply};
+
+const QUEUE: usize = 4096;
+const RECONNECT_AFTER: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topic {
+    Live,    // LiveEvent: fills, equity, positions, activations
+    Orders,  // OrderIntent for whichever process runs execution
+}
+
+impl Topic {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Topic::Live => "live",
+            Topic::Orders => "orders",
+        }
+    }
+
+    pub fn channel(&self, prefix: &str) -> String {
+        format!("{}:{}", prefix, self.as_str())
+    }
+}
+
+/// What goes over the wire; `origin` lets a process skip its own messages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Envelope {
+    pub origin: Uuid,
+    pub sent_at: DateTime<Utc>,
+    pub payload: Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct BusConfig {
+    pub url: RedisUrl,
+    pub prefix: String,        // channels are <prefix>:live and <prefix>:orders
+    pub accept_orders: bool,
+}
+
+impl BusConfig {
+    /// None unless MESSAGE_BUS=redis; REDIS_URL then has to parse
+    pub fn from_env() -> Result<Option<Self>, RedisError> {
+        match std::env::var("MESSAGE_BUS").unwrap_or_default().trim().to_lowercase().as_str() {
+            "" | "none" | "off" => return Ok(None),
+            "redis" => {}
+            other => return Err(RedisError::Url(format!("unknown MESSAGE_BUS {} (redis or none)", other))),
+        }
+        let url = secrets::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        Ok(Some(BusConfig {
+            url: RedisUrl::parse(&url)?,
+            prefix: std::env::var("BUS_CHANNEL_PREFIX").ok().filter(|p| !p.trim().is_empty()).unwrap_or_else(|| "v26meme".to_string()),
+            accept_orders: std::env::var("BUS_ACCEPT_ORDERS").map(|v| v == "true").unwrap_or(false),
+        }))
+    }
+}
+
+#[derive(Clone)]
+pub struct Bus {
+    config: Arc<BusConfig>,
+    origin: Uuid,
+    outgoing: mpsc::Sender<(Topic, Vec<u8>)>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl Bus {
+    /// Spawn the publisher; needs a running tokio runtime
+    pub fn start(config: BusConfig) -> Self {
+        let (outgoing, queue) = mpsc::channel(QUEUE);
+        let bus = Bus { config: Arc::new(config), origin: Uuid::new_v4(), outgoing, dropped: Arc::new(AtomicU64::new(0)) };
+        tokio::spawn(publish_loop(bus.config.clone(), queue, bus.dropped.clone()));
+        bus
+    }
+
+    pub fn config(&self) -> &BusConfig {
+        &self.config
+    }
+
+    /// Messages lost to a full queue or an unreachable Redis since startup
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Never waits; drops the message when the queue is full
+    pub fn publish<T: Serialize>(&self, topic: Topic, payload: &T) {
+        let envelope = match serde_json::to_value(payload) {
+            Ok(payload) => Envelope { origin: self.origin, sent_at: Utc::now(), payload },
+            Err(e) => {
+                warn!("⚠️ Could not serialize a {} message: {}", topic.as_str(), e);
+                return;
+            }
+        };
+        let body = serde_json::to_vec(&envelope).unwrap_or_default();
+        if self.outgoing.try_send((topic, body)).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Messages other processes publish on `topic`, resubscribing after a lost connection;
+    /// the subscription ends when the receiver is dropped
+    pub fn subscribe(&self, topic: Topic) -> mpsc::Receiver<Envelope> {
+        let (tx, rx) = mpsc::channel(QUEUE);
+        tokio::spawn(subscribe_loop(self.config.clone(), self.origin, topic, tx));
+        rx
+    }
+}
+
+async fn publish_loop(config: Arc<BusConfig>, mut queue: mpsc::Receiver<(Topic, Vec<u8>)>, dropped: Arc<AtomicU64>) {
+    let mut conn: Option<RedisConnection> = None;
+    let mut retry_at = Instant::now();
+
+    while let Some((topic, body)) = queue.recv().await {
+        if conn.is_none() && Instant::now() >= retry_at {
+            match RedisConnection::connect(&config.url).await {
+                Ok(connected) => {
+                    info!("📡 Message bus publishing to {}:{}", config.url.host, config.url.port);
+                    conn = Some(connected);
+                }
+                Err(e) => {
+                    warn!("⚠️ Message bus unreachable, dropping messages for {}s: {}", RECONNECT_AFTER.as_secs(), e);
+                    retry_at = Instant::now() + RECONNECT_AFTER;
+                }
+            }
+        }
+        let Some(connected) = conn.as_mut() else {
+            dropped.fetch_add(1, Ordering::Relaxed);
+            continue;
+        };
+
+        let channel = topic.channel(&config.prefix);
+        if let Err(e) = connected.command(&[b"PUBLISH", channel.as_bytes(), &body]).await {
+            warn!("⚠️ Message bus publish failed, reconnecting: {}", e);
+            dropped.fetch_add(1, Ordering::Relaxed);
+            conn = None;
+        }
+    }
+}
+
+async fn subscribe_loop(config: Arc<BusConfig>, origin: Uuid, topic: Topic, tx: mpsc::Sender<Envelope>) {
+    let channel = topic.channel(&config.prefix);
+    while !tx.is_closed() {
+        if let Err(e) = subscribe_once(&config.url, &channel, origin, &tx).await {
+            warn!("⚠️ Lost subscription to {}, retrying in {}s: {}", channel, RECONNECT_AFTER.as_secs(), e);
+            tokio::time::sleep(RECONNECT_AFTER).await;
+        }
+    }
+}
+
+/// Ok once the receiver is gone; any connection trouble is an error to retry on
+async fn subscribe_once(url: &RedisUrl, channel: &str, origin: Uuid, tx: &mpsc::Sender<Envelope>) -> Result<(), RedisError> {
+    let mut conn = RedisConnection::connect(url).await?;
+    conn.command(&[b"SUBSCRIBE", channel.as_bytes()]).await?;
+    info!("📡 Subscribed to {}", channel);
+
+    loop {
+        let Some(envelope) = message(conn.read().await?) else { continue };
+        if envelope.origin == origin {
+            continue;
+        }
+        if tx.send(envelope).await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+/// The envelope in a pushed ["message", channel, payload]; anything else is skipped
+fn message(reply: Reply) -> Option<Envelope> {
+    let Reply::Array(Some(items)) = reply else { return None };
+    match items.as_slice() {
+        [Reply::Bulk(Some(kind)), _, Reply::Bulk(Some(payload))] if kind == b"message" => {
+            serde_json::from_slice(payload).ok()
+        }
+        _ => None,
+    }
+}
+
+/// Publish every local live event to the bus
+pub fn forward_live_feed(bus: Bus, feed: &LiveFeed) -> JoinHandle<()> {
+    let (_, mut events) = feed.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => bus.publish(Topic::Live, &event),
+                Err(RecvError::Lagged(n)) => warn!("⚠️ Bus forwarder fell behind, skipped {} live events", n),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+/// Replay live events from other processes into a local feed, e.g. for a dashboard host
+pub fn relay_live_feed(bus: &Bus, feed: LiveFeed) -> JoinHandle<()> {
+    let mut incoming = bus.subscribe(Topic::Live);
+    tokio::spawn(async move {
+        while let Some(envelope) = incoming.recv().await {
+            match serde_json::from_value::<LiveEvent>(envelope.payload) {
+                Ok(event) => feed.publish(event),
+                Err(e) => warn!("⚠️ Skipped a live event from {}: {}", envelope.origin, e),
+            }
+        }
+    })
+}
+
+/// Queue order intents other processes publish on the local execution engine
+pub fn relay_orders(bus: &Bus, orders: mpsc::Sender<OrderIntent>) -> JoinHandle<()> {
+    let mut incoming = bus.subscribe(Topic::Orders);
+    tokio::spawn(async move {
+        while let Some(envelope) = incoming.recv().await {
+            match serde_json::from_value::<OrderIntent>(envelope.payload) {
+                Ok(intent) => {
+                    if orders.send(intent).await.is_err() {
+                        break;
+                    }
+                }
+                Err(e) => warn!("⚠️ Skipped an order intent from {}: {}", envelope.origin, e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::live_feed::{TradeEvent, TradeKind};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_pushed_messages_decode_to_envelopes() {
+        let trade = LiveEvent::Trade(TradeEvent {
+            at: Utc::now(),
+            kind: TradeKind::Exit,
+            position_id: "entry-1".to_string(),
+            pattern_hash: "abc123".to_string(),
+            symbol: "DOGE-USD".to_string(),
+            exchange: "coinbase".to_string(),
+            size: dec!(100),
+            price: dec!(0.11),
+            fees: dec!(0.6),
+            pnl: Some(dec!(9.4)),
+            reason: Some("take_profit".to_string()),
+            capital: dec!(209.4),
+        });
+        let envelope = Envelope { origin: Uuid::new_v4(), sent_at: Utc::now(), payload: serde_json::to_value(&trade).unwrap() };
+        let pushed = Reply::Array(Some(vec![
+            Reply::Bulk(Some(b"message".to_vec())),
+            Reply::Bulk(Some(Topic::Live.channel("v26meme").into_bytes())),
+            Reply::Bulk(Some(serde_json::to_vec(&envelope).unwrap())),
+        ]));
+
+        let received = message(pushed).unwrap();
+        assert_eq!(received, envelope);
+        assert!(matches!(serde_json::from_value::<LiveEvent>(received.payload).unwrap(), LiveEvent::Trade(t) if t.pnl == Some(dec!(9.4))));
+
+        // The subscribe confirmation is not a message
+        let confirmation = Reply::Array(Some(vec![
+            Reply::Bulk(Some(b"subscribe".to_vec())),
+            Reply::Bulk(Some(b"v26meme:live".to_vec())),
+            Reply::Integer(1),
+        ]));
+        assert_eq!(message(confirmation), None);
+        assert_eq!(Topic::Orders.channel("staging"), "staging:orders");
+    }
+}
@@ -0,0 +1,150 @@
+// Evolution Cadence
+// Evolution normally runs once a day (every `interval`, or at `daily_at` local time when the
+// [clock] section sets evolution_at), but a regime change shouldn't have to wait for it.
+// When the live win rate across the portfolio falls sharply against its baseline, an extra
+// cycle runs early, no sooner than `min_interval` after the last one
+
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+
+use crate::core::clock::Calendar;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WinRate {
+    pub trades: u32,
+    pub wins: u32,
+}
+
+impl WinRate {
+    pub fn rate(&self) -> Option<f64> {
+        (self.trades > 0).then(|| self.wins as f64 / self.trades as f64)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvolutionCadence {
+    pub interval: Duration,          // the regular cycle
+    pub daily_at: Option<NaiveTime>, // instead of `interval`: once a day at this local time
+    pub calendar: Calendar,          // whose local time daily_at is
+    pub min_interval: Duration,      // never run two cycles closer than this
+    pub check_every: Duration,       // how often the win rate is looked at
+    pub recent_window: Duration,     // live trades judged for a regime change
+    pub baseline_window: Duration,   // what they are compared against
+    pub min_recent_trades: u32,      // too few recent trades is noise, not a regime change
+    pub max_win_rate_drop: f64,      // absolute drop (0.15 = 15 points) that triggers a cycle
+}
+
+impl Default for EvolutionCadence {
+    fn default() -> Self {
+        EvolutionCadence {
+            interval: Duration::hours(24),
+            daily_at: None,
+            calendar: Calendar::default(),
+            min_interval: Duration::hours(4),
+            check_every: Duration::minutes(15),
+            recent_window: Duration::hours(6),
+            baseline_window: Duration::days(7),
+            min_recent_trades: 20,
+            max_win_rate_drop: 0.15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CycleTrigger {
+    Scheduled,
+    RegimeChange,
+}
+
+impl EvolutionCadence {
+    /// True when the recent win rate has fallen at least `max_win_rate_drop` below the baseline
+    pub fn regime_changed(&self, recent: WinRate, baseline: WinRate) -> bool {
+        if recent.trades < self.min_recent_trades {
+            return false;
+        }
+        match (recent.rate(), baseline.rate()) {
+            (Some(recent), Some(baseline)) => baseline - recent >= self.max_win_rate_drop,
+            _ => false,
+        }
+    }
+
+    /// Whether a cycle is due now, and why; the first check always runs one
+    pub fn due(&self, last_cycle: Option<DateTime<Utc>>, now: DateTime<Utc>, regime_changed: bool) -> Option<CycleTrigger> {
+        let Some(last) = last_cycle else {
+            return Some(CycleTrigger::Scheduled);
+        };
+        let elapsed = now - last;
+        let scheduled = match self.daily_at {
+            Some(at) => last < self.calendar.last_at(at, now),
+            None => elapsed >= self.interval,
+        };
+
+        if scheduled {
+            Some(CycleTrigger::Scheduled)
+        } else if regime_changed && elapsed >= self.min_interval {
+            Some(CycleTrigger::RegimeChange)
+        } else {
+            None
+        }
+    }
+
+    /// Live win rates over the recent window and the baseline window that precedes it
+    pub async fn load_win_rates(&self, db_pool: &PgPool, now: DateTime<Utc>) -> Result<(WinRate, WinRate), sqlx::Error> {
+        let recent_start = now - self.recent_window;
+        let baseline_start = recent_start - self.baseline_window;
+
+        let row = sqlx::query("
+            SELECT COUNT(*) FILTER (WHERE exit_time > $2) AS recent_trades,
+                   COUNT(*) FILTER (WHERE exit_time > $2 AND profit_loss > 0) AS recent_wins,
+                   COUNT(*) FILTER (WHERE exit_time <= $2) AS baseline_trades,
+                   COUNT(*) FILTER (WHERE exit_time <= $2 AND profit_loss > 0) AS baseline_wins
+            FROM trades
+            WHERE status = 'closed' AND exit_time > $1
+        ")
+            .bind(baseline_start)
+            .bind(recent_start)
+            .fetch_one(db_pool)
+            .await?;
+
+        let count = |column: &str| row.get::<i64, _>(column) as u32;
+        Ok((
+            WinRate { trades: count("recent_trades"), wins: count("recent_wins") },
+            WinRate { trades: count("baseline_trades"), wins: count("baseline_wins") },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regime_change_needs_a_sharp_drop_on_enough_trades() {
+        let cadence = EvolutionCadence::default();
+        let baseline = WinRate { trades: 400, wins: 240 };  // 60%
+
+        assert!(cadence.regime_changed(WinRate { trades: 40, wins: 16 }, baseline));   // 40%
+        assert!(!cadence.regime_changed(WinRate { trades: 40, wins: 22 }, baseline));  // 55%
+        assert!(!cadence.regime_changed(WinRate { trades: 10, wins: 0 }, baseline));   // too few
+        assert!(!cadence.regime_changed(WinRate { trades: 40, wins: 0 }, WinRate { trades: 0, wins: 0 }));
+    }
+
+    #[test]
+    fn test_early_cycles_respect_the_minimum_interval() {
+        let cadence = EvolutionCadence::default();
+        let last = Utc::now();
+
+        assert_eq!(cadence.due(None, last, false), Some(CycleTrigger::Scheduled));
+        assert_eq!(cadence.due(Some(last), last + Duration::hours(2), true), None);
+        assert_eq!(cadence.due(Some(last), last + Duration::hours(5), true), Some(CycleTrigger::RegimeChange));
+        assert_eq!(cadence.due(Some(last), last + Duration::hours(5), false), None);
+        assert_eq!(cadence.due(Some(last), last + Duration::hours(24), false), Some(CycleTrigger::Scheduled));
+
+        // At 02:00 local instead: due once 02:00 has passed since the last cycle, however recent
+        let at_two = EvolutionCadence { daily_at: NaiveTime::from_hms_opt(2, 0, 0), ..EvolutionCadence::default() };
+        let evening = DateTime::parse_from_rfc3339("2025-03-01T23:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(at_two.due(Some(evening), evening + Duration::hours(2), false), None);
+        assert_eq!(at_two.due(Some(evening), evening + Duration::hours(3), false), Some(CycleTrigger::Scheduled));
+    }
+}
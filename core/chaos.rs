@@ -0,0 +1,355 @@
+// Chaos - Fault Injection for Paper and Dry Runs
+// With CHAOS_ENABLED set, a paper or dry run breaks on purpose so the discovery and execution
+// loops can be watched recovering before real capital depends on it. Venues answer with HTTP
+// 500s and fill only part of some orders (the venue cancels the rest), database writes time out,
+// and the market data stream drops for a while, as a venue websocket would. Each fault has its
+// own rate per call; draws come from the run seed, so a chaos run replays like any other. A
+// live run refuses to inject anything, whatever the environment says
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+use tracing::{error, info, warn};
+
+use crate::core::exchange::{Exchange, ExchangeError, OrderRequest, OrderStatusReport, Quote, VenueOrderState};
+use crate::core::execution_context::ExecutionContext;
+use crate::core::money::{self, Decimal};
+use crate::core::rng::SharedRng;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fault {
+    WebsocketDrop,  // the market data stream goes quiet for ws_drop_duration
+    DbTimeout,      // a database write fails with a pool timeout before it is sent
+    ExchangeError,  // a venue call fails as an HTTP 500 would
+    PartialFill,    // part of an order fills and the venue cancels the rest
+}
+
+impl Fault {
+    pub const ALL: [Fault; 4] = [Fault::WebsocketDrop, Fault::DbTimeout, Fault::ExchangeError, Fault::PartialFill];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Fault::WebsocketDrop => "websocket_drop",
+            Fault::DbTimeout => "db_timeout",
+            Fault::ExchangeError => "exchange_error",
+            Fault::PartialFill => "partial_fill",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Fault::ALL.into_iter().find(|fault| fault.as_str() == s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChaosConfig {
+    pub enabled: bool,
+    pub ws_drop_rate: f64,          // per tick: chance the stream drops
+    pub ws_drop_duration: Duration, // how long a drop lasts
+    pub db_timeout_rate: f64,       // per write
+    pub exchange_error_rate: f64,   // per venue call
+    pub partial_fill_rate: f64,     // per order submitted
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        ChaosConfig {
+            enabled: false,
+            ws_drop_rate: 0.001,
+            ws_drop_duration: Duration::from_secs(30),
+            db_timeout_rate: 0.02,
+            exchange_error_rate: 0.05,
+            partial_fill_rate: 0.10,
+        }
+    }
+}
+
+impl ChaosConfig {
+    pub fn from_env() -> Self {
+        let defaults = ChaosConfig::default();
+        let rate = |name: &str, default: f64| std::env::var(name).ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|v| (0.0..=1.0).contains(v))
+            .unwrap_or(default);
+        ChaosConfig {
+            enabled: std::env::var("CHAOS_ENABLED").map(|v| v == "true" || v == "1").unwrap_or(false),
+            ws_drop_rate: rate("CHAOS_WS_DROP_RATE", defaults.ws_drop_rate),
+            ws_drop_duration: std::env::var("CHAOS_WS_DROP_SECS").ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map_or(defaults.ws_drop_duration, Duration::from_secs),
+            db_timeout_rate: rate("CHAOS_DB_TIMEOUT_RATE", defaults.db_timeout_rate),
+            exchange_error_rate: rate("CHAOS_EXCHANGE_ERROR_RATE", defaults.exchange_error_rate),
+            partial_fill_rate: rate("CHAOS_PARTIAL_FILL_RATE", defaults.partial_fill_rate),
+        }
+    }
+
+    pub fn rate(&self, fault: Fault) -> f64 {
+        match fault {
+            Fault::WebsocketDrop => self.ws_drop_rate,
+            Fault::DbTimeout => self.db_timeout_rate,
+            Fault::ExchangeError => self.exchange_error_rate,
+            Fault::PartialFill => self.partial_fill_rate,
+        }
+    }
+}
+
+/// Shared, cheap to clone; the default injects nothing
+#[derive(Clone, Default)]
+pub struct FaultInjector {
+    config: ChaosConfig,
+    enabled: Arc<AtomicBool>,
+    rng: SharedRng,
+    stream_down_until: Arc<Mutex<Option<Instant>>>,
+    injected: Arc<Mutex<BTreeMap<Fault, u64>>>,
+}
+
+impl FaultInjector {
+    /// Faults as configured, but only outside a live run
+    pub fn for_context(config: ChaosConfig, context: ExecutionContext, rng: &SharedRng) -> Self {
+        if !config.enabled {
+            return FaultInjector::default();
+        }
+        if context.is_live() {
+            error!("❌ CHAOS_ENABLED is ignored in a live run; faults are only injected in paper and dry runs");
+            return FaultInjector::default();
+        }
+        warn!("💥 Chaos mode: {}", Fault::ALL.iter()
+            .map(|fault| format!("{} {:.1}%", fault.as_str(), config.rate(*fault) * 100.0))
+            .collect::<Vec<_>>()
+            .join(", "));
+        FaultInjector::new(config, rng.fork("chaos"))
+    }
+
+    pub fn new(config: ChaosConfig, rng: SharedRng) -> Self {
+        FaultInjector {
+            config,
+            enabled: Arc::new(AtomicBool::new(config.enabled)),
+            rng,
+            stream_down_until: Arc::default(),
+            injected: Arc::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    /// Pause or resume injection without a restart
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Whether this call suffers `fault`; every hit is counted and logged
+    pub fn inject(&self, fault: Fault) -> bool {
+        if !self.is_enabled() || !self.rng.clone().gen_bool(self.config.rate(fault).clamp(0.0, 1.0)) {
+            return false;
+        }
+        *self.injected.lock().unwrap().entry(fault).or_insert(0) += 1;
+        info!("💥 Injecting {}", fault.as_str());
+        true
+    }
+
+    /// How many of each fault have been injected so far
+    pub fn injected(&self) -> BTreeMap<Fault, u64> {
+        self.injected.lock().unwrap().clone()
+    }
+
+    /// Run a database call, unless this one times out first
+    pub async fn db<T>(&self, call: impl Future<Output = Result<T, sqlx::Error>>) -> Result<T, sqlx::Error> {
+        if self.inject(Fault::DbTimeout) {
+            return Err(sqlx::Error::PoolTimedOut);
+        }
+        call.await
+    }
+
+    /// Whether the market data stream is down: during a drop, or when this tick starts one
+    pub fn stream_down(&self) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        let mut until = self.stream_down_until.lock().unwrap();
+        if until.is_some_and(|t| Instant::now() < t) {
+            return true;
+        }
+        if self.inject(Fault::WebsocketDrop) {
+            *until = Some(Instant::now() + self.config.ws_drop_duration);
+            return true;
+        }
+        *until = None;
+        false
+    }
+
+    /// The share of an order allowed to fill, when this one fills only in part
+    fn partial_fill(&self) -> Option<f64> {
+        self.inject(Fault::PartialFill).then(|| self.rng.clone().gen_range(0.1..0.9))
+    }
+
+    fn exchange_error(&self, venue: &str) -> Result<(), ExchangeError> {
+        match self.inject(Fault::ExchangeError) {
+            true => Err(ExchangeError::Unavailable(format!("HTTP 500 from {} (injected)", venue))),
+            false => Ok(()),
+        }
+    }
+}
+
+/// A simulated venue that fails and short-fills as the injector decides
+pub struct ChaosExchange {
+    inner: Arc<dyn Exchange>,
+    faults: FaultInjector,
+    truncated: Mutex<HashSet<String>>,  // orders sent at part size: a full fill reads as cancelled
+}
+
+impl ChaosExchange {
+    pub fn new(inner: Arc<dyn Exchange>, faults: FaultInjector) -> Self {
+        ChaosExchange { inner, faults, truncated: Mutex::new(HashSet::new()) }
+    }
+
+    fn as_venue_sees_it(&self, mut report: OrderStatusReport) -> OrderStatusReport {
+        if report.state == VenueOrderState::Filled && self.truncated.lock().unwrap().contains(&report.exchange_order_id) {
+            report.state = VenueOrderState::Cancelled;
+        }
+        report
+    }
+}
+
+#[async_trait]
+impl Exchange for ChaosExchange {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<Quote, ExchangeError> {
+        self.faults.exchange_error(self.name())?;
+        self.inner.quote(symbol).await
+    }
+
+    async fn submit_order(&self, order: &OrderRequest) -> Result<OrderStatusReport, ExchangeError> {
+        self.faults.exchange_error(self.name())?;
+        let Some(fraction) = self.faults.partial_fill() else {
+            return self.inner.submit_order(order).await.map(|r| self.as_venue_sees_it(r));
+        };
+        let part = OrderRequest { size: money::share(order.size, fraction), ..order.clone() };
+        let report = self.inner.submit_order(&part).await?;
+        self.truncated.lock().unwrap().insert(report.exchange_order_id.clone());
+        Ok(self.as_venue_sees_it(report))
+    }
+
+    async fn find_order(&self, client_order_id: &str) -> Result<Option<OrderStatusReport>, ExchangeError> {
+        self.faults.exchange_error(self.name())?;
+        Ok(self.inner.find_order(client_order_id).await?.map(|r| self.as_venue_sees_it(r)))
+    }
+
+    async fn cancel_order(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError> {
+        self.faults.exchange_error(self.name())?;
+        self.inner.cancel_order(exchange_order_id).await.map(|r| self.as_venue_sees_it(r))
+    }
+
+    async fn order_status(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError> {
+        self.faults.exchange_error(self.name())?;
+        self.inner.order_status(exchange_order_id).await.map(|r| self.as_venue_sees_it(r))
+    }
+
+    async fn balances(&self) -> Result<HashMap<String, Decimal>, ExchangeError> {
+        self.faults.exchange_error(self.name())?;
+        self.inner.balances().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use crate::core::exchange::{PaperExchange, Side};
+    use crate::core::execution_engine::{ExecutionEngine, ExecutionError, OrderIntent};
+    use crate::core::execution_style::ExecutionStyle;
+    use crate::core::market_data::{MarketDataFeed, PriceTick};
+    use crate::core::order_manager::OrderManager;
+    use crate::core::risk_manager::RiskManager;
+    use crate::risk::liquidity::{LiquidityProfile, LiquidityProfiles};
+
+    fn only(fault: Fault) -> FaultInjector {
+        let config = ChaosConfig { enabled: true, ws_drop_rate: 0.0, db_timeout_rate: 0.0, exchange_error_rate: 0.0, partial_fill_rate: 0.0, ..ChaosConfig::default() };
+        let config = match fault {
+            Fault::WebsocketDrop => ChaosConfig { ws_drop_rate: 1.0, ws_drop_duration: Duration::from_secs(60), ..config },
+            Fault::DbTimeout => ChaosConfig { db_timeout_rate: 1.0, ..config },
+            Fault::ExchangeError => ChaosConfig { exchange_error_rate: 1.0, ..config },
+            Fault::PartialFill => ChaosConfig { partial_fill_rate: 1.0, ..config },
+        };
+        FaultInjector::new(config, SharedRng::seeded(7))
+    }
+
+    fn chaos_engine(faults: &FaultInjector) -> (ExecutionEngine, Arc<RiskManager>) {
+        let risk_manager = Arc::new(RiskManager::new(dec!(1000)));
+        let mut profiles = LiquidityProfiles::default();
+        profiles.insert(LiquidityProfile::flat("DOGE-USD"));
+        risk_manager.set_liquidity_profiles(profiles);
+        let paper = Arc::new(PaperExchange::new("paper", 0.0));
+        paper.update_price("DOGE-USD", 100.0);
+
+        let mut engine = ExecutionEngine::new(risk_manager.clone(), OrderManager::in_memory());
+        engine.retry.initial_backoff = std::time::Duration::from_millis(1);
+        engine.add_exchange(ExecutionContext::Paper.paper(paper).with_chaos(faults));
+        (engine, risk_manager)
+    }
+
+    fn open() -> OrderIntent {
+        OrderIntent::Open {
+            pattern_hash: "abc".to_string(),
+            symbol: "DOGE-USD".to_string(),
+            exchange: "paper".to_string(),
+            side: Side::Buy,
+            size: dec!(100),
+            style: ExecutionStyle::Market,
+            stop_loss_pct: dec!(0.05),
+            take_profit_pct: dec!(0.10),
+            decision: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_injected_faults_reach_execution_and_data_paths() {
+        // Never in a live run, whatever the config says
+        let live = FaultInjector::for_context(only(Fault::ExchangeError).config, ExecutionContext::Live, &SharedRng::seeded(1));
+        assert!(!live.is_enabled());
+
+        // A partial fill opens a smaller position; the venue cancelled the rest
+        let faults = only(Fault::PartialFill);
+        let (mut engine, risk_manager) = chaos_engine(&faults);
+        engine.submit(open()).await.unwrap();
+        let (_, position) = risk_manager.open_positions_snapshot().pop().unwrap();
+        assert!(position.size >= dec!(10) && position.size <= dec!(90), "{}", position.size);
+        assert_eq!(engine.working_orders(), 0);
+
+        // Every venue call fails as a 500 until chaos is switched off
+        let faults = only(Fault::ExchangeError);
+        let (mut engine, risk_manager) = chaos_engine(&faults);
+        assert!(matches!(engine.submit(open()).await, Err(ExecutionError::Exchange(_))));
+        assert!(risk_manager.open_positions_snapshot().is_empty());
+        faults.set_enabled(false);
+        engine.poll_working_orders().await;
+        engine.submit(open()).await.unwrap();
+        assert_eq!(risk_manager.open_positions_snapshot().len(), 1);
+        assert!(faults.injected()[&Fault::ExchangeError] >= 1);
+
+        let faults = only(Fault::DbTimeout);
+        assert!(matches!(faults.db(async { Ok(1) }).await, Err(sqlx::Error::PoolTimedOut)));
+
+        // A dropped stream loses ticks, so the feed reads as never having delivered
+        let faults = only(Fault::WebsocketDrop);
+        let feed = MarketDataFeed::new(16).with_chaos(faults.clone());
+        let mut ticks = feed.subscribe();
+        let tick = PriceTick { symbol: "DOGE-USD".into(), exchange: "paper".into(), price: 100.0, timestamp: chrono::Utc::now() };
+        assert_eq!(feed.publish(tick.clone()), 0);
+        assert_eq!(feed.is_fresh(std::time::Duration::from_secs(5)), None);
+        faults.set_enabled(false);
+        assert_eq!(feed.publish(tick), 1);
+        assert_eq!(ticks.recv().await.unwrap().price, 100.0);
+        assert_eq!(Fault::parse("partial_fill"), Some(Fault::PartialFill));
+    }
+}
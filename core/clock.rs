@@ -0,0 +1,185 @@
+// Clock - Where "Now" and "Today" Come From
+// Loops that act on time of day ask a Clock instead of reading the system time, so a backtest
+// or test can drive them with a SimulatedClock. The Calendar says where one trading day ends
+// and the next begins: at day_start in the configured time zone ([clock] in config/v26meme.toml,
+// UTC midnight by default). Daily reports, "today" in `risk status` and the evolution schedule
+// follow it; daylight-saving days are simply 23 or 25 hours long. Retention partitions and the
+// LLM budget stay on UTC days, the former for stable archive names, the latter to match how
+// providers bill
+
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Serialize, Deserialize};
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Time that moves only when told to
+#[derive(Debug)]
+pub struct SimulatedClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl SimulatedClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        SimulatedClock { now: Mutex::new(start) }
+    }
+
+    pub fn set(&self, at: DateTime<Utc>) {
+        *self.now.lock().unwrap() = at;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// A clock and the calendar that turns it into trading days, as the scheduling loops use them
+#[derive(Clone)]
+pub struct TradingClock {
+    clock: SharedClock,
+    pub calendar: Calendar,
+}
+
+impl TradingClock {
+    pub fn new(clock: SharedClock, calendar: Calendar) -> Self {
+        TradingClock { clock, calendar }
+    }
+
+    pub fn system(calendar: Calendar) -> Self {
+        TradingClock::new(Arc::new(SystemClock), calendar)
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    /// The clock alone, for components that keep time but not trading days
+    pub fn source(&self) -> SharedClock {
+        self.clock.clone()
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.calendar.day_of(self.now())
+    }
+}
+
+/// Trading days in a time zone, each starting at `day_start` local time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Calendar {
+    pub timezone: Tz,
+    pub day_start: NaiveTime,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Calendar { timezone: Tz::UTC, day_start: NaiveTime::MIN }
+    }
+}
+
+impl Calendar {
+    /// The trading day `at` falls in
+    pub fn day_of(&self, at: DateTime<Utc>) -> NaiveDate {
+        (at.with_timezone(&self.timezone).naive_local() - self.offset()).date()
+    }
+
+    /// When `day` begins
+    pub fn start_of(&self, day: NaiveDate) -> DateTime<Utc> {
+        self.local(day.and_time(self.day_start))
+    }
+
+    /// [start, end) of `day`
+    pub fn bounds(&self, day: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        (self.start_of(day), self.start_of(day + Duration::days(1)))
+    }
+
+    /// The latest moment at or before `now` when the local clock read `time`
+    pub fn last_at(&self, time: NaiveTime, now: DateTime<Utc>) -> DateTime<Utc> {
+        let today = now.with_timezone(&self.timezone).date_naive();
+        let candidate = self.local(today.and_time(time));
+        if candidate <= now {
+            candidate
+        } else {
+            self.local((today - Duration::days(1)).and_time(time))
+        }
+    }
+
+    /// Seconds from local midnight to day_start, for SQL that groups rows by trading day
+    pub fn day_start_secs(&self) -> f64 {
+        self.offset().num_seconds() as f64
+    }
+
+    pub fn timezone_name(&self) -> &'static str {
+        self.timezone.name()
+    }
+
+    fn offset(&self) -> Duration {
+        self.day_start - NaiveTime::MIN
+    }
+
+    /// A local time as UTC; the first of a repeated hour, and the end of a skipped one
+    fn local(&self, at: NaiveDateTime) -> DateTime<Utc> {
+        let mut probe = at;
+        loop {
+            if let Some(resolved) = self.timezone.from_local_datetime(&probe).earliest() {
+                return resolved.with_timezone(&Utc);
+            }
+            probe += Duration::minutes(15);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trading_days_follow_the_zone_and_day_start() {
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let day = |m: u32, d: u32| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+
+        let utc = Calendar::default();
+        assert_eq!(utc.day_of(at("2025-03-01T23:59:00Z")), day(3, 1));
+        assert_eq!(utc.bounds(day(3, 1)), (at("2025-03-01T00:00:00Z"), at("2025-03-02T00:00:00Z")));
+
+        // New York, days starting at 17:00 local; 2025-03-09 is the spring-forward day
+        let ny = Calendar { timezone: chrono_tz::America::New_York, day_start: NaiveTime::from_hms_opt(17, 0, 0).unwrap() };
+        assert_eq!(ny.day_of(at("2025-03-07T21:59:00Z")), day(3, 6));
+        assert_eq!(ny.day_of(at("2025-03-07T22:00:00Z")), day(3, 7));
+        let (start, end) = ny.bounds(day(3, 8));
+        assert_eq!((start, end), (at("2025-03-08T22:00:00Z"), at("2025-03-09T21:00:00Z")));
+        assert_eq!(end - start, Duration::hours(23));
+        assert_eq!(ny.day_start_secs(), 61_200.0);
+
+        let two_am = NaiveTime::from_hms_opt(2, 0, 0).unwrap();
+        assert_eq!(ny.last_at(two_am, at("2025-03-12T05:59:00Z")), at("2025-03-11T06:00:00Z"));
+        assert_eq!(ny.last_at(two_am, at("2025-03-12T06:00:00Z")), at("2025-03-12T06:00:00Z"));
+        assert_eq!(ny.last_at(two_am, at("2025-03-09T12:00:00Z")), at("2025-03-09T07:00:00Z"));  // 02:00 skipped, 03:00 EDT
+
+        let simulated = Arc::new(SimulatedClock::new(start));
+        let clock = TradingClock::new(simulated.clone(), ny);
+        assert_eq!(clock.today(), day(3, 8));
+        simulated.advance(Duration::hours(23));
+        assert_eq!(clock.today(), day(3, 9));
+    }
+}
@@ -0,0 +1,432 @@
+// Control API - Operating a Running System
+// Authenticated REST endpoints for what used to need a restart or a psql session: inspect
+// active patterns and open positions, pause and resume discovery, tighten risk limits (or
+// loosen them back to their launch values), tune discovery and evolution within fixed bounds,
+// flatten everything, re-arm after an emergency stop, switch feature flags or widen their
+// rollout, and work through the trades flagged for review. Every request needs a bearer key,
+// and each key has a role: viewers read, operators pause, tune, tighten, flag, flatten and resolve reviews, and only
+// admins loosen limits or re-arm. Every change is written to the audit log with the role
+// that made it
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use axum::extract::{Extension, Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, patch, post};
+use axum::{Json, Router};
+use chrono::{Duration, Utc};
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::core::audit::{AuditAction, AuditEntry, AuditLogger, Component};
+use crate::core::equity::{self, EquityAnalytics};
+use crate::core::feature_flags::{FeatureFlags, Flag, FlagUpdate};
+use crate::core::money::{self, Decimal};
+use crate::core::patterns::{self, PatternListing};
+use crate::core::review_queue::{self, ReviewVerdict, TradeReview};
+use crate::core::risk_manager::{BreakerStates, Position, RiskManager};
+use crate::core::tuning::{SharedTuning, TuningUpdate};
+use crate::risk::config::LimitUpdate;
+
+/// What a key may do; each role can do everything the ones below it can
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,    // status, patterns, positions, equity, limits, tuning and pending reviews
+    Operator,  // pause and resume discovery, tune, tighten limits, set feature flags, flatten, resolve reviews
+    Admin,     // loosen limits back toward launch values, re-arm after an emergency stop
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Operator => "operator",
+            Role::Admin => "admin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "viewer" => Some(Role::Viewer),
+            "operator" => Some(Role::Operator),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// `role:key` pairs separated by commas, as CONTROL_API_KEYS holds them
+pub fn parse_keys(spec: &str) -> Result<Vec<(Role, String)>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            // Never echo the entry back: without a colon it is probably a bare key
+            let (role, key) = pair.split_once(':').ok_or_else(|| "expected role:key entries".to_string())?;
+            let role = Role::parse(role.trim()).ok_or_else(|| format!("unknown role {}", role.trim()))?;
+            match key.trim() {
+                "" => Err(format!("empty {} key", role.as_str())),
+                key => Ok((role, key.to_string())),
+            }
+        })
+        .collect()
+}
+
+/// The strongest role `token` holds, if any
+pub fn role_for(keys: &[(Role, String)], token: &str) -> Option<Role> {
+    keys.iter().filter(|(_, key)| token_matches(token, key)).map(|(role, _)| *role).max()
+}
+
+fn require(role: Role, needed: Role) -> Result<(), ApiError> {
+    if role >= needed {
+        Ok(())
+    } else {
+        Err(ApiError(StatusCode::FORBIDDEN, json!({ "error": format!("needs an {} key", needed.as_str()) })))
+    }
+}
+
+#[derive(Clone)]
+pub struct ControlState {
+    db_pool: PgPool,
+    risk_manager: Arc<RiskManager>,
+    discovery_paused: Arc<AtomicBool>,
+    tuning: SharedTuning,
+    flags: FeatureFlags,
+    audit: AuditLogger,
+    keys: Arc<[(Role, String)]>,
+}
+
+impl ControlState {
+    pub fn new(
+        db_pool: PgPool,
+        risk_manager: Arc<RiskManager>,
+        discovery_paused: Arc<AtomicBool>,
+        tuning: SharedTuning,
+        flags: FeatureFlags,
+        audit: AuditLogger,
+        keys: Vec<(Role, String)>,
+    ) -> Self {
+        ControlState { db_pool, risk_manager, discovery_paused, tuning, flags, audit, keys: Arc::from(keys) }
+    }
+
+    fn record(&self, role: Role, action: AuditAction, reason_code: &str, detail: Value) {
+        let detail = match detail {
+            Value::Object(mut fields) => {
+                fields.insert("role".to_string(), json!(role));
+                Value::Object(fields)
+            }
+            _ => json!({ "role": role }),
+        };
+        self.audit.record(AuditEntry::new(Component::Control, action, "system", reason_code).with_detail(detail));
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Status {
+    pub capital: Decimal,
+    pub starting_capital: Decimal,
+    pub daily_high: Decimal,
+    pub drawdown_pct: f64,  // below the daily high, 0.0-1.0
+    pub max_daily_drawdown_pct: f64,
+    pub breakers: BreakerStates,
+    pub discovery_paused: bool,
+    pub open_positions: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenPosition {
+    pub position_id: String,
+    #[serde(flatten)]
+    pub position: Position,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EquityQuery {
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewDecision {
+    pub verdict: ReviewVerdict,
+    pub notes: Option<String>,
+}
+
+/// A JSON error body with its status
+pub struct ApiError(StatusCode, Value);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(self.1)).into_response()
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        ApiError(StatusCode::INTERNAL_SERVER_ERROR, json!({ "error": e.to_string() }))
+    }
+}
+
+/// Byte comparison that takes the same time wherever the first mismatch is
+pub(crate) fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Resolves the bearer key to a role for the handlers; 401 when it matches none
+async fn require_key(State(state): State<ControlState>, mut request: Request, next: Next) -> Response {
+    let role = request.headers().get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .and_then(|token| role_for(&state.keys, token));
+    let Some(role) = role else {
+        warn!("⚠️ Rejected unauthenticated control request {} {}", request.method(), request.uri().path());
+        return ApiError(StatusCode::UNAUTHORIZED, json!({ "error": "missing or invalid bearer token" })).into_response();
+    };
+    request.extensions_mut().insert(role);
+    next.run(request).await
+}
+
+pub fn router(state: ControlState) -> Router {
+    Router::new()
+        .route("/api/status", get(status))
+        .route("/api/patterns", get(patterns))
+        .route("/api/positions", get(positions))
+        .route("/api/equity", get(equity_analytics))
+        .route("/api/discovery/pause", post(pause_discovery))
+        .route("/api/discovery/resume", post(resume_discovery))
+        .route("/api/risk/limits", get(limits).patch(update_limits))
+        .route("/api/risk/rearm", post(rearm))
+        .route("/api/tuning", get(tuning).patch(update_tuning))
+        .route("/api/flags", get(flags))
+        .route("/api/flags/:name", patch(update_flag))
+        .route("/api/flatten", post(flatten))
+        .route("/api/reviews", get(reviews))
+        .route("/api/reviews/:id", post(resolve_review))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_key))
+        .with_state(state)
+}
+
+async fn status(State(state): State<ControlState>) -> Json<Status> {
+    let capital = state.risk_manager.current_capital();
+    let daily_high = state.risk_manager.daily_high();
+    Json(Status {
+        capital,
+        starting_capital: state.risk_manager.starting_capital(),
+        daily_high,
+        drawdown_pct: money::ratio(daily_high - capital, daily_high).max(0.0),
+        max_daily_drawdown_pct: state.risk_manager.limits().max_daily_drawdown_pct,
+        breakers: state.risk_manager.breaker_states(),
+        discovery_paused: state.discovery_paused.load(Ordering::SeqCst),
+        open_positions: state.risk_manager.open_positions_snapshot().len(),
+    })
+}
+
+async fn patterns(State(state): State<ControlState>) -> Result<Json<Vec<PatternListing>>, ApiError> {
+    Ok(Json(patterns::list(&state.db_pool, true, None).await?))
+}
+
+async fn positions(State(state): State<ControlState>) -> Json<Vec<OpenPosition>> {
+    Json(state.risk_manager.open_positions_snapshot().into_iter()
+        .map(|(position_id, position)| OpenPosition { position_id, position })
+        .collect())
+}
+
+/// Drawdown, CAGR, rolling Sharpe and underwater time over the last `days` (default 30)
+async fn equity_analytics(State(state): State<ControlState>, Query(query): Query<EquityQuery>) -> Result<Json<EquityAnalytics>, ApiError> {
+    let days = query.days.unwrap_or(30).clamp(1, 3650);
+    let now = Utc::now();
+    let snapshots = equity::load_snapshots(&state.db_pool, now - Duration::days(days), now).await?;
+    Ok(Json(EquityAnalytics::compute(&snapshots)))
+}
+
+async fn pause_discovery(State(state): State<ControlState>, Extension(role): Extension<Role>) -> Result<Json<Value>, ApiError> {
+    require(role, Role::Operator)?;
+    if !state.discovery_paused.swap(true, Ordering::SeqCst) {
+        info!("⏸️ Discovery paused via control API");
+        state.record(role, AuditAction::Halted, "discovery_paused", Value::Null);
+    }
+    Ok(Json(json!({ "discovery_paused": true })))
+}
+
+async fn resume_discovery(State(state): State<ControlState>, Extension(role): Extension<Role>) -> Result<Json<Value>, ApiError> {
+    require(role, Role::Operator)?;
+    if state.discovery_paused.swap(false, Ordering::SeqCst) {
+        info!("▶️ Discovery resumed via control API");
+        state.record(role, AuditAction::Resumed, "discovery_resumed", Value::Null);
+    }
+    Ok(Json(json!({ "discovery_paused": false })))
+}
+
+async fn limits(State(state): State<ControlState>) -> Json<Value> {
+    Json(json!({
+        "current": &*state.risk_manager.limits(),
+        "launch": state.risk_manager.launch_limits(),
+    }))
+}
+
+/// Operators tighten; admins may also loosen a limit back up to its launch value. 422 lists
+/// every violation
+async fn update_limits(
+    State(state): State<ControlState>,
+    Extension(role): Extension<Role>,
+    Json(update): Json<LimitUpdate>,
+) -> Result<Json<Value>, ApiError> {
+    require(role, Role::Operator)?;
+    match state.risk_manager.update_limits(&update, role >= Role::Admin) {
+        Ok(limits) => Ok(Json(json!({ "current": &*limits }))),
+        Err(errors) => {
+            state.record(role, AuditAction::Rejected, "limits_out_of_bounds", json!({ "update": update, "errors": errors }));
+            Err(ApiError(StatusCode::UNPROCESSABLE_ENTITY, json!({ "errors": errors })))
+        }
+    }
+}
+
+async fn tuning(State(state): State<ControlState>) -> Json<Value> {
+    Json(json!({ "current": *state.tuning.lock().unwrap() }))
+}
+
+/// Any knob within its hard-coded bounds; 422 lists every violation and changes nothing
+async fn update_tuning(
+    State(state): State<ControlState>,
+    Extension(role): Extension<Role>,
+    Json(update): Json<TuningUpdate>,
+) -> Result<Json<Value>, ApiError> {
+    require(role, Role::Operator)?;
+    let result = {
+        let mut tuning = state.tuning.lock().unwrap();
+        let before = *tuning;
+        tuning.with_update(&update).map(|next| {
+            *tuning = next;
+            (before, next)
+        })
+    };
+    match result {
+        Ok((before, next)) => {
+            info!("🎚️ Tuning updated: {}", serde_json::to_string(&update).unwrap_or_default());
+            state.record(role, AuditAction::Approved, "tuning_updated", json!({ "update": update, "before": before, "after": next }));
+            Ok(Json(json!({ "current": next })))
+        }
+        Err(errors) => {
+            state.record(role, AuditAction::Rejected, "tuning_out_of_bounds", json!({ "update": update, "errors": errors }));
+            Err(ApiError(StatusCode::UNPROCESSABLE_ENTITY, json!({ "errors": errors })))
+        }
+    }
+}
+
+async fn flags(State(state): State<ControlState>) -> Json<Value> {
+    Json(json!({ "flags": state.flags.snapshot() }))
+}
+
+/// Switch a flag or change its rollout share; stored for every process on the database
+async fn update_flag(
+    State(state): State<ControlState>,
+    Extension(role): Extension<Role>,
+    Path(name): Path<String>,
+    Json(update): Json<FlagUpdate>,
+) -> Result<Json<Value>, ApiError> {
+    require(role, Role::Operator)?;
+    let flag = Flag::parse(&name)
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, json!({ "error": format!("no feature flag {}", name) })))?;
+    let before = state.flags.state(flag);
+    let next = match before.with_update(&update) {
+        Ok(next) => next,
+        Err(errors) => {
+            state.record(role, AuditAction::Rejected, "flag_invalid", json!({ "flag": flag, "update": update, "errors": errors }));
+            return Err(ApiError(StatusCode::UNPROCESSABLE_ENTITY, json!({ "errors": errors })));
+        }
+    };
+    state.flags.set(flag, next, role.as_str()).await?;
+    info!("🚩 {} set to {} for {:.0}%", flag.as_str(), if next.enabled { "on" } else { "off" }, next.rollout_pct * 100.0);
+    state.record(role, AuditAction::Approved, "flag_updated", json!({ "flag": flag, "before": before, "after": next }));
+    Ok(Json(json!({ "flag": flag, "current": next })))
+}
+
+/// 409 until the emergency stop's incident has been acknowledged
+async fn rearm(State(state): State<ControlState>, Extension(role): Extension<Role>) -> Result<Json<Value>, ApiError> {
+    require(role, Role::Admin)?;
+    state.risk_manager.rearm().await
+        .map_err(|e| ApiError(StatusCode::CONFLICT, json!({ "error": e.to_string() })))?;
+    Ok(Json(json!({ "rearmed": true, "capital": state.risk_manager.current_capital() })))
+}
+
+/// Accepted, not done: the execution engine cancels, exits and alerts on anything stuck
+async fn flatten(State(state): State<ControlState>, Extension(role): Extension<Role>) -> Result<(StatusCode, Json<Value>), ApiError> {
+    require(role, Role::Operator)?;
+    let positions = state.risk_manager.open_positions_snapshot().len();
+    warn!("🧯 Flatten-all requested via control API ({} positions)", positions);
+    state.record(role, AuditAction::Approved, "flatten_all", json!({ "open_positions": positions }));
+    state.risk_manager.close_all_positions();
+    Ok((StatusCode::ACCEPTED, Json(json!({ "flatten_requested": true, "open_positions": positions }))))
+}
+
+/// Flagged trades still waiting for an operator, oldest first, at most `limit` (default 50)
+async fn reviews(State(state): State<ControlState>, Query(query): Query<ReviewQuery>) -> Result<Json<Vec<TradeReview>>, ApiError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    Ok(Json(review_queue::pending(&state.db_pool, limit).await?))
+}
+
+/// Approve or escalate a flagged trade; 409 when it isn't pending
+async fn resolve_review(
+    State(state): State<ControlState>,
+    Extension(role): Extension<Role>,
+    Path(review_id): Path<i64>,
+    Json(decision): Json<ReviewDecision>,
+) -> Result<Json<Value>, ApiError> {
+    require(role, Role::Operator)?;
+    if !review_queue::resolve(&state.db_pool, review_id, decision.verdict, role.as_str(), decision.notes.as_deref()).await? {
+        return Err(ApiError(StatusCode::CONFLICT, json!({ "error": format!("review {} is not pending", review_id) })));
+    }
+    info!("🔎 Review #{} resolved: {:?}", review_id, decision.verdict);
+    state.record(role, AuditAction::Approved, "review_resolved", json!({ "review_id": review_id, "decision": decision }));
+    Ok(Json(json!({ "review_id": review_id, "status": decision.verdict })))
+}
+
+pub async fn serve(state: ControlState, bind: &str, port: u16) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind((bind, port)).await?;
+    info!("🎛️ Control API on {}:{}", bind, port);
+    axum::serve(listener, router(state)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_comparison() {
+        assert!(token_matches("s3cret-token", "s3cret-token"));
+        assert!(!token_matches("s3cret-tokem", "s3cret-token"));
+        assert!(!token_matches("s3cret", "s3cret-token"));
+        assert!(!token_matches("", "s3cret-token"));
+    }
+
+    #[test]
+    fn test_keys_resolve_to_their_strongest_role() {
+        let keys = parse_keys(" viewer:dash-key, operator:ops-key ,admin:root-key,").unwrap();
+        assert_eq!(keys.len(), 3);
+        assert_eq!(role_for(&keys, "dash-key"), Some(Role::Viewer));
+        assert_eq!(role_for(&keys, "root-key"), Some(Role::Admin));
+        assert_eq!(role_for(&keys, "nope"), None);
+
+        // The same key listed twice counts at its strongest
+        let doubled = parse_keys("viewer:shared,operator:shared").unwrap();
+        assert_eq!(role_for(&doubled, "shared"), Some(Role::Operator));
+
+        assert!(require(Role::Viewer, Role::Operator).is_err());
+        assert!(require(Role::Operator, Role::Admin).is_err());
+        assert!(require(Role::Admin, Role::Operator).is_ok());
+
+        assert_eq!(parse_keys("root:abc").unwrap_err(), "unknown role root");
+        assert_eq!(parse_keys("admin:").unwrap_err(), "empty admin key");
+        assert!(parse_keys("just-a-key").is_err());
+    }
+}
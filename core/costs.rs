@@ -0,0 +1,111 @@
+// Trading Costs - Fees and Slippage
+// Every simulated or test trade pays the same costs a live one would:
+// the venue's maker/taker fee on both legs, plus slippage estimated from half the spread
+// and the order's size relative to the depth shown at the touch.
+// Without this a pattern that only wins before costs looks like an edge.
+// Rates and bps are measurements and stay f64; the notional and the costs are money.
+
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+
+use crate::core::money::{self, Decimal, MONEY_DP};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VenueFees {
+    pub maker_rate: f64,  // fraction of notional for resting (limit) fills
+    pub taker_rate: f64,  // fraction of notional for crossing (market) fills
+}
+
+/// Costs of one trade, in quote currency
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TradeCosts {
+    pub fees: Decimal,
+    pub slippage: Decimal,
+}
+
+impl TradeCosts {
+    pub fn total(&self) -> Decimal {
+        self.fees + self.slippage
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostModel {
+    pub venues: HashMap<String, VenueFees>,
+    pub default_fees: VenueFees,  // for venues without a schedule
+    pub half_spread_bps: f64,
+    pub impact_bps: f64,          // extra slippage when the order equals the depth at the touch
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        // Lowest retail tier of each venue's published schedule
+        let venues = HashMap::from([
+            ("coinbase".to_string(), VenueFees { maker_rate: 0.004, taker_rate: 0.006 }),
+            ("kraken".to_string(), VenueFees { maker_rate: 0.0016, taker_rate: 0.0026 }),
+        ]);
+
+        CostModel {
+            venues,
+            default_fees: VenueFees { maker_rate: 0.004, taker_rate: 0.006 },
+            half_spread_bps: 5.0,
+            impact_bps: 20.0,
+        }
+    }
+}
+
+impl CostModel {
+    pub fn fees_for(&self, venue: &str) -> VenueFees {
+        self.venues.get(&venue.to_lowercase()).copied().unwrap_or(self.default_fees)
+    }
+
+    /// Expected slippage in bps for crossing the spread with `notional`
+    /// Impact grows with the square root of size over depth; unknown depth prices the spread only
+    pub fn slippage_bps(&self, notional: f64, depth: Option<f64>) -> f64 {
+        let impact = match depth {
+            Some(depth) if depth > 0.0 => self.impact_bps * (notional / depth).sqrt(),
+            _ => 0.0,
+        };
+        self.half_spread_bps + impact
+    }
+
+    /// Entry plus exit: fees on both legs and slippage on both crossings
+    /// A maker entry pays the maker fee and no slippage on the way in
+    pub fn round_trip(&self, venue: &str, notional: Decimal, depth: Option<f64>, maker_entry: bool) -> TradeCosts {
+        let fees = self.fees_for(venue);
+        let bps = self.slippage_bps(money::to_f64(notional), depth);
+        let crossing = money::share(notional, bps / 10_000.0).round_dp(MONEY_DP);
+
+        let (entry_fee, entry_slippage) = if maker_entry {
+            (fees.maker_rate, Decimal::ZERO)
+        } else {
+            (fees.taker_rate, crossing)
+        };
+
+        TradeCosts {
+            fees: (money::share(notional, entry_fee) + money::share(notional, fees.taker_rate)).round_dp(MONEY_DP),
+            slippage: entry_slippage + crossing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_round_trip_costs() {
+        let model = CostModel::default();
+
+        // $5 taker round trip on coinbase: 2 × 0.6% fees and 2 × 5bps spread
+        let costs = model.round_trip("Coinbase", dec!(5), None, false);
+        assert_eq!(costs.fees, dec!(0.06));
+        assert_eq!(costs.slippage, dec!(0.005));
+
+        // Resting entry is cheaper; eating the whole touch costs more
+        assert!(model.round_trip("coinbase", dec!(5), None, true).total() < costs.total());
+        assert!(model.slippage_bps(1000.0, Some(1000.0)) > model.slippage_bps(10.0, Some(1000.0)));
+        assert_eq!(model.fees_for("unknown"), model.default_fees);
+    }
+}
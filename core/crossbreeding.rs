@@ -0,0 +1,176 @@
+// Cross-Symbol Crossbreeding
+// A pattern that works on one market often works on markets that move with it. Elite patterns
+// are re-bred onto correlated symbols as fresh hypotheses, which gives new listings candidates
+// long before random discovery would stumble on them
+
+use std::collections::{BTreeMap, HashMap};
+use chrono::Utc;
+use sha2::{Sha256, Digest};
+use sqlx::{PgPool, Row};
+
+use crate::core::evolution_engine::{Individual, Offspring};
+use crate::risk::correlation::pearson;
+
+/// Hours both symbols must have traded before their correlation counts; a day of history
+/// is enough to pair a new listing with an established market
+pub const MIN_OVERLAP_HOURS: usize = 24;
+
+/// Hourly close-to-close returns keyed by the hour's unix timestamp
+pub type HourlyReturns = HashMap<String, BTreeMap<i64, f64>>;
+
+/// Returns between consecutive hourly closes; gaps in the series are skipped, not bridged
+pub fn hourly_returns(closes: &BTreeMap<i64, f64>) -> BTreeMap<i64, f64> {
+    closes.iter()
+        .zip(closes.iter().skip(1))
+        .filter(|((prev_hour, prev), (hour, _))| **hour - **prev_hour == 3600 && **prev > 0.0)
+        .map(|((_, prev), (hour, close))| (*hour, close / prev - 1.0))
+        .collect()
+}
+
+/// Symbols whose returns correlate with `symbol` at or above `min_correlation`, strongest first
+pub fn correlated_symbols(returns: &HourlyReturns, symbol: &str, min_correlation: f64) -> Vec<(String, f64)> {
+    let Some(base) = returns.get(symbol) else {
+        return Vec::new();
+    };
+
+    let mut correlated: Vec<(String, f64)> = returns.iter()
+        .filter(|(other, _)| other.as_str() != symbol)
+        .filter_map(|(other, series)| {
+            let (xs, ys): (Vec<f64>, Vec<f64>) = base.iter()
+                .filter_map(|(hour, r)| series.get(hour).map(|o| (*r, *o)))
+                .unzip();
+            if xs.len() < MIN_OVERLAP_HOURS {
+                return None;
+            }
+            pearson(&xs, &ys)
+                .filter(|corr| *corr >= min_correlation)
+                .map(|corr| (other.clone(), corr))
+        })
+        .collect();
+
+    correlated.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    correlated
+}
+
+/// The parent's conditions, bound to `symbol`
+/// The hash is derived from parent and symbol, so breeding the same pair twice is a no-op
+pub fn crossbreed(parent: &Individual, symbol: &str) -> Offspring {
+    let mut hypothesis = parent.hypothesis.clone();
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}:{}", parent.hypothesis.hash, symbol));
+    hypothesis.hash = format!("{:x}", hasher.finalize())[..16].to_string();
+    hypothesis.symbol = Some(symbol.to_string());
+    hypothesis.created_at = Utc::now().timestamp();
+
+    Offspring {
+        hypothesis,
+        generation: parent.generation + 1,
+        parent_patterns: vec![parent.hypothesis.hash.clone()],
+    }
+}
+
+/// Hourly returns for every symbol with market data in the last `days`
+pub async fn load_hourly_returns(db_pool: &PgPool, days: i32) -> Result<HourlyReturns, sqlx::Error> {
+    let query = "
+        SELECT symbol, EXTRACT(EPOCH FROM date_trunc('hour', timestamp))::bigint AS hour,
+               (array_agg(price::float8 ORDER BY timestamp DESC))[1] AS close
+        FROM market_data
+        WHERE timestamp > NOW() - make_interval(days => $1)
+        GROUP BY symbol, date_trunc('hour', timestamp)
+    ";
+
+    let rows = sqlx::query(query)
+        .bind(days)
+        .fetch_all(db_pool)
+        .await?;
+
+    let mut closes: HashMap<String, BTreeMap<i64, f64>> = HashMap::new();
+    for row in rows {
+        closes.entry(row.get("symbol")).or_default().insert(row.get("hour"), row.get("close"));
+    }
+
+    Ok(closes.into_iter().map(|(symbol, series)| (symbol, hourly_returns(&series))).collect())
+}
+
+/// The symbol each unbound pattern has made the most money on in live trades
+pub async fn load_best_symbols(db_pool: &PgPool, pattern_hashes: &[String]) -> Result<HashMap<String, String>, sqlx::Error> {
+    let query = "
+        SELECT DISTINCT ON (pattern_hash) pattern_hash, symbol
+        FROM trades
+        WHERE status = 'closed' AND pattern_hash = ANY($1)
+        GROUP BY pattern_hash, symbol
+        HAVING SUM(profit_loss) > 0
+        ORDER BY pattern_hash, SUM(profit_loss) DESC
+    ";
+
+    let rows = sqlx::query(query)
+        .bind(pattern_hashes)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| (row.get("pattern_hash"), row.get("symbol"))).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::discovery_engine::Hypothesis;
+    use crate::core::performance::PerformanceWindow;
+
+    fn series(returns: impl Iterator<Item = f64>) -> BTreeMap<i64, f64> {
+        returns.enumerate().map(|(i, r)| (i as i64 * 3600, r)).collect()
+    }
+
+    #[test]
+    fn test_correlated_symbols_needs_overlap_and_strength() {
+        let wave = |i: usize| ((i as f64) * 0.7).sin() / 100.0;
+        let mut returns = HourlyReturns::new();
+        returns.insert("BTC-USD".to_string(), series((0..48).map(wave)));
+        returns.insert("ETH-USD".to_string(), series((0..48).map(|i| 1.5 * wave(i))));
+        returns.insert("INV-USD".to_string(), series((0..48).map(|i| -wave(i))));
+        // A brand-new listing with less than a day of history
+        returns.insert("NEW-USD".to_string(), series((0..10).map(wave)));
+
+        let correlated = correlated_symbols(&returns, "BTC-USD", 0.7);
+        assert_eq!(correlated.len(), 1);
+        assert_eq!(correlated[0].0, "ETH-USD");
+        assert!((correlated[0].1 - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hourly_returns_skip_gaps() {
+        let closes: BTreeMap<i64, f64> = [(0, 100.0), (3600, 110.0), (10800, 121.0), (14400, 99.0)].into_iter().collect();
+        let returns = hourly_returns(&closes);
+
+        assert_eq!(returns.len(), 2);
+        assert!((returns[&3600] - 0.1).abs() < 1e-12);
+        assert!((returns[&14400] + 0.18181818).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_crossbreed_binds_symbol_with_stable_lineage() {
+        let parent = Individual {
+            hypothesis: Hypothesis {
+                hash: "parent".to_string(),
+                entry_conditions: vec![],
+                exit_conditions: vec![],
+                timeframe: 30,
+                created_at: 0,
+                symbol: Some("BTC-USD".to_string()),
+            },
+            generation: 4,
+            parent_patterns: vec![],
+            window: PerformanceWindow::default(),
+            shortfall_bps: 0.0,
+            fitness: 1.0,
+            protected: false,
+        };
+
+        let child = crossbreed(&parent, "ETH-USD");
+        assert_eq!(child.hypothesis.symbol.as_deref(), Some("ETH-USD"));
+        assert_eq!(child.generation, 5);
+        assert_eq!(child.parent_patterns, vec!["parent".to_string()]);
+        assert_eq!(child.hypothesis.hash, crossbreed(&parent, "ETH-USD").hypothesis.hash);
+        assert_ne!(child.hypothesis.hash, crossbreed(&parent, "SOL-USD").hypothesis.hash);
+    }
+}
@@ -0,0 +1,374 @@
+// Daily Performance Report
+// At each day rollover (the [clock] calendar's, UTC midnight by default) the monitoring loop
+// closes out the previous day: live PnL and trades,
+// the equity curve it leaves behind, test activity and the state of the pattern population.
+// The report is stored as that day's performance_metrics row and summarized in the log
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::core::clock::Calendar;
+use crate::core::equity::{self, EquityAnalytics};
+use crate::core::monte_carlo::{self, MonteCarloConfig, Projection};
+use crate::core::performance::sharpe;
+use crate::core::rng::SharedRng;
+
+/// Days of equity curve carried in each report
+pub const EQUITY_CURVE_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PatternSummary {
+    pub total_patterns: i64,
+    pub active_patterns: i64,
+    pub avg_win_rate: f64,  // over patterns with at least one test
+}
+
+/// Closed live trades for one trading day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyPnl {
+    pub day: NaiveDate,
+    pub trades: u32,
+    pub wins: u32,
+    pub pnl: f64,  // net of fees
+    pub fees: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub day: NaiveDate,
+    pub equity: f64,
+    pub drawdown: f64,  // below the running peak, 0.0-1.0
+}
+
+/// Equity at each day's close, from `starting_capital` plus cumulative PnL
+pub fn equity_curve(starting_capital: f64, daily: &[DailyPnl]) -> Vec<EquityPoint> {
+    let mut sorted: Vec<&DailyPnl> = daily.iter().collect();
+    sorted.sort_by_key(|d| d.day);
+
+    let mut equity = starting_capital;
+    let mut peak = starting_capital;
+    sorted.iter().map(|d| {
+        equity += d.pnl;
+        peak = peak.max(equity);
+        EquityPoint {
+            day: d.day,
+            equity,
+            drawdown: if peak > 0.0 { ((peak - equity) / peak).clamp(0.0, 1.0) } else { 0.0 },
+        }
+    }).collect()
+}
+
+/// Annualized Sharpe of day-over-day equity returns
+pub fn curve_sharpe(starting_capital: f64, curve: &[EquityPoint]) -> f64 {
+    let mut previous = starting_capital;
+    let returns: Vec<f64> = curve.iter().filter_map(|point| {
+        let r = (previous > 0.0).then(|| point.equity / previous - 1.0);
+        previous = point.equity;
+        r
+    }).collect();
+    sharpe(&returns)
+}
+
+/// Fires once per trading day change with the day that just ended
+pub struct DayRollover {
+    calendar: Calendar,
+    current: NaiveDate,
+}
+
+impl DayRollover {
+    pub fn new(calendar: Calendar, now: DateTime<Utc>) -> Self {
+        DayRollover { calendar, current: calendar.day_of(now) }
+    }
+
+    pub fn check(&mut self, now: DateTime<Utc>) -> Option<NaiveDate> {
+        let today = self.calendar.day_of(now);
+        if today <= self.current {
+            return None;
+        }
+        let ended = today - Duration::days(1);
+        self.current = today;
+        Some(ended)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyReport {
+    pub report_date: NaiveDate,
+    pub run_id: Option<Uuid>,
+    pub capital: f64,             // risk manager's tracked capital at rollover
+    pub daily_pnl: f64,
+    pub fees: f64,
+    pub trades: u32,
+    pub wins: u32,
+    pub tests_run: i64,
+    pub test_pnl: f64,
+    pub patterns: PatternSummary,
+    pub best_pattern_hash: Option<String>,
+    pub worst_pattern_hash: Option<String>,
+    pub sharpe_30d: f64,
+    pub max_drawdown_30d: f64,
+    pub equity_curve: Vec<EquityPoint>,
+    pub equity: EquityAnalytics,  // all-time, from minute snapshots through the end of the day
+    #[serde(default)]
+    pub projection: Option<Projection>,  // Monte Carlo equity paths from the active patterns' realized trades
+}
+
+impl DailyReport {
+    /// The headline numbers for webhooks, without the equity curve
+    pub fn webhook_summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "report_date": self.report_date,
+            "capital": self.capital,
+            "daily_pnl": self.daily_pnl,
+            "fees": self.fees,
+            "trades": self.trades,
+            "win_rate": self.win_rate(),
+            "tests_run": self.tests_run,
+            "active_patterns": self.patterns.active_patterns,
+            "total_patterns": self.patterns.total_patterns,
+            "sharpe_30d": self.sharpe_30d,
+            "max_drawdown_30d": self.max_drawdown_30d,
+            "probability_of_ruin": self.projection.as_ref().map(|p| p.probability_of_ruin),
+        })
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.trades > 0 { self.wins as f64 / self.trades as f64 } else { 0.0 }
+    }
+
+    /// Gather the report for `day`; the curve is anchored so that it ends at the realized
+    /// equity implied by `starting_capital` and every closed trade
+    pub async fn build(
+        db_pool: &PgPool,
+        calendar: &Calendar,
+        day: NaiveDate,
+        starting_capital: f64,
+        capital: f64,
+        run_id: Option<Uuid>,
+    ) -> Result<Self, sqlx::Error> {
+        let curve_start = day - Duration::days(EQUITY_CURVE_DAYS - 1);
+        let daily = daily_pnl(db_pool, calendar, curve_start, day).await?;
+        let pnl_before = realized_pnl_before(db_pool, calendar.start_of(curve_start)).await?;
+        let curve = equity_curve(starting_capital + pnl_before, &daily);
+
+        let today = daily.iter().find(|d| d.day == day);
+        let bounds = calendar.bounds(day);
+        let (tests_run, test_pnl) = test_activity(db_pool, bounds).await?;
+        let (best_pattern_hash, worst_pattern_hash) = best_and_worst_patterns(db_pool, bounds).await?;
+        let day_end = bounds.1;
+        let snapshots = equity::load_snapshots(db_pool, DateTime::UNIX_EPOCH, day_end).await?;
+
+        Ok(DailyReport {
+            report_date: day,
+            run_id,
+            capital,
+            daily_pnl: today.map(|d| d.pnl).unwrap_or(0.0),
+            fees: today.map(|d| d.fees).unwrap_or(0.0),
+            trades: today.map(|d| d.trades).unwrap_or(0),
+            wins: today.map(|d| d.wins).unwrap_or(0),
+            tests_run,
+            test_pnl,
+            patterns: pattern_summary(db_pool).await?,
+            best_pattern_hash,
+            worst_pattern_hash,
+            sharpe_30d: curve_sharpe(starting_capital + pnl_before, &curve),
+            max_drawdown_30d: curve.iter().map(|p| p.drawdown).fold(0.0, f64::max),
+            equity_curve: curve,
+            equity: EquityAnalytics::compute(&snapshots),
+            projection: None,
+        })
+    }
+
+    /// Project equity forward from the report's capital by resampling the active patterns'
+    /// recent closed trades; left empty when they have none
+    pub async fn project_equity(&mut self, db_pool: &PgPool, config: &MonteCarloConfig, rng: &mut SharedRng) -> Result<(), sqlx::Error> {
+        let since = Utc::now() - Duration::days(config.lookback_days);
+        let sample = monte_carlo::load_trades(db_pool, since, config.lookback_days).await?;
+        self.projection = monte_carlo::project(&sample, self.capital, config, rng);
+        Ok(())
+    }
+
+    /// Upsert as the day's performance_metrics row; re-running a day replaces it
+    pub async fn store(&self, db_pool: &PgPool) -> Result<(), sqlx::Error> {
+        sqlx::query("
+            INSERT INTO performance_metrics
+            (metric_date, total_capital, daily_pnl, total_trades, winning_trades, active_patterns, total_patterns,
+             avg_win_rate, best_pattern_hash, worst_pattern_hash, sharpe_ratio, max_drawdown, run_id, report)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            ON CONFLICT (metric_date) DO UPDATE SET
+                total_capital = EXCLUDED.total_capital, daily_pnl = EXCLUDED.daily_pnl,
+                total_trades = EXCLUDED.total_trades, winning_trades = EXCLUDED.winning_trades,
+                active_patterns = EXCLUDED.active_patterns, total_patterns = EXCLUDED.total_patterns,
+                avg_win_rate = EXCLUDED.avg_win_rate, best_pattern_hash = EXCLUDED.best_pattern_hash,
+                worst_pattern_hash = EXCLUDED.worst_pattern_hash, sharpe_ratio = EXCLUDED.sharpe_ratio,
+                max_drawdown = EXCLUDED.max_drawdown, run_id = EXCLUDED.run_id, report = EXCLUDED.report
+        ")
+            .bind(self.report_date)
+            .bind(self.capital)
+            .bind(self.daily_pnl)
+            .bind(self.trades as i32)
+            .bind(self.wins as i32)
+            .bind(self.patterns.active_patterns as i32)
+            .bind(self.patterns.total_patterns as i32)
+            .bind(self.patterns.avg_win_rate)
+            .bind(&self.best_pattern_hash)
+            .bind(&self.worst_pattern_hash)
+            .bind(self.sharpe_30d.clamp(-9999.0, 9999.0))  // DECIMAL(8,4)
+            .bind(self.max_drawdown_30d)
+            .bind(self.run_id)
+            .bind(serde_json::to_value(self).unwrap_or_default())
+            .execute(db_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn log_summary(&self) {
+        info!("📅 Daily report {}", self.report_date);
+        info!("   Capital: ${:.2} | PnL: ${:+.2} (fees ${:.2}) | Trades: {} ({:.1}% wins)",
+            self.capital, self.daily_pnl, self.fees, self.trades, self.win_rate() * 100.0);
+        info!("   Tests: {} (${:+.2}) | Patterns: {} total, {} active, avg win rate {:.2}%",
+            self.tests_run, self.test_pnl, self.patterns.total_patterns,
+            self.patterns.active_patterns, self.patterns.avg_win_rate * 100.0);
+        info!("   30d Sharpe: {:.2} | 30d max drawdown: {:.2}%",
+            self.sharpe_30d, self.max_drawdown_30d * 100.0);
+        if self.equity.snapshots > 0 {
+            let max_dd = self.equity.max_drawdown.as_ref().map(|d| d.depth).unwrap_or(0.0);
+            info!("   All-time max drawdown: {:.2}% | CAGR: {} | underwater {}h (longest {}h)",
+                max_dd * 100.0,
+                self.equity.cagr.map(|c| format!("{:.2}%", c * 100.0)).unwrap_or_else(|| "n/a".to_string()),
+                self.equity.underwater_secs / 3600, self.equity.longest_underwater_secs / 3600);
+        }
+        if let Some((projection, horizon)) = self.projection.as_ref().and_then(|p| Some((p, p.at_horizon()?))) {
+            info!("   {}d projection: ${:.2} median (${:.2}-${:.2}) | P(ruin) {:.2}% | P(loss) {:.1}%",
+                projection.horizon_days, horizon.p50, horizon.p5, horizon.p95,
+                projection.probability_of_ruin * 100.0, projection.probability_of_loss * 100.0);
+        }
+        if let (Some(best), Some(worst)) = (&self.best_pattern_hash, &self.worst_pattern_hash) {
+            info!("   Best pattern: {} | Worst pattern: {}", best, worst);
+        }
+    }
+}
+
+pub async fn pattern_summary(db_pool: &PgPool) -> Result<PatternSummary, sqlx::Error> {
+    let row = sqlx::query("
+        SELECT COUNT(*) AS total_patterns,
+               COUNT(*) FILTER (WHERE is_active) AS active_patterns,
+               COALESCE(AVG(win_rate) FILTER (WHERE test_count > 0), 0)::float8 AS avg_win_rate
+        FROM discovered_patterns
+    ")
+        .fetch_one(db_pool)
+        .await?;
+
+    Ok(PatternSummary {
+        total_patterns: row.get("total_patterns"),
+        active_patterns: row.get("active_patterns"),
+        avg_win_rate: row.get("avg_win_rate"),
+    })
+}
+
+/// Closed live trades per trading day over [from, to], days without trades omitted
+pub async fn daily_pnl(db_pool: &PgPool, calendar: &Calendar, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyPnl>, sqlx::Error> {
+    let rows = sqlx::query("
+        SELECT ((exit_time AT TIME ZONE $3) - make_interval(secs => $4))::date AS day, COUNT(*) AS trades,
+               COUNT(*) FILTER (WHERE profit_loss > 0) AS wins,
+               COALESCE(SUM(profit_loss), 0)::float8 AS pnl,
+               COALESCE(SUM(fees), 0)::float8 AS fees
+        FROM trades
+        WHERE status = 'closed' AND exit_time >= $1 AND exit_time < $2
+        GROUP BY 1
+        ORDER BY 1
+    ")
+        .bind(calendar.start_of(from))
+        .bind(calendar.bounds(to).1)
+        .bind(calendar.timezone_name())
+        .bind(calendar.day_start_secs())
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| DailyPnl {
+        day: row.get("day"),
+        trades: row.get::<i64, _>("trades") as u32,
+        wins: row.get::<i64, _>("wins") as u32,
+        pnl: row.get("pnl"),
+        fees: row.get("fees"),
+    }).collect())
+}
+
+async fn realized_pnl_before(db_pool: &PgPool, before: DateTime<Utc>) -> Result<f64, sqlx::Error> {
+    let row = sqlx::query("
+        SELECT COALESCE(SUM(profit_loss), 0)::float8 AS pnl
+        FROM trades
+        WHERE status = 'closed' AND exit_time < $1
+    ")
+        .bind(before)
+        .fetch_one(db_pool)
+        .await?;
+
+    Ok(row.get("pnl"))
+}
+
+async fn test_activity(db_pool: &PgPool, (start, end): (DateTime<Utc>, DateTime<Utc>)) -> Result<(i64, f64), sqlx::Error> {
+    let row = sqlx::query("
+        SELECT COUNT(*) AS tests, COALESCE(SUM(profit), 0)::float8 AS pnl
+        FROM test_results
+        WHERE timestamp >= $1 AND timestamp < $2
+    ")
+        .bind(start)
+        .bind(end)
+        .fetch_one(db_pool)
+        .await?;
+
+    Ok((row.get("tests"), row.get("pnl")))
+}
+
+/// Patterns with the highest and lowest closed-trade PnL between `start` and `end`
+async fn best_and_worst_patterns(db_pool: &PgPool, (start, end): (DateTime<Utc>, DateTime<Utc>)) -> Result<(Option<String>, Option<String>), sqlx::Error> {
+    let rows = sqlx::query("
+        SELECT pattern_hash, SUM(profit_loss)::float8 AS pnl
+        FROM trades
+        WHERE status = 'closed' AND pattern_hash IS NOT NULL AND exit_time >= $1 AND exit_time < $2
+        GROUP BY pattern_hash
+        ORDER BY pnl DESC
+    ")
+        .bind(start)
+        .bind(end)
+        .fetch_all(db_pool)
+        .await?;
+
+    let hash = |row: Option<&sqlx::postgres::PgRow>| row.map(|r| r.get::<String, _>("pattern_hash"));
+    Ok((hash(rows.first()), hash(rows.last())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equity_curve_tracks_drawdown_from_peak() {
+        let day = |d: u32, pnl: f64| DailyPnl {
+            day: NaiveDate::from_ymd_opt(2025, 3, d).unwrap(), trades: 1, wins: (pnl > 0.0) as u32, pnl, fees: 0.0,
+        };
+        // Out of order on purpose
+        let curve = equity_curve(200.0, &[day(3, 30.0), day(1, 50.0), day(2, -50.0)]);
+
+        assert_eq!(curve.iter().map(|p| p.equity).collect::<Vec<_>>(), vec![250.0, 200.0, 230.0]);
+        assert_eq!(curve[0].drawdown, 0.0);
+        assert!((curve[1].drawdown - 0.2).abs() < 1e-12);
+        assert!((curve[2].drawdown - 0.08).abs() < 1e-12);
+        assert!(curve_sharpe(200.0, &curve) > 0.0);
+    }
+
+    #[test]
+    fn test_rollover_fires_once_per_day_with_the_day_that_ended() {
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let mut rollover = DayRollover::new(Calendar::default(), at("2025-03-01T23:58:00Z"));
+
+        assert_eq!(rollover.check(at("2025-03-01T23:59:00Z")), None);
+        assert_eq!(rollover.check(at("2025-03-02T00:00:30Z")), NaiveDate::from_ymd_opt(2025, 3, 1));
+        assert_eq!(rollover.check(at("2025-03-02T00:01:30Z")), None);
+    }
+}
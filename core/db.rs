@@ -0,0 +1,46 @@
+// Database - The Shared Postgres Pool
+// Every process opens its pool here, sized and timed by the [database] section. sqlx prepares
+// each statement once per connection and keeps it in a per-connection cache, so the hot writes
+// and reads (test results, ticks, positions) skip the parse and plan after their first use on a
+// connection. A query that cannot get a connection within acquire_timeout_secs fails instead of
+// queueing behind a stuck one, so a slow database shows up as errors rather than as latency on
+// the trading path
+
+use std::str::FromStr;
+use std::time::Duration;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::PgPool;
+
+use crate::config::DatabaseSettings;
+
+/// Pool sizes and timeouts; 0 leaves idle connections open, or connections unrecycled
+pub fn pool_options(settings: &DatabaseSettings) -> PgPoolOptions {
+    let secs = |secs: u64| (secs > 0).then(|| Duration::from_secs(secs));
+    PgPoolOptions::new()
+        .max_connections(settings.max_connections)
+        .min_connections(settings.min_connections)
+        .acquire_timeout(Duration::from_secs(settings.acquire_timeout_secs))
+        .idle_timeout(secs(settings.idle_timeout_secs))
+        .max_lifetime(secs(settings.max_lifetime_secs))
+}
+
+pub async fn connect(database_url: &str, settings: &DatabaseSettings) -> Result<PgPool, sqlx::Error> {
+    let options = PgConnectOptions::from_str(database_url)?
+        .statement_cache_capacity(settings.statement_cache_capacity);
+    pool_options(settings).connect_with(options).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_options_follow_the_settings() {
+        let settings = DatabaseSettings { max_connections: 12, min_connections: 3, idle_timeout_secs: 0, ..DatabaseSettings::default() };
+        let options = pool_options(&settings);
+        assert_eq!((options.get_max_connections(), options.get_min_connections()), (12, 3));
+        assert_eq!(options.get_acquire_timeout(), Duration::from_secs(5));
+        assert_eq!(options.get_idle_timeout(), None);
+        assert_eq!(options.get_max_lifetime(), Some(Duration::from_secs(1800)));
+    }
+}
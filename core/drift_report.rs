@@ -0,0 +1,200 @@
+// Simulation vs Live Drift - Closing the Loop on Simulator Fidelity
+// Every live signal is paired with what the simulator predicted for the same signal,
+// so patterns that only work on paper get exposed per-pattern
+
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+/// What the simulator expected to happen for a signal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedFill {
+    pub price: f64,
+    pub pnl: f64,
+    pub signal_time: DateTime<Utc>,
+}
+
+/// What actually happened on the exchange (None when the order never filled)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveFill {
+    pub price: f64,
+    pub pnl: f64,
+    pub fill_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillComparison {
+    pub pattern_hash: String,
+    pub simulated: SimulatedFill,
+    pub live: Option<LiveFill>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PatternDrift {
+    pub pattern_hash: String,
+    pub signals: u32,
+    pub missed_fills: u32,
+    pub avg_slippage_bps: f64,  // live vs simulated fill price
+    pub avg_delay_ms: f64,      // signal to live fill
+    pub pnl_drift: f64,         // total live pnl minus total simulated pnl
+}
+
+impl PatternDrift {
+    pub fn missed_fill_rate(&self) -> f64 {
+        if self.signals == 0 {
+            return 0.0;
+        }
+        self.missed_fills as f64 / self.signals as f64
+    }
+}
+
+/// Aggregate raw comparisons into one drift row per pattern, worst pnl drift first
+pub fn summarize(comparisons: &[FillComparison]) -> Vec<PatternDrift> {
+    let mut by_pattern: HashMap<&str, (PatternDrift, u32)> = HashMap::new();
+
+    for c in comparisons {
+        let (drift, filled) = by_pattern
+            .entry(c.pattern_hash.as_str())
+            .or_insert_with(|| (PatternDrift {
+                pattern_hash: c.pattern_hash.clone(),
+                ..Default::default()
+            }, 0));
+
+        drift.signals += 1;
+
+        match &c.live {
+            Some(live) => {
+                *filled += 1;
+                if c.simulated.price != 0.0 {
+                    drift.avg_slippage_bps += (live.price - c.simulated.price) / c.simulated.price * 10_000.0;
+                }
+                drift.avg_delay_ms += (live.fill_time - c.simulated.signal_time).num_milliseconds() as f64;
+                drift.pnl_drift += live.pnl - c.simulated.pnl;
+            }
+            None => {
+                // A missed fill forfeits whatever the simulator expected to earn
+                drift.missed_fills += 1;
+                drift.pnl_drift -= c.simulated.pnl;
+            }
+        }
+    }
+
+    let mut report: Vec<PatternDrift> = by_pattern
+        .into_values()
+        .map(|(mut drift, filled)| {
+            if filled > 0 {
+                drift.avg_slippage_bps /= filled as f64;
+                drift.avg_delay_ms /= filled as f64;
+            }
+            drift
+        })
+        .collect();
+
+    report.sort_by(|a, b| a.pnl_drift.partial_cmp(&b.pnl_drift).unwrap_or(std::cmp::Ordering::Equal));
+    report
+}
+
+pub struct DriftTracker {
+    pub run_id: Option<Uuid>,
+    db_pool: PgPool,
+}
+
+impl DriftTracker {
+    pub fn new(db_pool: PgPool) -> Self {
+        DriftTracker { run_id: None, db_pool }
+    }
+
+    pub async fn record(&self, comparison: &FillComparison) -> Result<(), sqlx::Error> {
+        let query = "
+            INSERT INTO fill_comparisons
+            (pattern_hash, sim_price, sim_pnl, signal_time, live_price, live_pnl, fill_time, run_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ";
+
+        sqlx::query(query)
+            .bind(&comparison.pattern_hash)
+            .bind(comparison.simulated.price)
+            .bind(comparison.simulated.pnl)
+            .bind(comparison.simulated.signal_time)
+            .bind(comparison.live.as_ref().map(|l| l.price))
+            .bind(comparison.live.as_ref().map(|l| l.pnl))
+            .bind(comparison.live.as_ref().map(|l| l.fill_time))
+            .bind(self.run_id)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Per-pattern drift over the trailing window
+    pub async fn report(&self, hours: i64) -> Result<Vec<PatternDrift>, sqlx::Error> {
+        let query = "
+            SELECT pattern_hash, sim_price, sim_pnl, signal_time, live_price, live_pnl, fill_time
+            FROM fill_comparisons
+            WHERE signal_time > NOW() - make_interval(hours => $1::int)
+        ";
+
+        let rows = sqlx::query(query)
+            .bind(hours)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        let comparisons: Vec<FillComparison> = rows.iter().map(|row| {
+            let live_price: Option<f64> = row.get("live_price");
+            let live_pnl: Option<f64> = row.get("live_pnl");
+            let fill_time: Option<DateTime<Utc>> = row.get("fill_time");
+
+            FillComparison {
+                pattern_hash: row.get("pattern_hash"),
+                simulated: SimulatedFill {
+                    price: row.get("sim_price"),
+                    pnl: row.get("sim_pnl"),
+                    signal_time: row.get("signal_time"),
+                },
+                live: match (live_price, live_pnl, fill_time) {
+                    (Some(price), Some(pnl), Some(fill_time)) => Some(LiveFill { price, pnl, fill_time }),
+                    _ => None,
+                },
+            }
+        }).collect();
+
+        Ok(summarize(&comparisons))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_summarize_drift() {
+        let t0 = Utc::now();
+        let sim = |pnl| SimulatedFill { price: 100.0, pnl, signal_time: t0 };
+
+        let comparisons = vec![
+            FillComparison {
+                pattern_hash: "a".to_string(),
+                simulated: sim(1.0),
+                live: Some(LiveFill { price: 100.5, pnl: 0.5, fill_time: t0 + Duration::milliseconds(200) }),
+            },
+            FillComparison { pattern_hash: "a".to_string(), simulated: sim(2.0), live: None },
+            FillComparison {
+                pattern_hash: "b".to_string(),
+                simulated: sim(1.0),
+                live: Some(LiveFill { price: 100.0, pnl: 1.0, fill_time: t0 }),
+            },
+        ];
+
+        let report = summarize(&comparisons);
+        assert_eq!(report[0].pattern_hash, "a");
+        assert_eq!(report[0].signals, 2);
+        assert_eq!(report[0].missed_fills, 1);
+        assert!((report[0].avg_slippage_bps - 50.0).abs() < 1e-9);
+        assert!((report[0].avg_delay_ms - 200.0).abs() < 1e-9);
+        assert!((report[0].pnl_drift + 2.5).abs() < 1e-9);
+        assert_eq!(report[1].pnl_drift, 0.0);
+    }
+}
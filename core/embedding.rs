@@ -0,0 +1,284 @@
+// Pattern Embeddings
+// A fixed-length vector describing what a hypothesis looks at and how: per metric and side,
+// the condition weight, the squashed threshold and the direction of the comparison, plus the
+// timeframe. Vectors live in pgvector so near neighbours are one indexed query away: for
+// de-duplicating fresh hypotheses, for niching, and for "what else looks like this one"
+
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+
+use crate::core::discovery_engine::{Condition, Hypothesis};
+
+/// Bump with any change to the layout below; rows written under an older version are re-embedded
+pub const EMBEDDING_VERSION: i32 = 1;
+
+/// The vocabulary at version 1, frozen so new metrics can't silently shift every slot.
+/// Anything else (including the random `pattern_*`/`metric_*` names) shares the last slot
+const EMBEDDED_METRICS: [&str; 16] = [
+    "price_delta_1m", "price_delta_5m", "price_delta_15m", "price_acceleration",
+    "volume_ratio_1m", "volume_ratio_5m", "volume_spike", "volume_acceleration",
+    "order_book_imbalance", "bid_ask_spread", "trade_count_1m", "buy_sell_ratio",
+    "sentiment_1h", "news_shock", "social_velocity", "social_engagement",
+];
+
+const FEATURES_PER_SLOT: usize = 3;  // weight, threshold, direction
+const SLOTS_PER_SIDE: usize = EMBEDDED_METRICS.len() + 1;
+
+/// Must match the `vector(N)` column in pattern_embeddings
+pub const EMBEDDING_DIM: usize = 2 * SLOTS_PER_SIDE * FEATURES_PER_SLOT + 1;
+
+fn slot(metric: &str) -> usize {
+    EMBEDDED_METRICS.iter()
+        .position(|m| *m == metric)
+        .unwrap_or(EMBEDDED_METRICS.len())
+}
+
+fn direction(operator: &str) -> f32 {
+    match operator {
+        ">" | "crosses_above" => 1.0,
+        "<" | "crosses_below" => -1.0,
+        _ => 0.0,
+    }
+}
+
+/// Unit-length structural embedding; thresholds are squashed so ±100 and ±1000 look alike
+pub fn embed(h: &Hypothesis) -> Vec<f32> {
+    let mut v = vec![0.0f32; EMBEDDING_DIM];
+
+    let mut add_side = |conditions: &[Condition], side: usize| {
+        let mut counts = [0u32; SLOTS_PER_SIDE];
+        for c in conditions {
+            let s = slot(&c.metric);
+            let base = (side * SLOTS_PER_SIDE + s) * FEATURES_PER_SLOT;
+            counts[s] += 1;
+            v[base] += c.weight as f32;
+            v[base + 1] += (c.value / 50.0).tanh() as f32;
+            v[base + 2] += direction(&c.operator);
+        }
+        // Threshold and direction are means, weight is a sum
+        for (s, &n) in counts.iter().enumerate().filter(|(_, &n)| n > 1) {
+            let base = (side * SLOTS_PER_SIDE + s) * FEATURES_PER_SLOT;
+            v[base + 1] /= n as f32;
+            v[base + 2] /= n as f32;
+        }
+    };
+    add_side(&h.entry_conditions, 0);
+    add_side(&h.exit_conditions, 1);
+
+    v[EMBEDDING_DIM - 1] = ((h.timeframe.max(1) as f64).ln() / 1440f64.ln()) as f32;
+
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+    v
+}
+
+/// 1 - cosine similarity, as pgvector's `<=>` computes it
+pub fn cosine_distance(a: &[f32], b: &[f32]) -> f64 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let na = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let nb = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if na == 0.0 || nb == 0.0 {
+        return 1.0;
+    }
+    1.0 - (dot / (na * nb)) as f64
+}
+
+/// pgvector text form, bound as `$n::vector`
+pub fn to_pgvector(v: &[f32]) -> String {
+    let parts: Vec<String> = v.iter().map(|x| x.to_string()).collect();
+    format!("[{}]", parts.join(","))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Neighbor {
+    pub pattern_hash: String,
+    pub distance: f64,
+    pub test_count: i32,
+    pub win_rate: f64,
+    pub sharpe_ratio: f64,
+    pub is_active: bool,
+}
+
+#[derive(Clone)]
+pub struct PatternIndex {
+    pub dedup_distance: f64,  // fresh hypotheses closer than this to a stored pattern are not tested
+    db_pool: PgPool,
+}
+
+impl PatternIndex {
+    pub fn new(db_pool: PgPool) -> Self {
+        PatternIndex {
+            dedup_distance: 0.01,
+            db_pool,
+        }
+    }
+
+    /// PATTERN_DEDUP_DISTANCE overrides the default; 0 disables de-duplication
+    pub fn from_env(db_pool: PgPool) -> Self {
+        let mut index = PatternIndex::new(db_pool);
+        if let Some(d) = std::env::var("PATTERN_DEDUP_DISTANCE").ok().and_then(|v| v.parse::<f64>().ok()) {
+            index.dedup_distance = d.max(0.0);
+        }
+        index
+    }
+
+    pub async fn upsert(&self, h: &Hypothesis) -> Result<(), sqlx::Error> {
+        sqlx::query("
+            INSERT INTO pattern_embeddings (pattern_hash, embedding, version, updated_at)
+            VALUES ($1, $2::vector, $3, NOW())
+            ON CONFLICT (pattern_hash) DO UPDATE SET
+                embedding = EXCLUDED.embedding, version = EXCLUDED.version, updated_at = NOW()
+        ")
+            .bind(&h.hash)
+            .bind(to_pgvector(&embed(h)))
+            .bind(EMBEDDING_VERSION)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The `k` stored patterns closest to `embedding`, nearest first
+    pub async fn nearest(&self, embedding: &[f32], k: i64, exclude: Option<&str>) -> Result<Vec<Neighbor>, sqlx::Error> {
+        let rows = sqlx::query("
+            SELECT p.pattern_hash, (e.embedding <=> $1::vector)::float8 AS distance,
+                   COALESCE(p.test_count, 0) AS test_count, COALESCE(p.win_rate, 0)::float8 AS win_rate,
+                   COALESCE(p.sharpe_ratio, 0)::float8 AS sharpe_ratio, COALESCE(p.is_active, false) AS is_active
+            FROM pattern_embeddings e
+            JOIN discovered_patterns p ON p.pattern_hash = e.pattern_hash
+            WHERE e.version = $2 AND p.pattern_hash IS DISTINCT FROM $3
+            ORDER BY e.embedding <=> $1::vector
+            LIMIT $4
+        ")
+            .bind(to_pgvector(embedding))
+            .bind(EMBEDDING_VERSION)
+            .bind(exclude)
+            .bind(k)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| Neighbor {
+            pattern_hash: row.get("pattern_hash"),
+            distance: row.get("distance"),
+            test_count: row.get("test_count"),
+            win_rate: row.get("win_rate"),
+            sharpe_ratio: row.get("sharpe_ratio"),
+            is_active: row.get("is_active"),
+        }).collect())
+    }
+
+    /// Patterns most like a stored one; None when the pattern is unknown
+    pub async fn similar_to(&self, pattern_hash: &str, k: i64) -> Result<Option<Vec<Neighbor>>, sqlx::Error> {
+        let row = sqlx::query("
+            SELECT entry_conditions, exit_conditions, COALESCE(timeframe_minutes, 60) AS timeframe_minutes
+            FROM discovered_patterns WHERE pattern_hash = $1
+        ")
+            .bind(pattern_hash)
+            .fetch_optional(&self.db_pool)
+            .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let h = Hypothesis {
+            hash: pattern_hash.to_string(),
+            entry_conditions: serde_json::from_value(row.get("entry_conditions")).unwrap_or_default(),
+            exit_conditions: serde_json::from_value(row.get("exit_conditions")).unwrap_or_default(),
+            timeframe: row.get::<i32, _>("timeframe_minutes") as u32,
+            created_at: 0,
+            symbol: None,
+        };
+        self.nearest(&embed(&h), k, Some(pattern_hash)).await.map(Some)
+    }
+
+    /// The stored pattern this hypothesis duplicates, if any
+    pub async fn find_duplicate(&self, h: &Hypothesis) -> Result<Option<Neighbor>, sqlx::Error> {
+        if self.dedup_distance <= 0.0 {
+            return Ok(None);
+        }
+        let nearest = self.nearest(&embed(h), 1, Some(&h.hash)).await?;
+        Ok(nearest.into_iter().find(|n| n.distance < self.dedup_distance))
+    }
+
+    /// Embed up to `limit` patterns with no embedding, or one from an older version
+    pub async fn backfill(&self, limit: i64) -> Result<usize, sqlx::Error> {
+        let rows = sqlx::query("
+            SELECT p.pattern_hash, p.entry_conditions, p.exit_conditions, COALESCE(p.timeframe_minutes, 60) AS timeframe_minutes
+            FROM discovered_patterns p
+            LEFT JOIN pattern_embeddings e ON e.pattern_hash = p.pattern_hash
+            WHERE e.pattern_hash IS NULL OR e.version <> $1
+            LIMIT $2
+        ")
+            .bind(EMBEDDING_VERSION)
+            .bind(limit)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        let mut embedded = 0;
+        for row in rows {
+            let (Ok(entry_conditions), Ok(exit_conditions)) = (
+                serde_json::from_value(row.get("entry_conditions")),
+                serde_json::from_value(row.get("exit_conditions")),
+            ) else {
+                continue;
+            };
+            let h = Hypothesis {
+                hash: row.get("pattern_hash"),
+                entry_conditions,
+                exit_conditions,
+                timeframe: row.get::<i32, _>("timeframe_minutes") as u32,
+                created_at: 0,
+                symbol: None,
+            };
+            self.upsert(&h).await?;
+            embedded += 1;
+        }
+
+        Ok(embedded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hypothesis(conditions: &[(&str, &str, f64)], timeframe: u32) -> Hypothesis {
+        Hypothesis {
+            hash: "h".to_string(),
+            entry_conditions: conditions.iter().map(|(metric, operator, value)| Condition {
+                metric: metric.to_string(),
+                operator: operator.to_string(),
+                value: *value,
+                weight: 0.5,
+            }).collect(),
+            exit_conditions: vec![Condition { metric: "price_delta_5m".to_string(), operator: "<".to_string(), value: -1.0, weight: 0.5 }],
+            timeframe,
+            created_at: 0,
+            symbol: None,
+        }
+    }
+
+    #[test]
+    fn test_embedding_shape_and_norm() {
+        let v = embed(&hypothesis(&[("volume_spike", ">", 3.0)], 15));
+        assert_eq!(v.len(), EMBEDDING_DIM);
+        assert_eq!(EMBEDDING_DIM, 103);  // pattern_embeddings.embedding is vector(103)
+        assert!((v.iter().map(|x| x * x).sum::<f32>() - 1.0).abs() < 1e-5);
+        assert!(to_pgvector(&v).starts_with('['));
+    }
+
+    #[test]
+    fn test_threshold_tweaks_are_near_and_different_ideas_are_far() {
+        let base = embed(&hypothesis(&[("volume_spike", ">", 3.0), ("buy_sell_ratio", ">", 1.2)], 15));
+        let tweaked = embed(&hypothesis(&[("volume_spike", ">", 3.1), ("buy_sell_ratio", ">", 1.25)], 15));
+        let inverted = embed(&hypothesis(&[("volume_spike", "<", 3.0), ("buy_sell_ratio", "<", 1.2)], 15));
+        let different = embed(&hypothesis(&[("order_book_imbalance", ">", 40.0)], 600));
+
+        assert!(cosine_distance(&base, &tweaked) < 0.01);
+        assert!(cosine_distance(&base, &inverted) > 0.1);
+        assert!(cosine_distance(&base, &different) > 0.3);
+        assert!(cosine_distance(&base, &base).abs() < 1e-6);
+    }
+}
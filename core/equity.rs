@@ -0,0 +1,241 @@
+// Equity Curve - Minute Snapshots and Drawdown Analytics
+// The monitoring loop snapshots tracked equity and open exposure every minute. From those
+// snapshots: max drawdown with its peak, trough and recovery, CAGR, rolling Sharpe over
+// daily closes, and how long equity has spent below its high-water mark
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::core::money;
+use crate::core::performance::sharpe;
+use crate::core::risk_manager::RiskManager;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquitySnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub equity: f64,
+    pub open_exposure: f64,  // notional in open positions
+    pub open_positions: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Drawdown {
+    pub depth: f64,  // fraction of the peak, 0.0-1.0
+    pub peak_at: DateTime<Utc>,
+    pub trough_at: DateTime<Utc>,
+    pub recovered_at: Option<DateTime<Utc>>,  // None while still below the peak
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EquityAnalytics {
+    pub snapshots: usize,
+    pub start_equity: f64,
+    pub end_equity: f64,
+    pub max_drawdown: Option<Drawdown>,
+    pub cagr: Option<f64>,             // None over less than a day
+    pub sharpe_30d: f64,               // annualized, last 30 daily closes
+    pub underwater_secs: i64,          // current time below the high-water mark
+    pub longest_underwater_secs: i64,
+}
+
+impl EquityAnalytics {
+    pub fn compute(points: &[EquitySnapshot]) -> Self {
+        let (Some(first), Some(last)) = (points.first(), points.last()) else {
+            return EquityAnalytics::default();
+        };
+        let closes = daily_closes(points);
+        let (underwater, longest) = underwater(points);
+
+        EquityAnalytics {
+            snapshots: points.len(),
+            start_equity: first.equity,
+            end_equity: last.equity,
+            max_drawdown: max_drawdown(points),
+            cagr: cagr(points),
+            sharpe_30d: rolling_sharpe(&closes, 30).last().map(|(_, s)| *s).unwrap_or(0.0),
+            underwater_secs: underwater.num_seconds(),
+            longest_underwater_secs: longest.num_seconds(),
+        }
+    }
+}
+
+/// The deepest peak-to-trough decline; points must be in time order
+pub fn max_drawdown(points: &[EquitySnapshot]) -> Option<Drawdown> {
+    let mut peak = points.first()?;
+    let mut worst: Option<Drawdown> = None;
+
+    for p in points {
+        if p.equity >= peak.equity {
+            if let Some(w) = worst.as_mut().filter(|w| w.recovered_at.is_none() && w.peak_at == peak.taken_at) {
+                w.recovered_at = Some(p.taken_at);
+            }
+            peak = p;
+            continue;
+        }
+        let depth = if peak.equity > 0.0 { (peak.equity - p.equity) / peak.equity } else { 0.0 };
+        if worst.as_ref().map(|w| depth > w.depth).unwrap_or(true) {
+            worst = Some(Drawdown { depth, peak_at: peak.taken_at, trough_at: p.taken_at, recovered_at: None });
+        }
+    }
+
+    worst
+}
+
+/// Compound annual growth rate between the first and last snapshot
+pub fn cagr(points: &[EquitySnapshot]) -> Option<f64> {
+    let (first, last) = (points.first()?, points.last()?);
+    let years = (last.taken_at - first.taken_at).num_seconds() as f64 / (365.25 * 86_400.0);
+    if years < 1.0 / 365.25 || first.equity <= 0.0 || last.equity <= 0.0 {
+        return None;
+    }
+    Some((last.equity / first.equity).powf(1.0 / years) - 1.0)
+}
+
+/// (current, longest) stretch spent below the running high-water mark
+pub fn underwater(points: &[EquitySnapshot]) -> (Duration, Duration) {
+    let mut peak = f64::MIN;
+    let mut since: Option<DateTime<Utc>> = None;
+    let mut longest = Duration::zero();
+
+    for p in points {
+        if p.equity >= peak {
+            peak = p.equity;
+            if let Some(start) = since.take() {
+                longest = longest.max(p.taken_at - start);
+            }
+        } else if since.is_none() {
+            since = Some(p.taken_at);
+        }
+    }
+
+    let current = match (since, points.last()) {
+        (Some(start), Some(last)) => last.taken_at - start,
+        _ => Duration::zero(),
+    };
+    (current, longest.max(current))
+}
+
+/// Last snapshot of each UTC day
+pub fn daily_closes(points: &[EquitySnapshot]) -> Vec<(NaiveDate, f64)> {
+    let mut closes: Vec<(NaiveDate, f64)> = Vec::new();
+    for p in points {
+        let day = p.taken_at.date_naive();
+        match closes.last_mut() {
+            Some((d, equity)) if *d == day => *equity = p.equity,
+            _ => closes.push((day, p.equity)),
+        }
+    }
+    closes
+}
+
+/// Annualized Sharpe of the `window` daily returns ending on each day that has a full window
+pub fn rolling_sharpe(closes: &[(NaiveDate, f64)], window: usize) -> Vec<(NaiveDate, f64)> {
+    let returns: Vec<(NaiveDate, f64)> = closes.windows(2)
+        .filter(|pair| pair[0].1 > 0.0)
+        .map(|pair| (pair[1].0, pair[1].1 / pair[0].1 - 1.0))
+        .collect();
+
+    returns.windows(window.max(2))
+        .map(|w| (w[w.len() - 1].0, sharpe(&w.iter().map(|(_, r)| *r).collect::<Vec<_>>())))
+        .collect()
+}
+
+pub struct EquityTracker {
+    pub run_id: Option<Uuid>,
+    db_pool: PgPool,
+}
+
+impl EquityTracker {
+    pub fn new(db_pool: PgPool, run_id: Option<Uuid>) -> Self {
+        EquityTracker { run_id, db_pool }
+    }
+
+    /// Snapshot the risk manager's tracked equity and open exposure
+    pub async fn record(&self, risk_manager: &RiskManager) -> Result<EquitySnapshot, sqlx::Error> {
+        let positions = risk_manager.open_positions_snapshot();
+        let snapshot = EquitySnapshot {
+            taken_at: Utc::now(),
+            equity: money::to_f64(risk_manager.current_capital()),
+            open_exposure: money::to_f64(positions.iter().map(|(_, p)| p.size).sum()),
+            open_positions: positions.len() as i32,
+        };
+
+        sqlx::query("
+            INSERT INTO equity_snapshots (taken_at, run_id, equity, open_exposure, open_positions)
+            VALUES ($1, $2, $3, $4, $5)
+        ")
+            .bind(snapshot.taken_at)
+            .bind(self.run_id)
+            .bind(snapshot.equity)
+            .bind(snapshot.open_exposure)
+            .bind(snapshot.open_positions)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(snapshot)
+    }
+
+    /// Analytics over every snapshot from `since` until now
+    pub async fn analytics(&self, since: DateTime<Utc>) -> Result<EquityAnalytics, sqlx::Error> {
+        Ok(EquityAnalytics::compute(&load_snapshots(&self.db_pool, since, Utc::now()).await?))
+    }
+}
+
+/// Snapshots taken in [from, to), oldest first
+pub async fn load_snapshots(db_pool: &PgPool, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<EquitySnapshot>, sqlx::Error> {
+    let rows = sqlx::query("
+        SELECT taken_at, equity, open_exposure, open_positions
+        FROM equity_snapshots
+        WHERE taken_at >= $1 AND taken_at < $2
+        ORDER BY taken_at
+    ")
+        .bind(from)
+        .bind(to)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| EquitySnapshot {
+        taken_at: row.get("taken_at"),
+        equity: row.get("equity"),
+        open_exposure: row.get("open_exposure"),
+        open_positions: row.get("open_positions"),
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(equities: &[f64], step: Duration) -> Vec<EquitySnapshot> {
+        let start = DateTime::parse_from_rfc3339("2025-03-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        equities.iter().enumerate().map(|(i, &equity)| EquitySnapshot {
+            taken_at: start + step * i as i32,
+            equity,
+            open_exposure: 0.0,
+            open_positions: 0,
+        }).collect()
+    }
+
+    #[test]
+    fn test_drawdown_recovery_and_underwater_time() {
+        let points = curve(&[100.0, 120.0, 90.0, 110.0, 125.0, 115.0], Duration::hours(1));
+
+        let dd = max_drawdown(&points).unwrap();
+        assert!((dd.depth - 0.25).abs() < 1e-12);
+        assert_eq!(dd.peak_at, points[1].taken_at);
+        assert_eq!(dd.trough_at, points[2].taken_at);
+        assert_eq!(dd.recovered_at, Some(points[4].taken_at));
+
+        let (current, longest) = underwater(&points);
+        assert_eq!(current, Duration::zero());  // the last point is the start of a new dip
+        assert_eq!(longest, Duration::hours(2));
+
+        // Doubling over a year is a 100% CAGR; closes collapse to one per day
+        let yearly = curve(&[100.0, 200.0], Duration::days(365) + Duration::hours(6));
+        assert!((cagr(&yearly).unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(daily_closes(&points).len(), 1);
+        assert!(cagr(&points).is_none());
+    }
+}
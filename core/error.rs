@@ -0,0 +1,96 @@
+// Errors - One Type for Failures That Cross Subsystems
+// What a caller sees when a failure leaves its subsystem: the venue, the database, the risk
+// manager or the data, and whether a retry could help
+
+use crate::core::exchange::ExchangeError;
+use crate::core::execution_engine::ExecutionError;
+use crate::core::order_manager::OrderError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{0}")]
+    Exchange(#[from] ExchangeError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("risk manager refused: {0}")]
+    RiskRejected(String),
+    #[error("bad data: {0}")]
+    Data(String),  // unreadable or inconsistent input, e.g. a payload that won't serialize
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Worth trying again: the venue or the database may simply not have answered
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Exchange(e) => e.is_retryable(),
+            Error::Database(e) => matches!(e, sqlx::Error::PoolTimedOut | sqlx::Error::Io(_) | sqlx::Error::PoolClosed),
+            Error::RiskRejected(_) | Error::Data(_) => false,
+        }
+    }
+
+    /// Reason code for the audit log and anomaly counts
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Exchange(_) => "exchange_error",
+            Error::Database(_) => "db_error",
+            Error::RiskRejected(_) => "risk_rejected",
+            Error::Data(_) => "data_error",
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Data(e.to_string())
+    }
+}
+
+impl From<OrderError> for Error {
+    fn from(e: OrderError) -> Self {
+        match e {
+            OrderError::Database(e) => Error::Database(e),
+            e => Error::Data(e.to_string()),
+        }
+    }
+}
+
+impl From<ExecutionError> for Error {
+    fn from(e: ExecutionError) -> Self {
+        match e {
+            ExecutionError::Exchange(e) => Error::Exchange(e),
+            ExecutionError::Order(e) => e.into(),
+            ExecutionError::RiskRejected(reason) | ExecutionError::Halted(reason) => Error::RiskRejected(reason),
+            e @ (ExecutionError::UnknownExchange(_) | ExecutionError::UnknownPosition(_)) => Error::Data(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subsystem_errors_land_in_their_variant() {
+        let timeout: Error = sqlx::Error::PoolTimedOut.into();
+        assert!(matches!(timeout, Error::Database(_)));
+        assert!(timeout.is_retryable());
+        assert!(!Error::from(sqlx::Error::RowNotFound).is_retryable());
+
+        let rejected: Error = ExecutionError::RiskRejected("max positions".to_string()).into();
+        assert_eq!(rejected.to_string(), "risk manager refused: max positions");
+        assert_eq!(rejected.code(), "risk_rejected");
+
+        let unavailable: Error = ExecutionError::Exchange(ExchangeError::Unavailable("timeout".to_string())).into();
+        assert!(matches!(unavailable, Error::Exchange(_)));
+        assert!(unavailable.is_retryable());
+
+        let unknown: Error = OrderError::UnknownOrder("entry-1".to_string()).into();
+        assert!(matches!(unknown, Error::Data(_)));
+        assert!(matches!(Error::from(serde_json::from_str::<u32>("x").unwrap_err()), Error::Data(_)));
+        let unencodable: Error = OrderError::from(serde_json::from_str::<u32>("x").unwrap_err()).into();
+        assert_eq!(unencodable.code(), "data_error");
+        assert!(!unencodable.is_retryable());
+    }
+}
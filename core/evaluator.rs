@@ -0,0 +1,339 @@
+// Evaluator - Hypothesis Conditions Against Market Data
+// Ticks roll up into one-minute candles, metrics are computed from them, and a hypothesis
+// enters and exits where its conditions hold
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+
+use crate::core::discovery_engine::{Condition, Hypothesis};
+use crate::core::metric_cache::SeriesCache;
+
+/// Minutes of volume a ratio is measured against
+const VOLUME_BASELINE_MINUTES: usize = 60;
+const VOLUME_SPIKE_MINUTES: usize = 15;
+
+/// Width of the candles `aggregate` and `load_candles` build
+pub const CANDLE_MINUTES: u32 = 1;
+
+/// "==" holds within this of the threshold; metrics are percents, ratios and counts
+pub const EQUALS_TOLERANCE: f64 = 0.05;
+
+/// A trade print as a venue reports it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TradeTick {
+    pub timestamp: DateTime<Utc>,
+    pub price: f64,
+    pub volume: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    pub start: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub trades: u32,
+}
+
+/// One-minute candles from ticks in time order; minutes without a trade are skipped
+pub fn aggregate(ticks: &[TradeTick]) -> Vec<Candle> {
+    let mut candles: Vec<Candle> = Vec::new();
+    for tick in ticks {
+        let start = tick.timestamp.duration_trunc(Duration::minutes(1)).unwrap_or(tick.timestamp);
+        match candles.last_mut() {
+            Some(candle) if candle.start == start => {
+                candle.high = candle.high.max(tick.price);
+                candle.low = candle.low.min(tick.price);
+                candle.close = tick.price;
+                candle.volume += tick.volume;
+                candle.trades += 1;
+            }
+            _ => candles.push(Candle {
+                start,
+                open: tick.price,
+                high: tick.price,
+                low: tick.price,
+                close: tick.price,
+                volume: tick.volume,
+                trades: 1,
+            }),
+        }
+    }
+    candles
+}
+
+/// Every name `metric` can answer; any other metric is always None
+pub const METRICS: [&str; 9] = [
+    "price_delta_1m", "price_delta_5m", "price_delta_15m", "price_acceleration",
+    "volume_ratio_1m", "volume_ratio_5m", "volume_spike", "volume_acceleration",
+    "trade_count_1m",
+];
+
+/// `name` as of the close of candle `i`, or None without enough history or data to say
+pub fn metric(candles: &[Candle], i: usize, name: &str) -> Option<f64> {
+    match name {
+        "price_delta_1m" => price_delta(candles, i, 1),
+        "price_delta_5m" => price_delta(candles, i, 5),
+        "price_delta_15m" => price_delta(candles, i, 15),
+        "price_acceleration" => Some(price_delta(candles, i, 1)? - price_delta(candles, i.checked_sub(1)?, 1)?),
+        "volume_ratio_1m" => volume_ratio(candles, i, 1, VOLUME_BASELINE_MINUTES),
+        "volume_ratio_5m" => volume_ratio(candles, i, 5, VOLUME_BASELINE_MINUTES),
+        "volume_spike" => volume_ratio(candles, i, 1, VOLUME_SPIKE_MINUTES),
+        "volume_acceleration" => Some(volume_ratio(candles, i, 1, VOLUME_BASELINE_MINUTES)?
+            - volume_ratio(candles, i.checked_sub(1)?, 1, VOLUME_BASELINE_MINUTES)?),
+        "trade_count_1m" => candles.get(i).map(|c| c.trades as f64),
+        _ => None,
+    }
+}
+
+/// Percent change in close over `minutes` candles
+fn price_delta(candles: &[Candle], i: usize, minutes: usize) -> Option<f64> {
+    let now = candles.get(i)?.close;
+    let then = candles.get(i.checked_sub(minutes)?)?.close;
+    (then > 0.0).then(|| (now / then - 1.0) * 100.0)
+}
+
+/// Volume over the last `minutes` candles against the same span's average over the baseline
+/// before it; at least `minutes` candles of baseline are needed
+fn volume_ratio(candles: &[Candle], i: usize, minutes: usize, baseline: usize) -> Option<f64> {
+    let first = (i + 1).checked_sub(minutes)?;
+    let recent: f64 = candles.get(first..=i)?.iter().map(|c| c.volume).sum();
+    let before = &candles[first.saturating_sub(baseline)..first];
+    if before.len() < minutes {
+        return None;
+    }
+    let expected = before.iter().map(|c| c.volume).sum::<f64>() / before.len() as f64 * minutes as f64;
+    (expected > 0.0).then(|| recent / expected)
+}
+
+/// What one condition saw at one minute
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConditionEval {
+    pub metric: String,
+    pub operator: String,
+    pub threshold: f64,
+    pub value: Option<f64>,
+    pub previous: Option<f64>,  // the minute before; only crosses_* read it
+    pub holds: bool,
+}
+
+pub fn evaluate_condition(condition: &Condition, candles: &[Candle], i: usize) -> ConditionEval {
+    evaluate_condition_in(condition, candles, i, None)
+}
+
+/// `name` at candle `i`, from `cache` if another condition already read it there
+fn cached_metric(candles: &[Candle], i: usize, name: &str, cache: Option<&SeriesCache>) -> Option<f64> {
+    match cache {
+        Some(cache) => cache.get_or_compute(name, candles.get(i)?.start, || metric(candles, i, name)),
+        None => metric(candles, i, name),
+    }
+}
+
+fn evaluate_condition_in(condition: &Condition, candles: &[Candle], i: usize, cache: Option<&SeriesCache>) -> ConditionEval {
+    let value = cached_metric(candles, i, &condition.metric, cache);
+    let previous = i.checked_sub(1).and_then(|p| cached_metric(candles, p, &condition.metric, cache));
+    let threshold = condition.value;
+    let holds = match (condition.operator.as_str(), value, previous) {
+        (">", Some(v), _) => v > threshold,
+        ("<", Some(v), _) => v < threshold,
+        ("==", Some(v), _) => (v - threshold).abs() <= EQUALS_TOLERANCE,
+        ("crosses_above", Some(v), Some(p)) => p <= threshold && v > threshold,
+        ("crosses_below", Some(v), Some(p)) => p >= threshold && v < threshold,
+        _ => false,
+    };
+    ConditionEval {
+        metric: condition.metric.clone(),
+        operator: condition.operator.clone(),
+        threshold,
+        value,
+        previous,
+        holds,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalKind {
+    Entry,
+    Exit,     // every exit condition held
+    Timeout,  // the hypothesis' timeframe ran out first
+}
+
+impl SignalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalKind::Entry => "entry",
+            SignalKind::Exit => "exit",
+            SignalKind::Timeout => "timeout",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Signal {
+    pub at: DateTime<Utc>,          // close of the candle that decided it
+    pub kind: SignalKind,
+    pub price: f64,
+    pub conditions: Vec<ConditionEval>,  // the side that fired; empty on a timeout
+}
+
+/// Every minute of a walk: which side was checked, what each condition saw, and what it decided
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Step {
+    pub at: DateTime<Utc>,
+    pub price: f64,
+    pub holding: bool,                   // exit conditions were checked, else entry
+    pub conditions: Vec<ConditionEval>,
+    pub signal: Option<SignalKind>,
+}
+
+/// Walk `candles` in order, one position at a time, keeping every minute
+pub fn trace(h: &Hypothesis, candles: &[Candle]) -> Vec<Step> {
+    trace_in(h, candles, None)
+}
+
+fn trace_in(h: &Hypothesis, candles: &[Candle], cache: Option<&SeriesCache>) -> Vec<Step> {
+    let mut steps = Vec::with_capacity(candles.len());
+    let mut entered: Option<DateTime<Utc>> = None;
+    for (i, candle) in candles.iter().enumerate() {
+        let at = candle.start + Duration::minutes(1);
+        let side = if entered.is_some() { &h.exit_conditions } else { &h.entry_conditions };
+        let conditions: Vec<ConditionEval> = side.iter().map(|c| evaluate_condition_in(c, candles, i, cache)).collect();
+        let fired = !conditions.is_empty() && conditions.iter().all(|c| c.holds);
+
+        let signal = match entered {
+            None if fired => Some(SignalKind::Entry),
+            Some(_) if fired => Some(SignalKind::Exit),
+            Some(since) if at - since >= Duration::minutes(h.timeframe as i64) => Some(SignalKind::Timeout),
+            _ => None,
+        };
+        let holding = entered.is_some();
+        if let Some(kind) = signal {
+            entered = (kind == SignalKind::Entry).then_some(at);
+        }
+        steps.push(Step { at, price: candle.close, holding, conditions, signal });
+    }
+    steps
+}
+
+/// The minutes of a walk that entered or left a position
+pub fn evaluate(h: &Hypothesis, candles: &[Candle]) -> Vec<Signal> {
+    signals(trace(h, candles))
+}
+
+/// `evaluate`, reading metrics through `cache`; every walk sharing it must use the same candles
+pub fn evaluate_cached(h: &Hypothesis, candles: &[Candle], cache: &SeriesCache) -> Vec<Signal> {
+    signals(trace_in(h, candles, Some(cache)))
+}
+
+fn signals(steps: Vec<Step>) -> Vec<Signal> {
+    steps.into_iter().filter_map(|step| {
+        let kind = step.signal?;
+        Some(Signal {
+            at: step.at,
+            kind,
+            price: step.price,
+            conditions: if kind == SignalKind::Timeout { Vec::new() } else { step.conditions },
+        })
+    }).collect()
+}
+
+/// One-minute candles of stored ticks for `symbol` in [from, to), from any venue
+pub async fn load_candles(db_pool: &PgPool, symbol: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Candle>, sqlx::Error> {
+    let rows = sqlx::query("
+        SELECT date_trunc('minute', timestamp) AS start,
+               (array_agg(price::float8 ORDER BY timestamp))[1] AS open,
+               MAX(price)::float8 AS high,
+               MIN(price)::float8 AS low,
+               (array_agg(price::float8 ORDER BY timestamp DESC))[1] AS close,
+               COALESCE(SUM(volume), 0)::float8 AS volume,
+               COUNT(*) AS trades
+        FROM market_data
+        WHERE symbol = $1 AND timestamp >= $2 AND timestamp < $3
+        GROUP BY 1
+        ORDER BY 1
+    ")
+        .bind(symbol)
+        .bind(from)
+        .bind(to)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| Candle {
+        start: row.get("start"),
+        open: row.get("open"),
+        high: row.get("high"),
+        low: row.get("low"),
+        close: row.get("close"),
+        volume: row.get("volume"),
+        trades: row.get::<i64, _>("trades") as u32,
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    /// A recorded window and the hypothesis evaluated over it
+    #[derive(Deserialize)]
+    struct Fixture {
+        candles: Vec<Candle>,
+        hypothesis: Hypothesis,
+    }
+
+    fn fixtures() -> Vec<PathBuf> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/evaluation");
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|e| e == "json") && !p.to_string_lossy().ends_with(".golden.json"))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Goldens keep six decimals, so reordering a sum can't fail them but a changed metric will
+    fn rounded(signals: Vec<Signal>) -> Vec<Signal> {
+        let round = |v: f64| (v * 1e6).round() / 1e6;
+        signals.into_iter().map(|s| Signal {
+            price: round(s.price),
+            conditions: s.conditions.into_iter().map(|c| ConditionEval {
+                value: c.value.map(round),
+                previous: c.previous.map(round),
+                ..c
+            }).collect(),
+            ..s
+        }).collect()
+    }
+
+    #[test]
+    fn test_recorded_windows_match_golden_signals() {
+        let update = std::env::var("UPDATE_GOLDEN").is_ok();
+        let paths = fixtures();
+        assert!(!paths.is_empty());
+        for path in paths {
+            let fixture: Fixture = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            let signals = rounded(evaluate(&fixture.hypothesis, &fixture.candles));
+            let golden = path.with_extension("golden.json");
+            if update {
+                std::fs::write(&golden, serde_json::to_string_pretty(&signals).unwrap() + "\n").unwrap();
+                continue;
+            }
+            let expected: Vec<Signal> = serde_json::from_str(&std::fs::read_to_string(&golden)
+                .unwrap_or_else(|_| panic!("no {}; run with UPDATE_GOLDEN=1 to record it", golden.display())))
+                .unwrap();
+            assert_eq!(signals, expected, "{} no longer trades as recorded", path.display());
+        }
+
+        // The same minute-candles come back out of their ticks
+        let start = "2026-03-14T14:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let tick = |secs: i64, price: f64| TradeTick { timestamp: start + Duration::seconds(secs), price, volume: 2.0 };
+        let candles = aggregate(&[tick(5, 1.0), tick(30, 1.2), tick(59, 0.9), tick(61, 1.1)]);
+        assert_eq!(candles.len(), 2);
+        assert_eq!((candles[0].open, candles[0].high, candles[0].low, candles[0].close), (1.0, 1.2, 0.9, 0.9));
+        assert_eq!((candles[0].volume, candles[0].trades, candles[1].start), (6.0, 3, start + Duration::minutes(1)));
+    }
+}
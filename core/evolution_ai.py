@@ -121,6 +121,11 @@ class EvolutionEngine:
             # Bonus for consistent patterns
             if win_rate > 0.6 and sharpe > 1.5:
                 pattern['fitness'] *= 1.5
+            
+            # Penalize patterns that only work at zero latency: every 10bps of live
+            # implementation shortfall costs 10% of fitness
+            shortfall = max(0.0, pattern.get('avg_shortfall_bps', 0.0))
+            pattern['fitness'] *= max(0.0, 1.0 - shortfall / 100.0)
         
         return patterns
     
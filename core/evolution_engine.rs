@@ -0,0 +1,587 @@
+// Evolution Engine - Native Genetic Search Over Patterns
+// Runs once a day on the frozen 30-day performance snapshot: scores every pattern,
+// retires the bottom half, and breeds the elite through threshold mutation and
+// entry/exit crossover. Offspring are written to discovered_patterns with their lineage
+// and queued for the discovery engine, which tests them ahead of random hypotheses.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use chrono::{NaiveDate, Utc};
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+use sha2::{Sha256, Digest};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+use tracing::{info, warn};
+
+use crate::core::crossbreeding;
+use crate::core::discovery_engine::Hypothesis;
+use crate::core::execution_quality;
+use crate::core::fitness::{FitnessFunction, FitnessMetric};
+use crate::core::hall_of_fame::HallOfFame;
+use crate::core::money::Decimal;
+use crate::core::mutation::{self, MutationCounts, MutationOperator, MutationRates};
+use crate::core::performance::{self, PerformanceWindow};
+use crate::core::rng::SharedRng;
+use crate::core::speciation::{self, Niching};
+use crate::core::test_budget::{BudgetPolicy, SharedTestBudget, TestBudget};
+
+/// The window selection is made on
+pub const SELECTION_WINDOW_DAYS: i32 = 30;
+/// Market history used to find correlated symbols for crossbreeding
+pub const CROSSBREED_LOOKBACK_DAYS: i32 = 14;
+
+/// A hypothesis bred by evolution, waiting to be tested
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Offspring {
+    pub hypothesis: Hypothesis,
+    pub generation: u32,
+    pub parent_patterns: Vec<String>,
+}
+
+/// Shared between the evolution task (producer) and the discovery loop (consumer)
+pub type OffspringQueue = Arc<Mutex<VecDeque<Offspring>>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvolutionConfig {
+    pub survival_rate: f64,      // fraction kept active each cycle
+    pub elite_fraction: f64,     // fraction that reproduces
+    pub mutation: MutationRates,  // per-operator chance of firing on a mutant
+    pub mutants_per_elite: usize,
+    pub min_trades: u32,         // patterns with fewer trades in the window are left alone
+    pub fitness: FitnessMetric,  // what selection ranks on
+    pub niching: Niching,           // signature (Jaccard) or embedding (cosine) similarity
+    pub similarity_threshold: f64,  // Jaccard similarity at which two patterns are the same species
+    pub niche_distance: f64,        // cosine distance within which embeddings are the same species
+    pub max_per_species: usize,     // active patterns allowed per species
+    pub crossbreed_min_correlation: f64,  // hourly return correlation needed to try a pattern on another symbol
+    pub crossbreed_max_symbols: usize,    // symbols each elite is bred onto per cycle; 0 disables
+}
+
+impl Default for EvolutionConfig {
+    fn default() -> Self {
+        EvolutionConfig {
+            survival_rate: 0.5,
+            elite_fraction: 0.2,
+            mutation: MutationRates::default(),
+            mutants_per_elite: 3,
+            min_trades: 10,
+            fitness: FitnessMetric::default(),
+            niching: Niching::default(),
+            similarity_threshold: 0.6,
+            niche_distance: 0.15,
+            max_per_species: 5,
+            crossbreed_min_correlation: 0.7,
+            crossbreed_max_symbols: 3,
+        }
+    }
+}
+
+impl EvolutionConfig {
+    /// Defaults, with the fitness metric taken from EVOLUTION_FITNESS and niching from EVOLUTION_NICHING
+    pub fn from_env() -> Self {
+        EvolutionConfig {
+            fitness: FitnessMetric::from_env(),
+            niching: Niching::from_env(),
+            ..Default::default()
+        }
+    }
+}
+
+/// One scored member of the population
+#[derive(Debug, Clone)]
+pub struct Individual {
+    pub hypothesis: Hypothesis,
+    pub generation: u32,
+    pub parent_patterns: Vec<String>,
+    pub window: PerformanceWindow,
+    pub shortfall_bps: f64,  // live implementation shortfall, 0.0 without live fills
+    pub fitness: f64,
+    pub protected: bool,     // hall-of-fame member: never retired, never rewritten
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EvolutionOutcome {
+    pub survivors: Vec<String>,
+    pub culled: Vec<String>,
+    pub elite: Vec<String>,  // the survivors that bred this cycle, hall-of-fame candidates
+    pub offspring: Vec<Offspring>,
+    pub mutations: usize,
+    pub crossovers: usize,
+    pub crossbreeds: usize,  // elites re-bred onto correlated symbols
+    pub operator_counts: MutationCounts,  // how often each mutation operator fired
+    pub species: usize,  // distinct niches among this cycle's population
+    pub diversity_index: f64,
+    pub population: usize,
+    pub avg_fitness: f64,
+    pub max_fitness: f64,
+    pub top_performer: Option<String>,
+    pub generation: u32,  // evolution_history generation, set once the cycle is persisted
+}
+
+/// Score with the configured metric, then charge for live execution cost
+pub fn fitness(function: &dyn FitnessFunction, window: &PerformanceWindow, shortfall_bps: f64) -> f64 {
+    if window.trades == 0 {
+        return 0.0;
+    }
+
+    // Every 10bps of shortfall costs 10% of the score's magnitude, so it hurts negative scores too
+    let score = function.score(window);
+    score - score.abs() * (shortfall_bps.max(0.0) / 100.0).min(1.0)
+}
+
+/// Select, cull and breed one generation; pure so it can be tested and replayed
+pub fn evolve<R: Rng>(population: &[Individual], config: &EvolutionConfig, rng: &mut R) -> EvolutionOutcome {
+    let mut ranked: Vec<&Individual> = population.iter().collect();
+    ranked.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap_or(std::cmp::Ordering::Equal));
+
+    let keep = ((ranked.len() as f64) * config.survival_rate).ceil() as usize;
+    let elite_count = ((ranked.len() as f64) * config.elite_fraction).ceil() as usize;
+
+    // Best-first, skipping anyone whose species already has its quota of active patterns
+    let hypotheses: Vec<&Hypothesis> = ranked.iter().map(|i| &i.hypothesis).collect();
+    let species = match config.niching {
+        Niching::Signature => speciation::assign_species(&hypotheses, config.similarity_threshold),
+        Niching::Embedding => speciation::assign_species_by_embedding(&hypotheses, config.niche_distance),
+    };
+    let mut per_species: HashMap<usize, usize> = HashMap::new();
+    let mut survivors: Vec<&Individual> = Vec::new();
+    let mut culled = Vec::new();
+    for (individual, s) in ranked.iter().zip(&species) {
+        let active = per_species.entry(*s).or_insert(0);
+        if individual.protected || (survivors.len() < keep && *active < config.max_per_species) {
+            *active += 1;
+            survivors.push(individual);
+        } else {
+            culled.push(individual.hypothesis.hash.clone());
+        }
+    }
+    let elite = &survivors[..elite_count.min(survivors.len())];
+
+    let mut outcome = EvolutionOutcome {
+        survivors: survivors.iter().map(|i| i.hypothesis.hash.clone()).collect(),
+        culled,
+        elite: elite.iter().map(|i| i.hypothesis.hash.clone()).collect(),
+        species: per_species.len(),
+        diversity_index: speciation::diversity_index(&species),
+        population: ranked.len(),
+        avg_fitness: if ranked.is_empty() { 0.0 } else {
+            ranked.iter().map(|i| i.fitness).sum::<f64>() / ranked.len() as f64
+        },
+        max_fitness: ranked.first().map_or(0.0, |i| i.fitness),
+        top_performer: ranked.first().map(|i| i.hypothesis.hash.clone()),
+        ..Default::default()
+    };
+
+    for (index, parent) in elite.iter().enumerate() {
+        for _ in 0..config.mutants_per_elite {
+            let (child, applied) = mutate(parent, &config.mutation, rng);
+            applied.into_iter().for_each(|op| outcome.operator_counts.record(op));
+            outcome.offspring.push(child);
+            outcome.mutations += 1;
+        }
+
+        if elite.len() > 1 {
+            // Any other elite member, chosen uniformly
+            let mut partner = rng.gen_range(0..elite.len() - 1);
+            if partner >= index {
+                partner += 1;
+            }
+            outcome.offspring.push(crossover(parent, elite[partner], rng));
+            outcome.crossovers += 1;
+        }
+    }
+
+    outcome
+}
+
+/// Copy the parent and apply the configured mutation operators; returns the operators that fired
+pub fn mutate<R: Rng>(parent: &Individual, rates: &MutationRates, rng: &mut R) -> (Offspring, Vec<MutationOperator>) {
+    let mut hypothesis = parent.hypothesis.clone();
+    let applied = mutation::mutate_hypothesis(&mut hypothesis, rates, rng);
+
+    hypothesis.hash = offspring_hash(&[&parent.hypothesis.hash], rng.gen());
+    hypothesis.created_at = Utc::now().timestamp();
+
+    let offspring = Offspring {
+        hypothesis,
+        generation: parent.generation + 1,
+        parent_patterns: vec![parent.hypothesis.hash.clone()],
+    };
+    (offspring, applied)
+}
+
+/// Entry conditions from the fitter parent, exit conditions from the other
+pub fn crossover<R: Rng>(a: &Individual, b: &Individual, rng: &mut R) -> Offspring {
+    let (fitter, other) = if a.fitness >= b.fitness { (a, b) } else { (b, a) };
+
+    let hypothesis = Hypothesis {
+        hash: offspring_hash(&[&a.hypothesis.hash, &b.hypothesis.hash], rng.gen()),
+        entry_conditions: fitter.hypothesis.entry_conditions.clone(),
+        exit_conditions: other.hypothesis.exit_conditions.clone(),
+        timeframe: (a.hypothesis.timeframe + b.hypothesis.timeframe) / 2,
+        created_at: Utc::now().timestamp(),
+        symbol: fitter.hypothesis.symbol.clone(),
+    };
+
+    Offspring {
+        hypothesis,
+        generation: a.generation.max(b.generation) + 1,
+        parent_patterns: vec![a.hypothesis.hash.clone(), b.hypothesis.hash.clone()],
+    }
+}
+
+fn offspring_hash(parents: &[&str], salt: u64) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}{}{}", parents.join(""), Utc::now().timestamp_nanos_opt().unwrap_or(0), salt));
+    format!("{:x}", hasher.finalize())[..16].to_string()
+}
+
+pub struct EvolutionEngine {
+    pub config: EvolutionConfig,
+    pub run_id: Option<Uuid>,
+    pub hall_of_fame: HallOfFame,
+    pub budget_policy: BudgetPolicy,
+    pub test_budget: SharedTestBudget,  // shared with the discovery loop
+    pub rng: SharedRng,                 // selection, mutation and crossover draws
+    queue: OffspringQueue,
+    db_pool: PgPool,
+}
+
+impl EvolutionEngine {
+    pub fn new(db_pool: PgPool, queue: OffspringQueue) -> Self {
+        EvolutionEngine {
+            config: EvolutionConfig::default(),
+            run_id: None,
+            hall_of_fame: HallOfFame::new(db_pool.clone()),
+            budget_policy: BudgetPolicy::default(),
+            test_budget: SharedTestBudget::default(),
+            rng: SharedRng::default(),
+            queue,
+            db_pool,
+        }
+    }
+
+    pub fn offspring_queue(&self) -> OffspringQueue {
+        self.queue.clone()
+    }
+
+    /// Resize per-test capital and the testing rate to current equity
+    /// Returns the new budget when it changed
+    pub fn rebalance_budget(&self, equity: Decimal) -> Option<TestBudget> {
+        let budget = self.budget_policy.budget_for(equity);
+        let mut current = self.test_budget.lock().unwrap();
+        if *current == budget {
+            return None;
+        }
+        *current = budget;
+        Some(budget)
+    }
+
+    /// Score patterns from the snapshot taken for `snapshot_date` and breed the next generation
+    pub async fn run_cycle(&self, snapshot_date: NaiveDate) -> Result<EvolutionOutcome, sqlx::Error> {
+        let mut population = self.load_population(snapshot_date).await?;
+        if population.is_empty() {
+            // Nothing left to select from, e.g. after a purge: start again from the archive
+            let restored = self.hall_of_fame.reseed(&self.queue).await?;
+            info!("🏛️ Empty population, reseeded {} patterns from the hall of fame", restored);
+            return Ok(EvolutionOutcome::default());
+        }
+
+        let members = self.hall_of_fame.members().await?;
+        for individual in &mut population {
+            individual.protected = members.contains(&individual.hypothesis.hash);
+        }
+
+        let mut outcome = evolve(&population, &self.config, &mut self.rng.clone());
+        let crossbred = self.crossbreed_elite(&population, &outcome.elite).await?;
+        outcome.crossbreeds = crossbred.len();
+        outcome.offspring.extend(crossbred);
+
+        outcome.generation = self.persist(&population, &outcome).await?;
+        self.queue.lock().unwrap().extend(outcome.offspring.iter().cloned());
+
+        let elite: Vec<&Individual> = population.iter()
+            .filter(|i| outcome.elite.contains(&i.hypothesis.hash))
+            .collect();
+        self.hall_of_fame.induct(&elite, self.config.fitness).await?;
+
+        Ok(outcome)
+    }
+
+    /// Breed each elite onto the symbols most correlated with the one it works on,
+    /// skipping pairings already bred in an earlier cycle
+    async fn crossbreed_elite(&self, population: &[Individual], elite: &[String]) -> Result<Vec<Offspring>, sqlx::Error> {
+        if self.config.crossbreed_max_symbols == 0 || elite.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let parents: Vec<&Individual> = population.iter()
+            .filter(|i| elite.contains(&i.hypothesis.hash))
+            .collect();
+        let unbound: Vec<String> = parents.iter()
+            .filter(|p| p.hypothesis.symbol.is_none())
+            .map(|p| p.hypothesis.hash.clone())
+            .collect();
+        let best_symbols = crossbreeding::load_best_symbols(&self.db_pool, &unbound).await?;
+        let returns = crossbreeding::load_hourly_returns(&self.db_pool, CROSSBREED_LOOKBACK_DAYS).await?;
+
+        let mut children = Vec::new();
+        for parent in parents {
+            let Some(source) = parent.hypothesis.symbol.as_ref().or_else(|| best_symbols.get(&parent.hypothesis.hash)) else {
+                continue;
+            };
+            let targets = crossbreeding::correlated_symbols(&returns, source, self.config.crossbreed_min_correlation);
+            for (symbol, _) in targets.into_iter().take(self.config.crossbreed_max_symbols) {
+                children.push(crossbreeding::crossbreed(parent, &symbol));
+            }
+        }
+
+        let hashes: Vec<String> = children.iter().map(|c| c.hypothesis.hash.clone()).collect();
+        let existing: HashSet<String> = sqlx::query("SELECT pattern_hash FROM discovered_patterns WHERE pattern_hash = ANY($1)")
+            .bind(&hashes)
+            .fetch_all(&self.db_pool)
+            .await?
+            .iter()
+            .map(|row| row.get("pattern_hash"))
+            .collect();
+        children.retain(|c| !existing.contains(&c.hypothesis.hash));
+
+        Ok(children)
+    }
+
+    async fn load_population(&self, snapshot_date: NaiveDate) -> Result<Vec<Individual>, sqlx::Error> {
+        let snapshots: HashMap<String, PerformanceWindow> =
+            performance::load_snapshots(&self.db_pool, snapshot_date, SELECTION_WINDOW_DAYS).await?
+                .into_iter()
+                .filter(|s| s.window.trades >= self.config.min_trades)
+                .map(|s| (s.pattern_hash, s.window))
+                .collect();
+
+        let since = Utc::now() - chrono::Duration::days(SELECTION_WINDOW_DAYS as i64);
+        let shortfalls: HashMap<String, f64> = execution_quality::load_pattern_stats(&self.db_pool, since).await?
+            .into_iter()
+            .map(|s| (s.pattern_hash, s.avg_shortfall_bps))
+            .collect();
+
+        let function = self.config.fitness.function();
+        let hashes: Vec<String> = snapshots.keys().cloned().collect();
+        let rows = sqlx::query("
+            SELECT pattern_hash, entry_conditions, exit_conditions, timeframe_minutes, generation, parent_patterns, symbol,
+                   EXTRACT(EPOCH FROM created_at)::bigint AS created_at
+            FROM discovered_patterns
+            WHERE pattern_hash = ANY($1)
+        ")
+            .bind(&hashes)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        let mut population = Vec::new();
+        for row in rows {
+            let hash: String = row.get("pattern_hash");
+            let (Ok(entry_conditions), Ok(exit_conditions)) = (
+                serde_json::from_value(row.get("entry_conditions")),
+                serde_json::from_value(row.get("exit_conditions")),
+            ) else {
+                warn!("⚠️ Pattern {} has unreadable conditions, left out of evolution", hash);
+                continue;
+            };
+
+            let window = snapshots[&hash].clone();
+            let shortfall_bps = shortfalls.get(&hash).copied().unwrap_or(0.0);
+            population.push(Individual {
+                fitness: fitness(function.as_ref(), &window, shortfall_bps),
+                hypothesis: Hypothesis {
+                    hash,
+                    entry_conditions,
+                    exit_conditions,
+                    timeframe: row.get::<Option<i32>, _>("timeframe_minutes").unwrap_or(60) as u32,
+                    created_at: row.get::<Option<i64>, _>("created_at").unwrap_or(0),
+                    symbol: row.get("symbol"),
+                },
+                generation: row.get::<Option<i32>, _>("generation").unwrap_or(0) as u32,
+                parent_patterns: row.get::<Option<Vec<String>>, _>("parent_patterns").unwrap_or_default(),
+                window,
+                shortfall_bps,
+                protected: false,
+            });
+        }
+
+        Ok(population)
+    }
+
+    /// Write offspring, retirements and the cycle's history and stats; returns the generation number
+    async fn persist(&self, population: &[Individual], outcome: &EvolutionOutcome) -> Result<u32, sqlx::Error> {
+        for offspring in &outcome.offspring {
+            let h = &offspring.hypothesis;
+            sqlx::query("
+                INSERT INTO discovered_patterns
+                (pattern_hash, entry_conditions, exit_conditions, timeframe_minutes, generation, parent_patterns, symbol,
+                 run_id, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())
+                ON CONFLICT (pattern_hash) DO NOTHING
+            ")
+                .bind(&h.hash)
+                .bind(serde_json::to_value(&h.entry_conditions).unwrap_or_default())
+                .bind(serde_json::to_value(&h.exit_conditions).unwrap_or_default())
+                .bind(h.timeframe as i32)
+                .bind(offspring.generation as i32)
+                .bind(&offspring.parent_patterns)
+                .bind(&h.symbol)
+                .bind(self.run_id)
+                .execute(&self.db_pool)
+                .await?;
+        }
+
+        sqlx::query("UPDATE discovered_patterns SET is_active = false, updated_at = NOW() WHERE pattern_hash = ANY($1)")
+            .bind(&outcome.culled)
+            .execute(&self.db_pool)
+            .await?;
+
+        let row = sqlx::query("
+            INSERT INTO evolution_history
+            (generation, patterns_before, patterns_after, avg_fitness_before, top_performer_hash,
+             mutation_count, crossover_count, mutation_rates, mutation_operators)
+            SELECT COALESCE(MAX(generation), 0) + 1, $1, $2, $3, $4, $5, $6, $7, $8 FROM evolution_history
+            RETURNING generation
+        ")
+            .bind(population.len() as i32)
+            .bind((outcome.survivors.len() + outcome.offspring.len()) as i32)
+            .bind(outcome.avg_fitness)
+            .bind(&outcome.top_performer)
+            .bind(outcome.mutations as i32)
+            .bind(outcome.crossovers as i32)
+            .bind(serde_json::to_value(&self.config.mutation).unwrap_or_default())
+            .bind(serde_json::to_value(&outcome.operator_counts).unwrap_or_default())
+            .fetch_one(&self.db_pool)
+            .await?;
+        let generation: i32 = row.get("generation");
+
+        sqlx::query("
+            INSERT INTO evolution_runs
+            (generation, fitness_metric, population, mean_fitness, max_fitness, diversity_index, species,
+             survivors, culled, offspring, mutations, crossovers, crossbreeds, run_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+        ")
+            .bind(generation)
+            .bind(self.config.fitness.as_str())
+            .bind(outcome.population as i32)
+            .bind(outcome.avg_fitness)
+            .bind(outcome.max_fitness)
+            .bind(outcome.diversity_index)
+            .bind(outcome.species as i32)
+            .bind(outcome.survivors.len() as i32)
+            .bind(outcome.culled.len() as i32)
+            .bind(outcome.offspring.len() as i32)
+            .bind(outcome.mutations as i32)
+            .bind(outcome.crossovers as i32)
+            .bind(outcome.crossbreeds as i32)
+            .bind(self.run_id)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(generation as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::core::discovery_engine::Condition;
+    use crate::core::fitness::Composite;
+
+    fn individual(hash: &str, fitness: f64) -> Individual {
+        let condition = |value| Condition { metric: "price_delta_5m".to_string(), operator: ">".to_string(), value, weight: 0.5 };
+        Individual {
+            hypothesis: Hypothesis {
+                hash: hash.to_string(),
+                entry_conditions: vec![condition(fitness * 10.0)],
+                exit_conditions: vec![condition(-fitness * 10.0)],
+                timeframe: 60,
+                created_at: 0,
+                symbol: None,
+            },
+            generation: 2,
+            parent_patterns: vec![],
+            window: PerformanceWindow::default(),
+            shortfall_bps: 0.0,
+            fitness,
+            protected: false,
+        }
+    }
+
+    #[test]
+    fn test_evolve_culls_and_breeds_the_elite() {
+        let population: Vec<Individual> = (1..=10).map(|i| individual(&format!("p{}", i), i as f64)).collect();
+        let outcome = evolve(&population, &EvolutionConfig::default(), &mut StdRng::seed_from_u64(7));
+
+        assert_eq!(outcome.survivors.len(), 5);
+        assert!(outcome.culled.contains(&"p1".to_string()));
+        assert_eq!(outcome.top_performer.as_deref(), Some("p10"));
+
+        // Two elites: three mutants and one crossover each
+        assert_eq!(outcome.offspring.len(), 8);
+        let child = &outcome.offspring[0];
+        assert_eq!(child.generation, 3);
+        assert_eq!(child.parent_patterns, vec!["p10".to_string()]);
+        assert_ne!(child.hypothesis.hash, "p10");
+        // Every mutant had at least one operator fire
+        assert!(outcome.operator_counts.total() >= outcome.mutations);
+
+        let cross = outcome.offspring.iter().find(|o| o.parent_patterns.len() == 2).unwrap();
+        assert_eq!(cross.hypothesis.entry_conditions[0].value, 100.0);
+        assert_eq!(cross.hypothesis.exit_conditions[0].value, -90.0);
+    }
+
+    #[test]
+    fn test_species_cap_keeps_weaker_distinct_ideas_alive() {
+        // Eight clones of one idea outrank two unrelated patterns
+        let mut population: Vec<Individual> = (3..=10).map(|i| individual(&format!("clone{}", i), i as f64)).collect();
+        for (hash, fitness) in [("rsi", 2.0), ("spread", 1.0)] {
+            let mut distinct = individual(hash, fitness);
+            distinct.hypothesis.entry_conditions[0].metric = format!("{}_1h", hash);
+            distinct.hypothesis.exit_conditions[0].metric = format!("{}_1h", hash);
+            population.push(distinct);
+        }
+
+        let config = EvolutionConfig { max_per_species: 3, ..EvolutionConfig::default() };
+        let outcome = evolve(&population, &config, &mut StdRng::seed_from_u64(7));
+
+        assert_eq!(outcome.species, 3);
+        assert_eq!(outcome.population, 10);
+        assert_eq!(outcome.max_fitness, 10.0);
+        assert_eq!(outcome.survivors, vec!["clone10", "clone9", "clone8", "rsi", "spread"]);
+        assert!(outcome.culled.contains(&"clone7".to_string()));
+    }
+
+    #[test]
+    fn test_hall_of_fame_members_are_never_culled() {
+        let mut population: Vec<Individual> = (1..=10).map(|i| individual(&format!("p{}", i), i as f64)).collect();
+        population[0].protected = true;
+
+        let outcome = evolve(&population, &EvolutionConfig::default(), &mut StdRng::seed_from_u64(7));
+
+        assert!(outcome.survivors.contains(&"p1".to_string()));
+        assert!(!outcome.culled.contains(&"p1".to_string()));
+        assert_eq!(outcome.elite, vec!["p10", "p9"]);
+        assert!(outcome.offspring.iter().all(|o| !o.parent_patterns.contains(&"p1".to_string())));
+    }
+
+    #[test]
+    fn test_fitness_rewards_consistency_and_penalizes_shortfall() {
+        let window = PerformanceWindow {
+            window_days: 30, trades: 120, hit_rate: 0.65, total_pnl: 50.0, sharpe: 2.0, max_drawdown: 0.1,
+            ..Default::default()
+        };
+        let weak = PerformanceWindow { hit_rate: 0.4, sharpe: 0.2, ..window.clone() };
+
+        assert!(fitness(&Composite, &window, 0.0) > fitness(&Composite, &weak, 0.0));
+        assert!(fitness(&Composite, &window, 20.0) < fitness(&Composite, &window, 0.0));
+        assert_eq!(fitness(&Composite, &PerformanceWindow::default(), 0.0), 0.0);
+
+        let losing = PerformanceWindow { sharpe: -1.0, ..window.clone() };
+        assert!(fitness(&crate::core::fitness::Sharpe, &losing, 20.0) < -1.0);
+    }
+}
@@ -0,0 +1,342 @@
+// Exchange Abstraction
+// Every venue the execution engine trades on implements `Exchange`
+// The paper exchange fills against the live market data feed, so paper and live share one path
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use async_trait::async_trait;
+use serde::{Serialize, Deserialize};
+
+use crate::core::market_data::TickStream;
+use crate::core::money::{self, Decimal};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Side::Buy => "buy",
+            Side::Sell => "sell",
+        }
+    }
+
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OrderType {
+    Market,
+    Limit(Decimal),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderRequest {
+    pub client_order_id: String,
+    pub symbol: String,
+    pub side: Side,
+    pub size: Decimal,  // notional in quote currency
+    pub order_type: OrderType,
+    pub reduce_only: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VenueOrderState {
+    Open,
+    Filled,
+    Cancelled,
+    Rejected,
+}
+
+/// Cumulative view of one order as the venue sees it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderStatusReport {
+    pub exchange_order_id: String,
+    pub state: VenueOrderState,
+    pub filled_size: Decimal,
+    pub avg_fill_price: Decimal,
+    pub fees: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Quote {
+    pub bid: f64,
+    pub ask: f64,
+    pub bid_size: f64,  // notional resting at the best bid
+    pub ask_size: f64,  // notional resting at the best ask
+}
+
+impl Quote {
+    /// Best bid as money, where a buy limit joins and a long exits
+    pub fn bid_price(&self) -> Decimal {
+        money::from_f64(self.bid)
+    }
+
+    /// Best ask as money, where a sell limit joins and a short exits
+    pub fn ask_price(&self) -> Decimal {
+        money::from_f64(self.ask)
+    }
+
+    /// Notional available at the touch to an order on `side`, as money
+    pub fn depth_for(&self, side: Side) -> Decimal {
+        money::from_f64(match side {
+            Side::Buy => self.ask_size,
+            Side::Sell => self.bid_size,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum ExchangeError {
+    Rejected(String),      // the venue refused the order
+    Unavailable(String),   // venue or data not reachable right now
+    UnknownOrder(String),
+}
+
+impl std::fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExchangeError::Rejected(e) => write!(f, "order rejected: {}", e),
+            ExchangeError::Unavailable(e) => write!(f, "exchange unavailable: {}", e),
+            ExchangeError::UnknownOrder(id) => write!(f, "unknown order {}", id),
+        }
+    }
+}
+
+impl std::error::Error for ExchangeError {}
+
+impl ExchangeError {
+    /// Worth trying again: the venue may simply not have answered
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ExchangeError::Unavailable(_))
+    }
+}
+
+#[async_trait]
+pub trait Exchange: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn quote(&self, symbol: &str) -> Result<Quote, ExchangeError>;
+
+    /// Submitting twice with the same client order id must not create a second order
+    async fn submit_order(&self, order: &OrderRequest) -> Result<OrderStatusReport, ExchangeError>;
+
+    /// Look an order up by our client order id; how a submit that timed out is checked before resending
+    async fn find_order(&self, client_order_id: &str) -> Result<Option<OrderStatusReport>, ExchangeError>;
+
+    async fn cancel_order(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError>;
+
+    async fn order_status(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError>;
+
+    /// Units held per asset (quote currency included), as the venue sees the account
+    async fn balances(&self) -> Result<HashMap<String, Decimal>, ExchangeError>;
+}
+
+/// "DOGE-USD" → ("DOGE", "USD")
+pub fn split_symbol(symbol: &str) -> (&str, &str) {
+    symbol.split_once('-').unwrap_or((symbol, "USD"))
+}
+
+struct PaperOrder {
+    request: OrderRequest,
+    report: OrderStatusReport,
+}
+
+/// Simulated venue: market orders fill at the last tick, limit orders rest until the price crosses
+pub struct PaperExchange {
+    name: String,
+    fee_rate: Decimal,     // charged on filled notional
+    half_spread_pct: f64,  // synthetic spread around the last tick
+    liquidity_per_tick: Option<Decimal>,  // notional a resting order can take per crossing tick
+    top_of_book: f64,                 // notional quoted on each side of the touch
+    balances: Mutex<HashMap<String, Decimal>>,
+    last_prices: Mutex<HashMap<String, f64>>,
+    orders: Mutex<HashMap<String, PaperOrder>>,
+    next_id: AtomicU64,
+}
+
+impl PaperExchange {
+    pub fn new(name: &str, fee_rate: f64) -> Self {
+        PaperExchange {
+            name: name.to_string(),
+            fee_rate: money::from_f64(fee_rate),
+            half_spread_pct: 0.0005,
+            liquidity_per_tick: None,
+            top_of_book: 25_000.0,
+            balances: Mutex::new(HashMap::new()),
+            last_prices: Mutex::new(HashMap::new()),
+            orders: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Cap how much a resting limit order fills per crossing tick, so partial fills happen
+    pub fn with_liquidity_per_tick(mut self, size: Decimal) -> Self {
+        self.liquidity_per_tick = Some(size);
+        self
+    }
+
+    /// Notional shown at the touch on each side, which decides when orders get sliced
+    pub fn with_top_of_book_depth(mut self, size: f64) -> Self {
+        self.top_of_book = size;
+        self
+    }
+
+    /// Fund the simulated account
+    pub fn with_balance(self, asset: &str, units: Decimal) -> Self {
+        self.balances.lock().unwrap().insert(asset.to_string(), units);
+        self
+    }
+
+    /// Feed a price, filling any resting limit order it crosses
+    pub fn update_price(&self, symbol: &str, price: f64) {
+        self.last_prices.lock().unwrap().insert(symbol.to_string(), price);
+
+        for order in self.orders.lock().unwrap().values_mut() {
+            if order.report.state != VenueOrderState::Open || order.request.symbol != symbol {
+                continue;
+            }
+            if let OrderType::Limit(limit) = order.request.order_type {
+                let crossed = match order.request.side {
+                    Side::Buy => money::from_f64(price) <= limit,
+                    Side::Sell => money::from_f64(price) >= limit,
+                };
+                if crossed {
+                    let remaining = order.request.size - order.report.filled_size;
+                    let size = self.liquidity_per_tick.map_or(remaining, |l| l.min(remaining));
+                    self.fill(order, limit, size);
+                }
+            }
+        }
+    }
+
+    /// Follow a market data subscription (ticks from any venue) until it closes
+    pub async fn track(self: Arc<Self>, mut ticks: TickStream) {
+        while let Some(tick) = ticks.recv().await {
+            self.update_price(&tick.symbol, tick.price);
+        }
+    }
+
+    fn fill(&self, order: &mut PaperOrder, price: Decimal, size: Decimal) {
+        let fee = size * self.fee_rate;
+        let report = &mut order.report;
+        let filled = report.filled_size + size;
+
+        report.avg_fill_price = (report.avg_fill_price * report.filled_size + price * size) / filled;
+        report.filled_size = filled;
+        report.fees += fee;
+        if filled >= order.request.size {
+            report.state = VenueOrderState::Filled;
+        }
+
+        let (base, quote) = split_symbol(&order.request.symbol);
+        let signed = match order.request.side {
+            Side::Buy => size,
+            Side::Sell => -size,
+        };
+        let mut balances = self.balances.lock().unwrap();
+        *balances.entry(base.to_string()).or_default() += signed / price;
+        *balances.entry(quote.to_string()).or_default() -= signed + fee;
+    }
+}
+
+#[async_trait]
+impl Exchange for PaperExchange {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<Quote, ExchangeError> {
+        let last = self.last_prices.lock().unwrap().get(symbol).copied()
+            .ok_or_else(|| ExchangeError::Unavailable(format!("no price yet for {}", symbol)))?;
+        Ok(Quote {
+            bid: last * (1.0 - self.half_spread_pct),
+            ask: last * (1.0 + self.half_spread_pct),
+            bid_size: self.top_of_book,
+            ask_size: self.top_of_book,
+        })
+    }
+
+    async fn submit_order(&self, order: &OrderRequest) -> Result<OrderStatusReport, ExchangeError> {
+        if order.size <= Decimal::ZERO {
+            return Err(ExchangeError::Rejected(format!("size {} must be positive", order.size)));
+        }
+        if let Some(existing) = self.find_order(&order.client_order_id).await? {
+            return Ok(existing);
+        }
+        let quote = self.quote(&order.symbol).await?;
+
+        let mut paper = PaperOrder {
+            request: order.clone(),
+            report: OrderStatusReport {
+                exchange_order_id: format!("{}-{}", self.name, self.next_id.fetch_add(1, Ordering::SeqCst)),
+                state: VenueOrderState::Open,
+                filled_size: Decimal::ZERO,
+                avg_fill_price: Decimal::ZERO,
+                fees: Decimal::ZERO,
+            },
+        };
+
+        match order.order_type {
+            OrderType::Market => {
+                let price = match order.side {
+                    Side::Buy => quote.ask,
+                    Side::Sell => quote.bid,
+                };
+                let price = money::from_f64(price);
+                self.fill(&mut paper, price, order.size);
+            }
+            OrderType::Limit(limit) => {
+                let marketable = match order.side {
+                    Side::Buy => limit >= money::from_f64(quote.ask),
+                    Side::Sell => limit <= money::from_f64(quote.bid),
+                };
+                if marketable {
+                    self.fill(&mut paper, limit, order.size);
+                }
+            }
+        }
+
+        let report = paper.report.clone();
+        self.orders.lock().unwrap().insert(report.exchange_order_id.clone(), paper);
+        Ok(report)
+    }
+
+    async fn find_order(&self, client_order_id: &str) -> Result<Option<OrderStatusReport>, ExchangeError> {
+        Ok(self.orders.lock().unwrap()
+            .values()
+            .find(|order| order.request.client_order_id == client_order_id)
+            .map(|order| order.report.clone()))
+    }
+
+    async fn cancel_order(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError> {
+        let mut orders = self.orders.lock().unwrap();
+        let order = orders.get_mut(exchange_order_id)
+            .ok_or_else(|| ExchangeError::UnknownOrder(exchange_order_id.to_string()))?;
+        if order.report.state == VenueOrderState::Open {
+            order.report.state = VenueOrderState::Cancelled;
+        }
+        Ok(order.report.clone())
+    }
+
+    async fn order_status(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError> {
+        self.orders.lock().unwrap()
+            .get(exchange_order_id)
+            .map(|order| order.report.clone())
+            .ok_or_else(|| ExchangeError::UnknownOrder(exchange_order_id.to_string()))
+    }
+
+    async fn balances(&self) -> Result<HashMap<String, Decimal>, ExchangeError> {
+        Ok(self.balances.lock().unwrap().clone())
+    }
+}
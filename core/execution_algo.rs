@@ -0,0 +1,143 @@
+// Execution Algorithms - TWAP Slicing and Iceberg Orders
+// Entries larger than the venue shows at the touch are split into child orders
+// instead of sweeping the book: TWAP sends equal market slices on a timer,
+// iceberg keeps one limit slice resting at the touch and refills it when it is done.
+// Every child after the first fills into the first child's position.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal_macros::dec;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::core::exchange::{OrderRequest, OrderType, Quote, Side};
+use crate::core::execution_engine::OrderIntent;
+use crate::core::execution_style::ExecutionStyle;
+use crate::core::money::{Decimal, MONEY_DP};
+
+// Never take more than this share of the displayed touch in one child order
+const DEPTH_SHARE: Decimal = dec!(0.5);
+const MIN_TWAP_SLICES: u32 = 2;
+const MAX_TWAP_SLICES: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionAlgo {
+    Direct,
+    Twap { slices: u32, interval_secs: i64 },
+    Iceberg { display_size: Decimal },
+}
+
+impl ExecutionAlgo {
+    /// Pick how to work an entry of `size` given the notional shown at the touch
+    /// Small orders go direct; a pattern bound to TWAP is always sliced
+    pub fn route(style: ExecutionStyle, size: Decimal, depth: Decimal, twap_interval: Duration) -> Self {
+        let child_cap = (depth * DEPTH_SHARE).round_dp(MONEY_DP).max(Decimal::new(1, MONEY_DP));
+        let slices_needed = (size / child_cap).ceil().to_u32().unwrap_or(MAX_TWAP_SLICES).min(MAX_TWAP_SLICES);
+
+        match style {
+            ExecutionStyle::Twap => ExecutionAlgo::Twap {
+                slices: slices_needed.max(MIN_TWAP_SLICES),
+                interval_secs: twap_interval.num_seconds(),
+            },
+            _ if size <= child_cap => ExecutionAlgo::Direct,
+            ExecutionStyle::PassiveLimit => ExecutionAlgo::Iceberg { display_size: child_cap },
+            ExecutionStyle::Market => ExecutionAlgo::Twap {
+                slices: slices_needed.max(MIN_TWAP_SLICES),
+                interval_secs: twap_interval.num_seconds(),
+            },
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionAlgo::Direct => "direct",
+            ExecutionAlgo::Twap { .. } => "twap",
+            ExecutionAlgo::Iceberg { .. } => "iceberg",
+        }
+    }
+}
+
+/// One sliced entry being worked; lives in memory only, so a restart abandons
+/// the unsent remainder while the filled slices survive as the position
+#[derive(Debug, Clone)]
+pub struct AlgoRun {
+    pub algo: ExecutionAlgo,
+    pub exchange: String,
+    pub intent: OrderIntent,
+    pub parent: OrderRequest,           // the full order being worked
+    pub root_order_id: Option<String>,  // first child; later children fill into its position
+    pub unsent: Decimal,
+    pub next_slice_at: DateTime<Utc>,
+}
+
+impl AlgoRun {
+    pub fn new(algo: ExecutionAlgo, exchange: &str, intent: OrderIntent, parent: OrderRequest) -> Self {
+        AlgoRun {
+            algo,
+            exchange: exchange.to_string(),
+            intent,
+            unsent: parent.size,
+            parent,
+            root_order_id: None,
+            next_slice_at: Utc::now(),
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.unsent <= Decimal::ZERO
+    }
+
+    /// Whether the next child may go out; icebergs wait until no child is working
+    pub fn is_due(&self, now: DateTime<Utc>, child_working: bool) -> bool {
+        match self.algo {
+            ExecutionAlgo::Iceberg { .. } => !child_working,
+            _ => now >= self.next_slice_at,
+        }
+    }
+
+    /// Build the next child order and account for it as sent
+    pub fn next_child(&mut self, quote: &Quote, now: DateTime<Utc>) -> OrderRequest {
+        let (size, order_type) = match self.algo {
+            ExecutionAlgo::Direct => (self.unsent, self.parent.order_type),
+            ExecutionAlgo::Twap { slices, interval_secs } => {
+                self.next_slice_at = now + Duration::seconds(interval_secs);
+                ((self.parent.size / Decimal::from(slices)).round_dp(MONEY_DP), OrderType::Market)
+            }
+            ExecutionAlgo::Iceberg { display_size } => {
+                let touch = if self.parent.side == Side::Buy { quote.bid_price() } else { quote.ask_price() };
+                (display_size, OrderType::Limit(touch))
+            }
+        };
+
+        // Fold a sliver left by rounding into the final child
+        let size = if self.unsent - size < self.parent.size / Decimal::from(1_000_000) { self.unsent } else { size.min(self.unsent) };
+        self.unsent -= size;
+
+        OrderRequest {
+            client_order_id: Uuid::new_v4().to_string(),
+            size,
+            order_type,
+            ..self.parent.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes_by_size_against_depth() {
+        let interval = Duration::seconds(30);
+
+        assert_eq!(ExecutionAlgo::route(ExecutionStyle::Market, dec!(100), dec!(1000), interval), ExecutionAlgo::Direct);
+        assert_eq!(ExecutionAlgo::route(ExecutionStyle::Market, dec!(100), dec!(40), interval),
+                   ExecutionAlgo::Twap { slices: 5, interval_secs: 30 });
+        assert_eq!(ExecutionAlgo::route(ExecutionStyle::PassiveLimit, dec!(100), dec!(40), interval),
+                   ExecutionAlgo::Iceberg { display_size: dec!(20) });
+        // A TWAP-bound pattern is sliced even when the book could take it whole
+        assert_eq!(ExecutionAlgo::route(ExecutionStyle::Twap, dec!(100), dec!(1000), interval),
+                   ExecutionAlgo::Twap { slices: 2, interval_secs: 30 });
+    }
+}
@@ -0,0 +1,118 @@
+// Execution Context - Live, Paper or Dry Run, Decided Once Per Process
+// The context is settled at startup and is the only way to hand the execution engine an
+// exchange: the engine takes a Venue, and only a context can make one. A paper venue is
+// allowed anywhere; a venue backed by a real API is refused unless the context is Live, so a
+// `--dry-run` process has no code path to a real order endpoint. A dry run is also recorded
+// as such in its run manifest, and every row the run writes carries that run_id
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::core::autoscale::{MeteredExchange, RequestMeter};
+use crate::core::chaos::{ChaosExchange, FaultInjector};
+use crate::core::exchange::{Exchange, PaperExchange};
+use crate::core::run_manifest::RunManifest;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionContext {
+    Live,                      // real venues, where adapters exist
+    Paper,                     // ENABLE_PAPER_TRADING: simulated fills, a normal run otherwise
+    DryRun { run_id: Uuid },   // --dry-run: simulated fills only, whatever else is configured
+}
+
+impl ExecutionContext {
+    /// `--dry-run` (or DRY_RUN=true) wins over the manifest's paper or live mode
+    pub fn for_run(dry_run: bool, manifest: &RunManifest) -> Self {
+        if dry_run {
+            ExecutionContext::DryRun { run_id: manifest.run_id }
+        } else if manifest.trading_mode == "paper" {
+            ExecutionContext::Paper
+        } else {
+            ExecutionContext::Live
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionContext::Live => "live",
+            ExecutionContext::Paper => "paper",
+            ExecutionContext::DryRun { .. } => "dry_run",
+        }
+    }
+
+    pub fn is_live(&self) -> bool {
+        *self == ExecutionContext::Live
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        matches!(self, ExecutionContext::DryRun { .. })
+    }
+
+    /// A simulated venue; every context may trade on one
+    pub fn paper(&self, exchange: Arc<PaperExchange>) -> Venue {
+        Venue { exchange, simulated: true }
+    }
+
+    /// A venue backed by a real API, only in a live context
+    pub fn live(&self, exchange: Arc<dyn Exchange>) -> Result<Venue, String> {
+        match self {
+            ExecutionContext::Live => Ok(Venue { exchange, simulated: false }),
+            other => Err(format!("{} is a real venue and this is a {} run", exchange.name(), other.as_str())),
+        }
+    }
+}
+
+/// An exchange the execution engine may send orders to; made only by an ExecutionContext
+#[derive(Clone)]
+pub struct Venue {
+    exchange: Arc<dyn Exchange>,
+    simulated: bool,
+}
+
+impl Venue {
+    pub fn name(&self) -> &str {
+        self.exchange.name()
+    }
+
+    pub fn is_simulated(&self) -> bool {
+        self.simulated
+    }
+
+    /// For read-only callers such as reconciliation
+    pub fn exchange(&self) -> Arc<dyn Exchange> {
+        self.exchange.clone()
+    }
+
+    /// The same venue failing as `faults` decides; a real venue is never touched
+    pub fn with_chaos(self, faults: &FaultInjector) -> Venue {
+        if !self.simulated || !faults.is_enabled() {
+            return self;
+        }
+        Venue { exchange: Arc::new(ChaosExchange::new(self.exchange, faults.clone())), simulated: true }
+    }
+
+    /// The same venue with every call counted against its rate limit
+    pub fn metered(self, meter: &RequestMeter) -> Venue {
+        Venue { exchange: Arc::new(MeteredExchange::new(self.exchange, meter.clone())), simulated: self.simulated }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dry_run_only_hands_out_paper_venues() {
+        let mut manifest = RunManifest::collect(serde_json::json!({}));
+        manifest.trading_mode = "live".to_string();
+        let dry_run = ExecutionContext::for_run(true, &manifest);
+        assert_eq!(dry_run, ExecutionContext::DryRun { run_id: manifest.run_id });
+        assert_eq!(ExecutionContext::for_run(false, &manifest), ExecutionContext::Live);
+
+        let paper = Arc::new(PaperExchange::new("coinbase", 0.006));
+        assert!(dry_run.paper(paper.clone()).is_simulated());
+        assert!(matches!(dry_run.live(paper.clone()), Err(e) if e.contains("dry_run")));
+        assert!(ExecutionContext::Paper.live(paper.clone()).is_err());
+        assert!(!ExecutionContext::Live.live(paper).unwrap().is_simulated());
+    }
+}
@@ -21,6 +21,7 @@ type Pattern struct {
     SharpeRatio    float64         `json:"sharpe_ratio"`
     IsActive       bool            `json:"is_active"`
     LastTriggered  time.Time       `json:"last_triggered"`
+    ExecutionStyle string          `json:"execution_style"` // market, passive_limit, twap
 }
 
 type Condition struct {
@@ -152,6 +153,7 @@ func (e *ExecutionEngine) executePattern(ctx context.Context, p *Pattern, wg *sy
         PatternHash: p.Hash,
         Side:        "buy", // Determined by pattern
         Size:        positionSize,
+        Style:       p.ExecutionStyle,
         Timestamp:   time.Now(),
     }
     
@@ -389,6 +391,7 @@ type Order struct {
     PatternHash string
     Side        string
     Size        float64
+    Style       string // execution style bound to the pattern; empty means market
     Timestamp   time.Time
 }
 
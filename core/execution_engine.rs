@@ -0,0 +1,1297 @@
+// Execution Engine - Turns Order Intents Into Exchange Orders
+// Consumes the order queue, re-checks every intent with the risk manager, routes it
+// to its venue and tracks it through the order manager until the venue is done with it.
+// Fills, not requests, are what the risk manager records as positions: each fill is applied
+// as it arrives, and resting limit orders can be re-priced after a timeout.
+// Entries too large for the touch are worked as TWAP or iceberg children (see execution_algo).
+// With a dead-man's switch attached, silence from the risk tasks halts new entries; exits still go out.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use serde::{Serialize, Deserialize};
+use chrono::{Duration, Utc};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+use tracing::{info, warn, error, info_span, Instrument, Span};
+
+use crate::core::alerts::{Alert, Severity};
+use crate::core::anomaly::{self, Event};
+use crate::core::audit::{AuditAction, AuditEntry, AuditLogger, Component};
+use crate::core::error::Error;
+use crate::core::execution_algo::{AlgoRun, ExecutionAlgo};
+use crate::core::execution_context::Venue;
+use crate::core::execution_quality::{Decision, ExecutionQuality};
+use crate::core::exchange::{Exchange, ExchangeError, OrderRequest, OrderStatusReport, OrderType, Quote, Side};
+use crate::core::execution_style::ExecutionStyle;
+use crate::core::feature_flags::{FeatureFlags, Flag};
+use crate::core::heartbeat::Heartbeat;
+use crate::core::latency::{self, Stage};
+use crate::core::ledger::LedgerWrite;
+use crate::core::live_feed::{LiveEvent, TradeEvent, TradeKind};
+use crate::core::money::{Decimal, MONEY_DP};
+use crate::core::order_manager::{FillDelta, ManagedOrder, OrderError, OrderManager, OrderState};
+use crate::core::reconciliation::Reconciler;
+use crate::core::recovery::{self, RecoveryReport};
+use crate::core::review_queue::{LiveTradeJournal, ReviewQueue};
+use crate::core::risk_manager::{Position, RiskManager, WorkingEntry};
+use crate::core::shutdown::Shutdown;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OrderIntent {
+    /// Take new risk for a pattern; stops are set relative to the actual fill price
+    Open {
+        pattern_hash: String,
+        symbol: String,
+        exchange: String,
+        side: Side,
+        size: Decimal,
+        style: ExecutionStyle,
+        stop_loss_pct: Decimal,  // of the fill price
+        take_profit_pct: Decimal,
+        #[serde(default)]
+        decision: Option<Decision>,  // measured against for execution quality
+    },
+    /// Reduce or close an open position; never blocked by risk limits
+    Close {
+        position_id: String,
+        size: Decimal,
+        reason: String,
+        #[serde(default)]
+        decision: Option<Decision>,
+    },
+}
+
+#[derive(Debug)]
+pub enum ExecutionError {
+    RiskRejected(String),
+    Halted(String),
+    UnknownExchange(String),
+    UnknownPosition(String),
+    Exchange(ExchangeError),
+    Order(OrderError),
+}
+
+impl std::fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionError::RiskRejected(e) => write!(f, "risk manager refused: {}", e),
+            ExecutionError::Halted(e) => write!(f, "execution halted: {}", e),
+            ExecutionError::UnknownExchange(name) => write!(f, "no exchange registered as '{}'", name),
+            ExecutionError::UnknownPosition(id) => write!(f, "no open position {}", id),
+            ExecutionError::Exchange(e) => write!(f, "{}", e),
+            ExecutionError::Order(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExecutionError {}
+
+impl ExecutionError {
+    /// Reason code for the audit log
+    pub fn code(&self) -> &'static str {
+        match self {
+            ExecutionError::RiskRejected(_) => "risk_rejected",
+            ExecutionError::Halted(_) => "halted",
+            ExecutionError::UnknownExchange(_) => "unknown_exchange",
+            ExecutionError::UnknownPosition(_) => "unknown_position",
+            ExecutionError::Exchange(_) => "exchange_error",
+            ExecutionError::Order(_) => "order_error",
+        }
+    }
+}
+
+impl From<ExchangeError> for ExecutionError {
+    fn from(e: ExchangeError) -> Self {
+        ExecutionError::Exchange(e)
+    }
+}
+
+impl From<OrderError> for ExecutionError {
+    fn from(e: OrderError) -> Self {
+        ExecutionError::Order(e)
+    }
+}
+
+/// Outcome of a flatten-all
+#[derive(Debug, Clone, Default)]
+pub struct FlattenReport {
+    pub cancelled_orders: usize,
+    pub closed: Vec<String>,
+    pub stuck: Vec<String>,  // positions still open when the timeout ran out
+}
+
+/// Outcome of winding down for a shutdown
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownReport {
+    pub cancelled_orders: usize,
+    pub orphaned: Vec<String>,  // client order ids still working on a venue
+}
+
+// Residuals smaller than this are left unfilled rather than re-placed
+const MIN_RESIDUAL: Decimal = Decimal::ONE;
+
+/// How hard to try when a venue does not answer a submit
+/// The client order id is the idempotency key: before every resend the venue is asked
+/// whether it already has the order, so a timeout can never place it twice
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub attempt_timeout: std::time::Duration,
+    pub initial_backoff: std::time::Duration,
+    pub max_backoff: std::time::Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 4,
+            attempt_timeout: std::time::Duration::from_secs(10),
+            initial_backoff: std::time::Duration::from_millis(250),
+            max_backoff: std::time::Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Exponential backoff before retry number `attempt` (1-based)
+    pub fn backoff(&self, attempt: u32) -> std::time::Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+pub struct ExecutionEngine {
+    pub poll_interval: std::time::Duration,
+    pub reprice_after: Option<Duration>,  // None leaves limit orders resting until they fill
+    pub max_reprices: u32,
+    pub retry: RetryPolicy,
+    pub twap_interval: Duration,
+    pub flatten_timeout: std::time::Duration,
+    pub flags: FeatureFlags,  // execution_algos, by symbol
+    risk_manager: Arc<RiskManager>,
+    exchanges: HashMap<String, Arc<dyn Exchange>>,
+    orders: OrderManager,
+    algos: Vec<AlgoRun>,
+    heartbeat: Option<Arc<Heartbeat>>,
+    halted: Option<String>,  // why the dead-man's switch tripped
+    audit: Option<AuditLogger>,
+    shutdown: Option<Shutdown>,
+    reviews: Option<ReviewQueue>,
+}
+
+impl ExecutionEngine {
+    pub fn new(risk_manager: Arc<RiskManager>, orders: OrderManager) -> Self {
+        ExecutionEngine {
+            poll_interval: std::time::Duration::from_secs(1),
+            reprice_after: None,
+            max_reprices: 3,
+            retry: RetryPolicy::default(),
+            twap_interval: Duration::seconds(30),
+            flatten_timeout: std::time::Duration::from_secs(60),
+            flags: FeatureFlags::default(),
+            risk_manager,
+            exchanges: HashMap::new(),
+            orders,
+            algos: Vec::new(),
+            heartbeat: None,
+            halted: None,
+            audit: None,
+            shutdown: None,
+            reviews: None,
+        }
+    }
+
+    /// Trip the dead-man's switch whenever a source registered on `heartbeat` goes silent
+    pub fn set_heartbeat(&mut self, heartbeat: Arc<Heartbeat>) {
+        self.heartbeat = Some(heartbeat);
+    }
+
+    /// Record every placed and refused intent, and dead-man's switch changes
+    pub fn set_audit_logger(&mut self, audit: AuditLogger) {
+        self.audit = Some(audit);
+    }
+    
+    fn audit(&self, entry: AuditEntry) {
+        if let Some(audit) = &self.audit {
+            audit.record(entry);
+        }
+    }
+
+    /// Flag a random sample of fills for operator spot checks
+    pub fn set_review_queue(&mut self, reviews: ReviewQueue) {
+        self.reviews = Some(reviews);
+    }
+
+    /// Wind down and return from `run` once shutdown is triggered
+    pub fn set_shutdown(&mut self, shutdown: Shutdown) {
+        self.shutdown = Some(shutdown);
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted.is_some()
+    }
+
+    /// Orders only ever go to a Venue, which the run's ExecutionContext decides is allowed
+    pub fn add_exchange(&mut self, venue: Venue) {
+        self.exchanges.insert(venue.name().to_string(), venue.exchange());
+    }
+
+    pub fn working_orders(&self) -> usize {
+        self.orders.working().count()
+    }
+
+    /// Sliced entries that still have children to send
+    pub fn running_algos(&self) -> usize {
+        self.algos.len()
+    }
+
+    /// Rebuild what a previous process left behind; call once exchanges are added
+    /// Positions come back from the ledger first, so fills on unfinished orders (reconciled
+    /// with their venues) land on them; orders tagged orphaned at shutdown are cancelled, and
+    /// positions whose asset the venue no longer holds are closed out of the book
+    pub async fn recover(&mut self) -> Result<RecoveryReport, ExecutionError> {
+        let mut report = RecoveryReport::default();
+        if let Some(db_pool) = self.orders.db_pool() {
+            let positions = recovery::load_open_positions(db_pool).await.map_err(OrderError::from)?;
+            report.restored_positions = positions.len();
+            for (position_id, position) in positions {
+                self.risk_manager.record_position_opened(&position_id, position);
+            }
+        }
+
+        let reconciled = self.orders.recover(&self.exchanges).await?;
+        for (order, fill) in &reconciled {
+            self.settle(order, *fill).await;
+        }
+        report.orders_reconciled = reconciled.len();
+
+        let orphans = self.orders.orphaned().await?;
+        report.orphans_cancelled = self.cancel_orders(&orphans).await;
+        report.orphans_left = orphans.into_iter()
+            .filter(|id| self.orders.get(id).is_some_and(|o| !o.state.is_terminal()))
+            .collect();
+
+        let reconciler = Reconciler::new(self.risk_manager.clone(), self.exchanges.values().cloned().collect());
+        let mut divergences = reconciler.reconcile().await?.divergences;
+        report.positions_closed = recovery::vanished(&self.risk_manager.open_positions_snapshot(), &divergences);
+        if !report.positions_closed.is_empty() {
+            for position_id in &report.positions_closed {
+                self.risk_manager.record_position_closed(position_id);
+            }
+            divergences = reconciler.reconcile().await?.divergences;
+        }
+        report.divergences = divergences;
+
+        report.log();
+        self.audit(AuditEntry::new(Component::Execution, AuditAction::Resumed, "system", "recovery")
+            .with_detail(serde_json::to_value(&report).unwrap_or_default()));
+        Ok(report)
+    }
+
+    /// Consume intents until every sender is dropped, polling working orders in between
+    pub async fn run(mut self, mut queue: mpsc::Receiver<OrderIntent>) {
+        let mut poll = tokio::time::interval(self.poll_interval);
+        let risk_manager = self.risk_manager.clone();
+        let shutdown = self.shutdown.clone();
+        let shutdown_requested = async {
+            match &shutdown {
+                Some(shutdown) => shutdown.requested().await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::pin!(shutdown_requested);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_requested => {
+                    self.wind_down().await;
+                    return;
+                },
+                _ = risk_manager.flatten_requested() => {
+                    let timeout = self.flatten_timeout;
+                    self.flatten_all(timeout).await;
+                },
+                intent = queue.recv() => match intent {
+                    Some(intent) => {
+                        // A refusal is routine, a venue that didn't answer may next time
+                        if let Err(e) = self.submit(intent).await {
+                            match Error::from(e) {
+                                e @ Error::RiskRejected(_) => info!("🛑 Order not placed: {}", e),
+                                e if e.is_retryable() => warn!("⚠️ Order not placed ({}): {}", e.code(), e),
+                                e => error!("❌ Order not placed ({}): {}", e.code(), e),
+                            }
+                        }
+                    }
+                    None => return,
+                },
+                _ = poll.tick() => {
+                    self.check_heartbeats().await;
+                    self.poll_working_orders().await;
+                },
+            }
+        }
+    }
+
+    /// Trip the dead-man's switch on silence, and release it once every source beats again
+    pub async fn check_heartbeats(&mut self) {
+        let Some(heartbeat) = &self.heartbeat else {
+            return;
+        };
+        let stale = heartbeat.stale_sources();
+
+        match (&self.halted, stale.is_empty()) {
+            (None, false) => {
+                let reason = stale.iter()
+                    .map(|(source, silence)| format!("{} silent {}s", source, silence.as_secs()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                error!("🚨 Dead-man's switch tripped ({}) - cancelling all orders", reason);
+                self.audit(AuditEntry::new(Component::Execution, AuditAction::Halted, "system", "dead_mans_switch")
+                    .with_detail(serde_json::json!({ "stale": reason })));
+                self.halted = Some(reason);
+                self.cancel_all().await;
+            }
+            (Some(reason), true) => {
+                info!("💓 Heartbeats resumed after halt ({}) - accepting orders again", reason);
+                self.audit(AuditEntry::new(Component::Execution, AuditAction::Resumed, "system", "dead_mans_switch")
+                    .with_detail(serde_json::json!({ "stale": reason })));
+                self.halted = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Place one intent; returns the client order id
+    /// New entries are refused while the dead-man's switch is tripped; exits still go through,
+    /// since getting flat is never blocked
+    pub async fn submit(&mut self, intent: OrderIntent) -> Result<String, ExecutionError> {
+        let subject = match &intent {
+            OrderIntent::Open { pattern_hash, .. } => pattern_hash.clone(),
+            OrderIntent::Close { position_id, .. } => position_id.clone(),
+        };
+        let detail = serde_json::to_value(&intent).unwrap_or_default();
+
+        let result = match (&self.halted, &intent) {
+            (Some(reason), OrderIntent::Open { .. }) => Err(ExecutionError::Halted(reason.clone())),
+            _ => {
+                let span = self.intent_span(&intent);
+                self.route(intent).instrument(span).await
+            }
+        };
+
+        let entry = match &result {
+            Ok(client_order_id) => AuditEntry::new(Component::Execution, AuditAction::Approved, &subject, "placed")
+                .with_detail(serde_json::json!({ "client_order_id": client_order_id, "intent": detail })),
+            Err(e) => AuditEntry::new(Component::Execution, AuditAction::Rejected, &subject, e.code())
+                .with_detail(serde_json::json!({ "error": e.to_string(), "intent": detail })),
+        };
+        self.audit(entry);
+
+        result
+    }
+
+    /// Span for everything done on an intent's behalf, risk updates included, keyed by the
+    /// hash of the pattern behind it
+    fn intent_span(&self, intent: &OrderIntent) -> Span {
+        match intent {
+            OrderIntent::Open { pattern_hash, symbol, .. } => info_span!("order", hash = %pattern_hash, symbol = %symbol),
+            OrderIntent::Close { position_id, .. } => {
+                let hash = self.open_position(position_id).map(|p| p.pattern_hash).unwrap_or_default();
+                info_span!("order", hash = %hash, position = %position_id)
+            }
+        }
+    }
+
+    /// Route an intent to its venue, directly or as an algo
+    async fn route(&mut self, intent: OrderIntent) -> Result<String, ExecutionError> {
+        let (exchange_name, request) = self.build_request(&intent).await?;
+        let client_order_id = request.client_order_id.clone();
+        let decided_at = match &intent {
+            OrderIntent::Open { decision, .. } | OrderIntent::Close { decision, .. } => decision.map(|d| d.at),
+        };
+
+        match &intent {
+            OrderIntent::Open { style, .. } => {
+                let quote = self.exchange(&exchange_name)?.quote(&request.symbol).await?;
+                let algo = ExecutionAlgo::route(*style, request.size, quote.depth_for(request.side), self.twap_interval);
+                if algo != ExecutionAlgo::Direct && self.flags.enabled_for(Flag::ExecutionAlgos, &request.symbol) {
+                    info!("🧩 Working ${:.2} {} as {} ({:?})", request.size, request.symbol, algo.as_str(), algo);
+                    let mut run = AlgoRun::new(algo, &exchange_name, intent, request);
+                    let first_child = self.send_slice(&mut run, &quote).await?;
+                    if let Some(at) = decided_at {
+                        latency::record_since(Stage::SignalToOrder, at, &run.parent.symbol);
+                    }
+                    if !run.is_done() {
+                        self.algos.push(run);
+                    }
+                    return Ok(first_child);
+                }
+            }
+            // An exit stops any entry still being worked into the same position
+            OrderIntent::Close { position_id, .. } => {
+                self.algos.retain(|run| run.root_order_id.as_deref() != Some(position_id.as_str()));
+            }
+        }
+
+        // Durable before it leaves the building, so a crash can never orphan it
+        self.orders.create(&exchange_name, request.clone(), intent).await?;
+        self.place(&exchange_name, &request).await?;
+        if let Some(at) = decided_at {
+            latency::record_since(Stage::SignalToOrder, at, &request.symbol);
+        }
+
+        Ok(client_order_id)
+    }
+
+    /// Send an order that is already recorded to its venue and apply any immediate fill
+    async fn place(&mut self, exchange_name: &str, request: &OrderRequest) -> Result<(), ExecutionError> {
+        let exchange = self.exchange(exchange_name)?;
+
+        let report = match self.submit_with_retry(exchange.as_ref(), request).await {
+            Ok(report) => report,
+            // Out of retries with no answer: the order stays pending and the next poll
+            // asks the venue whether it ever arrived
+            Err(e) if e.is_retryable() => return Err(e.into()),
+            Err(e) => {
+                self.orders.reject(&request.client_order_id, &e.to_string()).await?;
+                return Err(e.into());
+            }
+        };
+        info!("📤 {} {} ${:.2} {} on {} ({:?})",
+              request.side.as_str(), request.symbol, request.size,
+              request.client_order_id, exchange_name, report.state);
+
+        let (order, fill) = self.orders.apply_report(&request.client_order_id, &report).await?;
+        self.settle(&order, fill).await;
+        Ok(())
+    }
+
+    /// Cancel every working order and drop unsent algo slices; returns how many orders were cancelled
+    pub async fn cancel_all(&mut self) -> usize {
+        self.algos.clear();
+
+        let working: Vec<String> = self.orders.working().map(|o| o.client_order_id.clone()).collect();
+        let cancelled = self.cancel_orders(&working).await;
+        info!("🧹 Cancelled {} working orders", cancelled);
+        cancelled
+    }
+
+    /// Cancel the named orders that are still working; returns how many the venues confirmed
+    pub async fn cancel_orders(&mut self, client_order_ids: &[String]) -> usize {
+        let working: Vec<(String, String, Option<String>)> = self.orders.working()
+            .filter(|o| client_order_ids.contains(&o.client_order_id))
+            .map(|o| (o.client_order_id.clone(), o.exchange.clone(), o.exchange_order_id.clone()))
+            .collect();
+
+        let mut cancelled = 0;
+        for (id, exchange_name, exchange_order_id) in working {
+            // Unacknowledged orders are resolved by the next poll instead
+            let Some(exchange_order_id) = exchange_order_id else {
+                continue;
+            };
+            let report = match self.exchange(&exchange_name) {
+                Ok(exchange) => exchange.cancel_order(&exchange_order_id).await.map_err(ExecutionError::from),
+                Err(e) => Err(e),
+            };
+
+            match report {
+                Ok(report) => match self.orders.apply_report(&id, &report).await {
+                    Ok((order, fill)) => {
+                        if order.state == OrderState::Cancelled {
+                            cancelled += 1;
+                        }
+                        self.settle(&order, fill).await;
+                    }
+                    Err(e) => warn!("⚠️ {}", e),
+                },
+                Err(e) => error!("🚨 Could not cancel {}: {}", id, e),
+            }
+        }
+        cancelled
+    }
+
+    /// Stop for a shutdown: cancel every working order and tag whatever could not be cancelled
+    /// (unacknowledged, or the venue refused) as orphaned for the next start to sweep
+    /// Open positions are left to their stops, which the next start picks up again
+    pub async fn wind_down(&mut self) -> ShutdownReport {
+        info!("🛑 Execution winding down - cancelling working orders");
+        let cancelled_orders = self.cancel_all().await;
+        let orphaned = match self.orders.mark_orphaned().await {
+            Ok(orphaned) => orphaned,
+            Err(e) => {
+                error!("❌ Could not tag orphaned orders: {}", e);
+                self.orders.working().map(|o| o.client_order_id.clone()).collect()
+            }
+        };
+        if !orphaned.is_empty() {
+            warn!("⚠️ {} orders left working on the venues, tagged orphaned: {}", orphaned.len(), orphaned.join(", "));
+        }
+
+        let report = ShutdownReport { cancelled_orders, orphaned };
+        self.audit(AuditEntry::new(Component::Execution, AuditAction::Halted, "system", "shutdown")
+            .with_detail(serde_json::json!({ "cancelled_orders": report.cancelled_orders, "orphaned": report.orphaned })));
+        report
+    }
+
+    /// Cancel everything, then exit every open position at market and wait for the fills
+    /// Positions still open after `timeout` are reported as stuck and alerted on
+    pub async fn flatten_all(&mut self, timeout: std::time::Duration) -> FlattenReport {
+        let mut report = FlattenReport {
+            cancelled_orders: self.cancel_all().await,
+            ..Default::default()
+        };
+        let targets: Vec<String> = self.risk_manager.open_positions_snapshot()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            let open: Vec<(String, Decimal)> = self.risk_manager.open_positions_snapshot()
+                .into_iter()
+                .map(|(id, position)| (id, position.size))
+                .collect();
+            if open.is_empty() || tokio::time::Instant::now() >= deadline {
+                report.stuck = open.into_iter().map(|(id, _)| id).collect();
+                break;
+            }
+
+            // (Re-)send an exit for every position without one working
+            for (position_id, size) in open {
+                let exiting = self.orders.working().any(|o| {
+                    matches!(&o.intent, OrderIntent::Close { position_id: id, .. } if *id == position_id)
+                });
+                if exiting {
+                    continue;
+                }
+                let exit = OrderIntent::Close { position_id: position_id.clone(), size, reason: "flatten all".to_string(), decision: None };
+                // An explicit flatten goes through even while the dead-man's switch is tripped
+                if let Err(e) = self.route(exit).await {
+                    warn!("⚠️ Flatten exit for {} not placed: {}", position_id, e);
+                }
+            }
+
+            self.poll_working_orders().await;
+            if !self.risk_manager.open_positions_snapshot().is_empty() {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        }
+
+        report.closed = targets.into_iter().filter(|id| !report.stuck.contains(id)).collect();
+        if report.stuck.is_empty() {
+            info!("✅ Flattened {} positions", report.closed.len());
+        } else {
+            error!("🚨🚨 FLATTEN INCOMPLETE after {:?}: {} positions still open: {:?}",
+                   timeout, report.stuck.len(), report.stuck);
+            if let Some(alerts) = self.risk_manager.alerts() {
+                alerts.raise(Alert::new(Severity::Critical, "flatten_incomplete", "FLATTEN INCOMPLETE - positions still open")
+                    .with_body(format!("{} positions still open after {:?}: {}", report.stuck.len(), timeout, report.stuck.join(", "))));
+            }
+        }
+        report
+    }
+
+    /// Record and send an algo's next child
+    async fn send_slice(&mut self, run: &mut AlgoRun, quote: &Quote) -> Result<String, ExecutionError> {
+        let request = run.next_child(quote, Utc::now());
+        self.orders.create_child(run.root_order_id.as_deref(), &run.exchange, request.clone(), run.intent.clone()).await?;
+        run.root_order_id.get_or_insert_with(|| request.client_order_id.clone());
+
+        self.place(&run.exchange, &request).await?;
+        Ok(request.client_order_id)
+    }
+
+    /// Send the next child of every algo that is due
+    async fn advance_algos(&mut self) {
+        let now = Utc::now();
+        let mut running = Vec::new();
+
+        for mut run in std::mem::take(&mut self.algos) {
+            let child_working = run.root_order_id.as_deref()
+                .is_some_and(|root| self.orders.working().any(|o| o.position_id() == root));
+            if !run.is_due(now, child_working) {
+                running.push(run);
+                continue;
+            }
+
+            let quote = match self.exchange(&run.exchange) {
+                Ok(exchange) => exchange.quote(&run.parent.symbol).await.map_err(ExecutionError::from),
+                Err(e) => Err(e),
+            };
+            let quote = match quote {
+                Ok(quote) => quote,
+                Err(e) => {
+                    warn!("⚠️ No quote for next {} slice of {}: {}", run.algo.as_str(), run.parent.symbol, e);
+                    running.push(run);
+                    continue;
+                }
+            };
+
+            match self.send_slice(&mut run, &quote).await {
+                Ok(_) if !run.is_done() => running.push(run),
+                Ok(_) => {}
+                Err(e) => error!("❌ Abandoning ${:.2} unsent {} on {}: {}",
+                                 run.unsent, run.algo.as_str(), run.parent.symbol, e),
+            }
+        }
+
+        self.algos = running;
+    }
+
+    async fn submit_with_retry(&self, exchange: &dyn Exchange, request: &OrderRequest) -> Result<OrderStatusReport, ExchangeError> {
+        let mut attempt = 1;
+        loop {
+            let result = tokio::time::timeout(self.retry.attempt_timeout, exchange.submit_order(request)).await
+                .unwrap_or_else(|_| Err(ExchangeError::Unavailable("submit timed out".to_string())));
+
+            let error = match result {
+                Ok(report) => return Ok(report),
+                Err(e) if e.is_retryable() && attempt < self.retry.max_attempts => e,
+                Err(e) => return Err(e),
+            };
+
+            let backoff = self.retry.backoff(attempt);
+            warn!("⚠️ Submit of {} failed ({}), retrying in {:?}", request.client_order_id, error, backoff);
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+
+            // The venue may have taken it despite the error
+            if let Ok(Some(report)) = exchange.find_order(&request.client_order_id).await {
+                info!("🔁 {} already reached {}, not resending", request.client_order_id, exchange.name());
+                return Ok(report);
+            }
+        }
+    }
+
+    /// Ask each venue about orders still working, apply new fills and re-price stale limits
+    pub async fn poll_working_orders(&mut self) {
+        let working: Vec<(String, String, Option<String>)> = self.orders.working()
+            .map(|o| (o.client_order_id.clone(), o.exchange.clone(), o.exchange_order_id.clone()))
+            .collect();
+
+        for (id, exchange_name, exchange_order_id) in working {
+            let exchange = match self.exchange(&exchange_name) {
+                Ok(exchange) => exchange,
+                Err(e) => {
+                    error!("❌ {}", e);
+                    continue;
+                }
+            };
+
+            let report = match exchange_order_id {
+                Some(exchange_order_id) => exchange.order_status(&exchange_order_id).await,
+                // A submit that never got an answer: the venue either has it under our id or never saw it
+                None => match exchange.find_order(&id).await {
+                    Ok(Some(report)) => Ok(report),
+                    Ok(None) => {
+                        if let Err(e) = self.orders.reject(&id, "never reached the venue").await {
+                            warn!("⚠️ {}", e);
+                        }
+                        continue;
+                    }
+                    Err(e) => Err(e),
+                },
+            };
+
+            let report = match report {
+                Ok(report) => report,
+                Err(e) => {
+                    warn!("⚠️ Status check failed for {}: {}", id, e);
+                    continue;
+                }
+            };
+
+            let order = match self.orders.apply_report(&id, &report).await {
+                Ok((order, fill)) => {
+                    let span = self.intent_span(&order.intent);
+                    self.settle(&order, fill).instrument(span).await;
+                    order
+                }
+                Err(e) => {
+                    warn!("⚠️ {}", e);
+                    continue;
+                }
+            };
+
+            if self.due_for_reprice(&order) {
+                let span = self.intent_span(&order.intent);
+                if let Err(e) = self.reprice(order).instrument(span).await {
+                    warn!("⚠️ Re-price of {} failed: {}", id, e);
+                }
+            }
+        }
+
+        self.advance_algos().await;
+    }
+
+    fn due_for_reprice(&self, order: &ManagedOrder) -> bool {
+        let Some(reprice_after) = self.reprice_after else {
+            return false;
+        };
+        !order.state.is_terminal()
+            && matches!(order.request.order_type, OrderType::Limit(_))
+            && order.reprice_count < self.max_reprices
+            && Utc::now() - order.created_at >= reprice_after
+    }
+
+    /// Cancel a resting limit order and re-place its unfilled remainder at the current touch
+    async fn reprice(&mut self, order: ManagedOrder) -> Result<(), ExecutionError> {
+        let exchange = self.exchange(&order.exchange)?;
+        let Some(exchange_order_id) = &order.exchange_order_id else {
+            return Ok(());
+        };
+
+        let report = exchange.cancel_order(exchange_order_id).await?;
+        let (cancelled, fill) = self.orders.apply_report(&order.client_order_id, &report).await?;
+        self.settle(&cancelled, fill).await;
+
+        // It may have filled completely before the cancel landed
+        let remaining = cancelled.remaining();
+        if cancelled.state != OrderState::Cancelled || remaining < MIN_RESIDUAL {
+            return Ok(());
+        }
+
+        let quote = exchange.quote(&cancelled.request.symbol).await?;
+        let limit = if cancelled.request.side == Side::Buy { quote.bid_price() } else { quote.ask_price() };
+        let request = OrderRequest {
+            client_order_id: Uuid::new_v4().to_string(),
+            size: remaining,
+            order_type: OrderType::Limit(limit),
+            ..cancelled.request.clone()
+        };
+        info!("🔄 Re-pricing ${:.2} left on {} at {:.6} (attempt {})",
+              remaining, cancelled.position_id(), limit, cancelled.reprice_count + 1);
+
+        self.orders.create_reprice(&cancelled, request.clone()).await?;
+        self.place(&cancelled.exchange, &request).await
+    }
+
+    /// Entries not yet in the book: the unfilled part of working orders and algo slices still to send
+    fn working_entries(&self) -> Vec<WorkingEntry> {
+        let entry = |intent: &OrderIntent, notional: Decimal| match intent {
+            OrderIntent::Open { symbol, exchange, .. } => {
+                Some(WorkingEntry { symbol: symbol.clone(), exchange: exchange.clone(), notional })
+            }
+            OrderIntent::Close { .. } => None,
+        };
+        let unfilled = self.orders.working()
+            .filter_map(|o| entry(&o.intent, (o.request.size - o.filled_size).max(Decimal::ZERO)));
+        let unsent = self.algos.iter().filter_map(|run| entry(&run.intent, run.unsent));
+        unfilled.chain(unsent).collect()
+    }
+
+    fn exchange(&self, name: &str) -> Result<Arc<dyn Exchange>, ExecutionError> {
+        self.exchanges.get(name).cloned()
+            .ok_or_else(|| ExecutionError::UnknownExchange(name.to_string()))
+    }
+
+    async fn build_request(&self, intent: &OrderIntent) -> Result<(String, OrderRequest), ExecutionError> {
+        let client_order_id = Uuid::new_v4().to_string();
+
+        match intent {
+            OrderIntent::Open { pattern_hash, symbol, exchange, side, size, style, stop_loss_pct, .. } => {
+                // A re-sent intent for the same hypothesis must not spend its capital twice
+                let is_same_entry = |intent: &OrderIntent| {
+                    matches!(intent, OrderIntent::Open { pattern_hash: p, symbol: s, .. } if p == pattern_hash && s == symbol)
+                };
+                let already_entering = self.orders.working().any(|o| is_same_entry(&o.intent))
+                    || self.algos.iter().any(|run| is_same_entry(&run.intent));
+                if already_entering {
+                    return Err(ExecutionError::RiskRejected(format!("{} already has a working entry on {}", pattern_hash, symbol)));
+                }
+
+                if !self.risk_manager.approve_order(pattern_hash, symbol, exchange, *size, &self.working_entries()) {
+                    return Err(ExecutionError::RiskRejected(format!("{} ${:.2} for {}", symbol, size, pattern_hash)));
+                }
+                if !self.risk_manager.check_stop_distance(symbol, Decimal::ONE, Decimal::ONE - *stop_loss_pct) {
+                    return Err(ExecutionError::RiskRejected(format!("stop too tight on {}", symbol)));
+                }
+
+                let order_type = match style {
+                    // TWAP parents are sliced into market children by submit
+                    ExecutionStyle::Market | ExecutionStyle::Twap => OrderType::Market,
+                    ExecutionStyle::PassiveLimit => {
+                        let quote = self.exchange(exchange)?.quote(symbol).await?;
+                        OrderType::Limit(if *side == Side::Buy { quote.bid_price() } else { quote.ask_price() })
+                    }
+                };
+
+                Ok((exchange.clone(), OrderRequest {
+                    client_order_id,
+                    symbol: symbol.clone(),
+                    side: *side,
+                    size: *size,
+                    order_type,
+                    reduce_only: false,
+                }))
+            }
+            OrderIntent::Close { position_id, size, .. } => {
+                let already_closing = self.orders.working().any(|o| {
+                    matches!(&o.intent, OrderIntent::Close { position_id: id, .. } if id == position_id)
+                });
+                if already_closing {
+                    return Err(ExecutionError::RiskRejected(format!("{} already has a working exit", position_id)));
+                }
+
+                let position = self.open_position(position_id)?;
+                let size = (*size).min(position.size);
+                if !self.risk_manager.approve_reduction(position_id, size) {
+                    return Err(ExecutionError::RiskRejected(format!("reduction of {} by ${:.2}", position_id, size)));
+                }
+
+                // Same units as were bought, priced where the exit will fill
+                let quote = self.exchange(&position.exchange)?.quote(&position.symbol).await?;
+                let exit_price = if position.is_long() { quote.bid_price() } else { quote.ask_price() };
+
+                Ok((position.exchange.clone(), OrderRequest {
+                    client_order_id,
+                    symbol: position.symbol.clone(),
+                    side: if position.is_long() { Side::Sell } else { Side::Buy },
+                    size: (size * exit_price).checked_div(position.entry_price).unwrap_or(size).round_dp(MONEY_DP),
+                    order_type: OrderType::Market,
+                    reduce_only: true,
+                }))
+            }
+        }
+    }
+
+    fn open_position(&self, position_id: &str) -> Result<Position, ExecutionError> {
+        self.risk_manager.open_positions_snapshot()
+            .into_iter()
+            .find(|(id, _)| id == position_id)
+            .map(|(_, position)| position)
+            .ok_or_else(|| ExecutionError::UnknownPosition(position_id.to_string()))
+    }
+
+    /// Apply a new fill, if any, and note orders that ended without one
+    async fn settle(&self, order: &ManagedOrder, fill: Option<FillDelta>) {
+        // Measured before the fill is applied, while a closing order's position still exists
+        if order.state.is_terminal() {
+            self.record_quality(order).await;
+        }
+        if let Some(fill) = fill {
+            latency::record_since(Stage::OrderToFill, order.created_at, &order.request.symbol);
+            self.apply_fill(order, &fill);
+        }
+        if order.state.is_terminal() {
+            anomaly::note(if order.filled_size > Decimal::ZERO { Event::OrderFilled } else { Event::OrderUnfilled });
+        }
+        if order.state.is_terminal() && order.filled_size <= Decimal::ZERO {
+            warn!("🚫 {} finished {} with nothing filled ({}s after submit)",
+                  order.client_order_id, order.state.as_str(), (Utc::now() - order.created_at).num_seconds());
+        }
+    }
+
+    async fn record_quality(&self, order: &ManagedOrder) {
+        let (decision, pattern_hash) = match &order.intent {
+            OrderIntent::Open { decision, pattern_hash, .. } => (decision, Some(pattern_hash.clone())),
+            OrderIntent::Close { decision, position_id, .. } => {
+                (decision, self.open_position(position_id).ok().map(|p| p.pattern_hash))
+            }
+        };
+        let Some(quality) = decision.and_then(|d| ExecutionQuality::measure(order, &d, pattern_hash)) else {
+            return;
+        };
+
+        info!("⏱️ {} {} shortfall {:.1}bps (${:.4}), {}ms to submit",
+              quality.client_order_id, quality.symbol, quality.shortfall_bps, quality.shortfall,
+              quality.decision_to_submit_ms);
+        if let Some(db_pool) = self.orders.db_pool() {
+            if let Err(e) = quality.store(db_pool, self.orders.run_id).await {
+                warn!("⚠️ Failed to store execution quality for {}: {}", quality.client_order_id, e);
+            }
+        }
+    }
+
+    /// Report what just filled to the risk manager, so position sizes track fills rather than requests
+    fn apply_fill(&self, order: &ManagedOrder, fill: &FillDelta) {
+        let price = fill.price;
+        let capital = self.risk_manager.current_capital();
+
+        match &order.intent {
+            OrderIntent::Open { pattern_hash, symbol, exchange, side, stop_loss_pct, take_profit_pct, .. } => {
+                let position_id = order.position_id();
+                // Later fills add to the position; its stops stay where the first fill put them
+                if !self.risk_manager.add_to_position(position_id, fill.size, price) {
+                    let (stop_loss, take_profit) = match side {
+                        Side::Buy => (price * (Decimal::ONE - stop_loss_pct), price * (Decimal::ONE + take_profit_pct)),
+                        Side::Sell => (price * (Decimal::ONE + stop_loss_pct), price * (Decimal::ONE - take_profit_pct)),
+                    };
+
+                    self.risk_manager.record_position_opened(position_id, Position {
+                        pattern_hash: pattern_hash.clone(),
+                        symbol: symbol.clone(),
+                        exchange: exchange.clone(),
+                        size: fill.size,
+                        entry_price: price,
+                        entry_time: Utc::now(),
+                        stop_loss,
+                        take_profit,
+                    });
+                }
+                self.risk_manager.update_capital(capital - fill.fees);
+                self.risk_manager.record_account_pnl(exchange, -fill.fees);
+                info!("✅ Filled ${:.2} of {} {} @ {:.6}", fill.size, position_id, symbol, price);
+                self.publish_trade(order, TradeEvent {
+                    at: Utc::now(),
+                    kind: TradeKind::Entry,
+                    position_id: position_id.to_string(),
+                    pattern_hash: pattern_hash.clone(),
+                    symbol: symbol.clone(),
+                    exchange: exchange.clone(),
+                    size: fill.size,
+                    price,
+                    fees: fill.fees,
+                    pnl: None,
+                    reason: None,
+                    capital: capital - fill.fees,
+                });
+            }
+            OrderIntent::Close { position_id, reason, .. } => {
+                let Ok(position) = self.open_position(position_id) else {
+                    warn!("⚠️ Exit filled for {} but the position is already gone", position_id);
+                    return;
+                };
+
+                // Positions are sized in entry notional; the fill is in exit notional
+                let reduced = (fill.size * position.entry_price).checked_div(price).unwrap_or_default().round_dp(MONEY_DP);
+                let direction = if position.is_long() { Decimal::ONE } else { Decimal::NEGATIVE_ONE };
+                let pnl = direction * ((price - position.entry_price) * reduced).checked_div(position.entry_price).unwrap_or_default()
+                    - fill.fees;
+
+                self.risk_manager.reduce_position(position_id, reduced);
+                self.risk_manager.update_capital(capital + pnl);
+                self.risk_manager.record_account_pnl(&position.exchange, pnl);
+                info!("✅ Closed ${:.2} of {} @ {:.6} ({}) pnl ${:.2}",
+                      reduced, position_id, price, reason, pnl);
+                self.publish_trade(order, TradeEvent {
+                    at: Utc::now(),
+                    kind: TradeKind::Exit,
+                    position_id: position_id.clone(),
+                    pattern_hash: position.pattern_hash,
+                    symbol: position.symbol,
+                    exchange: position.exchange,
+                    size: fill.size,
+                    price,
+                    fees: fill.fees,
+                    pnl: Some(pnl),
+                    reason: Some(reason.to_string()),
+                    capital: capital + pnl,
+                });
+            }
+        }
+    }
+    
+    /// Record the fill in the ledger and stream it to dashboards and plugins
+    fn publish_trade(&self, order: &ManagedOrder, trade: TradeEvent) {
+        if let Some(ledger) = self.risk_manager.ledger() {
+            ledger.record(LedgerWrite::Fill { client_order_id: order.client_order_id.clone(), trade: trade.clone() });
+        }
+        if let Some(reviews) = self.reviews.as_ref().filter(|reviews| reviews.should_sample()) {
+            let journal = LiveTradeJournal {
+                pattern_hash: trade.pattern_hash.clone(),
+                client_order_id: order.client_order_id.clone(),
+                intent: order.intent.clone(),
+                position: self.open_position(&trade.position_id).ok(),
+                trade: trade.clone(),
+                recorded_at: Utc::now(),
+            };
+            let reviews = reviews.clone();
+            tokio::spawn(async move {
+                if let Err(e) = reviews.flag(&journal.pattern_hash, &journal).await {
+                    error!("❌ Failed to queue fill {} for review: {}", journal.client_order_id, e);
+                }
+            });
+        }
+        if let Some(feed) = self.risk_manager.live_feed() {
+            feed.publish(LiveEvent::Trade(trade));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use crate::core::exchange::PaperExchange;
+    use crate::core::execution_context::ExecutionContext;
+    use crate::risk::liquidity::{LiquidityProfile, LiquidityProfiles};
+
+    fn engine() -> (ExecutionEngine, Arc<RiskManager>, Arc<PaperExchange>) {
+        engine_with(PaperExchange::new("paper", 0.0))
+    }
+
+    fn engine_with(paper: PaperExchange) -> (ExecutionEngine, Arc<RiskManager>, Arc<PaperExchange>) {
+        let risk_manager = Arc::new(RiskManager::new(dec!(1000)));
+        let mut profiles = LiquidityProfiles::default();
+        profiles.insert(LiquidityProfile::flat("DOGE-USD"));
+        risk_manager.set_liquidity_profiles(profiles);
+
+        let paper = Arc::new(paper);
+        paper.update_price("DOGE-USD", 100.0);
+
+        let mut engine = ExecutionEngine::new(risk_manager.clone(), OrderManager::in_memory());
+        engine.add_exchange(ExecutionContext::Paper.paper(paper.clone()));
+        (engine, risk_manager, paper)
+    }
+
+    fn open(style: ExecutionStyle) -> OrderIntent {
+        OrderIntent::Open {
+            pattern_hash: "abc".to_string(),
+            symbol: "DOGE-USD".to_string(),
+            exchange: "paper".to_string(),
+            side: Side::Buy,
+            size: dec!(100),
+            style,
+            stop_loss_pct: dec!(0.05),
+            take_profit_pct: dec!(0.10),
+            decision: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fills_open_and_close_positions() {
+        let (mut engine, risk_manager, paper) = engine();
+
+        let id = engine.submit(open(ExecutionStyle::Market)).await.unwrap();
+        let (_, position) = risk_manager.open_positions_snapshot().pop().unwrap();
+        assert_eq!(position.size, dec!(100));
+        assert!(position.stop_loss < position.entry_price);
+
+        paper.update_price("DOGE-USD", 110.0);
+        let exit = OrderIntent::Close { position_id: id, size: dec!(100), reason: "take profit".to_string(), decision: None };
+        engine.submit(exit).await.unwrap();
+
+        assert!(risk_manager.open_positions_snapshot().is_empty());
+        assert!(risk_manager.current_capital() > dec!(1009));
+    }
+
+    #[tokio::test]
+    async fn test_passive_order_waits_for_the_market() {
+        let (mut engine, risk_manager, paper) = engine();
+
+        engine.submit(open(ExecutionStyle::PassiveLimit)).await.unwrap();
+        assert_eq!(engine.working_orders(), 1);
+        assert!(risk_manager.open_positions_snapshot().is_empty());
+
+        paper.update_price("DOGE-USD", 99.0);
+        engine.poll_working_orders().await;
+        assert_eq!(engine.working_orders(), 0);
+        assert_eq!(risk_manager.open_positions_snapshot().len(), 1);
+    }
+
+    /// Places the order but reports a timeout for the first `failures` submits
+    struct LostAcks {
+        inner: Arc<PaperExchange>,
+        failures: AtomicU32,
+    }
+
+    #[async_trait::async_trait]
+    impl Exchange for LostAcks {
+        fn name(&self) -> &str {
+            self.inner.name()
+        }
+
+        async fn quote(&self, symbol: &str) -> Result<Quote, ExchangeError> {
+            self.inner.quote(symbol).await
+        }
+
+        async fn submit_order(&self, order: &OrderRequest) -> Result<OrderStatusReport, ExchangeError> {
+            let report = self.inner.submit_order(order).await?;
+            if self.failures.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)).is_ok() {
+                return Err(ExchangeError::Unavailable("timed out".to_string()));
+            }
+            Ok(report)
+        }
+
+        async fn find_order(&self, client_order_id: &str) -> Result<Option<OrderStatusReport>, ExchangeError> {
+            self.inner.find_order(client_order_id).await
+        }
+
+        async fn cancel_order(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError> {
+            self.inner.cancel_order(exchange_order_id).await
+        }
+
+        async fn order_status(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError> {
+            self.inner.order_status(exchange_order_id).await
+        }
+
+        async fn balances(&self) -> Result<HashMap<String, Decimal>, ExchangeError> {
+            self.inner.balances().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lost_ack_is_not_submitted_twice() {
+        let (mut engine, risk_manager, paper) = engine();
+        engine.retry.initial_backoff = std::time::Duration::from_millis(1);
+        let flaky = ExecutionContext::Live.live(Arc::new(LostAcks { inner: paper, failures: AtomicU32::new(1) })).unwrap();
+        engine.add_exchange(flaky);
+
+        engine.submit(open(ExecutionStyle::Market)).await.unwrap();
+        let positions = risk_manager.open_positions_snapshot();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].1.size, dec!(100));
+
+        // The same hypothesis cannot enter again while its entry is still working
+        engine.submit(open(ExecutionStyle::PassiveLimit)).await.unwrap();
+        assert!(matches!(engine.submit(open(ExecutionStyle::PassiveLimit)).await,
+                         Err(ExecutionError::RiskRejected(_))));
+    }
+
+    #[tokio::test]
+    async fn test_resting_entries_count_toward_the_asset_cap() {
+        let (mut engine, _, _) = engine();
+
+        // Four resting entries of 100 fill the 40% DOGE cap before anything has filled
+        for i in 0..4 {
+            let mut entry = open(ExecutionStyle::PassiveLimit);
+            if let OrderIntent::Open { pattern_hash, .. } = &mut entry {
+                *pattern_hash = format!("p{i}");
+            }
+            engine.submit(entry).await.unwrap();
+        }
+        assert_eq!(engine.working_orders(), 4);
+
+        let mut fifth = open(ExecutionStyle::PassiveLimit);
+        if let OrderIntent::Open { pattern_hash, .. } = &mut fifth {
+            *pattern_hash = "p4".to_string();
+        }
+        assert!(matches!(engine.submit(fifth).await, Err(ExecutionError::RiskRejected(_))));
+    }
+
+    #[tokio::test]
+    async fn test_large_entries_are_sliced() {
+        let (mut engine, risk_manager, paper) = engine_with(PaperExchange::new("paper", 0.0).with_top_of_book_depth(40.0));
+        engine.twap_interval = Duration::zero();
+
+        // 100 against 40 shown: five market children of 20, all into one position
+        engine.submit(open(ExecutionStyle::Market)).await.unwrap();
+        assert_eq!(engine.running_algos(), 1);
+        for _ in 0..4 {
+            engine.poll_working_orders().await;
+        }
+        assert_eq!(engine.running_algos(), 0);
+        let positions = risk_manager.open_positions_snapshot();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].1.size, dec!(100));
+
+        // Iceberg: one resting child of 20 at a time, refilled once it is done
+        let exit = OrderIntent::Close { position_id: positions[0].0.clone(), size: dec!(100), reason: "test".to_string(), decision: None };
+        engine.submit(exit).await.unwrap();
+        engine.submit(open(ExecutionStyle::PassiveLimit)).await.unwrap();
+        assert_eq!(engine.working_orders(), 1);
+        paper.update_price("DOGE-USD", 99.0);
+        engine.poll_working_orders().await;
+        assert_eq!(engine.working_orders(), 1);
+        assert_eq!(risk_manager.open_positions_snapshot()[0].1.size, dec!(20));
+    }
+
+    #[tokio::test]
+    async fn test_flatten_all_cancels_and_exits() {
+        let (mut engine, risk_manager, paper) = engine();
+        engine.poll_interval = std::time::Duration::from_millis(1);
+
+        engine.submit(open(ExecutionStyle::Market)).await.unwrap();
+        let mut other = open(ExecutionStyle::PassiveLimit);
+        if let OrderIntent::Open { pattern_hash, .. } = &mut other {
+            *pattern_hash = "def".to_string();
+        }
+        engine.submit(other).await.unwrap();
+        assert_eq!(engine.working_orders(), 1);
+
+        paper.update_price("DOGE-USD", 110.0);
+        let report = engine.flatten_all(std::time::Duration::from_secs(5)).await;
+        assert_eq!(report.cancelled_orders, 1);
+        assert_eq!(report.closed.len(), 1);
+        assert!(report.stuck.is_empty());
+        assert!(risk_manager.open_positions_snapshot().is_empty());
+        assert_eq!(engine.working_orders(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_cancels_orders_and_leaves_positions() {
+        let (mut engine, risk_manager, _) = engine();
+        engine.submit(open(ExecutionStyle::Market)).await.unwrap();
+        let mut other = open(ExecutionStyle::PassiveLimit);
+        if let OrderIntent::Open { pattern_hash, .. } = &mut other {
+            *pattern_hash = "def".to_string();
+        }
+        engine.submit(other).await.unwrap();
+
+        let report = engine.wind_down().await;
+        assert_eq!(report.cancelled_orders, 1);
+        assert!(report.orphaned.is_empty());
+        assert_eq!(engine.working_orders(), 0);
+        assert_eq!(risk_manager.open_positions_snapshot().len(), 1);
+
+        // run() returns once shutdown is triggered, even with the intent queue still open
+        let shutdown = Shutdown::default();
+        engine.set_shutdown(shutdown.clone());
+        shutdown.trigger();
+        let (_intents, queue) = mpsc::channel(1);
+        tokio::time::timeout(std::time::Duration::from_secs(5), engine.run(queue)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dead_mans_switch_halts_and_resumes() {
+        let (mut engine, risk_manager, _) = engine();
+        let heartbeat = Arc::new(Heartbeat::new());
+        heartbeat.expect("risk", std::time::Duration::from_millis(20));
+        engine.set_heartbeat(heartbeat.clone());
+
+        engine.submit(open(ExecutionStyle::PassiveLimit)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(40)).await;
+        engine.check_heartbeats().await;
+        assert!(engine.is_halted());
+        assert_eq!(engine.working_orders(), 0);
+        assert!(matches!(engine.submit(open(ExecutionStyle::Market)).await, Err(ExecutionError::Halted(_))));
+
+        heartbeat.beat("risk");
+        engine.check_heartbeats().await;
+        engine.submit(open(ExecutionStyle::Market)).await.unwrap();
+        let (position_id, _) = risk_manager.open_positions_snapshot().pop().unwrap();
+
+        // Halted again, a stop-loss exit still gets the position flat
+        tokio::time::sleep(std::time::Duration::from_millis(40)).await;
+        engine.check_heartbeats().await;
+        assert!(engine.is_halted());
+        let exit = OrderIntent::Close { position_id, size: dec!(100), reason: "stop_loss".to_string(), decision: None };
+        engine.submit(exit).await.unwrap();
+        assert!(risk_manager.open_positions_snapshot().is_empty());
+
+        heartbeat.beat("risk");
+        engine.check_heartbeats().await;
+        assert!(!engine.is_halted());
+        engine.submit(open(ExecutionStyle::Market)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_partial_fill_sizes_position_and_reprices_residual() {
+        let (mut engine, risk_manager, paper) = engine_with(PaperExchange::new("paper", 0.0).with_liquidity_per_tick(dec!(40)));
+
+        engine.submit(open(ExecutionStyle::PassiveLimit)).await.unwrap();
+        paper.update_price("DOGE-USD", 99.0);
+        engine.poll_working_orders().await;
+
+        let (first_id, position) = risk_manager.open_positions_snapshot().pop().unwrap();
+        assert_eq!(position.size, dec!(40));
+        assert_eq!(engine.working_orders(), 1);
+
+        // Market moved away: the residual 60 is cancelled and re-placed at the new bid
+        engine.reprice_after = Some(Duration::zero());
+        paper.update_price("DOGE-USD", 101.0);
+        engine.poll_working_orders().await;
+        assert_eq!(engine.working_orders(), 1);
+
+        engine.reprice_after = None;
+        paper.update_price("DOGE-USD", 100.0);
+        engine.poll_working_orders().await;
+
+        let (id, position) = risk_manager.open_positions_snapshot().pop().unwrap();
+        assert_eq!(id, first_id);
+        assert_eq!(position.size, dec!(80));
+        assert!(position.entry_price > dec!(99.95) && position.entry_price < dec!(100.95));
+    }
+}
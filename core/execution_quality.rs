@@ -0,0 +1,183 @@
+// Execution Quality - Latency and Implementation Shortfall
+// Every order that fills is measured against the price and moment the decision was made:
+// how long it took to reach the venue, how long until it first filled, and how far the
+// average fill landed from the decision price. Patterns whose edge disappears once
+// these are paid show up here before the evolution engine breeds more of them.
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::core::exchange::Side;
+use crate::core::money::{self, Decimal};
+use crate::core::order_manager::ManagedOrder;
+
+/// The price and moment a signal decided to trade
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    pub price: f64,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionQuality {
+    pub client_order_id: String,
+    pub pattern_hash: Option<String>,
+    pub symbol: String,
+    pub side: Side,
+    pub decision_price: f64,
+    pub avg_fill_price: f64,
+    pub filled_size: f64,
+    pub decision_to_submit_ms: i64,
+    pub submit_to_first_fill_ms: Option<i64>,
+    pub shortfall_bps: f64,  // positive = paid more (buy) or received less (sell) than decided
+    pub shortfall: f64,      // same, in quote currency on the filled size
+    pub decided_at: DateTime<Utc>,
+}
+
+impl ExecutionQuality {
+    /// Measure a filled order against its decision; None if it never filled
+    pub fn measure(order: &ManagedOrder, decision: &Decision, pattern_hash: Option<String>) -> Option<Self> {
+        if order.filled_size <= Decimal::ZERO || decision.price <= 0.0 {
+            return None;
+        }
+
+        let submitted_at = order.submitted_at.unwrap_or(order.created_at);
+        let (avg_fill_price, filled_size) = (money::to_f64(order.avg_fill_price), money::to_f64(order.filled_size));
+        let shortfall_bps = shortfall_bps(order.request.side, decision.price, avg_fill_price);
+
+        Some(ExecutionQuality {
+            client_order_id: order.client_order_id.clone(),
+            pattern_hash,
+            symbol: order.request.symbol.clone(),
+            side: order.request.side,
+            decision_price: decision.price,
+            avg_fill_price,
+            filled_size,
+            decision_to_submit_ms: (submitted_at - decision.at).num_milliseconds(),
+            submit_to_first_fill_ms: order.first_fill_at.map(|at| (at - submitted_at).num_milliseconds()),
+            shortfall_bps,
+            shortfall: shortfall_bps / 10_000.0 * filled_size,
+            decided_at: decision.at,
+        })
+    }
+
+    pub async fn store(&self, db_pool: &PgPool, run_id: Option<Uuid>) -> Result<(), sqlx::Error> {
+        sqlx::query("
+            INSERT INTO execution_quality
+            (client_order_id, pattern_hash, symbol, side, decision_price, avg_fill_price, filled_size,
+             decision_to_submit_ms, submit_to_first_fill_ms, shortfall_bps, shortfall, decided_at, run_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (client_order_id) DO NOTHING
+        ")
+            .bind(&self.client_order_id)
+            .bind(&self.pattern_hash)
+            .bind(&self.symbol)
+            .bind(self.side.as_str())
+            .bind(self.decision_price)
+            .bind(self.avg_fill_price)
+            .bind(self.filled_size)
+            .bind(self.decision_to_submit_ms)
+            .bind(self.submit_to_first_fill_ms)
+            .bind(self.shortfall_bps)
+            .bind(self.shortfall)
+            .bind(self.decided_at)
+            .bind(run_id)
+            .execute(db_pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Signed cost of the fill relative to the decision price, in basis points
+pub fn shortfall_bps(side: Side, decision_price: f64, fill_price: f64) -> f64 {
+    let direction = match side {
+        Side::Buy => 1.0,
+        Side::Sell => -1.0,
+    };
+    direction * (fill_price - decision_price) / decision_price * 10_000.0
+}
+
+/// Size-weighted execution costs per pattern, for penalising latency-sensitive patterns
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PatternExecutionStats {
+    pub pattern_hash: String,
+    pub orders: i64,
+    pub avg_shortfall_bps: f64,
+    pub avg_decision_to_submit_ms: f64,
+    pub avg_submit_to_first_fill_ms: f64,
+}
+
+pub async fn load_pattern_stats(db_pool: &PgPool, since: DateTime<Utc>) -> Result<Vec<PatternExecutionStats>, sqlx::Error> {
+    let rows = sqlx::query("
+        SELECT pattern_hash,
+               COUNT(*) AS orders,
+               SUM(shortfall_bps * filled_size) / NULLIF(SUM(filled_size), 0) AS avg_shortfall_bps,
+               AVG(decision_to_submit_ms)::DOUBLE PRECISION AS avg_decision_to_submit_ms,
+               AVG(submit_to_first_fill_ms)::DOUBLE PRECISION AS avg_submit_to_first_fill_ms
+        FROM execution_quality
+        WHERE pattern_hash IS NOT NULL AND decided_at >= $1
+        GROUP BY pattern_hash
+    ")
+        .bind(since)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| PatternExecutionStats {
+        pattern_hash: row.get("pattern_hash"),
+        orders: row.get("orders"),
+        avg_shortfall_bps: row.get::<Option<f64>, _>("avg_shortfall_bps").unwrap_or(0.0),
+        avg_decision_to_submit_ms: row.get::<Option<f64>, _>("avg_decision_to_submit_ms").unwrap_or(0.0),
+        avg_submit_to_first_fill_ms: row.get::<Option<f64>, _>("avg_submit_to_first_fill_ms").unwrap_or(0.0),
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use chrono::Duration;
+    use crate::core::exchange::{OrderRequest, OrderType};
+    use crate::core::execution_engine::OrderIntent;
+    use crate::core::order_manager::OrderState;
+
+    #[test]
+    fn test_measures_latency_and_shortfall() {
+        let decided_at = Utc::now();
+        let request = OrderRequest {
+            client_order_id: "a".to_string(),
+            symbol: "DOGE-USD".to_string(),
+            side: Side::Sell,
+            size: dec!(100),
+            order_type: OrderType::Market,
+            reduce_only: true,
+        };
+        let order = ManagedOrder {
+            client_order_id: "a".to_string(),
+            exchange: "paper".to_string(),
+            exchange_order_id: Some("paper-1".to_string()),
+            request,
+            intent: OrderIntent::Close { position_id: "p".to_string(), size: dec!(100), reason: "test".to_string(), decision: None },
+            state: OrderState::Filled,
+            filled_size: dec!(100),
+            avg_fill_price: dec!(99),
+            fees: Decimal::ZERO,
+            parent_order_id: None,
+            reprice_count: 0,
+            submitted_at: Some(decided_at + Duration::milliseconds(40)),
+            first_fill_at: Some(decided_at + Duration::milliseconds(140)),
+            created_at: decided_at,
+            updated_at: decided_at,
+        };
+
+        let quality = ExecutionQuality::measure(&order, &Decision { price: 100.0, at: decided_at }, None).unwrap();
+        assert_eq!(quality.decision_to_submit_ms, 40);
+        assert_eq!(quality.submit_to_first_fill_ms, Some(100));
+        // Sold a point below the decision: 100bps against us
+        assert!((quality.shortfall_bps - 100.0).abs() < 1e-9);
+        assert!((quality.shortfall - 1.0).abs() < 1e-9);
+        assert!(shortfall_bps(Side::Buy, 100.0, 99.0) < 0.0);
+    }
+}
@@ -0,0 +1,118 @@
+// Execution Style Binding
+// Each pattern trades with the order type that suits it: fast patterns take liquidity,
+// slow or slippage-sensitive patterns rest passively or slice the order over time
+
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+use tracing::info;
+
+use crate::core::drift_report::PatternDrift;
+
+const FAST_TIMEFRAME_MINUTES: u32 = 5;      // a resting order would miss the move
+const SLOW_TIMEFRAME_MINUTES: u32 = 60;     // plenty of time to work an order
+const SLIPPAGE_SENSITIVE_BPS: f64 = 10.0;
+const SLIPPAGE_SEVERE_BPS: f64 = 25.0;
+const MIN_FILLS_FOR_SLIPPAGE: u32 = 5;      // fewer live fills than this is just noise
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStyle {
+    #[default]
+    Market,        // cross the spread immediately
+    PassiveLimit,  // rest at the touch, earn the spread
+    Twap,          // slice evenly over the pattern's timeframe
+}
+
+impl ExecutionStyle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionStyle::Market => "market",
+            ExecutionStyle::PassiveLimit => "passive_limit",
+            ExecutionStyle::Twap => "twap",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "market" => Some(ExecutionStyle::Market),
+            "passive_limit" => Some(ExecutionStyle::PassiveLimit),
+            "twap" => Some(ExecutionStyle::Twap),
+            _ => None,
+        }
+    }
+
+    /// Pick a style from the pattern's timeframe and its observed live slippage
+    /// `avg_slippage_bps` is None until the pattern has enough live fills to judge
+    pub fn choose(timeframe_minutes: u32, avg_slippage_bps: Option<f64>) -> Self {
+        let slippage = avg_slippage_bps.map(f64::abs).unwrap_or(0.0);
+
+        if timeframe_minutes <= FAST_TIMEFRAME_MINUTES {
+            ExecutionStyle::Market
+        } else if slippage >= SLIPPAGE_SEVERE_BPS && timeframe_minutes >= SLOW_TIMEFRAME_MINUTES {
+            ExecutionStyle::Twap
+        } else if slippage >= SLIPPAGE_SENSITIVE_BPS || timeframe_minutes >= SLOW_TIMEFRAME_MINUTES {
+            ExecutionStyle::PassiveLimit
+        } else {
+            ExecutionStyle::Market
+        }
+    }
+
+    /// Same as `choose`, taking slippage from a drift report row when it has enough fills
+    pub fn choose_from_drift(timeframe_minutes: u32, drift: Option<&PatternDrift>) -> Self {
+        let slippage = drift
+            .filter(|d| d.signals.saturating_sub(d.missed_fills) >= MIN_FILLS_FOR_SLIPPAGE)
+            .map(|d| d.avg_slippage_bps);
+        Self::choose(timeframe_minutes, slippage)
+    }
+}
+
+/// Re-bind every active pattern's execution style from its timeframe and the latest drift report
+/// Returns the number of patterns whose style changed
+pub async fn rebind_styles(db_pool: &PgPool, drift: &[PatternDrift]) -> Result<u64, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT pattern_hash, timeframe_minutes, execution_style FROM discovered_patterns WHERE is_active = true"
+    )
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut changed = 0;
+    for row in rows {
+        let hash: String = row.get("pattern_hash");
+        let timeframe = row.get::<Option<i32>, _>("timeframe_minutes").unwrap_or(0).max(0) as u32;
+        let current = ExecutionStyle::parse(row.get("execution_style"));
+
+        let style = ExecutionStyle::choose_from_drift(timeframe, drift.iter().find(|d| d.pattern_hash == hash));
+        if current == Some(style) {
+            continue;
+        }
+
+        sqlx::query("UPDATE discovered_patterns SET execution_style = $2, updated_at = NOW() WHERE pattern_hash = $1")
+            .bind(&hash)
+            .bind(style.as_str())
+            .execute(db_pool)
+            .await?;
+
+        info!("🎛️ Pattern {} now executes as {}", hash, style.as_str());
+        changed += 1;
+    }
+
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_style() {
+        assert_eq!(ExecutionStyle::choose(1, Some(40.0)), ExecutionStyle::Market);
+        assert_eq!(ExecutionStyle::choose(15, None), ExecutionStyle::Market);
+        assert_eq!(ExecutionStyle::choose(15, Some(-12.0)), ExecutionStyle::PassiveLimit);
+        assert_eq!(ExecutionStyle::choose(240, None), ExecutionStyle::PassiveLimit);
+        assert_eq!(ExecutionStyle::choose(240, Some(30.0)), ExecutionStyle::Twap);
+
+        // Too few live fills to trust the slippage figure
+        let drift = PatternDrift { pattern_hash: "a".to_string(), signals: 3, avg_slippage_bps: 50.0, ..Default::default() };
+        assert_eq!(ExecutionStyle::choose_from_drift(15, Some(&drift)), ExecutionStyle::Market);
+    }
+}
@@ -0,0 +1,199 @@
+// Feature Flags - Runtime Switches for Gradual Rollouts
+// Subsystems ask a flag at the point where they would take a newer code path: whether to draw a
+// hypothesis from an LLM template, skip a near-duplicate, or work a large entry as an algo. Each
+// flag is on or off, plus a rollout share: a subject (template, symbol, hypothesis) is in the
+// rollout when its hash bucket falls below the share, so the same subject gets the same answer
+// on every check and in every process. State lives in the feature_flags table, changes through
+// the control API, and is re-read by each process every flag_refresh_secs; a flag without a
+// row keeps its default
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Flag {
+    LlmSeeding,      // fresh hypotheses may come from LLM templates (by template name)
+    PatternDedup,    // near-duplicates of stored patterns are skipped untested (by hypothesis hash)
+    ExecutionAlgos,  // entries larger than the touch are worked as TWAP or iceberg (by symbol)
+}
+
+impl Flag {
+    pub const ALL: [Flag; 3] = [Flag::LlmSeeding, Flag::PatternDedup, Flag::ExecutionAlgos];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Flag::LlmSeeding => "llm_seeding",
+            Flag::PatternDedup => "pattern_dedup",
+            Flag::ExecutionAlgos => "execution_algos",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Flag::ALL.into_iter().find(|flag| flag.as_str() == s)
+    }
+
+    /// What a flag is with no row in feature_flags: everything that shipped before flags existed
+    pub fn default_state(&self) -> FlagState {
+        FlagState { enabled: true, rollout_pct: 1.0 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FlagState {
+    pub enabled: bool,
+    pub rollout_pct: f64,  // 0.0-1.0 share of subjects the flag is on for, while enabled
+}
+
+/// A control API change; fields left out keep their value
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FlagUpdate {
+    pub enabled: Option<bool>,
+    pub rollout_pct: Option<f64>,
+}
+
+impl FlagState {
+    pub fn with_update(&self, update: &FlagUpdate) -> Result<FlagState, Vec<String>> {
+        let next = FlagState {
+            enabled: update.enabled.unwrap_or(self.enabled),
+            rollout_pct: update.rollout_pct.unwrap_or(self.rollout_pct),
+        };
+        if !(0.0..=1.0).contains(&next.rollout_pct) {
+            return Err(vec![format!("rollout_pct must be in [0, 1], got {}", next.rollout_pct)]);
+        }
+        Ok(next)
+    }
+}
+
+/// Shared, cheap to clone; checks read the cached state and never touch the database
+#[derive(Clone, Default)]
+pub struct FeatureFlags {
+    states: Arc<RwLock<HashMap<Flag, FlagState>>>,
+    db_pool: Option<PgPool>,
+}
+
+impl FeatureFlags {
+    pub fn new(db_pool: PgPool) -> Self {
+        FeatureFlags { states: Arc::default(), db_pool: Some(db_pool) }
+    }
+
+    pub fn state(&self, flag: Flag) -> FlagState {
+        self.states.read().unwrap().get(&flag).copied().unwrap_or_else(|| flag.default_state())
+    }
+
+    /// Whether `subject` takes the flagged path
+    pub fn enabled_for(&self, flag: Flag, subject: &str) -> bool {
+        let state = self.state(flag);
+        state.enabled && bucket(flag, subject) < state.rollout_pct
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<Flag, FlagState> {
+        Flag::ALL.into_iter().map(|flag| (flag, self.state(flag))).collect()
+    }
+
+    /// Re-read the table; returns how many flags have a row
+    pub async fn refresh(&self) -> Result<usize, sqlx::Error> {
+        let Some(db_pool) = &self.db_pool else {
+            return Ok(0);
+        };
+        let rows = sqlx::query("SELECT name, enabled, rollout_pct FROM feature_flags")
+            .fetch_all(db_pool)
+            .await?;
+        let mut states = HashMap::new();
+        for row in rows {
+            let name: String = row.get("name");
+            match Flag::parse(&name) {
+                Some(flag) => {
+                    states.insert(flag, FlagState { enabled: row.get("enabled"), rollout_pct: row.get("rollout_pct") });
+                }
+                None => warn!("⚠️ Ignoring unknown feature flag {}", name),
+            }
+        }
+        let count = states.len();
+        *self.states.write().unwrap() = states;
+        Ok(count)
+    }
+
+    /// Store a new state for every process sharing the database, and use it here at once
+    pub async fn set(&self, flag: Flag, state: FlagState, updated_by: &str) -> Result<(), sqlx::Error> {
+        if let Some(db_pool) = &self.db_pool {
+            sqlx::query("
+                INSERT INTO feature_flags (name, enabled, rollout_pct, updated_by, updated_at)
+                VALUES ($1, $2, $3, $4, NOW())
+                ON CONFLICT (name) DO UPDATE
+                SET enabled = EXCLUDED.enabled, rollout_pct = EXCLUDED.rollout_pct,
+                    updated_by = EXCLUDED.updated_by, updated_at = NOW()
+            ")
+            .bind(flag.as_str())
+            .bind(state.enabled)
+            .bind(state.rollout_pct)
+            .bind(updated_by)
+            .execute(db_pool)
+            .await?;
+        }
+        self.states.write().unwrap().insert(flag, state);
+        Ok(())
+    }
+
+    /// Pick up changes made through another process's control API
+    pub fn watch(self, every: std::time::Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            let mut last = self.snapshot();
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.refresh().await {
+                    warn!("⚠️ Feature flag refresh failed: {}", e);
+                    continue;
+                }
+                let current = self.snapshot();
+                for (flag, state) in &current {
+                    if last.get(flag) != Some(state) {
+                        info!("🚩 {} is now {} for {:.0}%", flag.as_str(), if state.enabled { "on" } else { "off" }, state.rollout_pct * 100.0);
+                    }
+                }
+                last = current;
+            }
+        })
+    }
+}
+
+/// Where `subject` falls in [0, 1) for `flag`; salted with the flag so rollouts don't all pick
+/// the same subjects first
+fn bucket(flag: Flag, subject: &str) -> f64 {
+    let digest = Sha256::new().chain_update(flag.as_str()).chain_update(b":").chain_update(subject).finalize();
+    let mut head = [0u8; 8];
+    head.copy_from_slice(&digest[..8]);
+    (u64::from_be_bytes(head) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rollout_is_sticky_and_proportional() {
+        let flags = FeatureFlags::default();
+        assert!(flags.enabled_for(Flag::ExecutionAlgos, "DOGE-USD"));
+
+        let half = flags.state(Flag::ExecutionAlgos).with_update(&FlagUpdate { rollout_pct: Some(0.5), ..Default::default() }).unwrap();
+        flags.set(Flag::ExecutionAlgos, half, "test").await.unwrap();
+        let subjects: Vec<String> = (0..2000).map(|i| format!("SYM{}-USD", i)).collect();
+        let on = subjects.iter().filter(|s| flags.enabled_for(Flag::ExecutionAlgos, s)).count();
+        assert!((900..1100).contains(&on), "{} of 2000", on);
+        assert!(subjects.iter().all(|s| flags.enabled_for(Flag::ExecutionAlgos, s) == flags.enabled_for(Flag::ExecutionAlgos, s)));
+        assert!(flags.enabled_for(Flag::LlmSeeding, "momentum"));
+
+        let off = half.with_update(&FlagUpdate { enabled: Some(false), ..Default::default() }).unwrap();
+        flags.set(Flag::ExecutionAlgos, off, "test").await.unwrap();
+        assert!(!subjects.iter().any(|s| flags.enabled_for(Flag::ExecutionAlgos, s)));
+        assert!(half.with_update(&FlagUpdate { rollout_pct: Some(1.5), ..Default::default() }).is_err());
+        assert_eq!(Flag::parse("pattern_dedup"), Some(Flag::PatternDedup));
+    }
+}
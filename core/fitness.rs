@@ -0,0 +1,194 @@
+// Fitness Functions
+// What evolution optimizes for. Ranking on win rate alone favours patterns that take many
+// tiny wins and the occasional huge loss, so the metric is pluggable and chosen by config
+
+use serde::{Serialize, Deserialize};
+
+use crate::core::performance::PerformanceWindow;
+
+/// Drawdowns below this are treated as this, so a lucky flat equity curve doesn't divide by ~0
+const DRAWDOWN_FLOOR: f64 = 0.01;
+/// Profit factor reported for a window with wins and no losses
+const PROFIT_FACTOR_CAP: f64 = 10.0;
+
+pub trait FitnessFunction: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Higher is fitter; only the ordering within one population matters
+    fn score(&self, window: &PerformanceWindow) -> f64;
+}
+
+/// The original blend: squared hit rate, Sharpe, pnl and a sample-size confidence term
+pub struct Composite;
+
+impl FitnessFunction for Composite {
+    fn name(&self) -> &'static str {
+        "composite"
+    }
+
+    fn score(&self, window: &PerformanceWindow) -> f64 {
+        if window.trades == 0 {
+            return 0.0;
+        }
+
+        let sharpe = window.sharpe.max(0.0);
+        let confidence = (window.trades as f64 / 100.0).min(1.0);
+        let score = window.hit_rate.powi(2) * 0.3
+            + (sharpe / 3.0) * 0.3
+            + (window.total_pnl / 1000.0) * 0.2
+            + confidence * 0.2;
+
+        if window.hit_rate > 0.6 && sharpe > 1.5 {
+            score * 1.5
+        } else {
+            score
+        }
+    }
+}
+
+pub struct Sharpe;
+
+impl FitnessFunction for Sharpe {
+    fn name(&self) -> &'static str {
+        "sharpe"
+    }
+
+    fn score(&self, window: &PerformanceWindow) -> f64 {
+        window.sharpe
+    }
+}
+
+/// Gross profit over gross loss; a pattern whose rare losses eat its wins scores below 1
+pub struct ProfitFactor;
+
+impl FitnessFunction for ProfitFactor {
+    fn name(&self) -> &'static str {
+        "profit_factor"
+    }
+
+    fn score(&self, window: &PerformanceWindow) -> f64 {
+        if window.gross_loss <= 0.0 {
+            return if window.gross_profit > 0.0 { PROFIT_FACTOR_CAP } else { 0.0 };
+        }
+        (window.gross_profit / window.gross_loss).min(PROFIT_FACTOR_CAP)
+    }
+}
+
+/// Annualized return over maximum drawdown
+pub struct Calmar;
+
+impl FitnessFunction for Calmar {
+    fn name(&self) -> &'static str {
+        "calmar"
+    }
+
+    fn score(&self, window: &PerformanceWindow) -> f64 {
+        annualized_return(window) / window.max_drawdown.max(DRAWDOWN_FLOOR)
+    }
+}
+
+/// Annualized return minus `penalty` times the maximum drawdown
+pub struct DrawdownPenalizedReturn {
+    pub penalty: f64,
+}
+
+impl FitnessFunction for DrawdownPenalizedReturn {
+    fn name(&self) -> &'static str {
+        "drawdown_penalized"
+    }
+
+    fn score(&self, window: &PerformanceWindow) -> f64 {
+        annualized_return(window) - self.penalty * window.max_drawdown
+    }
+}
+
+fn annualized_return(window: &PerformanceWindow) -> f64 {
+    // Crypto trades every day of the year
+    window.avg_daily_return * 365.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FitnessMetric {
+    #[default]
+    Composite,
+    Sharpe,
+    ProfitFactor,
+    Calmar,
+    DrawdownPenalized,
+}
+
+impl FitnessMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FitnessMetric::Composite => "composite",
+            FitnessMetric::Sharpe => "sharpe",
+            FitnessMetric::ProfitFactor => "profit_factor",
+            FitnessMetric::Calmar => "calmar",
+            FitnessMetric::DrawdownPenalized => "drawdown_penalized",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "composite" => Some(FitnessMetric::Composite),
+            "sharpe" => Some(FitnessMetric::Sharpe),
+            "profit_factor" => Some(FitnessMetric::ProfitFactor),
+            "calmar" => Some(FitnessMetric::Calmar),
+            "drawdown_penalized" => Some(FitnessMetric::DrawdownPenalized),
+            _ => None,
+        }
+    }
+
+    /// Metric comes from EVOLUTION_FITNESS; unknown values fall back to the composite score
+    pub fn from_env() -> Self {
+        std::env::var("EVOLUTION_FITNESS")
+            .ok()
+            .and_then(|v| Self::parse(v.trim()))
+            .unwrap_or_default()
+    }
+
+    pub fn function(&self) -> Box<dyn FitnessFunction> {
+        match self {
+            FitnessMetric::Composite => Box::new(Composite),
+            FitnessMetric::Sharpe => Box::new(Sharpe),
+            FitnessMetric::ProfitFactor => Box::new(ProfitFactor),
+            FitnessMetric::Calmar => Box::new(Calmar),
+            FitnessMetric::DrawdownPenalized => Box::new(DrawdownPenalizedReturn { penalty: 2.0 }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail_risk_pattern_loses_on_risk_aware_metrics() {
+        // 90% winners, but the one loser wipes out nine wins and then some
+        let tail_risk = PerformanceWindow {
+            window_days: 30, trades: 100, hit_rate: 0.9, total_pnl: -10.0, sharpe: -0.2, max_drawdown: 0.6,
+            gross_profit: 45.0, gross_loss: 55.0, avg_daily_return: -0.002,
+        };
+        let steady = PerformanceWindow {
+            window_days: 30, trades: 100, hit_rate: 0.55, total_pnl: 20.0, sharpe: 1.2, max_drawdown: 0.08,
+            gross_profit: 60.0, gross_loss: 40.0, avg_daily_return: 0.004,
+        };
+
+        for metric in [FitnessMetric::Sharpe, FitnessMetric::ProfitFactor, FitnessMetric::Calmar, FitnessMetric::DrawdownPenalized] {
+            let f = metric.function();
+            assert!(f.score(&steady) > f.score(&tail_risk), "{} ranked the tail-risk pattern higher", f.name());
+        }
+        assert!(ProfitFactor.score(&tail_risk) < 1.0);
+    }
+
+    #[test]
+    fn test_metric_round_trip() {
+        for metric in [FitnessMetric::Composite, FitnessMetric::Sharpe, FitnessMetric::ProfitFactor,
+                       FitnessMetric::Calmar, FitnessMetric::DrawdownPenalized] {
+            assert_eq!(FitnessMetric::parse(metric.as_str()), Some(metric));
+            assert_eq!(metric.function().name(), metric.as_str());
+        }
+        assert_eq!(FitnessMetric::parse("win_rate"), None);
+    }
+}
@@ -0,0 +1,219 @@
+// Pattern Genome Format
+// The serialized form of a hypothesis, versioned independently of the in-memory structs.
+// `Condition` and `Hypothesis` are free to change; the genome schema only changes with a new
+// version number and an upgrade step, so genomes exported by any earlier deployment stay loadable
+
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+
+use crate::core::discovery_engine::{Condition, Hypothesis};
+
+/// Version written by this build
+/// 1: the bare `Hypothesis` JSON (entry_conditions/exit_conditions with `value` thresholds)
+/// 2: named genes with `threshold`, `timeframe_minutes`, optional `symbol`
+pub const GENOME_SCHEMA_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gene {
+    pub metric: String,
+    pub operator: String,
+    pub threshold: f64,
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Genome {
+    pub schema_version: u32,
+    pub hash: String,
+    pub entry: Vec<Gene>,
+    pub exit: Vec<Gene>,
+    pub timeframe_minutes: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub created_at: i64,
+}
+
+#[derive(Debug)]
+pub enum GenomeError {
+    UnsupportedVersion(u32),
+    Malformed(String),
+}
+
+impl std::fmt::Display for GenomeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenomeError::UnsupportedVersion(v) => write!(f, "genome schema version {} is newer than {}", v, GENOME_SCHEMA_VERSION),
+            GenomeError::Malformed(e) => write!(f, "malformed genome: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for GenomeError {}
+
+impl Genome {
+    pub fn from_hypothesis(h: &Hypothesis) -> Self {
+        let genes = |conditions: &[Condition]| conditions.iter().map(|c| Gene {
+            metric: c.metric.clone(),
+            operator: c.operator.clone(),
+            threshold: c.value,
+            weight: c.weight,
+        }).collect();
+
+        Genome {
+            schema_version: GENOME_SCHEMA_VERSION,
+            hash: h.hash.clone(),
+            entry: genes(&h.entry_conditions),
+            exit: genes(&h.exit_conditions),
+            timeframe_minutes: h.timeframe,
+            symbol: h.symbol.clone(),
+            created_at: h.created_at,
+        }
+    }
+
+    pub fn to_hypothesis(&self) -> Hypothesis {
+        let conditions = |genes: &[Gene]| genes.iter().map(|g| Condition {
+            metric: g.metric.clone(),
+            operator: g.operator.clone(),
+            value: g.threshold,
+            weight: g.weight,
+        }).collect();
+
+        Hypothesis {
+            hash: self.hash.clone(),
+            entry_conditions: conditions(&self.entry),
+            exit_conditions: conditions(&self.exit),
+            timeframe: self.timeframe_minutes,
+            created_at: self.created_at,
+            symbol: self.symbol.clone(),
+        }
+    }
+
+    /// Read a genome written under any known schema version, upgrading it step by step
+    /// A document without `schema_version` is version 1
+    pub fn decode(mut value: Value) -> Result<Self, GenomeError> {
+        let mut version = match value.get("schema_version") {
+            None => 1,
+            Some(v) => v.as_u64().ok_or_else(|| GenomeError::Malformed("schema_version is not a number".to_string()))? as u32,
+        };
+        if version == 0 {
+            return Err(GenomeError::Malformed("schema_version 0".to_string()));
+        }
+        if version > GENOME_SCHEMA_VERSION {
+            return Err(GenomeError::UnsupportedVersion(version));
+        }
+
+        while version < GENOME_SCHEMA_VERSION {
+            value = match version {
+                1 => upgrade_v1(value)?,
+                _ => unreachable!("every version below the current one has an upgrade step"),
+            };
+            version += 1;
+        }
+
+        serde_json::from_value(value).map_err(|e| GenomeError::Malformed(e.to_string()))
+    }
+}
+
+/// v1 → v2: rename the condition lists and `value`, stamp the version
+fn upgrade_v1(value: Value) -> Result<Value, GenomeError> {
+    let field = |name: &str| value.get(name).cloned().ok_or_else(|| GenomeError::Malformed(format!("v1 genome missing {}", name)));
+    let genes = |conditions: Value| -> Result<Vec<Value>, GenomeError> {
+        conditions.as_array()
+            .ok_or_else(|| GenomeError::Malformed("conditions are not a list".to_string()))?
+            .iter()
+            .map(|c| {
+                let mut gene = c.clone();
+                let map = gene.as_object_mut().ok_or_else(|| GenomeError::Malformed("condition is not an object".to_string()))?;
+                if let Some(threshold) = map.remove("value") {
+                    map.insert("threshold".to_string(), threshold);
+                }
+                Ok(gene)
+            })
+            .collect()
+    };
+
+    Ok(json!({
+        "schema_version": 2,
+        "hash": field("hash")?,
+        "entry": genes(field("entry_conditions")?)?,
+        "exit": genes(field("exit_conditions")?)?,
+        "timeframe_minutes": field("timeframe")?,
+        "symbol": value.get("symbol").cloned().unwrap_or(Value::Null),
+        "created_at": value.get("created_at").cloned().unwrap_or(json!(0)),
+    }))
+}
+
+/// `#[serde(with = ...)]` adapter: store a `Hypothesis` field as a versioned genome
+pub mod serde_hypothesis {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use super::{Genome, Hypothesis, Value};
+
+    pub fn serialize<S: Serializer>(h: &Hypothesis, serializer: S) -> Result<S::Ok, S::Error> {
+        Genome::from_hypothesis(h).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Hypothesis, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Genome::decode(value)
+            .map(|g| g.to_hypothesis())
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// `#[serde(with = ...)]` adapter for a list of hypotheses
+pub mod serde_hypotheses {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use super::{Genome, Hypothesis, Value};
+
+    pub fn serialize<S: Serializer>(hypotheses: &[Hypothesis], serializer: S) -> Result<S::Ok, S::Error> {
+        hypotheses.iter().map(Genome::from_hypothesis).collect::<Vec<_>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Hypothesis>, D::Error> {
+        Vec::<Value>::deserialize(deserializer)?
+            .into_iter()
+            .map(|value| Genome::decode(value).map(|g| g.to_hypothesis()).map_err(serde::de::Error::custom))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v1_documents_upgrade() {
+        let v1 = json!({
+            "hash": "abc123",
+            "entry_conditions": [{"metric": "price_delta_5m", "operator": ">", "value": 1.5, "weight": 0.5}],
+            "exit_conditions": [{"metric": "price_delta_5m", "operator": "<", "value": -1.0, "weight": 0.5}],
+            "timeframe": 15,
+            "created_at": 1700000000
+        });
+
+        let genome = Genome::decode(v1).unwrap();
+        assert_eq!(genome.schema_version, GENOME_SCHEMA_VERSION);
+        assert_eq!(genome.entry[0].threshold, 1.5);
+        assert_eq!(genome.timeframe_minutes, 15);
+        assert_eq!(genome.symbol, None);
+
+        let h = genome.to_hypothesis();
+        assert_eq!(h.exit_conditions[0].value, -1.0);
+        assert_eq!(Genome::decode(serde_json::to_value(Genome::from_hypothesis(&h)).unwrap()).unwrap(), genome);
+    }
+
+    #[test]
+    fn test_future_and_malformed_versions_are_rejected() {
+        assert!(matches!(
+            Genome::decode(json!({"schema_version": GENOME_SCHEMA_VERSION + 1, "hash": "x"})),
+            Err(GenomeError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(Genome::decode(json!({"hash": "x"})), Err(GenomeError::Malformed(_))));
+    }
+}
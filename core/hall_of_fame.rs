@@ -0,0 +1,182 @@
+// Hall of Fame
+// An archive of the best patterns evolution has ever seen. Members are never retired by
+// selection, and the archive can rebuild the population after a purge or a regime change
+
+use std::collections::HashSet;
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use tracing::warn;
+
+use crate::core::discovery_engine::Hypothesis;
+use crate::core::evolution_engine::{Individual, Offspring, OffspringQueue};
+use crate::core::fitness::FitnessMetric;
+use crate::core::performance::PerformanceWindow;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HallOfFameEntry {
+    pub hypothesis: Hypothesis,
+    pub generation: u32,
+    pub parent_patterns: Vec<String>,
+    pub fitness: f64,
+    pub fitness_metric: String,
+    pub performance: PerformanceWindow,
+    pub inducted_at: DateTime<Utc>,
+}
+
+pub struct HallOfFame {
+    pub capacity: usize,  // members kept per fitness metric
+    db_pool: PgPool,
+}
+
+impl HallOfFame {
+    pub fn new(db_pool: PgPool) -> Self {
+        HallOfFame {
+            capacity: 20,
+            db_pool,
+        }
+    }
+
+    pub async fn members(&self) -> Result<HashSet<String>, sqlx::Error> {
+        let rows = sqlx::query("SELECT pattern_hash FROM hall_of_fame")
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get("pattern_hash")).collect())
+    }
+
+    /// Archive candidates with their result history, then trim the metric's board to capacity
+    /// A member keeps its best score; re-inducting refreshes its window and history
+    /// Returns how many candidates are on the board afterwards
+    pub async fn induct(&self, candidates: &[&Individual], metric: FitnessMetric) -> Result<usize, sqlx::Error> {
+        let query = "
+            INSERT INTO hall_of_fame
+            (pattern_hash, entry_conditions, exit_conditions, timeframe_minutes, generation, parent_patterns,
+             symbol, fitness, fitness_metric, performance, result_history)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
+                    (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.timestamp), '[]'::jsonb)
+                     FROM test_results t WHERE t.pattern_hash = $1))
+            ON CONFLICT (pattern_hash) DO UPDATE SET
+                fitness = GREATEST(hall_of_fame.fitness, EXCLUDED.fitness),
+                fitness_metric = EXCLUDED.fitness_metric,
+                performance = EXCLUDED.performance,
+                result_history = EXCLUDED.result_history,
+                updated_at = NOW()
+        ";
+
+        for candidate in candidates {
+            let h = &candidate.hypothesis;
+            sqlx::query(query)
+                .bind(&h.hash)
+                .bind(serde_json::to_value(&h.entry_conditions).unwrap_or_default())
+                .bind(serde_json::to_value(&h.exit_conditions).unwrap_or_default())
+                .bind(h.timeframe as i32)
+                .bind(candidate.generation as i32)
+                .bind(&candidate.parent_patterns)
+                .bind(&h.symbol)
+                .bind(candidate.fitness)
+                .bind(metric.as_str())
+                .bind(serde_json::to_value(&candidate.window).unwrap_or_default())
+                .execute(&self.db_pool)
+                .await?;
+        }
+
+        sqlx::query("
+            DELETE FROM hall_of_fame
+            WHERE fitness_metric = $1 AND pattern_hash NOT IN (
+                SELECT pattern_hash FROM hall_of_fame WHERE fitness_metric = $1
+                ORDER BY fitness DESC LIMIT $2
+            )
+        ")
+            .bind(metric.as_str())
+            .bind(self.capacity as i64)
+            .execute(&self.db_pool)
+            .await?;
+
+        let hashes: Vec<String> = candidates.iter().map(|c| c.hypothesis.hash.clone()).collect();
+        let row = sqlx::query("SELECT COUNT(*) AS inducted FROM hall_of_fame WHERE pattern_hash = ANY($1)")
+            .bind(&hashes)
+            .fetch_one(&self.db_pool)
+            .await?;
+
+        Ok(row.get::<i64, _>("inducted") as usize)
+    }
+
+    /// Every member, fittest first
+    pub async fn load(&self) -> Result<Vec<HallOfFameEntry>, sqlx::Error> {
+        let rows = sqlx::query("
+            SELECT pattern_hash, entry_conditions, exit_conditions, timeframe_minutes, generation,
+                   parent_patterns, symbol, fitness, fitness_metric, performance, inducted_at
+            FROM hall_of_fame
+            ORDER BY fitness DESC
+        ")
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let hash: String = row.get("pattern_hash");
+            let (Ok(entry_conditions), Ok(exit_conditions)) = (
+                serde_json::from_value(row.get("entry_conditions")),
+                serde_json::from_value(row.get("exit_conditions")),
+            ) else {
+                warn!("⚠️ Hall of fame entry {} has unreadable conditions", hash);
+                continue;
+            };
+            let inducted_at: DateTime<Utc> = row.get::<Option<DateTime<Utc>>, _>("inducted_at").unwrap_or_else(Utc::now);
+
+            entries.push(HallOfFameEntry {
+                hypothesis: Hypothesis {
+                    hash,
+                    entry_conditions,
+                    exit_conditions,
+                    timeframe: row.get::<i32, _>("timeframe_minutes") as u32,
+                    created_at: inducted_at.timestamp(),
+                    symbol: row.get("symbol"),
+                },
+                generation: row.get::<i32, _>("generation") as u32,
+                parent_patterns: row.get("parent_patterns"),
+                fitness: row.get("fitness"),
+                fitness_metric: row.get("fitness_metric"),
+                performance: serde_json::from_value(row.get("performance")).unwrap_or_default(),
+                inducted_at,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Restore every member to discovered_patterns as active and queue it for fresh tests,
+    /// so the archive is re-scored against the current regime. Returns the number restored
+    pub async fn reseed(&self, queue: &OffspringQueue) -> Result<usize, sqlx::Error> {
+        let entries = self.load().await?;
+
+        for entry in &entries {
+            let h = &entry.hypothesis;
+            sqlx::query("
+                INSERT INTO discovered_patterns
+                (pattern_hash, entry_conditions, exit_conditions, timeframe_minutes, generation, parent_patterns,
+                 symbol, is_active, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, true, NOW())
+                ON CONFLICT (pattern_hash) DO UPDATE SET is_active = true, updated_at = NOW()
+            ")
+                .bind(&h.hash)
+                .bind(serde_json::to_value(&h.entry_conditions).unwrap_or_default())
+                .bind(serde_json::to_value(&h.exit_conditions).unwrap_or_default())
+                .bind(h.timeframe as i32)
+                .bind(entry.generation as i32)
+                .bind(&entry.parent_patterns)
+                .bind(&h.symbol)
+                .execute(&self.db_pool)
+                .await?;
+        }
+
+        queue.lock().unwrap().extend(entries.iter().map(|entry| Offspring {
+            hypothesis: entry.hypothesis.clone(),
+            generation: entry.generation,
+            parent_patterns: entry.parent_patterns.clone(),
+        }));
+
+        Ok(entries.len())
+    }
+}
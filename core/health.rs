@@ -0,0 +1,424 @@
+// Health Checks - Liveness, Readiness and Public Status
+// /healthz answers whether the process is alive: its own loops still beat. /readyz checks
+// every subsystem trading depends on: the database, price feed freshness, exchange
+// credentials and the circuit breakers, and during startup the readiness gates still closed. Under systemd the liveness check also pets the
+// watchdog, so a wedged process is restarted the same way a crashed one is. /status.json
+// is the shareable view of a run: percentages and counts only, never dollars or positions
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::core::exchange::Exchange;
+use crate::core::heartbeat::Heartbeat;
+use crate::core::market_data::MarketDataFeed;
+use crate::core::money;
+use crate::core::readiness::Startup;
+use crate::core::risk_manager::{BreakerStates, RiskManager};
+
+/// How often exchange credentials are re-checked; probes read the cached result
+const EXCHANGE_AUTH_EVERY: Duration = Duration::from_secs(60);
+
+/// /status.json is unauthenticated, so it answers from a cache rather than the database
+const PUBLIC_STATUS_TTL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Degraded,  // reported, but the process still serves (e.g. a breaker is holding new risk)
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubsystemCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl SubsystemCheck {
+    pub fn new(name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        SubsystemCheck { name: name.to_string(), status, detail: detail.into() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub status: CheckStatus,  // the worst check
+    pub checks: Vec<SubsystemCheck>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl HealthReport {
+    pub fn from_checks(checks: Vec<SubsystemCheck>) -> Self {
+        HealthReport {
+            status: checks.iter().map(|c| c.status).max().unwrap_or(CheckStatus::Ok),
+            checks,
+            checked_at: Utc::now(),
+        }
+    }
+
+    /// 503 only when something is down; degraded still answers 200
+    pub fn http_status(&self) -> StatusCode {
+        match self.status {
+            CheckStatus::Down => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::OK,
+        }
+    }
+}
+
+pub fn heartbeat_check(stale: &[(String, Duration)]) -> SubsystemCheck {
+    if stale.is_empty() {
+        return SubsystemCheck::new("heartbeats", CheckStatus::Ok, "all loops beating");
+    }
+    let silent: Vec<String> = stale.iter().map(|(source, silence)| format!("{} silent {}s", source, silence.as_secs())).collect();
+    SubsystemCheck::new("heartbeats", CheckStatus::Down, silent.join(", "))
+}
+
+/// `fresh` as MarketDataFeed::is_fresh reports it
+pub fn feed_check(fresh: Option<bool>, max_age: Duration) -> SubsystemCheck {
+    match fresh {
+        None => SubsystemCheck::new("market_data", CheckStatus::Down, "no ticks received yet"),
+        Some(false) => SubsystemCheck::new("market_data", CheckStatus::Down, format!("no tick for over {}s", max_age.as_secs())),
+        Some(true) => SubsystemCheck::new("market_data", CheckStatus::Ok, "ticking"),
+    }
+}
+
+/// Down until every startup gate has opened; afterwards how long each took
+pub fn startup_check(startup: &Startup) -> SubsystemCheck {
+    let waiting = startup.waiting();
+    if waiting.is_empty() {
+        return SubsystemCheck::new("startup", CheckStatus::Ok, startup.summary());
+    }
+    SubsystemCheck::new("startup", CheckStatus::Down, format!("waiting for {} ({})", waiting.join(", "), startup.summary()))
+}
+
+/// An emergency stop takes the process out of rotation; breakers and reduce-only only degrade it
+pub fn breaker_check(states: &BreakerStates) -> SubsystemCheck {
+    if states.emergency_stop {
+        return SubsystemCheck::new("circuit_breakers", CheckStatus::Down, "emergency stop");
+    }
+
+    let mut active = Vec::new();
+    if states.loss_15min {
+        active.push("15min loss".to_string());
+    }
+    if states.loss_1hr {
+        active.push("1hr loss".to_string());
+    }
+    if states.var {
+        active.push("VaR".to_string());
+    }
+    if let Some(reason) = &states.reduce_only {
+        active.push(format!("reduce-only ({:?})", reason));
+    }
+
+    if active.is_empty() {
+        SubsystemCheck::new("circuit_breakers", CheckStatus::Ok, "none active")
+    } else {
+        SubsystemCheck::new("circuit_breakers", CheckStatus::Degraded, active.join(", "))
+    }
+}
+
+/// What anyone may see about a run: no capital, positions, symbols or venues
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublicStatus {
+    pub equity_change_pct: f64,  // since the run started, to 0.1%
+    pub active_patterns: i64,
+    pub discovered_patterns: i64,  // ever activated, including retired ones
+    pub uptime_seconds: i64,
+    pub as_of: DateTime<Utc>,
+}
+
+impl PublicStatus {
+    pub fn new(
+        starting_capital: f64,
+        current_capital: f64,
+        (active_patterns, discovered_patterns): (i64, i64),
+        started_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let change = if starting_capital > 0.0 { (current_capital / starting_capital - 1.0) * 100.0 } else { 0.0 };
+        PublicStatus {
+            equity_change_pct: (change * 10.0).round() / 10.0,
+            active_patterns,
+            discovered_patterns,
+            uptime_seconds: (now - started_at).num_seconds().max(0),
+            as_of: now,
+        }
+    }
+}
+
+/// Latest credential check per venue: when it last succeeded, or why it failed
+pub type ExchangeAuth = Arc<Mutex<HashMap<String, Result<DateTime<Utc>, String>>>>;
+
+#[derive(Clone)]
+pub struct HealthState {
+    pub feed_max_age: Duration,
+    pub started_at: DateTime<Utc>,  // uptime for /status.json; the run manifest's start when set
+    pub startup: Option<Startup>,   // `run`'s readiness gates; None for a process that has none
+    db_pool: PgPool,
+    risk_manager: Arc<RiskManager>,
+    market_data: MarketDataFeed,
+    heartbeat: Arc<Heartbeat>,
+    exchanges: Vec<Arc<dyn Exchange>>,
+    exchange_auth: ExchangeAuth,
+    public_status: Arc<Mutex<Option<(Instant, PublicStatus)>>>,
+}
+
+impl HealthState {
+    pub fn new(
+        db_pool: PgPool,
+        risk_manager: Arc<RiskManager>,
+        market_data: MarketDataFeed,
+        heartbeat: Arc<Heartbeat>,
+        exchanges: Vec<Arc<dyn Exchange>>,
+    ) -> Self {
+        HealthState {
+            feed_max_age: Duration::from_secs(60),
+            started_at: Utc::now(),
+            startup: None,
+            db_pool,
+            risk_manager,
+            market_data,
+            heartbeat,
+            exchanges,
+            exchange_auth: Arc::new(Mutex::new(HashMap::new())),
+            public_status: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn liveness(&self) -> HealthReport {
+        HealthReport::from_checks(vec![heartbeat_check(&self.heartbeat.stale_sources())])
+    }
+
+    pub async fn readiness(&self) -> HealthReport {
+        let mut checks = vec![
+            self.database_check().await,
+            feed_check(self.market_data.is_fresh(self.feed_max_age), self.feed_max_age),
+        ];
+        checks.extend(self.exchange_checks());
+        checks.push(breaker_check(&self.risk_manager.breaker_states()));
+        checks.push(heartbeat_check(&self.heartbeat.stale_sources()));
+        if let Some(startup) = &self.startup {
+            checks.push(startup_check(startup));
+        }
+
+        HealthReport::from_checks(checks)
+    }
+
+    /// The cached public status, recomputed at most every PUBLIC_STATUS_TTL
+    pub async fn public_status(&self) -> Result<PublicStatus, sqlx::Error> {
+        if let Some((at, status)) = self.public_status.lock().unwrap().as_ref() {
+            if at.elapsed() < PUBLIC_STATUS_TTL {
+                return Ok(status.clone());
+            }
+        }
+
+        let counts: (i64, i64) = sqlx::query_as("
+            SELECT COUNT(*) FILTER (WHERE is_active), COUNT(*)
+            FROM discovered_patterns
+        ")
+            .fetch_one(&self.db_pool)
+            .await?;
+        let status = PublicStatus::new(
+            money::to_f64(self.risk_manager.starting_capital()),
+            money::to_f64(self.risk_manager.current_capital()),
+            counts,
+            self.started_at,
+            Utc::now(),
+        );
+        *self.public_status.lock().unwrap() = Some((Instant::now(), status.clone()));
+        Ok(status)
+    }
+
+    async fn database_check(&self) -> SubsystemCheck {
+        let ping = sqlx::query("SELECT 1").execute(&self.db_pool);
+        match tokio::time::timeout(Duration::from_secs(2), ping).await {
+            Ok(Ok(_)) => SubsystemCheck::new("database", CheckStatus::Ok, "connected"),
+            Ok(Err(e)) => SubsystemCheck::new("database", CheckStatus::Down, e.to_string()),
+            Err(_) => SubsystemCheck::new("database", CheckStatus::Down, "ping timed out after 2s"),
+        }
+    }
+
+    fn exchange_checks(&self) -> Vec<SubsystemCheck> {
+        let auth = self.exchange_auth.lock().unwrap();
+        self.exchanges.iter().map(|exchange| {
+            let name = format!("exchange:{}", exchange.name());
+            match auth.get(exchange.name()) {
+                None => SubsystemCheck::new(&name, CheckStatus::Down, "credentials not checked yet"),
+                Some(Ok(at)) => SubsystemCheck::new(&name, CheckStatus::Ok, format!("authenticated {}", at.to_rfc3339())),
+                Some(Err(e)) => SubsystemCheck::new(&name, CheckStatus::Down, e.clone()),
+            }
+        }).collect()
+    }
+
+    /// Fetch balances from every venue: the cheapest call that needs valid credentials
+    pub async fn refresh_exchange_auth(&self) {
+        for exchange in &self.exchanges {
+            let result = match tokio::time::timeout(Duration::from_secs(10), exchange.balances()).await {
+                Ok(Ok(_)) => Ok(Utc::now()),
+                Ok(Err(e)) => Err(e.to_string()),
+                Err(_) => Err("balance request timed out after 10s".to_string()),
+            };
+            if let Err(e) = &result {
+                warn!("⚠️ {} credential check failed: {}", exchange.name(), e);
+            }
+            self.exchange_auth.lock().unwrap().insert(exchange.name().to_string(), result);
+        }
+    }
+}
+
+pub fn router(state: HealthState) -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/status.json", get(status_json))
+        .with_state(state)
+}
+
+async fn healthz(State(state): State<HealthState>) -> (StatusCode, Json<HealthReport>) {
+    let report = state.liveness();
+    (report.http_status(), Json(report))
+}
+
+async fn readyz(State(state): State<HealthState>) -> (StatusCode, Json<HealthReport>) {
+    let report = state.readiness().await;
+    (report.http_status(), Json(report))
+}
+
+async fn status_json(State(state): State<HealthState>) -> Result<Json<PublicStatus>, StatusCode> {
+    state.public_status().await.map(Json).map_err(|e| {
+        warn!("⚠️ Public status unavailable: {}", e);
+        StatusCode::SERVICE_UNAVAILABLE
+    })
+}
+
+/// Serve the probes and public status on `port`, keep the exchange credential cache fresh, and drive the
+/// systemd watchdog when running under one
+pub async fn serve(state: HealthState, port: u16) -> std::io::Result<()> {
+    let auth_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(EXCHANGE_AUTH_EVERY);
+        loop {
+            interval.tick().await;
+            auth_state.refresh_exchange_auth().await;
+        }
+    });
+
+    if let Some(every) = watchdog_interval() {
+        let watchdog_state = state.clone();
+        tokio::spawn(async move { run_watchdog(watchdog_state, every).await });
+    }
+
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port)).await?;
+    info!("🩺 Health endpoints on :{} (/healthz, /readyz, /status.json)", port);
+    axum::serve(listener, router(state)).await
+}
+
+/// Half of WATCHDOG_USEC, as sd_watchdog_enabled(3) recommends; None outside systemd
+fn watchdog_interval() -> Option<Duration> {
+    std::env::var("NOTIFY_SOCKET").ok()?;
+    let usec = std::env::var("WATCHDOG_USEC").ok()?.parse::<u64>().ok()?;
+    Some(Duration::from_micros(usec / 2))
+}
+
+/// READY=1 once readiness first passes; WATCHDOG=1 only while liveness does, so systemd
+/// restarts a process whose loops have stalled
+async fn run_watchdog(state: HealthState, every: Duration) {
+    let mut interval = tokio::time::interval(every);
+    let mut ready_sent = false;
+
+    loop {
+        interval.tick().await;
+
+        if !ready_sent && state.readiness().await.status != CheckStatus::Down {
+            ready_sent = notify_systemd("READY=1");
+        }
+        if state.liveness().status != CheckStatus::Down {
+            notify_systemd("WATCHDOG=1");
+        } else {
+            warn!("⚠️ Liveness failing - withholding systemd watchdog ping");
+        }
+    }
+}
+
+/// sd_notify(3) without libsystemd: one datagram to $NOTIFY_SOCKET
+#[cfg(target_os = "linux")]
+fn notify_systemd(message: &str) -> bool {
+    use std::os::linux::net::SocketAddrExt;
+    use std::os::unix::net::{SocketAddr, UnixDatagram};
+
+    let Ok(path) = std::env::var("NOTIFY_SOCKET") else {
+        return false;
+    };
+    let addr = match path.strip_prefix('@') {
+        Some(abstract_name) => SocketAddr::from_abstract_name(abstract_name.as_bytes()),
+        None => SocketAddr::from_pathname(&path),
+    };
+    let sent = addr.and_then(|addr| UnixDatagram::unbound()?.send_to_addr(message.as_bytes(), &addr));
+    if let Err(e) = &sent {
+        warn!("⚠️ systemd notify failed: {}", e);
+    }
+    sent.is_ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn notify_systemd(_message: &str) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_status_is_the_worst_check() {
+        let checks = vec![
+            feed_check(Some(true), Duration::from_secs(60)),
+            breaker_check(&BreakerStates { var: true, ..BreakerStates::default() }),
+        ];
+        let report = HealthReport::from_checks(checks);
+        assert_eq!(report.status, CheckStatus::Degraded);
+        assert_eq!(report.http_status(), StatusCode::OK);
+
+        let report = HealthReport::from_checks(vec![feed_check(None, Duration::from_secs(60)), heartbeat_check(&[])]);
+        assert_eq!(report.status, CheckStatus::Down);
+        assert_eq!(report.http_status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let stopped = breaker_check(&BreakerStates { emergency_stop: true, ..BreakerStates::default() });
+        assert_eq!(stopped.status, CheckStatus::Down);
+        assert_eq!(heartbeat_check(&[("risk".to_string(), Duration::from_secs(200))]).detail, "risk silent 200s");
+
+        let startup = Startup::new(crate::core::readiness::Gate::new("market_data"));
+        startup.migrations.open();
+        startup.venues.open();
+        startup.risk_state.open();
+        let starting = startup_check(&startup);
+        assert_eq!(starting.status, CheckStatus::Down);
+        assert!(starting.detail.starts_with("waiting for market_data ("));
+        startup.market_data.open();
+        assert_eq!(startup_check(&startup).status, CheckStatus::Ok);
+    }
+
+    #[test]
+    fn test_public_status_shows_percentages_and_counts_only() {
+        let started = Utc::now() - chrono::Duration::hours(2);
+        let status = PublicStatus::new(200.0, 212.34, (3, 11), started, started + chrono::Duration::hours(2));
+        assert_eq!(status.equity_change_pct, 6.2);
+        assert_eq!(status.uptime_seconds, 7200);
+
+        let json = serde_json::to_value(&status).unwrap();
+        let mut keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, ["active_patterns", "as_of", "discovered_patterns", "equity_change_pct", "uptime_seconds"]);
+
+        assert_eq!(PublicStatus::new(0.0, 50.0, (0, 0), started, started).equity_change_pct, 0.0);
+    }
+}
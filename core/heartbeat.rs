@@ -0,0 +1,69 @@
+// Heartbeats - Dead-Man's Switch Input
+// Tasks the execution engine depends on (the risk monitor, discovery) beat here.
+// Each expected source has its own allowed silence; once any source goes quiet for
+// longer than that, the execution engine cancels everything and refuses new orders.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct Source {
+    last_beat: Instant,
+    max_silence: Duration,
+}
+
+#[derive(Default)]
+pub struct Heartbeat {
+    sources: Mutex<HashMap<String, Source>>,
+}
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a source that must keep beating; the clock starts now
+    pub fn expect(&self, source: &str, max_silence: Duration) {
+        self.sources.lock().unwrap().insert(source.to_string(), Source {
+            last_beat: Instant::now(),
+            max_silence,
+        });
+    }
+
+    /// Beats from sources that were never registered are ignored
+    pub fn beat(&self, source: &str) {
+        if let Some(entry) = self.sources.lock().unwrap().get_mut(source) {
+            entry.last_beat = Instant::now();
+        }
+    }
+
+    /// Sources silent for longer than they are allowed, with how long they have been quiet
+    pub fn stale_sources(&self) -> Vec<(String, Duration)> {
+        let mut stale: Vec<(String, Duration)> = self.sources.lock().unwrap()
+            .iter()
+            .map(|(name, source)| (name.clone(), source.last_beat.elapsed(), source.max_silence))
+            .filter(|(_, silence, max_silence)| silence > max_silence)
+            .map(|(name, silence, _)| (name, silence))
+            .collect();
+        stale.sort_by(|a, b| a.0.cmp(&b.0));
+        stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_silent_sources() {
+        let heartbeat = Heartbeat::new();
+        heartbeat.expect("risk", Duration::ZERO);
+        heartbeat.expect("discovery", Duration::from_secs(3600));
+        heartbeat.beat("unregistered");
+
+        std::thread::sleep(Duration::from_millis(2));
+        let stale = heartbeat.stale_sources();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].0, "risk");
+    }
+}
@@ -0,0 +1,281 @@
+// Config Hot Reload - Safe Settings Change Without a Restart
+// Watches the config files and applies the settings that may change at runtime; risk limits
+// only tighten, and only with ALLOW_RISK_RELOAD=true
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use tokio::task::JoinHandle;
+use tracing::{info, warn, error};
+
+use crate::config::{self, AppConfig, Profile};
+use crate::core::risk_manager::RiskManager;
+use crate::core::tuning::{SharedTuning, TuningUpdate};
+use crate::risk::{LimitUpdate, RiskConfig};
+
+/// Discovery fields the control API can already tune at runtime
+const TUNING_FIELDS: [&str; 5] = [
+    "hypotheses_per_hour", "min_win_rate", "min_tests_required", "evolution_interval_hours", "evolution_min_interval_hours",
+];
+
+/// Risk fields a LimitUpdate carries; caps per asset or venue, tiers and stops need a restart
+const SCALAR_LIMITS: [&str; 11] = [
+    "max_position_size_pct", "max_daily_drawdown_pct", "max_var_1d_pct", "max_concurrent_positions", "min_win_rate",
+    "kelly_fraction", "max_order_pct", "loss_15min_pct", "loss_1hr_pct", "max_asset_exposure_pct", "max_venue_exposure_pct",
+];
+
+/// What one reload changes, by dotted field path
+#[derive(Debug, Default, PartialEq)]
+pub struct ReloadPlan {
+    pub symbol_universe: Option<Vec<String>>,
+    pub alerts: bool,                 // routing, dedup window or rate limit
+    pub tuning: Option<TuningUpdate>,
+    pub limits: Option<LimitUpdate>,  // only with ALLOW_RISK_RELOAD
+    pub refused: Vec<String>,         // risk fields left at their running values
+    pub rejected: Vec<(String, String)>,  // changed fields that didn't parse, and why
+    pub needs_restart: Vec<String>,
+}
+
+impl ReloadPlan {
+    pub fn between(old: &AppConfig, new: &AppConfig, allow_risk: bool) -> Self {
+        let before = serde_json::to_value(old).unwrap_or_default();
+        let after = serde_json::to_value(new).unwrap_or_default();
+        let mut changed = Vec::new();
+        changed_paths(&before, &after, "", &mut changed);
+
+        let mut plan = ReloadPlan::default();
+        let mut tuning = Map::new();
+        let mut limits = Map::new();
+        for path in changed {
+            let (section, field) = path.split_once('.').unwrap_or((path.as_str(), ""));
+            match section {
+                "symbol_universe" => plan.symbol_universe = Some(new.symbol_universe.clone()),
+                "alerts" => plan.alerts = true,
+                "discovery" if TUNING_FIELDS.contains(&field) => {
+                    // Unsetting the rate hands it back to the test budget, which the update spells as 0
+                    let value = after["discovery"][field].clone();
+                    tuning.insert(field.to_string(), if value.is_null() { Value::from(0) } else { value });
+                }
+                "risk" if allow_risk && SCALAR_LIMITS.contains(&field) => {
+                    limits.insert(field.to_string(), after["risk"][field].clone());
+                }
+                "risk" => plan.refused.push(path),
+                _ => plan.needs_restart.push(path),
+            }
+        }
+        plan.tuning = parse_update("discovery", tuning, &mut plan.rejected);
+        plan.limits = parse_update("risk", limits, &mut plan.rejected);
+        plan
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == ReloadPlan::default()
+    }
+}
+
+/// The update the changed `fields` of `section` spell, or None with the parse error in `rejected`
+fn parse_update<T: DeserializeOwned>(
+    section: &str,
+    fields: Map<String, Value>,
+    rejected: &mut Vec<(String, String)>,
+) -> Option<T> {
+    if fields.is_empty() {
+        return None;
+    }
+    let paths = fields.keys().map(|field| format!("{}.{}", section, field)).collect::<Vec<_>>().join(", ");
+    match serde_json::from_value(Value::Object(fields)) {
+        Ok(update) => Some(update),
+        Err(e) => {
+            rejected.push((paths, e.to_string()));
+            None
+        }
+    }
+}
+
+/// Dotted paths of every leaf that differs; arrays compare whole
+fn changed_paths(before: &Value, after: &Value, prefix: &str, out: &mut Vec<String>) {
+    match (before, after) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys().filter(|k| !a.contains_key(*k))).collect();
+            keys.sort();
+            for key in keys {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                changed_paths(a.get(key).unwrap_or(&Value::Null), b.get(key).unwrap_or(&Value::Null), &path, out);
+            }
+        }
+        (a, b) if a != b => out.push(prefix.to_string()),
+        _ => {}
+    }
+}
+
+pub struct ConfigWatcher {
+    current: AppConfig,  // as last read from disk
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    allow_risk: bool,
+    risk_manager: Arc<RiskManager>,
+    tuning: SharedTuning,
+}
+
+impl ConfigWatcher {
+    /// `current` is the config the process started with
+    pub fn new(current: AppConfig, risk_manager: Arc<RiskManager>, tuning: SharedTuning) -> Self {
+        let files = [AppConfig::path(), RiskConfig::path()]
+            .into_iter()
+            .chain(config::profile_name().map(|name| Profile::path(&name)))
+            .map(PathBuf::from)
+            .map(|path| {
+                let seen = modified(&path);
+                (path, seen)
+            })
+            .collect();
+        let allow_risk = std::env::var("ALLOW_RISK_RELOAD").map(|v| v == "true").unwrap_or(false);
+        ConfigWatcher { current, files, allow_risk, risk_manager, tuning }
+    }
+
+    pub fn allow_risk(&self) -> bool {
+        self.allow_risk
+    }
+
+    /// Reload and apply if a file changed since the last look
+    pub fn check(&mut self) -> Option<ReloadPlan> {
+        let mut edited = false;
+        for (path, seen) in &mut self.files {
+            let now = modified(path);
+            if now != *seen {
+                *seen = now;
+                edited = true;
+            }
+        }
+        if !edited {
+            return None;
+        }
+
+        let next = match AppConfig::load() {
+            Ok(next) => next,
+            Err(e) => {
+                error!("❌ Config reload rejected, nothing changed: {}", e);
+                return None;
+            }
+        };
+        let plan = ReloadPlan::between(&self.current, &next, self.allow_risk);
+        self.apply(&plan, &next);
+        self.current = next;
+        Some(plan)
+    }
+
+    fn apply(&self, plan: &ReloadPlan, next: &AppConfig) {
+        if let Some(symbols) = &plan.symbol_universe {
+            self.risk_manager.set_symbol_universe(symbols.clone());
+            info!("🔄 Symbol universe now {}", symbols.join(", "));
+        }
+        if plan.alerts {
+            match self.risk_manager.alerts() {
+                Some(alerts) => {
+                    alerts.set_config(next.alerts.alert_config());
+                    info!("🔄 Alert routing reloaded");
+                }
+                None => warn!("⚠️ Alert settings changed but no alert manager is running"),
+            }
+        }
+        if let Some(update) = &plan.tuning {
+            let mut tuning = self.tuning.lock().unwrap();
+            match tuning.with_update(update) {
+                Ok(next) => {
+                    *tuning = next;
+                    info!("🔄 Discovery tuning reloaded: {:?}", next);
+                }
+                Err(errors) => error!("❌ Reloaded discovery tuning refused: {}", errors.join("; ")),
+            }
+        }
+        if let Some(update) = &plan.limits {
+            if let Err(errors) = self.risk_manager.update_limits(update, true) {
+                error!("❌ Reloaded risk limits refused: {}", errors.join("; "));
+            }
+        }
+        for (paths, reason) in &plan.rejected {
+            error!("❌ Reloaded {} not applied, they don't parse: {}", paths, reason);
+        }
+        for path in &plan.refused {
+            let hint = if self.allow_risk { "needs a restart" } else { "needs a restart or ALLOW_RISK_RELOAD=true" };
+            warn!("🛡️ {} changed on disk but risk limits are hard limits; it {}", path, hint);
+        }
+        if !plan.needs_restart.is_empty() {
+            warn!("🔄 {} take effect on the next restart", plan.needs_restart.join(", "));
+        }
+    }
+
+    /// Poll the files every `every`
+    pub fn start(mut self, every: Duration) -> JoinHandle<()> {
+        info!("🔄 Watching {} for edits{}",
+            self.files.iter().map(|(p, _)| p.display().to_string()).collect::<Vec<_>>().join(" and "),
+            if self.allow_risk { " (risk limits included)" } else { "" });
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            loop {
+                interval.tick().await;
+                self.check();
+            }
+        })
+    }
+}
+
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_plan_applies_safe_fields_and_guards_risk() {
+        let old = AppConfig::default();
+        let mut new = old.clone();
+        new.symbol_universe.push("PEPE-USD".to_string());
+        new.alerts.critical = Some(vec!["email".to_string()]);
+        new.discovery.hypotheses_per_hour = Some(80);
+        new.discovery.budget.max_test_capital = dec!(100);
+        new.phases.sentiment_secs = 120;
+        new.risk.kelly_fraction = 0.20;
+        new.risk.capital_tiers.clear();
+
+        let guarded = ReloadPlan::between(&old, &new, false);
+        assert_eq!(guarded.symbol_universe.as_ref().map(Vec::len), Some(5));
+        assert!(guarded.alerts);
+        assert_eq!(guarded.tuning, Some(TuningUpdate { hypotheses_per_hour: Some(80), ..TuningUpdate::default() }));
+        assert_eq!(guarded.limits, None);
+        assert_eq!(guarded.refused, vec!["risk.capital_tiers", "risk.kelly_fraction"]);
+        assert_eq!(guarded.needs_restart, vec!["discovery.budget.max_test_capital", "phases.sentiment_secs"]);
+
+        let allowed = ReloadPlan::between(&old, &new, true);
+        assert_eq!(allowed.limits, Some(LimitUpdate { kelly_fraction: Some(0.20), ..LimitUpdate::default() }));
+        assert_eq!(allowed.refused, vec!["risk.capital_tiers"]);
+
+        // Unsetting the rate returns discovery to the test budget
+        let back = ReloadPlan::between(&new, &old, false);
+        assert_eq!(back.tuning.and_then(|t| t.hypotheses_per_hour), Some(0));
+        assert!(ReloadPlan::between(&old, &old, false).is_empty());
+        assert!(allowed.rejected.is_empty());
+    }
+
+    #[test]
+    fn test_updates_that_dont_parse_are_rejected_with_the_reason() {
+        let mut rejected = Vec::new();
+        let mut fields = Map::new();
+        fields.insert("hypotheses_per_hour".to_string(), Value::from(-5));
+        fields.insert("min_win_rate".to_string(), Value::from(0.6));
+
+        let update: Option<TuningUpdate> = parse_update("discovery", fields, &mut rejected);
+        assert_eq!(update, None);
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].0, "discovery.hypotheses_per_hour, discovery.min_win_rate");
+        assert!(rejected[0].1.contains("-5"), "{}", rejected[0].1);
+
+        // Nothing changed is no update and nothing rejected
+        assert_eq!(parse_update::<LimitUpdate>("risk", Map::new(), &mut rejected), None);
+        assert_eq!(rejected.len(), 1);
+    }
+}
@@ -0,0 +1,354 @@
+// Incidents - Escalating an Emergency Stop
+// A chat message can be missed; an emergency stop opens an incident with the on-call
+// provider (PagerDuty or Opsgenie) carrying the drawdown and every open position at the
+// moment of the halt. Trading can only be re-armed once someone has acknowledged that
+// incident, so a halt is never cleared by a person who does not know why it happened
+
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+use tracing::{error, info, warn};
+
+use crate::core::secrets;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Incident {
+    pub dedup_key: String,
+    pub summary: String,
+    pub details: Value,  // drawdown figures and open positions
+    pub opened_at: DateTime<Utc>,
+}
+
+impl Incident {
+    pub fn new(summary: impl Into<String>, details: Value) -> Self {
+        let opened_at = Utc::now();
+        Incident {
+            dedup_key: format!("v26meme-emergency-stop-{}", opened_at.timestamp()),
+            summary: summary.into(),
+            details,
+            opened_at,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum IncidentError {
+    Http(String),
+    Malformed(String),
+}
+
+impl std::fmt::Display for IncidentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IncidentError::Http(e) => write!(f, "request failed: {}", e),
+            IncidentError::Malformed(e) => write!(f, "unexpected response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for IncidentError {}
+
+impl From<reqwest::Error> for IncidentError {
+    fn from(e: reqwest::Error) -> Self {
+        IncidentError::Http(e.to_string())
+    }
+}
+
+#[async_trait]
+pub trait IncidentService: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn open(&self, incident: &Incident) -> Result<(), IncidentError>;
+
+    /// Whether a responder has acknowledged (or already resolved) the incident
+    async fn is_acknowledged(&self, dedup_key: &str) -> Result<bool, IncidentError>;
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Events API v2 to open; the REST API (read-only key) to check acknowledgement
+pub struct PagerDuty {
+    routing_key: String,
+    api_key: String,
+    http: reqwest::Client,
+}
+
+impl PagerDuty {
+    pub fn new(routing_key: String, api_key: String) -> Self {
+        PagerDuty { routing_key, api_key, http: http_client() }
+    }
+}
+
+pub fn pagerduty_event(routing_key: &str, incident: &Incident) -> Value {
+    json!({
+        "routing_key": routing_key,
+        "event_action": "trigger",
+        "dedup_key": incident.dedup_key,
+        "payload": {
+            "summary": incident.summary,
+            "source": "v26meme",
+            "severity": "critical",
+            "timestamp": incident.opened_at.to_rfc3339(),
+            "custom_details": incident.details,
+        },
+    })
+}
+
+/// From GET /incidents?incident_key=…; None when no incident carries the key yet
+pub fn pagerduty_acknowledged(response: &Value) -> Option<bool> {
+    let incident = response.get("incidents")?.as_array()?.first()?;
+    Some(matches!(incident.get("status")?.as_str()?, "acknowledged" | "resolved"))
+}
+
+#[async_trait]
+impl IncidentService for PagerDuty {
+    fn name(&self) -> &str {
+        "pagerduty"
+    }
+
+    async fn open(&self, incident: &Incident) -> Result<(), IncidentError> {
+        self.http.post("https://events.pagerduty.com/v2/enqueue")
+            .json(&pagerduty_event(&self.routing_key, incident))
+            .send().await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    async fn is_acknowledged(&self, dedup_key: &str) -> Result<bool, IncidentError> {
+        let response: Value = self.http.get("https://api.pagerduty.com/incidents")
+            .query(&[("incident_key", dedup_key)])
+            .header("Authorization", format!("Token token={}", self.api_key))
+            .header("Accept", "application/vnd.pagerduty+json;version=2")
+            .send().await?
+            .error_for_status()?
+            .json().await?;
+        pagerduty_acknowledged(&response)
+            .ok_or_else(|| IncidentError::Malformed(format!("no incident with key {}", dedup_key)))
+    }
+}
+
+/// Alert API; the dedup key is the alert alias
+pub struct Opsgenie {
+    api_url: String,  // https://api.opsgenie.com, or api.eu.opsgenie.com for EU accounts
+    api_key: String,
+    http: reqwest::Client,
+}
+
+impl Opsgenie {
+    pub fn new(api_url: String, api_key: String) -> Self {
+        Opsgenie { api_url: api_url.trim_end_matches('/').to_string(), api_key, http: http_client() }
+    }
+}
+
+/// From GET /v2/alerts/{alias}?identifierType=alias
+pub fn opsgenie_acknowledged(response: &Value) -> Option<bool> {
+    let alert = response.get("data")?;
+    let acknowledged = alert.get("acknowledged")?.as_bool()?;
+    Some(acknowledged || alert.get("status").and_then(Value::as_str) == Some("closed"))
+}
+
+#[async_trait]
+impl IncidentService for Opsgenie {
+    fn name(&self) -> &str {
+        "opsgenie"
+    }
+
+    async fn open(&self, incident: &Incident) -> Result<(), IncidentError> {
+        // Opsgenie details are a flat string map
+        let details: serde_json::Map<String, Value> = incident.details.as_object()
+            .map(|d| d.iter().map(|(k, v)| (k.clone(), Value::String(v.to_string()))).collect())
+            .unwrap_or_default();
+        self.http.post(format!("{}/v2/alerts", self.api_url))
+            .header("Authorization", format!("GenieKey {}", self.api_key))
+            .json(&json!({
+                "message": incident.summary.chars().take(130).collect::<String>(),
+                "alias": incident.dedup_key,
+                "description": serde_json::to_string_pretty(&incident.details).unwrap_or_default(),
+                "details": details,
+                "priority": "P1",
+                "source": "v26meme",
+            }))
+            .send().await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    async fn is_acknowledged(&self, dedup_key: &str) -> Result<bool, IncidentError> {
+        let response: Value = self.http.get(format!("{}/v2/alerts/{}", self.api_url, dedup_key))
+            .query(&[("identifierType", "alias")])
+            .header("Authorization", format!("GenieKey {}", self.api_key))
+            .send().await?
+            .error_for_status()?
+            .json().await?;
+        opsgenie_acknowledged(&response)
+            .ok_or_else(|| IncidentError::Malformed("alert has no acknowledged field".to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IncidentState {
+    Opening(String),  // dedup key; the request is still in flight
+    Open(String),
+    Failed(String),   // the provider never accepted it; nothing to acknowledge
+}
+
+/// Why re-arming was refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum RearmBlocked {
+    NotHalted,
+    IncidentOpening(String),
+    Unacknowledged(String),
+    CheckFailed(String),
+}
+
+impl std::fmt::Display for RearmBlocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RearmBlocked::NotHalted => write!(f, "no emergency stop is active"),
+            RearmBlocked::IncidentOpening(key) => write!(f, "incident {} is still being opened", key),
+            RearmBlocked::Unacknowledged(key) => write!(f, "incident {} has not been acknowledged", key),
+            RearmBlocked::CheckFailed(e) => write!(f, "could not check the incident: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RearmBlocked {}
+
+pub struct IncidentManager {
+    service: Option<Box<dyn IncidentService>>,
+    current: Mutex<Option<IncidentState>>,
+}
+
+impl IncidentManager {
+    pub fn new(service: Option<Box<dyn IncidentService>>) -> Self {
+        IncidentManager { service, current: Mutex::new(None) }
+    }
+
+    /// INCIDENT_PROVIDER=pagerduty|opsgenie with that provider's keys; anything else disables
+    /// escalation, and re-arming then needs no acknowledgement
+    pub fn from_env() -> Self {
+        let var = |name: &str| secrets::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+        let service: Option<Box<dyn IncidentService>> = match var("INCIDENT_PROVIDER").as_deref() {
+            Some("pagerduty") => match (var("PAGERDUTY_ROUTING_KEY"), var("PAGERDUTY_API_KEY")) {
+                (Some(routing_key), Some(api_key)) => Some(Box::new(PagerDuty::new(routing_key, api_key))),
+                _ => {
+                    error!("❌ PagerDuty escalation needs PAGERDUTY_ROUTING_KEY and PAGERDUTY_API_KEY");
+                    None
+                }
+            },
+            Some("opsgenie") => match var("OPSGENIE_API_KEY") {
+                Some(api_key) => {
+                    let api_url = var("OPSGENIE_API_URL").unwrap_or_else(|| "https://api.opsgenie.com".to_string());
+                    Some(Box::new(Opsgenie::new(api_url, api_key)))
+                }
+                None => {
+                    error!("❌ Opsgenie escalation needs OPSGENIE_API_KEY");
+                    None
+                }
+            },
+            Some(other) => {
+                error!("❌ Unknown INCIDENT_PROVIDER {}", other);
+                None
+            }
+            None => None,
+        };
+        IncidentManager::new(service)
+    }
+
+    pub fn provider(&self) -> Option<&str> {
+        self.service.as_ref().map(|s| s.name())
+    }
+
+    pub fn current(&self) -> Option<IncidentState> {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// Mark the incident as opening right away, so nothing can re-arm in the gap, and open
+    /// it in the background
+    pub fn escalate(self: &Arc<Self>, incident: Incident) {
+        if self.service.is_none() {
+            return;
+        }
+        *self.current.lock().unwrap() = Some(IncidentState::Opening(incident.dedup_key.clone()));
+
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("⚠️ No runtime to open incident {}", incident.dedup_key);
+            *self.current.lock().unwrap() = Some(IncidentState::Failed(incident.dedup_key));
+            return;
+        };
+        let manager = self.clone();
+        runtime.spawn(async move { manager.open(incident).await });
+    }
+
+    pub async fn open(&self, incident: Incident) {
+        let Some(service) = &self.service else {
+            return;
+        };
+        *self.current.lock().unwrap() = Some(IncidentState::Opening(incident.dedup_key.clone()));
+
+        let state = match service.open(&incident).await {
+            Ok(()) => {
+                info!("📟 Opened {} incident {}", service.name(), incident.dedup_key);
+                IncidentState::Open(incident.dedup_key)
+            }
+            Err(e) => {
+                error!("❌ Failed to open {} incident {}: {}", service.name(), incident.dedup_key, e);
+                IncidentState::Failed(incident.dedup_key)
+            }
+        };
+        *self.current.lock().unwrap() = Some(state);
+    }
+
+    /// Ok once re-arming is allowed: no provider, an incident that never opened, or an
+    /// acknowledged one
+    pub async fn check_rearm(&self) -> Result<(), RearmBlocked> {
+        let (Some(service), Some(state)) = (&self.service, self.current()) else {
+            return Ok(());
+        };
+        match state {
+            IncidentState::Opening(key) => Err(RearmBlocked::IncidentOpening(key)),
+            IncidentState::Failed(key) => {
+                warn!("⚠️ Re-arming without acknowledgement: incident {} was never opened", key);
+                Ok(())
+            }
+            IncidentState::Open(key) => match service.is_acknowledged(&key).await {
+                Ok(true) => Ok(()),
+                Ok(false) => Err(RearmBlocked::Unacknowledged(key)),
+                Err(e) => Err(RearmBlocked::CheckFailed(e.to_string())),
+            },
+        }
+    }
+
+    /// Forget the incident once trading is re-armed
+    pub fn clear(&self) {
+        *self.current.lock().unwrap() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_payloads_and_acknowledgement() {
+        let incident = Incident::new("EMERGENCY STOP", json!({ "drawdown_pct": 10.4, "positions": [] }));
+        let event = pagerduty_event("routing", &incident);
+        assert_eq!(event["dedup_key"], incident.dedup_key);
+        assert_eq!(event["payload"]["custom_details"]["drawdown_pct"], 10.4);
+
+        assert_eq!(pagerduty_acknowledged(&json!({ "incidents": [{ "status": "triggered" }] })), Some(false));
+        assert_eq!(pagerduty_acknowledged(&json!({ "incidents": [{ "status": "acknowledged" }] })), Some(true));
+        assert_eq!(pagerduty_acknowledged(&json!({ "incidents": [] })), None);
+
+        assert_eq!(opsgenie_acknowledged(&json!({ "data": { "acknowledged": false, "status": "open" } })), Some(false));
+        assert_eq!(opsgenie_acknowledged(&json!({ "data": { "acknowledged": false, "status": "closed" } })), Some(true));
+    }
+}
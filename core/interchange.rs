@@ -0,0 +1,378 @@
+// Hypothesis Interchange Format
+// Versioned JSON/TOML bundles of hypotheses and patterns, so corpora can be shared
+// between instances, backed up selectively, or hand-authored for seeding. Imports start
+// every pattern over unless the source is trusted (a migration, or a research copy), in
+// which case stats and activation come across too
+
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+
+use crate::core::discovery_engine::{Condition, Hypothesis, Pattern};
+use crate::core::execution_style::ExecutionStyle;
+use crate::core::genome;
+use crate::core::money::Decimal;
+
+pub const FORMAT_NAME: &str = "v26meme.patterns";
+/// 1: hypotheses serialized as bare structs
+/// 2: hypotheses serialized as versioned genomes (see genome.rs); version 1 bundles still load
+pub const FORMAT_VERSION: u32 = 2;
+
+const OPERATORS: [&str; 5] = [">", "<", "==", "crosses_above", "crosses_below"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    #[serde(default, with = "genome::serde_hypotheses")]
+    pub hypotheses: Vec<Hypothesis>,
+    #[serde(default)]
+    pub patterns: Vec<Pattern>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleFormat {
+    Json,
+    Toml,
+}
+
+impl BundleFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Some(BundleFormat::Json),
+            "toml" => Some(BundleFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Pick the format from a file extension, defaulting to JSON
+    pub fn from_path(path: &str) -> Self {
+        if path.to_lowercase().ends_with(".toml") {
+            BundleFormat::Toml
+        } else {
+            BundleFormat::Json
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BundleError {
+    Parse(String),
+    Invalid(Vec<String>),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for BundleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BundleError::Parse(e) => write!(f, "could not parse bundle: {}", e),
+            BundleError::Invalid(errors) => write!(f, "invalid bundle: {}", errors.join("; ")),
+            BundleError::Database(e) => write!(f, "database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+impl From<sqlx::Error> for BundleError {
+    fn from(e: sqlx::Error) -> Self {
+        BundleError::Database(e)
+    }
+}
+
+impl PatternBundle {
+    pub fn new(hypotheses: Vec<Hypothesis>, patterns: Vec<Pattern>) -> Self {
+        PatternBundle {
+            format: FORMAT_NAME.to_string(),
+            version: FORMAT_VERSION,
+            exported_at: Utc::now(),
+            hypotheses,
+            patterns,
+        }
+    }
+
+    pub fn to_text(&self, format: BundleFormat) -> Result<String, BundleError> {
+        match format {
+            BundleFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| BundleError::Parse(e.to_string())),
+            BundleFormat::Toml => toml::to_string_pretty(self)
+                .map_err(|e| BundleError::Parse(e.to_string())),
+        }
+    }
+
+    /// Parse and validate; an invalid bundle is never returned
+    pub fn parse(input: &str, format: BundleFormat) -> Result<Self, BundleError> {
+        let bundle: PatternBundle = match format {
+            BundleFormat::Json => serde_json::from_str(input)
+                .map_err(|e| BundleError::Parse(e.to_string()))?,
+            BundleFormat::Toml => toml::from_str(input)
+                .map_err(|e| BundleError::Parse(e.to_string()))?,
+        };
+
+        let errors = bundle.validate();
+        if !errors.is_empty() {
+            return Err(BundleError::Invalid(errors));
+        }
+
+        Ok(bundle)
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if self.format != FORMAT_NAME {
+            errors.push(format!("unknown format '{}'", self.format));
+        }
+        if self.version == 0 || self.version > FORMAT_VERSION {
+            errors.push(format!("unsupported version {} (max {})", self.version, FORMAT_VERSION));
+        }
+
+        for h in &self.hypotheses {
+            validate_hypothesis(h, &mut errors);
+        }
+
+        for p in &self.patterns {
+            validate_hypothesis(&p.hypothesis, &mut errors);
+            if p.hash != p.hypothesis.hash {
+                errors.push(format!("pattern {}: hash does not match its hypothesis", p.hash));
+            }
+            if p.win_count > p.test_count {
+                errors.push(format!("pattern {}: win_count exceeds test_count", p.hash));
+            }
+            if !(0.0..=1.0).contains(&p.win_rate) {
+                errors.push(format!("pattern {}: win_rate {} outside 0-1", p.hash, p.win_rate));
+            }
+        }
+
+        errors
+    }
+}
+
+fn validate_hypothesis(h: &Hypothesis, errors: &mut Vec<String>) {
+    if h.hash.is_empty() || h.hash.len() > 64 || !h.hash.chars().all(|c| c.is_ascii_alphanumeric()) {
+        errors.push(format!("hypothesis '{}': hash must be 1-64 alphanumeric chars", h.hash));
+    }
+    if h.entry_conditions.is_empty() {
+        errors.push(format!("hypothesis {}: no entry conditions", h.hash));
+    }
+    if h.exit_conditions.is_empty() {
+        errors.push(format!("hypothesis {}: no exit conditions", h.hash));
+    }
+    if h.timeframe == 0 || h.timeframe > 1440 {
+        errors.push(format!("hypothesis {}: timeframe {} outside 1-1440 minutes", h.hash, h.timeframe));
+    }
+
+    for c in h.entry_conditions.iter().chain(h.exit_conditions.iter()) {
+        validate_condition(&h.hash, c, errors);
+    }
+}
+
+fn validate_condition(hash: &str, c: &Condition, errors: &mut Vec<String>) {
+    if c.metric.is_empty() {
+        errors.push(format!("hypothesis {}: condition with empty metric", hash));
+    }
+    if !OPERATORS.contains(&c.operator.as_str()) {
+        errors.push(format!("hypothesis {}: unknown operator '{}'", hash, c.operator));
+    }
+    if !c.value.is_finite() {
+        errors.push(format!("hypothesis {}: non-finite threshold on {}", hash, c.metric));
+    }
+    if !(0.0..=1.0).contains(&c.weight) {
+        errors.push(format!("hypothesis {}: weight {} outside 0-1 on {}", hash, c.weight, c.metric));
+    }
+}
+
+/// Export hypotheses (all, or only active patterns) from the database
+pub async fn export_bundle(db_pool: &PgPool, active_only: bool) -> Result<PatternBundle, BundleError> {
+    let query = "
+        SELECT pattern_hash, entry_conditions, exit_conditions, timeframe_minutes,
+               EXTRACT(EPOCH FROM created_at)::bigint AS created_at,
+               test_count, win_count, total_profit,
+               win_rate::float8 AS win_rate, sharpe_ratio::float8 AS sharpe_ratio,
+               is_active, generation, parent_patterns, execution_style, symbol
+        FROM discovered_patterns
+        WHERE is_active = true OR NOT $1
+        ORDER BY created_at
+    ";
+
+    let rows = sqlx::query(query)
+        .bind(active_only)
+        .fetch_all(db_pool)
+        .await?;
+
+    let mut hypotheses = Vec::new();
+    let mut patterns = Vec::new();
+
+    for row in rows {
+        let entry: serde_json::Value = row.get("entry_conditions");
+        let exit: serde_json::Value = row.get("exit_conditions");
+
+        let hypothesis = Hypothesis {
+            hash: row.get("pattern_hash"),
+            entry_conditions: serde_json::from_value(entry).map_err(|e| BundleError::Parse(e.to_string()))?,
+            exit_conditions: serde_json::from_value(exit).map_err(|e| BundleError::Parse(e.to_string()))?,
+            timeframe: row.get::<Option<i32>, _>("timeframe_minutes").unwrap_or(0) as u32,
+            created_at: row.get::<Option<i64>, _>("created_at").unwrap_or(0),
+            symbol: row.get("symbol"),
+        };
+
+        let test_count = row.get::<Option<i32>, _>("test_count").unwrap_or(0);
+        if test_count > 0 {
+            patterns.push(Pattern {
+                hash: hypothesis.hash.clone(),
+                hypothesis,
+                test_count: test_count as u32,
+                win_count: row.get::<Option<i32>, _>("win_count").unwrap_or(0) as u32,
+                total_profit: row.get::<Option<Decimal>, _>("total_profit").unwrap_or_default(),
+                win_rate: row.get::<Option<f64>, _>("win_rate").unwrap_or(0.0),
+                sharpe_ratio: row.get::<Option<f64>, _>("sharpe_ratio").unwrap_or(0.0),
+                is_active: row.get::<Option<bool>, _>("is_active").unwrap_or(false),
+                generation: row.get::<Option<i32>, _>("generation").unwrap_or(0) as u32,
+                parent_patterns: row.get::<Option<Vec<String>>, _>("parent_patterns").unwrap_or_default(),
+                execution_style: ExecutionStyle::parse(row.get("execution_style")).unwrap_or_default(),
+            });
+        } else {
+            hypotheses.push(hypothesis);
+        }
+    }
+
+    Ok(PatternBundle::new(hypotheses, patterns))
+}
+
+/// Import a bundle; hypotheses already known here are left untouched
+/// By default foreign stats are not trusted: every imported pattern arrives inactive and must
+/// re-earn activation. `keep_stats` carries test counts, win rate, profit, Sharpe, execution
+/// style and activation across, for bundles exported from an instance you control
+/// Returns the number of hypotheses that were new to this instance
+pub async fn import_bundle(db_pool: &PgPool, bundle: &PatternBundle, keep_stats: bool) -> Result<u64, BundleError> {
+    let query = "
+        INSERT INTO discovered_patterns
+        (pattern_hash, entry_conditions, exit_conditions, timeframe_minutes, generation, parent_patterns, symbol, created_at,
+         test_count, win_count, total_profit, win_rate, sharpe_ratio, is_active, execution_style)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW(), $8, $9, $10, $11, $12, $13, $14)
+        ON CONFLICT (pattern_hash) DO NOTHING
+    ";
+
+    let lineage = bundle.patterns.iter().map(|p| (&p.hypothesis, p.generation, p.parent_patterns.clone(), Some(p).filter(|_| keep_stats)));
+    let plain = bundle.hypotheses.iter().map(|h| (h, 0, Vec::new(), None));
+
+    let mut imported = 0;
+    for (h, generation, parents, stats) in plain.chain(lineage) {
+        let result = sqlx::query(query)
+            .bind(&h.hash)
+            .bind(serde_json::to_value(&h.entry_conditions).unwrap_or_default())
+            .bind(serde_json::to_value(&h.exit_conditions).unwrap_or_default())
+            .bind(h.timeframe as i32)
+            .bind(generation as i32)
+            .bind(&parents)
+            .bind(&h.symbol)
+            .bind(stats.map_or(0, |p| p.test_count as i32))
+            .bind(stats.map_or(0, |p| p.win_count as i32))
+            .bind(stats.map_or(Decimal::ZERO, |p| p.total_profit))
+            .bind(stats.map_or(0.0, |p| p.win_rate))
+            .bind(stats.map_or(0.0, |p| p.sharpe_ratio))
+            .bind(stats.is_some_and(|p| p.is_active))
+            .bind(stats.map_or(ExecutionStyle::default(), |p| p.execution_style).as_str())
+            .execute(db_pool)
+            .await?;
+        imported += result.rows_affected();
+    }
+
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn hypothesis(hash: &str) -> Hypothesis {
+        let condition = Condition {
+            metric: "price_delta_5m".to_string(),
+            operator: ">".to_string(),
+            value: 1.5,
+            weight: 0.5,
+        };
+        Hypothesis {
+            hash: hash.to_string(),
+            entry_conditions: vec![condition.clone()],
+            exit_conditions: vec![condition],
+            timeframe: 15,
+            created_at: 0,
+            symbol: None,
+        }
+    }
+
+    #[test]
+    fn test_round_trip_json_and_toml() {
+        let bundle = PatternBundle::new(vec![hypothesis("abc123")], vec![]);
+
+        for format in [BundleFormat::Json, BundleFormat::Toml] {
+            let text = bundle.to_text(format).unwrap();
+            let parsed = PatternBundle::parse(&text, format).unwrap();
+            assert_eq!(parsed.hypotheses[0].hash, "abc123");
+            assert_eq!(parsed.version, FORMAT_VERSION);
+        }
+    }
+
+    #[test]
+    fn test_loads_version_1_bundles() {
+        let v1 = r#"{
+            "format": "v26meme.patterns",
+            "version": 1,
+            "exported_at": "2025-01-01T00:00:00Z",
+            "hypotheses": [{
+                "hash": "abc123",
+                "entry_conditions": [{"metric": "price_delta_5m", "operator": ">", "value": 1.5, "weight": 0.5}],
+                "exit_conditions": [{"metric": "price_delta_5m", "operator": "<", "value": -1.5, "weight": 0.5}],
+                "timeframe": 15,
+                "created_at": 0
+            }]
+        }"#;
+
+        let bundle = PatternBundle::parse(v1, BundleFormat::Json).unwrap();
+        assert_eq!(bundle.version, 1);
+        assert_eq!(bundle.hypotheses[0].entry_conditions[0].value, 1.5);
+    }
+
+    #[test]
+    fn test_pattern_stats_survive_a_round_trip() {
+        let pattern = Pattern {
+            hash: "abc123".to_string(),
+            hypothesis: hypothesis("abc123"),
+            test_count: 40,
+            win_count: 26,
+            total_profit: dec!(3.25),
+            win_rate: 0.65,
+            sharpe_ratio: 1.8,
+            is_active: true,
+            generation: 3,
+            parent_patterns: vec!["def456".to_string()],
+            execution_style: ExecutionStyle::Twap,
+        };
+        let text = PatternBundle::new(vec![], vec![pattern]).to_text(BundleFormat::Json).unwrap();
+
+        let parsed = &PatternBundle::parse(&text, BundleFormat::parse("JSON").unwrap()).unwrap().patterns[0];
+        assert_eq!((parsed.test_count, parsed.win_count, parsed.generation), (40, 26, 3));
+        assert_eq!((parsed.win_rate, parsed.sharpe_ratio, parsed.total_profit), (0.65, 1.8, dec!(3.25)));
+        assert!(parsed.is_active);
+        assert_eq!(parsed.execution_style, ExecutionStyle::Twap);
+        assert_eq!(BundleFormat::parse("yaml"), None);
+    }
+
+    #[test]
+    fn test_rejects_invalid_hypotheses() {
+        let mut bad = hypothesis("abc123");
+        bad.entry_conditions[0].operator = "~".to_string();
+        bad.timeframe = 0;
+
+        let bundle = PatternBundle::new(vec![bad], vec![]);
+        let text = bundle.to_text(BundleFormat::Json).unwrap();
+
+        match PatternBundle::parse(&text, BundleFormat::Json) {
+            Err(BundleError::Invalid(errors)) => assert_eq!(errors.len(), 2),
+            other => panic!("expected validation failure, got {:?}", other.map(|b| b.version)),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+// Trade Journal Export - Every Fill as a Row for pandas or a Spreadsheet
+// One record per fill from the fills table, with the pattern that traded it, the side, size,
+// price, fees and (on exits) realized PnL. CSV opens anywhere; Parquet keeps the types and
+// loads straight into pandas.read_parquet or DuckDB
+
+use std::io::Write;
+use std::sync::Arc;
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMicrosecondArray};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use chrono::{DateTime, Utc};
+use parquet::arrow::ArrowWriter;
+use parquet::errors::ParquetError;
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+
+use crate::core::money::{self, Decimal};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalRow {
+    pub filled_at: DateTime<Utc>,
+    pub client_order_id: String,
+    pub position_id: String,
+    pub pattern_hash: Option<String>,
+    pub symbol: String,
+    pub exchange: String,
+    pub side: Option<String>,  // buy or sell; None only for fills whose order and position are both gone
+    pub kind: String,          // entry or exit
+    pub size: Decimal,         // notional
+    pub price: Decimal,
+    pub fees: Decimal,
+    pub pnl: Option<Decimal>,  // exits only, net of fees
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalFormat {
+    Csv,
+    Parquet,
+}
+
+impl JournalFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "csv" => Some(JournalFormat::Csv),
+            "parquet" => Some(JournalFormat::Parquet),
+            _ => None,
+        }
+    }
+
+    /// Pick the format from a file extension, defaulting to CSV
+    pub fn from_path(path: &str) -> Self {
+        if path.to_lowercase().ends_with(".parquet") {
+            JournalFormat::Parquet
+        } else {
+            JournalFormat::Csv
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum JournalError {
+    Csv(csv::Error),
+    Parquet(ParquetError),
+}
+
+impl std::fmt::Display for JournalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JournalError::Csv(e) => write!(f, "csv: {}", e),
+            JournalError::Parquet(e) => write!(f, "parquet: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for JournalError {}
+
+impl From<csv::Error> for JournalError {
+    fn from(e: csv::Error) -> Self {
+        JournalError::Csv(e)
+    }
+}
+
+impl From<ParquetError> for JournalError {
+    fn from(e: ParquetError) -> Self {
+        JournalError::Parquet(e)
+    }
+}
+
+/// Fills in [from, to), oldest first, optionally for one pattern
+pub async fn load(
+    db_pool: &PgPool,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    pattern_hash: Option<&str>,
+) -> Result<Vec<JournalRow>, sqlx::Error> {
+    // The order row has the side; failing that, the position's stop gives its direction
+    let rows = sqlx::query("
+        SELECT f.filled_at, f.client_order_id, f.position_id, p.pattern_hash, f.symbol, f.exchange,
+               COALESCE(o.side, CASE
+                   WHEN p.position_id IS NULL THEN NULL
+                   WHEN (p.stop_loss <= p.entry_price) = (f.kind = 'entry') THEN 'buy'
+                   ELSE 'sell'
+               END) AS side,
+               f.kind, f.size, f.price, f.fees, f.pnl
+        FROM fills f
+        LEFT JOIN positions p ON p.position_id = f.position_id
+        LEFT JOIN orders o ON o.client_order_id = f.client_order_id
+        WHERE ($1::timestamptz IS NULL OR f.filled_at >= $1)
+          AND ($2::timestamptz IS NULL OR f.filled_at < $2)
+          AND ($3::text IS NULL OR p.pattern_hash = $3)
+        ORDER BY f.filled_at, f.id
+    ")
+        .bind(from)
+        .bind(to)
+        .bind(pattern_hash)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| JournalRow {
+        filled_at: row.get("filled_at"),
+        client_order_id: row.get("client_order_id"),
+        position_id: row.get("position_id"),
+        pattern_hash: row.get("pattern_hash"),
+        symbol: row.get("symbol"),
+        exchange: row.get("exchange"),
+        side: row.get("side"),
+        kind: row.get("kind"),
+        size: row.get("size"),
+        price: row.get("price"),
+        fees: row.get("fees"),
+        pnl: row.get("pnl"),
+    }).collect())
+}
+
+pub fn write<W: Write + Send>(rows: &[JournalRow], format: JournalFormat, out: W) -> Result<(), JournalError> {
+    match format {
+        JournalFormat::Csv => write_csv(rows, out),
+        JournalFormat::Parquet => write_parquet(rows, out),
+    }
+}
+
+/// Header row first; timestamps are RFC 3339, missing values are empty
+pub fn write_csv<W: Write>(rows: &[JournalRow], out: W) -> Result<(), JournalError> {
+    let mut writer = csv::Writer::from_writer(out);
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush().map_err(csv::Error::from)?;
+    Ok(())
+}
+
+/// One row group; filled_at is a UTC microsecond timestamp
+pub fn write_parquet<W: Write + Send>(rows: &[JournalRow], out: W) -> Result<(), JournalError> {
+    let text = |name: &str, nullable: bool| Field::new(name, DataType::Utf8, nullable);
+    let number = |name: &str, nullable: bool| Field::new(name, DataType::Float64, nullable);
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("filled_at", DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())), false),
+        text("client_order_id", false),
+        text("position_id", false),
+        text("pattern_hash", true),
+        text("symbol", false),
+        text("exchange", false),
+        text("side", true),
+        text("kind", false),
+        number("size", false),
+        number("price", false),
+        number("fees", false),
+        number("pnl", true),
+    ]));
+
+    let strings = |f: fn(&JournalRow) -> Option<&str>| -> ArrayRef {
+        Arc::new(rows.iter().map(f).collect::<StringArray>())
+    };
+    let numbers = |f: fn(&JournalRow) -> Option<f64>| -> ArrayRef {
+        Arc::new(rows.iter().map(f).collect::<Float64Array>())
+    };
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(TimestampMicrosecondArray::from(rows.iter().map(|r| r.filled_at.timestamp_micros()).collect::<Vec<_>>())
+            .with_timezone("UTC")),
+        strings(|r| Some(&r.client_order_id)),
+        strings(|r| Some(&r.position_id)),
+        strings(|r| r.pattern_hash.as_deref()),
+        strings(|r| Some(&r.symbol)),
+        strings(|r| Some(&r.exchange)),
+        strings(|r| r.side.as_deref()),
+        strings(|r| Some(&r.kind)),
+        numbers(|r| Some(money::to_f64(r.size))),
+        numbers(|r| Some(money::to_f64(r.price))),
+        numbers(|r| Some(money::to_f64(r.fees))),
+        numbers(|r| r.pnl.map(money::to_f64)),
+    ];
+
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(|e| ParquetError::ArrowError(e.to_string()))?;
+    let mut writer = ArrowWriter::try_new(out, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_csv_and_parquet_carry_every_fill() {
+        let at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let fill = |kind: &str, side: &str, price: Decimal, pnl: Option<Decimal>| JournalRow {
+            filled_at: at,
+            client_order_id: format!("{}-1", kind),
+            position_id: "entry-1".to_string(),
+            pattern_hash: Some("abc123".to_string()),
+            symbol: "DOGE-USD".to_string(),
+            exchange: "coinbase".to_string(),
+            side: Some(side.to_string()),
+            kind: kind.to_string(),
+            size: dec!(100),
+            price,
+            fees: dec!(0.6),
+            pnl,
+        };
+        let rows = vec![fill("entry", "buy", dec!(0.10), None), fill("exit", "sell", dec!(0.11), Some(dec!(9.4)))];
+
+        let mut csv = Vec::new();
+        write(&rows, JournalFormat::Csv, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "filled_at,client_order_id,position_id,pattern_hash,symbol,exchange,side,kind,size,price,fees,pnl");
+        assert_eq!(lines[1], "2023-11-14T22:13:20Z,entry-1,entry-1,abc123,DOGE-USD,coinbase,buy,entry,100,0.10,0.6,");
+        assert!(lines[2].ends_with(",sell,exit,100,0.11,0.6,9.4"));
+
+        let path = std::env::temp_dir().join(format!("journal-{}.parquet", uuid::Uuid::new_v4()));
+        write(&rows, JournalFormat::from_path(path.to_str().unwrap()), std::fs::File::create(&path).unwrap()).unwrap();
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), 2);
+        assert_eq!(metadata.schema_descr().num_columns(), 12);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,64 @@
+// Pipeline Latency
+// How long each hop of the trading pipeline takes: a tick to the signal it triggers, the
+// signal to its order reaching the venue, and the order to each fill. Always emitted as debug
+// events; built with the `otel` feature they are also an OTLP histogram, pipeline.latency
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use tracing::debug;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stage {
+    DataToSignal,
+    SignalToOrder,
+    OrderToFill,
+}
+
+impl Stage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::DataToSignal => "data_to_signal",
+            Stage::SignalToOrder => "signal_to_order",
+            Stage::OrderToFill => "order_to_fill",
+        }
+    }
+}
+
+/// Record the time from `since` until now; clocks that disagree never yield a negative latency
+pub fn record_since(stage: Stage, since: DateTime<Utc>, symbol: &str) {
+    let elapsed_ms = ((Utc::now() - since).num_microseconds().unwrap_or(i64::MAX) as f64 / 1000.0).max(0.0);
+    record(stage, elapsed_ms, symbol);
+}
+
+pub fn record(stage: Stage, elapsed_ms: f64, symbol: &str) {
+    debug!(stage = stage.as_str(), symbol, elapsed_ms, "pipeline latency");
+
+    #[cfg(feature = "otel")]
+    otel::record(stage, elapsed_ms, symbol);
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use std::sync::OnceLock;
+    use opentelemetry::metrics::Histogram;
+    use opentelemetry::{global, KeyValue};
+
+    use super::Stage;
+
+    static LATENCY: OnceLock<Histogram<f64>> = OnceLock::new();
+
+    /// Bound to the global meter provider on first use, so telemetry::init must run first
+    pub fn record(stage: Stage, elapsed_ms: f64, symbol: &str) {
+        let histogram = LATENCY.get_or_init(|| {
+            global::meter("v26meme")
+                .f64_histogram("pipeline.latency")
+                .with_unit("ms")
+                .with_description("Time spent in each hop of the data → signal → order → fill pipeline")
+                .build()
+        });
+        histogram.record(elapsed_ms, &[
+            KeyValue::new("stage", stage.as_str()),
+            KeyValue::new("symbol", symbol.to_string()),
+        ]);
+    }
+}
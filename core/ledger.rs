@@ -0,0 +1,216 @@
+// Ledger - Positions, Fills and Risk Events in Postgres
+// The open book lived only in RiskManager's map, and fills and breaker trips only in logs and
+// the live feed. Every change is now written through to positions, fills and risk_events by
+// a single background writer, so writes land in the order they happened (an update can never
+// overtake the insert it updates) and a slow database never blocks a risk check or a fill.
+// Writes reach it through a bounded WriteQueue, and whatever has queued up is applied in one
+// transaction. Orders and equity snapshots already persist themselves in order_manager and equity
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{PgConnection, PgPool};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::core::alerts::Severity;
+use crate::core::anomaly::{self, Event};
+use crate::core::error::Result;
+use crate::core::live_feed::{TradeEvent, TradeKind};
+use crate::core::money::Decimal;
+use crate::core::risk_manager::Position;
+use crate::core::write_queue::{WriteBatches, WriteQueue, WRITE_QUEUE_BATCH, WRITE_QUEUE_CAPACITY, WRITE_QUEUE_LIMIT};
+
+#[derive(Debug, Clone)]
+pub enum LedgerWrite {
+    PositionOpened { position_id: String, position: Position },
+    PositionChanged { position_id: String, position: Position },  // size, entry or stops moved
+    PositionClosed { position_id: String, at: DateTime<Utc> },
+    Fill { client_order_id: String, trade: TradeEvent },
+    RiskEvent(RiskEvent),
+}
+
+#[derive(Debug, Clone)]
+pub struct RiskEvent {
+    pub event_type: String,  // the breaker name, emergency_state or shutdown
+    pub severity: Severity,
+    pub description: String,
+    pub capital: Decimal,
+    pub drawdown_pct: f64,   // from the daily high, 0.0-1.0
+    pub detail: Value,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct Ledger {
+    writes: WriteQueue<LedgerWrite>,
+}
+
+impl Ledger {
+    /// A ledger and the receiving end of its writes, for a writer (or a test) to drain
+    pub fn channel() -> (Self, WriteBatches<LedgerWrite>) {
+        let (writes, queued) = WriteQueue::channel("Ledger", WRITE_QUEUE_CAPACITY, WRITE_QUEUE_LIMIT, WRITE_QUEUE_BATCH);
+        (Ledger { writes }, queued)
+    }
+
+    /// Spawn the writer; needs a running tokio runtime
+    pub fn start(db_pool: PgPool, run_id: Option<Uuid>) -> Self {
+        let (ledger, mut queued) = Self::channel();
+        tokio::spawn(async move {
+            while let Some(batch) = queued.next().await {
+                write_batch(&db_pool, run_id, &batch).await;
+                queued.written(batch.len());
+            }
+        });
+        ledger
+    }
+
+    pub fn record(&self, write: LedgerWrite) {
+        if let Err(write) = self.writes.push(write) {
+            warn!("⚠️ Ledger writer is gone, dropped {}", write.describe());
+        }
+    }
+
+    /// Wait for everything recorded so far to be written; returns how many writes are still queued
+    pub async fn flush(&self, timeout: std::time::Duration) -> usize {
+        self.writes.flush(timeout).await
+    }
+}
+
+/// A batch in one transaction; if it fails, each write again on its own, so one bad write
+/// doesn't take the rest with it
+async fn write_batch(db_pool: &PgPool, run_id: Option<Uuid>, batch: &[LedgerWrite]) {
+    let committed: Result<()> = async {
+        let mut tx = db_pool.begin().await?;
+        for write in batch {
+            apply(&mut tx, run_id, write).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }.await;
+    if committed.is_ok() {
+        return;
+    }
+
+    for write in batch {
+        let applied = match db_pool.acquire().await {
+            Ok(mut conn) => apply(&mut conn, run_id, write).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = applied {
+            error!("❌ Failed to write {} to the ledger ({}): {}", write.describe(), e.code(), e);
+            anomaly::note(Event::DbWriteError);
+        }
+    }
+}
+
+impl LedgerWrite {
+    fn describe(&self) -> String {
+        match self {
+            LedgerWrite::PositionOpened { position_id, .. } => format!("open of {}", position_id),
+            LedgerWrite::PositionChanged { position_id, .. } => format!("change to {}", position_id),
+            LedgerWrite::PositionClosed { position_id, .. } => format!("close of {}", position_id),
+            LedgerWrite::Fill { client_order_id, .. } => format!("fill on {}", client_order_id),
+            LedgerWrite::RiskEvent(event) => format!("{} risk event", event.event_type),
+        }
+    }
+}
+
+async fn apply(conn: &mut PgConnection, run_id: Option<Uuid>, write: &LedgerWrite) -> Result<()> {
+    match write {
+        LedgerWrite::PositionOpened { position_id, position } => {
+            sqlx::query("
+                INSERT INTO positions
+                (position_id, run_id, pattern_hash, symbol, exchange, size, entry_price,
+                 stop_loss, take_profit, opened_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                ON CONFLICT (position_id) DO UPDATE
+                SET size = EXCLUDED.size, entry_price = EXCLUDED.entry_price, stop_loss = EXCLUDED.stop_loss,
+                    take_profit = EXCLUDED.take_profit, state = 'open', closed_at = NULL, updated_at = NOW()
+            ")
+                .bind(position_id)
+                .bind(run_id)
+                .bind(&position.pattern_hash)
+                .bind(&position.symbol)
+                .bind(&position.exchange)
+                .bind(position.size)
+                .bind(position.entry_price)
+                .bind(position.stop_loss)
+                .bind(position.take_profit)
+                .bind(position.entry_time)
+                .execute(&mut *conn)
+                .await?;
+        }
+        LedgerWrite::PositionChanged { position_id, position } => {
+            sqlx::query("
+                UPDATE positions
+                SET size = $2, entry_price = $3, stop_loss = $4, take_profit = $5, updated_at = NOW()
+                WHERE position_id = $1
+            ")
+                .bind(position_id)
+                .bind(position.size)
+                .bind(position.entry_price)
+                .bind(position.stop_loss)
+                .bind(position.take_profit)
+                .execute(&mut *conn)
+                .await?;
+        }
+        LedgerWrite::PositionClosed { position_id, at } => {
+            sqlx::query("
+                UPDATE positions SET size = 0, state = 'closed', closed_at = $2, updated_at = NOW()
+                WHERE position_id = $1
+            ")
+                .bind(position_id)
+                .bind(at)
+                .execute(&mut *conn)
+                .await?;
+        }
+        LedgerWrite::Fill { client_order_id, trade } => {
+            let kind = match trade.kind { TradeKind::Entry => "entry", TradeKind::Exit => "exit" };
+            sqlx::query("
+                INSERT INTO fills
+                (client_order_id, position_id, run_id, kind, symbol, exchange, size, price, fees, pnl, filled_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ")
+                .bind(client_order_id)
+                .bind(&trade.position_id)
+                .bind(run_id)
+                .bind(kind)
+                .bind(&trade.symbol)
+                .bind(&trade.exchange)
+                .bind(trade.size)
+                .bind(trade.price)
+                .bind(trade.fees)
+                .bind(trade.pnl)
+                .bind(trade.at)
+                .execute(&mut *conn)
+                .await?;
+
+            if let Some(pnl) = trade.pnl {
+                sqlx::query("UPDATE positions SET realized_pnl = realized_pnl + $2 WHERE position_id = $1")
+                    .bind(&trade.position_id)
+                    .bind(pnl)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+        }
+        LedgerWrite::RiskEvent(event) => {
+            // drawdown_pct is DECIMAL(5,4); anything past the column's range is already a wipeout
+            sqlx::query("
+                INSERT INTO risk_events
+                (event_type, severity, description, capital_at_event, drawdown_pct, run_id, detail, timestamp)
+                VALUES ($1, $2, $3, $4, $5::float8, $6, $7, $8)
+            ")
+                .bind(&event.event_type)
+                .bind(event.severity.as_str())
+                .bind(&event.description)
+                .bind(event.capital)
+                .bind(event.drawdown_pct.clamp(0.0, 9.9999))
+                .bind(run_id)
+                .bind(&event.detail)
+                .bind(event.at)
+                .execute(&mut *conn)
+                .await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,269 @@
+// Live Feed - Streaming State to Dashboards
+// A websocket at /ws pushes JSON events as they happen: equity each minute, the open book
+// whenever a position changes, every fill, and each newly activated pattern. A new client
+// (or one that fell behind) first gets a snapshot of the latest equity, positions and recent
+// history, so a dashboard or TUI can render live state without polling Postgres
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+
+use crate::core::control_api::{token_matches, OpenPosition};
+use crate::core::equity::EquitySnapshot;
+use crate::core::money::{self, Decimal};
+
+const RECENT_TRADES: usize = 50;
+const RECENT_PATTERNS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeKind {
+    Entry,
+    Exit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeEvent {
+    pub at: DateTime<Utc>,
+    pub kind: TradeKind,
+    pub position_id: String,
+    pub pattern_hash: String,
+    pub symbol: String,
+    pub exchange: String,
+    pub size: Decimal,   // notional filled
+    pub price: Decimal,
+    pub fees: Decimal,
+    pub pnl: Option<Decimal>,  // exits only
+    pub reason: Option<String>,
+    pub capital: Decimal,  // tracked equity after the fill
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternEvent {
+    pub at: DateTime<Utc>,
+    pub pattern_hash: String,
+    pub symbol: Option<String>,
+    pub win_rate: f64,
+    pub sharpe_ratio: f64,
+    pub test_count: u32,
+    pub generation: u32,
+}
+
+/// Everything a newly connected client needs before the deltas make sense
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub equity: Option<EquitySnapshot>,
+    pub positions: Vec<OpenPosition>,
+    pub recent_trades: VecDeque<TradeEvent>,      // oldest first
+    pub recent_patterns: VecDeque<PatternEvent>,  // oldest first
+}
+
+impl Snapshot {
+    /// Fold one event in; clients keep their own copy current the same way
+    pub fn apply(&mut self, event: &LiveEvent) {
+        match event {
+            LiveEvent::Snapshot(snapshot) => *self = snapshot.clone(),
+            LiveEvent::Equity(snapshot) => self.equity = Some(snapshot.clone()),
+            LiveEvent::Positions { positions } => self.positions = positions.clone(),
+            LiveEvent::Trade(trade) => {
+                self.recent_trades.push_back(trade.clone());
+                if self.recent_trades.len() > RECENT_TRADES {
+                    self.recent_trades.pop_front();
+                }
+            }
+            LiveEvent::PatternActivated(pattern) => {
+                self.recent_patterns.push_back(pattern.clone());
+                if self.recent_patterns.len() > RECENT_PATTERNS {
+                    self.recent_patterns.pop_front();
+                }
+            }
+        }
+    }
+    
+    /// Tracked equity as of the latest equity snapshot or fill, whichever is newer
+    pub fn capital(&self) -> Option<f64> {
+        let from_equity = self.equity.as_ref().map(|s| (s.taken_at, s.equity));
+        let from_trade = self.recent_trades.back().map(|t| (t.at, money::to_f64(t.capital)));
+        match (from_equity, from_trade) {
+            (Some(e), Some(t)) => Some(if t.0 > e.0 { t.1 } else { e.1 }),
+            (e, t) => e.or(t).map(|(_, capital)| capital),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent {
+    Snapshot(Snapshot),
+    Equity(EquitySnapshot),
+    Positions { positions: Vec<OpenPosition> },
+    Trade(TradeEvent),
+    PatternActivated(PatternEvent),
+}
+
+/// Cheap to clone; every clone publishes to the same subscribers
+#[derive(Clone)]
+pub struct LiveFeed {
+    tx: broadcast::Sender<LiveEvent>,
+    latest: Arc<Mutex<Snapshot>>,
+}
+
+impl Default for LiveFeed {
+    fn default() -> Self {
+        LiveFeed::new(1024)
+    }
+}
+
+impl LiveFeed {
+    /// `capacity` events can queue per client before it is resynced from a snapshot
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity.max(1));
+        LiveFeed { tx, latest: Arc::new(Mutex::new(Snapshot::default())) }
+    }
+
+    pub fn publish(&self, event: LiveEvent) {
+        // Update and send under one lock, so a subscriber never misses or double-counts an event
+        if matches!(event, LiveEvent::Snapshot(_)) {
+            return;
+        }
+        let mut latest = self.latest.lock().unwrap();
+        latest.apply(&event);
+        let _ = self.tx.send(event);  // no subscribers is fine
+    }
+
+    /// The current snapshot, and every event published after it
+    pub fn subscribe(&self) -> (Snapshot, broadcast::Receiver<LiveEvent>) {
+        let latest = self.latest.lock().unwrap();
+        (latest.clone(), self.tx.subscribe())
+    }
+
+    pub fn subscribers(&self) -> usize {
+        self.tx.receiver_count()
+    }
+}
+
+#[derive(Clone)]
+struct FeedState {
+    feed: LiveFeed,
+    token: Option<Arc<str>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedQuery {
+    token: Option<String>,
+}
+
+pub fn router(feed: LiveFeed, token: Option<&str>) -> Router {
+    Router::new()
+        .route("/ws", get(upgrade))
+        .with_state(FeedState { feed, token: token.map(Arc::from) })
+}
+
+/// Browsers cannot set headers on a websocket, so the token (if any) rides in `?token=`
+async fn upgrade(State(state): State<FeedState>, Query(query): Query<FeedQuery>, ws: WebSocketUpgrade) -> Response {
+    if let Some(expected) = &state.token {
+        if !query.token.as_deref().is_some_and(|token| token_matches(token, expected)) {
+            warn!("⚠️ Rejected live feed connection without a valid token");
+            return (StatusCode::UNAUTHORIZED, "missing or invalid token").into_response();
+        }
+    }
+    ws.on_upgrade(move |socket| stream(socket, state.feed))
+}
+
+async fn send(socket: &mut WebSocket, event: &LiveEvent) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(event).unwrap_or_default();
+    socket.send(Message::Text(text)).await
+}
+
+async fn stream(mut socket: WebSocket, feed: LiveFeed) {
+    let (snapshot, mut events) = feed.subscribe();
+    if send(&mut socket, &LiveEvent::Snapshot(snapshot)).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if send(&mut socket, &event).await.is_err() {
+                        return;
+                    }
+                }
+                // Too slow to keep up: start again from a fresh snapshot rather than show gaps
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("🐢 Live feed client skipped {} events, resyncing", skipped);
+                    let (snapshot, fresh) = feed.subscribe();
+                    events = fresh;
+                    if send(&mut socket, &LiveEvent::Snapshot(snapshot)).await.is_err() {
+                        return;
+                    }
+                }
+                Err(RecvError::Closed) => return,
+            },
+            // Clients only listen; anything but a close is ignored (axum answers pings)
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+pub async fn serve(feed: LiveFeed, token: Option<&str>, bind: &str, port: u16) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind((bind, port)).await?;
+    info!("📡 Live feed on ws://{}:{}/ws", bind, port);
+    axum::serve(listener, router(feed, token)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn trade(position_id: &str) -> TradeEvent {
+        TradeEvent {
+            at: Utc::now(),
+            kind: TradeKind::Exit,
+            position_id: position_id.to_string(),
+            pattern_hash: "abc".to_string(),
+            symbol: "DOGE-USD".to_string(),
+            exchange: "coinbase".to_string(),
+            size: dec!(25),
+            price: dec!(0.2),
+            fees: dec!(0.05),
+            pnl: Some(dec!(1.2)),
+            reason: Some("take_profit".to_string()),
+            capital: dec!(201.2),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_late_subscribers_start_from_a_snapshot() {
+        let feed = LiveFeed::new(16);
+        for i in 0..(RECENT_TRADES + 5) {
+            feed.publish(LiveEvent::Trade(trade(&format!("p{}", i))));
+        }
+
+        let (snapshot, mut events) = feed.subscribe();
+        assert_eq!(snapshot.recent_trades.len(), RECENT_TRADES);
+        assert_eq!(snapshot.recent_trades.front().unwrap().position_id, "p5");
+
+        feed.publish(LiveEvent::Positions { positions: Vec::new() });
+        let json = serde_json::to_value(events.recv().await.unwrap()).unwrap();
+        assert_eq!(json["type"], "positions");
+
+        // Tagged JSON round-trips, so clients can reuse these types
+        let sent = trade("p1");
+        let text = serde_json::to_string(&LiveEvent::Trade(sent.clone())).unwrap();
+        assert!(matches!(serde_json::from_str(&text).unwrap(), LiveEvent::Trade(t) if t == sent));
+    }
+}
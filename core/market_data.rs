@@ -0,0 +1,334 @@
+// Market Data Feed - Fan-out of Price Ticks to Every Consumer
+// Sources publish ticks; watchers (stops, signals, dashboards) subscribe independently.
+// A burst of trades on a memecoin can be thousands of ticks a second, so nothing on the path
+// grows with it: the channel is a ring of `channel_capacity` slots allocated up front, a tick's
+// symbol and venue are shared Bytes that clone without copying, and the database poll reads a
+// bounded page at a time. When a subscriber can't keep up the drop policy decides which ticks
+// it loses, and every loss is counted rather than queued
+
+use serde::{Serialize, Deserialize, Deserializer, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
+use sqlx::{PgPool, Row};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{error, warn};
+
+use crate::config::MarketDataSettings;
+use crate::core::chaos::FaultInjector;
+use crate::core::readiness::Gate;
+
+/// Distinct symbols and venues the poller keeps shared copies of; past this it starts over
+const INTERN_LIMIT: usize = 10_000;
+
+/// A symbol or venue name; clones share one buffer, so fanning a tick out copies no text
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(Bytes);
+
+impl Symbol {
+    pub fn as_str(&self) -> &str {
+        // Only ever built from a str, so always valid UTF-8
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&'static str> for Symbol {
+    fn from(name: &'static str) -> Self {
+        Symbol(Bytes::from_static(name.as_bytes()))
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Self {
+        Symbol(Bytes::from(name.into_bytes()))
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<Symbol> for String {
+    fn eq(&self, other: &Symbol) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Symbol::from)
+    }
+}
+
+/// One shared Symbol per distinct name, so a stream of rows allocates each name once
+#[derive(Default)]
+pub struct SymbolTable(HashMap<String, Symbol>);
+
+impl SymbolTable {
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(symbol) = self.0.get(name) {
+            return symbol.clone();
+        }
+        if self.0.len() >= INTERN_LIMIT {
+            self.0.clear();
+        }
+        let symbol = Symbol::from(name.to_string());
+        self.0.insert(name.to_string(), symbol.clone());
+        symbol
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceTick {
+    pub symbol: Symbol,
+    pub exchange: Symbol,
+    pub price: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Which ticks are lost once a subscriber has `channel_capacity` of them waiting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DropPolicy {
+    /// The slow subscriber skips ahead; prices only matter while they are current
+    #[default]
+    Oldest,
+    /// New ticks are refused until the slowest subscriber catches up; what it gets has no gaps
+    Newest,
+}
+
+#[derive(Clone)]
+pub struct MarketDataFeed {
+    sender: broadcast::Sender<PriceTick>,
+    capacity: usize,
+    policy: DropPolicy,
+    poll_batch: i64,
+    last_tick_ms: Arc<AtomicI64>,  // 0 until the first tick arrives
+    dropped: Arc<AtomicU64>,       // ticks lost to either policy, across subscribers
+    ready: Gate,                   // opened by the first tick; execution waits on it
+    chaos: FaultInjector,          // drops the stream for a while in chaos runs
+}
+
+/// One subscriber's view of the feed
+pub struct TickStream {
+    receiver: broadcast::Receiver<PriceTick>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl TickStream {
+    /// The next tick, or None once the feed is gone; ticks overwritten before this subscriber
+    /// read them are counted and skipped
+    pub async fn recv(&mut self) -> Option<PriceTick> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(tick) => return Some(tick),
+                Err(RecvError::Lagged(skipped)) => {
+                    self.dropped.fetch_add(skipped, Ordering::Relaxed);
+                    warn!("⚠️ Market data subscriber lagged, skipped {} ticks", skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+impl MarketDataFeed {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_settings(&MarketDataSettings { channel_capacity: capacity, ..MarketDataSettings::default() })
+    }
+
+    pub fn with_settings(settings: &MarketDataSettings) -> Self {
+        let capacity = settings.channel_capacity.max(1);
+        let (sender, _) = broadcast::channel(capacity);
+        MarketDataFeed {
+            sender,
+            capacity,
+            policy: settings.drop_policy,
+            poll_batch: settings.poll_batch.max(1),
+            last_tick_ms: Arc::new(AtomicI64::new(0)),
+            dropped: Arc::new(AtomicU64::new(0)),
+            ready: Gate::new("market_data"),
+            chaos: FaultInjector::default(),
+        }
+    }
+
+    pub fn with_chaos(mut self, faults: FaultInjector) -> Self {
+        self.chaos = faults;
+        self
+    }
+
+    pub fn subscribe(&self) -> TickStream {
+        TickStream { receiver: self.sender.subscribe(), dropped: self.dropped.clone() }
+    }
+
+    /// Returns the number of subscribers that received the tick
+    pub fn publish(&self, tick: PriceTick) -> usize {
+        // A dropped stream loses ticks outright, as a disconnected websocket would
+        if self.chaos.stream_down() {
+            return 0;
+        }
+        if self.last_tick_ms.swap(Utc::now().timestamp_millis(), Ordering::Relaxed) == 0 {
+            self.ready.open();
+        }
+        if self.policy == DropPolicy::Newest && self.sender.len() >= self.capacity {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return 0;
+        }
+        self.sender.send(tick).unwrap_or(0)
+    }
+
+    /// Open once the first tick has been published
+    pub fn ready(&self) -> &Gate {
+        &self.ready
+    }
+
+    /// Ticks lost to slow subscribers since startup
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// None until the feed has delivered anything; afterwards whether it is still flowing
+    pub fn is_fresh(&self, max_age: std::time::Duration) -> Option<bool> {
+        let last = self.last_tick_ms.load(Ordering::Relaxed);
+        if last == 0 {
+            return None;
+        }
+        Some(Utc::now().timestamp_millis() - last <= max_age.as_millis() as i64)
+    }
+
+    /// Poll the market_data table and publish every new row
+    /// Used until venues stream directly into the feed
+    pub async fn poll_database(&self, db_pool: PgPool, every: std::time::Duration) {
+        let mut interval = tokio::time::interval(every);
+        let mut since = Utc::now();
+        let mut symbols = SymbolTable::default();
+
+        loop {
+            interval.tick().await;
+
+            let query = "
+                SELECT symbol, COALESCE(exchange, 'unknown') AS exchange, price::float8 AS price, timestamp
+                FROM market_data
+                WHERE timestamp > $1
+                ORDER BY timestamp
+                LIMIT $2
+            ";
+
+            // Rows are published as they stream in, never collected, and at most poll_batch at a time
+            let mut rows = sqlx::query(query).bind(since).bind(self.poll_batch).fetch(&db_pool);
+            let mut read = 0;
+            loop {
+                let row = match rows.try_next().await {
+                    Ok(Some(row)) => row,
+                    Ok(None) => break,
+                    Err(e) => {
+                        error!("❌ Market data poll failed: {}", e);
+                        break;
+                    }
+                };
+                let tick = PriceTick {
+                    symbol: symbols.intern(row.get("symbol")),
+                    exchange: symbols.intern(row.get("exchange")),
+                    price: row.get("price"),
+                    timestamp: row.get("timestamp"),
+                };
+                since = since.max(tick.timestamp);
+                read += 1;
+                self.publish(tick);
+            }
+
+            // A full page means a burst is still being read; take the next one straight away
+            if read == self.poll_batch {
+                interval.reset_immediately();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(price: f64) -> PriceTick {
+        PriceTick { symbol: "PEPE-USD".into(), exchange: "coinbase".into(), price, timestamp: Utc::now() }
+    }
+
+    fn feed(capacity: usize, drop_policy: DropPolicy) -> MarketDataFeed {
+        MarketDataFeed::with_settings(&MarketDataSettings { channel_capacity: capacity, drop_policy, ..MarketDataSettings::default() })
+    }
+
+    #[tokio::test]
+    async fn test_a_burst_past_capacity_is_dropped_by_policy() {
+        // A slow subscriber skips to the newest ticks
+        let oldest = feed(4, DropPolicy::Oldest);
+        let mut slow = oldest.subscribe();
+        assert!(!oldest.ready().is_open());
+        for i in 0..10 {
+            assert_eq!(oldest.publish(tick(i as f64)), 1);
+        }
+        assert_eq!(slow.recv().await.unwrap().price, 6.0);
+        assert_eq!(oldest.dropped(), 6);
+        assert!(oldest.ready().is_open());
+
+        // Or keeps the first ticks it was sent, and the rest are refused
+        let newest = feed(4, DropPolicy::Newest);
+        let mut slow = newest.subscribe();
+        let delivered: usize = (0..10).map(|i| newest.publish(tick(i as f64))).sum();
+        assert_eq!((delivered, newest.dropped()), (4, 6));
+        assert_eq!(slow.recv().await.unwrap().price, 0.0);
+        assert_eq!(newest.publish(tick(10.0)), 1);
+    }
+
+    #[test]
+    fn test_interned_symbols_share_one_buffer() {
+        let mut symbols = SymbolTable::default();
+        let first = symbols.intern("DOGE-USD");
+        let second = symbols.intern("DOGE-USD");
+        assert_eq!(first.as_ptr(), second.as_ptr());
+        // Positions keep their symbol as a String and match ticks against it
+        let position_symbol = String::from("DOGE-USD");
+        assert!(first == "DOGE-USD" && position_symbol == second);
+
+        let json = serde_json::to_string(&tick(0.5)).unwrap();
+        assert!(json.contains(r#""symbol":"PEPE-USD""#));
+        assert_eq!(serde_json::from_str::<PriceTick>(&json).unwrap().exchange, "coinbase");
+    }
+}
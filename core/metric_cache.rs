@@ -0,0 +1,144 @@
+// Metric Cache - One Computation per Metric per Minute, Shared by Every Hypothesis
+// Hypotheses draw on a small vocabulary of metrics: a few thousand candidates watching DOGE
+// mostly read the same handful, price_delta_5m among them, at the same minutes, and crosses_*
+// conditions read the minute before too. Values are kept under (symbol, metric, timeframe,
+// bucket), the bucket being the start of the candle they were read at, so the first condition
+// to need one computes it and every other reads it. A bucket is final once its candle has
+// closed; `invalidate_from` drops a forming minute that a tick has moved, and `evict_before`
+// keeps a long-running series to a window
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use chrono::{DateTime, Utc};
+
+use crate::core::evaluator::METRICS;
+
+/// Shards in a SeriesCache, by minute; walks on different threads rarely hold the same one
+pub const CACHE_SHARDS: usize = 16;
+
+/// Which metric, and the start of the candle it was read at in seconds
+type Slot = (u8, i64);
+
+/// Every cached metric value for one symbol's candles of one timeframe
+#[derive(Debug)]
+pub struct SeriesCache {
+    shards: Vec<RwLock<HashMap<Slot, Option<f64>>>>,
+}
+
+impl Default for SeriesCache {
+    fn default() -> Self {
+        SeriesCache { shards: (0..CACHE_SHARDS).map(|_| RwLock::new(HashMap::new())).collect() }
+    }
+}
+
+impl SeriesCache {
+    fn shard(&self, bucket: i64) -> &RwLock<HashMap<Slot, Option<f64>>> {
+        &self.shards[(bucket / 60).rem_euclid(self.shards.len() as i64) as usize]
+    }
+
+    /// The cached value of `metric` at `bucket`, computing and keeping it on the first read.
+    /// Metrics the evaluator doesn't know are computed every time; they are always None
+    pub fn get_or_compute(&self, metric: &str, bucket: DateTime<Utc>, compute: impl FnOnce() -> Option<f64>) -> Option<f64> {
+        let Some(id) = METRICS.iter().position(|name| *name == metric) else {
+            return compute();
+        };
+        let slot = (id as u8, bucket.timestamp());
+        let shard = self.shard(slot.1);
+        if let Some(value) = shard.read().unwrap().get(&slot) {
+            return *value;
+        }
+        // Two walks may both compute a missing value; they compute the same one
+        let value = compute();
+        shard.write().unwrap().insert(slot, value);
+        value
+    }
+
+    /// Drop every value from `bucket` on, once a tick has moved that candle
+    pub fn invalidate_from(&self, bucket: DateTime<Utc>) {
+        let from = bucket.timestamp();
+        for shard in &self.shards {
+            shard.write().unwrap().retain(|(_, at), _| *at < from);
+        }
+    }
+
+    /// Drop every value before `bucket`
+    pub fn evict_before(&self, bucket: DateTime<Utc>) {
+        let before = bucket.timestamp();
+        for shard in &self.shards {
+            shard.write().unwrap().retain(|(_, at), _| *at >= before);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.read().unwrap().len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Series by symbol and timeframe (candle width in minutes)
+#[derive(Default)]
+pub struct MetricCache {
+    series: RwLock<HashMap<(String, u32), Arc<SeriesCache>>>,
+}
+
+impl MetricCache {
+    /// The series walks on `symbol`'s candles of `timeframe` share; created on first use
+    pub fn series(&self, symbol: &str, timeframe: u32) -> Arc<SeriesCache> {
+        let key = (symbol.to_string(), timeframe);
+        if let Some(series) = self.series.read().unwrap().get(&key) {
+            return series.clone();
+        }
+        self.series.write().unwrap().entry(key).or_default().clone()
+    }
+
+    /// Release a symbol's series once nothing will walk its candles again
+    pub fn forget(&self, symbol: &str) {
+        self.series.write().unwrap().retain(|(cached, _), _| cached != symbol);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use crate::core::discovery_engine::{Condition, Hypothesis};
+    use crate::core::evaluator::{self, Candle};
+
+    #[test]
+    fn test_hypotheses_share_one_computation_per_metric_and_minute() {
+        let start = "2026-03-14T14:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let candles: Vec<Candle> = (0..240).map(|i| {
+            let close = 1.0 + 0.03 * (i as f64 / 7.0).sin();
+            Candle { start: start + Duration::minutes(i), open: close, high: close, low: close, close, volume: 100.0 + (i % 13) as f64 * 40.0, trades: 5 }
+        }).collect();
+        let hypotheses: Vec<Hypothesis> = (0..40).map(|i| Hypothesis {
+            hash: format!("h{}", i),
+            entry_conditions: vec![
+                Condition { metric: "price_delta_5m".to_string(), operator: "crosses_above".to_string(), value: 0.5 + i as f64 * 0.05, weight: 0.5 },
+                Condition { metric: "volume_spike".to_string(), operator: ">".to_string(), value: 0.8, weight: 0.5 },
+            ],
+            exit_conditions: vec![Condition { metric: "price_delta_5m".to_string(), operator: "<".to_string(), value: -(i as f64) * 0.05, weight: 0.5 }],
+            timeframe: 30,
+            created_at: 0,
+            symbol: Some("DOGE-USD".to_string()),
+        }).collect();
+
+        let cache = MetricCache::default();
+        let series = cache.series("DOGE-USD", evaluator::CANDLE_MINUTES);
+        for h in &hypotheses {
+            assert_eq!(evaluator::evaluate_cached(h, &candles, &series), evaluator::evaluate(h, &candles));
+        }
+        // Two metrics read at every minute, however many hypotheses read them
+        assert_eq!(series.len(), 2 * candles.len());
+        assert!(Arc::ptr_eq(&series, &cache.series("DOGE-USD", evaluator::CANDLE_MINUTES)));
+
+        series.invalidate_from(start + Duration::minutes(200));
+        series.evict_before(start + Duration::minutes(60));
+        assert_eq!(series.len(), 2 * 140);
+        cache.forget("DOGE-USD");
+        assert!(cache.series("DOGE-USD", 1).is_empty());
+    }
+}
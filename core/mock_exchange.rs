@@ -0,0 +1,476 @@
+// Mock Exchange - A Scripted Venue for Integration Tests
+// MockExchange implements `Exchange` and answers each submit from a script: fill all or part of
+// it at the touch, leave it resting, reject it, fail as unavailable, or place it and lose the
+// acknowledgement. Every call can be delayed by an injected latency. The same venue can be
+// served over HTTP and a websocket of order updates (`serve`), and MockVenueClient trades
+// against that server through `Exchange`, so the full order lifecycle can be tested over a
+// real connection without touching a venue. Compiled for tests and the `test-support` feature
+
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use async_trait::async_trait;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::Utc;
+use serde::{Serialize, Deserialize};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::core::exchange::{
+    split_symbol, Exchange, ExchangeError, OrderRequest, OrderStatusReport, OrderType, Quote, Side, VenueOrderState,
+};
+use crate::core::money::{self, Decimal};
+
+/// What the venue does with the next order submitted; with the script empty, market and
+/// marketable limit orders fill in full and other limit orders rest
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scripted {
+    Fill { fraction: f64 },  // this share of the order fills at the touch, the rest rests
+    Rest,                    // accepted, nothing filled
+    Reject(String),          // refused: ExchangeError::Rejected
+    Unavailable(String),     // never placed: ExchangeError::Unavailable
+    LostAck,                 // placed as if unscripted, but the caller sees a timeout
+}
+
+struct MockOrder {
+    request: OrderRequest,
+    report: OrderStatusReport,
+}
+
+pub struct MockExchange {
+    name: String,
+    fee_rate: Decimal,
+    latency: Mutex<Duration>,
+    quotes: Mutex<HashMap<String, Quote>>,
+    script: Mutex<VecDeque<Scripted>>,
+    orders: Mutex<HashMap<String, MockOrder>>,
+    balances: Mutex<HashMap<String, Decimal>>,
+    submitted: Mutex<Vec<OrderRequest>>,
+    updates: broadcast::Sender<OrderStatusReport>,
+    next_id: AtomicU64,
+}
+
+impl MockExchange {
+    pub fn new(name: &str, fee_rate: f64) -> Self {
+        MockExchange {
+            name: name.to_string(),
+            fee_rate: money::from_f64(fee_rate),
+            latency: Mutex::new(Duration::ZERO),
+            quotes: Mutex::new(HashMap::new()),
+            script: Mutex::new(VecDeque::new()),
+            orders: Mutex::new(HashMap::new()),
+            balances: Mutex::new(HashMap::new()),
+            submitted: Mutex::new(Vec::new()),
+            updates: broadcast::channel(256).0,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub fn with_balance(self, asset: &str, units: Decimal) -> Self {
+        self.balances.lock().unwrap().insert(asset.to_string(), units);
+        self
+    }
+
+    /// Delay every call by this much
+    pub fn set_latency(&self, latency: Duration) {
+        *self.latency.lock().unwrap() = latency;
+    }
+
+    /// Quote `symbol` with 25k notional on each side of the touch
+    pub fn set_quote(&self, symbol: &str, bid: f64, ask: f64) {
+        let quote = Quote { bid, ask, bid_size: 25_000.0, ask_size: 25_000.0 };
+        self.quotes.lock().unwrap().insert(symbol.to_string(), quote);
+    }
+
+    /// Queue outcomes for the next submits, in order
+    pub fn script(&self, steps: impl IntoIterator<Item = Scripted>) {
+        self.script.lock().unwrap().extend(steps);
+    }
+
+    /// Every order submit as received, repeats and refused ones included
+    pub fn submitted(&self) -> Vec<OrderRequest> {
+        self.submitted.lock().unwrap().clone()
+    }
+
+    /// Order reports as they change: placed, filled, cancelled
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderStatusReport> {
+        self.updates.subscribe()
+    }
+
+    /// Fill `fraction` of what is left of a resting order at its limit (or the touch)
+    pub fn fill_resting(&self, exchange_order_id: &str, fraction: f64) -> Option<OrderStatusReport> {
+        let mut orders = self.orders.lock().unwrap();
+        let order = orders.get_mut(exchange_order_id).filter(|o| o.report.state == VenueOrderState::Open)?;
+        let price = match order.request.order_type {
+            OrderType::Limit(limit) => limit,
+            OrderType::Market => self.touch(&order.request)?,
+        };
+        let size = money::share(order.request.size - order.report.filled_size, fraction.clamp(0.0, 1.0));
+        self.fill(order, price, size);
+        Some(order.report.clone())
+    }
+
+    async fn delay(&self) {
+        let latency = *self.latency.lock().unwrap();
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+    }
+
+    fn touch(&self, request: &OrderRequest) -> Option<Decimal> {
+        let quote = *self.quotes.lock().unwrap().get(&request.symbol)?;
+        Some(money::from_f64(match request.side {
+            Side::Buy => quote.ask,
+            Side::Sell => quote.bid,
+        }))
+    }
+
+    fn fill(&self, order: &mut MockOrder, price: Decimal, size: Decimal) {
+        if size <= Decimal::ZERO {
+            return;
+        }
+        let fee = size * self.fee_rate;
+        let report = &mut order.report;
+        let filled = report.filled_size + size;
+        report.avg_fill_price = (report.avg_fill_price * report.filled_size + price * size) / filled;
+        report.filled_size = filled;
+        report.fees += fee;
+        if filled >= order.request.size {
+            report.state = VenueOrderState::Filled;
+        }
+
+        let (base, quote) = split_symbol(&order.request.symbol);
+        let signed = match order.request.side {
+            Side::Buy => size,
+            Side::Sell => -size,
+        };
+        let mut balances = self.balances.lock().unwrap();
+        *balances.entry(base.to_string()).or_default() += signed / price;
+        *balances.entry(quote.to_string()).or_default() -= signed + fee;
+        let _ = self.updates.send(report.clone());
+    }
+
+    fn place(&self, request: &OrderRequest, step: Option<&Scripted>) -> Result<OrderStatusReport, ExchangeError> {
+        let touch = self.touch(request)
+            .ok_or_else(|| ExchangeError::Unavailable(format!("no quote for {}", request.symbol)))?;
+        let mut order = MockOrder {
+            request: request.clone(),
+            report: OrderStatusReport {
+                exchange_order_id: format!("{}-{}", self.name, self.next_id.fetch_add(1, Ordering::SeqCst)),
+                state: VenueOrderState::Open,
+                filled_size: Decimal::ZERO,
+                avg_fill_price: Decimal::ZERO,
+                fees: Decimal::ZERO,
+            },
+        };
+        let _ = self.updates.send(order.report.clone());
+
+        let marketable = match (request.order_type, request.side) {
+            (OrderType::Market, _) => true,
+            (OrderType::Limit(limit), Side::Buy) => limit >= touch,
+            (OrderType::Limit(limit), Side::Sell) => limit <= touch,
+        };
+        let fraction = match step {
+            Some(Scripted::Fill { fraction }) => fraction.clamp(0.0, 1.0),
+            Some(Scripted::Rest) => 0.0,
+            _ if marketable => 1.0,
+            _ => 0.0,
+        };
+        self.fill(&mut order, touch, money::share(request.size, fraction));
+
+        let report = order.report.clone();
+        self.orders.lock().unwrap().insert(report.exchange_order_id.clone(), order);
+        Ok(report)
+    }
+}
+
+#[async_trait]
+impl Exchange for MockExchange {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<Quote, ExchangeError> {
+        self.delay().await;
+        self.quotes.lock().unwrap().get(symbol).copied()
+            .ok_or_else(|| ExchangeError::Unavailable(format!("no quote for {}", symbol)))
+    }
+
+    async fn submit_order(&self, order: &OrderRequest) -> Result<OrderStatusReport, ExchangeError> {
+        self.delay().await;
+        self.submitted.lock().unwrap().push(order.clone());
+        if let Some(existing) = self.find_order(&order.client_order_id).await? {
+            return Ok(existing);
+        }
+        let step = self.script.lock().unwrap().pop_front();
+        match step {
+            Some(Scripted::Reject(reason)) => Err(ExchangeError::Rejected(reason)),
+            Some(Scripted::Unavailable(reason)) => Err(ExchangeError::Unavailable(reason)),
+            Some(Scripted::LostAck) => {
+                self.place(order, None)?;
+                Err(ExchangeError::Unavailable("acknowledgement lost".to_string()))
+            }
+            step => self.place(order, step.as_ref()),
+        }
+    }
+
+    async fn find_order(&self, client_order_id: &str) -> Result<Option<OrderStatusReport>, ExchangeError> {
+        Ok(self.orders.lock().unwrap()
+            .values()
+            .find(|order| order.request.client_order_id == client_order_id)
+            .map(|order| order.report.clone()))
+    }
+
+    async fn cancel_order(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError> {
+        self.delay().await;
+        let mut orders = self.orders.lock().unwrap();
+        let order = orders.get_mut(exchange_order_id)
+            .ok_or_else(|| ExchangeError::UnknownOrder(exchange_order_id.to_string()))?;
+        if order.report.state == VenueOrderState::Open {
+            order.report.state = VenueOrderState::Cancelled;
+            let _ = self.updates.send(order.report.clone());
+        }
+        Ok(order.report.clone())
+    }
+
+    async fn order_status(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError> {
+        self.delay().await;
+        self.orders.lock().unwrap()
+            .get(exchange_order_id)
+            .map(|order| order.report.clone())
+            .ok_or_else(|| ExchangeError::UnknownOrder(exchange_order_id.to_string()))
+    }
+
+    async fn balances(&self) -> Result<HashMap<String, Decimal>, ExchangeError> {
+        self.delay().await;
+        Ok(self.balances.lock().unwrap().clone())
+    }
+}
+
+/// An error body: the kind picks the ExchangeError the client rebuilds
+#[derive(Debug, Serialize, Deserialize)]
+struct ErrorBody {
+    kind: String,
+    error: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FindQuery {
+    client_order_id: String,
+}
+
+fn error_response(e: ExchangeError) -> Response {
+    let (status, kind, error) = match e {
+        ExchangeError::Rejected(e) => (StatusCode::BAD_REQUEST, "rejected", e),
+        ExchangeError::Unavailable(e) => (StatusCode::SERVICE_UNAVAILABLE, "unavailable", e),
+        ExchangeError::UnknownOrder(e) => (StatusCode::NOT_FOUND, "unknown_order", e),
+    };
+    (status, Json(ErrorBody { kind: kind.to_string(), error })).into_response()
+}
+
+fn reply<T: Serialize>(result: Result<T, ExchangeError>) -> Response {
+    match result {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+pub fn router(exchange: Arc<MockExchange>) -> Router {
+    Router::new()
+        .route("/time", get(|| async { Json(serde_json::json!({ "iso": Utc::now().to_rfc3339() })) }))
+        .route("/quote/:symbol", get(|State(x): State<Arc<MockExchange>>, Path(symbol): Path<String>| async move {
+            reply(x.quote(&symbol).await)
+        }))
+        .route("/orders", get(|State(x): State<Arc<MockExchange>>, Query(q): Query<FindQuery>| async move {
+            reply(x.find_order(&q.client_order_id).await)
+        }).post(|State(x): State<Arc<MockExchange>>, Json(order): Json<OrderRequest>| async move {
+            reply(x.submit_order(&order).await)
+        }))
+        .route("/orders/:id", get(|State(x): State<Arc<MockExchange>>, Path(id): Path<String>| async move {
+            reply(x.order_status(&id).await)
+        }).delete(|State(x): State<Arc<MockExchange>>, Path(id): Path<String>| async move {
+            reply(x.cancel_order(&id).await)
+        }))
+        .route("/balances", get(|State(x): State<Arc<MockExchange>>| async move { reply(x.balances().await) }))
+        .route("/ws", get(|State(x): State<Arc<MockExchange>>, ws: WebSocketUpgrade| async move {
+            ws.on_upgrade(move |socket| stream_updates(socket, x.subscribe()))
+        }))
+        .with_state(exchange)
+}
+
+/// Each order update as a JSON OrderStatusReport
+async fn stream_updates(mut socket: WebSocket, mut updates: broadcast::Receiver<OrderStatusReport>) {
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(report) => {
+                    let text = serde_json::to_string(&report).unwrap_or_default();
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        return;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// Serve on an ephemeral port of 127.0.0.1; returns the address and the server task
+pub async fn serve(exchange: Arc<MockExchange>) -> std::io::Result<(SocketAddr, JoinHandle<()>)> {
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await?;
+    let addr = listener.local_addr()?;
+    let handle = tokio::spawn(async move {
+        let _ = axum::serve(listener, router(exchange)).await;
+    });
+    Ok((addr, handle))
+}
+
+/// `Exchange` over the mock server's HTTP API, the way a venue adapter would talk to a venue
+pub struct MockVenueClient {
+    name: String,
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl MockVenueClient {
+    pub fn new(name: &str, addr: SocketAddr) -> Self {
+        MockVenueClient { name: name.to_string(), base_url: format!("http://{}", addr), http: reqwest::Client::new() }
+    }
+
+    pub fn websocket_url(&self) -> String {
+        format!("{}/ws", self.base_url.replacen("http", "ws", 1))
+    }
+
+    async fn call<T: serde::de::DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T, ExchangeError> {
+        let response = request.send().await.map_err(|e| ExchangeError::Unavailable(e.to_string()))?;
+        if response.status().is_success() {
+            return response.json().await.map_err(|e| ExchangeError::Unavailable(e.to_string()));
+        }
+        let body: ErrorBody = response.json().await.map_err(|e| ExchangeError::Unavailable(e.to_string()))?;
+        Err(match body.kind.as_str() {
+            "rejected" => ExchangeError::Rejected(body.error),
+            "unknown_order" => ExchangeError::UnknownOrder(body.error),
+            _ => ExchangeError::Unavailable(body.error),
+        })
+    }
+}
+
+#[async_trait]
+impl Exchange for MockVenueClient {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<Quote, ExchangeError> {
+        self.call(self.http.get(format!("{}/quote/{}", self.base_url, symbol))).await
+    }
+
+    async fn submit_order(&self, order: &OrderRequest) -> Result<OrderStatusReport, ExchangeError> {
+        self.call(self.http.post(format!("{}/orders", self.base_url)).json(order)).await
+    }
+
+    async fn find_order(&self, client_order_id: &str) -> Result<Option<OrderStatusReport>, ExchangeError> {
+        self.call(self.http.get(format!("{}/orders", self.base_url)).query(&[("client_order_id", client_order_id)])).await
+    }
+
+    async fn cancel_order(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError> {
+        self.call(self.http.delete(format!("{}/orders/{}", self.base_url, exchange_order_id))).await
+    }
+
+    async fn order_status(&self, exchange_order_id: &str) -> Result<OrderStatusReport, ExchangeError> {
+        self.call(self.http.get(format!("{}/orders/{}", self.base_url, exchange_order_id))).await
+    }
+
+    async fn balances(&self) -> Result<HashMap<String, Decimal>, ExchangeError> {
+        self.call(self.http.get(format!("{}/balances", self.base_url))).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use futures_util::StreamExt;
+    use crate::core::execution_context::ExecutionContext;
+    use crate::core::execution_engine::{ExecutionEngine, ExecutionError, OrderIntent};
+    use crate::core::execution_style::ExecutionStyle;
+    use crate::core::order_manager::OrderManager;
+    use crate::core::risk_manager::RiskManager;
+    use crate::risk::liquidity::{LiquidityProfile, LiquidityProfiles};
+
+    fn open(pattern_hash: &str, style: ExecutionStyle) -> OrderIntent {
+        OrderIntent::Open {
+            pattern_hash: pattern_hash.to_string(),
+            symbol: "DOGE-USD".to_string(),
+            exchange: "mock".to_string(),
+            side: Side::Buy,
+            size: dec!(100),
+            style,
+            stop_loss_pct: dec!(0.05),
+            take_profit_pct: dec!(0.10),
+            decision: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_order_lifecycle_over_http_with_scripted_venue() {
+        let venue = Arc::new(MockExchange::new("mock", 0.001).with_balance("USD", dec!(1000)));
+        venue.set_quote("DOGE-USD", 0.99, 1.0);
+        venue.set_latency(Duration::from_millis(5));
+        let (addr, server) = serve(venue.clone()).await.unwrap();
+        let client = MockVenueClient::new("mock", addr);
+        let (mut updates, _) = tokio_tungstenite::connect_async(client.websocket_url()).await.unwrap();
+
+        let risk_manager = Arc::new(RiskManager::new(dec!(1000)));
+        let mut profiles = LiquidityProfiles::default();
+        profiles.insert(LiquidityProfile::flat("DOGE-USD"));
+        risk_manager.set_liquidity_profiles(profiles);
+        let mut engine = ExecutionEngine::new(risk_manager.clone(), OrderManager::in_memory());
+        engine.retry.initial_backoff = Duration::from_millis(1);
+        engine.add_exchange(ExecutionContext::Live.live(Arc::new(client)).unwrap());
+
+        // Refused outright, then a lost acknowledgement the engine finds instead of resending
+        venue.script([Scripted::Reject("insufficient funds".to_string()), Scripted::LostAck]);
+        assert!(matches!(engine.submit(open("a", ExecutionStyle::Market)).await, Err(ExecutionError::Exchange(_))));
+        engine.submit(open("b", ExecutionStyle::Market)).await.unwrap();
+        let positions = risk_manager.open_positions_snapshot();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].1.size, dec!(100));
+        let placed: Vec<String> = venue.submitted().into_iter().map(|o| o.client_order_id).collect();
+        assert_eq!(placed.len(), 2);
+
+        // A resting entry that fills in two parts later
+        venue.script([Scripted::Rest]);
+        engine.submit(open("c", ExecutionStyle::PassiveLimit)).await.unwrap();
+        assert_eq!(engine.working_orders(), 1);
+        let resting = venue.orders.lock().unwrap().values()
+            .find(|o| o.report.state == VenueOrderState::Open)
+            .map(|o| o.report.exchange_order_id.clone())
+            .unwrap();
+        venue.fill_resting(&resting, 0.5);
+        venue.fill_resting(&resting, 1.0);
+        engine.poll_working_orders().await;
+        assert_eq!(engine.working_orders(), 0);
+        assert_eq!(risk_manager.open_positions_snapshot().len(), 2);
+
+        // The websocket saw the first order placed
+        let first = updates.next().await.unwrap().unwrap();
+        let report: OrderStatusReport = serde_json::from_str(first.to_text().unwrap()).unwrap();
+        assert_eq!(report.state, VenueOrderState::Open);
+        let usd = venue.balances().await.unwrap()["USD"];
+        assert!(usd < dec!(800), "{}", usd);
+        server.abort();
+    }
+}
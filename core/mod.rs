@@ -1,7 +1,148 @@
 // Core module exports
+pub mod accounts;
+pub mod age;
+pub mod alerts;
+pub mod anomaly;
+pub mod archive;
+pub mod audit;
+pub mod autoscale;
+pub mod backtest;
+pub mod backtest_pool;
+pub mod batch_writer;
+pub mod bus;
+pub mod cadence;
+pub mod chaos;
+pub mod clock;
+pub mod control_api;
+pub mod costs;
+pub mod crossbreeding;
+pub mod daily_report;
+pub mod db;
 pub mod discovery_engine;
+pub mod drift_report;
+pub mod embedding;
+pub mod equity;
+pub mod error;
+pub mod evaluator;
+pub mod evolution_engine;
+pub mod exchange;
+pub mod execution_algo;
+pub mod execution_context;
+pub mod execution_engine;
+pub mod execution_quality;
+pub mod execution_style;
+pub mod feature_flags;
+pub mod fitness;
+pub mod genome;
+pub mod hall_of_fame;
+pub mod health;
+pub mod heartbeat;
+pub mod hot_reload;
+pub mod incidents;
+pub mod interchange;
+pub mod journal;
+pub mod latency;
+pub mod ledger;
+pub mod live_feed;
+pub mod market_data;
+pub mod metric_cache;
+#[cfg(any(test, feature = "test-support"))]
+pub mod mock_exchange;
+pub mod money;
+pub mod monte_carlo;
+pub mod mutation;
+pub mod order_manager;
+pub mod pattern_replay;
+pub mod patterns;
+#[cfg(test)]
+mod pipeline_tests;
+pub mod performance;
+pub mod plugins;
+pub mod preflight;
+pub mod readiness;
+pub mod reconciliation;
+pub mod recovery;
+pub mod redis;
 pub mod risk_manager;
+pub mod risk_state;
+pub mod retention;
+pub mod review_queue;
+pub mod rng;
+pub mod run_manifest;
+pub mod secrets;
+pub mod seeding;
+pub mod shutdown;
+pub mod speciation;
+pub mod stop_engine;
+pub mod stress;
+pub mod tax_lots;
+pub mod telemetry;
+pub mod test_budget;
+pub mod test_stats;
+pub mod tuning;
+pub mod webhooks;
+pub mod write_queue;
 
 // Re-export main structs for convenience
-pub use discovery_engine::*;
-pub use risk_manager::*;
+pub use accounts::{AccountBook, AccountConfig, AccountRole};
+pub use alerts::{Alert, AlertChannel, AlertManager, Severity};
+pub use anomaly::{Anomaly, AnomalyDetector};
+pub use audit::{AuditAction, AuditEntry, AuditLogger};
+pub use autoscale::{AutoscalePolicy, Autoscaler, RequestMeter};
+pub use backtest::BacktestReport;
+pub use batch_writer::{BatchConfig, BatchWriter};
+pub use bus::{Bus, BusConfig, Envelope, Topic};
+pub use cadence::EvolutionCadence;
+pub use chaos::{ChaosConfig, ChaosExchange, Fault, FaultInjector};
+pub use clock::{Calendar, Clock, SharedClock, SimulatedClock, SystemClock, TradingClock};
+pub use costs::{CostModel, TradeCosts, VenueFees};
+pub use daily_report::{DailyReport, DayRollover, EquityPoint};
+pub use discovery_engine::{Condition, DiscoveryEngine, Hypothesis, Pattern, TestResult};
+pub use drift_report::{DriftTracker, FillComparison, PatternDrift};
+pub use embedding::{Neighbor, PatternIndex};
+pub use equity::{EquityAnalytics, EquitySnapshot, EquityTracker};
+pub use evaluator::{Candle, ConditionEval, Signal, SignalKind, Step, TradeTick};
+pub use evolution_engine::{EvolutionConfig, EvolutionEngine, Offspring};
+pub use exchange::{Exchange, ExchangeError, OrderRequest, OrderType, PaperExchange, Side};
+pub use execution_algo::ExecutionAlgo;
+pub use execution_context::{ExecutionContext, Venue};
+pub use execution_engine::{ExecutionEngine, FlattenReport, OrderIntent, RetryPolicy};
+pub use execution_quality::{Decision, ExecutionQuality};
+pub use execution_style::ExecutionStyle;
+pub use feature_flags::{FeatureFlags, Flag, FlagState, FlagUpdate};
+pub use fitness::{FitnessFunction, FitnessMetric};
+pub use genome::{Genome, GENOME_SCHEMA_VERSION};
+pub use hall_of_fame::{HallOfFame, HallOfFameEntry};
+pub use health::{CheckStatus, HealthReport, HealthState};
+pub use heartbeat::Heartbeat;
+pub use hot_reload::{ConfigWatcher, ReloadPlan};
+pub use incidents::{Incident, IncidentManager, RearmBlocked};
+pub use interchange::{BundleFormat, PatternBundle};
+pub use journal::{JournalFormat, JournalRow};
+pub use ledger::{Ledger, LedgerWrite, RiskEvent};
+pub use live_feed::{LiveEvent, LiveFeed};
+pub use market_data::{MarketDataFeed, PriceTick};
+pub use money::Decimal;
+pub use monte_carlo::{MonteCarloConfig, Projection, TradeSample};
+pub use mutation::{MutationCounts, MutationOperator, MutationRates};
+pub use order_manager::{ManagedOrder, OrderManager, OrderState};
+pub use patterns::PatternListing;
+pub use plugins::{PluginService, SubmissionQueue};
+pub use performance::{PerformanceSnapshot, PerformanceWindow};
+pub use preflight::{Preflight, PreflightConfig};
+pub use readiness::{Gate, Startup};
+pub use reconciliation::{Reconciler, ReconciliationReport};
+pub use recovery::RecoveryReport;
+pub use risk_manager::{BreakerStates, OrderRejection, Position, ReduceOnlyReason, RiskManager};
+pub use review_queue::{LiveTradeJournal, ReviewQueue, ReviewVerdict, TradeJournal};
+pub use rng::SharedRng;
+pub use run_manifest::RunManifest;
+pub use secrets::{SecretError, SecretSource, Secrets};
+pub use seeding::{HypothesisTemplate, SeedSource, SeedingConfig, TemplatePool};
+pub use stop_engine::{ExitReason, ExitSignal, StopEngine, TrailingConfig};
+pub use stress::{Scenario, StressConfig, StressReport, StressStrategy};
+pub use tax_lots::{Disposal, LotBook, LotMethod, TaxReport};
+pub use telemetry::LogFormat;
+pub use test_budget::{BudgetPolicy, TestBudget};
+pub use tuning::{SharedTuning, Tuning, TuningUpdate};
+pub use webhooks::{WebhookEvent, WebhookNotifier};
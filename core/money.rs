@@ -0,0 +1,51 @@
+// Money - Exact Amounts for Capital, Sizes, Prices and P&L
+// rust_decimal::Decimal for everything we account in, and the few conversions at the edges
+// where f64 market data, rates and statistics meet it
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+
+pub use rust_decimal::Decimal;
+
+/// Decimal places capital is held to (see risk_state::AtomicMoney)
+pub const MONEY_DP: u32 = 8;
+
+/// A measured price or amount as money; NaN and infinities become zero
+pub fn from_f64(value: f64) -> Decimal {
+    Decimal::from_f64(value).unwrap_or_default()
+}
+
+/// Money as a measurement, for ratios and statistics
+pub fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
+
+/// `fraction` of `amount`, e.g. a limit expressed as a share of capital
+pub fn share(amount: Decimal, fraction: f64) -> Decimal {
+    amount * from_f64(fraction)
+}
+
+/// `part` over `whole` as a measurement; 0.0 when there is no whole
+pub fn ratio(part: Decimal, whole: Decimal) -> f64 {
+    part.checked_div(whole).map_or(0.0, to_f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_a_thousand_fees_add_up_exactly() {
+        let fee = dec!(0.1);
+        let capital = (0..1000).fold(dec!(1000), |capital, _| capital - fee);
+        assert_eq!(capital, dec!(900));
+        let drifted = (0..1000).fold(1000.0_f64, |capital, _| capital - 0.1);
+        assert_ne!(drifted, 900.0);
+
+        assert_eq!(from_f64(0.1), dec!(0.1));
+        assert_eq!(from_f64(f64::NAN), Decimal::ZERO);
+        assert_eq!(share(dec!(1000), 0.25), dec!(250));
+        assert_eq!(ratio(dec!(25), dec!(1000)), 0.025);
+        assert_eq!(ratio(dec!(25), Decimal::ZERO), 0.0);
+    }
+}
@@ -0,0 +1,191 @@
+// Monte Carlo - Equity Paths Resampled From Realized Trades
+// The closed trades of today's active patterns over the lookback window are the distribution:
+// each simulated day draws as many trades as the window averaged per day, each one a realized
+// trade's P&L picked at random with replacement, added to current equity. Thousands of such
+// paths give the spread of equity at the horizon, the drawdown a typical path suffers, and the
+// probability of ruin: equity touching (1 - ruin_pct) of where it started. `v26meme
+// monte-carlo` prints a projection and each daily report carries one; draws come from the run seed
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+
+use crate::core::rng::SharedRng;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MonteCarloConfig {
+    pub paths: usize,
+    pub horizon_days: u32,
+    pub ruin_pct: f64,       // share of starting equity lost that counts as ruin
+    pub lookback_days: i64,  // realized trades sampled from
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        MonteCarloConfig { paths: 10_000, horizon_days: 30, ruin_pct: 0.5, lookback_days: 90 }
+    }
+}
+
+impl MonteCarloConfig {
+    pub fn from_env() -> Self {
+        let defaults = MonteCarloConfig::default();
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok()).filter(|v| *v > 0);
+        MonteCarloConfig {
+            paths: var("MONTE_CARLO_PATHS").map_or(defaults.paths, |v| v as usize),
+            horizon_days: var("MONTE_CARLO_HORIZON_DAYS").map_or(defaults.horizon_days, |v| v as u32),
+            ruin_pct: std::env::var("MONTE_CARLO_RUIN_PCT").ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|v| *v > 0.0 && *v <= 1.0)
+                .unwrap_or(defaults.ruin_pct),
+            lookback_days: var("MONTE_CARLO_LOOKBACK_DAYS").map_or(defaults.lookback_days, |v| v as i64),
+        }
+    }
+}
+
+/// Realized trade P&L to resample, and how often trades closed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TradeSample {
+    pub pnls: Vec<f64>,
+    pub trades_per_day: f64,
+}
+
+impl TradeSample {
+    pub fn new(pnls: Vec<f64>, days: i64) -> Self {
+        let trades_per_day = pnls.len() as f64 / days.max(1) as f64;
+        TradeSample { pnls, trades_per_day }
+    }
+}
+
+/// Closed trades of currently active patterns since `since`
+pub async fn load_trades(db_pool: &PgPool, since: DateTime<Utc>, days: i64) -> Result<TradeSample, sqlx::Error> {
+    let rows = sqlx::query("
+        SELECT t.profit_loss::float8 AS pnl
+        FROM trades t
+        JOIN discovered_patterns p ON p.pattern_hash = t.pattern_hash
+        WHERE p.is_active AND t.status = 'closed' AND t.profit_loss IS NOT NULL AND t.exit_time >= $1
+        ORDER BY t.exit_time
+    ")
+        .bind(since)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(TradeSample::new(rows.iter().map(|row| row.get("pnl")).collect(), days))
+}
+
+/// Equity across paths at the end of one simulated day
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EquityBand {
+    pub day: u32,
+    pub p5: f64,
+    pub p50: f64,
+    pub p95: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Projection {
+    pub paths: usize,
+    pub horizon_days: u32,
+    pub trades_sampled: usize,
+    pub trades_per_day: f64,
+    pub starting_equity: f64,
+    pub ruin_level: f64,
+    pub probability_of_ruin: f64,
+    pub probability_of_loss: f64,    // ending below starting equity
+    pub median_max_drawdown: f64,    // 0.0-1.0 below each path's running peak
+    pub bands: Vec<EquityBand>,      // one per day; the last is the horizon
+}
+
+impl Projection {
+    pub fn at_horizon(&self) -> Option<&EquityBand> {
+        self.bands.last()
+    }
+}
+
+/// None without any realized trades to draw from
+pub fn project(sample: &TradeSample, starting_equity: f64, config: &MonteCarloConfig, rng: &mut SharedRng) -> Option<Projection> {
+    if sample.pnls.is_empty() || config.paths == 0 || config.horizon_days == 0 {
+        return None;
+    }
+    let ruin_level = starting_equity * (1.0 - config.ruin_pct);
+    let whole = sample.trades_per_day.floor() as usize;
+    let fraction = sample.trades_per_day.fract();
+
+    let days = config.horizon_days as usize;
+    let mut by_day: Vec<Vec<f64>> = vec![Vec::with_capacity(config.paths); days];
+    let mut drawdowns = Vec::with_capacity(config.paths);
+    let mut ruined = 0;
+    for _ in 0..config.paths {
+        let (mut equity, mut peak, mut max_drawdown, mut is_ruined) = (starting_equity, starting_equity, 0.0_f64, false);
+        for day in by_day.iter_mut() {
+            let trades = whole + usize::from(rng.gen_bool(fraction));
+            for _ in 0..trades {
+                if is_ruined {
+                    break;
+                }
+                equity += sample.pnls[rng.gen_range(0..sample.pnls.len())];
+                peak = peak.max(equity);
+                if peak > 0.0 {
+                    max_drawdown = max_drawdown.max((peak - equity) / peak);
+                }
+                is_ruined = equity <= ruin_level;
+            }
+            day.push(equity);
+        }
+        ruined += usize::from(is_ruined);
+        drawdowns.push(max_drawdown.min(1.0));
+    }
+
+    let bands: Vec<EquityBand> = by_day.iter_mut().enumerate().map(|(day, equities)| {
+        equities.sort_by(f64::total_cmp);
+        EquityBand { day: day as u32 + 1, p5: percentile(equities, 0.05), p50: percentile(equities, 0.50), p95: percentile(equities, 0.95) }
+    }).collect();
+    let finals = by_day.last()?;
+    drawdowns.sort_by(f64::total_cmp);
+
+    Some(Projection {
+        paths: config.paths,
+        horizon_days: config.horizon_days,
+        trades_sampled: sample.pnls.len(),
+        trades_per_day: sample.trades_per_day,
+        starting_equity,
+        ruin_level,
+        probability_of_ruin: ruined as f64 / config.paths as f64,
+        probability_of_loss: finals.iter().filter(|e| **e < starting_equity).count() as f64 / config.paths as f64,
+        median_max_drawdown: percentile(&drawdowns, 0.50),
+        bands,
+    })
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    sorted[((sorted.len() - 1) as f64 * p).round() as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projection_spreads_and_ruin_follow_the_sample() {
+        let config = MonteCarloConfig { paths: 2_000, horizon_days: 20, ..MonteCarloConfig::default() };
+        let winning = TradeSample::new(vec![30.0, -10.0, 25.0, -15.0, 40.0], 5);
+        let projection = project(&winning, 1000.0, &config, &mut SharedRng::seeded(3)).unwrap();
+        assert_eq!(projection.bands.len(), 20);
+        let horizon = projection.at_horizon().unwrap();
+        assert!(horizon.p5 < horizon.p50 && horizon.p50 < horizon.p95);
+        assert!((horizon.p50 - 1000.0 - 20.0 * 14.0).abs() < 60.0, "{:?}", horizon);
+        assert_eq!(projection.probability_of_ruin, 0.0);
+        assert!(projection.bands.windows(2).all(|w| w[0].p50 <= w[1].p50));
+
+        // A losing book is ruined on most paths; the same seed replays the same projection
+        let losing = TradeSample::new(vec![-60.0, 20.0, -40.0], 1);
+        let ruinous = project(&losing, 1000.0, &config, &mut SharedRng::seeded(3)).unwrap();
+        assert!(ruinous.probability_of_ruin > 0.9 && ruinous.probability_of_loss > 0.9, "{:?}", ruinous.probability_of_ruin);
+        assert_eq!(ruinous, project(&losing, 1000.0, &config, &mut SharedRng::seeded(3)).unwrap());
+        assert!(project(&TradeSample::default(), 1000.0, &config, &mut SharedRng::seeded(3)).is_none());
+    }
+}
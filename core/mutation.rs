@@ -0,0 +1,259 @@
+// Mutation Operators
+// How evolution changes a hypothesis: nudge thresholds, add or drop a condition, swap a metric
+// for a sibling from the same family, or shift the timeframe. Each operator has its own rate,
+// and every generation records how often each fired
+
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::core::discovery_engine::{Condition, Hypothesis};
+
+/// Metrics that measure the same thing at different horizons or in different ways
+pub const METRIC_FAMILIES: &[&[&str]] = &[
+    &["price_delta_1m", "price_delta_5m", "price_delta_15m", "price_acceleration"],
+    &["volume_ratio_1m", "volume_ratio_5m", "volume_spike", "volume_acceleration"],
+    &["order_book_imbalance", "bid_ask_spread"],
+    &["trade_count_1m", "buy_sell_ratio"],
+    &["sentiment_1h", "news_shock"],
+    &["social_velocity", "social_engagement"],
+];
+
+const OPERATORS: [&str; 5] = [">", "<", "==", "crosses_above", "crosses_below"];
+
+// Same bounds the discovery engine draws random hypotheses from
+const MAX_ENTRY_CONDITIONS: usize = 5;
+const MAX_EXIT_CONDITIONS: usize = 3;
+const MAX_TIMEFRAME_MINUTES: u32 = 1439;
+
+pub fn family_of(metric: &str) -> Option<&'static [&'static str]> {
+    METRIC_FAMILIES.iter().copied().find(|family| family.contains(&metric))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MutationOperator {
+    Threshold,
+    AddCondition,
+    RemoveCondition,
+    SwapMetric,
+    ShiftTimeframe,
+}
+
+/// Chance of each operator firing on one mutant; `threshold` applies per condition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MutationRates {
+    pub threshold: f64,
+    pub add_condition: f64,
+    pub remove_condition: f64,
+    pub swap_metric: f64,
+    pub shift_timeframe: f64,
+}
+
+impl Default for MutationRates {
+    fn default() -> Self {
+        MutationRates {
+            threshold: 0.1,
+            add_condition: 0.1,
+            remove_condition: 0.1,
+            swap_metric: 0.15,
+            shift_timeframe: 0.1,
+        }
+    }
+}
+
+/// How many times each operator fired in one generation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MutationCounts {
+    pub threshold: usize,
+    pub add_condition: usize,
+    pub remove_condition: usize,
+    pub swap_metric: usize,
+    pub shift_timeframe: usize,
+}
+
+impl MutationCounts {
+    pub fn record(&mut self, operator: MutationOperator) {
+        match operator {
+            MutationOperator::Threshold => self.threshold += 1,
+            MutationOperator::AddCondition => self.add_condition += 1,
+            MutationOperator::RemoveCondition => self.remove_condition += 1,
+            MutationOperator::SwapMetric => self.swap_metric += 1,
+            MutationOperator::ShiftTimeframe => self.shift_timeframe += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.threshold + self.add_condition + self.remove_condition + self.swap_metric + self.shift_timeframe
+    }
+}
+
+/// Apply each operator with its rate; returns the operators that changed something
+/// A mutant never comes out identical to its parent: if nothing fired, one threshold moves
+pub fn mutate_hypothesis<R: Rng>(h: &mut Hypothesis, rates: &MutationRates, rng: &mut R) -> Vec<MutationOperator> {
+    let mut applied = Vec::new();
+
+    for condition in h.entry_conditions.iter_mut().chain(h.exit_conditions.iter_mut()) {
+        if rng.gen_bool(rates.threshold) {
+            nudge_threshold(condition, rng);
+            applied.push(MutationOperator::Threshold);
+        }
+    }
+    if rng.gen_bool(rates.add_condition) && add_condition(h, rng) {
+        applied.push(MutationOperator::AddCondition);
+    }
+    if rng.gen_bool(rates.remove_condition) && remove_condition(h, rng) {
+        applied.push(MutationOperator::RemoveCondition);
+    }
+    if rng.gen_bool(rates.swap_metric) && swap_metric(h, rng) {
+        applied.push(MutationOperator::SwapMetric);
+    }
+    if rng.gen_bool(rates.shift_timeframe) && shift_timeframe(h, rng) {
+        applied.push(MutationOperator::ShiftTimeframe);
+    }
+
+    if applied.is_empty() {
+        let total = h.entry_conditions.len() + h.exit_conditions.len();
+        if total > 0 {
+            let pick = rng.gen_range(0..total);
+            let condition = if pick < h.entry_conditions.len() {
+                &mut h.entry_conditions[pick]
+            } else {
+                &mut h.exit_conditions[pick - h.entry_conditions.len()]
+            };
+            nudge_threshold(condition, rng);
+            applied.push(MutationOperator::Threshold);
+        }
+    }
+
+    applied
+}
+
+/// ±10% of the current threshold
+fn nudge_threshold<R: Rng>(condition: &mut Condition, rng: &mut R) {
+    condition.value *= rng.gen_range(0.9..1.1);
+}
+
+/// A fresh random condition on the entry or exit side, unless that side is full
+fn add_condition<R: Rng>(h: &mut Hypothesis, rng: &mut R) -> bool {
+    let entry = rng.gen_bool(0.5);
+    let (conditions, max) = if entry {
+        (&mut h.entry_conditions, MAX_ENTRY_CONDITIONS)
+    } else {
+        (&mut h.exit_conditions, MAX_EXIT_CONDITIONS)
+    };
+    if conditions.len() >= max {
+        return false;
+    }
+
+    let family = METRIC_FAMILIES[rng.gen_range(0..METRIC_FAMILIES.len())];
+    conditions.push(Condition {
+        metric: family[rng.gen_range(0..family.len())].to_string(),
+        operator: OPERATORS[rng.gen_range(0..OPERATORS.len())].to_string(),
+        value: rng.gen_range(-100.0..100.0),
+        weight: rng.gen_range(0.1..1.0),
+    });
+    true
+}
+
+/// Drop one condition from a side that has more than one
+fn remove_condition<R: Rng>(h: &mut Hypothesis, rng: &mut R) -> bool {
+    let entry = rng.gen_bool(0.5);
+    let conditions = if entry { &mut h.entry_conditions } else { &mut h.exit_conditions };
+    if conditions.len() <= 1 {
+        return false;
+    }
+
+    conditions.remove(rng.gen_range(0..conditions.len()));
+    true
+}
+
+/// Replace one condition's metric with another member of its family; random metrics have no family
+fn swap_metric<R: Rng>(h: &mut Hypothesis, rng: &mut R) -> bool {
+    let mut candidates: Vec<&mut Condition> = h.entry_conditions.iter_mut()
+        .chain(h.exit_conditions.iter_mut())
+        .filter(|c| family_of(&c.metric).is_some())
+        .collect();
+    if candidates.is_empty() {
+        return false;
+    }
+
+    let pick = rng.gen_range(0..candidates.len());
+    let condition = &mut candidates[pick];
+    let siblings: Vec<&str> = family_of(&condition.metric)
+        .unwrap_or_default()
+        .iter()
+        .copied()
+        .filter(|m| *m != condition.metric)
+        .collect();
+    if siblings.is_empty() {
+        return false;
+    }
+
+    condition.metric = siblings[rng.gen_range(0..siblings.len())].to_string();
+    true
+}
+
+/// ±20% of the current timeframe, kept within a day
+fn shift_timeframe<R: Rng>(h: &mut Hypothesis, rng: &mut R) -> bool {
+    let scaled = h.timeframe as f64 * rng.gen_range(0.8..1.2);
+    let shifted = (scaled.round() as u32).clamp(1, MAX_TIMEFRAME_MINUTES);
+    let changed = shifted != h.timeframe;
+    h.timeframe = shifted;
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn hypothesis() -> Hypothesis {
+        let condition = |metric: &str| Condition { metric: metric.to_string(), operator: ">".to_string(), value: 10.0, weight: 0.5 };
+        Hypothesis {
+            hash: "parent".to_string(),
+            entry_conditions: vec![condition("price_delta_5m"), condition("volume_spike")],
+            exit_conditions: vec![condition("metric_deadbeef")],
+            timeframe: 120,
+            created_at: 0,
+            symbol: None,
+        }
+    }
+
+    #[test]
+    fn test_each_operator_respects_its_bounds() {
+        let mut rng = StdRng::seed_from_u64(11);
+
+        for _ in 0..200 {
+            let mut h = hypothesis();
+            let rates = MutationRates { threshold: 0.0, add_condition: 1.0, remove_condition: 1.0, swap_metric: 1.0, shift_timeframe: 1.0 };
+            let applied = mutate_hypothesis(&mut h, &rates, &mut rng);
+
+            assert!(!applied.is_empty());
+            assert!((1..=MAX_ENTRY_CONDITIONS).contains(&h.entry_conditions.len()));
+            assert!((1..=MAX_EXIT_CONDITIONS).contains(&h.exit_conditions.len()));
+            assert!((1..=MAX_TIMEFRAME_MINUTES).contains(&h.timeframe));
+            if applied.contains(&MutationOperator::SwapMetric) {
+                // Swaps stay in family, and the unknown metric is never touched
+                for c in h.entry_conditions.iter().chain(&h.exit_conditions) {
+                    assert!(family_of(&c.metric).is_some() || c.metric == "metric_deadbeef");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mutant_always_differs_and_counts_are_recorded() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let rates = MutationRates { threshold: 0.0, add_condition: 0.0, remove_condition: 0.0, swap_metric: 0.0, shift_timeframe: 0.0 };
+        let mut h = hypothesis();
+
+        let applied = mutate_hypothesis(&mut h, &rates, &mut rng);
+        assert_eq!(applied, vec![MutationOperator::Threshold]);
+        assert!(h.entry_conditions.iter().chain(&h.exit_conditions).any(|c| c.value != 10.0));
+
+        let mut counts = MutationCounts::default();
+        applied.into_iter().for_each(|op| counts.record(op));
+        assert_eq!(counts, MutationCounts { threshold: 1, ..Default::default() });
+    }
+}
@@ -0,0 +1,521 @@
+// Order Manager - Explicit Order Lifecycle With Persistence
+// PendingSubmit → Open → PartiallyFilled → Filled / Cancelled / Rejected
+// Every transition is written to the orders table before anyone acts on it,
+// so a restart can pick up exactly where the last process left off
+
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+use tracing::{info, warn, error};
+
+use crate::core::anomaly::{self, Event};
+use crate::core::chaos::FaultInjector;
+use crate::core::exchange::{Exchange, OrderRequest, OrderStatusReport, OrderType, VenueOrderState};
+use crate::core::execution_engine::OrderIntent;
+use crate::core::money::Decimal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderState {
+    PendingSubmit,    // persisted, not yet acknowledged by the venue
+    Open,
+    PartiallyFilled,
+    Filled,
+    Cancelled,
+    Rejected,
+}
+
+impl OrderState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderState::PendingSubmit => "pending_submit",
+            OrderState::Open => "open",
+            OrderState::PartiallyFilled => "partially_filled",
+            OrderState::Filled => "filled",
+            OrderState::Cancelled => "cancelled",
+            OrderState::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending_submit" => Some(OrderState::PendingSubmit),
+            "open" => Some(OrderState::Open),
+            "partially_filled" => Some(OrderState::PartiallyFilled),
+            "filled" => Some(OrderState::Filled),
+            "cancelled" => Some(OrderState::Cancelled),
+            "rejected" => Some(OrderState::Rejected),
+            _ => None,
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, OrderState::Filled | OrderState::Cancelled | OrderState::Rejected)
+    }
+
+    /// Legal moves of the state machine; staying put is always allowed for live orders
+    pub fn can_transition_to(&self, next: OrderState) -> bool {
+        use OrderState::*;
+        matches!(
+            (self, next),
+            (PendingSubmit, _)
+                | (Open, Open | PartiallyFilled | Filled | Cancelled)
+                | (PartiallyFilled, PartiallyFilled | Filled | Cancelled)
+        )
+    }
+
+    /// Where a venue status report puts an order
+    pub fn from_report(report: &OrderStatusReport) -> Self {
+        match report.state {
+            VenueOrderState::Open if report.filled_size > Decimal::ZERO => OrderState::PartiallyFilled,
+            VenueOrderState::Open => OrderState::Open,
+            VenueOrderState::Filled => OrderState::Filled,
+            VenueOrderState::Cancelled => OrderState::Cancelled,
+            VenueOrderState::Rejected => OrderState::Rejected,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedOrder {
+    pub client_order_id: String,
+    pub exchange: String,
+    pub exchange_order_id: Option<String>,
+    pub request: OrderRequest,
+    pub intent: OrderIntent,
+    pub state: OrderState,
+    pub filled_size: Decimal,
+    pub avg_fill_price: Decimal,
+    pub fees: Decimal,
+    pub parent_order_id: Option<String>,  // set on re-priced residuals and later algo slices
+    pub reprice_count: u32,
+    pub submitted_at: Option<DateTime<Utc>>,   // first acknowledgement from the venue
+    pub first_fill_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ManagedOrder {
+    /// Notional still to be filled
+    pub fn remaining(&self) -> Decimal {
+        (self.request.size - self.filled_size).max(Decimal::ZERO)
+    }
+
+    /// The position an opening order builds: re-priced residuals and algo slices add to their root order's position
+    pub fn position_id(&self) -> &str {
+        self.parent_order_id.as_deref().unwrap_or(&self.client_order_id)
+    }
+}
+
+/// What filled between two consecutive reports for the same order
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FillDelta {
+    pub size: Decimal,
+    pub price: Decimal,
+    pub fees: Decimal,
+}
+
+impl FillDelta {
+    /// Difference between cumulative fill figures, None when nothing new filled
+    pub fn between(before: &ManagedOrder, report: &OrderStatusReport) -> Option<Self> {
+        let size = report.filled_size - before.filled_size;
+        if size <= Decimal::ZERO {
+            return None;
+        }
+
+        let notional = report.avg_fill_price * report.filled_size - before.avg_fill_price * before.filled_size;
+        Some(FillDelta {
+            size,
+            price: notional / size,
+            fees: (report.fees - before.fees).max(Decimal::ZERO),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum OrderError {
+    UnknownOrder(String),
+    InvalidTransition { client_order_id: String, from: OrderState, to: OrderState },
+    Database(sqlx::Error),
+    Encoding(serde_json::Error),  // request or intent that won't serialize into the orders row
+}
+
+impl std::fmt::Display for OrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderError::UnknownOrder(id) => write!(f, "unknown order {}", id),
+            OrderError::InvalidTransition { client_order_id, from, to } => {
+                write!(f, "order {} cannot move from {} to {}", client_order_id, from.as_str(), to.as_str())
+            }
+            OrderError::Database(e) => write!(f, "database error: {}", e),
+            OrderError::Encoding(e) => write!(f, "order won't serialize: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for OrderError {}
+
+impl From<sqlx::Error> for OrderError {
+    fn from(e: sqlx::Error) -> Self {
+        OrderError::Database(e)
+    }
+}
+
+impl From<serde_json::Error> for OrderError {
+    fn from(e: serde_json::Error) -> Self {
+        OrderError::Encoding(e)
+    }
+}
+
+pub struct OrderManager {
+    pub run_id: Option<Uuid>,
+    pub chaos: FaultInjector,  // times out some writes in chaos runs
+    orders: HashMap<String, ManagedOrder>,
+    db_pool: Option<PgPool>,  // None keeps everything in memory (tests, dry runs)
+}
+
+impl OrderManager {
+    pub fn new(db_pool: PgPool) -> Self {
+        OrderManager { run_id: None, chaos: FaultInjector::default(), orders: HashMap::new(), db_pool: Some(db_pool) }
+    }
+
+    pub fn in_memory() -> Self {
+        OrderManager { run_id: None, chaos: FaultInjector::default(), orders: HashMap::new(), db_pool: None }
+    }
+
+    pub fn db_pool(&self) -> Option<&PgPool> {
+        self.db_pool.as_ref()
+    }
+
+    pub fn get(&self, client_order_id: &str) -> Option<&ManagedOrder> {
+        self.orders.get(client_order_id)
+    }
+
+    /// Orders the venue is still working (or that were never acknowledged)
+    pub fn working(&self) -> impl Iterator<Item = &ManagedOrder> {
+        self.orders.values().filter(|o| !o.state.is_terminal())
+    }
+
+    /// Record a new order as PendingSubmit; it must be durable before it is sent
+    pub async fn create(&mut self, exchange: &str, request: OrderRequest, intent: OrderIntent) -> Result<(), OrderError> {
+        self.create_child(None, exchange, request, intent).await
+    }
+
+    /// Record an order that fills into an earlier order's position (algo slices after the first)
+    pub async fn create_child(
+        &mut self,
+        parent_order_id: Option<&str>,
+        exchange: &str,
+        request: OrderRequest,
+        intent: OrderIntent,
+    ) -> Result<(), OrderError> {
+        let order = ManagedOrder {
+            client_order_id: request.client_order_id.clone(),
+            exchange: exchange.to_string(),
+            exchange_order_id: None,
+            request,
+            intent,
+            state: OrderState::PendingSubmit,
+            filled_size: Decimal::ZERO,
+            avg_fill_price: Decimal::ZERO,
+            fees: Decimal::ZERO,
+            parent_order_id: parent_order_id.map(str::to_string),
+            reprice_count: 0,
+            submitted_at: None,
+            first_fill_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        self.insert(order).await
+    }
+
+    /// Record the residual of a cancelled order, re-priced as a new order
+    pub async fn create_reprice(&mut self, parent: &ManagedOrder, request: OrderRequest) -> Result<(), OrderError> {
+        let order = ManagedOrder {
+            client_order_id: request.client_order_id.clone(),
+            exchange: parent.exchange.clone(),
+            exchange_order_id: None,
+            request,
+            intent: parent.intent.clone(),
+            state: OrderState::PendingSubmit,
+            filled_size: Decimal::ZERO,
+            avg_fill_price: Decimal::ZERO,
+            fees: Decimal::ZERO,
+            parent_order_id: Some(parent.position_id().to_string()),
+            reprice_count: parent.reprice_count + 1,
+            submitted_at: None,
+            first_fill_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        self.insert(order).await
+    }
+
+    async fn insert(&mut self, order: ManagedOrder) -> Result<(), OrderError> {
+        if let Some(db_pool) = &self.db_pool {
+            let limit_price = match order.request.order_type {
+                OrderType::Limit(price) => Some(price),
+                OrderType::Market => None,
+            };
+
+            self.chaos.db(sqlx::query("
+                INSERT INTO orders
+                (client_order_id, exchange, symbol, side, size, limit_price, reduce_only,
+                 request, intent, state, parent_order_id, reprice_count, run_id, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $14)
+            ")
+                .bind(&order.client_order_id)
+                .bind(&order.exchange)
+                .bind(&order.request.symbol)
+                .bind(order.request.side.as_str())
+                .bind(order.request.size)
+                .bind(limit_price)
+                .bind(order.request.reduce_only)
+                .bind(serde_json::to_value(&order.request)?)
+                .bind(serde_json::to_value(&order.intent)?)
+                .bind(order.state.as_str())
+                .bind(&order.parent_order_id)
+                .bind(order.reprice_count as i32)
+                .bind(self.run_id)
+                .bind(order.created_at)
+                .execute(db_pool))
+                .await?;
+        }
+
+        self.orders.insert(order.client_order_id.clone(), order);
+        Ok(())
+    }
+
+    /// Move an order according to the venue's latest report
+    /// Also returns whatever filled since the previous report
+    pub async fn apply_report(
+        &mut self,
+        client_order_id: &str,
+        report: &OrderStatusReport,
+    ) -> Result<(ManagedOrder, Option<FillDelta>), OrderError> {
+        let next = OrderState::from_report(report);
+        let order = self.orders.get_mut(client_order_id)
+            .ok_or_else(|| OrderError::UnknownOrder(client_order_id.to_string()))?;
+
+        if !order.state.can_transition_to(next) {
+            return Err(OrderError::InvalidTransition {
+                client_order_id: client_order_id.to_string(),
+                from: order.state,
+                to: next,
+            });
+        }
+
+        let fill = FillDelta::between(order, report);
+        let now = Utc::now();
+        order.submitted_at.get_or_insert(now);
+        if fill.is_some() {
+            order.first_fill_at.get_or_insert(now);
+        }
+        order.state = next;
+        order.exchange_order_id = Some(report.exchange_order_id.clone());
+        order.filled_size = report.filled_size;
+        order.avg_fill_price = report.avg_fill_price;
+        order.fees = report.fees;
+        order.updated_at = now;
+        let updated = order.clone();
+
+        self.persist(&updated, None).await;
+        self.forget_if_finished(&updated);
+        Ok((updated, fill))
+    }
+
+    /// The venue never accepted the order (submit failed outright)
+    pub async fn reject(&mut self, client_order_id: &str, reason: &str) -> Result<ManagedOrder, OrderError> {
+        let order = self.orders.get_mut(client_order_id)
+            .ok_or_else(|| OrderError::UnknownOrder(client_order_id.to_string()))?;
+
+        if !order.state.can_transition_to(OrderState::Rejected) {
+            return Err(OrderError::InvalidTransition {
+                client_order_id: client_order_id.to_string(),
+                from: order.state,
+                to: OrderState::Rejected,
+            });
+        }
+
+        order.state = OrderState::Rejected;
+        order.updated_at = Utc::now();
+        let updated = order.clone();
+
+        self.persist(&updated, Some(reason)).await;
+        self.forget_if_finished(&updated);
+        Ok(updated)
+    }
+
+    /// Tag every order still working as orphaned, for the next start to sweep
+    /// Returns the client order ids tagged; they stay tracked until the process exits
+    pub async fn mark_orphaned(&self) -> Result<Vec<String>, OrderError> {
+        let ids: Vec<String> = self.working().map(|o| o.client_order_id.clone()).collect();
+        if let (Some(db_pool), false) = (&self.db_pool, ids.is_empty()) {
+            sqlx::query("UPDATE orders SET orphaned_at = NOW(), updated_at = NOW() WHERE client_order_id = ANY($1)")
+                .bind(&ids)
+                .execute(db_pool)
+                .await?;
+        }
+        Ok(ids)
+    }
+
+    /// Unfinished orders a previous process tagged as orphaned when it shut down
+    pub async fn orphaned(&self) -> Result<Vec<String>, OrderError> {
+        let Some(db_pool) = &self.db_pool else {
+            return Ok(Vec::new());
+        };
+        let rows = sqlx::query("
+            SELECT client_order_id FROM orders
+            WHERE orphaned_at IS NOT NULL AND state IN ('pending_submit', 'open', 'partially_filled')
+        ")
+            .fetch_all(db_pool)
+            .await?;
+        Ok(rows.iter().map(|row| row.get("client_order_id")).collect())
+    }
+
+    /// Finished orders live on in the orders table only
+    fn forget_if_finished(&mut self, order: &ManagedOrder) {
+        if order.state.is_terminal() {
+            self.orders.remove(&order.client_order_id);
+        }
+    }
+
+    /// A failed status write is logged, not fatal: the venue remains the source of truth
+    /// and recovery re-reads it on the next start
+    async fn persist(&self, order: &ManagedOrder, reject_reason: Option<&str>) {
+        let Some(db_pool) = &self.db_pool else {
+            return;
+        };
+
+        let result = self.chaos.db(sqlx::query("
+            UPDATE orders
+            SET state = $2, exchange_order_id = $3, filled_size = $4, avg_fill_price = $5,
+                fees = $6, reject_reason = COALESCE($7, reject_reason), updated_at = $8,
+                submitted_at = $9, first_fill_at = $10
+            WHERE client_order_id = $1
+        ")
+            .bind(&order.client_order_id)
+            .bind(order.state.as_str())
+            .bind(&order.exchange_order_id)
+            .bind(order.filled_size)
+            .bind(order.avg_fill_price)
+            .bind(order.fees)
+            .bind(reject_reason)
+            .bind(order.updated_at)
+            .bind(order.submitted_at)
+            .bind(order.first_fill_at)
+            .execute(db_pool))
+            .await;
+
+        if let Err(e) = result {
+            error!("❌ Failed to persist order {} ({}): {}", order.client_order_id, order.state.as_str(), e);
+            anomaly::note(Event::DbWriteError);
+        }
+    }
+
+    /// Reload every unfinished order from the database and reconcile it with its venue
+    /// Returns every reconciled order with whatever filled while we were down, so the fills
+    /// can be applied; orders still working stay tracked here
+    pub async fn recover(
+        &mut self,
+        exchanges: &HashMap<String, std::sync::Arc<dyn Exchange>>,
+    ) -> Result<Vec<(ManagedOrder, Option<FillDelta>)>, OrderError> {
+        let Some(db_pool) = self.db_pool.clone() else {
+            return Ok(Vec::new());
+        };
+
+        let rows = sqlx::query("
+            SELECT client_order_id, exchange, exchange_order_id, request, intent, state,
+                   filled_size, avg_fill_price, fees, parent_order_id, reprice_count,
+                   submitted_at, first_fill_at, created_at, updated_at
+            FROM orders
+            WHERE state IN ('pending_submit', 'open', 'partially_filled')
+            ORDER BY created_at
+        ")
+            .fetch_all(&db_pool)
+            .await?;
+
+        let mut reconciled = Vec::new();
+        for row in rows {
+            let client_order_id: String = row.get("client_order_id");
+            let (Ok(request), Ok(intent)) = (
+                serde_json::from_value::<OrderRequest>(row.get("request")),
+                serde_json::from_value::<OrderIntent>(row.get("intent")),
+            ) else {
+                warn!("⚠️ Order {} has an unreadable payload, leaving it for an operator", client_order_id);
+                continue;
+            };
+
+            let order = ManagedOrder {
+                client_order_id: client_order_id.clone(),
+                exchange: row.get("exchange"),
+                exchange_order_id: row.get("exchange_order_id"),
+                request,
+                intent,
+                state: OrderState::parse(row.get("state")).unwrap_or(OrderState::PendingSubmit),
+                filled_size: row.get("filled_size"),
+                avg_fill_price: row.get("avg_fill_price"),
+                fees: row.get("fees"),
+                parent_order_id: row.get("parent_order_id"),
+                reprice_count: row.get::<i32, _>("reprice_count") as u32,
+                submitted_at: row.get("submitted_at"),
+                first_fill_at: row.get("first_fill_at"),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            };
+            let exchange_order_id = order.exchange_order_id.clone();
+            let venue = exchanges.get(&order.exchange).cloned();
+            self.orders.insert(client_order_id.clone(), order);
+
+            let Some(venue) = venue else {
+                warn!("⚠️ Order {} is on an exchange that is not configured, still tracked", client_order_id);
+                continue;
+            };
+
+            // Never acknowledged: ask the venue by our own id whether it ever arrived
+            let report = match exchange_order_id {
+                Some(exchange_order_id) => venue.order_status(&exchange_order_id).await.map(Some),
+                None => venue.find_order(&client_order_id).await,
+            };
+
+            match report {
+                Ok(Some(report)) => reconciled.push(self.apply_report(&client_order_id, &report).await?),
+                Ok(None) => reconciled.push((self.reject(&client_order_id, "never reached the venue").await?, None)),
+                Err(e) => warn!("⚠️ Could not reconcile order {}: {}", client_order_id, e),
+            }
+        }
+
+        info!("🔁 Order recovery: {} still working, {} reconciled",
+                 self.working().count(), reconciled.len());
+        Ok(reconciled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_machine() {
+        use OrderState::*;
+        assert!(PendingSubmit.can_transition_to(Filled));
+        assert!(Open.can_transition_to(PartiallyFilled));
+        assert!(PartiallyFilled.can_transition_to(Cancelled));
+        assert!(!PartiallyFilled.can_transition_to(Open));
+        assert!(!Filled.can_transition_to(Cancelled));
+        assert!(!Rejected.can_transition_to(Rejected));
+
+        let report = OrderStatusReport {
+            exchange_order_id: "x".to_string(),
+            state: VenueOrderState::Open,
+            filled_size: Decimal::TEN,
+            avg_fill_price: Decimal::ONE,
+            fees: Decimal::ZERO,
+        };
+        assert_eq!(OrderState::from_report(&report), PartiallyFilled);
+    }
+}
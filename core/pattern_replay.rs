@@ -0,0 +1,134 @@
+// Pattern Replay - One Pattern's Decisions, Minute by Minute
+// "Why did this pattern fire here?" `v26meme replay-pattern <hash>` rebuilds minute candles
+// from stored ticks, walks the pattern's hypothesis over them with the evaluator, and prints
+// every minute's condition values next to what the system actually recorded for the pattern:
+// risk approvals and rejections, orders placed, exits. A minute the evaluator signals without a
+// matching decision, or a decision without a signal, is where to look
+
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+
+use crate::core::audit::AuditEntry;
+use crate::core::discovery_engine::Hypothesis;
+use crate::core::evaluator::Step;
+
+/// The columns `hypothesis` reads from discovered_patterns
+pub const PATTERN_COLUMNS: &str =
+    "pattern_hash, entry_conditions, exit_conditions, COALESCE(timeframe_minutes, 60) AS timeframe_minutes, symbol, created_at";
+
+/// A stored pattern's hypothesis and when it was discovered
+pub async fn load_pattern(db_pool: &PgPool, pattern_hash: &str) -> Result<Option<(Hypothesis, DateTime<Utc>)>, sqlx::Error> {
+    let row = sqlx::query(&format!("SELECT {} FROM discovered_patterns WHERE pattern_hash = $1", PATTERN_COLUMNS))
+        .bind(pattern_hash)
+        .fetch_optional(db_pool)
+        .await?;
+
+    Ok(row.map(|row| hypothesis(&row)))
+}
+
+/// A discovered_patterns row selected with PATTERN_COLUMNS
+pub fn hypothesis(row: &PgRow) -> (Hypothesis, DateTime<Utc>) {
+    let created_at: DateTime<Utc> = row.get("created_at");
+    let hypothesis = Hypothesis {
+        hash: row.get("pattern_hash"),
+        entry_conditions: serde_json::from_value(row.get("entry_conditions")).unwrap_or_default(),
+        exit_conditions: serde_json::from_value(row.get("exit_conditions")).unwrap_or_default(),
+        timeframe: row.get::<i32, _>("timeframe_minutes") as u32,
+        created_at: created_at.timestamp(),
+        symbol: row.get("symbol"),
+    };
+    (hypothesis, created_at)
+}
+
+#[derive(Debug, Clone)]
+pub enum ReplayEvent {
+    Minute(Step),
+    Decision(AuditEntry),
+}
+
+impl ReplayEvent {
+    pub fn at(&self) -> DateTime<Utc> {
+        match self {
+            ReplayEvent::Minute(step) => step.at,
+            ReplayEvent::Decision(entry) => entry.occurred_at,
+        }
+    }
+
+    pub fn is_signal(&self) -> bool {
+        matches!(self, ReplayEvent::Minute(step) if step.signal.is_some())
+    }
+
+    /// One line: a minute's condition values and signal, or a recorded decision
+    pub fn describe(&self) -> String {
+        match self {
+            ReplayEvent::Minute(step) => {
+                let conditions: Vec<String> = step.conditions.iter().map(|c| format!("{} {} {} {} {}",
+                    if c.holds { "✓" } else { "✗" },
+                    c.metric,
+                    c.value.map_or("n/a".to_string(), |v| format!("{:.4}", v)),
+                    c.operator,
+                    c.threshold)).collect();
+                let signal = step.signal.map_or(String::new(), |kind| format!("  → {}", kind.as_str().to_uppercase()));
+                format!("{}  {:>12.6}  {:<5}  {}{}",
+                    step.at.format("%Y-%m-%d %H:%M"), step.price,
+                    if step.holding { "exit" } else { "entry" },
+                    conditions.join(" · "), signal)
+            }
+            ReplayEvent::Decision(entry) => {
+                let detail = if entry.detail.is_null() { String::new() } else { format!(" {}", entry.detail) };
+                format!("{}  🧾 {} {} {} {}{}",
+                    entry.occurred_at.format("%Y-%m-%d %H:%M:%S"), entry.component.as_str(), entry.action.as_str(),
+                    entry.subject, entry.reason_code, detail)
+            }
+        }
+    }
+}
+
+/// Minutes and decisions in one sequence; a decision lands after the minute it was made in
+pub fn timeline(steps: Vec<Step>, decisions: Vec<AuditEntry>) -> Vec<ReplayEvent> {
+    let mut events: Vec<ReplayEvent> = steps.into_iter().map(ReplayEvent::Minute)
+        .chain(decisions.into_iter().map(ReplayEvent::Decision))
+        .collect();
+    events.sort_by_key(|event| (event.at(), matches!(event, ReplayEvent::Decision(_))));
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use crate::core::audit::{AuditAction, Component};
+    use crate::core::discovery_engine::Condition;
+    use crate::core::evaluator::{self, Candle};
+
+    #[test]
+    fn test_timeline_interleaves_decisions_with_the_minutes_behind_them() {
+        let start = "2026-03-14T14:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let closes = [1.0, 1.0, 1.03, 1.04, 1.01];
+        let candles: Vec<Candle> = closes.iter().enumerate().map(|(i, close)| Candle {
+            start: start + Duration::minutes(i as i64),
+            open: *close, high: *close, low: *close, close: *close, volume: 100.0, trades: 5,
+        }).collect();
+        let condition = |operator: &str, value: f64| Condition { metric: "price_delta_1m".to_string(), operator: operator.to_string(), value, weight: 0.5 };
+        let h = Hypothesis {
+            hash: "abc".to_string(),
+            entry_conditions: vec![condition(">", 2.0)],
+            exit_conditions: vec![condition("<", -1.0)],
+            timeframe: 60,
+            created_at: 0,
+            symbol: Some("DOGE-USD".to_string()),
+        };
+
+        let mut approved = AuditEntry::new(Component::Execution, AuditAction::Approved, "abc", "placed");
+        approved.occurred_at = start + Duration::seconds(3 * 60 + 2);
+        let events = timeline(evaluator::trace(&h, &candles), vec![approved]);
+        assert_eq!(events.len(), 6);
+
+        // Entry signalled at the close of the third minute, the order approved two seconds later
+        assert!(events[2].is_signal() && events[2].describe().ends_with("→ ENTRY"), "{}", events[2].describe());
+        assert!(matches!(&events[3], ReplayEvent::Decision(e) if e.reason_code == "placed"));
+        assert!(events[4].describe().contains("exit") && events[5].describe().ends_with("→ EXIT"));
+        assert!(events[0].describe().contains("✗ price_delta_1m n/a > 2"));
+    }
+}
@@ -0,0 +1,87 @@
+// Pattern Maintenance - Listing and Retiring Without psql
+// The leaderboard the control API and the CLI both show, and operator retirement: a retired
+// pattern stops trading at once, keeps its history and test results, and leaves an audit
+// entry saying who pulled it and why
+
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+
+use crate::core::audit::{AuditAction, AuditEntry, AuditLogger, Component};
+use crate::core::money::Decimal;
+
+const SELECT_LISTING: &str = "
+    SELECT pattern_hash, symbol, COALESCE(is_active, false) AS is_active, win_rate::float8 AS win_rate,
+           test_count, total_profit, sharpe_ratio::float8 AS sharpe_ratio, generation
+    FROM discovered_patterns";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternListing {
+    pub pattern_hash: String,
+    pub symbol: Option<String>,
+    pub is_active: bool,
+    pub win_rate: f64,
+    pub test_count: i32,
+    pub total_profit: Decimal,
+    pub sharpe_ratio: f64,
+    pub generation: i32,
+}
+
+/// Patterns by Sharpe, best first; `limit` of None returns them all
+pub async fn list(db_pool: &PgPool, active_only: bool, limit: Option<i64>) -> Result<Vec<PatternListing>, sqlx::Error> {
+    let rows = sqlx::query(&format!("
+        {}
+        WHERE is_active OR NOT $1
+        ORDER BY sharpe_ratio DESC NULLS LAST
+        LIMIT $2
+    ", SELECT_LISTING))
+        .bind(active_only)
+        .bind(limit)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(listing_from_row).collect())
+}
+
+/// The named patterns, active or not; unknown hashes are left out
+pub async fn lookup(db_pool: &PgPool, hashes: &[String]) -> Result<Vec<PatternListing>, sqlx::Error> {
+    let rows = sqlx::query(&format!("{} WHERE pattern_hash = ANY($1)", SELECT_LISTING))
+        .bind(hashes)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(listing_from_row).collect())
+}
+
+fn listing_from_row(row: &PgRow) -> PatternListing {
+    PatternListing {
+        pattern_hash: row.get("pattern_hash"),
+        symbol: row.get("symbol"),
+        is_active: row.get("is_active"),
+        win_rate: row.get::<Option<f64>, _>("win_rate").unwrap_or(0.0),
+        test_count: row.get::<Option<i32>, _>("test_count").unwrap_or(0),
+        total_profit: row.get::<Option<Decimal>, _>("total_profit").unwrap_or_default(),
+        sharpe_ratio: row.get::<Option<f64>, _>("sharpe_ratio").unwrap_or(0.0),
+        generation: row.get::<Option<i32>, _>("generation").unwrap_or(0),
+    }
+}
+
+/// Deactivate the given patterns; returns the hashes that were active and now are not
+pub async fn retire(db_pool: &PgPool, audit: &AuditLogger, hashes: &[String], reason_code: &str) -> Result<Vec<String>, sqlx::Error> {
+    let rows = sqlx::query("
+        UPDATE discovered_patterns SET is_active = false, updated_at = NOW()
+        WHERE pattern_hash = ANY($1) AND is_active
+        RETURNING pattern_hash
+    ")
+        .bind(hashes)
+        .fetch_all(db_pool)
+        .await?;
+    let retired: Vec<String> = rows.iter().map(|row| row.get("pattern_hash")).collect();
+
+    for hash in &retired {
+        audit.write(&AuditEntry::new(Component::Control, AuditAction::Retired, hash, reason_code)
+            .with_detail(json!({ "requested": hashes.len() }))).await?;
+    }
+    Ok(retired)
+}
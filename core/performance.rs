@@ -0,0 +1,267 @@
+// Rolling Performance Attribution
+// Standardized 30/90-day windows per pattern (Sharpe, hit rate, drawdown), snapshotted daily
+// so every evolution decision can be replayed from the exact numbers it was made on
+
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use chrono::{Duration, NaiveDate};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+/// The canonical windows fed to evolution selection
+pub const WINDOWS: [i32; 2] = [30, 90];
+
+/// One pattern's aggregated test results for a single day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub day: NaiveDate,
+    pub trades: u32,
+    pub wins: u32,
+    pub pnl: f64,
+    pub gross_profit: f64,  // sum of winning trades
+    pub gross_loss: f64,    // sum of losing trades, as a positive number
+    pub capital: f64,       // committed across the day's tests
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceWindow {
+    pub window_days: i32,
+    pub trades: u32,
+    pub hit_rate: f64,
+    pub total_pnl: f64,
+    pub sharpe: f64,        // annualized over daily returns
+    pub max_drawdown: f64,  // peak-to-trough of compounded daily returns, 0.0-1.0
+    #[serde(default)]
+    pub gross_profit: f64,
+    #[serde(default)]
+    pub gross_loss: f64,
+    #[serde(default)]
+    pub avg_daily_return: f64,  // mean over days that traded
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceSnapshot {
+    pub snapshot_date: NaiveDate,
+    pub pattern_hash: String,
+    pub window: PerformanceWindow,
+}
+
+/// Summarize the `window_days` ending on `as_of` (inclusive)
+/// Daily return is the day's pnl over the capital deployed that day; days without a recorded
+/// capital are assumed to have committed `test_capital` per trade
+pub fn compute_window(days: &[DailyResult], as_of: NaiveDate, window_days: i32, test_capital: f64) -> PerformanceWindow {
+    let start = as_of - Duration::days(window_days as i64 - 1);
+    let mut in_window: Vec<&DailyResult> = days
+        .iter()
+        .filter(|d| d.day >= start && d.day <= as_of && d.trades > 0)
+        .collect();
+    in_window.sort_by_key(|d| d.day);
+
+    let trades: u32 = in_window.iter().map(|d| d.trades).sum();
+    let wins: u32 = in_window.iter().map(|d| d.wins).sum();
+    let returns: Vec<f64> = in_window
+        .iter()
+        .map(|d| {
+            let deployed = if d.capital > 0.0 { d.capital } else { test_capital * d.trades as f64 };
+            d.pnl / deployed
+        })
+        .collect();
+
+    PerformanceWindow {
+        window_days,
+        trades,
+        hit_rate: if trades > 0 { wins as f64 / trades as f64 } else { 0.0 },
+        total_pnl: in_window.iter().map(|d| d.pnl).sum(),
+        sharpe: sharpe(&returns),
+        max_drawdown: max_drawdown(&returns),
+        gross_profit: in_window.iter().map(|d| d.gross_profit).sum(),
+        gross_loss: in_window.iter().map(|d| d.gross_loss).sum(),
+        avg_daily_return: if returns.is_empty() { 0.0 } else { returns.iter().sum::<f64>() / returns.len() as f64 },
+    }
+}
+
+/// Annualized Sharpe of daily returns
+pub fn sharpe(returns: &[f64]) -> f64 {
+    if returns.len() < 2 {
+        return 0.0;
+    }
+
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance = returns.iter()
+        .map(|r| (r - mean).powi(2))
+        .sum::<f64>() / (returns.len() - 1) as f64;
+    let std_dev = variance.sqrt();
+
+    if std_dev == 0.0 {
+        return 0.0;
+    }
+
+    // Crypto trades every day of the year
+    (mean / std_dev) * (365.0_f64).sqrt()
+}
+
+fn max_drawdown(returns: &[f64]) -> f64 {
+    let mut equity = 1.0;
+    let mut peak = 1.0;
+    let mut worst = 0.0_f64;
+
+    for r in returns {
+        equity *= 1.0 + r;
+        peak = f64::max(peak, equity);
+        worst = worst.max((peak - equity) / peak);
+    }
+
+    worst.min(1.0)
+}
+
+/// Daily aggregates per pattern over the trailing `days`
+/// Results stored before per-test capital was recorded count as `legacy_capital` each
+pub async fn load_daily_results(db_pool: &PgPool, days: i32, legacy_capital: f64) -> Result<HashMap<String, Vec<DailyResult>>, sqlx::Error> {
+    let query = "
+        SELECT pattern_hash, DATE(timestamp) AS day, COUNT(*) AS trades,
+               COUNT(*) FILTER (WHERE profitable) AS wins, SUM(profit)::float8 AS pnl,
+               COALESCE(SUM(profit) FILTER (WHERE profit > 0), 0)::float8 AS gross_profit,
+               COALESCE(-SUM(profit) FILTER (WHERE profit < 0), 0)::float8 AS gross_loss,
+               SUM(COALESCE(capital, $2))::float8 AS capital
+        FROM test_results
+        WHERE timestamp > NOW() - make_interval(days => $1)
+        GROUP BY pattern_hash, DATE(timestamp)
+    ";
+
+    let rows = sqlx::query(query)
+        .bind(days)
+        .bind(legacy_capital)
+        .fetch_all(db_pool)
+        .await?;
+
+    let mut by_pattern: HashMap<String, Vec<DailyResult>> = HashMap::new();
+    for row in rows {
+        by_pattern.entry(row.get("pattern_hash")).or_default().push(DailyResult {
+            day: row.get("day"),
+            trades: row.get::<i64, _>("trades") as u32,
+            wins: row.get::<i64, _>("wins") as u32,
+            pnl: row.get("pnl"),
+            gross_profit: row.get("gross_profit"),
+            gross_loss: row.get("gross_loss"),
+            capital: row.get("capital"),
+        });
+    }
+
+    Ok(by_pattern)
+}
+
+/// Compute and store every pattern's windows as of `as_of`
+/// Re-running for the same date replaces that date's rows; returns the number of rows written
+pub async fn take_snapshots(
+    db_pool: &PgPool,
+    as_of: NaiveDate,
+    test_capital: f64,
+    run_id: Option<Uuid>,
+) -> Result<usize, sqlx::Error> {
+    let longest = WINDOWS.iter().copied().max().unwrap_or(90);
+    let daily = load_daily_results(db_pool, longest + 1, test_capital).await?;
+
+    let query = "
+        INSERT INTO pattern_performance_snapshots
+        (snapshot_date, pattern_hash, window_days, trades, hit_rate, total_pnl, sharpe, max_drawdown,
+         gross_profit, gross_loss, avg_daily_return, run_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ON CONFLICT (snapshot_date, pattern_hash, window_days) DO UPDATE SET
+            trades = EXCLUDED.trades, hit_rate = EXCLUDED.hit_rate, total_pnl = EXCLUDED.total_pnl,
+            sharpe = EXCLUDED.sharpe, max_drawdown = EXCLUDED.max_drawdown,
+            gross_profit = EXCLUDED.gross_profit, gross_loss = EXCLUDED.gross_loss,
+            avg_daily_return = EXCLUDED.avg_daily_return, run_id = EXCLUDED.run_id
+    ";
+
+    let mut written = 0;
+    for (hash, days) in &daily {
+        for window_days in WINDOWS {
+            let window = compute_window(days, as_of, window_days, test_capital);
+            sqlx::query(query)
+                .bind(as_of)
+                .bind(hash)
+                .bind(window_days)
+                .bind(window.trades as i32)
+                .bind(window.hit_rate)
+                .bind(window.total_pnl)
+                .bind(window.sharpe)
+                .bind(window.max_drawdown)
+                .bind(window.gross_profit)
+                .bind(window.gross_loss)
+                .bind(window.avg_daily_return)
+                .bind(run_id)
+                .execute(db_pool)
+                .await?;
+            written += 1;
+        }
+    }
+
+    Ok(written)
+}
+
+/// Stored windows for one snapshot date, best Sharpe first
+pub async fn load_snapshots(
+    db_pool: &PgPool,
+    snapshot_date: NaiveDate,
+    window_days: i32,
+) -> Result<Vec<PerformanceSnapshot>, sqlx::Error> {
+    let query = "
+        SELECT pattern_hash, trades, hit_rate, total_pnl, sharpe, max_drawdown,
+               gross_profit, gross_loss, avg_daily_return
+        FROM pattern_performance_snapshots
+        WHERE snapshot_date = $1 AND window_days = $2
+        ORDER BY sharpe DESC
+    ";
+
+    let rows = sqlx::query(query)
+        .bind(snapshot_date)
+        .bind(window_days)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| PerformanceSnapshot {
+        snapshot_date,
+        pattern_hash: row.get("pattern_hash"),
+        window: PerformanceWindow {
+            window_days,
+            trades: row.get::<i32, _>("trades") as u32,
+            hit_rate: row.get("hit_rate"),
+            total_pnl: row.get("total_pnl"),
+            sharpe: row.get("sharpe"),
+            max_drawdown: row.get("max_drawdown"),
+            gross_profit: row.get("gross_profit"),
+            gross_loss: row.get("gross_loss"),
+            avg_daily_return: row.get("avg_daily_return"),
+        },
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_window() {
+        let as_of = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+        let day = |offset: i64, trades, wins, pnl: f64| DailyResult {
+            day: as_of - Duration::days(offset), trades, wins, pnl,
+            gross_profit: pnl.max(0.0), gross_loss: (-pnl).max(0.0), capital: 0.0,
+        };
+
+        // $5 per trade: +10%, -20%, +10% and one day outside the 30-day window
+        let days = vec![day(0, 2, 2, 1.0), day(1, 2, 0, -2.0), day(2, 2, 1, 1.0), day(45, 10, 0, -50.0)];
+
+        let w30 = compute_window(&days, as_of, 30, 5.0);
+        assert_eq!(w30.trades, 6);
+        assert!((w30.hit_rate - 0.5).abs() < 1e-12);
+        assert!((w30.total_pnl - 0.0).abs() < 1e-12);
+        assert!((w30.max_drawdown - 0.2).abs() < 1e-12);
+        assert!((w30.gross_profit - 2.0).abs() < 1e-12);
+        assert!((w30.gross_loss - 2.0).abs() < 1e-12);
+
+        let w90 = compute_window(&days, as_of, 90, 5.0);
+        assert_eq!(w90.trades, 16);
+        assert!(w90.max_drawdown > 0.99);
+        assert!(w90.sharpe < 0.0);
+    }
+}
@@ -0,0 +1,197 @@
+// Pipeline - Generation to Activation Against a Real Store
+// A pattern from hypothesis to activation in a throwaway Postgres; skipped without Docker
+
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal_macros::dec;
+
+use testcontainers_modules::postgres::Postgres;
+use testcontainers_modules::testcontainers::{ContainerAsync, ImageExt};
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+
+use crate::core::backtest;
+use crate::core::clock::SimulatedClock;
+use crate::core::discovery_engine::{DiscoveryEngine, Hypothesis, TestResult};
+use crate::core::evaluator::{self, Candle, SignalKind, TradeTick};
+use crate::core::exchange::{Exchange, PaperExchange, Side};
+use crate::core::execution_context::ExecutionContext;
+use crate::core::execution_engine::{ExecutionEngine, OrderIntent};
+use crate::core::execution_style::ExecutionStyle;
+use crate::core::money::Decimal;
+use crate::core::order_manager::OrderManager;
+use crate::core::patterns;
+use crate::core::risk_manager::RiskManager;
+use crate::core::rng::SharedRng;
+use crate::core::seeding::{ConditionTemplate, HypothesisTemplate, SeedSource, SeedingConfig};
+use crate::core::test_stats::TestStats;
+use crate::risk::liquidity::{LiquidityProfile, LiquidityProfiles};
+
+const SYMBOL: &str = "DOGE-USD";
+const TEST_SIZE: Decimal = dec!(25);
+
+/// A migrated database in a fresh container, or None when there is no Docker to start one in;
+/// the container goes when the handle drops
+async fn postgres() -> Option<(ContainerAsync<Postgres>, sqlx::PgPool)> {
+    // pgvector for pattern_embeddings, as in docker-compose
+    let container = Postgres::default().with_name("pgvector/pgvector").with_tag("pg15").start().await.ok()?;
+    let url = format!("postgresql://postgres:postgres@{}:{}/postgres",
+                      container.get_host().await.ok()?, container.get_host_port_ipv4(5432).await.ok()?);
+    let db_pool = sqlx::postgres::PgPoolOptions::new().max_connections(2).connect(&url).await.unwrap();
+    sqlx::migrate!("./migrations").run(&db_pool).await.unwrap();
+    Some((container, db_pool))
+}
+
+/// A day of a memecoin grinding up in waves: 20 minutes up 0.25% a minute, 10 down 0.3%,
+/// printing every 20 seconds
+fn day_of_candles(start: DateTime<Utc>) -> Vec<Candle> {
+    let price = |minute: f64| {
+        let (cycle, phase) = ((minute / 30.0).floor(), minute % 30.0);
+        let base = 0.08 * (1.05f64 * 0.97).powf(cycle);
+        if phase < 20.0 { base * (1.0 + 0.0025 * phase) } else { base * 1.05 * (1.0 - 0.003 * (phase - 20.0)) }
+    };
+    let ticks: Vec<TradeTick> = (0..1440 * 3).map(|i| TradeTick {
+        timestamp: start + Duration::seconds(i * 20),
+        price: price(i as f64 / 3.0),
+        volume: 5_000.0,
+    }).collect();
+    evaluator::aggregate(&ticks)
+}
+
+/// Momentum in, first dip out, as the intelligence layer might propose it
+fn momentum_template() -> HypothesisTemplate {
+    let condition = |metric: &str, operator: &str, min_value: f64, max_value: f64| ConditionTemplate {
+        metric: metric.to_string(),
+        operator: operator.to_string(),
+        min_value,
+        max_value,
+    };
+    HypothesisTemplate {
+        name: "wave_momentum".to_string(),
+        rationale: "five-minute strength continues until the first red minute".to_string(),
+        entry: vec![condition("price_delta_5m", ">", 0.8, 1.0)],
+        exit: vec![condition("price_delta_1m", "<", -0.2, -0.1)],
+        min_timeframe: 25,
+        max_timeframe: 30,
+        prompt_version: None,
+    }
+}
+
+/// Trade every signal through risk and the paper venue, one minute of prices at a time
+async fn paper_test(h: &Hypothesis, candles: &[Candle], engine: &mut ExecutionEngine, paper: &PaperExchange, risk_manager: &RiskManager) -> Vec<TestResult> {
+    let signals = evaluator::evaluate(h, candles);
+    let mut results = Vec::new();
+    let mut entry = None;
+    for candle in candles {
+        paper.update_price(SYMBOL, candle.close);
+        let Some(signal) = signals.iter().find(|s| s.at == candle.start + Duration::minutes(1)) else {
+            continue;
+        };
+        if signal.kind == SignalKind::Entry {
+            let client_order_id = engine.submit(OrderIntent::Open {
+                pattern_hash: h.hash.clone(),
+                symbol: SYMBOL.to_string(),
+                exchange: "paper".to_string(),
+                side: Side::Buy,
+                size: TEST_SIZE,
+                style: ExecutionStyle::Market,
+                stop_loss_pct: dec!(0.05),
+                take_profit_pct: dec!(0.10),
+                decision: None,
+            }).await.unwrap();
+            entry = Some((paper.find_order(&client_order_id).await.unwrap().unwrap(), signal.at));
+            continue;
+        }
+
+        let (opened, at) = entry.take().unwrap();
+        let (position_id, _) = risk_manager.open_positions_snapshot().pop().unwrap();
+        let client_order_id = engine.submit(OrderIntent::Close {
+            position_id,
+            size: TEST_SIZE,
+            reason: signal.kind.as_str().to_string(),
+            decision: None,
+        }).await.unwrap();
+        let closed = paper.find_order(&client_order_id).await.unwrap().unwrap();
+        let fees = opened.fees + closed.fees;
+        let profit = TEST_SIZE * closed.avg_fill_price / opened.avg_fill_price - TEST_SIZE - fees;
+        results.push(TestResult {
+            capital: TEST_SIZE,
+            profitable: profit > Decimal::ZERO,
+            profit,
+            entry_price: opened.avg_fill_price,
+            exit_price: closed.avg_fill_price,
+            duration_seconds: (signal.at - at).num_seconds() as u64,
+            fees,
+            slippage: Decimal::ZERO,
+        });
+    }
+    results
+}
+
+#[tokio::test]
+async fn test_pattern_goes_from_generation_to_activation() {
+    let start = "2026-03-14T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+    let candles = day_of_candles(start);
+    assert_eq!(candles.len(), 1440);
+
+    let Some((_container, db_pool)) = postgres().await else {
+        println!("Docker not available for testing");
+        return;
+    };
+
+    // Generation: every fresh hypothesis comes from the proposed template
+    let mut discovery = DiscoveryEngine::new(db_pool.clone());
+    discovery.clock = Arc::new(SimulatedClock::new(start));
+    discovery.set_rng(&SharedRng::seeded(369));
+    discovery.seeding = SeedingConfig { llm_share: 1.0 };
+    discovery.templates.lock().unwrap().push(momentum_template());
+    discovery.min_tests_required = 20;
+    let (hypothesis, source, _) = discovery.next_hypothesis();
+    assert_eq!(source, Some(SeedSource::Llm));
+    let worker = discovery.worker(0);
+    worker.store_hypothesis(&hypothesis, source, None).await.unwrap();
+
+    // Testing: evaluator signals, risk approval and paper fills
+    let risk_manager = Arc::new(RiskManager::new(Decimal::ONE_THOUSAND));
+    let mut profiles = LiquidityProfiles::default();
+    profiles.insert(LiquidityProfile::flat(SYMBOL));
+    risk_manager.set_liquidity_profiles(profiles);
+    let paper = Arc::new(PaperExchange::new("paper", 0.006).with_balance("USD", dec!(1000)));
+    paper.update_price(SYMBOL, candles[0].close);
+    let mut engine = ExecutionEngine::new(risk_manager.clone(), OrderManager::in_memory());
+    engine.add_exchange(ExecutionContext::Paper.paper(paper.clone()));
+    let results = paper_test(&hypothesis, &candles, &mut engine, &paper, &risk_manager).await;
+
+    // A trade per wave, each closed before the next opens
+    assert!(results.len() >= 40, "{} round trips", results.len());
+    assert!(risk_manager.open_positions_snapshot().len() <= 1);
+    assert!(results.iter().all(|r| r.fees > Decimal::ZERO && r.duration_seconds <= 30 * 60));
+
+    // Storage: each result lands in test_results and the running stats, as a worker stores it
+    let mut stats = TestStats::default();
+    for result in &results {
+        worker.store_test_result(&hypothesis.hash, result).await.unwrap();
+        let (recorded, stored) = worker.test_stats.record(&hypothesis.hash, result).await;
+        stored.unwrap();
+        stats = recorded;
+    }
+    let stored = backtest::load_test_results(&db_pool, &hypothesis.hash, None, None).await.unwrap();
+    assert_eq!(stored.len(), results.len());
+    assert_eq!(stored.iter().map(|r| r.profit).sum::<Decimal>(), stats.profit);
+    assert_eq!(stored.iter().filter(|r| r.profitable).count() as u32, stats.wins);
+
+    // Validation: enough winning tests activate the pattern, once however often it is validated
+    let pattern = discovery.validate_pattern(&hypothesis, &stats).unwrap();
+    assert!(discovery.validate_pattern(&hypothesis, &stats).is_none());
+    assert!(pattern.is_active && pattern.win_rate >= discovery.min_win_rate, "{:?}", pattern);
+    assert_eq!(pattern.test_count as usize, results.len());
+    assert!(pattern.total_profit > Decimal::ZERO && pattern.sharpe_ratio > 0.0);
+    assert_eq!(discovery.pattern_queue.len(), 1);
+
+    // Activation: the stored row is what the leaderboard and execution read
+    discovery.persist_activation(&pattern).await.unwrap();
+    let listed = patterns::lookup(&db_pool, std::slice::from_ref(&hypothesis.hash)).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert!(listed[0].is_active);
+    assert_eq!((listed[0].test_count as u32, listed[0].symbol.as_deref()), (pattern.test_count, pattern.hypothesis.symbol.as_deref()));
+    assert_eq!(patterns::list(&db_pool, true, None).await.unwrap().len(), 1);
+}
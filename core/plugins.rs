@@ -0,0 +1,264 @@
+// Strategy Plugins - gRPC for Processes Outside the Binary
+// Research notebooks and the Python strategist used to be wired in as subprocesses whose
+// stdout was parsed. Now they call proto/plugin.proto: submitted hypotheses join discovery
+// as their own seed arm (checked against the same bounds as generated ones, and hashed from
+// their content so a resubmission is the same hypothesis), pattern stats come from the
+// database, and fills stream from the live feed as they happen
+
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use chrono::Utc;
+use sha2::{Sha256, Digest};
+use sqlx::PgPool;
+use tokio::sync::{broadcast::error::RecvError, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+
+use crate::core::control_api::token_matches;
+use crate::core::discovery_engine::{Condition, Hypothesis};
+use crate::core::live_feed::{LiveEvent, LiveFeed, TradeEvent, TradeKind};
+use crate::core::money;
+use crate::core::patterns::{self, PatternListing};
+use crate::core::seeding::check_hypothesis;
+
+pub mod proto {
+    tonic::include_proto!("v26meme.plugin.v1");
+}
+
+use proto::strategy_plugin_server::{StrategyPlugin, StrategyPluginServer};
+
+/// Most submissions waiting for a test slot; past this, plugins are told to back off
+pub const MAX_QUEUED: usize = 1000;
+
+/// Shared between the plugin service (producer) and the discovery loop (consumer)
+pub type SubmissionQueue = Arc<Mutex<VecDeque<Hypothesis>>>;
+
+/// The same conditions, timeframe and symbol always hash the same
+pub fn hypothesis_from_proto(h: &proto::Hypothesis) -> Hypothesis {
+    let conditions = |conditions: &[proto::Condition]| -> Vec<Condition> {
+        conditions.iter().map(|c| Condition {
+            metric: c.metric.clone(),
+            operator: c.operator.clone(),
+            value: c.value,
+            weight: c.weight,
+        }).collect()
+    };
+    let entry_conditions = conditions(&h.entry_conditions);
+    let exit_conditions = conditions(&h.exit_conditions);
+
+    let mut hasher = Sha256::new();
+    hasher.update(format!(
+        "plugin{}{}{}{}",
+        serde_json::to_string(&entry_conditions).unwrap_or_default(),
+        serde_json::to_string(&exit_conditions).unwrap_or_default(),
+        h.timeframe_minutes,
+        h.symbol.as_deref().unwrap_or(""),
+    ));
+    let hash = format!("{:x}", hasher.finalize());
+
+    Hypothesis {
+        hash: hash[..16].to_string(),
+        entry_conditions,
+        exit_conditions,
+        timeframe: h.timeframe_minutes,
+        created_at: Utc::now().timestamp(),
+        symbol: h.symbol.clone(),
+    }
+}
+
+/// Check and queue each submission, answering for every one in order
+pub fn enqueue(queue: &mut VecDeque<Hypothesis>, submitted: &[proto::Hypothesis]) -> Vec<proto::Submission> {
+    submitted.iter().map(|h| {
+        let hypothesis = hypothesis_from_proto(h);
+        let rejected = |reason: String| proto::Submission { accepted: false, pattern_hash: String::new(), reason };
+
+        if let Err(reason) = check_hypothesis(&hypothesis) {
+            return rejected(reason);
+        }
+        if !queue.iter().any(|q| q.hash == hypothesis.hash) {
+            if queue.len() >= MAX_QUEUED {
+                return rejected(format!("queue full ({} waiting)", queue.len()));
+            }
+            queue.push_back(hypothesis.clone());
+        }
+        proto::Submission { accepted: true, pattern_hash: hypothesis.hash, reason: String::new() }
+    }).collect()
+}
+
+fn stats(p: PatternListing) -> proto::PatternStats {
+    proto::PatternStats {
+        pattern_hash: p.pattern_hash,
+        symbol: p.symbol,
+        is_active: p.is_active,
+        win_rate: p.win_rate,
+        test_count: p.test_count,
+        total_profit: money::to_f64(p.total_profit),
+        sharpe_ratio: p.sharpe_ratio,
+        generation: p.generation,
+    }
+}
+
+fn fill(t: TradeEvent) -> proto::Fill {
+    proto::Fill {
+        at_unix_ms: t.at.timestamp_millis(),
+        kind: match t.kind { TradeKind::Entry => "entry", TradeKind::Exit => "exit" }.to_string(),
+        position_id: t.position_id,
+        pattern_hash: t.pattern_hash,
+        symbol: t.symbol,
+        exchange: t.exchange,
+        size: money::to_f64(t.size),
+        price: money::to_f64(t.price),
+        fees: money::to_f64(t.fees),
+        pnl: t.pnl.map(money::to_f64),
+        reason: t.reason,
+    }
+}
+
+#[derive(Clone)]
+pub struct PluginService {
+    db_pool: PgPool,
+    submissions: SubmissionQueue,
+    feed: LiveFeed,
+}
+
+impl PluginService {
+    pub fn new(db_pool: PgPool, submissions: SubmissionQueue, feed: LiveFeed) -> Self {
+        PluginService { db_pool, submissions, feed }
+    }
+}
+
+fn internal(e: sqlx::Error) -> Status {
+    Status::internal(e.to_string())
+}
+
+#[tonic::async_trait]
+impl StrategyPlugin for PluginService {
+    async fn submit_hypotheses(
+        &self,
+        request: Request<proto::SubmitHypothesesRequest>,
+    ) -> Result<Response<proto::SubmitHypothesesResponse>, Status> {
+        let request = request.into_inner();
+        let plugin = if request.plugin.trim().is_empty() { "unnamed" } else { request.plugin.trim() };
+
+        let (submissions, queued) = {
+            let mut queue = self.submissions.lock().unwrap();
+            (enqueue(&mut queue, &request.hypotheses), queue.len())
+        };
+        let accepted = submissions.iter().filter(|s| s.accepted).count();
+        info!("🔌 Plugin {} submitted {} hypotheses, {} accepted ({} queued)",
+              plugin, submissions.len(), accepted, queued);
+
+        Ok(Response::new(proto::SubmitHypothesesResponse { submissions, queued: queued as u32 }))
+    }
+
+    async fn get_pattern_stats(
+        &self,
+        request: Request<proto::GetPatternStatsRequest>,
+    ) -> Result<Response<proto::PatternList>, Status> {
+        let hashes = request.into_inner().pattern_hashes;
+        let listings = patterns::lookup(&self.db_pool, &hashes).await.map_err(internal)?;
+        Ok(Response::new(proto::PatternList { patterns: listings.into_iter().map(stats).collect() }))
+    }
+
+    async fn list_patterns(
+        &self,
+        request: Request<proto::ListPatternsRequest>,
+    ) -> Result<Response<proto::PatternList>, Status> {
+        let request = request.into_inner();
+        let limit = if request.limit == 0 { 25 } else { request.limit as i64 };
+        let listings = patterns::list(&self.db_pool, !request.include_inactive, Some(limit)).await.map_err(internal)?;
+        Ok(Response::new(proto::PatternList { patterns: listings.into_iter().map(stats).collect() }))
+    }
+
+    type SubscribeFillsStream = ReceiverStream<Result<proto::Fill, Status>>;
+
+    async fn subscribe_fills(
+        &self,
+        request: Request<proto::SubscribeFillsRequest>,
+    ) -> Result<Response<Self::SubscribeFillsStream>, Status> {
+        let pattern_hash = request.into_inner().pattern_hash;
+        let (_, mut events) = self.feed.subscribe();
+        let (tx, rx) = mpsc::channel(64);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = events.recv() => match event {
+                        Ok(LiveEvent::Trade(trade)) => {
+                            if pattern_hash.as_ref().is_some_and(|hash| *hash != trade.pattern_hash) {
+                                continue;
+                            }
+                            if tx.send(Ok(fill(trade))).await.is_err() {
+                                return;
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(RecvError::Lagged(skipped)) => warn!("🐢 Plugin fill stream skipped {} events", skipped),
+                        Err(RecvError::Closed) => return,
+                    },
+                    _ = tx.closed() => return,
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+/// Serve until the listener fails; every call needs `authorization: Bearer <token>`
+pub async fn serve(service: PluginService, token: &str, bind: &str, port: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let addr: SocketAddr = format!("{}:{}", bind, port).parse()?;
+    let token: Arc<str> = Arc::from(token);
+    let authenticate = move |request: Request<()>| {
+        let authorized = request.metadata().get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .is_some_and(|given| token_matches(given, &token));
+        if authorized {
+            Ok(request)
+        } else {
+            Err(Status::unauthenticated("missing or invalid bearer token"))
+        }
+    };
+
+    info!("🔌 Strategy plugin gRPC on {}", addr);
+    Server::builder()
+        .add_service(StrategyPluginServer::with_interceptor(service, authenticate))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submitted(value: f64, metric: &str) -> proto::Hypothesis {
+        proto::Hypothesis {
+            entry_conditions: vec![proto::Condition { metric: metric.to_string(), operator: ">".to_string(), value, weight: 0.5 }],
+            exit_conditions: vec![proto::Condition { metric: "price_delta_5m".to_string(), operator: "<".to_string(), value: -1.0, weight: 0.5 }],
+            timeframe_minutes: 15,
+            symbol: Some("DOGE-USD".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_submissions_are_checked_hashed_by_content_and_deduplicated() {
+        let mut queue = VecDeque::new();
+        let answers = enqueue(&mut queue, &[submitted(3.0, "volume_spike"), submitted(3.0, "rsi_14"), submitted(3.0, "volume_spike")]);
+
+        assert!(answers[0].accepted);
+        assert!(!answers[1].accepted && answers[1].reason.contains("unknown metric"));
+        assert_eq!(answers[2].pattern_hash, answers[0].pattern_hash);  // same content, same hash
+        assert_eq!(queue.len(), 1);
+
+        // A different threshold is a different hypothesis
+        let answers = enqueue(&mut queue, &[submitted(4.0, "volume_spike")]);
+        assert!(answers[0].accepted);
+        assert_ne!(answers[0].pattern_hash, queue[0].hash);
+        assert_eq!(queue.len(), 2);
+    }
+}
@@ -0,0 +1,279 @@
+// Preflight - What Must Hold Before the Trading Loop Starts
+// `v26meme doctor` runs these checks and prints them; `run` runs the same checks before any
+// phase starts and, for a live run, refuses to start while one is down. The database must
+// answer and carry no migration this build doesn't know or whose file has changed since it was
+// applied. Every account's venue must accept its keys and hold at least its capital partition
+// in the quote currency. Each venue's clock must agree with ours within max_clock_skew (read from
+// the HTTP Date header of a public endpoint, so to the second), and its market data websocket
+// must complete a handshake. Pending migrations only degrade: `run` and `migrate` apply them.
+// Checks that don't depend on each other run at once, each venue's beside the others', so
+// startup waits for the slowest venue rather than the sum of them
+
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use rust_decimal_macros::dec;
+use sqlx::migrate::Migrator;
+use sqlx::{PgPool, Row};
+
+use crate::core::accounts::AccountConfig;
+use crate::core::execution_context::Venue;
+use crate::core::health::{CheckStatus, HealthReport, SubsystemCheck};
+use crate::core::money::{self, Decimal};
+
+/// Quote currency account partitions are held in
+const QUOTE_CURRENCY: &str = "USD";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreflightConfig {
+    pub max_clock_skew: Duration,
+    pub timeout: Duration,  // per network call
+}
+
+impl Default for PreflightConfig {
+    fn default() -> Self {
+        PreflightConfig { max_clock_skew: Duration::from_secs(2), timeout: Duration::from_secs(10) }
+    }
+}
+
+impl PreflightConfig {
+    pub fn from_env() -> Self {
+        let defaults = PreflightConfig::default();
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok()).filter(|v| *v > 0);
+        PreflightConfig {
+            max_clock_skew: var("PREFLIGHT_MAX_SKEW_MS").map_or(defaults.max_clock_skew, Duration::from_millis),
+            timeout: var("PREFLIGHT_TIMEOUT_SECS").map_or(defaults.timeout, Duration::from_secs),
+        }
+    }
+}
+
+/// Public endpoints of a venue: one that answers with a Date header, and its market data websocket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VenueEndpoints {
+    pub rest: &'static str,
+    pub websocket: &'static str,
+}
+
+pub fn endpoints(venue: &str) -> Option<VenueEndpoints> {
+    match venue.to_lowercase().as_str() {
+        "coinbase" => Some(VenueEndpoints { rest: "https://api.exchange.coinbase.com/time", websocket: "wss://ws-feed.exchange.coinbase.com" }),
+        "kraken" => Some(VenueEndpoints { rest: "https://api.kraken.com/0/public/Time", websocket: "wss://ws.kraken.com" }),
+        "gemini" => Some(VenueEndpoints { rest: "https://api.gemini.com/v1/symbols", websocket: "wss://api.gemini.com/v2/marketdata" }),
+        _ => None,
+    }
+}
+
+/// A migration as this build has it, or as _sqlx_migrations recorded it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationRecord {
+    pub version: i64,
+    pub checksum: Vec<u8>,
+    pub success: bool,
+}
+
+pub struct Preflight {
+    pub config: PreflightConfig,
+    pub venues: Vec<Venue>,
+    pub accounts: Vec<AccountConfig>,
+    pub starting_capital: f64,
+}
+
+impl Preflight {
+    /// Every check; `database` is the pool, or why there isn't one
+    pub async fn run(&self, database: Result<&PgPool, String>, migrator: &Migrator) -> HealthReport {
+        let database = async {
+            match database {
+                Ok(db_pool) => self.database_checks(db_pool, migrator).await,
+                Err(e) => vec![SubsystemCheck::new("database", CheckStatus::Down, e)],
+            }
+        };
+        let (mut checks, venues) = tokio::join!(database, self.venue_checks());
+        checks.extend(venues);
+        HealthReport::from_checks(checks)
+    }
+
+    /// The exchange and network checks, which need no database
+    pub async fn venue_checks(&self) -> Vec<SubsystemCheck> {
+        let (mut checks, network) = tokio::join!(self.exchange_checks(), self.network_checks());
+        checks.extend(network);
+        checks
+    }
+
+    pub async fn database_checks(&self, db_pool: &PgPool, migrator: &Migrator) -> Vec<SubsystemCheck> {
+        let applied = sqlx::query("SELECT version, checksum, success FROM _sqlx_migrations ORDER BY version")
+            .fetch_all(db_pool);
+        let rows = match tokio::time::timeout(self.config.timeout, applied).await {
+            Err(_) => return vec![SubsystemCheck::new("database", CheckStatus::Down, "query timed out")],
+            // A database no migration has touched yet has no table to read
+            Ok(Err(sqlx::Error::Database(e))) if e.code().as_deref() == Some("42P01") => Vec::new(),
+            Ok(Err(e)) => return vec![SubsystemCheck::new("database", CheckStatus::Down, e.to_string())],
+            Ok(Ok(rows)) => rows,
+        };
+        let applied: Vec<MigrationRecord> = rows.iter().map(|row| MigrationRecord {
+            version: row.get("version"),
+            checksum: row.get("checksum"),
+            success: row.get("success"),
+        }).collect();
+        let known: Vec<MigrationRecord> = migrator
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .map(|m| MigrationRecord { version: m.version, checksum: m.checksum.to_vec(), success: true })
+            .collect();
+        vec![
+            SubsystemCheck::new("database", CheckStatus::Ok, "connected"),
+            migration_check(&known, &applied),
+        ]
+    }
+
+    /// One balance request per account: it needs valid keys and shows what the account holds
+    pub async fn exchange_checks(&self) -> Vec<SubsystemCheck> {
+        join_all(self.venues.iter().map(|venue| self.exchange_check(venue))).await.into_iter().flatten().collect()
+    }
+
+    async fn exchange_check(&self, venue: &Venue) -> Vec<SubsystemCheck> {
+        let auth = format!("exchange:{}", venue.name());
+        let balances = match tokio::time::timeout(self.config.timeout, venue.exchange().balances()).await {
+            Ok(Ok(balances)) => balances,
+            Ok(Err(e)) => return vec![SubsystemCheck::new(&auth, CheckStatus::Down, e.to_string())],
+            Err(_) => return vec![SubsystemCheck::new(&auth, CheckStatus::Down, "balance request timed out")],
+        };
+        let kind = if venue.is_simulated() { "paper account" } else { "authenticated" };
+        let mut checks = vec![SubsystemCheck::new(&auth, CheckStatus::Ok, kind)];
+        if let Some(account) = self.accounts.iter().find(|a| a.name == venue.name()) {
+            checks.push(balance_check(account, &balances, money::share(money::from_f64(self.starting_capital), account.capital_pct)));
+        }
+        checks
+    }
+
+    /// Clock skew and websocket handshake, once per venue the accounts trade on
+    pub async fn network_checks(&self) -> Vec<SubsystemCheck> {
+        let venues: BTreeSet<String> = self.accounts.iter().map(|a| a.venue.to_lowercase()).collect();
+        let http = reqwest::Client::builder().timeout(self.config.timeout).build().unwrap_or_default();
+        let http = &http;
+        let checks = venues.iter().map(|venue| async move {
+            let Some(endpoints) = endpoints(venue) else {
+                return vec![SubsystemCheck::new(&format!("network:{}", venue), CheckStatus::Degraded, "no known public endpoints to check")];
+            };
+            let skew = async { server_time(http, endpoints.rest).await.map(|(server, midpoint)| skew_between(server, midpoint)) };
+            let (skew, websocket) = tokio::join!(skew, self.websocket_check(venue, endpoints.websocket));
+            vec![skew_check(venue, skew, self.config.max_clock_skew), websocket]
+        });
+        join_all(checks).await.into_iter().flatten().collect()
+    }
+
+    async fn websocket_check(&self, venue: &str, url: &str) -> SubsystemCheck {
+        let name = format!("websocket:{}", venue);
+        match tokio::time::timeout(self.config.timeout, tokio_tungstenite::connect_async(url)).await {
+            Ok(Ok((mut stream, _))) => {
+                let _ = stream.close(None).await;
+                SubsystemCheck::new(&name, CheckStatus::Ok, format!("handshake with {}", url))
+            }
+            Ok(Err(e)) => SubsystemCheck::new(&name, CheckStatus::Down, format!("{}: {}", url, e)),
+            Err(_) => SubsystemCheck::new(&name, CheckStatus::Down, format!("{}: handshake timed out", url)),
+        }
+    }
+}
+
+/// The venue's Date header and the midpoint of our request, the moment it most likely describes
+async fn server_time(http: &reqwest::Client, url: &str) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+    let sent = Utc::now();
+    let response = http.get(url).send().await.map_err(|e| e.to_string())?;
+    let received = Utc::now();
+    let date = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| format!("{} sent no Date header", url))?;
+    let server = DateTime::parse_from_rfc2822(date).map_err(|e| format!("bad Date header {:?}: {}", date, e))?;
+    Ok((server.with_timezone(&Utc), sent + (received - sent) / 2))
+}
+
+/// Date headers are whole seconds, so the venue's clock is half a second past what it says on average
+fn skew_between(server: DateTime<Utc>, local: DateTime<Utc>) -> chrono::Duration {
+    server + chrono::Duration::milliseconds(500) - local
+}
+
+pub fn skew_check(venue: &str, skew: Result<chrono::Duration, String>, max: Duration) -> SubsystemCheck {
+    let name = format!("clock_skew:{}", venue);
+    match skew {
+        Err(e) => SubsystemCheck::new(&name, CheckStatus::Down, e),
+        Ok(skew) => {
+            let ms = skew.num_milliseconds();
+            let status = if ms.unsigned_abs() as u128 > max.as_millis() { CheckStatus::Down } else { CheckStatus::Ok };
+            SubsystemCheck::new(&name, status, format!("{:+}ms (limit {}ms)", ms, max.as_millis()))
+        }
+    }
+}
+
+/// The account's venue must hold at least the partition it is meant to trade with
+pub fn balance_check(account: &AccountConfig, balances: &HashMap<String, Decimal>, required: Decimal) -> SubsystemCheck {
+    let name = format!("balance:{}", account.name);
+    let held = balances.get(QUOTE_CURRENCY).copied().unwrap_or_default();
+    // A cent of tolerance for rounding on the venue's side
+    if held + dec!(0.01) < required {
+        SubsystemCheck::new(&name, CheckStatus::Down, format!("{:.2} {} held, {:.2} required", held, QUOTE_CURRENCY, required))
+    } else {
+        SubsystemCheck::new(&name, CheckStatus::Ok, format!("{:.2} {} held", held, QUOTE_CURRENCY))
+    }
+}
+
+/// Down for a failed, unknown or edited migration; degraded while some are still to apply
+pub fn migration_check(known: &[MigrationRecord], applied: &[MigrationRecord]) -> SubsystemCheck {
+    let by_version: HashMap<i64, &MigrationRecord> = known.iter().map(|m| (m.version, m)).collect();
+    let mut problems = Vec::new();
+    for record in applied {
+        match by_version.get(&record.version) {
+            _ if !record.success => problems.push(format!("{} failed part-way", record.version)),
+            None => problems.push(format!("{} is newer than this build", record.version)),
+            Some(m) if m.checksum != record.checksum => problems.push(format!("{} was edited after it was applied", record.version)),
+            Some(_) => {}
+        }
+    }
+    if !problems.is_empty() {
+        return SubsystemCheck::new("migrations", CheckStatus::Down, problems.join(", "));
+    }
+
+    let pending = known.iter().filter(|m| !applied.iter().any(|a| a.version == m.version)).count();
+    let latest = applied.iter().map(|m| m.version).max().unwrap_or(0);
+    if pending > 0 {
+        SubsystemCheck::new("migrations", CheckStatus::Degraded, format!("at version {}, {} pending", latest, pending))
+    } else {
+        SubsystemCheck::new("migrations", CheckStatus::Ok, format!("at version {}", latest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::accounts::AccountRole;
+
+    #[test]
+    fn test_checks_flag_skew_short_balances_and_foreign_migrations() {
+        let max = Duration::from_secs(2);
+        assert_eq!(skew_check("kraken", Ok(chrono::Duration::milliseconds(-1500)), max).status, CheckStatus::Ok);
+        assert_eq!(skew_check("kraken", Ok(chrono::Duration::milliseconds(2500)), max).status, CheckStatus::Down);
+        assert_eq!(skew_check("kraken", Err("timed out".to_string()), max).status, CheckStatus::Down);
+        let at = DateTime::parse_from_rfc3339("2025-03-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(skew_between(at, at + chrono::Duration::milliseconds(200)).num_milliseconds(), 300);
+
+        let account = AccountConfig { name: "kraken-live".to_string(), venue: "kraken".to_string(), role: AccountRole::Live, capital_pct: 0.5, credentials: None };
+        let balances = HashMap::from([("USD".to_string(), dec!(99.995))]);
+        assert_eq!(balance_check(&account, &balances, dec!(100)).status, CheckStatus::Ok);
+        assert_eq!(balance_check(&account, &balances, dec!(150)).status, CheckStatus::Down);
+        assert_eq!(balance_check(&account, &HashMap::new(), dec!(1)).status, CheckStatus::Down);
+
+        let record = |version, checksum: &[u8], success| MigrationRecord { version, checksum: checksum.to_vec(), success };
+        let known = vec![record(1, b"a", true), record(2, b"b", true)];
+        assert_eq!(migration_check(&known, &known).status, CheckStatus::Ok);
+        assert_eq!(migration_check(&known, &known[..1]).status, CheckStatus::Degraded);
+        assert_eq!(migration_check(&known, &[]).detail, "at version 0, 2 pending");
+        let edited = migration_check(&known, &[record(1, b"a", true), record(2, b"x", true)]);
+        assert_eq!((edited.status, edited.detail.as_str()), (CheckStatus::Down, "2 was edited after it was applied"));
+        assert_eq!(migration_check(&known, &[record(1, b"a", true), record(3, b"c", true)]).status, CheckStatus::Down);
+        assert_eq!(migration_check(&known, &[record(1, b"a", false)]).status, CheckStatus::Down);
+
+        assert!(endpoints("Coinbase").is_some());
+        assert!(endpoints("paper").is_none());
+    }
+}
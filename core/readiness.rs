@@ -0,0 +1,127 @@
+// Startup Readiness - Gates Instead of Fixed Sleeps
+// Startup used to run one step after another and then sleep `warmup_secs` before Phase 2,
+// whether or not anything was ready. Independent steps now run at once: migrations beside the
+// venue checks, the market data poller as soon as the schema is in, the embedding
+// backfill inside discovery's own task. What depends on them waits on a Gate. Execution takes
+// orders only once the first tick has arrived, or `market_data_ready_secs` have passed, in which
+// case it starts reduce-only. It also waits for recovery to rebuild the book, and so do the
+// monitor's risk checks, equity snapshots and reconciliation. Every gate records how long it took to
+// open, and /readyz reports the ones still closed
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Opened once; every clone sees it and waiters wake together
+#[derive(Clone)]
+pub struct Gate {
+    name: &'static str,
+    opened: Arc<watch::Sender<Option<Duration>>>,  // how long after creation it opened
+    created: Instant,
+}
+
+impl Gate {
+    pub fn new(name: &'static str) -> Self {
+        Gate { name, opened: Arc::new(watch::channel(None).0), created: Instant::now() }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Later calls keep the first opening time
+    pub fn open(&self) {
+        let elapsed = self.created.elapsed();
+        self.opened.send_if_modified(|opened| {
+            if opened.is_some() {
+                return false;
+            }
+            *opened = Some(elapsed);
+            true
+        });
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.opened.borrow().is_some()
+    }
+
+    pub fn opened_after(&self) -> Option<Duration> {
+        *self.opened.borrow()
+    }
+
+    /// Whether the gate opened within `timeout`; at once if it already has
+    pub async fn wait(&self, timeout: Duration) -> bool {
+        let mut opened = self.opened.subscribe();
+        let waited = tokio::time::timeout(timeout, opened.wait_for(Option::is_some)).await.map(|opened| opened.is_ok());
+        matches!(waited, Ok(true))
+    }
+
+    /// Returns once the gate is open, however long that takes
+    pub async fn opened(&self) {
+        let mut opened = self.opened.subscribe();
+        let _ = opened.wait_for(Option::is_some).await;
+    }
+}
+
+/// What `run` waits on before trading
+#[derive(Clone)]
+pub struct Startup {
+    pub migrations: Gate,   // schema current and the run manifest stored
+    pub venues: Gate,       // keys, balances, clock skew and websockets checked
+    pub market_data: Gate,  // the feed has delivered its first tick
+    pub risk_state: Gate,   // recovery has rebuilt the book, or there is no book to rebuild
+}
+
+impl Startup {
+    /// Gates for a run whose first tick opens `market_data`
+    pub fn new(market_data: Gate) -> Self {
+        Startup {
+            migrations: Gate::new("migrations"),
+            venues: Gate::new("venues"),
+            market_data,
+            risk_state: Gate::new("risk_state"),
+        }
+    }
+
+    pub fn gates(&self) -> [&Gate; 4] {
+        [&self.migrations, &self.venues, &self.market_data, &self.risk_state]
+    }
+
+    pub fn waiting(&self) -> Vec<&'static str> {
+        self.gates().into_iter().filter(|gate| !gate.is_open()).map(Gate::name).collect()
+    }
+
+    /// How long each gate took, e.g. "migrations 0.4s, venues 1.2s, market_data waiting, ..."
+    pub fn summary(&self) -> String {
+        self.gates().into_iter().map(|gate| match gate.opened_after() {
+            Some(after) => format!("{} {:.1}s", gate.name(), after.as_secs_f64()),
+            None => format!("{} waiting", gate.name()),
+        }).collect::<Vec<_>>().join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_waiters_wake_when_the_gate_opens_and_time_out_otherwise() {
+        let startup = Startup::new(Gate::new("market_data"));
+        assert_eq!(startup.waiting(), ["migrations", "venues", "market_data", "risk_state"]);
+        assert!(!startup.market_data.wait(Duration::from_millis(10)).await);
+
+        let gate = startup.market_data.clone();
+        let waiter = tokio::spawn(async move { gate.wait(Duration::from_secs(5)).await });
+        startup.market_data.open();
+        assert!(waiter.await.unwrap());
+
+        let first = startup.market_data.opened_after().unwrap();
+        startup.market_data.open();
+        assert_eq!(startup.market_data.opened_after(), Some(first));
+        assert!(startup.market_data.wait(Duration::ZERO).await);
+
+        startup.migrations.open();
+        assert_eq!(startup.waiting(), ["venues", "risk_state"]);
+        assert!(startup.summary().starts_with("migrations 0.0s, venues waiting, market_data 0.0s"));
+    }
+}
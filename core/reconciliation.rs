@@ -0,0 +1,192 @@
+// Position Reconciliation - Tracked Book vs Venue Balances
+// Periodically compares what the risk manager believes is held with what each venue
+// reports: base-asset units per venue, and total equity (quote balances plus holdings
+// at the current mid) against tracked capital plus unrealized PnL.
+// Divergence is alerted on; tracked capital can optionally be corrected to the venue's figure.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use serde::{Serialize, Deserialize};
+
+use crate::core::exchange::{split_symbol, Exchange, ExchangeError};
+use crate::core::money::{self, Decimal};
+use crate::core::risk_manager::RiskManager;
+
+// Balances in these count at face value toward equity
+const QUOTE_ASSETS: [&str; 3] = ["USD", "USDT", "USDC"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetDivergence {
+    pub exchange: String,
+    pub asset: String,
+    pub tracked_units: Decimal,
+    pub venue_units: Decimal,
+}
+
+impl AssetDivergence {
+    pub fn difference(&self) -> Decimal {
+        self.venue_units - self.tracked_units
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    pub divergences: Vec<AssetDivergence>,
+    pub tracked_equity: f64,
+    pub venue_equity: f64,
+    pub capital_corrected: bool,
+}
+
+impl ReconciliationReport {
+    /// Venue minus tracked equity; positive means we hold more than we think
+    pub fn capital_drift(&self) -> f64 {
+        self.venue_equity - self.tracked_equity
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.divergences.is_empty() && !self.capital_corrected
+    }
+}
+
+pub struct Reconciler {
+    pub unit_tolerance_pct: f64,     // relative difference in units ignored as rounding
+    pub capital_tolerance_pct: f64,  // equity drift that triggers an alert (and correction)
+    pub auto_correct_capital: bool,
+    risk_manager: Arc<RiskManager>,
+    exchanges: Vec<Arc<dyn Exchange>>,
+}
+
+impl Reconciler {
+    pub fn new(risk_manager: Arc<RiskManager>, exchanges: Vec<Arc<dyn Exchange>>) -> Self {
+        Reconciler {
+            unit_tolerance_pct: 0.01,
+            capital_tolerance_pct: 0.005,
+            auto_correct_capital: false,
+            risk_manager,
+            exchanges,
+        }
+    }
+
+    pub async fn reconcile(&self) -> Result<ReconciliationReport, ExchangeError> {
+        let positions = self.risk_manager.open_positions_snapshot();
+
+        // (exchange, asset) → units we think we hold; longs positive
+        let mut tracked: BTreeMap<(String, String), Decimal> = BTreeMap::new();
+        let mut unrealized = 0.0;
+        for (_, position) in &positions {
+            let (base, _) = split_symbol(&position.symbol);
+            let units = if position.is_long() { position.units() } else { -position.units() };
+            *tracked.entry((position.exchange.clone(), base.to_string())).or_default() += units;
+
+            if let Some(exchange) = self.exchanges.iter().find(|e| e.name() == position.exchange) {
+                let mid = mid_price(exchange.as_ref(), &position.symbol).await?;
+                unrealized += (mid - money::to_f64(position.entry_price)) * money::to_f64(units);
+            }
+        }
+
+        let mut report = ReconciliationReport {
+            tracked_equity: money::to_f64(self.risk_manager.current_capital()) + unrealized,
+            ..Default::default()
+        };
+
+        let mut venue_units: BTreeMap<(String, String), Decimal> = BTreeMap::new();
+        for exchange in &self.exchanges {
+            for (asset, units) in exchange.balances().await? {
+                if QUOTE_ASSETS.contains(&asset.as_str()) {
+                    report.venue_equity += money::to_f64(units);
+                } else if !units.is_zero() {
+                    report.venue_equity += money::to_f64(units) * mid_price(exchange.as_ref(), &format!("{}-USD", asset)).await?;
+                    venue_units.insert((exchange.name().to_string(), asset), units);
+                }
+            }
+        }
+
+        let mut keys: Vec<&(String, String)> = tracked.keys().chain(venue_units.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let tracked_units = tracked.get(key).copied().unwrap_or_default();
+            let venue = venue_units.get(key).copied().unwrap_or_default();
+            let scale = tracked_units.abs().max(venue.abs());
+            if (venue - tracked_units).abs() > money::share(scale, self.unit_tolerance_pct) {
+                report.divergences.push(AssetDivergence {
+                    exchange: key.0.clone(),
+                    asset: key.1.clone(),
+                    tracked_units,
+                    venue_units: venue,
+                });
+            }
+        }
+
+        let drift = report.capital_drift();
+        if self.auto_correct_capital && drift.abs() > self.capital_tolerance_pct * report.tracked_equity.abs() {
+            let capital = self.risk_manager.current_capital();
+            self.risk_manager.update_capital(capital + money::from_f64(drift));
+            report.capital_corrected = true;
+        }
+
+        Ok(report)
+    }
+
+    /// Whether the equity drift alone is worth alerting on
+    pub fn drift_exceeds_tolerance(&self, report: &ReconciliationReport) -> bool {
+        report.capital_drift().abs() > self.capital_tolerance_pct * report.tracked_equity.abs()
+    }
+}
+
+async fn mid_price(exchange: &dyn Exchange, symbol: &str) -> Result<f64, ExchangeError> {
+    let quote = exchange.quote(symbol).await?;
+    Ok((quote.bid + quote.ask) / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use chrono::Utc;
+    use crate::core::exchange::{OrderRequest, OrderType, PaperExchange, Side};
+    use crate::core::risk_manager::Position;
+
+    #[tokio::test]
+    async fn test_detects_and_corrects_divergence() {
+        let risk_manager = Arc::new(RiskManager::new(dec!(1000)));
+        let paper = Arc::new(PaperExchange::new("paper", 0.006).with_balance("USD", dec!(1000)));
+        paper.update_price("DOGE-USD", 100.0);
+        let mut reconciler = Reconciler::new(risk_manager.clone(), vec![paper.clone()]);
+
+        assert!(reconciler.reconcile().await.unwrap().is_clean());
+
+        // Bought on the venue but never recorded locally
+        let request = OrderRequest {
+            client_order_id: "x".to_string(),
+            symbol: "DOGE-USD".to_string(),
+            side: Side::Buy,
+            size: dec!(100),
+            order_type: OrderType::Market,
+            reduce_only: false,
+        };
+        paper.submit_order(&request).await.unwrap();
+        let report = reconciler.reconcile().await.unwrap();
+        assert_eq!(report.divergences.len(), 1);
+        assert_eq!(report.divergences[0].asset, "DOGE");
+
+        // Once tracked, units agree; the fee the venue charged is the only equity drift
+        risk_manager.record_position_opened("x", Position {
+            pattern_hash: "abc".to_string(),
+            symbol: "DOGE-USD".to_string(),
+            exchange: "paper".to_string(),
+            size: dec!(100),
+            entry_price: dec!(100.05),
+            entry_time: Utc::now(),
+            stop_loss: dec!(95),
+            take_profit: dec!(110),
+        });
+        reconciler.auto_correct_capital = true;
+        reconciler.capital_tolerance_pct = 0.0001;
+        let report = reconciler.reconcile().await.unwrap();
+        assert!(report.divergences.is_empty());
+        assert!(report.capital_corrected);
+        assert!((report.capital_drift() + 0.6).abs() < 1e-9);
+        assert!((money::to_f64(risk_manager.current_capital()) - (1000.0 + report.capital_drift())).abs() < 1e-9);
+    }
+}
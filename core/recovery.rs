@@ -0,0 +1,120 @@
+// Crash Recovery - Rebuild the Book Before Trading Again
+// On boot, positions the ledger still has open are put back into the risk manager, orders a
+// previous process left unfinished are reconciled with their venues (and those it tagged as
+// orphaned on the way down are cancelled), and positions the venue no longer holds are
+// closed out of the book. The report is logged and audited, and discovery stays paused until
+// it is done, so nothing new is spent against a book that might be wrong
+
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+use tracing::{info, warn};
+
+use crate::core::exchange::split_symbol;
+use crate::core::money::Decimal;
+use crate::core::reconciliation::AssetDivergence;
+use crate::core::risk_manager::Position;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecoveryReport {
+    pub restored_positions: usize,
+    pub orders_reconciled: usize,
+    pub orphans_cancelled: usize,
+    pub orphans_left: Vec<String>,      // still working on a venue after the cancel attempt
+    pub positions_closed: Vec<String>,  // recorded open, but the venue holds none of the asset
+    pub divergences: Vec<AssetDivergence>,
+}
+
+impl RecoveryReport {
+    pub fn is_clean(&self) -> bool {
+        self.orphans_left.is_empty() && self.positions_closed.is_empty() && self.divergences.is_empty()
+    }
+
+    pub fn log(&self) {
+        info!("🔁 Recovery: {} positions restored, {} orders reconciled, {} orphans cancelled",
+              self.restored_positions, self.orders_reconciled, self.orphans_cancelled);
+        if !self.orphans_left.is_empty() {
+            warn!("⚠️ Orphaned orders still working: {}", self.orphans_left.join(", "));
+        }
+        if !self.positions_closed.is_empty() {
+            warn!("⚠️ Closed positions the venues no longer hold: {}", self.positions_closed.join(", "));
+        }
+        for d in &self.divergences {
+            warn!("⚠️ {} {}: tracked {:.6} units, venue {:.6}", d.exchange, d.asset, d.tracked_units, d.venue_units);
+        }
+    }
+}
+
+/// Positions the ledger has open, oldest first
+pub async fn load_open_positions(db_pool: &PgPool) -> Result<Vec<(String, Position)>, sqlx::Error> {
+    let rows = sqlx::query("
+        SELECT position_id, pattern_hash, symbol, exchange, size, entry_price, stop_loss, take_profit, opened_at
+        FROM positions
+        WHERE state = 'open'
+        ORDER BY opened_at
+    ")
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| (row.get("position_id"), Position {
+        pattern_hash: row.get("pattern_hash"),
+        symbol: row.get("symbol"),
+        exchange: row.get("exchange"),
+        size: row.get("size"),
+        entry_price: row.get("entry_price"),
+        entry_time: row.get("opened_at"),
+        stop_loss: row.get("stop_loss"),
+        take_profit: row.get("take_profit"),
+    })).collect())
+}
+
+/// Positions whose asset the venue no longer holds at all; partial mismatches are left in
+/// place for an operator, since closing them would guess at which position shrank
+pub fn vanished(positions: &[(String, Position)], divergences: &[AssetDivergence]) -> Vec<String> {
+    positions.iter()
+        .filter(|(_, position)| {
+            let (base, _) = split_symbol(&position.symbol);
+            divergences.iter().any(|d| {
+                d.exchange == position.exchange && d.asset == base
+                    && d.venue_units.abs() <= d.tracked_units.abs().max(Decimal::ONE) / Decimal::from(1_000_000_000)
+            })
+        })
+        .map(|(id, _)| id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_only_positions_the_venue_holds_nothing_of_are_closed() {
+        let position = |symbol: &str, exchange: &str| Position {
+            pattern_hash: "abc".to_string(),
+            symbol: symbol.to_string(),
+            exchange: exchange.to_string(),
+            size: dec!(100),
+            entry_price: dec!(1),
+            entry_time: Utc::now(),
+            stop_loss: dec!(0.9),
+            take_profit: dec!(1.2),
+        };
+        let positions = vec![
+            ("p1".to_string(), position("DOGE-USD", "coinbase")),
+            ("p2".to_string(), position("PEPE-USD", "coinbase")),
+            ("p3".to_string(), position("DOGE-USD", "kraken")),
+        ];
+        let divergence = |exchange: &str, asset: &str, venue_units: Decimal| AssetDivergence {
+            exchange: exchange.to_string(),
+            asset: asset.to_string(),
+            tracked_units: dec!(100),
+            venue_units,
+        };
+
+        // DOGE is gone from coinbase; PEPE is only partly there, which is left for an operator
+        let closed = vanished(&positions, &[divergence("coinbase", "DOGE", dec!(0)), divergence("coinbase", "PEPE", dec!(40))]);
+        assert_eq!(closed, vec!["p1".to_string()]);
+        assert!(vanished(&positions, &[]).is_empty());
+    }
+}
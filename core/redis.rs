@@ -0,0 +1,212 @@
+// Redis - Just Enough RESP for PUBLISH and SUBSCRIBE
+// The message bus needs two commands and a reply reader, so this speaks RESP2 over a plain
+// TCP connection rather than pulling in a full client: AUTH and SELECT from the URL on
+// connect, then commands as arrays of bulk strings and replies parsed out of a byte buffer
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+#[derive(Debug)]
+pub enum RedisError {
+    Io(std::io::Error),
+    Url(String),
+    Protocol(String),
+    Server(String),  // an -ERR reply
+}
+
+impl std::fmt::Display for RedisError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedisError::Io(e) => write!(f, "io: {}", e),
+            RedisError::Url(e) => write!(f, "bad redis url: {}", e),
+            RedisError::Protocol(e) => write!(f, "protocol: {}", e),
+            RedisError::Server(e) => write!(f, "server: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RedisError {}
+
+impl From<std::io::Error> for RedisError {
+    fn from(e: std::io::Error) -> Self {
+        RedisError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    Status(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<Reply>>),
+}
+
+/// redis://[user:password@]host[:port][/db]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedisUrl {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub db: u32,
+}
+
+impl RedisUrl {
+    pub fn parse(url: &str) -> Result<Self, RedisError> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| RedisError::Url(e.to_string()))?;
+        if parsed.scheme() != "redis" {
+            return Err(RedisError::Url(format!("{} (only redis:// is supported)", url)));
+        }
+        let host = parsed.host_str().filter(|h| !h.is_empty()).ok_or_else(|| RedisError::Url(format!("{} has no host", url)))?;
+        let db = match parsed.path().trim_start_matches('/') {
+            "" => 0,
+            db => db.parse().map_err(|_| RedisError::Url(format!("{} is not a database number", db)))?,
+        };
+        Ok(RedisUrl {
+            host: host.to_string(),
+            port: parsed.port().unwrap_or(6379),
+            username: Some(parsed.username().to_string()).filter(|u| !u.is_empty()),
+            password: parsed.password().map(str::to_string),
+            db,
+        })
+    }
+}
+
+/// A command as RESP: an array of bulk strings
+pub fn encode(args: &[&[u8]]) -> Vec<u8> {
+    let mut out = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        out.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        out.extend_from_slice(arg);
+        out.extend_from_slice(b"\r\n");
+    }
+    out
+}
+
+/// One reply from the front of `buf` and how many bytes it used; None until it is complete
+pub fn parse(buf: &[u8]) -> Result<Option<(Reply, usize)>, RedisError> {
+    let Some(end) = buf.windows(2).position(|w| w == b"\r\n") else { return Ok(None) };
+    let line = std::str::from_utf8(&buf[1..end]).map_err(|e| RedisError::Protocol(e.to_string()))?;
+    let next = end + 2;
+    let length = || line.parse::<i64>().map_err(|_| RedisError::Protocol(format!("bad length {:?}", line)));
+
+    match buf.first() {
+        Some(b'+') => Ok(Some((Reply::Status(line.to_string()), next))),
+        Some(b'-') => Ok(Some((Reply::Error(line.to_string()), next))),
+        Some(b':') => Ok(Some((Reply::Integer(length()?), next))),
+        Some(b'$') => {
+            let len = length()?;
+            if len < 0 {
+                return Ok(Some((Reply::Bulk(None), next)));
+            }
+            let len = len as usize;
+            if buf.len() < next + len + 2 {
+                return Ok(None);
+            }
+            Ok(Some((Reply::Bulk(Some(buf[next..next + len].to_vec())), next + len + 2)))
+        }
+        Some(b'*') => {
+            let len = length()?;
+            if len < 0 {
+                return Ok(Some((Reply::Array(None), next)));
+            }
+            let mut items = Vec::with_capacity(len as usize);
+            let mut used = next;
+            for _ in 0..len {
+                match parse(&buf[used..])? {
+                    Some((item, n)) => {
+                        items.push(item);
+                        used += n;
+                    }
+                    None => return Ok(None),
+                }
+            }
+            Ok(Some((Reply::Array(Some(items)), used)))
+        }
+        other => Err(RedisError::Protocol(format!("unexpected reply type {:?}", other.map(|b| *b as char)))),
+    }
+}
+
+pub struct RedisConnection {
+    stream: TcpStream,
+    buf: Vec<u8>,
+}
+
+impl RedisConnection {
+    pub async fn connect(url: &RedisUrl) -> Result<Self, RedisError> {
+        let stream = TcpStream::connect((url.host.as_str(), url.port)).await?;
+        stream.set_nodelay(true)?;
+        let mut conn = RedisConnection { stream, buf: Vec::new() };
+
+        if let Some(password) = &url.password {
+            match &url.username {
+                Some(user) => conn.command(&[b"AUTH", user.as_bytes(), password.as_bytes()]).await?,
+                None => conn.command(&[b"AUTH", password.as_bytes()]).await?,
+            };
+        }
+        if url.db != 0 {
+            conn.command(&[b"SELECT", url.db.to_string().as_bytes()]).await?;
+        }
+        Ok(conn)
+    }
+
+    /// Send a command and read its reply; an -ERR reply comes back as an error
+    pub async fn command(&mut self, args: &[&[u8]]) -> Result<Reply, RedisError> {
+        self.send(args).await?;
+        match self.read().await? {
+            Reply::Error(e) => Err(RedisError::Server(e)),
+            reply => Ok(reply),
+        }
+    }
+
+    /// Subscribers have no replies to wait for after the confirmation, only pushed messages
+    pub async fn send(&mut self, args: &[&[u8]]) -> Result<(), RedisError> {
+        self.stream.write_all(&encode(args)).await?;
+        Ok(())
+    }
+
+    pub async fn read(&mut self) -> Result<Reply, RedisError> {
+        loop {
+            if let Some((reply, used)) = parse(&self.buf)? {
+                self.buf.drain(..used);
+                return Ok(reply);
+            }
+            let mut chunk = [0u8; 8192];
+            let n = self.stream.read(&mut chunk).await?;
+            if n == 0 {
+                return Err(RedisError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+            }
+            self.buf.extend_from_slice(&chunk[..n]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resp_round_trip_and_url() {
+        assert_eq!(encode(&[b"PUBLISH", b"v26meme:live", b"{}"]), b"*3\r\n$7\r\nPUBLISH\r\n$12\r\nv26meme:live\r\n$2\r\n{}\r\n".to_vec());
+
+        let pushed = b"*3\r\n$7\r\nmessage\r\n$12\r\nv26meme:live\r\n$2\r\n{}\r\n:1\r\n";
+        let (reply, used) = parse(pushed).unwrap().unwrap();
+        assert_eq!(reply, Reply::Array(Some(vec![
+            Reply::Bulk(Some(b"message".to_vec())),
+            Reply::Bulk(Some(b"v26meme:live".to_vec())),
+            Reply::Bulk(Some(b"{}".to_vec())),
+        ])));
+        assert_eq!(parse(&pushed[used..]).unwrap(), Some((Reply::Integer(1), 4)));
+        // A reply split across reads waits for the rest
+        assert_eq!(parse(&pushed[..20]).unwrap(), None);
+        assert_eq!(parse(b"-ERR unknown\r\n").unwrap(), Some((Reply::Error("ERR unknown".to_string()), 14)));
+        assert_eq!(parse(b"$-1\r\n").unwrap(), Some((Reply::Bulk(None), 5)));
+
+        let url = RedisUrl::parse("redis://:secret@cache.internal:6380/2").unwrap();
+        assert_eq!((url.host.as_str(), url.port, url.password.as_deref(), url.db), ("cache.internal", 6380, Some("secret"), 2));
+        assert_eq!(url.username, None);
+        assert_eq!(RedisUrl::parse("redis://localhost").unwrap().port, 6379);
+        assert!(RedisUrl::parse("http://localhost").is_err());
+    }
+}
@@ -0,0 +1,355 @@
+// Retention - Roll Up, Archive and Prune Old Ticks and Test Results
+// Over a 90-day run market_data and test_results grow without bound. A scheduled pass walks
+// each table a UTC day at a time past its retention window: the day's rows are archived to a
+// snappy Parquet file (locally or in S3), rolled into hourly OHLC or daily per-pattern totals,
+// and deleted, all in one transaction so a failed archive never loses rows. Test results are
+// only pruned for patterns that are not active and have not been tested inside the window, so
+// validation counts for anything still under test are never cut short. audit_log is
+// append-only by design and is never pruned
+
+use std::path::PathBuf;
+use std::time::Duration;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use tracing::{error, info, warn};
+
+use crate::core::archive::{self, Archive, ArchiveError, S3Bucket};
+use crate::core::batch_writer::TestResultRow;
+use crate::core::discovery_engine::{TestResult, LEGACY_TEST_CAPITAL};
+use crate::core::market_data::PriceTick;
+
+// $1..$2 is the day being pruned, $3 the retention cutoff
+const STALE_TEST_RESULT: &str = "
+    t.timestamp >= $1 AND t.timestamp < $2
+    AND NOT EXISTS (SELECT 1 FROM discovered_patterns p WHERE p.pattern_hash = t.pattern_hash AND p.is_active)
+    AND NOT EXISTS (SELECT 1 FROM test_results recent WHERE recent.pattern_hash = t.pattern_hash AND recent.timestamp >= $3)
+";
+
+#[derive(Debug, Clone)]
+pub struct RetentionConfig {
+    pub tick_days: Option<i64>,         // None keeps ticks forever
+    pub test_result_days: Option<i64>,
+    pub archive_dir: Option<PathBuf>,   // None prunes without archiving
+    pub interval: Duration,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        RetentionConfig {
+            tick_days: Some(30),  // liquidity profiles learn from the last 28 days of ticks
+            test_result_days: Some(30),
+            archive_dir: Some(PathBuf::from("archive")),
+            interval: Duration::from_secs(24 * 3600),
+        }
+    }
+}
+
+impl RetentionConfig {
+    pub fn from_env() -> Self {
+        let defaults = RetentionConfig::default();
+        // 0 keeps the table forever
+        let days = |name: &str, default: Option<i64>| match std::env::var(name).ok().and_then(|v| v.parse::<i64>().ok()) {
+            Some(days) if days > 0 => Some(days),
+            Some(_) => None,
+            None => default,
+        };
+        let archive_dir = match std::env::var("RETENTION_ARCHIVE_DIR") {
+            Ok(dir) if dir.trim().is_empty() || dir.eq_ignore_ascii_case("none") => None,
+            Ok(dir) => Some(PathBuf::from(dir)),
+            Err(_) => defaults.archive_dir,
+        };
+        let hours = std::env::var("RETENTION_INTERVAL_HOURS").ok().and_then(|v| v.parse::<u64>().ok()).filter(|h| *h > 0);
+        let tick_days = days("TICK_RETENTION_DAYS", defaults.tick_days);
+        if tick_days.is_some_and(|days| days < 28) {
+            warn!("⚠️ TICK_RETENTION_DAYS below 28 leaves liquidity profiles with less than their four-week window");
+        }
+        RetentionConfig {
+            tick_days,
+            test_result_days: days("TEST_RESULT_RETENTION_DAYS", defaults.test_result_days),
+            archive_dir,
+            interval: hours.map_or(defaults.interval, |h| Duration::from_secs(h * 3600)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RetentionError {
+    Database(sqlx::Error),
+    Archive(ArchiveError),
+}
+
+impl std::fmt::Display for RetentionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RetentionError::Database(e) => write!(f, "database: {}", e),
+            RetentionError::Archive(e) => write!(f, "archive: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RetentionError {}
+
+impl From<sqlx::Error> for RetentionError {
+    fn from(e: sqlx::Error) -> Self {
+        RetentionError::Database(e)
+    }
+}
+
+impl From<ArchiveError> for RetentionError {
+    fn from(e: ArchiveError) -> Self {
+        RetentionError::Archive(e)
+    }
+}
+
+/// What one pass did to one table
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TablePass {
+    pub table: String,
+    pub days: usize,
+    pub rows_pruned: u64,
+    pub archived_to: Vec<String>,
+}
+
+impl TablePass {
+    fn new(table: &str) -> Self {
+        TablePass { table: table.to_string(), ..Default::default() }
+    }
+}
+
+/// Midnight UTC `keep_days` before `now`; rows before it are past retention
+pub fn cutoff(now: DateTime<Utc>, keep_days: i64) -> DateTime<Utc> {
+    midnight((now - chrono::Duration::days(keep_days)).date_naive())
+}
+
+fn midnight(day: NaiveDate) -> DateTime<Utc> {
+    day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+pub struct Retention {
+    db_pool: PgPool,
+    config: RetentionConfig,
+    archive: Option<Archive>,
+}
+
+impl Retention {
+    pub fn new(db_pool: PgPool, config: RetentionConfig, s3: Option<S3Bucket>) -> Self {
+        let archive = config.archive_dir.clone().map(|dir| Archive::new(dir, s3));
+        Retention { db_pool, config, archive }
+    }
+
+    pub fn from_env(db_pool: PgPool) -> Self {
+        Self::new(db_pool, RetentionConfig::from_env(), S3Bucket::from_env())
+    }
+
+    pub fn config(&self) -> &RetentionConfig {
+        &self.config
+    }
+
+    pub fn describe(&self) -> String {
+        let days = |d: Option<i64>| d.map_or("forever".to_string(), |d| format!("{}d", d));
+        format!("ticks {}, test results {}, archive {}",
+                days(self.config.tick_days), days(self.config.test_result_days),
+                self.archive.as_ref().map_or("off".to_string(), Archive::describe))
+    }
+
+    /// One pass over every table; a table that fails is logged and the rest still run
+    pub async fn run_once(&self) -> Vec<TablePass> {
+        let mut passes = Vec::new();
+        if let Some(days) = self.config.tick_days {
+            match self.prune_ticks(days).await {
+                Ok(pass) => passes.push(pass),
+                Err(e) => error!("❌ Tick retention failed: {}", e),
+            }
+        }
+        if let Some(days) = self.config.test_result_days {
+            match self.prune_test_results(days).await {
+                Ok(pass) => passes.push(pass),
+                Err(e) => error!("❌ Test result retention failed: {}", e),
+            }
+        }
+        for pass in passes.iter().filter(|pass| pass.rows_pruned > 0) {
+            info!("🗄️ {}: pruned {} rows over {} days, {} archive files", pass.table, pass.rows_pruned, pass.days, pass.archived_to.len());
+        }
+        passes
+    }
+
+    async fn prune_ticks(&self, keep_days: i64) -> Result<TablePass, RetentionError> {
+        let cutoff = cutoff(Utc::now(), keep_days);
+        let mut pass = TablePass::new("market_data");
+        let oldest: Option<DateTime<Utc>> = sqlx::query_scalar("SELECT MIN(timestamp) FROM market_data WHERE timestamp < $1")
+            .bind(cutoff)
+            .fetch_one(&self.db_pool)
+            .await?;
+
+        let mut day = match oldest { Some(oldest) => oldest.date_naive(), None => return Ok(pass) };
+        while midnight(day) < cutoff {
+            let (start, end) = (midnight(day), midnight(day) + chrono::Duration::days(1));
+            let mut tx = self.db_pool.begin().await?;
+            let rows = sqlx::query("
+                SELECT timestamp, symbol, COALESCE(exchange, 'unknown') AS exchange, price::float8 AS price
+                FROM market_data
+                WHERE timestamp >= $1 AND timestamp < $2
+                ORDER BY timestamp
+            ")
+                .bind(start)
+                .bind(end)
+                .fetch_all(&mut *tx)
+                .await?;
+
+            if !rows.is_empty() {
+                let ticks: Vec<PriceTick> = rows.iter().map(|row| PriceTick {
+                    symbol: row.get::<String, _>("symbol").into(),
+                    exchange: row.get::<String, _>("exchange").into(),
+                    price: row.get("price"),
+                    timestamp: row.get("timestamp"),
+                }).collect();
+                self.archive_day(&mut pass, "market_data", day, || archive::ticks_parquet(&ticks)).await?;
+
+                sqlx::query("
+                    INSERT INTO market_data_hourly (symbol, exchange, hour, open, high, low, close, volume, ticks)
+                    SELECT symbol, COALESCE(exchange, 'unknown'), date_trunc('hour', timestamp),
+                           (array_agg(price::float8 ORDER BY timestamp))[1], MAX(price::float8), MIN(price::float8),
+                           (array_agg(price::float8 ORDER BY timestamp DESC))[1], SUM(volume)::float8, COUNT(*)
+                    FROM market_data
+                    WHERE timestamp >= $1 AND timestamp < $2
+                    GROUP BY 1, 2, 3
+                    ON CONFLICT (symbol, exchange, hour) DO UPDATE
+                    SET high = GREATEST(market_data_hourly.high, EXCLUDED.high),
+                        low = LEAST(market_data_hourly.low, EXCLUDED.low),
+                        volume = COALESCE(market_data_hourly.volume, 0) + COALESCE(EXCLUDED.volume, 0),
+                        ticks = market_data_hourly.ticks + EXCLUDED.ticks
+                ")
+                    .bind(start)
+                    .bind(end)
+                    .execute(&mut *tx)
+                    .await?;
+                pass.rows_pruned += sqlx::query("DELETE FROM market_data WHERE timestamp >= $1 AND timestamp < $2")
+                    .bind(start)
+                    .bind(end)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+                pass.days += 1;
+            }
+            tx.commit().await?;
+            day = match day.succ_opt() { Some(next) => next, None => break };
+        }
+        Ok(pass)
+    }
+
+    async fn prune_test_results(&self, keep_days: i64) -> Result<TablePass, RetentionError> {
+        let cutoff = cutoff(Utc::now(), keep_days);
+        let mut pass = TablePass::new("test_results");
+        let oldest: Option<DateTime<Utc>> = sqlx::query_scalar("SELECT MIN(timestamp) FROM test_results WHERE timestamp < $1")
+            .bind(cutoff)
+            .fetch_one(&self.db_pool)
+            .await?;
+
+        let mut day = match oldest { Some(oldest) => oldest.date_naive(), None => return Ok(pass) };
+        while midnight(day) < cutoff {
+            let mut tx = self.db_pool.begin().await?;
+            let rows = self.stale_test_results(&mut tx, day, cutoff).await?;
+
+            if !rows.is_empty() {
+                self.archive_day(&mut pass, "test_results", day, || archive::test_results_parquet(&rows)).await?;
+
+                let (start, end) = (midnight(day), midnight(day) + chrono::Duration::days(1));
+                sqlx::query(&format!("
+                    INSERT INTO test_result_daily (pattern_hash, day, tests, wins, profit, fees, slippage, capital)
+                    SELECT COALESCE(t.pattern_hash, ''), $1::timestamptz::date, COUNT(*), COUNT(*) FILTER (WHERE t.profitable),
+                           SUM(t.profit), SUM(t.fees), SUM(t.slippage), SUM(COALESCE(t.capital, $4))
+                    FROM test_results t
+                    WHERE {}
+                    GROUP BY 1
+                    ON CONFLICT (pattern_hash, day) DO UPDATE
+                    SET tests = test_result_daily.tests + EXCLUDED.tests, wins = test_result_daily.wins + EXCLUDED.wins,
+                        profit = test_result_daily.profit + EXCLUDED.profit, fees = test_result_daily.fees + EXCLUDED.fees,
+                        slippage = test_result_daily.slippage + EXCLUDED.slippage,
+                        capital = test_result_daily.capital + EXCLUDED.capital
+                ", STALE_TEST_RESULT))
+                    .bind(start)
+                    .bind(end)
+                    .bind(cutoff)
+                    .bind(LEGACY_TEST_CAPITAL)
+                    .execute(&mut *tx)
+                    .await?;
+                pass.rows_pruned += sqlx::query(&format!("DELETE FROM test_results t WHERE {}", STALE_TEST_RESULT))
+                    .bind(start)
+                    .bind(end)
+                    .bind(cutoff)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+                pass.days += 1;
+            }
+            tx.commit().await?;
+            day = match day.succ_opt() { Some(next) => next, None => break };
+        }
+        Ok(pass)
+    }
+
+    async fn stale_test_results(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        day: NaiveDate,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<TestResultRow>, sqlx::Error> {
+        let rows = sqlx::query(&format!("
+            SELECT COALESCE(t.pattern_hash, '') AS pattern_hash, t.profitable, t.profit, t.entry_price, t.exit_price,
+                   t.duration_seconds::int8 AS duration_seconds, t.fees, t.slippage,
+                   COALESCE(t.capital, $4) AS capital, t.run_id, t.timestamp
+            FROM test_results t
+            WHERE {}
+            ORDER BY t.timestamp
+        ", STALE_TEST_RESULT))
+            .bind(midnight(day))
+            .bind(midnight(day) + chrono::Duration::days(1))
+            .bind(cutoff)
+            .bind(LEGACY_TEST_CAPITAL)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(rows.iter().map(|row| TestResultRow {
+            pattern_hash: row.get("pattern_hash"),
+            result: TestResult {
+                capital: row.get("capital"),
+                profitable: row.get("profitable"),
+                profit: row.get("profit"),
+                entry_price: row.get("entry_price"),
+                exit_price: row.get("exit_price"),
+                duration_seconds: row.get::<i64, _>("duration_seconds") as u64,
+                fees: row.get("fees"),
+                slippage: row.get("slippage"),
+            },
+            run_id: row.get("run_id"),
+            timestamp: row.get("timestamp"),
+        }).collect())
+    }
+
+    /// Archive first; an error here rolls back the day's rollup and delete
+    async fn archive_day<F>(&self, pass: &mut TablePass, table: &str, day: NaiveDate, parquet: F) -> Result<(), RetentionError>
+    where
+        F: FnOnce() -> Result<Vec<u8>, ArchiveError>,
+    {
+        if let Some(archive) = &self.archive {
+            pass.archived_to.push(archive.store(table, day, parquet()?).await?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cutoff_keeps_whole_days() {
+        let now = DateTime::parse_from_rfc3339("2026-03-10T15:30:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(cutoff(now, 7).to_rfc3339(), "2026-03-03T00:00:00+00:00");
+        assert_eq!(cutoff(now, 30).to_rfc3339(), "2026-02-08T00:00:00+00:00");
+
+        let config = RetentionConfig::default();
+        assert_eq!((config.tick_days, config.test_result_days), (Some(30), Some(30)));
+    }
+}
@@ -0,0 +1,196 @@
+// Trade Review Queue - Human Oversight Without Human Bottlenecks
+// Flags a random sample of live fills and discovery test trades for operator spot checks
+// Each flagged trade carries its full journal so the reviewer never has to dig through logs
+// Operators list and resolve them through /api/reviews on the control API
+
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+use tracing::info;
+
+use crate::core::discovery_engine::{Hypothesis, TestResult};
+use crate::core::error::Result;
+use crate::core::execution_engine::OrderIntent;
+use crate::core::live_feed::TradeEvent;
+use crate::core::money::Decimal;
+use crate::core::risk_manager::Position;
+use crate::core::rng::SharedRng;
+
+/// Everything an operator needs to judge a single trade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeJournal {
+    pub pattern_hash: String,
+    pub hypothesis: Hypothesis,
+    pub result: TestResult,
+    pub capital_committed: Decimal,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// A live fill with the order and position behind it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveTradeJournal {
+    pub pattern_hash: String,
+    pub client_order_id: String,
+    pub intent: OrderIntent,
+    pub trade: TradeEvent,
+    pub position: Option<Position>,  // as the fill left it; None once an exit closed it
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewVerdict {
+    Approved,   // trade looks like the pattern intended
+    Escalated,  // operator wants the pattern investigated
+}
+
+impl ReviewVerdict {
+    fn as_status(&self) -> &'static str {
+        match self {
+            ReviewVerdict::Approved => "approved",
+            ReviewVerdict::Escalated => "escalated",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeReview {
+    pub review_id: i64,
+    pub pattern_hash: String,
+    pub journal: serde_json::Value,
+    pub status: String,
+    pub flagged_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct ReviewQueue {
+    pub sample_rate: f64,  // fraction of live trades flagged, 0.0-1.0
+    pub run_id: Option<Uuid>,
+    pub rng: SharedRng,
+    db_pool: PgPool,
+}
+
+impl ReviewQueue {
+    /// A rate that isn't a number samples nothing
+    pub fn new(db_pool: PgPool, sample_rate: f64) -> Self {
+        ReviewQueue {
+            sample_rate: if sample_rate.is_finite() { sample_rate.clamp(0.0, 1.0) } else { 0.0 },
+            run_id: None,
+            rng: SharedRng::default(),
+            db_pool,
+        }
+    }
+
+    /// Sample rate comes from REVIEW_SAMPLE_PCT (percent of trades, default 5%)
+    pub fn from_env(db_pool: PgPool) -> Self {
+        let sample_pct = std::env::var("REVIEW_SAMPLE_PCT")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|pct| pct.is_finite())
+            .unwrap_or(5.0);
+
+        Self::new(db_pool, sample_pct / 100.0)
+    }
+
+    pub fn should_sample(&self) -> bool {
+        self.rng.clone().gen_bool(self.sample_rate)
+    }
+
+    /// Roll the dice for a trade and enqueue it if selected
+    /// Returns the review id when the trade was flagged
+    pub async fn submit(&self, journal: &TradeJournal) -> Result<Option<i64>> {
+        if !self.should_sample() {
+            return Ok(None);
+        }
+        self.flag(&journal.pattern_hash, journal).await.map(Some)
+    }
+
+    /// Enqueue a trade the caller has already sampled
+    pub async fn flag(&self, pattern_hash: &str, journal: &impl Serialize) -> Result<i64> {
+        let query = "
+            INSERT INTO trade_reviews (pattern_hash, journal, status, run_id, flagged_at)
+            VALUES ($1, $2, 'pending', $3, NOW())
+            RETURNING review_id
+        ";
+
+        let row = sqlx::query(query)
+            .bind(pattern_hash)
+            .bind(serde_json::to_value(journal)?)
+            .bind(self.run_id)
+            .fetch_one(&self.db_pool)
+            .await?;
+
+        let review_id: i64 = row.get("review_id");
+        info!("🔎 Trade on {} flagged for operator review (#{})", pattern_hash, review_id);
+
+        Ok(review_id)
+    }
+}
+
+/// Oldest-first list of trades still waiting for an operator
+pub async fn pending(db_pool: &PgPool, limit: i64) -> Result<Vec<TradeReview>, sqlx::Error> {
+    let query = "
+        SELECT review_id, pattern_hash, journal, status, flagged_at
+        FROM trade_reviews
+        WHERE status = 'pending'
+        ORDER BY flagged_at ASC
+        LIMIT $1
+    ";
+
+    let rows = sqlx::query(query)
+        .bind(limit)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| TradeReview {
+        review_id: row.get("review_id"),
+        pattern_hash: row.get("pattern_hash"),
+        journal: row.get("journal"),
+        status: row.get("status"),
+        flagged_at: row.get("flagged_at"),
+    }).collect())
+}
+
+/// Record the operator's decision; returns false if the review was already closed or never existed
+pub async fn resolve(
+    db_pool: &PgPool,
+    review_id: i64,
+    verdict: ReviewVerdict,
+    reviewer: &str,
+    notes: Option<&str>,
+) -> Result<bool, sqlx::Error> {
+    let query = "
+        UPDATE trade_reviews
+        SET status = $2, reviewer = $3, notes = $4, reviewed_at = NOW()
+        WHERE review_id = $1 AND status = 'pending'
+    ";
+
+    let outcome = sqlx::query(query)
+        .bind(review_id)
+        .bind(verdict.as_status())
+        .bind(reviewer)
+        .bind(notes)
+        .execute(db_pool)
+        .await?;
+
+    Ok(outcome.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sample_rate_is_clamped_and_nan_samples_nothing() {
+        let db_pool = sqlx::postgres::PgPoolOptions::new().connect_lazy("postgresql://localhost/v26meme").unwrap();
+        let never = ReviewQueue::new(db_pool.clone(), f64::NAN);
+        assert_eq!(never.sample_rate, 0.0);
+        assert!(!never.should_sample());
+        assert!(ReviewQueue::new(db_pool, 7.0).should_sample());
+
+        let verdict: ReviewVerdict = serde_json::from_str("\"escalated\"").unwrap();
+        assert_eq!(verdict, ReviewVerdict::Escalated);
+    }
+}
@@ -1,78 +1,337 @@
-use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc, Duration};
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+use tokio::sync::Notify;
+use tracing::{info, warn, error};
 
+use crate::core::accounts::AccountBook;
+use crate::core::alerts::{Alert, AlertManager, Severity};
+use crate::core::webhooks::{WebhookEvent, WebhookNotifier};
+use crate::core::audit::{AuditAction, AuditEntry, AuditLogger, Component};
+use crate::core::clock::{SharedClock, SystemClock};
+use crate::core::control_api::OpenPosition;
+use crate::core::exchange::split_symbol;
+use crate::core::incidents::{Incident, IncidentManager, RearmBlocked};
+use crate::core::ledger::{Ledger, LedgerWrite, RiskEvent};
+use crate::core::live_feed::{LiveEvent, LiveFeed};
+use crate::core::money::{self, Decimal, MONEY_DP};
+use crate::core::risk_state::{AtomicMoney, PositionBook};
+use crate::risk::config::{LimitUpdate, RiskConfig};
+use crate::risk::liquidity::LiquidityProfiles;
+
+// Timestamped losses inside a rolling window
+type LossLog = RwLock<Vec<(DateTime<Utc>, Decimal)>>;
+
+// Read on every order approval, so nothing here takes a lock shared by all orders: flags and
+// capital are atomics, the book is sharded (see core::risk_state), and what changes rarely sits
+// behind an RwLock that approvals only ever read
 pub struct RiskManager {
-    // Hard limits that cannot be overridden
-    max_position_size_pct: f64,     // 0.25 (25% of capital)
-    max_daily_drawdown_pct: f64,    // 0.30 (30% stop)
-    max_concurrent_positions: u32,   // 10 per strategy type
-    min_win_rate: f64,              // 0.55 minimum to trade
+    // Hard limits, validated at load time; the control API may tighten them at runtime, or
+    // loosen them back up to the launch values
+    launch_config: RiskConfig,
+    config: RwLock<Arc<RiskConfig>>,
     
-    // Kelly Criterion parameters
-    kelly_fraction: f64,            // 0.25 (conservative)
+    // Hour-of-week liquidity profiles tighten limits during thin hours
+    liquidity_profiles: RwLock<LiquidityProfiles>,
     
     // Circuit breakers
     emergency_stop: Arc<AtomicBool>,
-    circuit_breaker_15min: Arc<AtomicBool>,
-    circuit_breaker_1hr: Arc<AtomicBool>,
+    flatten_requested: Arc<Notify>,  // the execution engine flattens when this fires
+    circuit_breaker_15min: Mutex<Option<DateTime<Utc>>>,  // when it tripped; clears after an hour
+    circuit_breaker_1hr: Mutex<Option<DateTime<Utc>>>,    // clears after six hours
+    circuit_breaker_var: Arc<AtomicBool>,
+    
+    // Reduce-only mode: exits and reductions allowed, new risk refused
+    reduce_only: RwLock<Option<ReduceOnlyReason>>,
+    degraded_since: Arc<Mutex<HashMap<String, DateTime<Utc>>>>,
     
     // Capital tracking
-    starting_capital: f64,
-    current_capital: Arc<Mutex<f64>>,
-    daily_high: Arc<Mutex<f64>>,
+    starting_capital: Decimal,
+    current_capital: AtomicMoney,
+    daily_high: AtomicMoney,
     
     // Loss tracking
-    losses_15min: Arc<Mutex<Vec<(DateTime<Utc>, f64)>>>,
-    losses_1hr: Arc<Mutex<Vec<(DateTime<Utc>, f64)>>>,
-    losses_24hr: Arc<Mutex<Vec<(DateTime<Utc>, f64)>>>,
+    losses_15min: LossLog,
+    losses_1hr: LossLog,
+    losses_24hr: LossLog,
+    
+    // Symbols new positions may open in; empty allows any. Replaced by config hot reload
+    symbol_universe: RwLock<Arc<Vec<String>>>,
+    
+    // Capital partitions per exchange account, when [[accounts]] are configured
+    accounts: Option<Arc<AccountBook>>,
     
     // Position tracking
-    open_positions: Arc<Mutex<HashMap<String, Position>>>,
-    position_correlations: Arc<Mutex<HashMap<(String, String), f64>>>,
+    open_positions: PositionBook,
+    position_correlations: RwLock<HashMap<(String, String), f64>>,
+    
+    // Decision trail, once a database is attached
+    audit: Option<AuditLogger>,
+    
+    // Where breakers and the emergency stop are paged, and escalated
+    alerts: Option<Arc<AlertManager>>,
+    incidents: Option<Arc<IncidentManager>>,
+    
+    // Dashboards watching the open book
+    live_feed: Option<LiveFeed>,
+    
+    // Users' own automation, told when breakers trip and clear
+    webhooks: Option<Arc<WebhookNotifier>>,
+    
+    // positions and risk_events, once a database is attached
+    ledger: Option<Ledger>,
+    
+    // Loss windows, degradation grace periods and event timestamps; simulated in tests
+    clock: SharedClock,
+}
+
+/// An entry placed but not yet in the book: the unfilled part of a working order, or algo
+/// slices still to send. Counted against the asset, venue and account caps like a position
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkingEntry {
+    pub symbol: String,
+    pub exchange: String,  // account name, as on a position
+    pub notional: Decimal,
+}
+
+/// Why approve_order refused new risk; the code is what the audit log records
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderRejection {
+    EmergencyStop,
+    ReduceOnly,
+    RiskLimits,  // a circuit breaker or the daily drawdown limit
+    MaxConcurrentPositions,
+    PortfolioCorrelation,
+    OrderTooLarge,
+    Liquidity,
+    AssetExposure,
+    VenueExposure,
+    SymbolUniverse,  // the symbol is not in the configured universe
+    AccountBudget,   // more than the account's own balance
 }
 
-#[derive(Clone, Debug)]
+impl OrderRejection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderRejection::EmergencyStop => "emergency_stop",
+            OrderRejection::ReduceOnly => "reduce_only",
+            OrderRejection::RiskLimits => "risk_limits",
+            OrderRejection::MaxConcurrentPositions => "max_concurrent_positions",
+            OrderRejection::PortfolioCorrelation => "portfolio_correlation",
+            OrderRejection::OrderTooLarge => "order_too_large",
+            OrderRejection::Liquidity => "liquidity",
+            OrderRejection::AssetExposure => "asset_exposure",
+            OrderRejection::VenueExposure => "venue_exposure",
+            OrderRejection::SymbolUniverse => "symbol_universe",
+            OrderRejection::AccountBudget => "account_budget",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ReduceOnlyReason {
+    Manual(String),    // operator decision, only an operator clears it
+    Degraded(String),  // subsystem trouble, clears itself on recovery
+}
+
+/// Snapshot of every breaker, for health reporting
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BreakerStates {
+    pub emergency_stop: bool,
+    pub loss_15min: bool,
+    pub loss_1hr: bool,
+    pub var: bool,
+    pub reduce_only: Option<ReduceOnlyReason>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Position {
-    pattern_hash: String,
-    size: f64,
-    entry_price: f64,
-    entry_time: DateTime<Utc>,
-    stop_loss: f64,
-    take_profit: f64,
+    pub pattern_hash: String,
+    pub symbol: String,
+    pub exchange: String,
+    pub size: Decimal,
+    pub entry_price: Decimal,
+    pub entry_time: DateTime<Utc>,
+    pub stop_loss: Decimal,
+    pub take_profit: Decimal,
+}
+
+impl Position {
+    /// Direction is implied by the stop: at or below entry means long
+    pub fn is_long(&self) -> bool {
+        self.stop_loss <= self.entry_price
+    }
+    
+    /// Units of the base asset held; `size` is notional at entry
+    pub fn units(&self) -> Decimal {
+        self.size.checked_div(self.entry_price).unwrap_or_default()
+    }
 }
 
 impl RiskManager {
-    pub fn new(starting_capital: f64) -> Self {
+    pub fn new(starting_capital: Decimal) -> Self {
+        Self::with_config(starting_capital, RiskConfig::default())
+    }
+    
+    /// The config must already have passed `RiskConfig::validate`
+    pub fn with_config(starting_capital: Decimal, config: RiskConfig) -> Self {
         RiskManager {
-            max_position_size_pct: 0.25,
-            max_daily_drawdown_pct: 0.30,
-            max_concurrent_positions: 10,
-            min_win_rate: 0.55,
-            kelly_fraction: 0.25,
+            launch_config: config.clone(),
+            config: RwLock::new(Arc::new(config)),
+            liquidity_profiles: RwLock::new(LiquidityProfiles::default()),
             
             emergency_stop: Arc::new(AtomicBool::new(false)),
-            circuit_breaker_15min: Arc::new(AtomicBool::new(false)),
-            circuit_breaker_1hr: Arc::new(AtomicBool::new(false)),
+            flatten_requested: Arc::new(Notify::new()),
+            circuit_breaker_15min: Mutex::new(None),
+            circuit_breaker_1hr: Mutex::new(None),
+            circuit_breaker_var: Arc::new(AtomicBool::new(false)),
+            
+            reduce_only: RwLock::new(None),
+            degraded_since: Arc::new(Mutex::new(HashMap::new())),
             
             starting_capital,
-            current_capital: Arc::new(Mutex::new(starting_capital)),
-            daily_high: Arc::new(Mutex::new(starting_capital)),
+            current_capital: AtomicMoney::new(starting_capital),
+            daily_high: AtomicMoney::new(starting_capital),
+            
+            losses_15min: RwLock::new(Vec::new()),
+            losses_1hr: RwLock::new(Vec::new()),
+            losses_24hr: RwLock::new(Vec::new()),
+            
+            symbol_universe: RwLock::new(Arc::new(Vec::new())),
             
-            losses_15min: Arc::new(Mutex::new(Vec::new())),
-            losses_1hr: Arc::new(Mutex::new(Vec::new())),
-            losses_24hr: Arc::new(Mutex::new(Vec::new())),
+            accounts: None,
             
-            open_positions: Arc::new(Mutex::new(HashMap::new())),
-            position_correlations: Arc::new(Mutex::new(HashMap::new())),
+            open_positions: PositionBook::default(),
+            position_correlations: RwLock::new(HashMap::new()),
+            
+            audit: None,
+            alerts: None,
+            incidents: None,
+            live_feed: None,
+            webhooks: None,
+            ledger: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+    
+    /// Record every approval, rejection and breaker change in the audit log
+    pub fn set_audit_logger(&mut self, audit: AuditLogger) {
+        self.audit = Some(audit);
+    }
+    
+    fn audit(&self, entry: AuditEntry) {
+        if let Some(audit) = &self.audit {
+            audit.record(entry);
+        }
+    }
+    
+    /// Page the configured channels on emergency stops, breakers and reduce-only
+    pub fn set_alert_manager(&mut self, alerts: Arc<AlertManager>) {
+        self.alerts = Some(alerts);
+    }
+    
+    pub fn alerts(&self) -> Option<&Arc<AlertManager>> {
+        self.alerts.as_ref()
+    }
+    
+    /// Open an on-call incident on emergency stop and hold re-arming until it is acknowledged
+    pub fn set_incident_manager(&mut self, incidents: Arc<IncidentManager>) {
+        self.incidents = Some(incidents);
+    }
+    
+    /// Publish the open book on every change, and let the execution engine publish fills
+    pub fn set_live_feed(&mut self, feed: LiveFeed) {
+        self.live_feed = Some(feed);
+    }
+    
+    pub fn live_feed(&self) -> Option<&LiveFeed> {
+        self.live_feed.as_ref()
+    }
+    
+    fn publish_positions(&self) {
+        if let Some(feed) = &self.live_feed {
+            let positions = self.open_positions_snapshot().into_iter()
+                .map(|(position_id, position)| OpenPosition { position_id, position })
+                .collect();
+            feed.publish(LiveEvent::Positions { positions });
+        }
+    }
+    
+    /// Send circuit breaker trips, clears and re-arms to the configured webhooks
+    pub fn set_webhooks(&mut self, webhooks: Arc<WebhookNotifier>) {
+        self.webhooks = Some(webhooks);
+    }
+    
+    pub fn webhooks(&self) -> Option<&Arc<WebhookNotifier>> {
+        self.webhooks.as_ref()
+    }
+    
+    /// Write every position change and breaker change through to the database
+    pub fn set_ledger(&mut self, ledger: Ledger) {
+        self.ledger = Some(ledger);
+    }
+    
+    /// Read time from `clock` instead of the system, e.g. a SimulatedClock in a replayed run
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+    
+    fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+    
+    pub fn ledger(&self) -> Option<&Ledger> {
+        self.ledger.as_ref()
+    }
+    
+    fn write(&self, write: LedgerWrite) {
+        if let Some(ledger) = &self.ledger {
+            ledger.record(write);
+        }
+    }
+    
+    fn risk_event(&self, event_type: &str, severity: Severity, description: String, detail: serde_json::Value) {
+        let capital = self.current_capital();
+        let daily_high = self.daily_high.load();
+        self.write(LedgerWrite::RiskEvent(RiskEvent {
+            event_type: event_type.to_string(),
+            severity,
+            description,
+            capital,
+            drawdown_pct: money::ratio(daily_high - capital, daily_high).max(0.0),
+            detail,
+            at: self.now(),
+        }));
+    }
+    
+    /// Record a breaker trip, clear or re-arm in risk_events and send it to the webhooks
+    fn notify_breaker(&self, breaker: &str, tripped: bool, detail: serde_json::Value) {
+        let severity = match (tripped, breaker) {
+            (false, _) => Severity::Info,
+            (true, "emergency_stop") => Severity::Critical,
+            (true, _) => Severity::Warning,
+        };
+        let description = format!("{} {}", breaker, if tripped { "tripped" } else { "cleared" });
+        self.risk_event(breaker, severity, description, detail.clone());
+        
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.emit(WebhookEvent::CircuitBreaker { breaker: breaker.to_string(), tripped, detail });
+        }
+    }
+    
+    fn alert(&self, alert: Alert) {
+        if let Some(alerts) = &self.alerts {
+            alerts.raise(alert);
         }
     }
     
-    pub fn calculate_position_size(&self, pattern: &Pattern, available_capital: f64) -> f64 {
+    pub fn calculate_position_size(&self, pattern: &Pattern, available_capital: Decimal) -> Decimal {
         // Never trade patterns below minimum win rate
-        if pattern.win_rate < self.min_win_rate {
-            return 0.0;
+        if pattern.win_rate < self.limits().min_win_rate {
+            return Decimal::ZERO;
         }
         
         // Kelly Criterion with safety factor
@@ -85,7 +344,7 @@ impl RiskManager {
         
         if avg_loss == 0.0 || avg_win == 0.0 {
             // No position if we can't calculate risk/reward
-            return 0.0;
+            return Decimal::ZERO;
         }
         
         // Kelly formula: f = (p*b - q) / b
@@ -93,64 +352,72 @@ impl RiskManager {
         let b = avg_win / avg_loss;
         let kelly_pct = (win_prob * b - loss_prob) / b;
         
-        // Apply safety factor (quarter Kelly)
-        let safe_kelly = kelly_pct * self.kelly_fraction;
+        // Apply safety factor (quarter Kelly, less as capital grows)
+        let (max_position_size_pct, kelly_fraction) = self.active_sizing_limits();
+        let safe_kelly = kelly_pct * kelly_fraction;
         
         // Apply maximum position size limit
-        let max_position = available_capital * self.max_position_size_pct;
-        let kelly_position = available_capital * safe_kelly.max(0.0);
+        let max_position = money::share(available_capital, max_position_size_pct);
+        let kelly_position = money::share(available_capital, safe_kelly.max(0.0));
         
         // Use the smaller of Kelly or max position
         let position_size = kelly_position.min(max_position);
         
         // Minimum position size (don't trade dust)
-        if position_size < 5.0 {
-            return 0.0;
+        if position_size < money::from_f64(self.limits().min_position_size) {
+            return Decimal::ZERO;
         }
         
         position_size
     }
     
     pub fn check_risk_limits(&self) -> bool {
+        let limits = self.limits();
+        
         // Check emergency stop
         if self.emergency_stop.load(Ordering::SeqCst) {
-            println!("🚨 Emergency stop is active");
+            error!("🚨 Emergency stop is active");
             return false;
         }
         
         // Check circuit breakers
-        if self.circuit_breaker_15min.load(Ordering::SeqCst) {
-            println!("⚠️ 15-minute circuit breaker active");
+        if self.loss_breaker_active("loss_15min", &self.circuit_breaker_15min, Duration::hours(1)) {
+            warn!("⚠️ 15-minute circuit breaker active");
+            return false;
+        }
+        
+        if self.loss_breaker_active("loss_1hr", &self.circuit_breaker_1hr, Duration::hours(6)) {
+            warn!("⚠️ 1-hour circuit breaker active");
             return false;
         }
         
-        if self.circuit_breaker_1hr.load(Ordering::SeqCst) {
-            println!("⚠️ 1-hour circuit breaker active");
+        if self.circuit_breaker_var.load(Ordering::SeqCst) {
+            warn!("⚠️ VaR circuit breaker active");
             return false;
         }
         
         // Calculate current drawdown
-        let current = *self.current_capital.lock().unwrap();
-        let daily_high = *self.daily_high.lock().unwrap();
+        let current = self.current_capital.load();
+        let daily_high = self.daily_high.load();
         
-        let drawdown = (daily_high - current) / daily_high;
+        let drawdown = money::ratio(daily_high - current, daily_high);
         
         // Check daily drawdown limit
-        if drawdown > self.max_daily_drawdown_pct {
+        if drawdown > limits.max_daily_drawdown_pct {
             self.trigger_emergency_stop();
             return false;
         }
         
         // Check 15-minute loss rate
         let loss_15min = self.calculate_period_loss(Duration::minutes(15));
-        if loss_15min > 0.10 {
+        if loss_15min > limits.loss_15min_pct {
             self.trigger_circuit_breaker_15min();
             return false;
         }
         
         // Check 1-hour loss rate
         let loss_1hr = self.calculate_period_loss(Duration::hours(1));
-        if loss_1hr > 0.20 {
+        if loss_1hr > limits.loss_1hr_pct {
             self.trigger_circuit_breaker_1hr();
             return false;
         }
@@ -159,144 +426,570 @@ impl RiskManager {
     }
     
     fn calculate_period_loss(&self, period: Duration) -> f64 {
-        let now = Utc::now();
+        let now = self.now();
         let cutoff = now - period;
         
         let losses = match period.num_minutes() {
-            15 => self.losses_15min.lock().unwrap(),
-            60 => self.losses_1hr.lock().unwrap(),
-            _ => self.losses_24hr.lock().unwrap(),
+            15 => self.losses_15min.read().unwrap(),
+            60 => self.losses_1hr.read().unwrap(),
+            _ => self.losses_24hr.read().unwrap(),
         };
         
-        let period_losses: f64 = losses
+        let period_losses: Decimal = losses
             .iter()
             .filter(|(time, _)| *time > cutoff)
             .map(|(_, loss)| loss)
             .sum();
         
-        let current = *self.current_capital.lock().unwrap();
-        period_losses / current
+        money::ratio(period_losses, self.current_capital.load())
     }
     
     fn trigger_emergency_stop(&self) {
-        println!("🚨🚨🚨 EMERGENCY STOP TRIGGERED - 30% DAILY LOSS 🚨🚨🚨");
-        println!("System will halt all trading and require manual intervention");
+        let limits = self.limits();
+        error!("🚨🚨🚨 EMERGENCY STOP TRIGGERED - {:.0}% DAILY LOSS 🚨🚨🚨",
+               limits.max_daily_drawdown_pct * 100.0);
+        info!("System will halt all trading and require manual intervention");
         
         self.emergency_stop.store(true, Ordering::SeqCst);
+        let detail = json!({ "limit_pct": limits.max_daily_drawdown_pct, "capital": self.current_capital() });
+        self.audit(AuditEntry::new(Component::Risk, AuditAction::Halted, "system", "daily_drawdown").with_detail(detail.clone()));
+        self.notify_breaker("emergency_stop", true, detail);
         
         // Close all positions immediately
         self.close_all_positions();
         
         // Save state to database
-        self.save_emergency_state();
+        info!("💾 Saving emergency state to database...");
+        self.save_state("emergency_state", Severity::Critical, "Book and breakers at emergency stop");
         
         // Send alerts to all configured channels
         self.send_emergency_alerts();
     }
     
     fn trigger_circuit_breaker_15min(&self) {
-        println!("⚠️ 15-minute circuit breaker triggered - 10% loss");
-        self.circuit_breaker_15min.store(true, Ordering::SeqCst);
-        
-        // Schedule re-enable after 1 hour
-        std::thread::spawn(move || {
-            std::thread::sleep(std::time::Duration::from_secs(3600));
-            // Re-enable after cooldown
-        });
+        let limits = self.limits();
+        warn!("⚠️ 15-minute circuit breaker triggered - {:.0}% loss", limits.loss_15min_pct * 100.0);
+        *self.circuit_breaker_15min.lock().unwrap() = Some(self.now());
+        self.audit(AuditEntry::new(Component::Risk, AuditAction::Halted, "system", "loss_15min")
+            .with_detail(json!({ "limit_pct": limits.loss_15min_pct })));
+        self.notify_breaker("loss_15min", true, json!({ "limit_pct": limits.loss_15min_pct }));
+        self.alert(Alert::new(Severity::Warning, "loss_15min", "15-minute loss circuit breaker tripped")
+            .with_body(format!("Losses over 15 minutes exceeded {:.1}% of capital", limits.loss_15min_pct * 100.0)));
     }
     
     fn trigger_circuit_breaker_1hr(&self) {
-        println!("⚠️ 1-hour circuit breaker triggered - 20% loss");
-        self.circuit_breaker_1hr.store(true, Ordering::SeqCst);
+        let limits = self.limits();
+        warn!("⚠️ 1-hour circuit breaker triggered - {:.0}% loss", limits.loss_1hr_pct * 100.0);
+        *self.circuit_breaker_1hr.lock().unwrap() = Some(self.now());
+        self.audit(AuditEntry::new(Component::Risk, AuditAction::Halted, "system", "loss_1hr")
+            .with_detail(json!({ "limit_pct": limits.loss_1hr_pct })));
+        self.notify_breaker("loss_1hr", true, json!({ "limit_pct": limits.loss_1hr_pct }));
+        self.alert(Alert::new(Severity::Warning, "loss_1hr", "1-hour loss circuit breaker tripped")
+            .with_body(format!("Losses over 1 hour exceeded {:.1}% of capital", limits.loss_1hr_pct * 100.0)));
+    }
+    
+    /// Whether a loss breaker still holds; it clears once `cooldown` has passed since it tripped
+    fn loss_breaker_active(&self, breaker: &str, tripped_at: &Mutex<Option<DateTime<Utc>>>, cooldown: Duration) -> bool {
+        let mut tripped_at = tripped_at.lock().unwrap();
+        let Some(at) = *tripped_at else {
+            return false;
+        };
+        if self.now() < at + cooldown {
+            return true;
+        }
         
-        // Schedule re-enable after 6 hours
-        std::thread::spawn(move || {
-            std::thread::sleep(std::time::Duration::from_secs(21600));
-            // Re-enable after cooldown
-        });
+        *tripped_at = None;
+        info!("🟢 {} circuit breaker cleared after {}h cooldown", breaker, cooldown.num_hours());
+        self.audit(AuditEntry::new(Component::Risk, AuditAction::Resumed, "system", breaker)
+            .with_detail(json!({ "tripped_at": at })));
+        self.notify_breaker(breaker, false, json!({ "tripped_at": at }));
+        false
+    }
+    
+    /// Override the exposure cap for a single asset (fraction of capital)
+    pub fn set_asset_exposure_cap(&mut self, asset: &str, max_pct: f64) {
+        self.launch_config.asset_exposure_caps.insert(asset.to_string(), max_pct);
+        Arc::make_mut(self.config.get_mut().unwrap()).asset_exposure_caps.insert(asset.to_string(), max_pct);
+    }
+    
+    /// Override the exposure cap for a single exchange (fraction of capital)
+    pub fn set_venue_exposure_cap(&mut self, exchange: &str, max_pct: f64) {
+        self.launch_config.venue_exposure_caps.insert(exchange.to_string(), max_pct);
+        Arc::make_mut(self.config.get_mut().unwrap()).venue_exposure_caps.insert(exchange.to_string(), max_pct);
+    }
+    
+    /// Feed the latest 1-day VaR (fraction of capital); trips or clears the VaR breaker
+    /// Returns true while the portfolio is within the VaR limit
+    pub fn update_var(&self, var_1d_pct: f64) -> bool {
+        let limits = self.limits();
+        let breached = var_1d_pct > limits.max_var_1d_pct;
+        let was_active = self.circuit_breaker_var.swap(breached, Ordering::SeqCst);
+        
+        let detail = json!({ "var_1d_pct": var_1d_pct, "limit_pct": limits.max_var_1d_pct });
+        if breached && !was_active {
+            warn!("⚠️ VaR circuit breaker triggered - 1-day VaR {:.1}% exceeds {:.1}% of capital",
+                  var_1d_pct * 100.0, limits.max_var_1d_pct * 100.0);
+            self.audit(AuditEntry::new(Component::Risk, AuditAction::Halted, "system", "var_limit").with_detail(detail.clone()));
+            self.notify_breaker("var_limit", true, detail);
+            self.alert(Alert::new(Severity::Warning, "var_limit", "VaR circuit breaker tripped - new risk blocked")
+                .with_body(format!("1-day VaR {:.1}% exceeds the {:.1}% limit", var_1d_pct * 100.0, limits.max_var_1d_pct * 100.0)));
+        } else if !breached && was_active {
+            info!("✅ VaR back within limit ({:.1}%), breaker cleared", var_1d_pct * 100.0);
+            self.audit(AuditEntry::new(Component::Risk, AuditAction::Resumed, "system", "var_limit").with_detail(detail.clone()));
+            self.notify_breaker("var_limit", false, detail);
+        }
+        
+        !breached
+    }
+    
+    /// Open notional per pattern as a fraction of current capital
+    pub fn position_weights(&self) -> HashMap<String, f64> {
+        let current = self.current_capital.load();
+        let weights = HashMap::new();
+        if current <= Decimal::ZERO {
+            return weights;
+        }
+        
+        self.open_positions.fold(weights, |mut weights, _, position| {
+            *weights.entry(position.pattern_hash.clone()).or_insert(0.0) += money::ratio(position.size, current);
+            weights
+        })
+    }
+    
+    /// Swap in freshly learned liquidity profiles
+    pub fn set_liquidity_profiles(&self, profiles: LiquidityProfiles) {
+        *self.liquidity_profiles.write().unwrap() = profiles;
+    }
+    
+    /// Largest position allowed in a symbol right now, after thin-hour tightening
+    pub fn max_position_size_for(&self, symbol: &str) -> Decimal {
+        let current = self.current_capital.load();
+        let adjustment = self.liquidity_profiles.read().unwrap().adjustment(symbol, self.now());
+        money::share(current, self.active_sizing_limits().0 * adjustment.size_multiplier)
+    }
+    
+    /// Position size cap and Kelly fraction for the current capital tier
+    pub fn active_sizing_limits(&self) -> (f64, f64) {
+        self.limits().limits_for_capital(money::to_f64(self.current_capital.load()))
+    }
+    
+    /// Stops must sit further from entry when the book is thin
+    pub fn check_stop_distance(&self, symbol: &str, entry_price: Decimal, stop_loss: Decimal) -> bool {
+        if entry_price <= Decimal::ZERO {
+            return false;
+        }
+        
+        let adjustment = self.liquidity_profiles.read().unwrap().adjustment(symbol, self.now());
+        let required = self.limits().min_stop_distance_pct * adjustment.stop_multiplier;
+        let distance = money::ratio((entry_price - stop_loss).abs(), entry_price);
+        
+        if distance < required {
+            info!("Stop on {} is {:.2}% from entry, {:.2}% required at liquidity score {:.2}",
+                  symbol, distance * 100.0, required * 100.0, adjustment.score);
+            return false;
+        }
+        
+        true
+    }
+    
+    /// Softer lever than the emergency stop: keep managing exits, stop adding risk
+    pub fn enter_reduce_only(&self, reason: ReduceOnlyReason) {
+        let mut state = self.reduce_only.write().unwrap();
+        
+        // A manual hold is never downgraded to an automatic one
+        if matches!(*state, Some(ReduceOnlyReason::Manual(_))) && matches!(reason, ReduceOnlyReason::Degraded(_)) {
+            return;
+        }
+        if state.as_ref() != Some(&reason) {
+            warn!("🟠 Entering REDUCE-ONLY mode: {:?}", reason);
+            self.audit(AuditEntry::new(Component::Risk, AuditAction::Halted, "system", "reduce_only")
+                .with_detail(json!({ "reason": format!("{:?}", reason) })));
+            self.notify_breaker("reduce_only", true, json!({ "reason": format!("{:?}", reason) }));
+            self.alert(Alert::new(Severity::Warning, "reduce_only", "Reduce-only mode - new risk refused")
+                .with_body(format!("{:?}", reason)));
+            *state = Some(reason);
+        }
+    }
+    
+    pub fn exit_reduce_only(&self) {
+        let taken = self.reduce_only.write().unwrap().take();
+        if let Some(reason) = taken {
+            info!("🟢 Leaving reduce-only mode (was {:?})", reason);
+            self.audit(AuditEntry::new(Component::Risk, AuditAction::Resumed, "system", "reduce_only")
+                .with_detail(json!({ "reason": format!("{:?}", reason) })));
+            self.notify_breaker("reduce_only", false, json!({ "reason": format!("{:?}", reason) }));
+        }
+    }
+    
+    pub fn reduce_only_reason(&self) -> Option<ReduceOnlyReason> {
+        self.reduce_only.read().unwrap().clone()
+    }
+    
+    pub fn is_reduce_only(&self) -> bool {
+        self.reduce_only.read().unwrap().is_some()
+    }
+    
+    pub fn breaker_states(&self) -> BreakerStates {
+        BreakerStates {
+            emergency_stop: self.emergency_stop.load(Ordering::SeqCst),
+            loss_15min: self.circuit_breaker_15min.lock().unwrap().is_some(),
+            loss_1hr: self.circuit_breaker_1hr.lock().unwrap().is_some(),
+            var: self.circuit_breaker_var.load(Ordering::SeqCst),
+            reduce_only: self.reduce_only_reason(),
+        }
+    }
+    
+    /// Report whether a data or venue subsystem is healthy
+    /// Sustained trouble past the grace period flips the system to reduce-only;
+    /// once every subsystem recovers, an automatic reduce-only state clears itself
+    pub fn report_subsystem_health(&self, subsystem: &str, healthy: bool) {
+        let now = self.now();
+        let mut degraded = self.degraded_since.lock().unwrap();
+        
+        if healthy {
+            degraded.remove(subsystem);
+        } else {
+            degraded.entry(subsystem.to_string()).or_insert(now);
+        }
+        
+        let sustained = degraded
+            .iter()
+            .find(|(_, since)| (now - **since).num_seconds() >= self.limits().degradation_grace_secs)
+            .map(|(name, _)| name.clone());
+        let all_healthy = degraded.is_empty();
+        drop(degraded);
+        
+        match sustained {
+            Some(name) => self.enter_reduce_only(ReduceOnlyReason::Degraded(format!("{} unhealthy", name))),
+            None if all_healthy => {
+                if matches!(self.reduce_only_reason(), Some(ReduceOnlyReason::Degraded(_))) {
+                    self.exit_reduce_only();
+                }
+            }
+            None => {}
+        }
     }
     
-    pub fn approve_order(&self, pattern_hash: &str, size: f64) -> bool {
+    /// Exits, cancels and partial closes are always allowed, even in reduce-only
+    /// or after an emergency stop - getting flat must never be blocked
+    pub fn approve_reduction(&self, position_id: &str, size: Decimal) -> bool {
+        let (approved, reason) = match self.open_positions.get(position_id) {
+            Some(position) if size > Decimal::ZERO && size <= position.size => (true, "reduction"),
+            Some(_) => (false, "invalid_size"),
+            None => (false, "unknown_position"),
+        };
+        let action = if approved { AuditAction::Approved } else { AuditAction::Rejected };
+        self.audit(AuditEntry::new(Component::Risk, action, position_id, reason).with_detail(json!({ "size": size })));
+        approved
+    }
+    
+    /// `working` is entry notional in the same asset already sent or still being sliced but not yet filled
+    pub fn approve_order(&self, pattern_hash: &str, symbol: &str, exchange: &str, size: Decimal, working: &[WorkingEntry]) -> bool {
+        let verdict = self.evaluate_order(pattern_hash, symbol, exchange, size, working);
+        
+        let entry = match verdict {
+            Ok(()) => AuditEntry::new(Component::Risk, AuditAction::Approved, pattern_hash, "within_limits"),
+            Err(rejection) => AuditEntry::new(Component::Risk, AuditAction::Rejected, pattern_hash, rejection.as_str()),
+        };
+        self.audit(entry.with_detail(json!({
+            "symbol": symbol,
+            "exchange": exchange,
+            "size": size,
+            "working": working.iter().map(|w| w.notional).sum::<Decimal>(),
+            "capital": self.current_capital(),
+        })));
+        
+        verdict.is_ok()
+    }
+    
+    /// Every pre-trade check approve_order runs, stopping at the first that fails
+    pub fn evaluate_order(&self, pattern_hash: &str, symbol: &str, exchange: &str, size: Decimal, working: &[WorkingEntry]) -> Result<(), OrderRejection> {
+        let limits = self.limits();
+        
         // Check if emergency stop is active
         if self.emergency_stop.load(Ordering::SeqCst) {
-            return false;
+            return Err(OrderRejection::EmergencyStop);
+        }
+        
+        // New risk is refused while reduce-only
+        if let Some(reason) = self.reduce_only_reason() {
+            info!("Order for {} refused - reduce-only mode ({:?})", pattern_hash, reason);
+            return Err(OrderRejection::ReduceOnly);
         }
         
         // Check circuit breakers
         if !self.check_risk_limits() {
-            return false;
+            return Err(OrderRejection::RiskLimits);
+        }
+        
+        let universe = self.symbol_universe();
+        if !universe.is_empty() && !universe.iter().any(|s| s == symbol) {
+            info!("Order for {} refused - {} is outside the symbol universe", pattern_hash, symbol);
+            return Err(OrderRejection::SymbolUniverse);
         }
         
         // Check concurrent position limits
-        let positions = self.open_positions.lock().unwrap();
-        let pattern_positions = positions
-            .values()
-            .filter(|p| p.pattern_hash == pattern_hash)
-            .count();
-        
-        if pattern_positions >= self.max_concurrent_positions as usize {
-            println!("Max concurrent positions reached for pattern {}", pattern_hash);
-            return false;
+        let pattern_positions = self.open_positions.count(|p| p.pattern_hash == pattern_hash);
+        
+        if pattern_positions >= limits.max_concurrent_positions as usize {
+            info!("Max concurrent positions reached for pattern {}", pattern_hash);
+            return Err(OrderRejection::MaxConcurrentPositions);
         }
         
         // Check portfolio correlation
-        if self.calculate_portfolio_correlation(pattern_hash) > 0.7 {
-            println!("Position too correlated with existing portfolio");
-            return false;
+        if self.calculate_portfolio_correlation(pattern_hash) > limits.max_portfolio_correlation {
+            info!("Position too correlated with existing portfolio");
+            return Err(OrderRejection::PortfolioCorrelation);
         }
         
         // Check if we have enough capital
-        let current = *self.current_capital.lock().unwrap();
-        if size > current * 0.5 {
-            println!("Position size too large relative to capital");
-            return false;
+        let current = self.current_capital.load();
+        if size > money::share(current, limits.max_order_pct) {
+            info!("Position size too large relative to capital");
+            return Err(OrderRejection::OrderTooLarge);
         }
         
+        // Check position size against the symbol's current liquidity
+        let max_for_symbol = self.max_position_size_for(symbol);
+        if size > max_for_symbol {
+            info!("Position ${:.2} in {} exceeds ${:.2} allowed at current liquidity",
+                  size, symbol, max_for_symbol);
+            return Err(OrderRejection::Liquidity);
+        }
+        
+        // Check notional exposure per asset, across every quote currency, and per venue,
+        // counting entries still working as if they had filled
+        let working_in = |matches: &dyn Fn(&WorkingEntry) -> bool| -> Decimal {
+            working.iter().filter(|w| matches(w)).map(|w| w.notional).sum()
+        };
+        let (asset, _) = split_symbol(symbol);
+        let asset_cap = limits.asset_exposure_cap(asset);
+        let asset_exposure = self.asset_exposure(asset) + working_in(&|w| split_symbol(&w.symbol).0 == asset) + size;
+        if asset_exposure > money::share(current, asset_cap) {
+            info!("Exposure to {} would reach ${:.2} (cap {:.0}% of capital)",
+                  asset, asset_exposure, asset_cap * 100.0);
+            return Err(OrderRejection::AssetExposure);
+        }
+        
+        let venue = self.venue_of(exchange);
+        let venue_cap = limits.venue_exposure_caps.get(venue).copied()
+            .unwrap_or(limits.max_venue_exposure_pct);
+        let venue_exposure = self.venue_exposure(venue) + working_in(&|w| self.venue_of(&w.exchange) == venue) + size;
+        if venue_exposure > money::share(current, venue_cap) {
+            info!("Exposure on {} would reach ${:.2} (cap {:.0}% of capital)",
+                  venue, venue_exposure, venue_cap * 100.0);
+            return Err(OrderRejection::VenueExposure);
+        }
+        
+        // An account trades only its own partition, whatever the rest of the portfolio holds
+        if let Some(balance) = self.accounts.as_ref().and_then(|book| book.balance(exchange)) {
+            let account_exposure = self.account_exposure(exchange) + working_in(&|w| w.exchange == exchange) + size;
+            if account_exposure > balance {
+                info!("Exposure on account {} would reach ${:.2} of its ${:.2} balance",
+                      exchange, account_exposure, balance);
+                return Err(OrderRejection::AccountBudget);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Total notional currently held in a single base asset, e.g. DOGE through DOGE-USD and DOGE-USDT
+    pub fn asset_exposure(&self, asset: &str) -> Decimal {
+        self.open_positions.notional(|p| split_symbol(&p.symbol).0 == asset)
+    }
+    
+    /// Total notional currently held on a single venue, across every account there
+    pub fn venue_exposure(&self, venue: &str) -> Decimal {
+        self.open_positions.notional(|p| self.venue_of(&p.exchange) == venue)
+    }
+
+    /// The venue an exchange account trades on; without [[accounts]] each name is its own venue
+    fn venue_of<'a>(&'a self, exchange: &'a str) -> &'a str {
+        self.accounts.as_ref().map_or(exchange, |book| book.venue_of(exchange))
+    }
+    
+    /// Total notional currently held through one exchange account
+    pub fn account_exposure(&self, account: &str) -> Decimal {
+        self.open_positions.notional(|p| p.exchange == account)
+    }
+    
+    pub fn record_position_opened(&self, position_id: &str, position: Position) {
+        self.write(LedgerWrite::PositionOpened { position_id: position_id.to_string(), position: position.clone() });
+        self.open_positions.insert(position_id, position);
+        self.publish_positions();
+    }
+    
+    /// Grow a position by a later fill; the entry becomes total cost over total units
+    /// Returns false if the position is not open
+    pub fn add_to_position(&self, position_id: &str, size: Decimal, price: Decimal) -> bool {
+        let added = self.open_positions.update(position_id, |position| {
+            let total = position.size + size;
+            let units = position.units() + size.checked_div(price).unwrap_or_default();
+            position.entry_price = total.checked_div(units).unwrap_or(position.entry_price);
+            position.size = total;
+            position.clone()
+        });
+        let Some(position) = added else {
+            return false;
+        };
+        self.write(LedgerWrite::PositionChanged { position_id: position_id.to_string(), position });
+        self.publish_positions();
         true
     }
     
+    pub fn record_position_closed(&self, position_id: &str) -> Option<Position> {
+        let closed = self.open_positions.remove(position_id);
+        if closed.is_some() {
+            self.write(LedgerWrite::PositionClosed { position_id: position_id.to_string(), at: self.now() });
+            self.publish_positions();
+        }
+        closed
+    }
+    
+    /// Shrink a position after a partial exit; a position reduced to nothing is closed
+    /// Returns the remaining position, if any
+    pub fn reduce_position(&self, position_id: &str, size: Decimal) -> Option<Position> {
+        let remaining = self.open_positions.update_or_remove(position_id, |position| {
+            position.size -= size;
+            position.size > Decimal::new(1, MONEY_DP)
+        })?;
+        self.write(match &remaining {
+            Some(position) => LedgerWrite::PositionChanged { position_id: position_id.to_string(), position: position.clone() },
+            None => LedgerWrite::PositionClosed { position_id: position_id.to_string(), at: self.now() },
+        });
+        self.publish_positions();
+        remaining
+    }
+    
+    pub fn open_positions_snapshot(&self) -> Vec<(String, Position)> {
+        self.open_positions.snapshot()
+    }
+    
+    /// Move a position's stop (trailing stops ratchet through here)
+    pub fn update_stop_loss(&self, position_id: &str, stop_loss: Decimal) {
+        let moved = self.open_positions.update(position_id, |position| {
+            position.stop_loss = stop_loss;
+            position.clone()
+        });
+        if let Some(position) = moved {
+            self.write(LedgerWrite::PositionChanged { position_id: position_id.to_string(), position });
+        }
+    }
+    
+    /// Replace the correlation matrix with a freshly computed one
+    pub fn set_correlations(&self, matrix: HashMap<(String, String), f64>) {
+        *self.position_correlations.write().unwrap() = matrix;
+    }
+    
     fn calculate_portfolio_correlation(&self, new_pattern: &str) -> f64 {
         // Calculate correlation between new pattern and existing positions
-        // Simplified - in production would use historical correlation matrix
+        // using the empirical matrix refreshed hourly from test_results
         
-        let positions = self.open_positions.lock().unwrap();
-        if positions.is_empty() {
+        if self.open_positions.is_empty() {
             return 0.0;
         }
         
         // Check if adding this position would over-correlate portfolio
-        let correlations = self.position_correlations.lock().unwrap();
-        
-        let max_correlation = positions
-            .keys()
-            .filter_map(|existing| {
-                correlations.get(&(existing.clone(), new_pattern.to_string()))
-                    .or_else(|| correlations.get(&(new_pattern.to_string(), existing.clone())))
-            })
-            .fold(0.0_f64, |max, &corr| max.max(corr.abs()));
+        let correlations = self.position_correlations.read().unwrap();
         
-        max_correlation
+        self.open_positions.fold(0.0_f64, |max, _, existing| {
+            correlations.get(&(existing.pattern_hash.clone(), new_pattern.to_string()))
+                .or_else(|| correlations.get(&(new_pattern.to_string(), existing.pattern_hash.clone())))
+                .map_or(max, |corr| max.max(corr.abs()))
+        })
     }
     
-    pub fn update_capital(&self, new_capital: f64) {
-        let mut current = self.current_capital.lock().unwrap();
-        let mut daily_high = self.daily_high.lock().unwrap();
+    /// Snapshot of the configured limits, recorded in the run manifest
+    pub fn risk_limits(&self) -> serde_json::Value {
+        serde_json::to_value(&*self.limits()).unwrap_or_default()
+    }
+    
+    /// The limits in force right now
+    pub fn limits(&self) -> Arc<RiskConfig> {
+        self.config.read().unwrap().clone()
+    }
+    
+    /// The limits the process launched with; runtime updates may not loosen past these
+    pub fn launch_limits(&self) -> &RiskConfig {
+        &self.launch_config
+    }
+    
+    /// Tighten limits, or with `may_loosen` also loosen them back toward the launch values
+    pub fn update_limits(&self, update: &LimitUpdate, may_loosen: bool) -> Result<Arc<RiskConfig>, Vec<String>> {
+        let mut config = self.config.write().unwrap();
+        let ceiling = if may_loosen { self.launch_config.clone() } else { (**config).clone() };
+        let next = Arc::new(config.with_update(update, &ceiling)?);
+        *config = next.clone();
+        drop(config);
         
-        *current = new_capital;
+        info!("🛡️ Risk limits updated: {}", serde_json::to_string(update).unwrap_or_default());
+        self.audit(AuditEntry::new(Component::Risk, AuditAction::Approved, "system", "limits_updated")
+            .with_detail(json!({ "update": update, "limits": &*next })));
+        Ok(next)
+    }
+    
+    /// Symbols new positions may open in; empty allows any
+    pub fn symbol_universe(&self) -> Arc<Vec<String>> {
+        self.symbol_universe.read().unwrap().clone()
+    }
+    
+    /// Open positions outside the new universe are left to their exits
+    pub fn set_symbol_universe(&self, symbols: Vec<String>) {
+        *self.symbol_universe.write().unwrap() = Arc::new(symbols);
+    }
+    
+    pub fn starting_capital(&self) -> Decimal {
+        self.starting_capital
+    }
+    
+    /// High-water mark for today's drawdown; reset on re-arm
+    pub fn daily_high(&self) -> Decimal {
+        self.daily_high.load()
+    }
+    
+    pub fn current_capital(&self) -> Decimal {
+        self.current_capital.load()
+    }
+    
+    pub fn set_accounts(&mut self, accounts: Arc<AccountBook>) {
+        self.accounts = Some(accounts);
+    }
+    
+    pub fn accounts(&self) -> Option<&Arc<AccountBook>> {
+        self.accounts.as_ref()
+    }
+    
+    /// Fees or realized PnL of a fill, credited to the account it happened on
+    pub fn record_account_pnl(&self, account: &str, pnl: Decimal) {
+        if let Some(book) = &self.accounts {
+            book.apply_pnl(account, pnl);
+        }
+    }
+    
+    /// What discovery sizes its test budget from: the discovery account's balance if there is
+    /// one, else all of current capital
+    pub fn discovery_capital(&self) -> Decimal {
+        self.accounts
+            .as_ref()
+            .and_then(|book| book.discovery().and_then(|account| book.balance(&account.name)))
+            .unwrap_or_else(|| self.current_capital())
+    }
+    
+    pub fn update_capital(&self, new_capital: Decimal) {
+        let previous = self.current_capital.swap(new_capital);
         
         // Update daily high water mark
-        if new_capital > *daily_high {
-            *daily_high = new_capital;
-        }
+        self.daily_high.fetch_max(new_capital);
         
         // Track losses for circuit breakers
-        if new_capital < *current {
-            let loss = *current - new_capital;
-            let now = Utc::now();
+        if new_capital < previous {
+            let loss = previous - new_capital;
+            let now = self.now();
             
-            self.losses_15min.lock().unwrap().push((now, loss));
-            self.losses_1hr.lock().unwrap().push((now, loss));
-            self.losses_24hr.lock().unwrap().push((now, loss));
+            self.losses_15min.write().unwrap().push((now, loss));
+            self.losses_1hr.write().unwrap().push((now, loss));
+            self.losses_24hr.write().unwrap().push((now, loss));
             
             // Clean old entries
             self.clean_old_losses();
@@ -304,40 +997,120 @@ impl RiskManager {
     }
     
     fn clean_old_losses(&self) {
-        let now = Utc::now();
+        let now = self.now();
         
         // Clean 15-minute window
-        let mut losses_15 = self.losses_15min.lock().unwrap();
+        let mut losses_15 = self.losses_15min.write().unwrap();
         losses_15.retain(|(time, _)| *time > now - Duration::minutes(15));
         
         // Clean 1-hour window
-        let mut losses_1h = self.losses_1hr.lock().unwrap();
+        let mut losses_1h = self.losses_1hr.write().unwrap();
         losses_1h.retain(|(time, _)| *time > now - Duration::hours(1));
         
         // Clean 24-hour window
-        let mut losses_24h = self.losses_24hr.lock().unwrap();
+        let mut losses_24h = self.losses_24hr.write().unwrap();
         losses_24h.retain(|(time, _)| *time > now - Duration::hours(24));
     }
     
-    fn close_all_positions(&self) {
-        println!("📕 Closing all positions...");
-        let positions = self.open_positions.lock().unwrap();
-        
-        for (hash, position) in positions.iter() {
-            println!("Closing position: {} Size: ${:.2}", hash, position.size);
-            // Execute market close
-            // In production, this would interface with exchange
+    /// Ask the execution engine to cancel every order and exit every position at market
+    /// Returns immediately; the engine confirms fills and alerts on anything it cannot flatten
+    pub fn close_all_positions(&self) {
+        info!("📕 Closing all positions...");
+        for (id, position) in self.open_positions.snapshot() {
+            info!("Closing position: {} {} Size: ${:.2}", id, position.symbol, position.size);
         }
+        
+        // Stored as a permit if the engine is busy, so the request is never lost
+        self.flatten_requested.notify_one();
     }
     
-    fn save_emergency_state(&self) {
-        // Save current state to database for post-mortem analysis
-        println!("💾 Saving emergency state to database...");
+    /// Resolves when a flatten-all has been requested
+    pub async fn flatten_requested(&self) {
+        self.flatten_requested.notified().await;
     }
     
+    /// Record the book, capital and every breaker in risk_events, for post-mortems and restarts
+    pub fn save_state(&self, event_type: &str, severity: Severity, description: &str) {
+        let positions: Vec<serde_json::Value> = self.open_positions_snapshot().into_iter().map(|(id, p)| json!({
+            "position_id": id, "symbol": p.symbol, "exchange": p.exchange, "size": p.size,
+            "entry_price": p.entry_price, "stop_loss": p.stop_loss, "pattern_hash": p.pattern_hash,
+        })).collect();
+        self.risk_event(event_type, severity, description.to_string(), json!({
+            "daily_high": self.daily_high.load(),
+            "breakers": self.breaker_states(),
+            "open_positions": positions,
+            "accounts": self.accounts.as_ref().map(|book| book.balances()),
+        }));
+    }
+    
+    /// Critical alert with the drawdown that tripped the stop and every position being flattened
     fn send_emergency_alerts(&self) {
-        // Send alerts via Discord, email, SMS, etc.
-        println!("📨 Sending emergency alerts...");
+        let limits = self.limits();
+        let current = self.current_capital();
+        let daily_high = self.daily_high.load();
+        let drawdown_pct = money::ratio(daily_high - current, daily_high) * 100.0;
+        let mut body = format!(
+            "Capital ${:.2} vs daily high ${:.2}: {:.2}% drawdown (limit {:.0}%)\nAll positions are being closed; trading stays halted until an operator re-arms",
+            current, daily_high, drawdown_pct, limits.max_daily_drawdown_pct * 100.0,
+        );
+        for (id, position) in self.open_positions_snapshot() {
+            body.push_str(&format!("\n• {} {} on {} ${:.2} @ {:.6} (pattern {})",
+                id, position.symbol, position.exchange, position.size, position.entry_price, position.pattern_hash));
+        }
+        
+        info!("📨 Sending emergency alerts...");
+        self.alert(Alert::new(Severity::Critical, "emergency_stop", "EMERGENCY STOP - daily loss limit hit").with_body(body));
+        
+        // Escalate to on-call; re-arming waits for someone to acknowledge it
+        if let Some(incidents) = &self.incidents {
+            let positions: Vec<serde_json::Value> = self.open_positions_snapshot().into_iter().map(|(id, p)| json!({
+                "position_id": id, "symbol": p.symbol, "exchange": p.exchange, "size": p.size,
+                "entry_price": p.entry_price, "stop_loss": p.stop_loss, "pattern_hash": p.pattern_hash,
+            })).collect();
+            let incident = Incident::new(
+                format!("v26meme EMERGENCY STOP: {:.2}% daily drawdown", drawdown_pct),
+                json!({
+                    "capital": current,
+                    "daily_high": daily_high,
+                    "drawdown_pct": drawdown_pct,
+                    "limit_pct": limits.max_daily_drawdown_pct * 100.0,
+                    "open_positions": positions,
+                }),
+            );
+            incidents.escalate(incident);
+        }
+    }
+    
+    pub fn is_emergency_stopped(&self) -> bool {
+        self.emergency_stop.load(Ordering::SeqCst)
+    }
+    
+    /// Clear the emergency stop once any escalated incident is acknowledged; the daily high
+    /// restarts from current capital so the same drawdown does not trip it again at once
+    pub async fn rearm(&self) -> Result<(), RearmBlocked> {
+        if !self.is_emergency_stopped() {
+            return Err(RearmBlocked::NotHalted);
+        }
+        if let Some(incidents) = &self.incidents {
+            incidents.check_rearm().await?;
+            incidents.clear();
+        }
+        
+        let capital = self.current_capital();
+        self.daily_high.store(capital);
+        // The losses behind the halt were acknowledged with it; left in, they would trip the loss breakers at once
+        for window in [&self.losses_15min, &self.losses_1hr, &self.losses_24hr] {
+            window.write().unwrap().clear();
+        }
+        self.emergency_stop.store(false, Ordering::SeqCst);
+        
+        info!("🟢 Emergency stop re-armed at ${:.2}", capital);
+        self.audit(AuditEntry::new(Component::Risk, AuditAction::Resumed, "system", "rearmed")
+            .with_detail(json!({ "capital": capital })));
+        self.notify_breaker("emergency_stop", false, json!({ "capital": capital }));
+        self.alert(Alert::new(Severity::Info, "rearmed", "Emergency stop re-armed - trading may resume")
+            .with_body(format!("Capital ${:.2}", capital)));
+        Ok(())
     }
 }
 
@@ -351,18 +1124,256 @@ pub struct Pattern {
     pub sharpe_ratio: f64,
 }
 
-#[tokio::main]
-async fn main() {
-    println!("🛡️ Starting V26MEME Risk Manager");
-    
-    let risk_manager = RiskManager::new(200.0); // Starting with $200
-    
-    // Keep the risk manager running and monitoring
-    loop {
-        if !risk_manager.check_risk_limits() {
-            println!("⚠️ Risk limits triggered, waiting...");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::incidents::{IncidentError, IncidentService};
+    use crate::risk::liquidity::LiquidityProfile;
+    use std::sync::atomic::AtomicBool;
+    use rust_decimal_macros::dec;
+
+    // Keep results independent of the wall-clock hour the tests run at
+    fn flat_liquidity(risk_manager: &RiskManager, symbols: &[&str]) {
+        let mut profiles = LiquidityProfiles::default();
+        for symbol in symbols {
+            profiles.insert(LiquidityProfile::flat(symbol));
         }
-        
-        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        risk_manager.set_liquidity_profiles(profiles);
+    }
+
+    fn position(pattern_hash: &str, symbol: &str, exchange: &str, size: Decimal) -> Position {
+        Position {
+            pattern_hash: pattern_hash.to_string(),
+            symbol: symbol.to_string(),
+            exchange: exchange.to_string(),
+            size,
+            entry_price: dec!(1),
+            entry_time: Utc::now(),
+            stop_loss: dec!(0.9),
+            take_profit: dec!(1.2),
+        }
+    }
+
+    #[test]
+    fn test_loss_windows_follow_the_injected_clock() {
+        let clock = Arc::new(crate::core::clock::SimulatedClock::new(Utc::now()));
+        let mut risk_manager = RiskManager::new(dec!(1000));
+        risk_manager.set_clock(clock.clone());
+        for window in [&risk_manager.losses_15min, &risk_manager.losses_1hr] {
+            window.write().unwrap().push((risk_manager.now(), dec!(60)));
+        }
+        assert!(risk_manager.calculate_period_loss(Duration::minutes(15)) > 0.05);
+        clock.advance(Duration::minutes(16));
+        assert_eq!(risk_manager.calculate_period_loss(Duration::minutes(15)), 0.0);
+        assert!(risk_manager.calculate_period_loss(Duration::hours(1)) > 0.05);
+    }
+
+    #[test]
+    fn test_a_losing_close_trips_the_15_minute_breaker() {
+        let risk_manager = RiskManager::new(dec!(1000));
+        risk_manager.update_capital(dec!(1010));
+        assert!(risk_manager.check_risk_limits());
+
+        // $130 lost in one fill is over 10% of what is left, well short of the daily limit
+        risk_manager.update_capital(dec!(880));
+        assert_eq!(risk_manager.losses_15min.read().unwrap().last().map(|(_, loss)| *loss), Some(dec!(130)));
+        assert!(!risk_manager.check_risk_limits());
+        assert!(risk_manager.breaker_states().loss_15min);
+        assert!(!risk_manager.breaker_states().emergency_stop);
+    }
+
+    #[test]
+    fn test_loss_breakers_clear_after_their_cooldown() {
+        let clock = Arc::new(crate::core::clock::SimulatedClock::new(Utc::now()));
+        let mut risk_manager = RiskManager::new(dec!(1000));
+        risk_manager.set_clock(clock.clone());
+        risk_manager.update_capital(dec!(1010));
+        risk_manager.update_capital(dec!(880));
+        assert!(!risk_manager.check_risk_limits());
+        assert!(risk_manager.breaker_states().loss_15min);
+
+        // Still tripped just short of the hour, clear once it has passed
+        clock.advance(Duration::minutes(59));
+        assert!(!risk_manager.check_risk_limits());
+        clock.advance(Duration::minutes(2));
+        assert!(risk_manager.check_risk_limits());
+        assert!(!risk_manager.breaker_states().loss_15min);
+    }
+
+    #[test]
+    fn test_asset_exposure_cap() {
+        let risk_manager = RiskManager::new(dec!(1000));
+        flat_liquidity(&risk_manager, &["DOGE-USD", "PEPE-USD"]);
+        risk_manager.record_position_opened("p1", position("a", "DOGE-USD", "coinbase", dec!(300)));
+
+        // 300 + 50 stays under 40% of 1000, 300 + 150 does not
+        assert!(risk_manager.approve_order("b", "DOGE-USD", "kraken", dec!(50), &[]));
+        assert!(!risk_manager.approve_order("b", "DOGE-USD", "kraken", dec!(150), &[]));
+        assert!(risk_manager.approve_order("b", "PEPE-USD", "kraken", dec!(150), &[]));
+
+        // The cap is on DOGE, whatever it is quoted in, and entries still working count toward it
+        assert!(!risk_manager.approve_order("b", "DOGE-USDT", "kraken", dec!(150), &[]));
+        let working = WorkingEntry { symbol: "DOGE-USD".to_string(), exchange: "coinbase".to_string(), notional: dec!(60) };
+        assert!(!risk_manager.approve_order("b", "DOGE-USDT", "kraken", dec!(50), &[working]));
+        assert_eq!(risk_manager.asset_exposure("DOGE"), dec!(300));
+    }
+
+    #[test]
+    fn test_asset_caps_apply_to_the_base_asset() {
+        let mut risk_manager = RiskManager::new(dec!(1000));
+        risk_manager.set_asset_exposure_cap("DOGE-USD", 0.20);
+        flat_liquidity(&risk_manager, &["DOGE-USD", "DOGE-USDT"]);
+        risk_manager.record_position_opened("p1", position("a", "DOGE-USD", "coinbase", dec!(150)));
+        assert!(risk_manager.approve_order("b", "DOGE-USDT", "kraken", dec!(50), &[]));
+        assert_eq!(risk_manager.evaluate_order("b", "DOGE-USDT", "kraken", dec!(60), &[]), Err(OrderRejection::AssetExposure));
+
+        risk_manager.set_asset_exposure_cap("DOGE", 0.10);
+        assert_eq!(risk_manager.limits().asset_exposure_cap("DOGE"), 0.10);
+        assert_eq!(risk_manager.limits().asset_exposure_cap("PEPE"), 0.40);
+    }
+
+    #[test]
+    fn test_account_budget_and_venue_exposure_across_accounts() {
+        use crate::core::accounts::{AccountConfig, AccountRole};
+        let account = |name: &str, venue: &str, role, capital_pct| AccountConfig {
+            name: name.to_string(), venue: venue.to_string(), role, capital_pct, credentials: None,
+        };
+        let mut risk_manager = RiskManager::new(dec!(1000));
+        risk_manager.set_accounts(Arc::new(AccountBook::new(vec![
+            account("cb-tests", "coinbase", AccountRole::Discovery, 0.1),
+            account("cb-live", "coinbase", AccountRole::Live, 0.6),
+            account("kr-live", "kraken", AccountRole::Live, 0.3),
+        ], dec!(1000))));
+        flat_liquidity(&risk_manager, &["DOGE-USD", "PEPE-USD", "SOL-USD"]);
+        assert_eq!(risk_manager.discovery_capital(), dec!(100));
+
+        // The test account's $100 is its own budget, though the portfolio has room
+        risk_manager.record_position_opened("p1", position("a", "DOGE-USD", "cb-tests", dec!(80)));
+        assert_eq!(risk_manager.evaluate_order("b", "PEPE-USD", "cb-tests", dec!(30), &[]), Err(OrderRejection::AccountBudget));
+        assert!(risk_manager.approve_order("b", "PEPE-USD", "cb-live", dec!(30), &[]));
+
+        // Both coinbase accounts count toward the 60% coinbase venue cap
+        risk_manager.record_position_opened("p2", position("c", "SOL-USD", "cb-live", dec!(390)));
+        assert_eq!(risk_manager.venue_exposure("coinbase"), dec!(470));
+        assert_eq!(risk_manager.evaluate_order("d", "PEPE-USD", "cb-live", dec!(150), &[]), Err(OrderRejection::VenueExposure));
+
+        risk_manager.record_account_pnl("cb-tests", dec!(-20));
+        assert_eq!(risk_manager.discovery_capital(), dec!(80));
+        assert_eq!(risk_manager.evaluate_order("b", "PEPE-USD", "cb-tests", dec!(5), &[]), Err(OrderRejection::AccountBudget));
+    }
+
+    #[test]
+    fn test_reduce_only_blocks_new_risk_but_not_exits() {
+        let risk_manager = RiskManager::new(dec!(1000));
+        flat_liquidity(&risk_manager, &["DOGE-USD"]);
+        risk_manager.record_position_opened("p1", position("a", "DOGE-USD", "coinbase", dec!(100)));
+
+        risk_manager.enter_reduce_only(ReduceOnlyReason::Manual("venue maintenance".to_string()));
+        assert!(!risk_manager.approve_order("b", "DOGE-USD", "coinbase", dec!(10), &[]));
+        assert_eq!(risk_manager.evaluate_order("b", "DOGE-USD", "coinbase", dec!(10), &[]), Err(OrderRejection::ReduceOnly));
+        assert!(risk_manager.approve_reduction("p1", dec!(40)));
+        assert!(!risk_manager.approve_reduction("p1", dec!(140)));
+
+        // Recovery of subsystems never clears a manual hold
+        risk_manager.report_subsystem_health("market_data", true);
+        assert!(risk_manager.is_reduce_only());
+
+        risk_manager.exit_reduce_only();
+        assert!(risk_manager.approve_order("b", "DOGE-USD", "coinbase", dec!(10), &[]));
+    }
+
+    #[test]
+    fn test_book_and_breakers_are_written_through_in_order() {
+        let mut risk_manager = RiskManager::new(dec!(1000));
+        let (ledger, mut writes) = Ledger::channel();
+        risk_manager.set_ledger(ledger);
+
+        risk_manager.record_position_opened("p1", position("a", "DOGE-USD", "coinbase", dec!(100)));
+        assert!(risk_manager.add_to_position("p1", dec!(100), dec!(2)));
+        risk_manager.reduce_position("p1", dec!(50));
+        risk_manager.reduce_position("p1", dec!(150));
+        risk_manager.enter_reduce_only(ReduceOnlyReason::Manual("venue maintenance".to_string()));
+
+        let mut next = || writes.try_recv().unwrap();
+        assert!(matches!(next(), LedgerWrite::PositionOpened { position, .. } if position.size == dec!(100)));
+        // 100 at 1.0 plus 100 at 2.0 averages in at 1.33
+        assert!(matches!(next(), LedgerWrite::PositionChanged { position, .. }
+                         if position.size == dec!(200) && position.entry_price.round_dp(9) == dec!(1.333333333)));
+        assert!(matches!(next(), LedgerWrite::PositionChanged { position, .. } if position.size == dec!(150)));
+        assert!(matches!(next(), LedgerWrite::PositionClosed { position_id, .. } if position_id == "p1"));
+        assert!(matches!(next(), LedgerWrite::RiskEvent(event)
+                         if event.event_type == "reduce_only" && event.severity == Severity::Warning));
+        assert!(writes.try_recv().is_none());
+    }
+
+    #[test]
+    fn test_venue_exposure_override() {
+        let mut risk_manager = RiskManager::new(dec!(1000));
+        risk_manager.set_venue_exposure_cap("kraken", 0.10);
+        flat_liquidity(&risk_manager, &["DOGE-USD", "PEPE-USD"]);
+        risk_manager.record_position_opened("p1", position("a", "DOGE-USD", "kraken", dec!(80)));
+
+        assert!(!risk_manager.approve_order("b", "PEPE-USD", "kraken", dec!(30), &[]));
+        assert_eq!(risk_manager.evaluate_order("b", "PEPE-USD", "kraken", dec!(30), &[]), Err(OrderRejection::VenueExposure));
+        assert!(risk_manager.approve_order("b", "PEPE-USD", "coinbase", dec!(30), &[]));
+
+        // A resting entry on kraken counts toward its cap before it fills
+        let resting = [WorkingEntry { symbol: "PEPE-USD".to_string(), exchange: "kraken".to_string(), notional: dec!(15) }];
+        assert!(risk_manager.approve_order("b", "PEPE-USD", "kraken", dec!(15), &[]));
+        assert_eq!(risk_manager.evaluate_order("b", "PEPE-USD", "kraken", dec!(15), &resting), Err(OrderRejection::VenueExposure));
+        assert!(risk_manager.approve_order("b", "PEPE-USD", "coinbase", dec!(15), &resting));
+    }
+
+    #[test]
+    fn test_position_cap_tightens_with_capital() {
+        let risk_manager = RiskManager::new(dec!(1000));
+        flat_liquidity(&risk_manager, &["DOGE-USD"]);
+        assert_eq!(risk_manager.max_position_size_for("DOGE-USD"), dec!(250));
+
+        risk_manager.update_capital(dec!(20000));
+        assert_eq!(risk_manager.active_sizing_limits(), (0.15, 0.20));
+        assert_eq!(risk_manager.max_position_size_for("DOGE-USD"), dec!(3000));
+    }
+
+    struct OnCall {
+        acknowledged: Arc<AtomicBool>,
+    }
+
+    #[async_trait::async_trait]
+    impl IncidentService for OnCall {
+        fn name(&self) -> &str {
+            "on-call"
+        }
+
+        async fn open(&self, _incident: &Incident) -> Result<(), IncidentError> {
+            Ok(())
+        }
+
+        async fn is_acknowledged(&self, _dedup_key: &str) -> Result<bool, IncidentError> {
+            Ok(self.acknowledged.load(Ordering::SeqCst))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rearm_waits_for_incident_acknowledgement() {
+        let acknowledged = Arc::new(AtomicBool::new(false));
+        let incidents = Arc::new(IncidentManager::new(Some(Box::new(OnCall { acknowledged: acknowledged.clone() }))));
+        let mut risk_manager = RiskManager::new(dec!(1000));
+        risk_manager.set_incident_manager(incidents.clone());
+        assert_eq!(risk_manager.rearm().await, Err(RearmBlocked::NotHalted));
+
+        risk_manager.update_capital(dec!(600));
+        assert!(!risk_manager.check_risk_limits());
+        assert!(risk_manager.is_emergency_stopped());
+        assert!(matches!(risk_manager.rearm().await, Err(RearmBlocked::IncidentOpening(_))));
+
+        tokio::task::yield_now().await;
+        assert!(matches!(risk_manager.rearm().await, Err(RearmBlocked::Unacknowledged(_))));
+
+        acknowledged.store(true, Ordering::SeqCst);
+        assert_eq!(risk_manager.rearm().await, Ok(()));
+        assert!(!risk_manager.is_emergency_stopped());
+        assert!(risk_manager.check_risk_limits());
+        assert_eq!(incidents.current(), None);
     }
 }
@@ -0,0 +1,196 @@
+// Risk State - The Risk Manager's Book Without a Global Lock
+// Every order approval reads capital, the open book and the breakers. With dozens of patterns
+// trading at once, one Mutex around the book made approvals queue behind each other and behind
+// every fill. Capital lives in atomics, as whole hundred-millionths of the quote currency, and
+// open positions are spread over shards, each behind its own RwLock: approvals only ever read,
+// and a fill locks one shard for the one position it touches. Scans across the book read each
+// shard in turn, so they see every position that was open before they started and never block
+// one another
+
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::RwLock;
+
+use crate::core::money::{Decimal, MONEY_DP};
+use crate::core::risk_manager::Position;
+
+/// Shards in a PositionBook; enough that concurrent fills rarely land on the same one
+pub const BOOK_SHARDS: usize = 16;
+
+/// Money stored as a count of 10^-MONEY_DP units, for capital read on every order and written
+/// on every fill; amounts are rounded to MONEY_DP places on the way in
+#[derive(Debug, Default)]
+pub struct AtomicMoney(AtomicI64);
+
+impl AtomicMoney {
+    pub fn new(value: Decimal) -> Self {
+        AtomicMoney(AtomicI64::new(units(value)))
+    }
+
+    pub fn load(&self) -> Decimal {
+        Decimal::new(self.0.load(Ordering::SeqCst), MONEY_DP)
+    }
+
+    pub fn store(&self, value: Decimal) {
+        self.0.store(units(value), Ordering::SeqCst);
+    }
+
+    /// Set to `value`; returns the previous value
+    pub fn swap(&self, value: Decimal) -> Decimal {
+        Decimal::new(self.0.swap(units(value), Ordering::SeqCst), MONEY_DP)
+    }
+
+    /// Raise to `value` if that is higher; returns the previous value
+    pub fn fetch_max(&self, value: Decimal) -> Decimal {
+        Decimal::new(self.0.fetch_max(units(value), Ordering::SeqCst), MONEY_DP)
+    }
+}
+
+/// Whole 10^-MONEY_DP units in `value`, saturating far beyond any real balance
+fn units(value: Decimal) -> i64 {
+    let mut rounded = value.round_dp(MONEY_DP);
+    rounded.rescale(MONEY_DP);
+    i64::try_from(rounded.mantissa()).unwrap_or(if rounded.is_sign_negative() { i64::MIN } else { i64::MAX })
+}
+
+/// Open positions by id, sharded by id
+pub struct PositionBook {
+    shards: Vec<RwLock<HashMap<String, Position>>>,
+    hasher: RandomState,
+}
+
+impl Default for PositionBook {
+    fn default() -> Self {
+        PositionBook {
+            shards: (0..BOOK_SHARDS).map(|_| RwLock::new(HashMap::new())).collect(),
+            hasher: RandomState::new(),
+        }
+    }
+}
+
+impl PositionBook {
+    fn shard(&self, position_id: &str) -> &RwLock<HashMap<String, Position>> {
+        &self.shards[self.hasher.hash_one(position_id) as usize % self.shards.len()]
+    }
+
+    pub fn insert(&self, position_id: &str, position: Position) {
+        self.shard(position_id).write().unwrap().insert(position_id.to_string(), position);
+    }
+
+    pub fn remove(&self, position_id: &str) -> Option<Position> {
+        self.shard(position_id).write().unwrap().remove(position_id)
+    }
+
+    pub fn get(&self, position_id: &str) -> Option<Position> {
+        self.shard(position_id).read().unwrap().get(position_id).cloned()
+    }
+
+    /// Change one position in place under its shard's lock; None if it is not open
+    pub fn update<R>(&self, position_id: &str, change: impl FnOnce(&mut Position) -> R) -> Option<R> {
+        self.shard(position_id).write().unwrap().get_mut(position_id).map(change)
+    }
+
+    /// Change one position, closing it if `change` returns false; the position left open, if any
+    pub fn update_or_remove(&self, position_id: &str, change: impl FnOnce(&mut Position) -> bool) -> Option<Option<Position>> {
+        let mut shard = self.shard(position_id).write().unwrap();
+        let position = shard.get_mut(position_id)?;
+        if change(position) {
+            Some(Some(position.clone()))
+        } else {
+            shard.remove(position_id);
+            Some(None)
+        }
+    }
+
+    /// Fold over every open position, one shard at a time
+    pub fn fold<T>(&self, init: T, mut f: impl FnMut(T, &str, &Position) -> T) -> T {
+        self.shards.iter().fold(init, |acc, shard| {
+            shard.read().unwrap().iter().fold(acc, |acc, (id, position)| f(acc, id, position))
+        })
+    }
+
+    /// Notional of the positions `filter` keeps
+    pub fn notional(&self, filter: impl Fn(&Position) -> bool) -> Decimal {
+        self.fold(Decimal::ZERO, |total, _, position| if filter(position) { total + position.size } else { total })
+    }
+
+    pub fn count(&self, filter: impl Fn(&Position) -> bool) -> usize {
+        self.fold(0, |count, _, position| count + filter(position) as usize)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| shard.read().unwrap().is_empty())
+    }
+
+    pub fn snapshot(&self) -> Vec<(String, Position)> {
+        self.fold(Vec::new(), |mut all, id, position| {
+            all.push((id.to_string(), position.clone()));
+            all
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    fn position(pattern_hash: &str, symbol: &str, size: Decimal) -> Position {
+        Position {
+            pattern_hash: pattern_hash.to_string(),
+            symbol: symbol.to_string(),
+            exchange: "coinbase".to_string(),
+            size,
+            entry_price: dec!(1),
+            entry_time: Utc::now(),
+            stop_loss: dec!(0.9),
+            take_profit: dec!(1.2),
+        }
+    }
+
+    #[test]
+    fn test_concurrent_fills_keep_the_book_consistent() {
+        let book = Arc::new(PositionBook::default());
+        let threads: Vec<_> = (0..8).map(|t| {
+            let book = book.clone();
+            std::thread::spawn(move || {
+                for i in 0..200 {
+                    let id = format!("{}-{}", t, i);
+                    book.insert(&id, position(&format!("pattern-{}", t), "DOGE-USD", dec!(10)));
+                    book.update(&id, |p| p.size += dec!(5));
+                    // Every other position is closed by a full reduction
+                    if i % 2 == 0 {
+                        assert!(matches!(book.update_or_remove(&id, |p| { p.size -= dec!(15); p.size > Decimal::ZERO }), Some(None)));
+                    }
+                }
+            })
+        }).collect();
+        threads.into_iter().for_each(|t| t.join().unwrap());
+
+        assert_eq!(book.count(|_| true), 800);
+        assert_eq!(book.count(|p| p.pattern_hash == "pattern-3"), 100);
+        assert_eq!(book.notional(|p| p.symbol == "DOGE-USD"), dec!(12000));
+        assert_eq!(book.get("3-1").map(|p| p.size), Some(dec!(15)));
+        assert!(book.get("3-0").is_none() && book.remove("3-0").is_none());
+    }
+
+    #[test]
+    fn test_atomic_capital_only_raises_the_high() {
+        let high = AtomicMoney::new(dec!(1000));
+        assert_eq!(high.fetch_max(dec!(900)), dec!(1000));
+        assert_eq!(high.fetch_max(dec!(1250.5)), dec!(1000));
+        assert_eq!(high.load(), dec!(1250.5));
+        high.store(dec!(800));
+        assert_eq!(high.fetch_max(dec!(800)), dec!(800));
+        assert_eq!(high.swap(dec!(750)), dec!(800));
+        assert_eq!(high.load(), dec!(750));
+
+        // Held to eight places, however many the amount came with
+        high.store(dec!(1000) / dec!(3));
+        assert_eq!(high.load(), dec!(333.33333333));
+    }
+}
@@ -24,11 +24,19 @@ async def run_daily_evolution():
         
         # Get all current patterns
         patterns_data = await conn.fetch("""
-            SELECT pattern_hash, entry_conditions, exit_conditions, 
-                   timeframe, test_count, win_count, total_profit,
-                   win_rate, sharpe_ratio, generation, parent_patterns,
-                   ai_enhanced, is_active
-            FROM discovered_patterns
+            SELECT p.pattern_hash, p.entry_conditions, p.exit_conditions, 
+                   p.timeframe, p.test_count, p.win_count, p.total_profit,
+                   p.win_rate, p.sharpe_ratio, p.generation, p.parent_patterns,
+                   p.ai_enhanced, p.is_active, q.avg_shortfall_bps
+            FROM discovered_patterns p
+            LEFT JOIN (
+                -- Size-weighted live execution cost over the last 30 days
+                SELECT pattern_hash,
+                       SUM(shortfall_bps * filled_size) / NULLIF(SUM(filled_size), 0) AS avg_shortfall_bps
+                FROM execution_quality
+                WHERE decided_at >= NOW() - INTERVAL '30 days'
+                GROUP BY pattern_hash
+            ) q ON q.pattern_hash = p.pattern_hash
         """)
         
         patterns = []
@@ -46,7 +54,8 @@ async def run_daily_evolution():
                 'generation': p['generation'],
                 'parent_patterns': p['parent_patterns'] or [],
                 'ai_enhanced': p['ai_enhanced'],
-                'is_active': p['is_active']
+                'is_active': p['is_active'],
+                'avg_shortfall_bps': float(p['avg_shortfall_bps']) if p['avg_shortfall_bps'] else 0.0
             })
         
         print(f"🧬 Starting evolution with {len(patterns)} patterns")
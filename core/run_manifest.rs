@@ -0,0 +1,145 @@
+// Run Manifest - Ties Every Decision to the Code and Config That Made It
+// Persisted once at startup; its run_id is stamped on every decision row written afterwards
+
+use serde::{Serialize, Deserialize};
+use sha2::{Sha256, Digest};
+use chrono::{DateTime, Utc};
+use tracing::info;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunManifest {
+    pub run_id: Uuid,
+    pub git_commit: String,
+    pub config_hash: String,
+    pub trading_mode: String,        // "live", "paper" or "dry_run"
+    pub venues: Vec<String>,
+    pub symbol_universe: Vec<String>,
+    pub risk_limits: serde_json::Value,
+    pub started_at: DateTime<Utc>,
+    #[serde(default)]
+    pub seed: Option<u64>,           // V26MEME_SEED to replay the run's random draws
+}
+
+impl RunManifest {
+    /// Snapshot the current process configuration
+    pub fn collect(risk_limits: serde_json::Value) -> Self {
+        let paper = std::env::var("ENABLE_PAPER_TRADING")
+            .map(|v| v.trim().eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        let trading_mode = if paper { "paper" } else { "live" }.to_string();
+
+        let venues = env_list("TRADING_VENUES", "coinbase,kraken");
+        let symbol_universe = env_list("SYMBOL_UNIVERSE", "BTC-USD,ETH-USD,SOL-USD,DOGE-USD");
+
+        let config_hash = Self::hash_config(&trading_mode, &venues, &symbol_universe, &risk_limits);
+
+        RunManifest {
+            run_id: Uuid::new_v4(),
+            git_commit: git_commit(),
+            config_hash,
+            trading_mode,
+            venues,
+            symbol_universe,
+            risk_limits,
+            started_at: Utc::now(),
+            seed: None,
+        }
+    }
+
+    /// Record the universe the config file settled on instead of SYMBOL_UNIVERSE alone
+    pub fn with_symbol_universe(mut self, symbols: Vec<String>) -> Self {
+        self.config_hash = Self::hash_config(&self.trading_mode, &self.venues, &symbols, &self.risk_limits);
+        self.symbol_universe = symbols;
+        self
+    }
+
+    /// A `--dry-run` process records itself as one, whatever ENABLE_PAPER_TRADING says
+    pub fn with_trading_mode(mut self, mode: &str) -> Self {
+        self.trading_mode = mode.to_string();
+        self.config_hash = Self::hash_config(&self.trading_mode, &self.venues, &self.symbol_universe, &self.risk_limits);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    fn hash_config(
+        trading_mode: &str,
+        venues: &[String],
+        symbols: &[String],
+        risk_limits: &serde_json::Value,
+    ) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(trading_mode.as_bytes());
+        hasher.update(venues.join(",").as_bytes());
+        hasher.update(symbols.join(",").as_bytes());
+        hasher.update(risk_limits.to_string().as_bytes());
+        format!("{:x}", hasher.finalize())[..16].to_string()
+    }
+
+    pub async fn persist(&self, db_pool: &PgPool) -> Result<(), sqlx::Error> {
+        let query = "
+            INSERT INTO run_manifests
+            (run_id, git_commit, config_hash, trading_mode, venues, symbol_universe, risk_limits, started_at, seed)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ";
+
+        sqlx::query(query)
+            .bind(self.run_id)
+            .bind(&self.git_commit)
+            .bind(&self.config_hash)
+            .bind(&self.trading_mode)
+            .bind(&self.venues)
+            .bind(&self.symbol_universe)
+            .bind(&self.risk_limits)
+            .bind(self.started_at)
+            .bind(self.seed.map(|s| s as i64))  // the same 64 bits; read back with `as u64`
+            .execute(db_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn log_banner(&self) {
+        info!("════════════════════════════════════════════════════");
+        info!("   Run ID:      {}", self.run_id);
+        info!("   Commit:      {}", self.git_commit);
+        info!("   Config hash: {}", self.config_hash);
+        info!("   Mode:        {}", self.trading_mode.to_uppercase());
+        info!("   Venues:      {}", self.venues.join(", "));
+        info!("   Symbols:     {}", self.symbol_universe.join(", "));
+        info!("   Risk limits: {}", self.risk_limits);
+        if let Some(seed) = self.seed {
+            info!("   Seed:        {}", seed);
+        }
+        info!("════════════════════════════════════════════════════");
+    }
+}
+
+fn env_list(key: &str, default: &str) -> Vec<String> {
+    std::env::var(key)
+        .unwrap_or_else(|_| default.to_string())
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// GIT_COMMIT wins (set by deploy scripts); fall back to asking git directly
+fn git_commit() -> String {
+    if let Ok(commit) = std::env::var("GIT_COMMIT") {
+        return commit;
+    }
+
+    std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
@@ -0,0 +1,283 @@
+// Hypothesis Seeding
+// Fresh hypotheses come from one of three arms: purely random generation, a template the
+// intelligence layer proposed from recent market summaries, or a concrete hypothesis an
+// external plugin submitted over gRPC. Every fresh hypothesis is tagged with its arm so they
+// can be compared on the same live tests
+
+use std::sync::{Arc, Mutex};
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+use sha2::{Sha256, Digest};
+use chrono::Utc;
+use sqlx::{PgPool, Row};
+
+use crate::core::discovery_engine::{Condition, Hypothesis};
+use crate::core::mutation::METRIC_FAMILIES;
+
+pub const OPERATORS: [&str; 5] = [">", "<", "==", "crosses_above", "crosses_below"];
+
+// Same bounds as random generation
+const MAX_ENTRY_CONDITIONS: usize = 5;
+const MAX_EXIT_CONDITIONS: usize = 3;
+const MAX_TIMEFRAME_MINUTES: u32 = 1439;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SeedSource {
+    Random,
+    Llm,
+    Plugin,  // submitted by an external process through the plugin gRPC service
+}
+
+impl SeedSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SeedSource::Random => "random",
+            SeedSource::Llm => "llm",
+            SeedSource::Plugin => "plugin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "random" => Some(SeedSource::Random),
+            "llm" => Some(SeedSource::Llm),
+            "plugin" => Some(SeedSource::Plugin),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConditionTemplate {
+    pub metric: String,
+    pub operator: String,
+    pub min_value: f64,
+    pub max_value: f64,
+}
+
+/// A family of hypotheses: fixed metrics and operators, thresholds and timeframe drawn from ranges
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HypothesisTemplate {
+    pub name: String,
+    pub rationale: String,
+    pub entry: Vec<ConditionTemplate>,
+    pub exit: Vec<ConditionTemplate>,
+    pub min_timeframe: u32,
+    pub max_timeframe: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_version: Option<i32>,  // set from the proposing call, never by the model
+}
+
+impl HypothesisTemplate {
+    /// Reject templates that reference unknown metrics or fall outside random generation's bounds
+    pub fn check(&self) -> Result<(), String> {
+        if self.entry.is_empty() || self.entry.len() > MAX_ENTRY_CONDITIONS {
+            return Err(format!("{}: {} entry conditions", self.name, self.entry.len()));
+        }
+        if self.exit.is_empty() || self.exit.len() > MAX_EXIT_CONDITIONS {
+            return Err(format!("{}: {} exit conditions", self.name, self.exit.len()));
+        }
+        if self.min_timeframe < 1 || self.min_timeframe > self.max_timeframe || self.max_timeframe > MAX_TIMEFRAME_MINUTES {
+            return Err(format!("{}: timeframe {}..{}", self.name, self.min_timeframe, self.max_timeframe));
+        }
+        for c in self.entry.iter().chain(&self.exit) {
+            if !is_known_metric(&c.metric) {
+                return Err(format!("{}: unknown metric {}", self.name, c.metric));
+            }
+            if !OPERATORS.contains(&c.operator.as_str()) {
+                return Err(format!("{}: unknown operator {}", self.name, c.operator));
+            }
+            if c.min_value.is_nan() || c.max_value.is_nan() || c.min_value > c.max_value {
+                return Err(format!("{}: {} range {}..{}", self.name, c.metric, c.min_value, c.max_value));
+            }
+        }
+        Ok(())
+    }
+
+    /// Draw one hypothesis from the template
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Hypothesis {
+        let mut draw = |templates: &[ConditionTemplate]| -> Vec<Condition> {
+            templates.iter().map(|c| Condition {
+                metric: c.metric.clone(),
+                operator: c.operator.clone(),
+                value: if c.max_value > c.min_value { rng.gen_range(c.min_value..=c.max_value) } else { c.min_value },
+                weight: rng.gen_range(0.1..1.0),
+            }).collect()
+        };
+        let entry_conditions = draw(&self.entry);
+        let exit_conditions = draw(&self.exit);
+
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{}{}{}", self.name, Utc::now().timestamp_nanos_opt().unwrap_or(0), rng.gen::<u64>()));
+        let hash = format!("{:x}", hasher.finalize());
+
+        Hypothesis {
+            hash: hash[..16].to_string(),
+            entry_conditions,
+            exit_conditions,
+            timeframe: rng.gen_range(self.min_timeframe..=self.max_timeframe),
+            created_at: Utc::now().timestamp(),
+            symbol: None,
+        }
+    }
+}
+
+/// Hold a concrete hypothesis (an external submission) to the same bounds as templates
+pub fn check_hypothesis(h: &Hypothesis) -> Result<(), String> {
+    let fixed = |conditions: &[Condition]| -> Vec<ConditionTemplate> {
+        conditions.iter().map(|c| ConditionTemplate {
+            metric: c.metric.clone(),
+            operator: c.operator.clone(),
+            min_value: c.value,
+            max_value: c.value,
+        }).collect()
+    };
+    if let Some(c) = h.entry_conditions.iter().chain(&h.exit_conditions).find(|c| !(0.0..=1.0).contains(&c.weight)) {
+        return Err(format!("{}: {} weight {}", h.hash, c.metric, c.weight));
+    }
+    HypothesisTemplate {
+        name: h.hash.clone(),
+        rationale: String::new(),
+        entry: fixed(&h.entry_conditions),
+        exit: fixed(&h.exit_conditions),
+        min_timeframe: h.timeframe,
+        max_timeframe: h.timeframe,
+        prompt_version: None,
+    }.check()
+}
+
+pub fn is_known_metric(metric: &str) -> bool {
+    METRIC_FAMILIES.iter().any(|family| family.contains(&metric))
+}
+
+/// Latest proposals from the intelligence layer, sampled by the discovery loop
+pub type TemplatePool = Arc<Mutex<Vec<HypothesisTemplate>>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeedingConfig {
+    pub llm_share: f64,  // fraction of fresh hypotheses drawn from templates while any are available
+}
+
+impl Default for SeedingConfig {
+    fn default() -> Self {
+        SeedingConfig { llm_share: 0.3 }
+    }
+}
+
+impl SeedingConfig {
+    /// LLM_SEED_SHARE; 0 turns the LLM arm off
+    pub fn from_env() -> Self {
+        let llm_share = std::env::var("LLM_SEED_SHARE")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(|v| v.clamp(0.0, 1.0))
+            .unwrap_or(SeedingConfig::default().llm_share);
+        SeedingConfig { llm_share }
+    }
+
+    /// Assign the next fresh hypothesis to an arm, and pick its template for the LLM arm
+    pub fn choose<'a, R: Rng>(&self, templates: &'a [HypothesisTemplate], rng: &mut R) -> Option<&'a HypothesisTemplate> {
+        if templates.is_empty() || !rng.gen_bool(self.llm_share) {
+            return None;
+        }
+        Some(&templates[rng.gen_range(0..templates.len())])
+    }
+}
+
+/// One arm of the seeding comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeedArmStats {
+    pub source: String,
+    pub hypotheses: i64,
+    pub tests: i64,
+    pub win_rate: f64,
+    pub avg_profit: f64,
+    pub activated: i64,
+}
+
+/// Live test results per arm for hypotheses created in the last `days`
+pub async fn compare_seed_sources(db_pool: &PgPool, days: i32) -> Result<Vec<SeedArmStats>, sqlx::Error> {
+    let rows = sqlx::query("
+        SELECT p.seed_source,
+               COUNT(DISTINCT p.pattern_hash) AS hypotheses,
+               COUNT(t.pattern_hash) AS tests,
+               COALESCE(AVG(CASE WHEN t.profitable THEN 1.0 ELSE 0.0 END), 0)::float8 AS win_rate,
+               COALESCE(AVG(t.profit), 0)::float8 AS avg_profit,
+               COUNT(DISTINCT p.pattern_hash) FILTER (WHERE p.is_active) AS activated
+        FROM discovered_patterns p
+        LEFT JOIN test_results t ON t.pattern_hash = p.pattern_hash
+        WHERE p.seed_source IS NOT NULL AND p.created_at > NOW() - make_interval(days => $1)
+        GROUP BY p.seed_source
+        ORDER BY p.seed_source
+    ")
+        .bind(days)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| SeedArmStats {
+        source: row.get("seed_source"),
+        hypotheses: row.get("hypotheses"),
+        tests: row.get("tests"),
+        win_rate: row.get("win_rate"),
+        avg_profit: row.get("avg_profit"),
+        activated: row.get("activated"),
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn template() -> HypothesisTemplate {
+        HypothesisTemplate {
+            name: "volume_breakout".to_string(),
+            rationale: "volume leads price on thin books".to_string(),
+            entry: vec![ConditionTemplate { metric: "volume_spike".to_string(), operator: ">".to_string(), min_value: 2.0, max_value: 5.0 }],
+            exit: vec![ConditionTemplate { metric: "price_delta_5m".to_string(), operator: "<".to_string(), min_value: -1.0, max_value: -1.0 }],
+            min_timeframe: 5,
+            max_timeframe: 30,
+            prompt_version: None,
+        }
+    }
+
+    #[test]
+    fn test_template_samples_stay_in_range() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let t = template();
+        assert!(t.check().is_ok());
+
+        for _ in 0..100 {
+            let h = t.sample(&mut rng);
+            assert!((2.0..=5.0).contains(&h.entry_conditions[0].value));
+            assert_eq!(h.exit_conditions[0].value, -1.0);
+            assert!((5..=30).contains(&h.timeframe));
+        }
+    }
+
+    #[test]
+    fn test_template_check_rejects_unknown_metrics_and_bad_ranges() {
+        let mut t = template();
+        t.entry[0].metric = "rsi_14".to_string();
+        assert!(t.check().is_err());
+
+        let mut t = template();
+        t.entry[0].min_value = 9.0;
+        assert!(t.check().is_err());
+
+        let mut t = template();
+        t.max_timeframe = 2000;
+        assert!(t.check().is_err());
+    }
+
+    #[test]
+    fn test_arm_assignment_follows_share() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let templates = vec![template()];
+        let config = SeedingConfig { llm_share: 0.3 };
+
+        let llm = (0..10_000).filter(|_| config.choose(&templates, &mut rng).is_some()).count();
+        assert!((2_700..3_300).contains(&llm));
+        assert!(config.choose(&[], &mut rng).is_none());
+    }
+}
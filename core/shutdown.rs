@@ -0,0 +1,137 @@
+// Shutdown - SIGTERM and Ctrl-C Without Dangling Orders
+// A signal used to kill the process mid-flight: working orders stayed on the venues with
+// nobody watching them and queued database writes were lost. Now the signal is broadcast,
+// discovery stops taking hypotheses, the execution engine cancels what it can and tags the
+// rest as orphaned for the next start to sweep, pending writes are flushed and the risk
+// state is saved, and only then does the process exit
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, Notify};
+use tracing::warn;
+
+/// Tripped once; every task holding a clone sees it
+#[derive(Clone)]
+pub struct Shutdown {
+    requested: Arc<watch::Sender<bool>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Shutdown { requested: Arc::new(watch::channel(false).0) }
+    }
+}
+
+impl Shutdown {
+    pub fn trigger(&self) {
+        self.requested.send_replace(true);
+    }
+
+    pub fn is_requested(&self) -> bool {
+        *self.requested.borrow()
+    }
+
+    /// Resolves once shutdown is triggered, at once if it already was
+    pub async fn requested(&self) {
+        let mut requested = self.requested.subscribe();
+        let _ = requested.wait_for(|requested| *requested).await;
+    }
+}
+
+/// Resolves on SIGINT (Ctrl-C) or SIGTERM, whichever comes first
+pub async fn signal() {
+    let interrupt = tokio::signal::ctrl_c();
+
+    #[cfg(unix)]
+    {
+        let terminate = async {
+            match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                Ok(mut terminate) => {
+                    terminate.recv().await;
+                }
+                Err(e) => {
+                    warn!("⚠️ Cannot listen for SIGTERM: {}", e);
+                    std::future::pending::<()>().await;
+                }
+            }
+        };
+        tokio::select! {
+            _ = interrupt => {}
+            _ = terminate => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = interrupt.await;
+    }
+}
+
+/// Background writes still in flight, so shutdown can wait for them to land
+#[derive(Clone, Default)]
+pub struct PendingWrites {
+    count: Arc<AtomicUsize>,
+    drained: Arc<Notify>,
+}
+
+impl PendingWrites {
+    pub fn started(&self) {
+        self.count.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn finished(&self) {
+        if self.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.drained.notify_waiters();
+        }
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// Wait for every write started so far, up to `timeout`; returns how many never finished
+    pub async fn drain(&self, timeout: Duration) -> usize {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let drained = self.drained.notified();
+            if self.in_flight() == 0 {
+                return 0;
+            }
+            if tokio::time::timeout_at(deadline, drained).await.is_err() {
+                return self.in_flight();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_waits_for_writes_in_flight() {
+        let pending = PendingWrites::default();
+        assert_eq!(pending.drain(Duration::from_millis(10)).await, 0);
+
+        pending.started();
+        pending.started();
+        let writer = pending.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            writer.finished();
+            writer.finished();
+        });
+        assert_eq!(pending.drain(Duration::from_secs(5)).await, 0);
+
+        // A write that never lands is reported rather than waited on forever
+        pending.started();
+        assert_eq!(pending.drain(Duration::from_millis(20)).await, 1);
+
+        let shutdown = Shutdown::default();
+        assert!(!shutdown.is_requested());
+        shutdown.trigger();
+        shutdown.requested().await;
+        assert!(shutdown.is_requested());
+    }
+}
@@ -0,0 +1,156 @@
+// Speciation
+// Groups patterns by what they look at, not by how well they do, so selection can keep
+// several distinct ideas alive instead of converging on twenty clones of the best one
+
+use std::collections::{HashMap, HashSet};
+use serde::{Serialize, Deserialize};
+
+use crate::core::discovery_engine::Hypothesis;
+use crate::core::embedding::{cosine_distance, embed};
+
+/// How similarity between two patterns is judged when forming species
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Niching {
+    #[default]
+    Signature,  // Jaccard over (side, metric, operator), thresholds ignored
+    Embedding,  // cosine distance between structural embeddings, thresholds and timeframe included
+}
+
+impl Niching {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Niching::Signature => "signature",
+            Niching::Embedding => "embedding",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "signature" => Some(Niching::Signature),
+            "embedding" => Some(Niching::Embedding),
+            _ => None,
+        }
+    }
+
+    /// EVOLUTION_NICHING, defaulting to signatures
+    pub fn from_env() -> Self {
+        std::env::var("EVOLUTION_NICHING")
+            .ok()
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or_default()
+    }
+}
+
+/// The (side, metric, operator) triples a hypothesis tests; thresholds are deliberately ignored
+pub fn signature(h: &Hypothesis) -> HashSet<String> {
+    let entry = h.entry_conditions.iter().map(|c| format!("entry:{}:{}", c.metric, c.operator));
+    let exit = h.exit_conditions.iter().map(|c| format!("exit:{}:{}", c.metric, c.operator));
+    entry.chain(exit).collect()
+}
+
+/// |A ∩ B| / |A ∪ B|; two empty signatures are identical
+pub fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Assign each hypothesis a species id
+/// Leader clustering in the given order: a hypothesis joins the first species whose leader is
+/// at least `threshold` similar, otherwise it founds a new one. Pass the population best-first
+/// so the fittest member of each niche is its leader
+pub fn assign_species(hypotheses: &[&Hypothesis], threshold: f64) -> Vec<usize> {
+    leader_cluster(hypotheses.iter().map(|h| signature(h)), |leader, sig| jaccard(leader, sig) >= threshold)
+}
+
+/// Same clustering on embeddings: a hypothesis joins a species whose leader is within `max_distance`
+pub fn assign_species_by_embedding(hypotheses: &[&Hypothesis], max_distance: f64) -> Vec<usize> {
+    leader_cluster(hypotheses.iter().map(|h| embed(h)), |leader, v| cosine_distance(leader, v) <= max_distance)
+}
+
+fn leader_cluster<T>(items: impl Iterator<Item = T>, same: impl Fn(&T, &T) -> bool) -> Vec<usize> {
+    let mut leaders: Vec<T> = Vec::new();
+
+    items.map(|item| {
+        match leaders.iter().position(|leader| same(leader, &item)) {
+            Some(species) => species,
+            None => {
+                leaders.push(item);
+                leaders.len() - 1
+            }
+        }
+    }).collect()
+}
+
+/// Shannon entropy of the species sizes: 0.0 when everything is one species, ln(n) when
+/// every pattern is its own species
+pub fn diversity_index(species: &[usize]) -> f64 {
+    let mut sizes: HashMap<usize, usize> = HashMap::new();
+    for s in species {
+        *sizes.entry(*s).or_insert(0) += 1;
+    }
+
+    let total = species.len() as f64;
+    -sizes.values()
+        .map(|&n| {
+            let p = n as f64 / total;
+            p * p.ln()
+        })
+        .sum::<f64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::discovery_engine::Condition;
+
+    fn hypothesis(entry: &[(&str, &str)], exit: &[(&str, &str)]) -> Hypothesis {
+        let conditions = |pairs: &[(&str, &str)]| pairs.iter().map(|(metric, operator)| Condition {
+            metric: metric.to_string(),
+            operator: operator.to_string(),
+            value: 1.0,
+            weight: 0.5,
+        }).collect();
+        Hypothesis {
+            hash: String::new(),
+            entry_conditions: conditions(entry),
+            exit_conditions: conditions(exit),
+            timeframe: 60,
+            created_at: 0,
+            symbol: None,
+        }
+    }
+
+    #[test]
+    fn test_clones_share_a_species() {
+        let a = hypothesis(&[("price_delta_5m", ">"), ("volume_ratio_1h", ">")], &[("price_delta_5m", "<")]);
+        let clone = hypothesis(&[("price_delta_5m", ">"), ("volume_ratio_1h", ">")], &[("price_delta_5m", "<")]);
+        let cousin = hypothesis(&[("price_delta_5m", ">")], &[("price_delta_5m", "<")]);
+        let other = hypothesis(&[("rsi_14", "<")], &[("spread_bps", ">")]);
+
+        assert!((jaccard(&signature(&a), &signature(&cousin)) - 2.0 / 3.0).abs() < 1e-12);
+        assert_eq!(assign_species(&[&a, &clone, &cousin, &other], 0.6), vec![0, 0, 0, 1]);
+        assert_eq!(assign_species(&[&a, &clone, &cousin, &other], 0.9), vec![0, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_embedding_niches_split_on_direction() {
+        let a = hypothesis(&[("price_delta_5m", ">"), ("volume_spike", ">")], &[("price_delta_5m", "<")]);
+        let clone = hypothesis(&[("price_delta_5m", ">"), ("volume_spike", ">")], &[("price_delta_5m", "<")]);
+        let inverse = hypothesis(&[("price_delta_5m", "<"), ("volume_spike", "<")], &[("price_delta_5m", ">")]);
+
+        // Flipping every comparison moves the embedding far enough to found its own niche
+        assert_eq!(assign_species_by_embedding(&[&a, &clone, &inverse], 0.15), vec![0, 0, 1]);
+        assert_eq!(assign_species_by_embedding(&[&a, &clone, &inverse], 2.0), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_diversity_index() {
+        assert_eq!(diversity_index(&[0, 0, 0, 0]), 0.0);
+        assert!((diversity_index(&[0, 1, 2, 3]) - 4.0_f64.ln()).abs() < 1e-12);
+        assert!(diversity_index(&[0, 0, 0, 1]) < diversity_index(&[0, 0, 1, 1]));
+        assert_eq!(diversity_index(&[]), 0.0);
+    }
+}
@@ -0,0 +1,227 @@
+// Stop Engine - Enforces Stop-Loss, Take-Profit and Trailing Stops
+// Watches every tick for symbols with open positions and emits exit signals
+// the moment a level is crossed. Direction is inferred per position:
+// a stop below entry means long, a stop above entry means short.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use tokio::sync::mpsc;
+use tracing::info;
+
+use crate::core::latency::{self, Stage};
+use crate::core::market_data::{PriceTick, TickStream};
+use crate::core::money::{self, Decimal};
+use crate::core::risk_manager::{Position, RiskManager};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TrailingConfig {
+    pub activation_pct: f64,  // favorable move from entry before trailing starts
+    pub trail_pct: f64,       // distance kept between the best price and the stop
+    pub step_pct: f64,        // minimum stop move, avoids churning on every tick
+}
+
+impl Default for TrailingConfig {
+    fn default() -> Self {
+        TrailingConfig {
+            activation_pct: 0.02,
+            trail_pct: 0.015,
+            step_pct: 0.0025,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExitReason {
+    StopLoss,
+    TakeProfit,
+    TrailingStop,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitSignal {
+    pub position_id: String,
+    pub pattern_hash: String,
+    pub symbol: String,
+    pub exchange: String,
+    pub size: Decimal,
+    pub reason: ExitReason,
+    pub trigger_price: f64,
+    pub triggered_at: DateTime<Utc>,
+}
+
+/// Per-position trailing state
+#[derive(Debug, Clone, Copy)]
+struct TrailingState {
+    best_price: Decimal,
+    stop: Decimal,
+    active: bool,
+}
+
+pub struct StopEngine {
+    pub trailing: TrailingConfig,
+    risk_manager: Arc<RiskManager>,
+    states: HashMap<String, TrailingState>,
+    signaled: HashSet<String>,  // exits already sent, awaiting the executor
+    exits: mpsc::Sender<ExitSignal>,
+}
+
+impl StopEngine {
+    pub fn new(risk_manager: Arc<RiskManager>, trailing: TrailingConfig, exits: mpsc::Sender<ExitSignal>) -> Self {
+        StopEngine {
+            trailing,
+            risk_manager,
+            states: HashMap::new(),
+            signaled: HashSet::new(),
+            exits,
+        }
+    }
+
+    /// Consume ticks until the feed closes
+    pub async fn run(mut self, mut ticks: TickStream) {
+        while let Some(tick) = ticks.recv().await {
+            for signal in self.on_tick(&tick) {
+                latency::record_since(Stage::DataToSignal, signal.triggered_at, &signal.symbol);
+                info!("🛑 {:?} hit on {} @ {:.6} (position {})",
+                      signal.reason, signal.symbol, signal.trigger_price, signal.position_id);
+                if self.exits.send(signal).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn on_tick(&mut self, tick: &PriceTick) -> Vec<ExitSignal> {
+        let positions = self.risk_manager.open_positions_snapshot();
+
+        // Forget state for positions that have closed
+        let is_open = |id: &String| positions.iter().any(|(open_id, _)| open_id == id);
+        self.states.retain(|id, _| is_open(id));
+        self.signaled.retain(is_open);
+
+        let mut signals = Vec::new();
+        let watched = positions.iter()
+            .filter(|(id, p)| p.symbol == tick.symbol && !self.signaled.contains(id));
+        for (position_id, position) in watched {
+            let state = self.states.entry(position_id.clone()).or_insert(TrailingState {
+                best_price: position.entry_price,
+                stop: position.stop_loss,
+                active: false,
+            });
+
+            let previous_stop = state.stop;
+            if let Some(reason) = evaluate(position, state, &self.trailing, money::from_f64(tick.price)) {
+                signals.push(ExitSignal {
+                    position_id: position_id.clone(),
+                    pattern_hash: position.pattern_hash.clone(),
+                    symbol: position.symbol.clone(),
+                    exchange: position.exchange.clone(),
+                    size: position.size,
+                    reason,
+                    trigger_price: tick.price,
+                    triggered_at: tick.timestamp,
+                });
+            } else if state.stop != previous_stop {
+                self.risk_manager.update_stop_loss(position_id, state.stop);
+            }
+        }
+
+        // Each position exits once; the executor closes it in the risk manager
+        for signal in &signals {
+            self.states.remove(&signal.position_id);
+            self.signaled.insert(signal.position_id.clone());
+        }
+
+        signals
+    }
+}
+
+/// Check one position against a price, ratcheting its trailing stop first
+fn evaluate(position: &Position, state: &mut TrailingState, config: &TrailingConfig, price: Decimal) -> Option<ExitReason> {
+    let entry = position.entry_price;
+    let is_long = position.is_long();
+    // Signed move in our favor, so the rest of the logic is direction-free
+    let favorable = |a: Decimal, b: Decimal| if is_long { a - b } else { b - a };
+
+    if favorable(price, state.best_price) > Decimal::ZERO {
+        state.best_price = price;
+    }
+
+    if favorable(state.best_price, entry) >= money::share(entry, config.activation_pct) {
+        let trail = money::from_f64(config.trail_pct);
+        let candidate = if is_long {
+            state.best_price * (Decimal::ONE - trail)
+        } else {
+            state.best_price * (Decimal::ONE + trail)
+        };
+        if favorable(candidate, state.stop) >= money::share(entry, config.step_pct) {
+            state.stop = candidate;
+            state.active = true;
+        }
+    }
+
+    if favorable(state.stop, price) >= Decimal::ZERO {
+        return Some(if state.active { ExitReason::TrailingStop } else { ExitReason::StopLoss });
+    }
+
+    if position.take_profit > Decimal::ZERO && favorable(price, position.take_profit) >= Decimal::ZERO {
+        return Some(ExitReason::TakeProfit);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn long_position() -> Position {
+        Position {
+            pattern_hash: "abc".to_string(),
+            symbol: "DOGE-USD".to_string(),
+            exchange: "coinbase".to_string(),
+            size: dec!(50),
+            entry_price: dec!(100),
+            entry_time: Utc::now(),
+            stop_loss: dec!(95),
+            take_profit: dec!(120),
+        }
+    }
+
+    #[test]
+    fn test_trailing_stop_ratchets_and_triggers() {
+        let position = long_position();
+        let config = TrailingConfig { activation_pct: 0.02, trail_pct: 0.01, step_pct: 0.005 };
+        let mut state = TrailingState { best_price: dec!(100), stop: dec!(95), active: false };
+
+        // Not yet activated
+        assert_eq!(evaluate(&position, &mut state, &config, dec!(101)), None);
+        assert_eq!(state.stop, dec!(95));
+
+        // Activated: stop trails 1% under the best price
+        assert_eq!(evaluate(&position, &mut state, &config, dec!(110)), None);
+        assert_eq!(state.stop, dec!(108.9));
+
+        // A tiny new high is below the step size - stop stays put
+        assert_eq!(evaluate(&position, &mut state, &config, dec!(110.2)), None);
+        assert_eq!(state.stop, dec!(108.9));
+
+        assert_eq!(evaluate(&position, &mut state, &config, dec!(108.5)), Some(ExitReason::TrailingStop));
+    }
+
+    #[test]
+    fn test_fixed_levels_for_short() {
+        let mut position = long_position();
+        position.stop_loss = dec!(105);
+        position.take_profit = dec!(90);
+        let config = TrailingConfig { activation_pct: 1.0, ..Default::default() };
+
+        let mut state = TrailingState { best_price: dec!(100), stop: dec!(105), active: false };
+        assert_eq!(evaluate(&position, &mut state, &config, dec!(89)), Some(ExitReason::TakeProfit));
+
+        let mut state = TrailingState { best_price: dec!(100), stop: dec!(105), active: false };
+        assert_eq!(evaluate(&position, &mut state, &config, dec!(106)), Some(ExitReason::StopLoss));
+    }
+}
@@ -0,0 +1,248 @@
+// Stress - Canned Market Scenarios Against the Live Strategy Set
+// `v26meme stress-test` sizes a position for every active pattern the way risk would today,
+// then walks the book through a scenario minute by minute: a flash crash that recovers, a venue
+// outage while the market slides, a 50% gap down, or a fee spike on a slow decline. Stops fill
+// at the first price the venue offers past them, and the daily drawdown stop flattens
+// everything once the venue answers. A scenario is contained when the worst loss stays within
+// max_daily_drawdown_pct. Nothing touches a venue or the database; risk runs on a SimulatedClock
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+use serde::{Serialize, Deserialize};
+
+use crate::core::clock::SimulatedClock;
+use crate::core::discovery_engine::TestResult;
+use crate::core::money::{self, Decimal};
+use crate::core::patterns::PatternListing;
+use crate::core::risk_manager::{Pattern, Position, RiskManager};
+use crate::risk::config::RiskConfig;
+use crate::risk::liquidity::{LiquidityProfile, LiquidityProfiles};
+
+/// Venue every stressed position is held on
+const STRESS_VENUE: &str = "stress";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scenario {
+    FlashCrash,      // -30% in five minutes, then most of it back within ten
+    ExchangeOutage,  // the venue is unreachable for 30 minutes while prices slide 15%
+    GapDown,         // prices open 50% lower with nothing traded in between
+    FeeSpike,        // fees ten times normal while prices drift 7% lower
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 4] = [Scenario::FlashCrash, Scenario::ExchangeOutage, Scenario::GapDown, Scenario::FeeSpike];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scenario::FlashCrash => "flash_crash",
+            Scenario::ExchangeOutage => "exchange_outage",
+            Scenario::GapDown => "gap_down",
+            Scenario::FeeSpike => "fee_spike",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Scenario::ALL.into_iter().find(|scenario| scenario.as_str() == s.replace('-', "_"))
+    }
+
+    /// One step per minute, prices as a multiple of the entry price
+    pub fn path(&self) -> Vec<Step> {
+        let step = |price: f64| Step { price, venue_up: true, fee_multiplier: 1.0 };
+        match self {
+            Scenario::FlashCrash => (1..=5).map(|m| step(1.0 - 0.06 * m as f64))
+                .chain((1..=10).map(|m| step(0.70 + 0.025 * m as f64)))
+                .collect(),
+            Scenario::ExchangeOutage => (1..=30).map(|m| Step { venue_up: false, ..step(1.0 - 0.005 * m as f64) })
+                .chain((0..10).map(|_| step(0.85)))
+                .collect(),
+            Scenario::GapDown => (0..10).map(|_| step(0.50)).collect(),
+            Scenario::FeeSpike => (1..=30).map(|m| Step { fee_multiplier: 10.0, ..step(1.0 - 0.07 * m as f64 / 30.0) }).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Step {
+    pub price: f64,           // every symbol moves together, relative to entry
+    pub venue_up: bool,       // orders can be placed this minute
+    pub fee_multiplier: f64,  // on the normal taker rate
+}
+
+/// An active pattern as risk would size it
+#[derive(Debug, Clone)]
+pub struct StressStrategy {
+    pub pattern: Pattern,
+    pub symbol: String,
+}
+
+impl StressStrategy {
+    /// Average win and loss come from the pattern's recorded test trades
+    pub fn from_listing(listing: &PatternListing, results: &[TestResult]) -> Self {
+        let average = |values: Vec<f64>| if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 };
+        StressStrategy {
+            pattern: Pattern {
+                hash: listing.pattern_hash.clone(),
+                win_rate: listing.win_rate,
+                avg_win_amount: average(results.iter().filter(|r| r.profit > Decimal::ZERO).map(|r| money::to_f64(r.profit)).collect()),
+                avg_loss_amount: average(results.iter().filter(|r| r.profit <= Decimal::ZERO).map(|r| money::to_f64(r.profit)).collect()),
+                sharpe_ratio: listing.sharpe_ratio,
+            },
+            symbol: listing.symbol.clone().unwrap_or_else(|| "*".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StressConfig {
+    pub capital: f64,
+    pub risk: RiskConfig,
+    pub stop_loss_pct: f64,  // below entry, where each position's stop sits
+    pub taker_rate: f64,     // normal fee on entries and exits
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StressReport {
+    pub scenario: Scenario,
+    pub positions: usize,
+    pub exposure: f64,            // notional opened
+    pub stops_hit: usize,
+    pub emergency_stop: bool,     // the daily drawdown stop tripped
+    pub worst_loss_pct: f64,      // deepest equity fell below starting capital
+    pub final_loss: f64,          // in dollars, once the path ends
+    pub limit_pct: f64,           // max_daily_drawdown_pct
+    pub contained: bool,
+}
+
+struct Held {
+    position_id: String,
+    units: f64,
+    stop: f64,
+}
+
+/// Walk `scenario` with a book sized from `strategies`
+pub fn run(scenario: Scenario, strategies: &[StressStrategy], config: &StressConfig) -> StressReport {
+    let start = Utc::now();
+    let clock = Arc::new(SimulatedClock::new(start));
+    let mut risk_manager = RiskManager::with_config(money::from_f64(config.capital), config.risk.clone());
+    risk_manager.set_clock(clock.clone());
+    let mut profiles = LiquidityProfiles::default();
+    for strategy in strategies {
+        profiles.insert(LiquidityProfile::flat(&strategy.symbol));
+    }
+    risk_manager.set_liquidity_profiles(profiles);
+
+    // Open what risk would approve today, everything entered at a price of 1
+    let mut cash = config.capital;
+    let mut book: HashMap<String, Held> = HashMap::new();
+    for (i, strategy) in strategies.iter().enumerate() {
+        let size = risk_manager.calculate_position_size(&strategy.pattern, money::from_f64(cash));
+        if size <= Decimal::ZERO || risk_manager.evaluate_order(&strategy.pattern.hash, &strategy.symbol, STRESS_VENUE, size, &[]).is_err() {
+            continue;
+        }
+        let stop = 1.0 - config.stop_loss_pct;
+        let position_id = format!("stress-{}", i);
+        risk_manager.record_position_opened(&position_id, Position {
+            pattern_hash: strategy.pattern.hash.clone(),
+            symbol: strategy.symbol.clone(),
+            exchange: STRESS_VENUE.to_string(),
+            size,
+            entry_price: Decimal::ONE,
+            entry_time: start,
+            stop_loss: money::from_f64(stop),
+            take_profit: money::from_f64(1.0 + 2.0 * config.stop_loss_pct),
+        });
+        let units = money::to_f64(size);
+        cash -= units * (1.0 + config.taker_rate);
+        book.insert(position_id.clone(), Held { position_id, units, stop });
+    }
+    let positions = book.len();
+    let exposure: f64 = book.values().map(|held| held.units).sum();
+
+    let mut stops_hit = 0;
+    let mut worst = cash + exposure;
+    for step in scenario.path() {
+        clock.advance(Duration::minutes(1));
+        let fee = config.taker_rate * step.fee_multiplier;
+        let exit = |held: Held, cash: &mut f64| {
+            *cash += held.units * step.price * (1.0 - fee);
+            risk_manager.record_position_closed(&held.position_id);
+        };
+
+        // Stops fill at this minute's price, however far past the stop it is
+        if step.venue_up {
+            let stopped: Vec<String> = book.values().filter(|held| step.price <= held.stop).map(|held| held.position_id.clone()).collect();
+            stops_hit += stopped.len();
+            for id in stopped {
+                exit(book.remove(&id).unwrap(), &mut cash);
+            }
+        }
+
+        let equity = cash + book.values().map(|held| held.units * step.price).sum::<f64>();
+        worst = worst.min(equity);
+        risk_manager.update_capital(money::from_f64(equity));
+        risk_manager.check_risk_limits();
+
+        // Flattening waits for the venue to answer
+        if risk_manager.is_emergency_stopped() && step.venue_up {
+            for (_, held) in book.drain() {
+                exit(held, &mut cash);
+            }
+        }
+    }
+
+    let last = scenario.path().last().map_or(1.0, |step| step.price);
+    let final_equity = cash + book.values().map(|held| held.units * last).sum::<f64>();
+    let worst_loss_pct = ((config.capital - worst) / config.capital).max(0.0);
+    StressReport {
+        scenario,
+        positions,
+        exposure,
+        stops_hit,
+        emergency_stop: risk_manager.is_emergency_stopped(),
+        worst_loss_pct,
+        final_loss: (config.capital - final_equity).max(0.0),
+        limit_pct: config.risk.max_daily_drawdown_pct,
+        contained: worst_loss_pct <= config.risk.max_daily_drawdown_pct,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strategies(count: usize) -> Vec<StressStrategy> {
+        (0..count).map(|i| StressStrategy {
+            pattern: Pattern { hash: format!("p{}", i), win_rate: 0.65, avg_win_amount: 2.0, avg_loss_amount: -1.0, sharpe_ratio: 2.0 },
+            symbol: format!("SYM{}-USD", i),
+        }).collect()
+    }
+
+    #[test]
+    fn test_scenarios_against_default_limits() {
+        let config = StressConfig { capital: 1000.0, risk: RiskConfig::default(), stop_loss_pct: 0.05, taker_rate: 0.006 };
+        let book = strategies(4);
+
+        // Stops cap a flash crash near their distance plus the minute's slippage
+        let crash = run(Scenario::FlashCrash, &book, &config);
+        assert!(crash.positions > 0 && crash.exposure <= 600.0 + 1e-9, "{:?}", crash);
+        assert_eq!(crash.stops_hit, crash.positions);
+        assert!(crash.contained && !crash.emergency_stop, "{:?}", crash);
+
+        // A gap fills every stop at half price; an outage lets the slide run past the stops
+        let gap = run(Scenario::GapDown, &book, &config);
+        let outage = run(Scenario::ExchangeOutage, &book, &config);
+        assert!(gap.worst_loss_pct > crash.worst_loss_pct);
+        assert!(outage.worst_loss_pct > crash.worst_loss_pct);
+        assert!(gap.final_loss > 0.0 && gap.stops_hit == gap.positions);
+
+        // Tighter limits trip the drawdown stop instead
+        let tight = StressConfig { risk: RiskConfig { max_daily_drawdown_pct: 0.05, ..RiskConfig::default() }, ..config.clone() };
+        let gap = run(Scenario::GapDown, &book, &tight);
+        assert!(gap.emergency_stop && !gap.contained);
+
+        assert_eq!(Scenario::parse("fee-spike"), Some(Scenario::FeeSpike));
+        assert_eq!(run(Scenario::FeeSpike, &[], &config).positions, 0);
+    }
+}
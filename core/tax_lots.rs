@@ -0,0 +1,329 @@
+// Tax Lots - FIFO/LIFO Cost Basis and a Form 8949 Year-End Report
+// Hundreds of micro-trades a day each open and close lots of the traded asset. Every buy fill
+// is a lot with its own cost basis (fees included); every sell fill consumes lots of that asset
+// oldest-first (FIFO) or newest-first (LIFO), and each consumed slice becomes one disposal with
+// its proceeds, basis, gain and holding term. The year's disposals export as a CSV laid out
+// like Form 8949, split into short-term and long-term. Sells with no lot left to match
+// (history from before the fills table) are reported with zero basis rather than dropped
+
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use chrono::{DateTime, Datelike, Months, TimeZone, Utc};
+use serde::{Serialize, Deserialize};
+use tracing::warn;
+
+use crate::core::exchange::split_symbol;
+use crate::core::journal::JournalRow;
+use crate::core::money::{self, Decimal};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LotMethod {
+    Fifo,
+    Lifo,
+}
+
+impl LotMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LotMethod::Fifo => "fifo",
+            LotMethod::Lifo => "lifo",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "fifo" => Some(LotMethod::Fifo),
+            "lifo" => Some(LotMethod::Lifo),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Term {
+    Short,  // held one year or less
+    Long,
+}
+
+impl Term {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Term::Short => "short",
+            Term::Long => "long",
+        }
+    }
+
+    /// Long-term once held more than one year, counted from the day after acquisition
+    pub fn held(acquired_at: DateTime<Utc>, disposed_at: DateTime<Utc>) -> Self {
+        match acquired_at.date_naive().checked_add_months(Months::new(12)) {
+            Some(anniversary) if disposed_at.date_naive() > anniversary => Term::Long,
+            _ => Term::Short,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lot {
+    pub acquired_at: DateTime<Utc>,
+    pub units: f64,
+    pub cost_basis: f64,  // USD for the remaining units, buy fees included
+    pub order_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Disposal {
+    pub asset: String,
+    pub units: f64,
+    pub acquired_at: Option<DateTime<Utc>>,  // None when no lot was left to match
+    pub disposed_at: DateTime<Utc>,
+    pub proceeds: f64,    // USD, sell fees deducted
+    pub cost_basis: f64,
+    pub gain: f64,
+    pub term: Term,
+    pub order_id: String,
+}
+
+/// Open lots per asset (across every venue), consumed in the configured order
+pub struct LotBook {
+    method: LotMethod,
+    lots: HashMap<String, VecDeque<Lot>>,
+}
+
+impl LotBook {
+    pub fn new(method: LotMethod) -> Self {
+        LotBook { method, lots: HashMap::new() }
+    }
+
+    pub fn acquire(&mut self, asset: &str, lot: Lot) {
+        if lot.units > 0.0 {
+            self.lots.entry(asset.to_string()).or_default().push_back(lot);
+        }
+    }
+
+    /// Sell `units` of `asset`; one disposal per lot (or part of a lot) consumed
+    pub fn dispose(&mut self, asset: &str, units: f64, proceeds: f64, at: DateTime<Utc>, order_id: &str) -> Vec<Disposal> {
+        let mut disposals = Vec::new();
+        if units <= 0.0 {
+            return disposals;
+        }
+        let lots = self.lots.entry(asset.to_string()).or_default();
+        let mut remaining = units;
+
+        while remaining > 1e-12 {
+            let next = match self.method {
+                LotMethod::Fifo => lots.front_mut(),
+                LotMethod::Lifo => lots.back_mut(),
+            };
+            let Some(lot) = next else { break };
+
+            let taken = remaining.min(lot.units);
+            let basis = lot.cost_basis * taken / lot.units;
+            let share = proceeds * taken / units;
+            disposals.push(Disposal {
+                asset: asset.to_string(),
+                units: taken,
+                acquired_at: Some(lot.acquired_at),
+                disposed_at: at,
+                proceeds: share,
+                cost_basis: basis,
+                gain: share - basis,
+                term: Term::held(lot.acquired_at, at),
+                order_id: order_id.to_string(),
+            });
+
+            lot.units -= taken;
+            lot.cost_basis -= basis;
+            remaining -= taken;
+            if lot.units <= 1e-12 {
+                match self.method {
+                    LotMethod::Fifo => lots.pop_front(),
+                    LotMethod::Lifo => lots.pop_back(),
+                };
+            }
+        }
+
+        if remaining > 1e-12 {
+            warn!("⚠️ Sold {:.8} {} more than any open lot holds ({}); reported with zero basis", remaining, asset, order_id);
+            let share = proceeds * remaining / units;
+            disposals.push(Disposal {
+                asset: asset.to_string(),
+                units: remaining,
+                acquired_at: None,
+                disposed_at: at,
+                proceeds: share,
+                cost_basis: 0.0,
+                gain: share,
+                term: Term::Short,
+                order_id: order_id.to_string(),
+            });
+        }
+        disposals
+    }
+
+    pub fn open_lots<'a>(&'a self, asset: &str) -> impl Iterator<Item = &'a Lot> {
+        self.lots.get(asset).into_iter().flatten()
+    }
+
+    pub fn open_lot_count(&self) -> usize {
+        self.lots.values().map(VecDeque::len).sum()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxReport {
+    pub year: i32,
+    pub method: LotMethod,
+    pub disposals: Vec<Disposal>,
+    pub open_lots: usize,     // still held at year end
+    pub skipped_fills: usize, // no side or price to build a lot from
+}
+
+impl TaxReport {
+    /// Run every fill through the book up to the end of `year`; disposals from earlier years
+    /// only shape which lots are left
+    pub fn build(rows: &[JournalRow], year: i32, method: LotMethod) -> Self {
+        let mut book = LotBook::new(method);
+        let mut disposals = Vec::new();
+        let mut skipped_fills = 0;
+
+        for row in rows.iter().filter(|row| row.filled_at.year() <= year) {
+            if row.price <= Decimal::ZERO {
+                skipped_fills += 1;
+                continue;
+            }
+            let (asset, _) = split_symbol(&row.symbol);
+            let units = money::ratio(row.size, row.price);
+            let (size, fees) = (money::to_f64(row.size), money::to_f64(row.fees));
+            match row.side.as_deref() {
+                Some("buy") => book.acquire(asset, Lot {
+                    acquired_at: row.filled_at,
+                    units,
+                    cost_basis: size + fees,
+                    order_id: row.client_order_id.clone(),
+                }),
+                Some("sell") => {
+                    let sold = book.dispose(asset, units, size - fees, row.filled_at, &row.client_order_id);
+                    if row.filled_at.year() == year {
+                        disposals.extend(sold);
+                    }
+                }
+                _ => skipped_fills += 1,
+            }
+        }
+
+        TaxReport { year, method, disposals, open_lots: book.open_lot_count(), skipped_fills }
+    }
+
+    pub fn gain(&self, term: Term) -> f64 {
+        self.disposals.iter().filter(|d| d.term == term).map(|d| d.gain).sum()
+    }
+
+    /// The window of fills `build` needs: everything before the next new year
+    pub fn until(year: i32) -> Option<DateTime<Utc>> {
+        Utc.with_ymd_and_hms(year + 1, 1, 1, 0, 0, 0).single()
+    }
+}
+
+/// Form 8949 columns (a) through (h), short-term rows before long-term, each by sale date
+pub fn write_8949_csv<W: Write>(report: &TaxReport, out: W) -> Result<(), csv::Error> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record([
+        "term", "description", "date_acquired", "date_sold", "proceeds", "cost_basis",
+        "adjustment_code", "adjustment_amount", "gain_or_loss", "sale_order_id",
+    ])?;
+
+    let date = |at: DateTime<Utc>| at.format("%m/%d/%Y").to_string();
+    let usd = |amount: f64| format!("{:.2}", amount);
+    for term in [Term::Short, Term::Long] {
+        for d in report.disposals.iter().filter(|d| d.term == term) {
+            writer.write_record([
+                term.as_str().to_string(),
+                format!("{:.8} {}", d.units, d.asset),
+                d.acquired_at.map(date).unwrap_or_default(),
+                date(d.disposed_at),
+                usd(d.proceeds),
+                usd(d.cost_basis),
+                String::new(),
+                String::new(),
+                usd(d.gain),
+                d.order_id.clone(),
+            ])?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(day: &str, side: &str, size: f64, price: f64) -> JournalRow {
+        JournalRow {
+            filled_at: DateTime::parse_from_rfc3339(&format!("{}T12:00:00Z", day)).unwrap().with_timezone(&Utc),
+            client_order_id: format!("{}-{}", side, day),
+            position_id: "p1".to_string(),
+            pattern_hash: Some("abc123".to_string()),
+            symbol: "DOGE-USD".to_string(),
+            exchange: "coinbase".to_string(),
+            side: Some(side.to_string()),
+            kind: if side == "buy" { "entry" } else { "exit" }.to_string(),
+            size: money::from_f64(size),
+            price: money::from_f64(price),
+            fees: Decimal::ZERO,
+            pnl: None,
+        }
+    }
+
+    #[test]
+    fn test_fifo_and_lifo_match_different_lots() {
+        // 100 DOGE at $0.10 in 2023, 100 at $0.20 in 2024, then 100 sold at $0.30 in 2024
+        let rows = vec![
+            fill("2023-01-10", "buy", 10.0, 0.10),
+            fill("2024-06-01", "buy", 20.0, 0.20),
+            fill("2024-06-15", "sell", 30.0, 0.30),
+        ];
+
+        let fifo = TaxReport::build(&rows, 2024, LotMethod::Fifo);
+        assert_eq!(fifo.disposals.len(), 1);
+        assert!((fifo.disposals[0].cost_basis - 10.0).abs() < 1e-9);
+        assert!((fifo.gain(Term::Long) - 20.0).abs() < 1e-9);
+        assert_eq!(fifo.open_lots, 1);
+
+        let lifo = TaxReport::build(&rows, 2024, LotMethod::Lifo);
+        assert!((lifo.disposals[0].cost_basis - 20.0).abs() < 1e-9);
+        assert!((lifo.gain(Term::Short) - 10.0).abs() < 1e-9);
+        assert_eq!(lifo.gain(Term::Long), 0.0);
+
+        // Nothing was sold in 2023, and fills after the year are ignored
+        assert!(TaxReport::build(&rows, 2023, LotMethod::Fifo).disposals.is_empty());
+    }
+
+    #[test]
+    fn test_sale_spanning_lots_splits_and_unmatched_units_keep_their_proceeds() {
+        let mut book = LotBook::new(LotMethod::Fifo);
+        let at = |day: u32| Utc.with_ymd_and_hms(2024, 3, day, 0, 0, 0).unwrap();
+        book.acquire("DOGE", Lot { acquired_at: at(1), units: 50.0, cost_basis: 5.0, order_id: "b1".into() });
+        book.acquire("DOGE", Lot { acquired_at: at(2), units: 50.0, cost_basis: 10.0, order_id: "b2".into() });
+
+        let sold = book.dispose("DOGE", 75.0, 30.0, at(3), "s1");
+        assert_eq!(sold.len(), 2);
+        assert!((sold[0].proceeds - 20.0).abs() < 1e-9 && (sold[0].cost_basis - 5.0).abs() < 1e-9);
+        assert!((sold[1].proceeds - 10.0).abs() < 1e-9 && (sold[1].cost_basis - 5.0).abs() < 1e-9);
+        assert!((book.open_lots("DOGE").next().unwrap().units - 25.0).abs() < 1e-9);
+
+        let oversold = book.dispose("DOGE", 50.0, 20.0, at(4), "s2");
+        assert_eq!(oversold.len(), 2);
+        assert_eq!(oversold[1].acquired_at, None);
+        assert!((oversold[1].gain - 10.0).abs() < 1e-9);
+        assert_eq!(book.open_lot_count(), 0);
+
+        let report = TaxReport { year: 2024, method: LotMethod::Fifo, disposals: sold, open_lots: 0, skipped_fills: 0 };
+        let mut csv = Vec::new();
+        write_8949_csv(&report, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().nth(1).unwrap(), "short,50.00000000 DOGE,03/01/2024,03/03/2024,20.00,5.00,,,15.00,s1");
+        assert_eq!(Term::held(at(1), Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap()), Term::Short);
+        assert_eq!(Term::held(at(1), Utc.with_ymd_and_hms(2025, 3, 2, 0, 0, 0).unwrap()), Term::Long);
+    }
+}
@@ -0,0 +1,165 @@
+// Telemetry - Structured Logging and OTLP Export
+// One tracing subscriber for every binary. RUST_LOG filters as before; LOG_FORMAT=json emits
+// one JSON object per event, with the fields of every enclosing span (hypothesis hash,
+// order, position) attached, for log aggregation. Either way known secrets are masked before a
+// line is written. Built with the `otel` feature and given
+// OTEL_EXPORTER_OTLP_ENDPOINT, spans and pipeline latency metrics also go out over OTLP
+
+use serde::{Serialize, Deserialize};
+use crate::core::secrets::Redacted;
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogFormat {
+    Pretty,
+    Json,
+}
+
+impl LogFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogFormat::Pretty => "pretty",
+            LogFormat::Json => "json",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pretty" | "text" => Some(LogFormat::Pretty),
+            "json" => Some(LogFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// LOG_FORMAT, defaulting to human-readable lines
+    pub fn from_env() -> Self {
+        std::env::var("LOG_FORMAT")
+            .ok()
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or(LogFormat::Pretty)
+    }
+}
+
+/// Keeps the OTLP pipeline alive; dropping it flushes spans and metrics still buffered
+#[must_use = "dropping the guard shuts OTLP export down"]
+#[derive(Default)]
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    providers: Option<otel::Providers>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(providers) = self.providers.take() {
+            providers.shutdown();
+        }
+    }
+}
+
+/// Install the global subscriber; RUST_LOG defaults to `info`
+pub fn init() -> TelemetryGuard {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+    let fmt_layer = match LogFormat::from_env() {
+        LogFormat::Pretty => fmt::layer().with_writer(|| Redacted(std::io::stdout())).boxed(),
+        LogFormat::Json => fmt::layer().json().with_current_span(true).with_span_list(true).with_writer(|| Redacted(std::io::stdout())).boxed(),
+    };
+
+    #[cfg(feature = "otel")]
+    let providers = otel::endpoint().and_then(|endpoint| match otel::Providers::new(&endpoint) {
+        Ok(providers) => Some(providers),
+        Err(e) => {
+            eprintln!("⚠️ OTLP export to {} disabled: {}", endpoint, e);
+            None
+        }
+    });
+    #[cfg(feature = "otel")]
+    let otel_layer = providers.as_ref().map(|providers| providers.layer());
+    #[cfg(not(feature = "otel"))]
+    let otel_layer: Option<fmt::Layer<_>> = None;
+
+    let result = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer)
+        .with(otel_layer)
+        .try_init();
+    if let Err(e) = result {
+        eprintln!("⚠️ Tracing subscriber already installed: {}", e);
+    }
+
+    #[cfg(feature = "otel")]
+    if providers.is_some() {
+        tracing::info!("🔭 Exporting traces and metrics over OTLP to {}", otel::endpoint().unwrap_or_default());
+    }
+    #[cfg(not(feature = "otel"))]
+    if std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").is_ok() {
+        tracing::warn!("⚠️ OTEL_EXPORTER_OTLP_ENDPOINT is set but this build has no `otel` feature - not exporting");
+    }
+
+    TelemetryGuard {
+        #[cfg(feature = "otel")]
+        providers,
+    }
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use opentelemetry::{global, trace::TracerProvider};
+    use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::{metrics::SdkMeterProvider, trace::SdkTracerProvider, Resource};
+    use tracing_subscriber::{registry::LookupSpan, Layer};
+
+    /// The collector's gRPC endpoint; unset means no export even in an `otel` build
+    pub fn endpoint() -> Option<String> {
+        std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok().filter(|e| !e.trim().is_empty())
+    }
+
+    pub struct Providers {
+        tracer: SdkTracerProvider,
+        meter: SdkMeterProvider,
+    }
+
+    impl Providers {
+        /// Batched span export and a periodic metric reader, both over gRPC; the meter becomes
+        /// the global one that core::latency records into
+        pub fn new(endpoint: &str) -> Result<Self, Box<dyn std::error::Error>> {
+            // OTEL_SERVICE_NAME still wins when set
+            let resource = match std::env::var("OTEL_SERVICE_NAME") {
+                Ok(_) => Resource::builder().build(),
+                Err(_) => Resource::builder().with_service_name("v26meme").build(),
+            };
+
+            let spans = SpanExporter::builder().with_tonic().with_endpoint(endpoint).build()?;
+            let tracer = SdkTracerProvider::builder()
+                .with_batch_exporter(spans)
+                .with_resource(resource.clone())
+                .build();
+
+            let metrics = MetricExporter::builder().with_tonic().with_endpoint(endpoint).build()?;
+            let meter = SdkMeterProvider::builder()
+                .with_periodic_exporter(metrics)
+                .with_resource(resource)
+                .build();
+            global::set_meter_provider(meter.clone());
+
+            Ok(Providers { tracer, meter })
+        }
+
+        pub fn layer<S>(&self) -> impl Layer<S>
+        where
+            S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+        {
+            tracing_opentelemetry::layer().with_tracer(self.tracer.tracer("v26meme"))
+        }
+
+        pub fn shutdown(self) {
+            if let Err(e) = self.tracer.shutdown() {
+                eprintln!("⚠️ OTLP span flush failed: {}", e);
+            }
+            if let Err(e) = self.meter.shutdown() {
+                eprintln!("⚠️ OTLP metric flush failed: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+// Test Budget
+// How much capital each discovery test trade commits and how many hypotheses are tested per
+// hour. Both grow with equity: $5 tests at a few hundred dollars, larger tests and a faster
+// search once the account can absorb them. The evolution task owns the policy and rebalances
+// the shared budget; the discovery loop picks it up before every test
+
+use std::sync::{Arc, Mutex};
+use rust_decimal_macros::dec;
+use serde::{Serialize, Deserialize};
+
+use crate::core::money::{self, Decimal, MONEY_DP};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TestBudget {
+    pub test_capital: Decimal,
+    pub hypotheses_per_hour: u32,
+}
+
+impl Default for TestBudget {
+    fn default() -> Self {
+        TestBudget {
+            test_capital: dec!(5),
+            hypotheses_per_hour: 50,
+        }
+    }
+}
+
+/// Written by the evolution task, read by the discovery loop
+pub type SharedTestBudget = Arc<Mutex<TestBudget>>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BudgetPolicy {
+    pub min_test_capital: Decimal,
+    pub max_test_capital: Decimal,
+    pub test_capital_pct: f64,         // of equity, per test
+    pub hourly_budget_pct: f64,        // of equity, cycled through tests each hour
+    pub min_hypotheses_per_hour: u32,
+    pub max_hypotheses_per_hour: u32,
+}
+
+impl Default for BudgetPolicy {
+    fn default() -> Self {
+        BudgetPolicy {
+            min_test_capital: dec!(5),
+            max_test_capital: dec!(250),
+            test_capital_pct: 0.0025,
+            hourly_budget_pct: 0.25,
+            min_hypotheses_per_hour: 50,
+            max_hypotheses_per_hour: 200,
+        }
+    }
+}
+
+impl BudgetPolicy {
+    pub fn budget_for(&self, equity: Decimal) -> TestBudget {
+        let equity = equity.max(Decimal::ZERO);
+        let test_capital = money::share(equity, self.test_capital_pct)
+            .round_dp(MONEY_DP)
+            .clamp(self.min_test_capital, self.max_test_capital);
+        let affordable = money::ratio(money::share(equity, self.hourly_budget_pct), test_capital).floor() as u32;
+
+        TestBudget {
+            test_capital,
+            hypotheses_per_hour: affordable.clamp(self.min_hypotheses_per_hour, self.max_hypotheses_per_hour),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_scales_with_equity_within_caps() {
+        let policy = BudgetPolicy::default();
+
+        // Small accounts keep the original $5 / 50 per hour
+        assert_eq!(policy.budget_for(dec!(200)), TestBudget::default());
+        assert_eq!(policy.budget_for(Decimal::ZERO), TestBudget::default());
+
+        let mid = policy.budget_for(dec!(20000));
+        assert_eq!(mid.test_capital, dec!(50));
+        assert_eq!(mid.hypotheses_per_hour, 100);
+
+        let large = policy.budget_for(dec!(10000000));
+        assert_eq!(large.test_capital, dec!(250));
+        assert_eq!(large.hypotheses_per_hour, 200);
+    }
+}
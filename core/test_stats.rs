@@ -0,0 +1,192 @@
+// Test Stats - Running Aggregates of Every Hypothesis's Test Trades
+// Validation needs a hypothesis's test count, win rate, profit and Sharpe after every test.
+// Re-reading all of its test_results rows each time grew with the history; instead each result
+// is folded into a count, wins and the sum and sum of squares of its return, which give the
+// same figures in O(1). The aggregates are kept in memory for the hypotheses discovery is
+// testing, and in test_result_stats, so a restart or another discovery process picks up where
+// this one stopped with a single row read
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+
+use crate::core::chaos::FaultInjector;
+use crate::core::discovery_engine::TestResult;
+use crate::core::error::{Error, Result};
+use crate::core::money::{self, Decimal};
+
+/// Hypotheses kept in memory; past this the cache starts over and reloads from the table
+pub const CACHE_LIMIT: usize = 100_000;
+
+// One round trip per test: the result's contribution added to the stored totals
+const STATS_UPSERT: &str = "
+    INSERT INTO test_result_stats (pattern_hash, tests, wins, profit, return_sum, return_sq_sum, updated_at)
+    VALUES ($1, 1, $2, $3, $4, $5, NOW())
+    ON CONFLICT (pattern_hash) DO UPDATE SET
+        tests = test_result_stats.tests + 1,
+        wins = test_result_stats.wins + EXCLUDED.wins,
+        profit = test_result_stats.profit + EXCLUDED.profit,
+        return_sum = test_result_stats.return_sum + EXCLUDED.return_sum,
+        return_sq_sum = test_result_stats.return_sq_sum + EXCLUDED.return_sq_sum,
+        updated_at = NOW()
+";
+
+const STATS_SELECT: &str = "
+    SELECT tests, wins, profit, return_sum, return_sq_sum FROM test_result_stats WHERE pattern_hash = $1
+";
+
+/// Count, wins, profit and the first two moments of per-test returns on committed capital
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TestStats {
+    pub tests: u32,
+    pub wins: u32,
+    pub profit: Decimal,
+    pub return_sum: f64,
+    pub return_sq_sum: f64,
+}
+
+impl TestStats {
+    pub fn from_results(results: &[TestResult]) -> Self {
+        let mut stats = TestStats::default();
+        for result in results {
+            stats.add(result);
+        }
+        stats
+    }
+
+    pub fn add(&mut self, result: &TestResult) {
+        let r = money::ratio(result.profit, result.capital);
+        self.tests += 1;
+        self.wins += result.profitable as u32;
+        self.profit += result.profit;
+        self.return_sum += r;
+        self.return_sq_sum += r * r;
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.tests == 0 { 0.0 } else { self.wins as f64 / self.tests as f64 }
+    }
+
+    /// The annualized Sharpe `test_sharpe` computes from the full history
+    pub fn sharpe(&self) -> f64 {
+        if self.tests == 0 {
+            return 0.0;
+        }
+        let n = self.tests as f64;
+        let mean = self.return_sum / n;
+        let std_dev = (self.return_sq_sum / n - mean * mean).max(0.0).sqrt();
+
+        // Identical returns leave rounding noise rather than an exact zero
+        if std_dev < 1e-12 {
+            return 0.0;
+        }
+        (mean / std_dev) * (252.0_f64).sqrt()
+    }
+}
+
+/// The in-memory aggregates, shared by discovery's test workers, in front of test_result_stats
+#[derive(Clone)]
+pub struct TestStatsStore {
+    cache: Arc<Mutex<HashMap<String, TestStats>>>,
+    pub chaos: FaultInjector,
+    db_pool: PgPool,
+}
+
+impl TestStatsStore {
+    pub fn new(db_pool: PgPool) -> Self {
+        TestStatsStore { cache: Arc::new(Mutex::new(HashMap::new())), chaos: FaultInjector::default(), db_pool }
+    }
+
+    /// Fold a new result into `pattern_hash`'s aggregates; returns them with it included. When
+    /// the table can't be read or written the aggregates returned still count the result, but
+    /// one that couldn't be read isn't cached, so the next test reads it again
+    pub async fn record(&self, pattern_hash: &str, result: &TestResult) -> (TestStats, Result<()>) {
+        let cached = self.cache.lock().unwrap().get(pattern_hash).copied();
+        let loaded = match cached {
+            Some(stats) => Ok(stats),
+            None => self.load(pattern_hash).await.map(Option::unwrap_or_default),
+        };
+
+        let r = money::ratio(result.profit, result.capital);
+        let stored = self.chaos.db(sqlx::query(STATS_UPSERT)
+            .persistent(true)
+            .bind(pattern_hash)
+            .bind(result.profitable as i32)
+            .bind(result.profit)
+            .bind(r)
+            .bind(r * r)
+            .execute(&self.db_pool))
+            .await
+            .map(|_| ())
+            .map_err(Error::from);
+
+        let Ok(loaded) = loaded else {
+            let mut stats = TestStats::default();
+            stats.add(result);
+            return (stats, loaded.map(|_| ()));
+        };
+
+        let mut cache = self.cache.lock().unwrap();
+        if cache.len() >= CACHE_LIMIT && !cache.contains_key(pattern_hash) {
+            cache.clear();
+        }
+        // Another worker may have filled the entry while this one read the table
+        let stats = cache.entry(pattern_hash.to_string()).or_insert(loaded);
+        stats.add(result);
+        (*stats, stored)
+    }
+
+    /// The stored aggregates, or None before a hypothesis's first test
+    pub async fn load(&self, pattern_hash: &str) -> Result<Option<TestStats>> {
+        let row = sqlx::query(STATS_SELECT)
+            .persistent(true)
+            .bind(pattern_hash)
+            .fetch_optional(&self.db_pool)
+            .await?;
+
+        Ok(row.map(|row| TestStats {
+            tests: row.get::<i32, _>("tests") as u32,
+            wins: row.get::<i32, _>("wins") as u32,
+            profit: row.get("profit"),
+            return_sum: row.get("return_sum"),
+            return_sq_sum: row.get("return_sq_sum"),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::discovery_engine::test_sharpe;
+    use rust_decimal_macros::dec;
+
+    fn result(profit: Decimal, capital: Decimal) -> TestResult {
+        TestResult {
+            capital,
+            profitable: profit > Decimal::ZERO,
+            profit,
+            entry_price: dec!(100),
+            exit_price: dec!(100),
+            duration_seconds: 60,
+            fees: dec!(0.01),
+            slippage: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_running_aggregates_match_a_full_recount() {
+        let results: Vec<TestResult> = (0..250)
+            .map(|i| result(Decimal::new((i * 37) % 17, 1) - dec!(0.7), if i % 3 == 0 { dec!(5) } else { dec!(12.5) }))
+            .collect();
+        let stats = TestStats::from_results(&results);
+
+        assert_eq!(stats.tests, 250);
+        assert_eq!(stats.wins as usize, results.iter().filter(|r| r.profitable).count());
+        assert_eq!(stats.profit, results.iter().map(|r| r.profit).sum::<Decimal>());
+        assert!((stats.sharpe() - test_sharpe(&results)).abs() < 1e-9, "{} vs {}", stats.sharpe(), test_sharpe(&results));
+
+        assert_eq!(TestStats::default().sharpe(), 0.0);
+        assert_eq!(TestStats::from_results(&[result(dec!(0.3), dec!(5)), result(dec!(0.3), dec!(5))]).sharpe(), 0.0);
+    }
+}
@@ -0,0 +1,117 @@
+// Runtime Tuning - Discovery and Evolution Knobs Without a Restart
+// The search rate, the activation bar and how often evolution runs can be adjusted through
+// the control API while the system trades. Every knob has hard-coded bounds, so a typo can
+// slow the search or make activation stricter but can never activate patterns on a handful
+// of coin flips or run evolution continuously. The discovery loop and the evolution task
+// pick changes up on their next iteration
+
+use std::sync::{Arc, Mutex};
+use serde::{Serialize, Deserialize};
+
+pub const HYPOTHESES_PER_HOUR_BOUNDS: (u32, u32) = (1, 200);
+pub const MIN_WIN_RATE_BOUNDS: (f64, f64) = (0.50, 0.80);
+pub const MIN_TESTS_REQUIRED_BOUNDS: (u32, u32) = (30, 1000);
+pub const EVOLUTION_INTERVAL_HOURS_BOUNDS: (u32, u32) = (1, 168);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Tuning {
+    pub hypotheses_per_hour: Option<u32>,  // None follows the equity-scaled test budget
+    pub min_win_rate: f64,
+    pub min_tests_required: u32,
+    pub evolution_interval_hours: u32,      // the regular cycle
+    pub evolution_min_interval_hours: u32,  // floor between early (regime change) cycles
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Tuning {
+            hypotheses_per_hour: None,
+            min_win_rate: 0.55,
+            min_tests_required: 100,
+            evolution_interval_hours: 24,
+            evolution_min_interval_hours: 4,
+        }
+    }
+}
+
+/// Runtime change; unset fields keep their current value
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TuningUpdate {
+    pub hypotheses_per_hour: Option<u32>,  // 0 returns to the equity-scaled budget
+    pub min_win_rate: Option<f64>,
+    pub min_tests_required: Option<u32>,
+    pub evolution_interval_hours: Option<u32>,
+    pub evolution_min_interval_hours: Option<u32>,
+}
+
+impl Tuning {
+    /// The tuning after `update`, or every bound it breaks
+    pub fn with_update(&self, update: &TuningUpdate) -> Result<Tuning, Vec<String>> {
+        fn bounded<T: PartialOrd + Copy + std::fmt::Display>(errors: &mut Vec<String>, name: &str, value: Option<T>, (min, max): (T, T), target: &mut T) {
+            match value {
+                Some(v) if !(v >= min && v <= max) => errors.push(format!("{} must be within {}-{}, got {}", name, min, max, v)),
+                Some(v) => *target = v,
+                None => {}
+            }
+        }
+
+        let mut next = *self;
+        let mut errors = Vec::new();
+        if let Some(n) = update.hypotheses_per_hour {
+            let mut rate = n;
+            bounded(&mut errors, "hypotheses_per_hour", (n > 0).then_some(n), HYPOTHESES_PER_HOUR_BOUNDS, &mut rate);
+            next.hypotheses_per_hour = (n > 0).then_some(rate);
+        }
+        bounded(&mut errors, "min_win_rate", update.min_win_rate, MIN_WIN_RATE_BOUNDS, &mut next.min_win_rate);
+        bounded(&mut errors, "min_tests_required", update.min_tests_required, MIN_TESTS_REQUIRED_BOUNDS, &mut next.min_tests_required);
+        bounded(&mut errors, "evolution_interval_hours", update.evolution_interval_hours,
+                EVOLUTION_INTERVAL_HOURS_BOUNDS, &mut next.evolution_interval_hours);
+        bounded(&mut errors, "evolution_min_interval_hours", update.evolution_min_interval_hours,
+                EVOLUTION_INTERVAL_HOURS_BOUNDS, &mut next.evolution_min_interval_hours);
+
+        if next.evolution_min_interval_hours > next.evolution_interval_hours {
+            errors.push(format!("evolution_min_interval_hours {} exceeds evolution_interval_hours {}",
+                                next.evolution_min_interval_hours, next.evolution_interval_hours));
+        }
+        if errors.is_empty() { Ok(next) } else { Err(errors) }
+    }
+}
+
+/// Written by the control API, read by the discovery loop and the evolution task
+pub type SharedTuning = Arc<Mutex<Tuning>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_updates_are_bounded_and_all_or_nothing() {
+        let tuning = Tuning::default();
+
+        let next = tuning.with_update(&TuningUpdate {
+            hypotheses_per_hour: Some(120),
+            min_win_rate: Some(0.6),
+            evolution_interval_hours: Some(12),
+            ..TuningUpdate::default()
+        }).unwrap();
+        assert_eq!(next.hypotheses_per_hour, Some(120));
+        assert_eq!(next.min_win_rate, 0.6);
+        assert_eq!(next.min_tests_required, 100);
+
+        // 0 hands the rate back to the test budget
+        let back = next.with_update(&TuningUpdate { hypotheses_per_hour: Some(0), ..TuningUpdate::default() }).unwrap();
+        assert_eq!(back.hypotheses_per_hour, None);
+
+        // One bad field rejects the whole update, and every violation is reported
+        let errors = tuning.with_update(&TuningUpdate {
+            min_win_rate: Some(0.3),
+            min_tests_required: Some(5),
+            evolution_min_interval_hours: Some(48),
+            ..TuningUpdate::default()
+        }).unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].contains("min_win_rate must be within 0.5-0.8"));
+        assert!(tuning.with_update(&TuningUpdate { min_win_rate: Some(f64::NAN), ..TuningUpdate::default() }).is_err());
+    }
+}
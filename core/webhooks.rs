@@ -0,0 +1,182 @@
+// Webhooks - Lifecycle Events for Your Own Automation
+// Pattern activations and retirements, circuit breakers and the daily report are POSTed as
+// JSON to every URL in WEBHOOK_URLS, for Zapier, a chat bot or anything else that takes
+// HTTP. Each delivery is signed Stripe-style: `X-V26-Signature: t=<unix>,v1=<hex>` where
+// v1 is HMAC-SHA256 of "<t>.<body>" under WEBHOOK_SECRET, so receivers can reject forgeries
+// and replays. Deliveries retry briefly in the background and never block trading
+
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use sha2::Sha256;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::core::secrets;
+
+const ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    PatternActivated {
+        pattern_hash: String,
+        symbol: Option<String>,
+        win_rate: f64,
+        sharpe_ratio: f64,
+        test_count: u32,
+        generation: u32,
+    },
+    PatternRetired {
+        pattern_hash: String,
+        reason: String,  // "evolution_culled", or the operator's reason code
+    },
+    CircuitBreaker {
+        breaker: String,  // emergency_stop, loss_15min, loss_1hr, var_limit, reduce_only
+        tripped: bool,    // false when it clears or is re-armed
+        detail: Value,
+    },
+    DailyReport(Value),
+}
+
+impl WebhookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebhookEvent::PatternActivated { .. } => "pattern_activated",
+            WebhookEvent::PatternRetired { .. } => "pattern_retired",
+            WebhookEvent::CircuitBreaker { .. } => "circuit_breaker",
+            WebhookEvent::DailyReport(_) => "daily_report",
+        }
+    }
+}
+
+/// What each endpoint receives
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Envelope {
+    pub id: Uuid,  // the same across retries, for receivers to deduplicate on
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: WebhookEvent,
+}
+
+/// The X-V26-Signature header value for `body` sent at `timestamp`
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    let digest: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("t={},v1={}", timestamp, digest)
+}
+
+pub struct WebhookNotifier {
+    urls: Vec<String>,
+    secret: String,
+    events: Option<Vec<String>>,  // None sends every event
+    http: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(urls: Vec<String>, secret: String, events: Option<Vec<String>>) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        WebhookNotifier { urls, secret, events, http }
+    }
+
+    /// None unless both WEBHOOK_URLS and WEBHOOK_SECRET are set; unsigned webhooks are never sent
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| secrets::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let list = |value: String| -> Vec<String> {
+            value.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+        };
+
+        let urls = list(var("WEBHOOK_URLS")?);
+        let Some(secret) = var("WEBHOOK_SECRET") else {
+            warn!("⚠️ WEBHOOK_URLS set without WEBHOOK_SECRET - webhooks disabled");
+            return None;
+        };
+        info!("🪝 Webhooks to {} endpoints", urls.len());
+        Some(WebhookNotifier::new(urls, secret, var("WEBHOOK_EVENTS").map(list)))
+    }
+
+    pub fn wants(&self, event: &WebhookEvent) -> bool {
+        self.events.as_ref().is_none_or(|events| events.iter().any(|e| e == event.name()))
+    }
+
+    /// Deliver to every endpoint, retrying each a few times; returns how many accepted it
+    pub async fn deliver(&self, event: WebhookEvent) -> usize {
+        if !self.wants(&event) {
+            return 0;
+        }
+        let name = event.name();
+        let envelope = Envelope { id: Uuid::new_v4(), created_at: Utc::now(), event };
+        let Ok(body) = serde_json::to_string(&envelope) else {
+            return 0;
+        };
+
+        let mut delivered = 0;
+        for url in &self.urls {
+            for attempt in 1..=ATTEMPTS {
+                // Signed per attempt, so the timestamp stays fresh for receivers' replay windows
+                let signature = sign(&self.secret, Utc::now().timestamp(), &body);
+                let sent = self.http.post(url)
+                    .header("Content-Type", "application/json")
+                    .header("X-V26-Event", name)
+                    .header("X-V26-Delivery", envelope.id.to_string())
+                    .header("X-V26-Signature", signature)
+                    .body(body.clone())
+                    .send().await
+                    .and_then(|response| response.error_for_status());
+                match sent {
+                    Ok(_) => {
+                        delivered += 1;
+                        break;
+                    }
+                    Err(e) if attempt == ATTEMPTS => warn!("⚠️ {} webhook to {} failed: {}", name, url, e),
+                    Err(_) => tokio::time::sleep(std::time::Duration::from_secs(2u64.pow(attempt))).await,
+                }
+            }
+        }
+        delivered
+    }
+
+    /// Deliver in the background, for synchronous paths such as the risk checks
+    pub fn emit(self: &Arc<Self>, event: WebhookEvent) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("⚠️ No runtime to deliver {} webhook", event.name());
+            return;
+        };
+        let notifier = self.clone();
+        runtime.spawn(async move {
+            notifier.deliver(event).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_envelope_shape_and_signature() {
+        let envelope = Envelope {
+            id: Uuid::nil(),
+            created_at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            event: WebhookEvent::PatternRetired { pattern_hash: "abc123".to_string(), reason: "evolution_culled".to_string() },
+        };
+        let body = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(body["type"], "pattern_retired");
+        assert_eq!(body["data"], json!({ "pattern_hash": "abc123", "reason": "evolution_culled" }));
+
+        // What a receiver computes: hmac.new(secret, b"1700000000." + body, sha256).hexdigest()
+        assert_eq!(sign("whsec_test", 1_700_000_000, r#"{"ok":true}"#),
+                   "t=1700000000,v1=85876387ad9d6be57a04653bc0729da757049f58afb10ba6cac3bedaecf4fda3");
+
+        let only_breakers = WebhookNotifier::new(vec![], "s".to_string(), Some(vec!["circuit_breaker".to_string()]));
+        assert!(!only_breakers.wants(&envelope.event));
+        assert!(only_breakers.wants(&WebhookEvent::CircuitBreaker { breaker: "var_limit".to_string(), tripped: true, detail: Value::Null }));
+    }
+}
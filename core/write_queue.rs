@@ -0,0 +1,191 @@
+// Write Queue - Bounded Hand-off From Decision Paths to a Database Writer
+// Audit entries and ledger writes are recorded from inside order approval and fill handling,
+// which must never wait on Postgres. A record is a non-blocking push onto a bounded channel
+// drained by one writer task, which takes everything waiting (up to a batch) per round trip.
+// When the database slows and the channel fills, records spill into an overflow that stays in
+// order behind it; past WRITE_QUEUE_LIMIT the oldest spilled records are dropped and counted.
+// Nothing in the overflow is handed to the writer before the channel ahead of it is empty
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::error;
+
+use crate::core::anomaly::{self, Event};
+use crate::core::shutdown::PendingWrites;
+
+/// Records the channel holds before they spill
+pub const WRITE_QUEUE_CAPACITY: usize = 1024;
+
+/// Spilled records kept while the database can't keep up; the oldest go first past this
+pub const WRITE_QUEUE_LIMIT: usize = 50_000;
+
+/// Records handed to the writer per batch
+pub const WRITE_QUEUE_BATCH: usize = 256;
+
+/// The recording side; cheap to clone, every clone feeds the same writer
+pub struct WriteQueue<T> {
+    sender: mpsc::Sender<T>,
+    overflow: Arc<Mutex<VecDeque<T>>>,
+    limit: usize,
+    dropped: Arc<AtomicU64>,
+    pending: PendingWrites,
+    name: &'static str,
+}
+
+impl<T> Clone for WriteQueue<T> {
+    fn clone(&self) -> Self {
+        WriteQueue {
+            sender: self.sender.clone(),
+            overflow: self.overflow.clone(),
+            limit: self.limit,
+            dropped: self.dropped.clone(),
+            pending: self.pending.clone(),
+            name: self.name,
+        }
+    }
+}
+
+/// The writer's side: records in the order they were pushed, a batch at a time
+pub struct WriteBatches<T> {
+    receiver: mpsc::Receiver<T>,
+    overflow: Arc<Mutex<VecDeque<T>>>,
+    pending: PendingWrites,
+    batch: usize,
+}
+
+impl<T> WriteQueue<T> {
+    /// A queue named for its log lines, and the batches it hands its writer
+    pub fn channel(name: &'static str, capacity: usize, limit: usize, batch: usize) -> (Self, WriteBatches<T>) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let overflow = Arc::new(Mutex::new(VecDeque::new()));
+        let pending = PendingWrites::default();
+        let queue = WriteQueue { sender, overflow: overflow.clone(), limit, dropped: Arc::new(AtomicU64::new(0)), pending: pending.clone(), name };
+        (queue, WriteBatches { receiver, overflow, pending, batch: batch.max(1) })
+    }
+
+    /// Queue a record without waiting; hands it back if the writer is gone
+    pub fn push(&self, record: T) -> Result<(), T> {
+        self.pending.started();
+        let mut overflow = self.overflow.lock().unwrap();
+        // Once anything has spilled, later records spill behind it to keep their order
+        let record = if overflow.is_empty() {
+            match self.sender.try_send(record) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Closed(record)) => {
+                    self.pending.finished();
+                    return Err(record);
+                }
+                Err(TrySendError::Full(record)) => record,
+            }
+        } else {
+            record
+        };
+
+        overflow.push_back(record);
+        if overflow.len() > self.limit {
+            overflow.pop_front();
+            self.pending.finished();
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            if dropped.is_power_of_two() {
+                error!("🚨 {} writer is behind; dropped {} records past WRITE_QUEUE_LIMIT", self.name, dropped);
+                anomaly::note(Event::DbWriteError);
+            }
+        }
+        Ok(())
+    }
+
+    /// Records dropped because the writer fell too far behind
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Records waiting, in the channel and spilled
+    pub fn queued(&self) -> usize {
+        self.pending.in_flight()
+    }
+
+    /// Wait for everything pushed so far to be written; returns how many records are still queued
+    pub async fn flush(&self, timeout: std::time::Duration) -> usize {
+        self.pending.drain(timeout).await
+    }
+}
+
+impl<T> WriteBatches<T> {
+    /// Spilled records, if the channel ahead of them is empty
+    fn take_overflow(&mut self, room: usize, batch: &mut Vec<T>) {
+        let mut overflow = self.overflow.lock().unwrap();
+        if self.receiver.is_empty() {
+            let n = room.min(overflow.len());
+            batch.extend(overflow.drain(..n));
+        }
+    }
+
+    /// The next batch in the order it was pushed; None once every queue is gone and drained
+    pub async fn next(&mut self) -> Option<Vec<T>> {
+        let mut batch = Vec::with_capacity(self.batch);
+        self.take_overflow(self.batch, &mut batch);
+        if !batch.is_empty() {
+            return Some(batch);
+        }
+
+        if self.receiver.recv_many(&mut batch, self.batch).await == 0 {
+            batch.extend(self.overflow.lock().unwrap().drain(..));
+            return (!batch.is_empty()).then_some(batch);
+        }
+        let room = self.batch - batch.len();
+        self.take_overflow(room, &mut batch);
+        Some(batch)
+    }
+
+    /// One record if any is waiting, without blocking
+    pub fn try_recv(&mut self) -> Option<T> {
+        if let Ok(record) = self.receiver.try_recv() {
+            return Some(record);
+        }
+        let mut batch = Vec::new();
+        self.take_overflow(1, &mut batch);
+        batch.pop()
+    }
+
+    /// Mark `count` records from the last batch as done with, written or not
+    pub fn written(&self, count: usize) {
+        for _ in 0..count {
+            self.pending.finished();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_a_stalled_writer_never_blocks_and_order_survives_the_spill() {
+        let (queue, mut batches) = WriteQueue::channel("test", 4, 10, 3);
+
+        // The writer is stalled: 4 fit in the channel, 10 spill and 6 of the oldest spilled are dropped
+        for i in 0..20 {
+            assert!(queue.push(i).is_ok());
+        }
+        assert_eq!((queue.queued(), queue.dropped()), (14, 6));
+
+        let mut written = Vec::new();
+        while written.len() < 14 {
+            let batch = batches.next().await.unwrap();
+            assert!(batch.len() <= 3);
+            batches.written(batch.len());
+            written.extend(batch);
+        }
+        assert_eq!(written, [0, 1, 2, 3, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19]);
+        assert_eq!(queue.flush(Duration::from_millis(10)).await, 0);
+
+        // Caught up, records go through the channel again
+        assert!(queue.push(20).is_ok());
+        assert_eq!(batches.try_recv(), Some(20));
+        drop(queue);
+        assert_eq!(batches.next().await, None);
+    }
+}
@@ -0,0 +1,150 @@
+// Intelligence Budget
+// Prices every call at the model's list rate and holds the day's spend (UTC) against a
+// configurable budget: past the downgrade threshold calls move to a cheaper model, and once
+// the budget is gone they are skipped until the next day
+
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+
+use crate::intelligence::client::TokenUsage;
+
+/// USD per million (prompt, completion) tokens; longest prefix wins so dated snapshots
+/// (e.g. gpt-4o-2024-08-06) price like their family
+const MODEL_PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4.1-nano", 0.10, 0.40),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1", 2.00, 8.00),
+    ("o4-mini", 1.10, 4.40),
+    ("claude-3-5-haiku", 0.80, 4.00),
+    ("claude-3-7-sonnet", 3.00, 15.00),
+    ("claude-sonnet-4", 3.00, 15.00),
+    ("claude-opus-4", 15.00, 75.00),
+];
+
+/// Unknown models are priced like gpt-4o so the governor errs towards stopping early
+const UNKNOWN_MODEL_PRICE: (f64, f64) = (2.50, 10.00);
+
+pub fn model_price(model: &str) -> (f64, f64) {
+    MODEL_PRICES.iter()
+        .filter(|(prefix, _, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _, _)| prefix.len())
+        .map(|(_, prompt, completion)| (*prompt, *completion))
+        .unwrap_or(UNKNOWN_MODEL_PRICE)
+}
+
+pub fn cost_usd(model: &str, usage: TokenUsage) -> f64 {
+    let (prompt, completion) = model_price(model);
+    (usage.prompt_tokens as f64 * prompt + usage.completion_tokens as f64 * completion) / 1_000_000.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BudgetDecision {
+    Full,
+    Downgrade(String),  // the cheaper model to use instead
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetConfig {
+    pub daily_usd: f64,        // 0 disables the governor
+    pub downgrade_at: f64,     // fraction of the budget after which calls use the fallback model
+    pub fallback_model: String,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        BudgetConfig {
+            daily_usd: 1.0,
+            downgrade_at: 0.8,
+            fallback_model: "gpt-4.1-nano".to_string(),
+        }
+    }
+}
+
+impl BudgetConfig {
+    /// OPENAI_DAILY_BUDGET, OPENAI_BUDGET_DOWNGRADE_AT and OPENAI_FALLBACK_MODEL override the defaults
+    pub fn from_env() -> Self {
+        let defaults = BudgetConfig::default();
+        let number = |var: &str| std::env::var(var).ok().and_then(|v| v.parse::<f64>().ok());
+
+        BudgetConfig {
+            daily_usd: number("OPENAI_DAILY_BUDGET").map(|v| v.max(0.0)).unwrap_or(defaults.daily_usd),
+            downgrade_at: number("OPENAI_BUDGET_DOWNGRADE_AT").map(|v| v.clamp(0.0, 1.0)).unwrap_or(defaults.downgrade_at),
+            fallback_model: std::env::var("OPENAI_FALLBACK_MODEL").unwrap_or(defaults.fallback_model),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.daily_usd > 0.0
+    }
+
+    /// What a call to `model` may do with `spent_usd` already gone today
+    pub fn decide(&self, model: &str, spent_usd: f64) -> BudgetDecision {
+        if !self.enabled() {
+            return BudgetDecision::Full;
+        }
+        if spent_usd >= self.daily_usd {
+            return BudgetDecision::Skip;
+        }
+        if spent_usd >= self.daily_usd * self.downgrade_at && model != self.fallback_model {
+            return BudgetDecision::Downgrade(self.fallback_model.clone());
+        }
+        BudgetDecision::Full
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailySpend {
+    pub calls: i64,
+    pub total_tokens: i64,
+    pub cost_usd: f64,
+}
+
+/// Spend recorded in llm_usage since midnight UTC
+pub async fn spend_today(db_pool: &PgPool) -> Result<DailySpend, sqlx::Error> {
+    let row = sqlx::query("
+        SELECT COUNT(*) AS calls,
+               COALESCE(SUM(total_tokens), 0)::int8 AS total_tokens,
+               COALESCE(SUM(cost_usd), 0)::float8 AS cost_usd
+        FROM llm_usage
+        WHERE created_at >= date_trunc('day', NOW() AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+    ")
+        .fetch_one(db_pool)
+        .await?;
+
+    Ok(DailySpend {
+        calls: row.get("calls"),
+        total_tokens: row.get("total_tokens"),
+        cost_usd: row.get("cost_usd"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calls_are_priced_by_model_family() {
+        let usage = TokenUsage { prompt_tokens: 1_000_000, completion_tokens: 100_000, total_tokens: 1_100_000 };
+
+        assert!((cost_usd("gpt-4o-mini", usage) - 0.21).abs() < 1e-9);
+        assert!((cost_usd("gpt-4o-2024-08-06", usage) - 3.5).abs() < 1e-9);
+        assert!((cost_usd("gpt-4.1-mini-2025-04-14", usage) - 0.56).abs() < 1e-9);
+        assert_eq!(cost_usd("some-new-model", usage), cost_usd("gpt-4o", usage));
+    }
+
+    #[test]
+    fn test_budget_downgrades_then_skips() {
+        let budget = BudgetConfig::default();
+
+        assert_eq!(budget.decide("gpt-4o-mini", 0.2), BudgetDecision::Full);
+        assert_eq!(budget.decide("gpt-4o-mini", 0.85), BudgetDecision::Downgrade("gpt-4.1-nano".to_string()));
+        assert_eq!(budget.decide("gpt-4.1-nano", 0.85), BudgetDecision::Full);
+        assert_eq!(budget.decide("gpt-4o-mini", 1.0), BudgetDecision::Skip);
+
+        let unlimited = BudgetConfig { daily_usd: 0.0, ..BudgetConfig::default() };
+        assert_eq!(unlimited.decide("gpt-4o", 1_000.0), BudgetDecision::Full);
+    }
+}
@@ -0,0 +1,361 @@
+// Intelligence Client
+// Runs a structured prompt against the provider chain, parses the JSON-schema response into
+// the prompt's output type, and retries with exponential backoff on transient failures and
+// unparseable answers before falling back to the next provider. Every call's token usage and
+// cost is recorded in llm_usage, failures included, and the day's spend decides which hosted
+// models the next call may use. The system prompt is the task's active version from the
+// prompt store, and the version is recorded with everything the call produces
+
+use std::time::{Duration, Instant};
+use async_openai::error::OpenAIError;
+use serde::{Serialize, Deserialize};
+use sqlx::PgPool;
+use tracing::{info, warn, error};
+
+use crate::intelligence::budget::{self, BudgetConfig, BudgetDecision};
+use crate::intelligence::prompt_store::{ActivePrompt, PromptStore};
+use crate::intelligence::prompts::StructuredPrompt;
+use crate::intelligence::provider::{providers_from_env, ChatRequest, LlmProvider, ProviderConfig, ProviderKind};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 4,
+            initial_delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before retry number `attempt` (1 = the first retry)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.powi(attempt.saturating_sub(1) as i32);
+        self.initial_delay.mul_f64(factor).min(self.max_delay)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntelligenceConfig {
+    pub providers: Vec<ProviderConfig>,  // tried in order; later ones only when earlier ones fail
+    pub retry: RetryPolicy,
+    pub budget: BudgetConfig,
+}
+
+impl IntelligenceConfig {
+    /// None when no provider has credentials or an endpoint (see `providers_from_env`)
+    pub fn from_env() -> Option<Self> {
+        let providers = providers_from_env();
+        if providers.is_empty() {
+            return None;
+        }
+
+        Some(IntelligenceConfig {
+            providers,
+            retry: RetryPolicy::default(),
+            budget: BudgetConfig::from_env(),
+        })
+    }
+
+    /// The primary provider's model
+    pub fn model(&self) -> &str {
+        self.providers.first().map(|p| p.model.as_str()).unwrap_or_default()
+    }
+
+    /// e.g. "openai:gpt-4o-mini → local:llama3.1:8b"
+    pub fn chain(&self) -> String {
+        self.providers.iter()
+            .map(|p| format!("{}:{}", p.kind.as_str(), p.model))
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+impl TokenUsage {
+    pub fn add(&mut self, other: TokenUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+#[derive(Debug)]
+pub enum IntelligenceError {
+    Api(OpenAIError),
+    EmptyResponse,
+    Refused(String),
+    Schema(String),  // the answer didn't parse into the output type or failed validation
+    Http { status: Option<u16>, message: String },  // providers called without an SDK
+    Database(sqlx::Error),
+    BudgetExhausted { spent_usd: f64, budget_usd: f64 },
+    NoProvider,
+}
+
+impl IntelligenceError {
+    /// Network errors, rate limits, server errors and bad answers are worth another attempt;
+    /// request errors and refusals would fail the same way again
+    pub fn is_transient(&self) -> bool {
+        match self {
+            IntelligenceError::Api(OpenAIError::Reqwest(_)) => true,
+            IntelligenceError::Api(OpenAIError::JSONDeserialize(_)) => true,
+            IntelligenceError::Api(OpenAIError::ApiError(e)) => {
+                let kind = e.r#type.as_deref().unwrap_or_default();
+                let code = e.code.as_deref().unwrap_or_default();
+                kind == "server_error" || code == "rate_limit_exceeded"
+            }
+            IntelligenceError::Api(_) => false,
+            IntelligenceError::EmptyResponse => true,
+            IntelligenceError::Refused(_) => false,
+            IntelligenceError::Schema(_) => true,
+            IntelligenceError::Http { status, .. } => status.map(|s| s == 429 || s >= 500).unwrap_or(true),
+            IntelligenceError::Database(_) => false,
+            IntelligenceError::BudgetExhausted { .. } => false,
+            IntelligenceError::NoProvider => false,
+        }
+    }
+}
+
+impl std::fmt::Display for IntelligenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntelligenceError::Api(e) => write!(f, "openai: {}", e),
+            IntelligenceError::EmptyResponse => write!(f, "response had no content"),
+            IntelligenceError::Refused(reason) => write!(f, "model refused: {}", reason),
+            IntelligenceError::Schema(e) => write!(f, "response did not match schema: {}", e),
+            IntelligenceError::Http { status: Some(status), message } => write!(f, "http {}: {}", status, message),
+            IntelligenceError::Http { status: None, message } => write!(f, "http: {}", message),
+            IntelligenceError::Database(e) => write!(f, "database: {}", e),
+            IntelligenceError::BudgetExhausted { spent_usd, budget_usd } => {
+                write!(f, "daily budget exhausted: ${:.2} of ${:.2} spent", spent_usd, budget_usd)
+            }
+            IntelligenceError::NoProvider => write!(f, "no LLM provider configured"),
+        }
+    }
+}
+
+impl std::error::Error for IntelligenceError {}
+
+impl From<OpenAIError> for IntelligenceError {
+    fn from(e: OpenAIError) -> Self {
+        IntelligenceError::Api(e)
+    }
+}
+
+impl From<sqlx::Error> for IntelligenceError {
+    fn from(e: sqlx::Error) -> Self {
+        IntelligenceError::Database(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Completion<T> {
+    pub output: T,
+    pub usage: TokenUsage,
+    pub attempts: u32,
+    pub provider: ProviderKind,
+    pub model: String,  // differs from the configured model after a fallback or budget downgrade
+    pub prompt_version: Option<i32>,
+}
+
+/// One provider's share of a call, summed over its attempts
+struct CallStats {
+    usage: TokenUsage,
+    attempts: u32,
+    latency: Duration,
+}
+
+pub struct IntelligenceClient {
+    providers: Vec<(ProviderConfig, Box<dyn LlmProvider>)>,
+    config: IntelligenceConfig,
+    prompts: PromptStore,
+    db_pool: PgPool,
+}
+
+impl IntelligenceClient {
+    pub fn new(config: IntelligenceConfig, db_pool: PgPool) -> Self {
+        IntelligenceClient {
+            providers: config.providers.iter().map(|p| (p.clone(), p.build())).collect(),
+            config,
+            prompts: PromptStore::new(db_pool.clone()),
+            db_pool,
+        }
+    }
+
+    pub fn model(&self) -> &str {
+        self.config.model()
+    }
+
+    /// Run the prompt down the provider chain until one yields a valid answer
+    pub async fn complete<P: StructuredPrompt>(&self, prompt: &P) -> Result<Completion<P::Output>, IntelligenceError> {
+        let builtin = prompt.system();
+        let active = match self.prompts.resolve(P::NAME, &builtin).await {
+            Ok(active) => active,
+            Err(e) => {
+                warn!("⚠️ Prompt store unavailable for {}, using the built-in prompt: {}", P::NAME, e);
+                ActivePrompt { version: None, system: builtin }
+            }
+        };
+        let request = ChatRequest {
+            name: P::NAME.to_string(),
+            system: active.system,
+            user: prompt.user(),
+            schema: P::schema(),
+            temperature: prompt.temperature(),
+            max_tokens: prompt.max_completion_tokens(),
+        };
+        let decision = self.budget_decision().await?;
+        let mut last_error = IntelligenceError::NoProvider;
+
+        for (i, (slot, provider)) in self.providers.iter().enumerate() {
+            let model = match (slot.kind.is_hosted(), &decision) {
+                (false, _) => slot.model.clone(),
+                (true, Some((BudgetDecision::Skip, spent_usd))) => {
+                    last_error = IntelligenceError::BudgetExhausted {
+                        spent_usd: *spent_usd,
+                        budget_usd: self.config.budget.daily_usd,
+                    };
+                    continue;
+                }
+                // Only OpenAI has a configured cheaper model
+                (true, Some((BudgetDecision::Downgrade(cheaper), spent_usd))) if slot.kind == ProviderKind::OpenAi => {
+                    info!("💸 {} downgraded to {}: ${:.2} of ${:.2} spent today",
+                        P::NAME, cheaper, spent_usd, self.config.budget.daily_usd);
+                    cheaper.clone()
+                }
+                (true, _) => slot.model.clone(),
+            };
+
+            match self.complete_with::<P>(provider.as_ref(), &request, &model, active.version).await {
+                Ok(completion) => return Ok(completion),
+                Err(e) => {
+                    if i + 1 < self.providers.len() {
+                        warn!("⚠️ {} via {} failed ({}), falling back", P::NAME, slot.kind.as_str(), e);
+                    }
+                    last_error = e;
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Today's decision and spend; None when the governor is off or every provider is local
+    async fn budget_decision(&self) -> Result<Option<(BudgetDecision, f64)>, IntelligenceError> {
+        let budget = &self.config.budget;
+        if !budget.enabled() || !self.providers.iter().any(|(slot, _)| slot.kind.is_hosted()) {
+            return Ok(None);
+        }
+
+        let spent = budget::spend_today(&self.db_pool).await?;
+        Ok(Some((budget.decide(self.model(), spent.cost_usd), spent.cost_usd)))
+    }
+
+    /// One provider, retried until it answers or the retry policy gives up
+    async fn complete_with<P: StructuredPrompt>(&self, provider: &dyn LlmProvider, request: &ChatRequest, model: &str, prompt_version: Option<i32>) -> Result<Completion<P::Output>, IntelligenceError> {
+        let started = Instant::now();
+        let mut usage = TokenUsage::default();
+        let mut attempts = 0;
+
+        let result = loop {
+            attempts += 1;
+            let answer = provider.chat(request, model).await.and_then(|response| {
+                usage.add(response.usage);
+                parse_output::<P>(&response.content)
+            });
+            match answer {
+                Ok(output) => break Ok(output),
+                Err(e) if e.is_transient() && attempts < self.config.retry.max_attempts => {
+                    let delay = self.config.retry.delay(attempts);
+                    warn!("⚠️ {} attempt {} failed ({}), retrying in {:?}", P::NAME, attempts, e, delay);
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => break Err(e),
+            }
+        };
+
+        let error = result.as_ref().err().map(|e| e.to_string());
+        let stats = CallStats { usage, attempts, latency: started.elapsed() };
+        if let Err(e) = self.record_usage(P::NAME, prompt_version, provider.kind(), model, &stats, error.as_deref()).await {
+            error!("❌ Failed to record token usage for {}: {}", P::NAME, e);
+        }
+
+        result.map(|output| Completion { output, usage, attempts, provider: provider.kind(), model: model.to_string(), prompt_version })
+    }
+
+    async fn record_usage(&self, task: &str, prompt_version: Option<i32>, provider: ProviderKind, model: &str, stats: &CallStats, error: Option<&str>) -> Result<(), sqlx::Error> {
+        // Local models are free
+        let cost = if provider.is_hosted() { budget::cost_usd(model, stats.usage) } else { 0.0 };
+
+        sqlx::query("
+            INSERT INTO llm_usage
+            (task, model, prompt_tokens, completion_tokens, total_tokens, attempts, succeeded, error, latency_ms, cost_usd, provider, prompt_version)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ")
+            .bind(task)
+            .bind(model)
+            .bind(stats.usage.prompt_tokens as i32)
+            .bind(stats.usage.completion_tokens as i32)
+            .bind(stats.usage.total_tokens as i32)
+            .bind(stats.attempts as i32)
+            .bind(error.is_none())
+            .bind(error)
+            .bind(stats.latency.as_millis() as i64)
+            .bind(cost)
+            .bind(provider.as_str())
+            .bind(prompt_version)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Parse a response body into the prompt's output type and run its range checks
+pub fn parse_output<P: StructuredPrompt>(content: &str) -> Result<P::Output, IntelligenceError> {
+    let output: P::Output = serde_json::from_str(content).map_err(|e| IntelligenceError::Schema(e.to_string()))?;
+    P::validate(&output).map_err(IntelligenceError::Schema)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intelligence::prompts::SentimentPrompt;
+
+    #[test]
+    fn test_retry_delay_grows_and_caps() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(policy.delay(1), Duration::from_secs(2));
+        assert_eq!(policy.delay(2), Duration::from_secs(4));
+        assert_eq!(policy.delay(3), Duration::from_secs(8));
+        assert_eq!(policy.delay(10), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_bad_answers_are_retried_but_refusals_are_not() {
+        let garbled = parse_output::<SentimentPrompt>("{\"overall_sentiment\": \"bullish\"}").unwrap_err();
+        assert!(matches!(garbled, IntelligenceError::Schema(_)));
+        assert!(garbled.is_transient());
+
+        assert!(!IntelligenceError::Refused("no".to_string()).is_transient());
+        assert!(!IntelligenceError::BudgetExhausted { spent_usd: 5.0, budget_usd: 5.0 }.is_transient());
+        assert!(!IntelligenceError::Api(OpenAIError::InvalidArgument("model".to_string())).is_transient());
+    }
+}
@@ -0,0 +1,24 @@
+// Intelligence layer
+// LLM analysis running alongside discovery, natively instead of through the Python strategist.
+// The model only ever answers in JSON against a schema, parsed into typed structs; nothing it
+// returns is executed
+
+pub mod budget;
+pub mod client;
+pub mod prompt_store;
+pub mod prompts;
+pub mod provider;
+pub mod sentiment;
+pub mod social;
+pub mod strategist;
+
+pub use budget::{BudgetConfig, BudgetDecision, DailySpend};
+pub use client::{Completion, IntelligenceClient, IntelligenceConfig, IntelligenceError, RetryPolicy, TokenUsage};
+pub use prompts::{
+    MarketSummary, SentimentPrompt, SentimentReport, SignalAction, StructuredPrompt, TemplatePrompt, TemplateProposals, TradeSignal,
+};
+pub use prompt_store::{PromptSource, PromptStore, PromptVersion};
+pub use provider::{LlmProvider, ProviderConfig, ProviderKind};
+pub use sentiment::{SentimentMetrics, SentimentPipeline};
+pub use social::{Mention, SocialMetrics, SocialMonitor, SocialScanner};
+pub use strategist::Strategist;
@@ -0,0 +1,319 @@
+// Prompt Versions
+// System prompts live in prompt_versions, numbered per task. The prompt shipped in code is
+// registered as a version the first time it runs; operators add their own and activate any
+// version without a restart. Every LLM output is tagged with the version that produced it
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sha2::{Sha256, Digest};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use tracing::info;
+
+use crate::intelligence::prompts::{StructuredPrompt, TemplatePrompt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PromptSource {
+    Builtin,
+    Operator,
+}
+
+impl PromptSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PromptSource::Builtin => "builtin",
+            PromptSource::Operator => "operator",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "builtin" => Some(PromptSource::Builtin),
+            "operator" => Some(PromptSource::Operator),
+            _ => None,
+        }
+    }
+}
+
+/// A changed built-in prompt goes live on deploy unless an operator's version is active
+pub fn builtin_takes_over(active: Option<PromptSource>) -> bool {
+    active != Some(PromptSource::Operator)
+}
+
+pub fn content_hash(text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(text.trim().as_bytes());
+    format!("{:x}", hasher.finalize())[..16].to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptVersion {
+    pub task: String,
+    pub version: i32,
+    pub system_prompt: String,
+    pub source: PromptSource,
+    pub notes: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The system prompt a call should use
+#[derive(Debug, Clone)]
+pub struct ActivePrompt {
+    pub version: Option<i32>,  // None when running the built-in prompt unversioned
+    pub system: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptVersionStats {
+    pub version: i32,
+    pub calls: i64,
+    pub success_rate: f64,
+    pub avg_tokens: f64,
+    pub cost_usd: f64,
+    pub seeded_tests: i64,                 // live tests of hypotheses seeded from this version's templates
+    pub seeded_win_rate: Option<f64>,
+}
+
+pub struct PromptStore {
+    registered: Mutex<HashSet<String>>,  // tasks whose built-in prompt was registered by this process
+    db_pool: PgPool,
+}
+
+impl PromptStore {
+    pub fn new(db_pool: PgPool) -> Self {
+        PromptStore {
+            registered: Mutex::new(HashSet::new()),
+            db_pool,
+        }
+    }
+
+    /// The task's active version, registering `builtin` first if this process hasn't yet
+    pub async fn resolve(&self, task: &str, builtin: &str) -> Result<ActivePrompt, sqlx::Error> {
+        if !self.registered.lock().unwrap().contains(task) {
+            self.register_builtin(task, builtin).await?;
+            self.registered.lock().unwrap().insert(task.to_string());
+        }
+
+        let row = sqlx::query("SELECT version, system_prompt FROM prompt_versions WHERE task = $1 AND is_active")
+            .bind(task)
+            .fetch_optional(&self.db_pool)
+            .await?;
+
+        Ok(match row {
+            Some(row) => ActivePrompt { version: Some(row.get("version")), system: row.get("system_prompt") },
+            None => ActivePrompt { version: None, system: builtin.to_string() },
+        })
+    }
+
+    async fn register_builtin(&self, task: &str, builtin: &str) -> Result<(), sqlx::Error> {
+        let hash = content_hash(builtin);
+        let mut tx = self.lock_task(task).await?;
+
+        let known = sqlx::query("SELECT version FROM prompt_versions WHERE task = $1 AND content_hash = $2")
+            .bind(task)
+            .bind(&hash)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if known.is_some() {
+            return tx.commit().await;
+        }
+
+        let active: Option<String> = sqlx::query("SELECT source FROM prompt_versions WHERE task = $1 AND is_active")
+            .bind(task)
+            .fetch_optional(&mut *tx)
+            .await?
+            .map(|row| row.get("source"));
+        let activate = builtin_takes_over(active.as_deref().and_then(PromptSource::parse));
+        if activate {
+            sqlx::query("UPDATE prompt_versions SET is_active = false WHERE task = $1 AND is_active")
+                .bind(task)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let version = insert_version(&mut tx, task, builtin, &hash, PromptSource::Builtin, None, activate).await?;
+        tx.commit().await?;
+
+        info!("📝 {} built-in prompt registered as v{}{}", task, version, if activate { " (active)" } else { "" });
+        Ok(())
+    }
+
+    /// A new operator version, inactive until activated; identical text returns the existing version
+    pub async fn add_version(&self, task: &str, system_prompt: &str, notes: Option<&str>) -> Result<i32, sqlx::Error> {
+        let hash = content_hash(system_prompt);
+        let mut tx = self.lock_task(task).await?;
+
+        let known = sqlx::query("SELECT version FROM prompt_versions WHERE task = $1 AND content_hash = $2")
+            .bind(task)
+            .bind(&hash)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let version = match known {
+            Some(row) => row.get("version"),
+            None => insert_version(&mut tx, task, system_prompt, &hash, PromptSource::Operator, notes, false).await?,
+        };
+        tx.commit().await?;
+
+        Ok(version)
+    }
+
+    /// Make `version` the task's live prompt from the next call on; false when it doesn't exist
+    pub async fn activate(&self, task: &str, version: i32) -> Result<bool, sqlx::Error> {
+        let mut tx = self.lock_task(task).await?;
+
+        let exists = sqlx::query("SELECT 1 FROM prompt_versions WHERE task = $1 AND version = $2")
+            .bind(task)
+            .bind(version)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some();
+        if !exists {
+            return Ok(false);
+        }
+
+        sqlx::query("UPDATE prompt_versions SET is_active = false WHERE task = $1 AND is_active")
+            .bind(task)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE prompt_versions SET is_active = true, activated_at = NOW() WHERE task = $1 AND version = $2")
+            .bind(task)
+            .bind(version)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Every version of `task`, or of every task, oldest first
+    pub async fn versions(&self, task: Option<&str>) -> Result<Vec<PromptVersion>, sqlx::Error> {
+        let rows = sqlx::query("
+            SELECT task, version, system_prompt, source, notes, is_active, created_at
+            FROM prompt_versions
+            WHERE $1::text IS NULL OR task = $1
+            ORDER BY task, version
+        ")
+            .bind(task)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| PromptVersion {
+            task: row.get("task"),
+            version: row.get("version"),
+            system_prompt: row.get("system_prompt"),
+            source: PromptSource::parse(row.get("source")).unwrap_or(PromptSource::Operator),
+            notes: row.get("notes"),
+            is_active: row.get("is_active"),
+            created_at: row.get("created_at"),
+        }).collect())
+    }
+
+    /// Per-version call outcomes, plus live test results of seeded hypotheses for the template task
+    pub async fn version_stats(&self, task: &str) -> Result<Vec<PromptVersionStats>, sqlx::Error> {
+        let rows = sqlx::query("
+            SELECT prompt_version,
+                   COUNT(*) AS calls,
+                   AVG(CASE WHEN succeeded THEN 1.0 ELSE 0.0 END)::float8 AS success_rate,
+                   AVG(total_tokens)::float8 AS avg_tokens,
+                   COALESCE(SUM(cost_usd), 0)::float8 AS cost_usd
+            FROM llm_usage
+            WHERE task = $1 AND prompt_version IS NOT NULL
+            GROUP BY prompt_version
+            ORDER BY prompt_version
+        ")
+            .bind(task)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        let mut stats: Vec<PromptVersionStats> = rows.iter().map(|row| PromptVersionStats {
+            version: row.get("prompt_version"),
+            calls: row.get("calls"),
+            success_rate: row.get("success_rate"),
+            avg_tokens: row.get("avg_tokens"),
+            cost_usd: row.get("cost_usd"),
+            ..PromptVersionStats::default()
+        }).collect();
+
+        if task == TemplatePrompt::NAME {
+            let seeded: HashMap<i32, (i64, f64)> = sqlx::query("
+                SELECT p.prompt_version, COUNT(*) AS tests,
+                       AVG(CASE WHEN t.profitable THEN 1.0 ELSE 0.0 END)::float8 AS win_rate
+                FROM discovered_patterns p
+                JOIN test_results t ON t.pattern_hash = p.pattern_hash
+                WHERE p.prompt_version IS NOT NULL
+                GROUP BY p.prompt_version
+            ")
+                .fetch_all(&self.db_pool)
+                .await?
+                .iter()
+                .map(|row| (row.get("prompt_version"), (row.get("tests"), row.get("win_rate"))))
+                .collect();
+
+            for s in &mut stats {
+                if let Some((tests, win_rate)) = seeded.get(&s.version) {
+                    s.seeded_tests = *tests;
+                    s.seeded_win_rate = Some(*win_rate);
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// A transaction holding the task's advisory lock, so concurrent registrations can't
+    /// race on version numbers or the active flag
+    async fn lock_task(&self, task: &str) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        let mut tx = self.db_pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('prompt_versions:' || $1))")
+            .bind(task)
+            .execute(&mut *tx)
+            .await?;
+        Ok(tx)
+    }
+}
+
+async fn insert_version(
+    tx: &mut Transaction<'static, Postgres>,
+    task: &str,
+    system_prompt: &str,
+    hash: &str,
+    source: PromptSource,
+    notes: Option<&str>,
+    active: bool,
+) -> Result<i32, sqlx::Error> {
+    let row = sqlx::query("
+        INSERT INTO prompt_versions (task, version, system_prompt, content_hash, source, notes, is_active, activated_at)
+        SELECT $1, COALESCE(MAX(version), 0) + 1, $2, $3, $4, $5, $6, CASE WHEN $6 THEN NOW() END
+        FROM prompt_versions WHERE task = $1
+        RETURNING version
+    ")
+        .bind(task)
+        .bind(system_prompt)
+        .bind(hash)
+        .bind(source.as_str())
+        .bind(notes)
+        .bind(active)
+        .fetch_one(&mut **tx)
+        .await?;
+
+    Ok(row.get("version"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_changes_only_displace_builtin_versions() {
+        assert!(builtin_takes_over(None));
+        assert!(builtin_takes_over(Some(PromptSource::Builtin)));
+        assert!(!builtin_takes_over(Some(PromptSource::Operator)));
+
+        // Surrounding whitespace isn't a new version
+        assert_eq!(content_hash("Score headlines."), content_hash("  Score headlines.\n"));
+        assert_ne!(content_hash("Score headlines."), content_hash("Score headlines!"));
+        assert_eq!(content_hash("x").len(), 16);
+    }
+}
@@ -0,0 +1,402 @@
+// Structured Prompts
+// Each prompt pairs its instructions with the JSON schema the model must answer in and the
+// typed struct that answer is parsed into. Range checks the schema can't express in strict
+// mode live in `validate`; a response that fails them counts as a failed attempt
+
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+use serde_json::{json, Value};
+use sqlx::{PgPool, Row};
+
+use crate::core::mutation::METRIC_FAMILIES;
+use crate::core::seeding::{HypothesisTemplate, OPERATORS};
+
+pub trait StructuredPrompt {
+    type Output: DeserializeOwned;
+
+    /// Task name: the response schema name and the `task` column in llm_usage
+    const NAME: &'static str;
+
+    fn system(&self) -> String;
+    fn user(&self) -> String;
+    fn schema() -> Value;
+
+    fn temperature(&self) -> f32 {
+        0.3
+    }
+
+    fn max_completion_tokens(&self) -> u32 {
+        1000
+    }
+
+    fn validate(_output: &Self::Output) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// One symbol's last 24 hours, as the model sees it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketSummary {
+    pub symbol: String,
+    pub price: f64,
+    pub change_24h_pct: f64,
+    pub volume_24h: f64,
+}
+
+impl MarketSummary {
+    fn line(&self) -> String {
+        format!("{}: {:.6} ({:+.2}% 24h, volume {:.0})", self.symbol, self.price, self.change_24h_pct, self.volume_24h)
+    }
+}
+
+/// The most active symbols over the last 24 hours, largest movers first
+pub async fn load_market_summaries(db_pool: &PgPool, limit: i64) -> Result<Vec<MarketSummary>, sqlx::Error> {
+    let rows = sqlx::query("
+        SELECT symbol,
+               (array_agg(price::float8 ORDER BY timestamp DESC))[1] AS last,
+               (array_agg(price::float8 ORDER BY timestamp ASC))[1] AS first,
+               COALESCE(SUM(volume), 0)::float8 AS volume
+        FROM market_data
+        WHERE timestamp > NOW() - INTERVAL '24 hours'
+        GROUP BY symbol
+    ")
+        .fetch_all(db_pool)
+        .await?;
+
+    let mut summaries: Vec<MarketSummary> = rows.iter()
+        .filter_map(|row| {
+            let first: f64 = row.get("first");
+            let last: f64 = row.get("last");
+            (first > 0.0).then(|| MarketSummary {
+                symbol: row.get("symbol"),
+                price: last,
+                change_24h_pct: (last / first - 1.0) * 100.0,
+                volume_24h: row.get("volume"),
+            })
+        })
+        .collect();
+
+    summaries.sort_by(|a, b| b.change_24h_pct.abs().partial_cmp(&a.change_24h_pct.abs()).unwrap_or(std::cmp::Ordering::Equal));
+    summaries.truncate(limit.max(0) as usize);
+    Ok(summaries)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignalAction {
+    Buy,
+    Sell,
+    Wait,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeSignal {
+    pub symbol: String,
+    pub action: SignalAction,
+    pub confidence: f64,
+    pub reasoning: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskEvent {
+    pub event: String,
+    pub impact: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentimentReport {
+    pub overall_sentiment: f64,  // -1.0 (fear) to 1.0 (euphoria)
+    pub fear_greed_index: f64,   // 0 to 100
+    pub risk_events: Vec<RiskEvent>,
+    pub unusual_patterns: Vec<String>,
+    pub trade_signals: Vec<TradeSignal>,
+}
+
+/// Market-wide sentiment from recent price action and any headlines available
+pub struct SentimentPrompt {
+    pub markets: Vec<MarketSummary>,
+    pub headlines: Vec<String>,
+}
+
+impl StructuredPrompt for SentimentPrompt {
+    type Output = SentimentReport;
+    const NAME: &'static str = "market_sentiment";
+
+    fn system(&self) -> String {
+        "You assess crypto market sentiment for an autonomous pattern-discovery system. \
+         Judge only from the data provided; never recommend traditional indicator strategies."
+            .to_string()
+    }
+
+    fn user(&self) -> String {
+        let markets: Vec<String> = self.markets.iter().map(MarketSummary::line).collect();
+        let headlines = if self.headlines.is_empty() {
+            "(none)".to_string()
+        } else {
+            self.headlines.iter().take(50).cloned().collect::<Vec<_>>().join("\n")
+        };
+
+        format!(
+            "Market moves over the last 24 hours:\n{}\n\nHeadlines:\n{}\n\n\
+             Rate overall sentiment from -1.0 to 1.0 and a fear/greed index from 0 to 100. \
+             List risk events, unusual patterns, and per-symbol signals with confidence from 0.0 to 1.0.",
+            markets.join("\n"),
+            headlines,
+        )
+    }
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "overall_sentiment": {"type": "number"},
+                "fear_greed_index": {"type": "number"},
+                "risk_events": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "event": {"type": "string"},
+                            "impact": {"type": "string"}
+                        },
+                        "required": ["event", "impact"],
+                        "additionalProperties": false
+                    }
+                },
+                "unusual_patterns": {"type": "array", "items": {"type": "string"}},
+                "trade_signals": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "symbol": {"type": "string"},
+                            "action": {"type": "string", "enum": ["buy", "sell", "wait"]},
+                            "confidence": {"type": "number"},
+                            "reasoning": {"type": "string"}
+                        },
+                        "required": ["symbol", "action", "confidence", "reasoning"],
+                        "additionalProperties": false
+                    }
+                }
+            },
+            "required": ["overall_sentiment", "fear_greed_index", "risk_events", "unusual_patterns", "trade_signals"],
+            "additionalProperties": false
+        })
+    }
+
+    fn validate(report: &SentimentReport) -> Result<(), String> {
+        if !(-1.0..=1.0).contains(&report.overall_sentiment) {
+            return Err(format!("overall_sentiment {} outside [-1, 1]", report.overall_sentiment));
+        }
+        if !(0.0..=100.0).contains(&report.fear_greed_index) {
+            return Err(format!("fear_greed_index {} outside [0, 100]", report.fear_greed_index));
+        }
+        if let Some(signal) = report.trade_signals.iter().find(|s| !(0.0..=1.0).contains(&s.confidence)) {
+            return Err(format!("{} signal confidence {} outside [0, 1]", signal.symbol, signal.confidence));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeadlineScore {
+    pub index: usize,
+    pub score: f64,  // -1.0 (bearish) to 1.0 (bullish)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeadlineScores {
+    pub scores: Vec<HeadlineScore>,
+}
+
+/// Per-headline sentiment for the news pipeline; headlines are referred to by index
+pub struct HeadlineScorePrompt {
+    pub headlines: Vec<String>,
+}
+
+impl StructuredPrompt for HeadlineScorePrompt {
+    type Output = HeadlineScores;
+    const NAME: &'static str = "headline_scores";
+
+    fn system(&self) -> String {
+        "You score crypto news headlines for their likely short-term price impact. \
+         -1.0 is strongly bearish, 0.0 neutral, 1.0 strongly bullish."
+            .to_string()
+    }
+
+    fn user(&self) -> String {
+        let lines: Vec<String> = self.headlines.iter().enumerate().map(|(i, h)| format!("{}. {}", i, h)).collect();
+        format!("Score every headline by its index:\n{}", lines.join("\n"))
+    }
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "scores": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "index": {"type": "integer"},
+                            "score": {"type": "number"}
+                        },
+                        "required": ["index", "score"],
+                        "additionalProperties": false
+                    }
+                }
+            },
+            "required": ["scores"],
+            "additionalProperties": false
+        })
+    }
+
+    fn temperature(&self) -> f32 {
+        0.0
+    }
+
+    fn validate(scores: &HeadlineScores) -> Result<(), String> {
+        match scores.scores.iter().find(|s| !(-1.0..=1.0).contains(&s.score)) {
+            Some(s) => Err(format!("headline {} score {} outside [-1, 1]", s.index, s.score)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateProposals {
+    pub templates: Vec<HypothesisTemplate>,
+}
+
+/// Hypothesis templates for the LLM seeding arm, built only from the discovery vocabulary
+pub struct TemplatePrompt {
+    pub markets: Vec<MarketSummary>,
+    pub count: usize,
+}
+
+impl StructuredPrompt for TemplatePrompt {
+    type Output = TemplateProposals;
+    const NAME: &'static str = "hypothesis_templates";
+
+    fn system(&self) -> String {
+        "You propose hypothesis templates for an autonomous pattern-discovery system that tests \
+         them with small live trades. Use only the metrics and operators you are given; never \
+         name indicators outside that vocabulary."
+            .to_string()
+    }
+
+    fn user(&self) -> String {
+        let markets: Vec<String> = self.markets.iter().map(MarketSummary::line).collect();
+        let metrics: Vec<&str> = METRIC_FAMILIES.iter().flat_map(|family| family.iter().copied()).collect();
+
+        format!(
+            "Market moves over the last 24 hours:\n{}\n\n\
+             Metrics: {}\nOperators: {}\n\n\
+             Propose {} templates that might suit these conditions. Each has 1-5 entry and 1-3 exit \
+             conditions, a threshold range (min_value <= max_value) per condition, and a timeframe \
+             range in minutes between 1 and 1439. Explain each in one sentence.",
+            markets.join("\n"),
+            metrics.join(", "),
+            OPERATORS.join(", "),
+            self.count,
+        )
+    }
+
+    fn schema() -> Value {
+        let condition = json!({
+            "type": "object",
+            "properties": {
+                "metric": {"type": "string"},
+                "operator": {"type": "string", "enum": OPERATORS},
+                "min_value": {"type": "number"},
+                "max_value": {"type": "number"}
+            },
+            "required": ["metric", "operator", "min_value", "max_value"],
+            "additionalProperties": false
+        });
+
+        json!({
+            "type": "object",
+            "properties": {
+                "templates": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": {"type": "string"},
+                            "rationale": {"type": "string"},
+                            "entry": {"type": "array", "items": condition},
+                            "exit": {"type": "array", "items": condition},
+                            "min_timeframe": {"type": "integer"},
+                            "max_timeframe": {"type": "integer"}
+                        },
+                        "required": ["name", "rationale", "entry", "exit", "min_timeframe", "max_timeframe"],
+                        "additionalProperties": false
+                    }
+                }
+            },
+            "required": ["templates"],
+            "additionalProperties": false
+        })
+    }
+
+    fn temperature(&self) -> f32 {
+        0.7
+    }
+
+    fn max_completion_tokens(&self) -> u32 {
+        2000
+    }
+
+    /// Individual bad templates are dropped by the caller; an answer with none usable is retried
+    fn validate(proposals: &TemplateProposals) -> Result<(), String> {
+        if proposals.templates.iter().any(|t| t.check().is_ok()) {
+            Ok(())
+        } else {
+            Err("no usable templates".to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sentiment_report_parses_and_validates() {
+        let raw = json!({
+            "overall_sentiment": 0.4,
+            "fear_greed_index": 62,
+            "risk_events": [{"event": "exchange outage", "impact": "thin liquidity"}],
+            "unusual_patterns": [],
+            "trade_signals": [{"symbol": "PEPE-USD", "action": "buy", "confidence": 0.7, "reasoning": "volume spike"}]
+        });
+
+        let mut report: SentimentReport = serde_json::from_value(raw).unwrap();
+        assert_eq!(report.trade_signals[0].action, SignalAction::Buy);
+        assert!(SentimentPrompt::validate(&report).is_ok());
+
+        report.trade_signals[0].confidence = 1.5;
+        assert!(SentimentPrompt::validate(&report).is_err());
+        report.trade_signals[0].confidence = 0.5;
+        report.overall_sentiment = -3.0;
+        assert!(SentimentPrompt::validate(&report).is_err());
+    }
+
+    #[test]
+    fn test_template_answers_need_one_usable_template() {
+        let raw = json!({"templates": [{
+            "name": "spread_squeeze",
+            "rationale": "tight spreads precede breakouts",
+            "entry": [{"metric": "bid_ask_spread", "operator": "<", "min_value": 0.01, "max_value": 0.05}],
+            "exit": [{"metric": "macd_signal", "operator": ">", "min_value": 0.0, "max_value": 1.0}],
+            "min_timeframe": 5,
+            "max_timeframe": 60
+        }]});
+
+        let mut proposals: TemplateProposals = serde_json::from_value(raw).unwrap();
+        assert!(TemplatePrompt::validate(&proposals).is_err());
+
+        proposals.templates[0].exit[0].metric = "price_delta_5m".to_string();
+        assert!(TemplatePrompt::validate(&proposals).is_ok());
+    }
+}
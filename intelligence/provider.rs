@@ -0,0 +1,308 @@
+// LLM Providers
+// One chat call behind a trait so the client can walk a chain of backends: OpenAI, Anthropic,
+// and any local OpenAI-compatible endpoint (Ollama, llama.cpp, vLLM). When the hosted APIs are
+// down or the budget is gone, a local model keeps the intelligence layer answering
+
+use async_openai::{
+    config::OpenAIConfig,
+    types::{
+        ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+        CreateChatCompletionRequestArgs, ResponseFormat, ResponseFormatJsonSchema,
+    },
+    Client,
+};
+use async_trait::async_trait;
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+
+use crate::core::secrets;
+use crate::intelligence::client::{IntelligenceError, TokenUsage};
+
+const ANTHROPIC_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProviderKind {
+    OpenAi,
+    Anthropic,
+    Local,
+}
+
+impl ProviderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi => "openai",
+            ProviderKind::Anthropic => "anthropic",
+            ProviderKind::Local => "local",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "openai" => Some(ProviderKind::OpenAi),
+            "anthropic" => Some(ProviderKind::Anthropic),
+            "local" => Some(ProviderKind::Local),
+            _ => None,
+        }
+    }
+
+    /// Hosted providers cost money and count against the daily budget
+    pub fn is_hosted(&self) -> bool {
+        !matches!(self, ProviderKind::Local)
+    }
+}
+
+/// One structured prompt, rendered
+#[derive(Debug, Clone)]
+pub struct ChatRequest {
+    pub name: String,  // schema / tool name
+    pub system: String,
+    pub user: String,
+    pub schema: Value,
+    pub temperature: f32,
+    pub max_tokens: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatResponse {
+    pub content: String,  // the JSON answer, not yet parsed
+    pub usage: TokenUsage,
+}
+
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    fn kind(&self) -> ProviderKind;
+
+    async fn chat(&self, request: &ChatRequest, model: &str) -> Result<ChatResponse, IntelligenceError>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderConfig {
+    pub kind: ProviderKind,
+    pub model: String,
+    pub api_key: Option<String>,
+    pub base_url: Option<String>,  // local endpoints only
+}
+
+impl ProviderConfig {
+    pub fn build(&self) -> Box<dyn LlmProvider> {
+        match self.kind {
+            ProviderKind::OpenAi | ProviderKind::Local => Box::new(OpenAiCompatible::new(self)),
+            ProviderKind::Anthropic => Box::new(AnthropicProvider::new(self)),
+        }
+    }
+}
+
+/// Keys that are empty or obvious placeholders ("mock", "test", "xxxx") count as unset, so
+/// development setups never make live calls
+pub fn usable_key(var: &str) -> Option<String> {
+    let key = secrets::var(var).ok()?;
+    let lowered = key.to_lowercase();
+    if key.trim().is_empty() || lowered.contains("mock") || lowered.contains("test") || lowered.contains("xxxx") {
+        return None;
+    }
+    Some(key)
+}
+
+/// The providers LLM_PROVIDERS lists (default "openai,anthropic,local"), in that order, keeping
+/// only those with credentials or an endpoint configured
+pub fn providers_from_env() -> Vec<ProviderConfig> {
+    let order = std::env::var("LLM_PROVIDERS").unwrap_or_else(|_| "openai,anthropic,local".to_string());
+    let model = |var: &str, default: &str| std::env::var(var).unwrap_or_else(|_| default.to_string());
+
+    order.split(',')
+        .filter_map(|name| ProviderKind::parse(name.trim()))
+        .filter_map(|kind| match kind {
+            ProviderKind::OpenAi => usable_key("OPENAI_API_KEY").map(|key| ProviderConfig {
+                kind,
+                model: model("OPENAI_MODEL", "gpt-4o-mini"),
+                api_key: Some(key),
+                base_url: None,
+            }),
+            ProviderKind::Anthropic => usable_key("ANTHROPIC_API_KEY").map(|key| ProviderConfig {
+                kind,
+                model: model("ANTHROPIC_MODEL", "claude-3-5-haiku-latest"),
+                api_key: Some(key),
+                base_url: None,
+            }),
+            ProviderKind::Local => std::env::var("LOCAL_LLM_URL").ok().filter(|url| !url.trim().is_empty()).map(|url| ProviderConfig {
+                kind,
+                model: model("LOCAL_LLM_MODEL", "llama3.1:8b"),
+                api_key: secrets::var("LOCAL_LLM_API_KEY").ok(),
+                base_url: Some(url),
+            }),
+        })
+        .collect()
+}
+
+/// OpenAI itself, or a local server speaking its chat completions API
+pub struct OpenAiCompatible {
+    kind: ProviderKind,
+    client: Client<OpenAIConfig>,
+}
+
+impl OpenAiCompatible {
+    pub fn new(config: &ProviderConfig) -> Self {
+        let mut openai = OpenAIConfig::new().with_api_key(config.api_key.clone().unwrap_or_default());
+        if let Some(url) = &config.base_url {
+            openai = openai.with_api_base(url.trim_end_matches('/'));
+        }
+        OpenAiCompatible {
+            kind: config.kind,
+            client: Client::with_config(openai),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiCompatible {
+    fn kind(&self) -> ProviderKind {
+        self.kind
+    }
+
+    async fn chat(&self, request: &ChatRequest, model: &str) -> Result<ChatResponse, IntelligenceError> {
+        let body = CreateChatCompletionRequestArgs::default()
+            .model(model)
+            .messages([
+                ChatCompletionRequestSystemMessageArgs::default().content(request.system.clone()).build()?.into(),
+                ChatCompletionRequestUserMessageArgs::default().content(request.user.clone()).build()?.into(),
+            ])
+            .response_format(ResponseFormat::JsonSchema {
+                json_schema: ResponseFormatJsonSchema {
+                    description: None,
+                    name: request.name.clone(),
+                    schema: Some(request.schema.clone()),
+                    strict: Some(true),
+                },
+            })
+            .temperature(request.temperature)
+            .max_completion_tokens(request.max_tokens)
+            .build()?;
+        let response = self.client.chat().create(body).await?;
+
+        let usage = response.usage.as_ref().map(|u| TokenUsage {
+            prompt_tokens: u.prompt_tokens,
+            completion_tokens: u.completion_tokens,
+            total_tokens: u.total_tokens,
+        }).unwrap_or_default();
+
+        let message = response.choices.into_iter().next()
+            .map(|choice| choice.message)
+            .ok_or(IntelligenceError::EmptyResponse)?;
+        if let Some(refusal) = message.refusal {
+            return Err(IntelligenceError::Refused(refusal));
+        }
+        let content = message.content.ok_or(IntelligenceError::EmptyResponse)?;
+
+        Ok(ChatResponse { content, usage })
+    }
+}
+
+/// Anthropic's Messages API; the schema becomes a forced tool call whose input is the answer
+pub struct AnthropicProvider {
+    api_key: String,
+    http: reqwest::Client,
+}
+
+impl AnthropicProvider {
+    pub fn new(config: &ProviderConfig) -> Self {
+        AnthropicProvider {
+            api_key: config.api_key.clone().unwrap_or_default(),
+            http: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(120))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn body(request: &ChatRequest, model: &str) -> Value {
+        json!({
+            "model": model,
+            "max_tokens": request.max_tokens,
+            "temperature": request.temperature,
+            "system": request.system,
+            "messages": [{"role": "user", "content": request.user}],
+            "tools": [{
+                "name": request.name,
+                "description": "Submit the answer",
+                "input_schema": request.schema,
+            }],
+            "tool_choice": {"type": "tool", "name": request.name},
+        })
+    }
+}
+
+/// The forced tool call's input as JSON text, plus token usage
+pub fn parse_anthropic_response(body: &Value) -> Result<ChatResponse, IntelligenceError> {
+    let usage = &body["usage"];
+    let prompt_tokens = usage["input_tokens"].as_u64().unwrap_or(0) as u32;
+    let completion_tokens = usage["output_tokens"].as_u64().unwrap_or(0) as u32;
+
+    let input = body["content"].as_array()
+        .and_then(|blocks| blocks.iter().find(|b| b["type"] == "tool_use"))
+        .map(|block| block["input"].clone())
+        .ok_or(IntelligenceError::EmptyResponse)?;
+
+    Ok(ChatResponse {
+        content: input.to_string(),
+        usage: TokenUsage { prompt_tokens, completion_tokens, total_tokens: prompt_tokens + completion_tokens },
+    })
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicProvider {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Anthropic
+    }
+
+    async fn chat(&self, request: &ChatRequest, model: &str) -> Result<ChatResponse, IntelligenceError> {
+        let http_error = |status: Option<u16>, e: &dyn std::fmt::Display| IntelligenceError::Http { status, message: e.to_string() };
+
+        let response = self.http.post(ANTHROPIC_URL)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(&Self::body(request, model))
+            .send()
+            .await
+            .map_err(|e| http_error(None, &e))?;
+
+        let status = response.status();
+        let body: Value = response.json().await.map_err(|e| http_error(Some(status.as_u16()), &e))?;
+        if !status.is_success() {
+            let message = body["error"]["message"].as_str().unwrap_or("unknown error");
+            return Err(http_error(Some(status.as_u16()), &message));
+        }
+
+        parse_anthropic_response(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anthropic_tool_call_becomes_the_answer() {
+        let request = ChatRequest {
+            name: "market_sentiment".to_string(),
+            system: "s".to_string(),
+            user: "u".to_string(),
+            schema: json!({"type": "object"}),
+            temperature: 0.3,
+            max_tokens: 500,
+        };
+        let body = AnthropicProvider::body(&request, "claude-3-5-haiku-latest");
+        assert_eq!(body["tool_choice"]["name"], "market_sentiment");
+        assert_eq!(body["tools"][0]["input_schema"], json!({"type": "object"}));
+
+        let response = json!({
+            "content": [{"type": "text", "text": "Sure"}, {"type": "tool_use", "name": "market_sentiment", "input": {"overall_sentiment": 0.4}}],
+            "usage": {"input_tokens": 120, "output_tokens": 30},
+        });
+        let parsed = parse_anthropic_response(&response).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&parsed.content).unwrap(), json!({"overall_sentiment": 0.4}));
+        assert_eq!(parsed.usage.total_tokens, 150);
+
+        assert!(matches!(parse_anthropic_response(&json!({"content": []})), Err(IntelligenceError::EmptyResponse)));
+    }
+}
@@ -0,0 +1,471 @@
+// News Sentiment Pipeline
+// Pulls crypto news and exchange announcement RSS feeds, scores each new headline with the LLM
+// (or a keyword lexicon when no model is configured or the call fails), tags the symbols it
+// mentions, and publishes `sentiment_1h` and `news_shock` per symbol and market-wide
+
+use std::collections::{HashMap, HashSet};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+use tracing::warn;
+
+use crate::intelligence::client::IntelligenceClient;
+use crate::intelligence::prompts::HeadlineScorePrompt;
+
+/// Hourly |score| treated as the quietest normal news flow, so a silent baseline doesn't turn
+/// the first headline into an enormous shock
+const MIN_SHOCK_BASELINE: f64 = 0.5;
+
+const DEFAULT_NEWS_FEEDS: &[(&str, &str)] = &[
+    ("coindesk", "https://www.coindesk.com/arc/outboundfeeds/rss/"),
+    ("cointelegraph", "https://cointelegraph.com/rss"),
+    ("decrypt", "https://decrypt.co/feed"),
+];
+
+const DEFAULT_ANNOUNCEMENT_FEEDS: &[(&str, &str)] = &[
+    ("coinbase", "https://www.coinbase.com/blog/rss.xml"),
+    ("kraken", "https://blog.kraken.com/feed"),
+];
+
+// Lexicon stems, matched at the start of a word; a headline's score is tanh of the weights it contains
+const LEXICON: &[(&str, f64)] = &[
+    ("hack", -1.0), ("exploit", -1.0), ("drained", -1.0), ("rug pull", -1.2), ("delist", -1.0),
+    ("lawsuit", -0.6), ("charges", -0.6), ("banned", -0.7), ("bans", -0.7), ("outage", -0.6), ("halt", -0.6),
+    ("crash", -0.8), ("plunge", -0.7), ("liquidation", -0.5), ("insolvent", -1.0), ("bankrupt", -1.0),
+    ("listing", 0.8), ("lists", 0.6), ("launches", 0.4), ("partnership", 0.5), ("approval", 0.7),
+    ("approves", 0.7), ("etf", 0.3), ("surge", 0.6), ("rally", 0.6), ("record high", 0.7),
+    ("adoption", 0.4), ("upgrade", 0.3), ("integrates", 0.4), ("inflows", 0.4), ("soars", 0.7),
+];
+
+// Names headlines use instead of tickers
+const ASSET_NAMES: &[(&str, &str)] = &[
+    ("bitcoin", "BTC"), ("ethereum", "ETH"), ("ether", "ETH"), ("solana", "SOL"), ("dogecoin", "DOGE"),
+    ("ripple", "XRP"), ("cardano", "ADA"), ("avalanche", "AVAX"), ("shiba inu", "SHIB"), ("pepe", "PEPE"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FeedKind {
+    News,
+    Announcement,
+}
+
+impl FeedKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedKind::News => "news",
+            FeedKind::Announcement => "announcement",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedSource {
+    pub name: String,
+    pub url: String,
+    pub kind: FeedKind,
+}
+
+/// NEWS_FEEDS and ANNOUNCEMENT_FEEDS (comma-separated `name=url`) replace the defaults
+pub fn feeds_from_env() -> Vec<FeedSource> {
+    let parse = |var: &str, defaults: &[(&str, &str)], kind: FeedKind| -> Vec<FeedSource> {
+        match std::env::var(var) {
+            Ok(list) => list.split(',')
+                .filter_map(|entry| entry.trim().split_once('='))
+                .map(|(name, url)| FeedSource { name: name.trim().to_string(), url: url.trim().to_string(), kind })
+                .collect(),
+            Err(_) => defaults.iter()
+                .map(|(name, url)| FeedSource { name: name.to_string(), url: url.to_string(), kind })
+                .collect(),
+        }
+    };
+
+    let mut feeds = parse("NEWS_FEEDS", DEFAULT_NEWS_FEEDS, FeedKind::News);
+    feeds.extend(parse("ANNOUNCEMENT_FEEDS", DEFAULT_ANNOUNCEMENT_FEEDS, FeedKind::Announcement));
+    feeds
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScorerKind {
+    Llm,
+    Lexicon,
+}
+
+impl ScorerKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScorerKind::Llm => "llm",
+            ScorerKind::Lexicon => "lexicon",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "llm" => Some(ScorerKind::Llm),
+            "lexicon" => Some(ScorerKind::Lexicon),
+            _ => None,
+        }
+    }
+
+    /// SENTIMENT_SCORER, defaulting to the LLM (which still falls back to the lexicon)
+    pub fn from_env() -> Self {
+        std::env::var("SENTIMENT_SCORER")
+            .ok()
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or(ScorerKind::Llm)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsItem {
+    pub guid: String,
+    pub source: String,
+    pub kind: FeedKind,
+    pub title: String,
+    pub link: Option<String>,
+    pub published_at: DateTime<Utc>,
+    pub symbols: Vec<String>,
+}
+
+/// Items from one RSS document; undated items are stamped `fetched_at`
+pub fn parse_feed(feed: &FeedSource, body: &[u8], fetched_at: DateTime<Utc>) -> Result<Vec<NewsItem>, rss::Error> {
+    let channel = rss::Channel::read_from(body)?;
+
+    Ok(channel.items().iter()
+        .filter_map(|item| {
+            let title = item.title()?.trim().to_string();
+            let link = item.link().map(str::to_string);
+            let guid = item.guid().map(|g| g.value().to_string())
+                .or_else(|| link.clone())
+                .unwrap_or_else(|| title.clone());
+            let published_at = item.pub_date()
+                .and_then(|d| DateTime::parse_from_rfc2822(d).ok())
+                .map(|d| d.with_timezone(&Utc))
+                .unwrap_or(fetched_at);
+
+            Some(NewsItem {
+                guid: format!("{}:{}", feed.name, guid),
+                source: feed.name.clone(),
+                kind: feed.kind,
+                title,
+                link,
+                published_at,
+                symbols: Vec::new(),
+            })
+        })
+        .collect())
+}
+
+/// Lowercased words separated by single spaces, with a leading space so " term" finds word starts
+fn normalize(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+    format!(" {}", words.join(" "))
+}
+
+fn mentions(normalized: &str, term: &str) -> bool {
+    normalized.contains(&format!(" {}", term))
+}
+
+/// Lexicon score in [-1, 1]
+pub fn lexicon_score(text: &str) -> f64 {
+    let text = normalize(text);
+    LEXICON.iter()
+        .filter(|(term, _)| mentions(&text, term))
+        .map(|(_, weight)| weight)
+        .sum::<f64>()
+        .tanh()
+}
+
+/// Market symbols (e.g. "BTC-USD") whose base asset the text mentions by ticker, $ticker or name
+pub fn tag_symbols(text: &str, markets: &[String]) -> Vec<String> {
+    let tokens: HashSet<&str> = text
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '$'))
+        .map(|t| t.trim_start_matches('$'))
+        .filter(|t| !t.is_empty())
+        .collect();
+    let normalized = normalize(text);
+    let named: HashSet<&str> = ASSET_NAMES.iter()
+        .filter(|(name, _)| mentions(&normalized, name))
+        .map(|(_, ticker)| *ticker)
+        .collect();
+
+    markets.iter()
+        .filter(|market| {
+            let base = market.split(['-', '/']).next().unwrap_or(market);
+            tokens.contains(base) || named.contains(base)
+        })
+        .cloned()
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SentimentMetrics {
+    pub sentiment_1h: f64,  // mean score of the last hour's items, 0 when there are none
+    pub news_shock: f64,    // last hour's total |score| against the hourly mean of the day before
+    pub items_1h: u32,
+}
+
+/// Metrics from (published_at, score) pairs covering at least the last 25 hours
+pub fn compute_metrics(items: &[(DateTime<Utc>, f64)], now: DateTime<Utc>) -> SentimentMetrics {
+    let hour_ago = now - Duration::hours(1);
+    let day_before = hour_ago - Duration::hours(24);
+
+    let recent: Vec<f64> = items.iter().filter(|(t, _)| *t > hour_ago && *t <= now).map(|(_, s)| *s).collect();
+    let baseline: f64 = items.iter().filter(|(t, _)| *t > day_before && *t <= hour_ago).map(|(_, s)| s.abs()).sum::<f64>() / 24.0;
+
+    let sentiment_1h = if recent.is_empty() { 0.0 } else { recent.iter().sum::<f64>() / recent.len() as f64 };
+    let intensity: f64 = recent.iter().map(|s| s.abs()).sum();
+
+    SentimentMetrics {
+        sentiment_1h,
+        news_shock: intensity / baseline.max(MIN_SHOCK_BASELINE),
+        items_1h: recent.len() as u32,
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IngestReport {
+    pub fetched: usize,
+    pub new_items: usize,
+    pub failed_feeds: Vec<String>,
+    pub llm_scored: bool,
+}
+
+pub struct SentimentPipeline {
+    pub feeds: Vec<FeedSource>,
+    pub scorer: ScorerKind,
+    client: Option<IntelligenceClient>,
+    http: reqwest::Client,
+    db_pool: PgPool,
+}
+
+impl SentimentPipeline {
+    pub fn new(db_pool: PgPool, client: Option<IntelligenceClient>) -> Self {
+        SentimentPipeline {
+            feeds: feeds_from_env(),
+            scorer: ScorerKind::from_env(),
+            client,
+            http: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(20))
+                .user_agent("v26meme-sentiment/0.1")
+                .build()
+                .unwrap_or_default(),
+            db_pool,
+        }
+    }
+
+    /// Fetch every feed, store and score the unseen items, then publish fresh metrics
+    pub async fn run_once(&self) -> Result<IngestReport, sqlx::Error> {
+        let mut report = IngestReport::default();
+        let now = Utc::now();
+        let markets = self.load_markets().await?;
+
+        let mut items = Vec::new();
+        for feed in &self.feeds {
+            match self.fetch(feed, now).await {
+                Ok(fetched) => items.extend(fetched),
+                Err(e) => {
+                    warn!("⚠️ News feed {} failed: {}", feed.name, e);
+                    report.failed_feeds.push(feed.name.clone());
+                }
+            }
+        }
+        report.fetched = items.len();
+
+        let guids: Vec<String> = items.iter().map(|i| i.guid.clone()).collect();
+        let seen: HashSet<String> = sqlx::query("SELECT guid FROM news_items WHERE guid = ANY($1)")
+            .bind(&guids)
+            .fetch_all(&self.db_pool)
+            .await?
+            .iter()
+            .map(|row| row.get("guid"))
+            .collect();
+
+        let mut fresh: Vec<NewsItem> = Vec::new();
+        for mut item in items {
+            if seen.contains(&item.guid) || fresh.iter().any(|f| f.guid == item.guid) {
+                continue;
+            }
+            item.symbols = tag_symbols(&item.title, &markets);
+            fresh.push(item);
+        }
+
+        let (scores, scorer, prompt_version) = self.score(&fresh).await;
+        report.llm_scored = scorer == ScorerKind::Llm;
+        for (item, score) in fresh.iter().zip(&scores) {
+            self.store_item(item, *score, scorer, prompt_version).await?;
+        }
+        report.new_items = fresh.len();
+
+        self.publish_metrics(now).await?;
+        Ok(report)
+    }
+
+    async fn fetch(&self, feed: &FeedSource, now: DateTime<Utc>) -> Result<Vec<NewsItem>, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.http.get(&feed.url).send().await?.error_for_status()?.bytes().await?;
+        Ok(parse_feed(feed, &body, now)?)
+    }
+
+    /// LLM scores (with the prompt version used) when configured and the call succeeds, the lexicon otherwise
+    async fn score(&self, items: &[NewsItem]) -> (Vec<f64>, ScorerKind, Option<i32>) {
+        let lexicon = || items.iter().map(|i| lexicon_score(&i.title)).collect::<Vec<f64>>();
+        let (ScorerKind::Llm, Some(client)) = (self.scorer, &self.client) else {
+            return (lexicon(), ScorerKind::Lexicon, None);
+        };
+        if items.is_empty() {
+            return (Vec::new(), ScorerKind::Llm, None);
+        }
+
+        let prompt = HeadlineScorePrompt { headlines: items.iter().map(|i| i.title.clone()).collect() };
+        match client.complete(&prompt).await {
+            Ok(completion) => {
+                let by_index: HashMap<usize, f64> = completion.output.scores.iter().map(|s| (s.index, s.score)).collect();
+                // Headlines the model skipped keep their lexicon score
+                let scores = items.iter().enumerate()
+                    .map(|(i, item)| by_index.get(&i).copied().unwrap_or_else(|| lexicon_score(&item.title)))
+                    .collect();
+                (scores, ScorerKind::Llm, completion.prompt_version)
+            }
+            Err(e) => {
+                warn!("⚠️ LLM headline scoring failed, using lexicon: {}", e);
+                (lexicon(), ScorerKind::Lexicon, None)
+            }
+        }
+    }
+
+    async fn load_markets(&self) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query("SELECT DISTINCT symbol FROM market_data WHERE timestamp > NOW() - INTERVAL '24 hours'")
+            .fetch_all(&self.db_pool)
+            .await?;
+        Ok(rows.iter().map(|row| row.get("symbol")).collect())
+    }
+
+    async fn store_item(&self, item: &NewsItem, score: f64, scorer: ScorerKind, prompt_version: Option<i32>) -> Result<(), sqlx::Error> {
+        sqlx::query("
+            INSERT INTO news_items (guid, source, kind, title, link, published_at, symbols, score, scorer, prompt_version)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (guid) DO NOTHING
+        ")
+            .bind(&item.guid)
+            .bind(&item.source)
+            .bind(item.kind.as_str())
+            .bind(&item.title)
+            .bind(&item.link)
+            .bind(item.published_at)
+            .bind(&item.symbols)
+            .bind(score)
+            .bind(scorer.as_str())
+            .bind(prompt_version)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// One sentiment_metrics row market-wide (symbol NULL) and one per symbol in the news
+    async fn publish_metrics(&self, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+        let rows = sqlx::query("SELECT published_at, score, symbols FROM news_items WHERE published_at > $1")
+            .bind(now - Duration::hours(25))
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        let mut market = Vec::new();
+        let mut by_symbol: HashMap<String, Vec<(DateTime<Utc>, f64)>> = HashMap::new();
+        for row in &rows {
+            let point: (DateTime<Utc>, f64) = (row.get("published_at"), row.get("score"));
+            market.push(point);
+            for symbol in row.get::<Vec<String>, _>("symbols") {
+                by_symbol.entry(symbol).or_default().push(point);
+            }
+        }
+
+        let mut snapshots = vec![(None, compute_metrics(&market, now))];
+        snapshots.extend(by_symbol.iter().map(|(symbol, points)| (Some(symbol.as_str()), compute_metrics(points, now))));
+
+        for (symbol, metrics) in snapshots {
+            sqlx::query("
+                INSERT INTO sentiment_metrics (symbol, sentiment_1h, news_shock, items_1h, computed_at)
+                VALUES ($1, $2, $3, $4, $5)
+            ")
+                .bind(symbol)
+                .bind(metrics.sentiment_1h)
+                .bind(metrics.news_shock)
+                .bind(metrics.items_1h as i32)
+                .bind(now)
+                .execute(&self.db_pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Latest published metrics for `symbol`, or market-wide for None
+pub async fn latest_metrics(db_pool: &PgPool, symbol: Option<&str>) -> Result<Option<SentimentMetrics>, sqlx::Error> {
+    let row = sqlx::query("
+        SELECT sentiment_1h, news_shock, items_1h FROM sentiment_metrics
+        WHERE symbol IS NOT DISTINCT FROM $1
+        ORDER BY computed_at DESC LIMIT 1
+    ")
+        .bind(symbol)
+        .fetch_optional(db_pool)
+        .await?;
+
+    Ok(row.map(|row| SentimentMetrics {
+        sentiment_1h: row.get("sentiment_1h"),
+        news_shock: row.get("news_shock"),
+        items_1h: row.get::<i32, _>("items_1h") as u32,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lexicon_and_symbol_tagging() {
+        assert!(lexicon_score("Exchange drained in $40M hack") < -0.9);
+        assert!(lexicon_score("Coinbase announces listing of PEPE") > 0.5);
+        assert_eq!(lexicon_score("Weekly market wrap"), 0.0);
+
+        let markets = vec!["BTC-USD".to_string(), "PEPE-USD".to_string(), "SOL-USD".to_string()];
+        assert_eq!(tag_symbols("Coinbase lists $PEPE as Bitcoin dips", &markets), vec!["BTC-USD", "PEPE-USD"]);
+        assert!(tag_symbols("Console makers solve supply issues together", &markets).is_empty());
+        assert_eq!(lexicon_score("Bank earnings beat"), 0.0);
+    }
+
+    #[test]
+    fn test_metrics_measure_the_last_hour_against_the_day() {
+        let now = Utc::now();
+        // One mildly scored item per hour all day, then a burst of bad news
+        let mut items: Vec<(DateTime<Utc>, f64)> = (2..26).map(|h| (now - Duration::hours(h), 0.5)).collect();
+        items.extend([(now - Duration::minutes(5), -1.0), (now - Duration::minutes(20), -0.8), (now - Duration::minutes(40), -0.6)]);
+
+        let metrics = compute_metrics(&items, now);
+        assert_eq!(metrics.items_1h, 3);
+        assert!((metrics.sentiment_1h + 0.8).abs() < 1e-9);
+        assert!((metrics.news_shock - 2.4 / 0.5).abs() < 1e-9);
+
+        assert_eq!(compute_metrics(&[], now), SentimentMetrics::default());
+    }
+
+    #[test]
+    fn test_rss_items_are_parsed_and_namespaced() {
+        let feed = FeedSource { name: "coindesk".to_string(), url: String::new(), kind: FeedKind::News };
+        let body = br#"<?xml version="1.0"?><rss version="2.0"><channel><title>t</title><link>l</link><description>d</description>
+            <item><title>Bitcoin rallies</title><link>https://x/1</link><guid>abc</guid><pubDate>Tue, 10 Jun 2025 04:00:00 GMT</pubDate></item>
+            <item><title>No date</title><link>https://x/2</link></item>
+            </channel></rss>"#;
+
+        let now = Utc::now();
+        let items = parse_feed(&feed, body, now).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].guid, "coindesk:abc");
+        assert_eq!(items[0].published_at.to_rfc3339(), "2025-06-10T04:00:00+00:00");
+        assert_eq!(items[1].guid, "coindesk:https://x/2");
+        assert_eq!(items[1].published_at, now);
+    }
+}
@@ -0,0 +1,423 @@
+// Social Scanners
+// Memecoins move on attention before they move on price. Each scanner pulls recent posts from
+// one platform and reports which configured tickers they mention and how much engagement they
+// drew; the monitor stores the mentions and publishes `social_velocity` (mention rate against
+// the day before) and `social_engagement` (engagement rate against the day before) per ticker
+
+use std::collections::{HashMap, HashSet};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use tracing::warn;
+
+use crate::core::secrets;
+
+/// Hourly mentions treated as the quietest normal chatter for a ticker
+const MIN_MENTION_BASELINE: f64 = 1.0;
+/// Hourly engagement treated as the quietest normal reaction
+const MIN_ENGAGEMENT_BASELINE: f64 = 10.0;
+
+const DEFAULT_TICKERS: &str = "DOGE,SHIB,PEPE,WIF,BONK,FLOKI";
+const DEFAULT_SUBREDDITS: &str = "CryptoCurrency,CryptoMoonShots,SatoshiStreetBets,memecoins";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Mention {
+    pub platform: String,
+    pub post_id: String,
+    pub ticker: String,
+    pub posted_at: DateTime<Utc>,
+    pub engagement: f64,  // platform-weighted likes, shares and replies
+}
+
+#[derive(Debug)]
+pub enum ScanError {
+    Http(String),
+    Malformed(String),
+}
+
+impl std::fmt::Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanError::Http(e) => write!(f, "request failed: {}", e),
+            ScanError::Malformed(e) => write!(f, "unexpected response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+impl From<reqwest::Error> for ScanError {
+    fn from(e: reqwest::Error) -> Self {
+        ScanError::Http(e.to_string())
+    }
+}
+
+#[async_trait]
+pub trait SocialScanner: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Mentions of `tickers` in posts from the platform's recent feed
+    async fn scan(&self, tickers: &[String]) -> Result<Vec<Mention>, ScanError>;
+}
+
+/// Tickers the text mentions as a bare uppercase symbol or a $cashtag
+pub fn mentioned_tickers<'a>(text: &str, tickers: &'a [String]) -> Vec<&'a String> {
+    let tokens: HashSet<String> = text
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '$'))
+        .filter_map(|t| match t.strip_prefix('$') {
+            Some(tag) => Some(tag.to_uppercase()),
+            None => (!t.is_empty() && t.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())).then(|| t.to_string()),
+        })
+        .collect();
+
+    tickers.iter().filter(|ticker| tokens.contains(ticker.as_str())).collect()
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(20))
+        .user_agent("v26meme-social/0.1")
+        .build()
+        .unwrap_or_default()
+}
+
+/// Newest posts of public subreddits through Reddit's JSON listings; no credentials needed
+pub struct RedditScanner {
+    pub subreddits: Vec<String>,
+    http: reqwest::Client,
+}
+
+impl RedditScanner {
+    pub fn new(subreddits: Vec<String>) -> Self {
+        RedditScanner { subreddits, http: http_client() }
+    }
+}
+
+/// Engagement = upvotes + 2 × comments
+pub fn parse_reddit_listing(listing: &Value, tickers: &[String]) -> Result<Vec<Mention>, ScanError> {
+    let children = listing.pointer("/data/children")
+        .and_then(Value::as_array)
+        .ok_or_else(|| ScanError::Malformed("listing has no data.children".to_string()))?;
+
+    let mut mentions = Vec::new();
+    for post in children.iter().filter_map(|c| c.get("data")) {
+        let (Some(id), Some(created)) = (post.get("id").and_then(Value::as_str), post.get("created_utc").and_then(Value::as_f64)) else {
+            continue;
+        };
+        let text = format!(
+            "{} {}",
+            post.get("title").and_then(Value::as_str).unwrap_or_default(),
+            post.get("selftext").and_then(Value::as_str).unwrap_or_default(),
+        );
+        let engagement = post.get("score").and_then(Value::as_f64).unwrap_or(0.0).max(0.0)
+            + 2.0 * post.get("num_comments").and_then(Value::as_f64).unwrap_or(0.0);
+        let Some(posted_at) = Utc.timestamp_opt(created as i64, 0).single() else {
+            continue;
+        };
+
+        mentions.extend(mentioned_tickers(&text, tickers).into_iter().map(|ticker| Mention {
+            platform: "reddit".to_string(),
+            post_id: id.to_string(),
+            ticker: ticker.clone(),
+            posted_at,
+            engagement,
+        }));
+    }
+    Ok(mentions)
+}
+
+#[async_trait]
+impl SocialScanner for RedditScanner {
+    fn name(&self) -> &str {
+        "reddit"
+    }
+
+    async fn scan(&self, tickers: &[String]) -> Result<Vec<Mention>, ScanError> {
+        let mut mentions = Vec::new();
+        for subreddit in &self.subreddits {
+            let url = format!("https://www.reddit.com/r/{}/new.json?limit=100", subreddit);
+            let listing: Value = self.http.get(&url).send().await?.error_for_status()?.json().await?;
+            mentions.extend(parse_reddit_listing(&listing, tickers)?);
+        }
+        Ok(mentions)
+    }
+}
+
+/// Recent cashtag posts through the X API v2 recent search; needs a bearer token
+pub struct XScanner {
+    bearer_token: String,
+    http: reqwest::Client,
+}
+
+impl XScanner {
+    pub fn new(bearer_token: String) -> Self {
+        XScanner { bearer_token, http: http_client() }
+    }
+}
+
+/// Engagement = likes + 2 × reposts + replies + quotes
+pub fn parse_x_search(response: &Value, tickers: &[String]) -> Result<Vec<Mention>, ScanError> {
+    let Some(posts) = response.get("data") else {
+        // No matches comes back without a data field
+        return Ok(Vec::new());
+    };
+    let posts = posts.as_array().ok_or_else(|| ScanError::Malformed("data is not a list".to_string()))?;
+
+    let mut mentions = Vec::new();
+    for post in posts {
+        let (Some(id), Some(text), Some(created)) = (
+            post.get("id").and_then(Value::as_str),
+            post.get("text").and_then(Value::as_str),
+            post.get("created_at").and_then(Value::as_str),
+        ) else {
+            continue;
+        };
+        let Ok(posted_at) = DateTime::parse_from_rfc3339(created) else {
+            continue;
+        };
+        let metric = |name: &str| post.pointer(&format!("/public_metrics/{}", name)).and_then(Value::as_f64).unwrap_or(0.0);
+        let engagement = metric("like_count") + 2.0 * metric("retweet_count") + metric("reply_count") + metric("quote_count");
+
+        mentions.extend(mentioned_tickers(text, tickers).into_iter().map(|ticker| Mention {
+            platform: "x".to_string(),
+            post_id: id.to_string(),
+            ticker: ticker.clone(),
+            posted_at: posted_at.with_timezone(&Utc),
+            engagement,
+        }));
+    }
+    Ok(mentions)
+}
+
+#[async_trait]
+impl SocialScanner for XScanner {
+    fn name(&self) -> &str {
+        "x"
+    }
+
+    async fn scan(&self, tickers: &[String]) -> Result<Vec<Mention>, ScanError> {
+        if tickers.is_empty() {
+            return Ok(Vec::new());
+        }
+        let cashtags: Vec<String> = tickers.iter().map(|t| format!("${}", t)).collect();
+        let query = format!("({}) -is:retweet", cashtags.join(" OR "));
+
+        let response: Value = self.http.get("https://api.twitter.com/2/tweets/search/recent")
+            .bearer_auth(&self.bearer_token)
+            .query(&[("query", query.as_str()), ("max_results", "100"), ("tweet.fields", "created_at,public_metrics")])
+            .send().await?
+            .error_for_status()?
+            .json().await?;
+
+        parse_x_search(&response, tickers)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SocialMetrics {
+    pub mentions_1h: u32,
+    pub engagement_1h: f64,
+    pub social_velocity: f64,    // last hour's mentions against the hourly mean of the day before
+    pub social_engagement: f64,  // last hour's engagement against the hourly mean of the day before
+}
+
+/// Metrics from (posted_at, engagement) pairs covering at least the last 25 hours
+pub fn compute_social_metrics(mentions: &[(DateTime<Utc>, f64)], now: DateTime<Utc>) -> SocialMetrics {
+    let hour_ago = now - Duration::hours(1);
+    let day_before = hour_ago - Duration::hours(24);
+
+    let recent: Vec<f64> = mentions.iter().filter(|(t, _)| *t > hour_ago && *t <= now).map(|(_, e)| *e).collect();
+    let baseline: Vec<f64> = mentions.iter().filter(|(t, _)| *t > day_before && *t <= hour_ago).map(|(_, e)| *e).collect();
+
+    let engagement_1h: f64 = recent.iter().sum();
+    let baseline_mentions = baseline.len() as f64 / 24.0;
+    let baseline_engagement = baseline.iter().sum::<f64>() / 24.0;
+
+    SocialMetrics {
+        mentions_1h: recent.len() as u32,
+        engagement_1h,
+        social_velocity: recent.len() as f64 / baseline_mentions.max(MIN_MENTION_BASELINE),
+        social_engagement: engagement_1h / baseline_engagement.max(MIN_ENGAGEMENT_BASELINE),
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanReport {
+    pub mentions: usize,
+    pub failed_scanners: Vec<String>,
+}
+
+pub struct SocialMonitor {
+    pub tickers: Vec<String>,
+    scanners: Vec<Box<dyn SocialScanner>>,
+    db_pool: PgPool,
+}
+
+impl SocialMonitor {
+    pub fn new(db_pool: PgPool, tickers: Vec<String>) -> Self {
+        SocialMonitor {
+            tickers,
+            scanners: Vec::new(),
+            db_pool,
+        }
+    }
+
+    /// SOCIAL_TICKERS and SOCIAL_SUBREDDITS (comma-separated); X joins when X_BEARER_TOKEN is set
+    pub fn from_env(db_pool: PgPool) -> Self {
+        let list = |var: &str, default: &str| -> Vec<String> {
+            std::env::var(var).unwrap_or_else(|_| default.to_string())
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        };
+
+        let tickers = list("SOCIAL_TICKERS", DEFAULT_TICKERS).into_iter().map(|t| t.to_uppercase()).collect();
+        let mut monitor = SocialMonitor::new(db_pool, tickers);
+        monitor.add_scanner(Box::new(RedditScanner::new(list("SOCIAL_SUBREDDITS", DEFAULT_SUBREDDITS))));
+        if let Ok(token) = secrets::var("X_BEARER_TOKEN") {
+            if !token.trim().is_empty() {
+                monitor.add_scanner(Box::new(XScanner::new(token)));
+            }
+        }
+        monitor
+    }
+
+    pub fn add_scanner(&mut self, scanner: Box<dyn SocialScanner>) {
+        self.scanners.push(scanner);
+    }
+
+    pub fn scanner_names(&self) -> Vec<&str> {
+        self.scanners.iter().map(|s| s.name()).collect()
+    }
+
+    /// Run every scanner, store new mentions, and publish metrics for every configured ticker
+    pub async fn run_once(&self) -> Result<ScanReport, sqlx::Error> {
+        let mut report = ScanReport::default();
+        let now = Utc::now();
+
+        for scanner in &self.scanners {
+            match scanner.scan(&self.tickers).await {
+                Ok(mentions) => {
+                    report.mentions += mentions.len();
+                    for mention in &mentions {
+                        self.store_mention(mention).await?;
+                    }
+                }
+                Err(e) => {
+                    warn!("⚠️ Social scanner {} failed: {}", scanner.name(), e);
+                    report.failed_scanners.push(scanner.name().to_string());
+                }
+            }
+        }
+
+        self.publish_metrics(now).await?;
+        Ok(report)
+    }
+
+    async fn store_mention(&self, mention: &Mention) -> Result<(), sqlx::Error> {
+        sqlx::query("
+            INSERT INTO social_mentions (platform, post_id, ticker, posted_at, engagement)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (platform, post_id, ticker) DO UPDATE SET engagement = EXCLUDED.engagement
+        ")
+            .bind(&mention.platform)
+            .bind(&mention.post_id)
+            .bind(&mention.ticker)
+            .bind(mention.posted_at)
+            .bind(mention.engagement)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn publish_metrics(&self, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+        let rows: Vec<(String, DateTime<Utc>, f64)> = sqlx::query_as(
+            "SELECT ticker, posted_at, engagement FROM social_mentions WHERE posted_at > $1 AND ticker = ANY($2)"
+        )
+            .bind(now - Duration::hours(25))
+            .bind(&self.tickers)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        let mut by_ticker: HashMap<&str, Vec<(DateTime<Utc>, f64)>> = HashMap::new();
+        for (ticker, posted_at, engagement) in &rows {
+            by_ticker.entry(ticker.as_str()).or_default().push((*posted_at, *engagement));
+        }
+
+        for ticker in &self.tickers {
+            let metrics = compute_social_metrics(by_ticker.get(ticker.as_str()).map(Vec::as_slice).unwrap_or_default(), now);
+            sqlx::query("
+                INSERT INTO social_metrics (ticker, mentions_1h, engagement_1h, social_velocity, social_engagement, computed_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+            ")
+                .bind(ticker)
+                .bind(metrics.mentions_1h as i32)
+                .bind(metrics.engagement_1h)
+                .bind(metrics.social_velocity)
+                .bind(metrics.social_engagement)
+                .bind(now)
+                .execute(&self.db_pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tickers() -> Vec<String> {
+        vec!["PEPE".to_string(), "WIF".to_string(), "DOGE".to_string()]
+    }
+
+    #[test]
+    fn test_tickers_match_cashtags_and_uppercase_only() {
+        let t = tickers();
+        assert_eq!(mentioned_tickers("$pepe and WIF to the moon", &t), vec!["PEPE", "WIF"]);
+        assert!(mentioned_tickers("my doge is a good boy", &t).is_empty());
+    }
+
+    #[test]
+    fn test_reddit_and_x_responses_parse() {
+        let listing = json!({"data": {"children": [
+            {"data": {"id": "a1", "title": "PEPE breakout?", "selftext": "", "created_utc": 1718000000.0, "score": 40, "num_comments": 5}},
+            {"data": {"id": "a2", "title": "Weekly discussion", "selftext": "nothing here", "created_utc": 1718000100.0, "score": 3, "num_comments": 1}}
+        ]}});
+        let mentions = parse_reddit_listing(&listing, &tickers()).unwrap();
+        assert_eq!(mentions.len(), 1);
+        assert_eq!(mentions[0].engagement, 50.0);
+
+        let search = json!({"data": [
+            {"id": "9", "text": "$WIF $DOGE sending", "created_at": "2025-06-10T04:00:00.000Z",
+             "public_metrics": {"like_count": 10, "retweet_count": 3, "reply_count": 2, "quote_count": 1}}
+        ]});
+        let mentions = parse_x_search(&search, &tickers()).unwrap();
+        assert_eq!(mentions.len(), 2);
+        assert_eq!(mentions[0].engagement, 19.0);
+        assert!(parse_x_search(&json!({"meta": {"result_count": 0}}), &tickers()).unwrap().is_empty());
+        assert!(parse_reddit_listing(&json!({"error": 429}), &tickers()).is_err());
+    }
+
+    #[test]
+    fn test_velocity_compares_last_hour_to_the_day() {
+        let now = Utc::now();
+        // Two mentions an hour all day, then twelve in the last hour
+        let mut mentions: Vec<(DateTime<Utc>, f64)> = (1..25).flat_map(|h| [(now - Duration::hours(h), 10.0); 2]).collect();
+        mentions.extend((0..12).map(|i| (now - Duration::minutes(i * 4 + 1), 20.0)));
+
+        let metrics = compute_social_metrics(&mentions, now);
+        assert_eq!(metrics.mentions_1h, 12);
+        assert!((metrics.social_velocity - 6.0).abs() < 1e-9);
+        assert!((metrics.social_engagement - 12.0).abs() < 1e-9);
+
+        // Quiet tickers sit against the floor, not a zero baseline
+        assert_eq!(compute_social_metrics(&[(now, 5.0)], now).social_velocity, 1.0);
+    }
+}
@@ -0,0 +1,67 @@
+// Strategist
+// The scheduled intelligence tasks: gather context from the database, run the prompt, and
+// store what comes back for the rest of the system to read
+
+use sqlx::PgPool;
+use tracing::warn;
+
+use crate::intelligence::client::{IntelligenceClient, IntelligenceError};
+use crate::core::seeding::HypothesisTemplate;
+use crate::intelligence::prompts::{load_market_summaries, SentimentPrompt, SentimentReport, TemplatePrompt};
+
+/// Symbols described to the model per sentiment run
+const SENTIMENT_MARKETS: i64 = 30;
+
+/// Templates requested per seeding refresh
+const TEMPLATES_PER_REFRESH: usize = 8;
+
+pub struct Strategist {
+    client: IntelligenceClient,
+    db_pool: PgPool,
+}
+
+impl Strategist {
+    pub fn new(client: IntelligenceClient, db_pool: PgPool) -> Self {
+        Strategist { client, db_pool }
+    }
+
+    /// Score market-wide sentiment from the last day of price action and store the report
+    pub async fn analyze_sentiment(&self, headlines: Vec<String>) -> Result<SentimentReport, IntelligenceError> {
+        let markets = load_market_summaries(&self.db_pool, SENTIMENT_MARKETS).await?;
+        let completion = self.client.complete(&SentimentPrompt { markets, headlines }).await?;
+        let report = completion.output;
+
+        sqlx::query("
+            INSERT INTO sentiment_reports (overall_sentiment, fear_greed_index, report, model, prompt_version)
+            VALUES ($1, $2, $3, $4, $5)
+        ")
+            .bind(report.overall_sentiment)
+            .bind(report.fear_greed_index)
+            .bind(serde_json::to_value(&report).unwrap_or_default())
+            .bind(&completion.model)
+            .bind(completion.prompt_version)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(report)
+    }
+
+    /// Fresh hypothesis templates for the LLM seeding arm; templates that break the
+    /// discovery vocabulary or bounds are dropped
+    pub async fn propose_templates(&self) -> Result<Vec<HypothesisTemplate>, IntelligenceError> {
+        let markets = load_market_summaries(&self.db_pool, SENTIMENT_MARKETS).await?;
+        let completion = self.client.complete(&TemplatePrompt { markets, count: TEMPLATES_PER_REFRESH }).await?;
+
+        let prompt_version = completion.prompt_version;
+        Ok(completion.output.templates.into_iter()
+            .map(|t| HypothesisTemplate { prompt_version, ..t })
+            .filter(|t| match t.check() {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("⚠️ Dropping proposed template {}", e);
+                    false
+                }
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,8 @@
+// V26MEME shared library
+// Components live here so the orchestrator (main.rs) and the standalone
+// binaries in bin/ run the exact same code
+
+pub mod config;
+pub mod core;
+pub mod intelligence;
+pub mod risk;
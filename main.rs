@@ -1,159 +1,1258 @@
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use clap::{Args, Parser, Subcommand};
+use tokio::sync::mpsc;
 use tokio::time::{interval, Duration};
-use chrono::Utc;
-use log::{info, error};
+use tracing::{info, warn, error};
 use sqlx::PgPool;
+use uuid::Uuid;
 
-mod core;
-use core::{discovery_engine::DiscoveryEngine, risk_manager::RiskManager};
+use v26meme::config::{self, AppConfig, Phase, PhaseIntervals, Profile};
+use v26meme::core::{
+    accounts::{AccountBook, AccountConfig, AccountRole}, alerts::{AlertManager, Severity}, anomaly::{self, AnomalyConfig, AnomalyDetector}, audit::{self, AuditAction, AuditEntry, AuditFilter, AuditLogger, Component}, autoscale::RequestMeter, backtest::{self, BacktestReport}, backtest_pool::{self, CandidateReport, PoolSettings, Progress}, batch_writer::{BatchConfig, BatchWriter}, bus::{self, Bus, BusConfig}, cadence::{CycleTrigger, EvolutionCadence}, chaos::{ChaosConfig, FaultInjector}, clock::{Calendar, TradingClock}, control_api::{self, ControlState, Role}, costs::CostModel, daily_report::{self, DailyReport, DayRollover}, db,
+    discovery_engine::DiscoveryEngine, drift_report::DriftTracker, embedding::PatternIndex, equity::{self, EquityTracker}, evaluator,
+    evolution_engine::{EvolutionConfig, EvolutionEngine},
+    exchange::{Exchange, PaperExchange},
+    execution_context::{ExecutionContext, Venue}, execution_engine::{ExecutionEngine, OrderIntent}, execution_quality::Decision, execution_style, feature_flags::FeatureFlags,
+    health::{self, CheckStatus, HealthReport, HealthState, SubsystemCheck}, heartbeat::Heartbeat, hot_reload::ConfigWatcher, incidents::IncidentManager, interchange::{self, BundleFormat, PatternBundle}, journal::{self, JournalFormat}, ledger::Ledger, live_feed::{self, LiveEvent, LiveFeed}, market_data::MarketDataFeed, money, monte_carlo::{self, MonteCarloConfig},
+    order_manager::OrderManager, pattern_replay::{self, ReplayEvent}, readiness::{Gate, Startup}, patterns, performance, plugins::{self, PluginService, SubmissionQueue}, preflight::{Preflight, PreflightConfig}, reconciliation::Reconciler, retention::Retention, review_queue::ReviewQueue, risk_manager::{ReduceOnlyReason, RiskManager},
+    rng::SharedRng, run_manifest::RunManifest, secrets, seeding::{self, TemplatePool}, shutdown::{self, Shutdown}, tuning::SharedTuning,
+    stop_engine::{StopEngine, TrailingConfig}, stress::{self, Scenario, StressConfig, StressStrategy}, tax_lots::{self, LotMethod, TaxReport, Term}, telemetry, webhooks::{WebhookEvent, WebhookNotifier},
+};
+use v26meme::intelligence::{budget, BudgetConfig, IntelligenceClient, IntelligenceConfig, IntelligenceError, SentimentPipeline, SocialMonitor, Strategist};
+use v26meme::risk::{correlation, liquidity, var};
+
+/// V26MEME autonomous trading system; with no command it runs the full system
+#[derive(Parser)]
+#[command(name = "v26meme", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Config profile from config/profiles/ (conservative, standard, degen); V26MEME_PROFILE also works
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    /// Start only these phases, e.g. `discovery,monitor` (discovery, intelligence, execution, evolution, monitor); V26MEME_PHASES also works
+    #[arg(long, global = true)]
+    phases: Option<String>,
+    /// Route every order through the paper exchange and record the run as a dry run; DRY_RUN=true also works
+    #[arg(long, global = true)]
+    dry_run: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Start discovery, intelligence, execution and evolution (the default)
+    Run,
+    /// Re-score patterns from their recorded test trades under today's activation thresholds
+    Backtest(BacktestArgs),
+    /// Replay flash crash, exchange outage, gap down and fee spike scenarios against the active patterns; exits non-zero if risk would not contain one
+    StressTest(StressArgs),
+    /// Project equity by resampling the active patterns' closed trades: percentile bands and probability of ruin
+    MonteCarlo(MonteCarloArgs),
+    /// Embed stored patterns that have no embedding yet
+    Backfill {
+        #[arg(long, default_value_t = 10_000)]
+        limit: i64,
+    },
+    /// List, retire and export discovered patterns
+    #[command(subcommand)]
+    Patterns(PatternsCommand),
+    /// Inspect risk state
+    #[command(subcommand)]
+    Risk(RiskCommand),
+    /// Print audit log decisions in order, optionally for one run, subject or component
+    Replay(ReplayArgs),
+    /// Walk one pattern over stored ticks, printing every minute's condition values beside its recorded decisions
+    ReplayPattern(ReplayPatternArgs),
+    /// Export every fill as CSV or Parquet for pandas or a spreadsheet; to stdout when no path is given
+    Journal(JournalArgs),
+    /// Year-end realized gains by tax lot, as a Form 8949-style CSV; to stdout when no path is given
+    Tax(TaxArgs),
+    /// Run one retention pass now: archive, roll up and prune ticks and test results past their window
+    Retention,
+    /// Serve the live websocket feed from the message bus, for a dashboard on another host
+    Feed,
+    /// Print the configuration in force after file and env overrides, or what is wrong with it
+    Config,
+    /// Apply pending database migrations and exit
+    Migrate,
+    /// Check the database, exchange keys and balances, clock skew and venue websockets; exits non-zero if anything is down
+    Doctor,
+}
+
+#[derive(Args)]
+struct BacktestArgs {
+    /// Pattern hashes to score
+    patterns: Vec<String>,
+    /// Score every active pattern instead
+    #[arg(long)]
+    active: bool,
+    /// Replay the candidates waiting for activation over stored ticks instead, on every core
+    #[arg(long)]
+    queue: bool,
+    /// Only tests from the last N days; with --queue, ticks from the last N days (default 7)
+    #[arg(long)]
+    days: Option<i64>,
+    /// With --queue, how many candidates, newest first
+    #[arg(long, default_value_t = 5000)]
+    limit: i64,
+    /// With --queue, rayon threads walking candidates; 0 is one per core
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+    /// Scale recorded fees and slippage, e.g. 2.0 to ask what doubled costs would do
+    #[arg(long, default_value_t = 1.0)]
+    cost_multiplier: f64,
+    /// Defaults to discovery's own threshold
+    #[arg(long)]
+    min_tests: Option<u32>,
+    /// Defaults to discovery's own threshold
+    #[arg(long)]
+    min_win_rate: Option<f64>,
+}
+
+#[derive(Args)]
+struct StressArgs {
+    /// flash_crash, exchange_outage, gap_down or fee_spike; repeat for several, every scenario when omitted
+    #[arg(long = "scenario")]
+    scenarios: Vec<String>,
+    /// Stop distance below entry for every position
+    #[arg(long, default_value_t = 0.05)]
+    stop_loss: f64,
+    /// Defaults to initial_capital
+    #[arg(long)]
+    capital: Option<f64>,
+}
+
+#[derive(Args)]
+struct MonteCarloArgs {
+    /// Simulated paths; MONTE_CARLO_PATHS, else 10000
+    #[arg(long)]
+    paths: Option<usize>,
+    /// Days to project; MONTE_CARLO_HORIZON_DAYS, else 30
+    #[arg(long)]
+    horizon_days: Option<u32>,
+    /// Share of starting equity lost that counts as ruin; MONTE_CARLO_RUIN_PCT, else 0.5
+    #[arg(long)]
+    ruin: Option<f64>,
+    /// Sample closed trades from the last N days; MONTE_CARLO_LOOKBACK_DAYS, else 90
+    #[arg(long)]
+    days: Option<i64>,
+    /// Starting equity; defaults to the latest equity snapshot, else initial_capital
+    #[arg(long)]
+    capital: Option<f64>,
+}
+
+#[derive(Subcommand)]
+enum PatternsCommand {
+    /// Leaderboard by Sharpe
+    List {
+        /// Include inactive patterns
+        #[arg(long)]
+        all: bool,
+        #[arg(long, default_value_t = 25)]
+        limit: i64,
+    },
+    /// Take patterns out of live trading; history and test results are kept
+    Retire {
+        #[arg(required = true)]
+        hashes: Vec<String>,
+        /// Reason code recorded in the audit log
+        #[arg(long, default_value = "operator_retired")]
+        reason: String,
+    },
+    /// Write genomes and stats as a pattern bundle; to stdout when no path is given
+    Export {
+        path: Option<String>,
+        /// json or toml; defaults to the path's extension, else json
+        #[arg(long)]
+        format: Option<String>,
+        #[arg(long)]
+        active_only: bool,
+    },
+    /// Load a pattern bundle; known hashes are skipped
+    Import {
+        path: String,
+        /// json or toml; defaults to the path's extension
+        #[arg(long)]
+        format: Option<String>,
+        /// Keep stats and activation (for bundles from an instance you control); otherwise
+        /// every pattern arrives inactive and must re-earn activation
+        #[arg(long)]
+        keep_stats: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum RiskCommand {
+    /// Configured limits, today's equity and drawdown, and recent breaker activity
+    Status,
+}
+
+#[derive(Args)]
+struct ReplayArgs {
+    #[arg(long)]
+    run: Option<Uuid>,
+    /// A pattern hash, position id or "system"
+    #[arg(long)]
+    subject: Option<String>,
+    /// discovery, risk, execution or control
+    #[arg(long)]
+    component: Option<String>,
+    /// How far back to start
+    #[arg(long, default_value_t = 24)]
+    hours: i64,
+    #[arg(long, default_value_t = 500)]
+    limit: i64,
+}
+
+#[derive(Args)]
+struct ReplayPatternArgs {
+    hash: String,
+    /// Defaults to the pattern's own symbol; required for patterns that trade any symbol
+    #[arg(long)]
+    symbol: Option<String>,
+    /// Start of the window, e.g. 2026-03-14T14:00:00Z; defaults to --hours before now
+    #[arg(long)]
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Length of the window
+    #[arg(long, default_value_t = 24)]
+    hours: i64,
+    /// Print only minutes that signalled, and the recorded decisions
+    #[arg(long)]
+    signals_only: bool,
+}
+
+#[derive(Args)]
+struct JournalArgs {
+    path: Option<String>,
+    /// csv or parquet; defaults to the path's extension, else csv
+    #[arg(long)]
+    format: Option<String>,
+    /// Only fills from the last N days
+    #[arg(long)]
+    days: Option<i64>,
+    /// Only fills for one pattern hash
+    #[arg(long)]
+    pattern: Option<String>,
+}
+
+#[derive(Args)]
+struct TaxArgs {
+    /// Calendar year of the sales, in UTC
+    year: i32,
+    path: Option<String>,
+    /// fifo or lifo
+    #[arg(long, default_value = "fifo")]
+    method: String,
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize logging
-    env_logger::init();
+async fn main() -> ExitCode {
+    // Load environment first so RUST_LOG and LOG_FORMAT from .env apply to logging
+    dotenv::dotenv().ok();
+    secrets::install_panic_hook();
+    let cli = Cli::parse();
+    if let Some(profile) = &cli.profile {
+        config::select_profile(profile);
+    }
+    if let Some(phases) = &cli.phases {
+        config::select_phases(phases);
+    }
+    let dry_run = cli.dry_run || std::env::var("DRY_RUN").map(|v| v.trim().eq_ignore_ascii_case("true")).unwrap_or(false);
+    let command = cli.command.unwrap_or(Command::Run);
+    
+    // Credentials come from SECRETS_SOURCE before any command reads one, and errors are
+    // printed through the same redaction as log lines
+    let result = match secrets::init().await {
+        Ok(()) => dispatch(command, dry_run).await,
+        Err(e) => Err(e.into()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", secrets::redact(&e.to_string()));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn dispatch(command: Command, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        Command::Run => run(dry_run).await,
+        Command::Backtest(args) => backtest(args).await,
+        Command::StressTest(args) => stress_test(args).await,
+        Command::MonteCarlo(args) => monte_carlo_projection(args).await,
+        Command::Backfill { limit } => backfill(limit).await,
+        Command::Patterns(command) => patterns_command(command).await,
+        Command::Risk(RiskCommand::Status) => risk_status().await,
+        Command::Replay(args) => replay(args).await,
+        Command::ReplayPattern(args) => replay_pattern(args).await,
+        Command::Journal(args) => export_journal(args).await,
+        Command::Tax(args) => tax_report(args).await,
+        Command::Retention => retention_pass().await,
+        Command::Feed => relay_feed().await,
+        Command::Config => show_config(),
+        Command::Migrate => migrate().await,
+        Command::Doctor => doctor(dry_run).await,
+    }
+}
+
+async fn run(dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let _telemetry = telemetry::init();
     
     info!("🚀 V26MEME Autonomous Trading Intelligence Starting");
     info!("   Target: $200 → $1,000,000 in 90 days");
     info!("   Mode: Fully autonomous discovery");
+    if secrets::loaded() > 0 {
+        info!("🔐 {} credentials loaded from {}", secrets::loaded(), std::env::var("SECRETS_SOURCE").unwrap_or_default());
+    }
     
-    // Load environment
-    dotenv::dotenv().ok();
+    // config/v26meme.toml and config/risk.toml (or APP_CONFIG / RISK_CONFIG) plus env overrides;
+    // a bad value anywhere aborts startup
+    let config = AppConfig::load()?;
     
     // Initialize database
-    let database_url = std::env::var("DATABASE_URL")
+    let database_url = secrets::var("DATABASE_URL")
         .expect("DATABASE_URL must be set");
-    let db_pool = PgPool::connect(&database_url).await?;
-    
-    // Run database migrations
-    sqlx::migrate!("./migrations").run(&db_pool).await?;
+    let db_pool = db::connect(&database_url, &config.database).await?;
+    let migrator = sqlx::migrate!("./migrations");
+    let phases = config.phases;
+    // Daily reports, "today" and the evolution schedule follow [clock]
+    let clock = TradingClock::system(config.clock.calendar());
+    if clock.calendar != Calendar::default() {
+        info!("🕰️ Trading days start at {} {}", clock.calendar.day_start.format("%H:%M"), clock.calendar.timezone_name());
+    }
+    if let Some(profile) = &config.profile {
+        info!("🎚️ Profile: {} ({})", profile, Profile::path(profile));
+    }
+    let skipped: Vec<&str> = Phase::ALL.iter().filter(|p| !config.runs(**p)).map(Phase::as_str).collect();
+    if !skipped.is_empty() {
+        info!("⏭️ Not starting: {}", skipped.join(", "));
+    }
     
     // Initialize risk manager with starting capital
-    let starting_capital = std::env::var("INITIAL_CAPITAL")
-        .unwrap_or_else(|_| "200.0".to_string())
-        .parse::<f64>()?;
-    
-    let risk_manager = Arc::new(RiskManager::new(starting_capital));
+    let starting_capital = config.initial_capital;
+    let mut risk_manager = RiskManager::with_config(money::from_f64(starting_capital), config.risk.clone());
+    risk_manager.set_clock(clock.source());
+    risk_manager.set_symbol_universe(config.symbol_universe.clone());
     
     info!("💰 Starting capital: ${:.2}", starting_capital);
     
+    // Record exactly what code and config this run uses
+    // Every random draw comes from one seed, recorded so V26MEME_SEED can replay the run
+    let rng = SharedRng::from_env();
+    let mut manifest = RunManifest::collect(risk_manager.risk_limits())
+        .with_symbol_universe(config.symbol_universe.clone())
+        .with_seed(rng.seed());
+    // Settled once: only this context can hand the execution engine a venue
+    let context = ExecutionContext::for_run(dry_run, &manifest);
+    if context.is_dry_run() {
+        manifest = manifest.with_trading_mode(context.as_str());
+        info!("🧪 Dry run: every order goes to the paper exchange, every row is tagged with run {}", manifest.run_id);
+    }
+    manifest.log_banner();
+    // CHAOS_ENABLED breaks venues, writes and the market data stream on purpose; never in a live run
+    let chaos = FaultInjector::for_context(ChaosConfig::from_env(), context, &rng);
+    
+    // Configured [[accounts]] each get their own keys and capital partition; otherwise every
+    // venue of the run is one account with an even share and no budget of its own
+    let accounts = if config.accounts.is_empty() {
+        AccountConfig::per_venue(&manifest.venues)
+    } else {
+        let book = Arc::new(AccountBook::new(config.accounts.clone(), money::from_f64(starting_capital)));
+        for account in book.accounts() {
+            info!("🏦 Account {} on {} ({}): ${:.2}", account.name, account.venue, account.role.as_str(),
+                  book.balance(&account.name).unwrap_or_default());
+        }
+        risk_manager.set_accounts(book);
+        config.accounts.clone()
+    };
+    
+    // Venues are connected before any phase starts so preflight can check them: keys, balances,
+    // clock skew and websockets. A live run does not start with a check down
+    let market_data = MarketDataFeed::with_settings(&config.market_data).with_chaos(chaos.clone());
+    let venues = if config.runs(Phase::Execution) {
+        connect_exchanges(context, &accounts, &market_data, starting_capital)?
+    } else {
+        Vec::new()
+    };
+    let preflight = Preflight {
+        config: PreflightConfig::from_env(),
+        venues: venues.clone(),
+        accounts: if venues.is_empty() { Vec::new() } else { accounts.clone() },
+        starting_capital,
+    };
+    // Migrations and the run manifest go in while the venues are checked, which needs no database
+    let startup = Startup::new(market_data.ready().clone());
+    let migrate = async {
+        migrator.run(&db_pool).await?;
+        manifest.persist(&db_pool).await?;
+        startup.migrations.open();
+        Ok::<_, Box<dyn std::error::Error>>(())
+    };
+    let venue_checks = async {
+        let checks = preflight.venue_checks().await;
+        startup.venues.open();
+        checks
+    };
+    let (migrated, venue_checks) = tokio::join!(migrate, venue_checks);
+    migrated?;
+    let mut checks = preflight.database_checks(&db_pool, &migrator).await;
+    checks.extend(venue_checks);
+    let report = HealthReport::from_checks(checks);
+    for check in &report.checks {
+        match check.status {
+            CheckStatus::Ok => info!("{}", check_line(check)),
+            _ => warn!("{}", check_line(check)),
+        }
+    }
+    if report.status == CheckStatus::Down {
+        if context.is_live() {
+            return Err("preflight failed; `v26meme doctor` shows what is down".into());
+        }
+        warn!("⚠️ Preflight failed; starting anyway because this is a {} run", context.as_str());
+    }
+    // Every venue call counts against its rate limit; discovery's autoscaler reads the headroom
+    let venue_requests = RequestMeter::default();
+    let venues: Vec<Venue> = venues.into_iter().map(|venue| venue.with_chaos(&chaos).metered(&venue_requests)).collect();
+    // The feed warms up while the phases start; execution waits for its first tick
+    if config.runs(Phase::Execution) {
+        let (feed, db_pool) = (market_data.clone(), db_pool.clone());
+        tokio::spawn(async move {
+            feed.poll_database(db_pool, Duration::from_millis(phases.market_data_poll_ms)).await;
+        });
+    }
+    
+    // Every approval, rejection and activation lands in audit_log, tagged with this run
+    let audit = AuditLogger::new(db_pool.clone(), Some(manifest.run_id));
+    risk_manager.set_audit_logger(audit.clone());
+    // Positions, fills and breaker trips are written through to Postgres as they change
+    let ledger = Ledger::start(db_pool.clone(), Some(manifest.run_id));
+    risk_manager.set_ledger(ledger.clone());
+    // Test results (and ticks from venue streams) are inserted in batches rather than one by one
+    let batch_config = BatchConfig::from_env();
+    info!("📦 Buffered writes flush every {}ms or at {} rows", batch_config.flush_interval.as_millis(), batch_config.batch_size);
+    let batch_writer = BatchWriter::start(db_pool.clone(), batch_config);
+    // Feature flags are set through the control API and re-read so every process follows
+    let flags = FeatureFlags::new(db_pool.clone());
+    match flags.refresh().await {
+        Ok(0) => {}
+        Ok(n) => info!("🚩 {} feature flags set: {}", n, serde_json::to_string(&flags.snapshot()).unwrap_or_default()),
+        Err(e) => warn!("⚠️ Could not read feature flags, using defaults: {}", e),
+    }
+    flags.clone().watch(Duration::from_secs(phases.flag_refresh_secs));
+    
+    // Emergency stops, breakers and stuck flattens page Discord / Telegram / email
+    let alerts = Arc::new(AlertManager::from_env_with(config.alerts.alert_config()));
+    info!("📨 Alert channels: {:?}", alerts.channel_names());
+    risk_manager.set_alert_manager(alerts);
+    let incidents = Arc::new(IncidentManager::from_env());
+    if let Some(provider) = incidents.provider() {
+        info!("📟 Emergency stops escalate to {}; re-arming needs an acknowledged incident", provider);
+    }
+    risk_manager.set_incident_manager(incidents);
+    // Signed lifecycle webhooks for users' own automation
+    let webhooks = WebhookNotifier::from_env().map(Arc::new);
+    if let Some(webhooks) = &webhooks {
+        risk_manager.set_webhooks(webhooks.clone());
+    }
+    // Equity, positions, fills and activations stream to dashboards over a websocket
+    let live_feed = LiveFeed::default();
+    risk_manager.set_live_feed(live_feed.clone());
+    let risk_manager = Arc::new(risk_manager);
+    
+    // Operators can boot straight into reduce-only (e.g. during venue maintenance)
+    if std::env::var("REDUCE_ONLY").map(|v| v == "true").unwrap_or(false) {
+        risk_manager.enter_reduce_only(ReduceOnlyReason::Manual("REDUCE_ONLY set at startup".to_string()));
+    }
+    
+    // Dead-man's switch: execution halts if the risk monitor or discovery goes quiet
+    let dead_man_secs = config.dead_man_secs;
+    let heartbeat = Arc::new(Heartbeat::new());
+    
+    // SIGTERM / Ctrl-C wind everything down in order instead of killing it mid-flight
+    let shutdown = Shutdown::default();
+    
     // PHASE 1: Start Discovery Engine (MOST CRITICAL)
-    info!("🔬 Starting Discovery Engine - Phase 1");
     let mut discovery_engine = DiscoveryEngine::new(db_pool.clone());
-    let discovery_handle = tokio::spawn(async move {
-        discovery_engine.run_discovery_loop().await;
-    });
-    
-    // Wait for discovery engine to generate initial patterns
-    tokio::time::sleep(Duration::from_secs(10)).await;
+    discovery_engine.audit = Some(audit.clone());
+    discovery_engine.set_run_id(manifest.run_id);
+    discovery_engine.batch_writer = Some(batch_writer.clone());
+    discovery_engine.flags = flags.clone();
+    discovery_engine.clock = clock.source();
+    discovery_engine.set_rng(&rng);
+    discovery_engine.chaos = chaos.clone();
+    discovery_engine.venue_requests = venue_requests;
+    let test_account = accounts.iter().find(|a| a.role == AccountRole::Discovery).or(accounts.first());
+    if let Some(account) = test_account {
+        discovery_engine.test_venue = account.venue.clone();
+    }
+    discovery_engine.configure(&config.discovery, risk_manager.discovery_capital());
+    // Discovery beats once per hypothesis, so allow it at least three of its cycles
+    let discovery_cycle = 3600 / discovery_engine.hypotheses_per_hour.max(1) as u64;
+    if config.runs(Phase::Discovery) {
+        heartbeat.expect("discovery", Duration::from_secs(dead_man_secs.max(3 * discovery_cycle)));
+    }
+    if config.runs(Phase::Monitor) {
+        heartbeat.expect("risk", Duration::from_secs(dead_man_secs));
+    }
+    discovery_engine.heartbeat = Some(heartbeat.clone());
+    discovery_engine.live_feed = Some(live_feed.clone());
+    discovery_engine.webhooks = webhooks.clone();
+    // Held until crash recovery has rebuilt the book (phase 3)
+    let discovery_paused = discovery_engine.paused.clone();
+    discovery_paused.store(true, Ordering::SeqCst);
+    let offspring = discovery_engine.offspring.clone();
+    let submissions = discovery_engine.submissions.clone();
+    let tuning = discovery_engine.tuning.clone();
+    let test_budget = discovery_engine.test_budget.clone();
+    let templates = discovery_engine.templates.clone();
+    let (discovery_handle, sentiment_handle, social_handle) = if config.runs(Phase::Discovery) {
+        info!("🔬 Starting Discovery Engine - Phase 1");
+        let discovery_handle = tokio::spawn(async move {
+            // Patterns stored before embeddings existed (or under an older layout) need one to be
+            // found; discovery is held until recovery anyway, so the other phases don't wait for this
+            match discovery_engine.pattern_index.backfill(10_000).await {
+                Ok(0) => {}
+                Ok(n) => info!("🧭 Embedded {} existing patterns", n),
+                Err(e) => error!("Pattern embedding backfill failed: {}", e),
+            }
+            discovery_engine.run_discovery_loop().await;
+        });
+        
+        // News and announcement feeds feed the sentiment_1h / news_shock metrics; headlines are
+        // scored by the LLM only while the intelligence phase runs
+        let sentiment_handle = start_sentiment_pipeline(db_pool.clone(), config.runs(Phase::Intelligence), phases).await;
+        
+        // Memecoin mention velocity from Reddit (and X with a bearer token)
+        let social_handle = start_social_scanners(db_pool.clone(), phases).await;
+        (discovery_handle, sentiment_handle, social_handle)
+    } else {
+        (not_started(), not_started(), not_started())
+    };
     
     // PHASE 2: Start OpenAI Intelligence Layer
-    info!("🧠 Starting OpenAI Intelligence Layer - Phase 2");
-    let openai_handle = start_openai_layer(db_pool.clone()).await;
+    let openai_handle = if config.runs(Phase::Intelligence) {
+        info!("🧠 Starting OpenAI Intelligence Layer - Phase 2");
+        start_openai_layer(db_pool.clone(), templates, phases).await
+    } else {
+        not_started()
+    };
     
     // PHASE 3: Start Execution Engine
-    info!("⚡ Starting Execution Engine - Phase 3");
-    let execution_handle = start_execution_engine(risk_manager.clone()).await;
+    let (order_tx, order_rx) = mpsc::channel(1024);
+    let (exchanges, mut execution_handle) = if config.runs(Phase::Execution) {
+        info!("⚡ Starting Execution Engine - Phase 3");
+        let exchanges: Vec<Arc<dyn Exchange>> = venues.iter().map(Venue::exchange).collect();
+        let mut order_manager = OrderManager::new(db_pool.clone());
+        order_manager.run_id = Some(manifest.run_id);
+        order_manager.chaos = chaos.clone();
+        let mut engine = ExecutionEngine::new(risk_manager.clone(), order_manager);
+        engine.flags = flags.clone();
+        let mut reviews = ReviewQueue::from_env(db_pool.clone());
+        reviews.run_id = Some(manifest.run_id);
+        reviews.rng = rng.fork("live_review");
+        engine.set_review_queue(reviews);
+        let gates = ExecutionGates {
+            startup: startup.clone(),
+            market_data_wait: Duration::from_secs(phases.market_data_ready_secs),
+            discovery_paused: discovery_paused.clone(),
+            risk_manager: risk_manager.clone(),
+        };
+        let execution_handle = start_execution_engine(
+            engine, venues, heartbeat.clone(), audit.clone(), order_rx, shutdown.clone(), gates
+        );
+        (exchanges, Some(execution_handle))
+    } else {
+        // No book to recover without execution
+        startup.risk_state.open();
+        discovery_paused.store(false, Ordering::SeqCst);
+        (Vec::new(), None)
+    };
     
     // PHASE 4: Start Evolution Engine
-    info!("🧬 Starting Evolution Engine - Phase 4");
-    let evolution_handle = start_evolution_engine(db_pool.clone()).await;
+    let evolution_handle = if config.runs(Phase::Evolution) {
+        info!("🧬 Starting Evolution Engine - Phase 4");
+        let mut evolution = EvolutionEngine::new(db_pool.clone(), offspring);
+        evolution.rng = rng.fork("evolution");
+        evolution.test_budget = test_budget;
+        evolution.budget_policy = config.discovery.budget.clone();
+        start_evolution_engine(
+            db_pool.clone(), manifest.run_id, evolution, tuning.clone(), risk_manager.clone(), clock.clone(), config.clock.evolution_at()
+        ).await
+    } else {
+        not_started()
+    };
+    
+    // Watch open positions for stop-loss / take-profit / trailing exits
+    let stop_handle = if config.runs(Phase::Execution) {
+        start_stop_engine(risk_manager.clone(), market_data.clone(), order_tx.clone()).await
+    } else {
+        not_started()
+    };
+    
+    // Keep the empirical correlation matrix fresh for approve_order
+    let correlation_handle = start_correlation_refresh(db_pool.clone(), risk_manager.clone(), phases).await;
+    
+    // Archive, roll up and prune old ticks and test results so the database stays bounded
+    let retention_handle = start_retention(db_pool.clone(), phases).await;
+    
+    // Compare tracked positions and capital with what the venues hold
+    let reconciliation_handle = if config.runs(Phase::Execution) {
+        start_reconciliation(risk_manager.clone(), exchanges.clone(), phases, startup.risk_state.clone()).await
+    } else {
+        not_started()
+    };
+    
+    // /healthz and /readyz for Kubernetes probes or a systemd watchdog, /status.json for sharing
+    let mut health_state = HealthState::new(
+        db_pool.clone(), risk_manager.clone(), market_data.clone(), heartbeat.clone(), exchanges
+    );
+    health_state.started_at = manifest.started_at;
+    health_state.startup = Some(startup.clone());
+    let health_handle = start_health_server(health_state).await;
+    
+    // Edits to the config files apply without a restart where that is safe
+    if phases.config_watch_secs > 0 {
+        ConfigWatcher::new(config.clone(), risk_manager.clone(), tuning.clone())
+            .start(Duration::from_secs(phases.config_watch_secs));
+    }
+    
+    // Authenticated runtime control: patterns, positions, limits, pause, flatten, re-arm
+    let control_handle = start_control_api(
+        db_pool.clone(), risk_manager.clone(), discovery_paused.clone(), tuning, flags.clone(), audit.clone()
+    ).await;
+    
+    // gRPC for external strategy plugins: submit hypotheses, read stats, follow fills
+    let plugin_handle = start_plugin_server(db_pool.clone(), submissions, live_feed.clone()).await;
+    
+    // Optional Redis bus: dashboards and workers on other hosts see the same fills and risk state
+    if let Some(bus_config) = BusConfig::from_env()? {
+        let bus = Bus::start(bus_config);
+        bus::forward_live_feed(bus.clone(), &live_feed);
+        if bus.config().accept_orders && config.runs(Phase::Execution) {
+            info!("📡 Accepting order intents from the message bus");
+            bus::relay_orders(&bus, order_tx.clone());
+        }
+    }
+    
+    // Live websocket feed for a web dashboard or TUI
+    let live_feed_handle = start_live_feed(live_feed).await;
     
     // Start monitoring and reporting
-    let monitor_handle = start_monitoring_system(db_pool.clone(), risk_manager.clone()).await;
+    let monitor_handle = if config.runs(Phase::Monitor) {
+        // Risk checks and equity snapshots wait for recovery to rebuild the book. The risk loop
+        // still beats meanwhile: execution, which the dead-man's switch guards, waits on the same gate
+        let (risk_state, beat) = (startup.risk_state.clone(), heartbeat.clone());
+        let monitor = start_monitoring_system(
+            db_pool.clone(), risk_manager.clone(), market_data.clone(), heartbeat, manifest.clone(), phases, clock
+        );
+        tokio::spawn(async move {
+            while !risk_state.wait(Duration::from_secs(5)).await {
+                beat.beat("risk");
+            }
+            if let Err(e) = monitor.await.await {
+                error!("❌ Monitoring stopped: {}", e);
+            }
+        })
+    } else {
+        not_started()
+    };
     
-    info!("✅ All systems operational");
-    info!("📊 System will begin autonomous trading...");
+    info!("✅ All phases started");
+    info!("📊 Trading begins once {} are ready", startup.waiting().join(", "));
     
-    // Wait for all components
-    tokio::try_join!(
-        discovery_handle,
-        openai_handle,
-        execution_handle,
-        evolution_handle,
-        monitor_handle
-    )?;
+    // Run until a component fails or the process is asked to stop
+    let components = async {
+        tokio::try_join!(
+            discovery_handle,
+            openai_handle,
+            sentiment_handle,
+            social_handle,
+            evolution_handle,
+            correlation_handle,
+            retention_handle,
+            reconciliation_handle,
+            stop_handle,
+            health_handle,
+            control_handle,
+            plugin_handle,
+            live_feed_handle,
+            monitor_handle
+        )
+    };
+    tokio::select! {
+        joined = components => {
+            joined?;
+            return Ok(());
+        }
+        finished = async {
+            match execution_handle.as_mut() {
+                Some(handle) => handle.await,
+                None => std::future::pending().await,
+            }
+        } => {
+            finished?;
+            return Ok(());
+        }
+        _ = shutdown::signal() => {}
+    }
     
+    let equity = EquityTracker::new(db_pool.clone(), Some(manifest.run_id));
+    let writers = Writers { ledger, audit, batches: batch_writer };
+    let timeout = Duration::from_secs(config.shutdown_timeout_secs);
+    shut_down(shutdown, discovery_paused, execution_handle, risk_manager, writers, equity, timeout).await;
     Ok(())
 }
 
-async fn start_openai_layer(db_pool: PgPool) -> tokio::task::JoinHandle<()> {
+/// Stands in for a task of a phase left out of enabled_phases; it finishes at once, so the
+/// join over all components never waits on it
+fn not_started() -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async {})
+}
+
+/// Background writers shutdown waits on before exiting
+struct Writers {
+    ledger: Ledger,
+    audit: AuditLogger,
+    batches: BatchWriter,
+}
+
+/// Stop new hypotheses, let execution cancel or orphan its orders, save the risk state and
+/// flush pending writes; `timeout` (shutdown_timeout_secs) bounds each wait
+async fn shut_down(
+    shutdown: Shutdown,
+    discovery_paused: Arc<AtomicBool>,
+    execution_handle: Option<tokio::task::JoinHandle<()>>,
+    risk_manager: Arc<RiskManager>,
+    writers: Writers,
+    equity: EquityTracker,
+    timeout: Duration,
+) {
+    warn!("🛑 Shutdown requested - winding down (up to {}s per step)", timeout.as_secs());
+    
+    discovery_paused.store(true, Ordering::SeqCst);
+    shutdown.trigger();
+    if let Some(execution_handle) = execution_handle {
+        match tokio::time::timeout(timeout, execution_handle).await {
+            Ok(Ok(())) => info!("🛑 Execution stopped"),
+            Ok(Err(e)) => error!("❌ Execution task failed during shutdown: {}", e),
+            Err(_) => error!("🚨 Execution did not wind down within {}s - check the venues for working orders", timeout.as_secs()),
+        }
+    }
+    
+    risk_manager.save_state("shutdown", Severity::Info, "Book and breakers at shutdown");
+    if let Err(e) = equity.record(&risk_manager).await {
+        warn!("⚠️ Final equity snapshot failed: {}", e);
+    }
+    
+    let unwritten = writers.ledger.flush(timeout).await + writers.audit.flush(timeout).await;
+    if unwritten > 0 {
+        error!("❌ {} ledger and audit writes still pending at exit", unwritten);
+    }
+    let unbatched = writers.batches.flush(timeout).await;
+    if unbatched > 0 {
+        error!("❌ {} buffered test results and ticks never reached the database", unbatched);
+    }
+    info!("👋 Shutdown complete - capital ${:.2}, {} positions open",
+          risk_manager.current_capital(), risk_manager.open_positions_snapshot().len());
+}
+
+async fn start_openai_layer(db_pool: PgPool, templates: TemplatePool, phases: PhaseIntervals) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
-        // Initialize Python OpenAI strategist via subprocess
-        let mut interval = interval(Duration::from_secs(1800)); // 30 minutes
+        let Some(config) = IntelligenceConfig::from_env() else {
+            info!("🧠 No usable OPENAI_API_KEY, intelligence layer disabled");
+            return;
+        };
+        info!("🧠 Intelligence layer running on {} (daily budget ${:.2}, downgrades to {})",
+            config.chain(), config.budget.daily_usd, config.budget.fallback_model);
+        let strategist = Strategist::new(IntelligenceClient::new(config, db_pool.clone()), db_pool.clone());
+        let mut interval = interval(Duration::from_secs(phases.intelligence_secs));
+        let mut cycle: u64 = 0;
         
         loop {
             interval.tick().await;
             
-            // Call Python OpenAI strategist
-            let result = tokio::process::Command::new("python3")
-                .arg("intelligence/openai_strategist.py")
-                .arg("--mode")
-                .arg("sentiment_analysis")
-                .output()
-                .await;
-            
-            match result {
-                Ok(output) => {
-                    if output.status.success() {
-                        info!("🧠 OpenAI sentiment analysis completed");
-                    } else {
-                        error!("❌ OpenAI analysis failed: {}", 
-                            String::from_utf8_lossy(&output.stderr));
+            // Refresh the LLM seeding arm's templates every few cycles and report the A/B split
+            if cycle.is_multiple_of(phases.template_refresh_cycles) {
+                match strategist.propose_templates().await {
+                    Ok(proposed) if !proposed.is_empty() => {
+                        info!("🌱 {} hypothesis templates proposed", proposed.len());
+                        *templates.lock().unwrap() = proposed;
                     }
+                    Ok(_) => {}
+                    Err(e @ IntelligenceError::BudgetExhausted { .. }) => info!("💸 Template refresh skipped: {}", e),
+                    Err(e) => error!("❌ Template proposal failed: {}", e),
+                }
+                match seeding::compare_seed_sources(&db_pool, 7).await {
+                    Ok(arms) => for arm in arms {
+                        info!("🌱 {} arm: {} hypotheses, {} tests, {:.1}% win rate, ${:.4} avg, {} activated",
+                            arm.source, arm.hypotheses, arm.tests, arm.win_rate * 100.0, arm.avg_profit, arm.activated);
+                    },
+                    Err(e) => warn!("⚠️ Seed source comparison failed: {}", e),
+                }
+            }
+            cycle += 1;
+            
+            match strategist.analyze_sentiment(Vec::new()).await {
+                Ok(report) => {
+                    info!("🧠 Sentiment {:+.2}, fear/greed {:.0}, {} signals",
+                        report.overall_sentiment, report.fear_greed_index, report.trade_signals.len());
+                }
+                Err(e @ IntelligenceError::BudgetExhausted { .. }) => {
+                    info!("💸 Sentiment cycle skipped: {}", e);
                 }
                 Err(e) => {
-                    error!("❌ Failed to execute OpenAI strategist: {}", e);
+                    error!("❌ Sentiment analysis failed: {}", e);
                 }
             }
         }
     })
 }
 
-async fn start_execution_engine(risk_manager: Arc<RiskManager>) -> tokio::task::JoinHandle<()> {
+async fn start_sentiment_pipeline(db_pool: PgPool, llm: bool, phases: PhaseIntervals) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
-        // Initialize Go execution engine via subprocess
-        let mut child = tokio::process::Command::new("./core/execution_engine")
-            .spawn()
-            .expect("Failed to start execution engine");
+        let client = IntelligenceConfig::from_env().filter(|_| llm).map(|config| IntelligenceClient::new(config, db_pool.clone()));
+        let pipeline = SentimentPipeline::new(db_pool, client);
+        let mut interval = interval(Duration::from_secs(phases.sentiment_secs));
         
-        // Monitor the process
-        let status = child.wait().await.expect("Failed to wait for execution engine");
+        loop {
+            interval.tick().await;
+            
+            match pipeline.run_once().await {
+                Ok(report) => {
+                    if report.new_items > 0 {
+                        info!("📰 {} new headlines scored ({} fetched, {} feeds failed)",
+                            report.new_items, report.fetched, report.failed_feeds.len());
+                    }
+                }
+                Err(e) => error!("❌ Sentiment ingestion failed: {}", e),
+            }
+        }
+    })
+}
+
+async fn start_social_scanners(db_pool: PgPool, phases: PhaseIntervals) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let monitor = SocialMonitor::from_env(db_pool);
+        info!("📣 Social scanners {:?} tracking {:?}", monitor.scanner_names(), monitor.tickers);
+        let mut interval = interval(Duration::from_secs(phases.social_secs));
         
-        if !status.success() {
-            error!("❌ Execution engine exited with error: {}", status);
+        loop {
+            interval.tick().await;
+            
+            match monitor.run_once().await {
+                Ok(report) => {
+                    if !report.failed_scanners.is_empty() {
+                        error!("❌ Social scanners failed: {:?}", report.failed_scanners);
+                    }
+                }
+                Err(e) => error!("❌ Social metrics failed: {}", e),
+            }
         }
     })
 }
 
-async fn start_evolution_engine(db_pool: PgPool) -> tokio::task::JoinHandle<()> {
+/// One exchange per account, named after it and funded with its share of starting capital
+fn connect_exchanges(context: ExecutionContext, accounts: &[AccountConfig], market_data: &MarketDataFeed, starting_capital: f64) -> Result<Vec<Venue>, String> {
+    // Every venue is a paper exchange until a real adapter lands, so a live run must not start
+    if context.is_live() {
+        return Err("no live exchange adapters yet - set ENABLE_PAPER_TRADING=true or pass --dry-run".to_string());
+    }
+    
+    let cost_model = CostModel::default();
+    Ok(accounts.iter().map(|account| {
+        let exchange = Arc::new(
+            PaperExchange::new(&account.name, cost_model.fees_for(&account.venue).taker_rate)
+                .with_balance("USD", money::share(money::from_f64(starting_capital), account.capital_pct))
+        );
+        tokio::spawn(exchange.clone().track(market_data.subscribe()));
+        context.paper(exchange)
+    }).collect())
+}
+
+/// What execution waits on before it takes orders, and the discovery it releases after recovery
+struct ExecutionGates {
+    startup: Startup,
+    market_data_wait: Duration,  // market_data_ready_secs
+    discovery_paused: Arc<AtomicBool>,
+    risk_manager: Arc<RiskManager>,  // reduce-only if the first tick is late
+}
+
+/// Execution waits for the first tick, or market_data_ready_secs, then rebuilds the book
+/// before it takes orders; discovery resumes only after a clean recovery
+fn start_execution_engine(
+    mut engine: ExecutionEngine,
+    venues: Vec<Venue>,
+    heartbeat: Arc<Heartbeat>,
+    audit: AuditLogger,
+    orders: mpsc::Receiver<OrderIntent>,
+    shutdown: Shutdown,
+    gates: ExecutionGates,
+) -> tokio::task::JoinHandle<()> {
+    engine.set_heartbeat(heartbeat);
+    engine.set_audit_logger(audit);
+    engine.set_shutdown(shutdown.clone());
+    // Resting limit orders are cancelled and re-placed at the touch after this long; 0 disables
+    let reprice_secs = std::env::var("ORDER_REPRICE_SECS")
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+        .unwrap_or(30);
+    engine.reprice_after = (reprice_secs > 0).then(|| chrono::Duration::seconds(reprice_secs));
+    
+    for venue in venues {
+        engine.add_exchange(venue);
+    }
+    
     tokio::spawn(async move {
-        let mut interval = interval(Duration::from_secs(86400)); // 24 hours
+        let ExecutionGates { startup, market_data_wait, discovery_paused, risk_manager } = gates;
+        // Without prices execution still starts, so exits can be placed, but takes no new risk
+        let fed = tokio::select! {
+            fed = startup.market_data.wait(market_data_wait) => fed,
+            _ = shutdown.requested() => return,
+        };
+        if !fed {
+            warn!("⚠️ No market data after {}s - execution starts reduce-only until the first tick", market_data_wait.as_secs());
+            risk_manager.enter_reduce_only(ReduceOnlyReason::Degraded("market_data unhealthy".to_string()));
+        }
+        
+        // Rebuild the book and sweep what a previous run left working before taking new orders
+        let recovered = match engine.recover().await {
+            Ok(report) => {
+                info!("🔁 Recovery complete{}", if report.is_clean() { "" } else { " - review the warnings above" });
+                true
+            }
+            Err(e) => {
+                error!("❌ Recovery failed: {}", e);
+                false
+            }
+        };
+        startup.risk_state.open();
+        if recovered {
+            discovery_paused.store(false, Ordering::SeqCst);
+        } else {
+            error!("⏸️ Discovery stays paused until an operator checks the book and resumes it through the control API");
+        }
+        info!("🚦 Ready to trade: {}", startup.summary());
+        
+        engine.run(orders).await;
+    })
+}
+
+async fn start_evolution_engine(
+    db_pool: PgPool,
+    run_id: Uuid,
+    mut engine: EvolutionEngine,
+    tuning: SharedTuning,
+    risk_manager: Arc<RiskManager>,
+    clock: TradingClock,
+    evolution_at: Option<chrono::NaiveTime>,
+) -> tokio::task::JoinHandle<()> {
+    engine.config = EvolutionConfig::from_env();
+    engine.run_id = Some(run_id);
+    info!("🧬 Evolution ranks patterns by {}", engine.config.fitness.as_str());
+    
+    // After a regime change, operators can restart the population from the best-ever patterns
+    if std::env::var("HALL_OF_FAME_RESEED").map(|v| v == "true").unwrap_or(false) {
+        match engine.hall_of_fame.reseed(&engine.offspring_queue()).await {
+            Ok(restored) => info!("🏛️ Reseeded {} patterns from the hall of fame", restored),
+            Err(e) => error!("❌ Hall of fame reseed failed: {}", e),
+        }
+    }
+    
+    // Daily (or at [clock] evolution_at local time), or early when live win rates collapse across
+    // the portfolio; both intervals tunable at runtime
+    let mut cadence = EvolutionCadence { daily_at: evolution_at, calendar: clock.calendar, ..EvolutionCadence::default() };
+    if let Some(at) = evolution_at {
+        info!("🧬 Scheduled evolution cycles run at {} {}", at.format("%H:%M"), clock.calendar.timezone_name());
+    }
+    
+    tokio::spawn(async move {
+        let mut interval = interval(cadence.check_every.to_std().unwrap_or(Duration::from_secs(900)));
+        let mut last_cycle = None;
         
         loop {
             interval.tick().await;
             
-            info!("🧬 Starting daily evolution cycle");
-            
-            // Run Python evolution engine
-            let result = tokio::process::Command::new("python3")
-                .arg("core/evolution_ai.py")
-                .arg("--mode")
-                .arg("daily_evolution")
-                .output()
-                .await;
-            
-            match result {
-                Ok(output) => {
-                    if output.status.success() {
-                        info!("✅ Evolution cycle completed");
-                        info!("📈 {}", String::from_utf8_lossy(&output.stdout));
-                    } else {
-                        error!("❌ Evolution failed: {}", 
-                            String::from_utf8_lossy(&output.stderr));
+            let tuned = *tuning.lock().unwrap();
+            cadence.interval = chrono::Duration::hours(tuned.evolution_interval_hours as i64);
+            cadence.min_interval = chrono::Duration::hours(tuned.evolution_min_interval_hours as i64);
+            
+            // Discovery's test size and rate follow equity (the discovery account's, if there is one)
+            if let Some(budget) = engine.rebalance_budget(risk_manager.discovery_capital()) {
+                info!("💵 Test budget now ${:.2} per test, {} hypotheses/hour",
+                    budget.test_capital, budget.hypotheses_per_hour);
+            }
+            
+            let now = clock.now();
+            let regime_changed = match cadence.load_win_rates(&db_pool, now).await {
+                Ok((recent, baseline)) => cadence.regime_changed(recent, baseline),
+                Err(e) => {
+                    error!("❌ Failed to load live win rates: {}", e);
+                    false
+                }
+            };
+            match cadence.due(last_cycle, now, regime_changed) {
+                Some(CycleTrigger::Scheduled) => info!("🧬 Starting daily evolution cycle"),
+                Some(CycleTrigger::RegimeChange) => info!("🧬 Live win rate dropped sharply, starting an early evolution cycle"),
+                None => continue,
+            }
+            last_cycle = Some(now);
+            
+            // Freeze the 30/90-day windows this cycle selects on
+            let snapshot_date = clock.calendar.day_of(now);
+            match performance::take_snapshots(&db_pool, snapshot_date, 5.0, Some(run_id)).await {
+                Ok(rows) => info!("📸 Performance snapshot {} written ({} windows)", snapshot_date, rows),
+                Err(e) => error!("❌ Failed to snapshot pattern performance: {}", e),
+            }
+            
+            // Breed the next generation; offspring go straight to the discovery queue
+            match engine.run_cycle(snapshot_date).await {
+                Ok(outcome) => {
+                    info!("✅ Evolution cycle completed (generation {})", outcome.generation);
+                    info!("📈 fitness mean {:.4} / max {:.4} over {} patterns, diversity {:.3} across {} species",
+                        outcome.avg_fitness, outcome.max_fitness, outcome.population,
+                        outcome.diversity_index, outcome.species);
+                    info!("📈 {} survivors, {} retired, {} offspring ({} mutations, {} crossovers, {} cross-symbol)",
+                        outcome.survivors.len(), outcome.culled.len(), outcome.offspring.len(),
+                        outcome.mutations, outcome.crossovers, outcome.crossbreeds);
+                    if let Some(webhooks) = risk_manager.webhooks() {
+                        for hash in &outcome.culled {
+                            webhooks.emit(WebhookEvent::PatternRetired {
+                                pattern_hash: hash.clone(), reason: "evolution_culled".to_string(),
+                            });
+                        }
                     }
                 }
                 Err(e) => {
-                    error!("❌ Failed to execute evolution engine: {}", e);
+                    error!("❌ Evolution failed: {}", e);
+                }
+            }
+        }
+    })
+}
+
+async fn start_stop_engine(
+    risk_manager: Arc<RiskManager>,
+    market_data: MarketDataFeed,
+    orders: mpsc::Sender<OrderIntent>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let (exit_tx, mut exit_rx) = mpsc::channel(256);
+        let stop_engine = StopEngine::new(risk_manager, TrailingConfig::default(), exit_tx);
+        
+        // The poller is already running; positions appear once recovery has restored them
+        tokio::spawn(stop_engine.run(market_data.subscribe()));
+        
+        while let Some(signal) = exit_rx.recv().await {
+            info!("🛑 Exit requested: {} {} ${:.2} ({:?} @ {:.6})",
+                signal.symbol, signal.position_id, signal.size, signal.reason, signal.trigger_price);
+            
+            let exit = OrderIntent::Close {
+                position_id: signal.position_id,
+                size: signal.size,
+                reason: format!("{:?}", signal.reason),
+                decision: Some(Decision { price: signal.trigger_price, at: signal.triggered_at }),
+            };
+            if orders.send(exit).await.is_err() {
+                error!("❌ Execution engine is gone - exits cannot be placed");
+            }
+        }
+    })
+}
+
+async fn start_reconciliation(
+    risk_manager: Arc<RiskManager>,
+    exchanges: Vec<Arc<dyn Exchange>>,
+    phases: PhaseIntervals,
+    risk_state: Gate,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        // Before recovery the tracked book is empty and every venue holding would look like a divergence
+        risk_state.opened().await;
+        let mut reconciler = Reconciler::new(risk_manager, exchanges);
+        // Off by default: a venue account shared with other activity would skew capital
+        reconciler.auto_correct_capital = std::env::var("RECONCILE_AUTO_CORRECT")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        let mut interval = interval(Duration::from_secs(phases.reconciliation_secs));
+        
+        loop {
+            interval.tick().await;
+            
+            let report = match reconciler.reconcile().await {
+                Ok(report) => report,
+                Err(e) => {
+                    error!("❌ Position reconciliation failed: {}", e);
+                    continue;
+                }
+            };
+            
+            for divergence in &report.divergences {
+                error!("🚨 {} {} diverges: tracked {:.6} vs venue {:.6} units",
+                    divergence.exchange, divergence.asset, divergence.tracked_units, divergence.venue_units);
+            }
+            if report.capital_corrected {
+                error!("🚨 Tracked capital corrected by ${:.2} to match venue equity ${:.2}",
+                    report.capital_drift(), report.venue_equity);
+            } else if reconciler.drift_exceeds_tolerance(&report) {
+                error!("🚨 Venue equity ${:.2} differs from tracked ${:.2}",
+                    report.venue_equity, report.tracked_equity);
+            }
+        }
+    })
+}
+
+async fn start_health_server(state: HealthState) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let port = std::env::var("HEALTH_PORT")
+            .ok()
+            .and_then(|v| v.parse::<u16>().ok())
+            .unwrap_or(8080);
+        
+        if let Err(e) = health::serve(state, port).await {
+            error!("❌ Health server on :{} stopped: {}", port, e);
+        }
+    })
+}
+
+async fn start_control_api(
+    db_pool: PgPool,
+    risk_manager: Arc<RiskManager>,
+    discovery_paused: Arc<AtomicBool>,
+    tuning: SharedTuning,
+    flags: FeatureFlags,
+    audit: AuditLogger,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        // CONTROL_API_TOKEN predates roles and stays an admin key
+        let mut keys = match control_api::parse_keys(&secrets::var("CONTROL_API_KEYS").unwrap_or_default()) {
+            Ok(keys) => keys,
+            Err(e) => {
+                error!("❌ CONTROL_API_KEYS is invalid ({}), control API disabled", e);
+                return;
+            }
+        };
+        if let Some(token) = secrets::var("CONTROL_API_TOKEN").ok().filter(|t| !t.trim().is_empty()) {
+            keys.push((Role::Admin, token.trim().to_string()));
+        }
+        if keys.is_empty() {
+            info!("🎛️ No CONTROL_API_TOKEN or CONTROL_API_KEYS, control API disabled");
+            return;
+        }
+        let bind = std::env::var("CONTROL_BIND").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let port = std::env::var("CONTROL_PORT")
+            .ok()
+            .and_then(|v| v.parse::<u16>().ok())
+            .unwrap_or(8081);
+        
+        let state = ControlState::new(db_pool, risk_manager, discovery_paused, tuning, flags, audit, keys);
+        if let Err(e) = control_api::serve(state, &bind, port).await {
+            error!("❌ Control API on {}:{} stopped: {}", bind, port, e);
+        }
+    })
+}
+
+async fn start_plugin_server(
+    db_pool: PgPool,
+    submissions: SubmissionQueue,
+    feed: LiveFeed,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let Some(token) = secrets::var("PLUGIN_API_TOKEN").ok().filter(|t| !t.trim().is_empty()) else {
+            info!("🔌 No PLUGIN_API_TOKEN, strategy plugin gRPC disabled");
+            return;
+        };
+        let bind = std::env::var("PLUGIN_BIND").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let port = std::env::var("PLUGIN_PORT")
+            .ok()
+            .and_then(|v| v.parse::<u16>().ok())
+            .unwrap_or(50051);
+        
+        let service = PluginService::new(db_pool, submissions, feed);
+        if let Err(e) = plugins::serve(service, token.trim(), &bind, port).await {
+            error!("❌ Strategy plugin gRPC on {}:{} stopped: {}", bind, port, e);
+        }
+    })
+}
+
+async fn start_live_feed(feed: LiveFeed) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let bind = std::env::var("LIVE_FEED_BIND").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let port = std::env::var("LIVE_FEED_PORT")
+            .ok()
+            .and_then(|v| v.parse::<u16>().ok())
+            .unwrap_or(8082);
+        let token = secrets::var("LIVE_FEED_TOKEN").ok().filter(|t| !t.trim().is_empty());
+        
+        if let Err(e) = live_feed::serve(feed, token.as_deref().map(str::trim), &bind, port).await {
+            error!("❌ Live feed on {}:{} stopped: {}", bind, port, e);
+        }
+    })
+}
+
+/// A dashboard-only process: live events arrive over the bus instead of from local components
+async fn relay_feed() -> Result<(), Box<dyn std::error::Error>> {
+    let _telemetry = telemetry::init();
+    let config = BusConfig::from_env()?.ok_or("the feed relay needs MESSAGE_BUS=redis and REDIS_URL")?;
+    let bus = Bus::start(config);
+    let feed = LiveFeed::default();
+    bus::relay_live_feed(&bus, feed.clone());
+    info!("📡 Relaying the live feed from the message bus");
+    start_live_feed(feed).await.await?;
+    Ok(())
+}
+
+async fn start_retention(db_pool: PgPool, phases: PhaseIntervals) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let retention = Retention::from_env(db_pool);
+        info!("🗄️ Retention: {}", retention.describe());
+        // First pass once startup has settled, then every RETENTION_INTERVAL_HOURS
+        let every = retention.config().interval;
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + Duration::from_secs(phases.retention_first_pass_secs), every);
+        
+        loop {
+            interval.tick().await;
+            retention.run_once().await;
+        }
+    })
+}
+
+async fn start_correlation_refresh(
+    db_pool: PgPool,
+    risk_manager: Arc<RiskManager>,
+    phases: PhaseIntervals,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(phases.correlation_secs));
+        
+        loop {
+            interval.tick().await;
+            
+            // Rolling 30-day window over patterns with enough history
+            let hashes = match correlation::load_candidate_patterns(&db_pool, 30, 20, 200).await {
+                Ok(hashes) => hashes,
+                Err(e) => {
+                    error!("❌ Failed to load patterns for correlation: {}", e);
+                    continue;
+                }
+            };
+            
+            // Relearn hour-of-week liquidity from the last 4 weeks of market data
+            match liquidity::load_profiles(&db_pool, 28).await {
+                Ok(profiles) => risk_manager.set_liquidity_profiles(profiles),
+                Err(e) => error!("❌ Failed to load liquidity profiles: {}", e),
+            }
+            
+            match var::load_pattern_daily_returns(&db_pool, &hashes, 5.0, 30).await {
+                Ok(series) => {
+                    let matrix = correlation::correlation_matrix(&series, correlation::MIN_OVERLAP_DAYS);
+                    info!("🔗 Correlation matrix refreshed: {} patterns, {} pairs", series.len(), matrix.len());
+                    risk_manager.set_correlations(matrix);
+                }
+                Err(e) => {
+                    error!("❌ Failed to load returns for correlation: {}", e);
                 }
             }
         }
@@ -162,46 +1261,596 @@ async fn start_evolution_engine(db_pool: PgPool) -> tokio::task::JoinHandle<()>
 
 async fn start_monitoring_system(
     db_pool: PgPool, 
-    risk_manager: Arc<RiskManager>
+    risk_manager: Arc<RiskManager>,
+    market_data: MarketDataFeed,
+    heartbeat: Arc<Heartbeat>,
+    manifest: RunManifest,
+    phases: PhaseIntervals,
+    clock: TradingClock,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
-        let mut interval = interval(Duration::from_secs(60)); // 1 minute
+        let run_id = manifest.run_id;
+        // The run's own seed, so each day's projection replays with the run
+        let mut projection_rng = SharedRng::seeded(manifest.seed.unwrap_or_default()).fork("monte_carlo");
+        let projection_config = MonteCarloConfig::from_env();
+        let mut interval = interval(Duration::from_secs(phases.monitoring_secs));
+        let mut drift_tracker = DriftTracker::new(db_pool.clone());
+        drift_tracker.run_id = Some(run_id);
+        let mut rollover = DayRollover::new(clock.calendar, clock.now());
+        let equity_tracker = EquityTracker::new(db_pool.clone(), Some(run_id));
+        // Stalled discovery, collapsing fills and failing writes alert without touching the breakers
+        let mut anomaly_detector = AnomalyDetector::new(AnomalyConfig::default(), chrono::Utc::now());
+        if let Some(alerts) = risk_manager.alerts() {
+            anomaly_detector.set_alert_manager(alerts.clone());
+        }
+        let mut ticks: u64 = 0;
         
         loop {
             interval.tick().await;
+            ticks += 1;
             
             // Check risk limits
             if !risk_manager.check_risk_limits() {
                 error!("🚨 Risk limits violated - system may halt trading");
             }
+            heartbeat.beat("risk");
+            
+            // A stalled price feed degrades the system to reduce-only
+            if let Some(fresh) = market_data.is_fresh(Duration::from_secs(phases.market_data_stale_secs)) {
+                risk_manager.report_subsystem_health("market_data", fresh);
+            }
+            if let Some(reason) = risk_manager.reduce_only_reason() {
+                error!("🟠 Reduce-only mode active: {:?}", reason);
+            }
             
-            // Query performance metrics (commented out for initial testing)
-            /*
-            let result = sqlx::query!(
-                "SELECT COUNT(*) as total_patterns, 
-                 COUNT(*) FILTER (WHERE is_active = true) as active_patterns,
-                 AVG(win_rate) as avg_win_rate
-                 FROM discovered_patterns"
-            )
-            .fetch_one(&db_pool)
-            .await;
-            
-            match result {
-                Ok(row) => {
-                    info!("📊 System Status:");
-                    info!("   Total Patterns: {}", row.total_patterns.unwrap_or(0));
-                    info!("   Active Patterns: {}", row.active_patterns.unwrap_or(0));
-                    info!("   Avg Win Rate: {:.2}%", 
-                        row.avg_win_rate.unwrap_or(0.0) * 100.0);
+            match equity_tracker.record(&risk_manager).await {
+                Ok(snapshot) => {
+                    if let Some(feed) = risk_manager.live_feed() {
+                        feed.publish(LiveEvent::Equity(snapshot));
+                    }
                 }
                 Err(e) => {
-                    error!("❌ Failed to query performance metrics: {}", e);
+                    error!("❌ Failed to record equity snapshot: {}", e);
+                    anomaly::note(anomaly::Event::DbWriteError);
+                }
+            }
+            anomaly_detector.check(chrono::Utc::now());
+            
+            // Pattern population, every 15 minutes
+            if ticks.is_multiple_of(15) {
+                match daily_report::pattern_summary(&db_pool).await {
+                    Ok(summary) => {
+                        info!("📊 System Status: {} patterns, {} active, avg win rate {:.2}% | capital ${:.2}",
+                            summary.total_patterns, summary.active_patterns,
+                            summary.avg_win_rate * 100.0, risk_manager.current_capital());
+                    }
+                    Err(e) => {
+                        error!("❌ Failed to query performance metrics: {}", e);
+                    }
                 }
             }
-            */
             
-            // Placeholder system status
-            info!("📊 System Status: Discovery engine running, collecting patterns...");
+            // Close out the previous trading day at rollover
+            if let Some(day) = rollover.check(clock.now()) {
+                let report = DailyReport::build(
+                    &db_pool, &clock.calendar, day, money::to_f64(risk_manager.starting_capital()), money::to_f64(risk_manager.current_capital()), Some(run_id)
+                ).await;
+                match report {
+                    Ok(mut report) => {
+                        if let Err(e) = report.project_equity(&db_pool, &projection_config, &mut projection_rng).await {
+                            warn!("⚠️ Monte Carlo projection for {} failed: {}", day, e);
+                        }
+                        report.log_summary();
+                        if let Err(e) = report.store(&db_pool).await {
+                            error!("❌ Failed to store daily report for {}: {}", day, e);
+                        }
+                        if let Some(webhooks) = risk_manager.webhooks() {
+                            webhooks.emit(WebhookEvent::DailyReport(report.webhook_summary()));
+                        }
+                    }
+                    Err(e) => {
+                        error!("❌ Failed to build daily report for {}: {}", day, e);
+                    }
+                }
+            }
+            
+            // 1-day 95% VaR/ES over the live portfolio, every 15 minutes
+            if ticks.is_multiple_of(15) {
+                let weights = risk_manager.position_weights();
+                let hashes: Vec<String> = weights.keys().cloned().collect();
+                
+                match var::load_pattern_daily_returns(&db_pool, &hashes, 5.0, 90).await {
+                    Ok(pattern_returns) => {
+                        let returns = var::portfolio_returns(&pattern_returns, &weights);
+                        let report = var::compute_var_report(&returns, 0.95);
+                        
+                        info!("📉 1-day VaR95: hist {:.2}% / param {:.2}% | ES95: hist {:.2}% / param {:.2}% ({} days)",
+                            report.historical_var * 100.0, report.parametric_var * 100.0,
+                            report.historical_es * 100.0, report.parametric_es * 100.0,
+                            report.sample_size);
+                        
+                        if !risk_manager.update_var(report.worst_var()) {
+                            error!("🚨 Portfolio VaR above limit - new risk blocked");
+                        }
+                    }
+                    Err(e) => {
+                        error!("❌ Failed to load portfolio returns for VaR: {}", e);
+                    }
+                }
+            }
+            
+            // OpenAI spend against the daily budget, every 15 minutes
+            if ticks.is_multiple_of(15) {
+                match budget::spend_today(&db_pool).await {
+                    Ok(spend) if spend.calls > 0 => {
+                        let llm_budget = BudgetConfig::from_env();
+                        info!("💸 OpenAI spend today: ${:.4} of ${:.2} ({} calls, {} tokens)",
+                            spend.cost_usd, llm_budget.daily_usd, spend.calls, spend.total_tokens);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("❌ Failed to query OpenAI spend: {}", e);
+                    }
+                }
+            }
+            
+            // Hourly simulation-vs-live drift report
+            if ticks.is_multiple_of(60) {
+                match drift_tracker.report(24).await {
+                    Ok(report) => {
+                        info!("🧪 Sim-vs-live drift (24h, worst first):");
+                        for drift in report.iter().take(10) {
+                            info!("   {} signals={} missed={:.1}% slippage={:.1}bps delay={:.0}ms pnl_drift=${:.2}",
+                                drift.pattern_hash, drift.signals, drift.missed_fill_rate() * 100.0,
+                                drift.avg_slippage_bps, drift.avg_delay_ms, drift.pnl_drift);
+                        }
+                        
+                        // Slippage-sensitive patterns move to passive or sliced execution
+                        match execution_style::rebind_styles(&db_pool, &report).await {
+                            Ok(changed) if changed > 0 => info!("🎛️ Execution style rebound for {} patterns", changed),
+                            Ok(_) => {}
+                            Err(e) => error!("❌ Failed to rebind execution styles: {}", e),
+                        }
+                    }
+                    Err(e) => {
+                        error!("❌ Failed to build drift report: {}", e);
+                    }
+                }
+            }
         }
     })
 }
+
+fn show_config() -> Result<(), Box<dyn std::error::Error>> {
+    print!("{}", AppConfig::load()?.to_toml()?);
+    Ok(())
+}
+
+async fn connect() -> Result<PgPool, Box<dyn std::error::Error>> {
+    let database_url = secrets::var("DATABASE_URL").map_err(|_| "DATABASE_URL must be set")?;
+    Ok(db::connect(&database_url, &AppConfig::load()?.database).await?)
+}
+
+async fn backtest(args: BacktestArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if args.queue {
+        return backtest_queue(args).await;
+    }
+    let db_pool = connect().await?;
+    let hashes = if args.active {
+        patterns::list(&db_pool, true, None).await?.into_iter().map(|p| p.pattern_hash).collect()
+    } else {
+        args.patterns
+    };
+    if hashes.is_empty() {
+        return Err("name at least one pattern hash, or pass --active".into());
+    }
+    
+    let discovery = AppConfig::load()?.discovery;
+    let min_tests = args.min_tests.unwrap_or(discovery.min_tests_required);
+    let min_win_rate = args.min_win_rate.unwrap_or(discovery.min_win_rate);
+    let from = args.days.map(|days| chrono::Utc::now() - chrono::Duration::days(days));
+    
+    println!("🧪 Backtest: costs x{:.2}, activation at {} tests and {:.1}% wins",
+        args.cost_multiplier, min_tests, min_win_rate * 100.0);
+    println!("{:<18} {:>6} {:>7} {:>10} {:>9} {:>7} {:>9}  activates",
+        "pattern", "tests", "win", "net", "costs", "sharpe", "max dd");
+    for hash in hashes {
+        let results = backtest::load_test_results(&db_pool, &hash, from, None).await?;
+        let r = BacktestReport::run(&hash, &results, args.cost_multiplier, min_tests, min_win_rate);
+        println!("{:<18} {:>6} {:>6.1}% {:>10.2} {:>9.2} {:>7.2} {:>9.2}  {}",
+            r.pattern_hash, r.tests, r.win_rate * 100.0, r.net_profit, r.costs, r.sharpe_ratio, r.max_drawdown,
+            if r.would_activate { "yes" } else { "no" });
+    }
+    Ok(())
+}
+
+async fn backtest_queue(args: BacktestArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let config = AppConfig::load()?;
+    let db_pool = connect().await?;
+    let candidates = backtest_pool::load_queue(&db_pool, args.limit).await?;
+    if candidates.is_empty() {
+        println!("🧪 No candidates waiting for activation");
+        return Ok(());
+    }
+
+    let to = chrono::Utc::now();
+    let venue = config.accounts.first().map(|a| a.venue.clone()).unwrap_or_else(|| "coinbase".to_string());
+    let settings = PoolSettings {
+        threads: args.threads,
+        from: to - chrono::Duration::days(args.days.unwrap_or(7)),
+        to,
+        capital: config.discovery.budget.min_test_capital,
+        fee_rate: CostModel::default().fees_for(&venue).taker_rate * args.cost_multiplier,
+        min_tests: args.min_tests.unwrap_or(config.discovery.min_tests_required),
+        min_win_rate: args.min_win_rate.unwrap_or(config.discovery.min_win_rate),
+    };
+    println!("🧪 Replaying {} candidates over {} days of ticks, activation at {} trades and {:.1}% wins",
+        candidates.len(), args.days.unwrap_or(7), settings.min_tests, settings.min_win_rate * 100.0);
+
+    let progress = Arc::new(Progress::default());
+    let reporter = {
+        let progress = progress.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(10));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                println!("⏳ {}", progress.describe());
+            }
+        })
+    };
+    let reports = backtest_pool::run(&db_pool, candidates, &config.symbol_universe, settings, progress.clone()).await;
+    reporter.abort();
+    let mut reports = reports.map_err(|e| e.to_string())?;
+
+    reports.retain(|r| r.report.would_activate);
+    reports.sort_by(|a, b| b.report.sharpe_ratio.total_cmp(&a.report.sharpe_ratio));
+    println!("{:<18} {:<10} {:>6} {:>7} {:>10} {:>7} {:>9}",
+        "pattern", "symbol", "trades", "win", "net", "sharpe", "max dd");
+    for CandidateReport { symbol, report: r } in &reports {
+        println!("{:<18} {:<10} {:>6} {:>6.1}% {:>10.2} {:>7.2} {:>9.2}",
+            r.pattern_hash, symbol, r.tests, r.win_rate * 100.0, r.net_profit, r.sharpe_ratio, r.max_drawdown);
+    }
+    println!("✅ {}; {} would activate", progress.describe(), reports.len());
+    Ok(())
+}
+
+async fn stress_test(args: StressArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let scenarios = if args.scenarios.is_empty() {
+        Scenario::ALL.to_vec()
+    } else {
+        args.scenarios.iter()
+            .map(|s| Scenario::parse(s).ok_or_else(|| format!("unknown scenario {}", s)))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    let config = AppConfig::load()?;
+    let db_pool = connect().await?;
+    let mut strategies = Vec::new();
+    for listing in patterns::list(&db_pool, true, None).await? {
+        let results = backtest::load_test_results(&db_pool, &listing.pattern_hash, None, None).await?;
+        strategies.push(StressStrategy::from_listing(&listing, &results));
+    }
+    let venue = config.accounts.first().map(|a| a.venue.clone()).unwrap_or_else(|| "coinbase".to_string());
+    let stress = StressConfig {
+        capital: args.capital.unwrap_or(config.initial_capital),
+        risk: config.risk.clone(),
+        stop_loss_pct: args.stop_loss,
+        taker_rate: CostModel::default().fees_for(&venue).taker_rate,
+    };
+    
+    println!("🌪️ Stress test: {} active patterns, ${:.2} capital, {:.1}% stops, {:.0}% daily drawdown stop",
+        strategies.len(), stress.capital, stress.stop_loss_pct * 100.0, stress.risk.max_daily_drawdown_pct * 100.0);
+    println!("{:<16} {:>9} {:>10} {:>6} {:>10} {:>10}  emergency  contained",
+        "scenario", "positions", "exposure", "stops", "worst", "final");
+    let mut breached = 0;
+    for scenario in scenarios {
+        let r = stress::run(scenario, &strategies, &stress);
+        println!("{:<16} {:>9} {:>10.2} {:>6} {:>9.1}% {:>10.2}  {:<9}  {}",
+            r.scenario.as_str(), r.positions, r.exposure, r.stops_hit, r.worst_loss_pct * 100.0, r.final_loss,
+            if r.emergency_stop { "tripped" } else { "-" }, if r.contained { "✅" } else { "❌" });
+        if !r.contained {
+            breached += 1;
+        }
+    }
+    if breached > 0 {
+        return Err(format!("{} scenarios lose more than the {:.0}% daily drawdown limit", breached, stress.risk.max_daily_drawdown_pct * 100.0).into());
+    }
+    Ok(())
+}
+
+async fn monte_carlo_projection(args: MonteCarloArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let defaults = MonteCarloConfig::from_env();
+    let config = MonteCarloConfig {
+        paths: args.paths.unwrap_or(defaults.paths),
+        horizon_days: args.horizon_days.unwrap_or(defaults.horizon_days),
+        ruin_pct: args.ruin.unwrap_or(defaults.ruin_pct),
+        lookback_days: args.days.unwrap_or(defaults.lookback_days),
+    };
+    let db_pool = connect().await?;
+    let now = chrono::Utc::now();
+    let since = now - chrono::Duration::days(config.lookback_days);
+    let capital = match args.capital {
+        Some(capital) => capital,
+        None => match equity::load_snapshots(&db_pool, since, now).await?.last() {
+            Some(snapshot) => snapshot.equity,
+            None => AppConfig::load()?.initial_capital,
+        },
+    };
+    let sample = monte_carlo::load_trades(&db_pool, since, config.lookback_days).await?;
+    let mut rng = SharedRng::from_env().fork("monte_carlo");
+
+    let Some(projection) = monte_carlo::project(&sample, capital, &config, &mut rng) else {
+        return Err(format!("active patterns closed no trades in the last {} days", config.lookback_days).into());
+    };
+    println!("🎲 Monte Carlo: {} paths over {} days from ${:.2}, resampling {} trades ({:.1}/day, seed {})",
+        projection.paths, projection.horizon_days, capital, projection.trades_sampled, projection.trades_per_day, rng.seed());
+    println!("{:>5} {:>12} {:>12} {:>12}", "day", "p5", "median", "p95");
+    let step = (projection.bands.len() / 6).max(1);
+    for band in projection.bands.iter().filter(|b| (b.day as usize).is_multiple_of(step) || b.day == projection.horizon_days) {
+        println!("{:>5} {:>12.2} {:>12.2} {:>12.2}", band.day, band.p5, band.p50, band.p95);
+    }
+    println!("📉 Median max drawdown {:.1}% | P(loss) {:.1}% | P(ruin below ${:.2}) {:.2}%",
+        projection.median_max_drawdown * 100.0, projection.probability_of_loss * 100.0,
+        projection.ruin_level, projection.probability_of_ruin * 100.0);
+    Ok(())
+}
+
+async fn backfill(limit: i64) -> Result<(), Box<dyn std::error::Error>> {
+    let index = PatternIndex::from_env(connect().await?);
+    let embedded = index.backfill(limit).await?;
+    println!("🧭 Embedded {} patterns", embedded);
+    Ok(())
+}
+
+async fn patterns_command(command: PatternsCommand) -> Result<(), Box<dyn std::error::Error>> {
+    let db_pool = connect().await?;
+    
+    match command {
+        PatternsCommand::List { all, limit } => {
+            let listings = patterns::list(&db_pool, !all, Some(limit)).await?;
+            println!("{:<18} {:<12} {:>7} {:>7} {:>6} {:>10} {:>4}  active",
+                "pattern", "symbol", "sharpe", "win", "tests", "profit", "gen");
+            for p in listings {
+                println!("{:<18} {:<12} {:>7.2} {:>6.1}% {:>6} {:>10.2} {:>4}  {}",
+                    p.pattern_hash, p.symbol.as_deref().unwrap_or("*"), p.sharpe_ratio, p.win_rate * 100.0,
+                    p.test_count, p.total_profit, p.generation, if p.is_active { "yes" } else { "no" });
+            }
+        }
+        PatternsCommand::Retire { hashes, reason } => {
+            let audit = AuditLogger::new(db_pool.clone(), None);
+            let retired = patterns::retire(&db_pool, &audit, &hashes, &reason).await?;
+            for hash in hashes.iter().filter(|h| !retired.contains(h)) {
+                println!("⏭️ {} was not active", hash);
+            }
+            println!("🪦 Retired {} patterns ({})", retired.len(), reason);
+            if let Some(webhooks) = WebhookNotifier::from_env() {
+                for hash in &retired {
+                    webhooks.deliver(WebhookEvent::PatternRetired { pattern_hash: hash.clone(), reason: reason.clone() }).await;
+                }
+            }
+        }
+        PatternsCommand::Export { path, format, active_only } => {
+            let format = bundle_format(format.as_deref(), path.as_deref())?;
+            let bundle = interchange::export_bundle(&db_pool, active_only).await?;
+            let text = bundle.to_text(format)?;
+            match &path {
+                Some(path) => {
+                    std::fs::write(path, text)?;
+                    println!("📦 Exported {} hypotheses and {} patterns to {}",
+                        bundle.hypotheses.len(), bundle.patterns.len(), path);
+                }
+                None => println!("{}", text),
+            }
+        }
+        PatternsCommand::Import { path, format, keep_stats } => {
+            let format = bundle_format(format.as_deref(), Some(&path))?;
+            let bundle = PatternBundle::parse(&std::fs::read_to_string(&path)?, format)?;
+            let imported = interchange::import_bundle(&db_pool, &bundle, keep_stats).await?;
+            let total = (bundle.hypotheses.len() + bundle.patterns.len()) as u64;
+            println!("📥 Imported {} new hypotheses ({} already known){}",
+                imported, total - imported, if keep_stats { " with their stats" } else { "" });
+        }
+    }
+    Ok(())
+}
+
+/// An explicit --format wins over the file extension
+fn bundle_format(format: Option<&str>, path: Option<&str>) -> Result<BundleFormat, Box<dyn std::error::Error>> {
+    match format {
+        Some(name) => BundleFormat::parse(name).ok_or_else(|| format!("unknown format {} (json or toml)", name).into()),
+        None => Ok(path.map_or(BundleFormat::Json, BundleFormat::from_path)),
+    }
+}
+
+async fn export_journal(args: JournalArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let format = match args.format.as_deref() {
+        Some(name) => JournalFormat::parse(name).ok_or_else(|| format!("unknown format {} (csv or parquet)", name))?,
+        None => args.path.as_deref().map_or(JournalFormat::Csv, JournalFormat::from_path),
+    };
+    let db_pool = connect().await?;
+    let from = args.days.map(|days| chrono::Utc::now() - chrono::Duration::days(days));
+    let rows = journal::load(&db_pool, from, None, args.pattern.as_deref()).await?;
+    
+    match &args.path {
+        Some(path) => {
+            journal::write(&rows, format, std::io::BufWriter::new(std::fs::File::create(path)?))?;
+            println!("📒 Exported {} fills to {}", rows.len(), path);
+        }
+        None => journal::write(&rows, format, std::io::stdout())?,
+    }
+    Ok(())
+}
+
+async fn tax_report(args: TaxArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let method = LotMethod::parse(&args.method).ok_or_else(|| format!("unknown method {} (fifo or lifo)", args.method))?;
+    let db_pool = connect().await?;
+    let rows = journal::load(&db_pool, None, TaxReport::until(args.year), None).await?;
+    let report = TaxReport::build(&rows, args.year, method);
+    
+    match &args.path {
+        Some(path) => {
+            tax_lots::write_8949_csv(&report, std::io::BufWriter::new(std::fs::File::create(path)?))?;
+            println!("🧾 {} {} disposals to {}: short-term ${:.2}, long-term ${:.2}, {} lots still open",
+                     args.year, report.disposals.len(), path, report.gain(Term::Short), report.gain(Term::Long), report.open_lots);
+            if report.skipped_fills > 0 {
+                warn!("⚠️ {} fills had no side or price and were left out", report.skipped_fills);
+            }
+        }
+        None => tax_lots::write_8949_csv(&report, std::io::stdout())?,
+    }
+    Ok(())
+}
+
+async fn risk_status() -> Result<(), Box<dyn std::error::Error>> {
+    let config = AppConfig::load()?;
+    let limits = config.risk;  // with the selected profile's [risk]
+    let db_pool = connect().await?;
+    let now = chrono::Utc::now();
+    let calendar = config.clock.calendar();
+    let day_start = calendar.start_of(calendar.day_of(now));
+    
+    println!("🛡️ Configured limits");
+    println!("{}", serde_json::to_string_pretty(&limits)?);
+    
+    // The live process keeps its own state; snapshots are what it last wrote down
+    let snapshots = equity::load_snapshots(&db_pool, day_start, now).await?;
+    match snapshots.last() {
+        Some(last) => {
+            let high = snapshots.iter().map(|s| s.equity).fold(f64::MIN, f64::max);
+            let drawdown = if high > 0.0 { (high - last.equity) / high } else { 0.0 };
+            println!("💰 Equity ${:.2} at {} | {} open positions, ${:.2} exposure",
+                last.equity, last.taken_at.format("%H:%M UTC"), last.open_positions, last.open_exposure);
+            println!("📉 Today's drawdown {:.2}% from ${:.2} (stop at {:.1}%)",
+                drawdown * 100.0, high, limits.max_daily_drawdown_pct * 100.0);
+        }
+        None => println!("💰 No equity snapshots today"),
+    }
+    
+    let filter = AuditFilter {
+        component: Some(Component::Risk),
+        since: Some(now - chrono::Duration::hours(24)),
+        limit: 20,
+        ..AuditFilter::default()
+    };
+    let breakers: Vec<AuditEntry> = audit::load(&db_pool, &filter).await?.into_iter()
+        .filter(|e| matches!(e.action, AuditAction::Halted | AuditAction::Resumed))
+        .collect();
+    println!("🚦 Breaker activity, last 24h: {}", if breakers.is_empty() { "none" } else { "" });
+    for e in breakers {
+        println!("   {} {:<8} {}", e.occurred_at.format("%m-%d %H:%M:%S"), e.action.as_str(), e.reason_code);
+    }
+    Ok(())
+}
+
+async fn replay(args: ReplayArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let component = match args.component.as_deref() {
+        Some(name) => Some(Component::parse(name).ok_or_else(|| format!("unknown component {}", name))?),
+        None => None,
+    };
+    let filter = AuditFilter {
+        run_id: args.run,
+        subject: args.subject,
+        component,
+        since: Some(chrono::Utc::now() - chrono::Duration::hours(args.hours)),
+        limit: args.limit,
+    };
+    
+    let entries = audit::load(&connect().await?, &filter).await?;
+    for e in &entries {
+        let detail = if e.detail.is_null() { String::new() } else { e.detail.to_string() };
+        println!("{} {:<9} {:<9} {:<18} {:<28} {}",
+            e.occurred_at.format("%Y-%m-%d %H:%M:%S"), e.component.as_str(), e.action.as_str(),
+            e.subject, e.reason_code, detail);
+    }
+    println!("📜 {} entries", entries.len());
+    Ok(())
+}
+
+async fn replay_pattern(args: ReplayPatternArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let db_pool = connect().await?;
+    let (hypothesis, discovered_at) = pattern_replay::load_pattern(&db_pool, &args.hash).await?
+        .ok_or_else(|| format!("no pattern {}", args.hash))?;
+    let symbol = args.symbol.or_else(|| hypothesis.symbol.clone())
+        .ok_or_else(|| format!("{} trades any symbol; pass --symbol", args.hash))?;
+    let from = args.from.unwrap_or_else(|| chrono::Utc::now() - chrono::Duration::hours(args.hours));
+    let to = from + chrono::Duration::hours(args.hours);
+
+    // An hour of warm-up, so volume ratios have their baseline from the first printed minute
+    let warmup = chrono::Duration::minutes(60);
+    let candles = evaluator::load_candles(&db_pool, &symbol, from - warmup, to).await?;
+    let steps: Vec<_> = evaluator::trace(&hypothesis, &candles).into_iter().filter(|step| step.at > from).collect();
+    let decisions = audit::load_for_pattern(&db_pool, &args.hash, from, to).await?;
+
+    println!("🔎 {} on {} from {} to {} (discovered {}, timeframe {}m)", args.hash, symbol,
+        from.format("%Y-%m-%d %H:%M"), to.format("%Y-%m-%d %H:%M"), discovered_at.format("%Y-%m-%d %H:%M"), hypothesis.timeframe);
+    let (minutes, recorded) = (steps.len(), decisions.len());
+    let events = pattern_replay::timeline(steps, decisions);
+    let signals = events.iter().filter(|event| event.is_signal()).count();
+    for event in events.iter().filter(|event| !args.signals_only || !matches!(event, ReplayEvent::Minute(_)) || event.is_signal()) {
+        println!("{}", event.describe());
+    }
+    println!("📜 {} minutes, {} signals, {} recorded decisions", minutes, signals, recorded);
+    Ok(())
+}
+
+async fn retention_pass() -> Result<(), Box<dyn std::error::Error>> {
+    let retention = Retention::from_env(connect().await?);
+    println!("🗄️ Retention: {}", retention.describe());
+    for pass in retention.run_once().await {
+        println!("  {}: {} rows pruned over {} days", pass.table, pass.rows_pruned, pass.days);
+        for file in &pass.archived_to {
+            println!("    → {}", file);
+        }
+    }
+    Ok(())
+}
+
+/// Everything `run` checks before starting, printed, without starting anything
+async fn doctor(dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let config = AppConfig::load()?;
+    let manifest = RunManifest::collect(serde_json::json!({}));
+    let context = ExecutionContext::for_run(dry_run, &manifest);
+    let accounts = if config.accounts.is_empty() {
+        AccountConfig::per_venue(&manifest.venues)
+    } else {
+        config.accounts.clone()
+    };
+    let market_data = MarketDataFeed::new(16);
+    let preflight = Preflight {
+        config: PreflightConfig::from_env(),
+        venues: connect_exchanges(context, &accounts, &market_data, config.initial_capital)?,
+        accounts,
+        starting_capital: config.initial_capital,
+    };
+    
+    let db_pool = connect().await.map_err(|e| e.to_string());
+    let report = preflight.run(db_pool.as_ref().map_err(Clone::clone), &sqlx::migrate!("./migrations")).await;
+    println!("🩺 Preflight for a {} run", context.as_str());
+    for check in &report.checks {
+        println!("  {}", check_line(check));
+    }
+    match report.status {
+        CheckStatus::Down => {
+            let down = report.checks.iter().filter(|c| c.status == CheckStatus::Down).count();
+            Err(format!("{} checks down", down).into())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_line(check: &SubsystemCheck) -> String {
+    let icon = match check.status {
+        CheckStatus::Ok => "✅",
+        CheckStatus::Degraded => "⚠️",
+        CheckStatus::Down => "❌",
+    };
+    format!("{} {}: {}", icon, check.name, check.detail)
+}
+
+async fn migrate() -> Result<(), Box<dyn std::error::Error>> {
+    let migrator = sqlx::migrate!("./migrations");
+    migrator.run(&connect().await?).await?;
+    let latest = migrator.iter().map(|m| m.version).max().unwrap_or(0);
+    println!("🗄️ Database migrated to version {}", latest);
+    Ok(())
+}
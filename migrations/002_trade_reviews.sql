@@ -0,0 +1,15 @@
+-- Operator review queue for randomly sampled live trades
+
+CREATE TABLE trade_reviews (
+    review_id BIGSERIAL PRIMARY KEY,
+    pattern_hash VARCHAR(64) NOT NULL,
+    journal JSONB NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'escalated')),
+    reviewer VARCHAR(100),
+    notes TEXT,
+    flagged_at TIMESTAMPTZ DEFAULT NOW(),
+    reviewed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_trade_reviews_pending ON trade_reviews(flagged_at) WHERE status = 'pending';
+CREATE INDEX idx_trade_reviews_pattern ON trade_reviews(pattern_hash);
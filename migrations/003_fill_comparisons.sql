@@ -0,0 +1,16 @@
+-- Simulated vs live fills per signal, used for per-pattern drift reporting
+
+CREATE TABLE fill_comparisons (
+    id BIGSERIAL PRIMARY KEY,
+    pattern_hash VARCHAR(64) NOT NULL,
+    sim_price DOUBLE PRECISION NOT NULL,
+    sim_pnl DOUBLE PRECISION NOT NULL,
+    signal_time TIMESTAMPTZ NOT NULL,
+    live_price DOUBLE PRECISION,          -- NULL when the live order never filled
+    live_pnl DOUBLE PRECISION,
+    fill_time TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_fill_comparisons_pattern ON fill_comparisons(pattern_hash, signal_time);
+CREATE INDEX idx_fill_comparisons_time ON fill_comparisons(signal_time);
@@ -0,0 +1,34 @@
+-- Run manifests: the exact code and config behind every decision row
+
+CREATE TABLE run_manifests (
+    run_id UUID PRIMARY KEY,
+    git_commit VARCHAR(64) NOT NULL,
+    config_hash VARCHAR(64) NOT NULL,
+    trading_mode VARCHAR(10) NOT NULL CHECK (trading_mode IN ('live', 'paper')),
+    venues TEXT[] NOT NULL DEFAULT '{}',
+    symbol_universe TEXT[] NOT NULL DEFAULT '{}',
+    risk_limits JSONB NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Hypothesis test outcomes written by the discovery engine
+CREATE TABLE IF NOT EXISTS test_results (
+    id SERIAL PRIMARY KEY,
+    pattern_hash VARCHAR(64) REFERENCES discovered_patterns(pattern_hash),
+    profitable BOOLEAN NOT NULL,
+    profit DOUBLE PRECISION NOT NULL,
+    entry_price DOUBLE PRECISION NOT NULL,
+    exit_price DOUBLE PRECISION NOT NULL,
+    duration_seconds INTEGER NOT NULL,
+    timestamp TIMESTAMPTZ DEFAULT NOW()
+);
+
+ALTER TABLE discovered_patterns ADD COLUMN run_id UUID REFERENCES run_manifests(run_id);
+ALTER TABLE test_results ADD COLUMN run_id UUID REFERENCES run_manifests(run_id);
+ALTER TABLE trades ADD COLUMN run_id UUID REFERENCES run_manifests(run_id);
+ALTER TABLE risk_events ADD COLUMN run_id UUID REFERENCES run_manifests(run_id);
+ALTER TABLE trade_reviews ADD COLUMN run_id UUID REFERENCES run_manifests(run_id);
+ALTER TABLE fill_comparisons ADD COLUMN run_id UUID REFERENCES run_manifests(run_id);
+
+CREATE INDEX idx_test_results_pattern ON test_results(pattern_hash);
+CREATE INDEX idx_test_results_run ON test_results(run_id);
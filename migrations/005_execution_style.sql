@@ -0,0 +1,5 @@
+-- Execution style bound to each pattern and honored by the execution engine
+
+ALTER TABLE discovered_patterns
+    ADD COLUMN execution_style VARCHAR(20) NOT NULL DEFAULT 'market'
+    CHECK (execution_style IN ('market', 'passive_limit', 'twap'));
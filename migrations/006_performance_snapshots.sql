@@ -0,0 +1,17 @@
+-- Rolling per-pattern performance windows, frozen daily as the inputs to evolution selection
+
+CREATE TABLE pattern_performance_snapshots (
+    snapshot_date DATE NOT NULL,
+    pattern_hash VARCHAR(64) NOT NULL REFERENCES discovered_patterns(pattern_hash),
+    window_days INTEGER NOT NULL CHECK (window_days > 0),
+    trades INTEGER NOT NULL,
+    hit_rate DOUBLE PRECISION NOT NULL,
+    total_pnl DOUBLE PRECISION NOT NULL,
+    sharpe DOUBLE PRECISION NOT NULL,
+    max_drawdown DOUBLE PRECISION NOT NULL,
+    run_id UUID REFERENCES run_manifests(run_id),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    PRIMARY KEY (snapshot_date, pattern_hash, window_days)
+);
+
+CREATE INDEX idx_performance_snapshots_window ON pattern_performance_snapshots(snapshot_date, window_days);
@@ -0,0 +1,27 @@
+-- Every order the execution engine places, with its lifecycle state
+-- Unfinished rows are reconciled against the venue on restart
+
+CREATE TABLE orders (
+    client_order_id VARCHAR(64) PRIMARY KEY,
+    exchange VARCHAR(50) NOT NULL,
+    exchange_order_id VARCHAR(128),
+    symbol VARCHAR(20) NOT NULL,
+    side VARCHAR(4) NOT NULL CHECK (side IN ('buy', 'sell')),
+    size DOUBLE PRECISION NOT NULL,
+    limit_price DOUBLE PRECISION,
+    reduce_only BOOLEAN NOT NULL DEFAULT FALSE,
+    request JSONB NOT NULL,
+    intent JSONB NOT NULL,
+    state VARCHAR(20) NOT NULL CHECK (state IN
+        ('pending_submit', 'open', 'partially_filled', 'filled', 'cancelled', 'rejected')),
+    filled_size DOUBLE PRECISION NOT NULL DEFAULT 0,
+    avg_fill_price DOUBLE PRECISION NOT NULL DEFAULT 0,
+    fees DOUBLE PRECISION NOT NULL DEFAULT 0,
+    reject_reason TEXT,
+    run_id UUID REFERENCES run_manifests(run_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_orders_unfinished ON orders(created_at)
+    WHERE state IN ('pending_submit', 'open', 'partially_filled');
@@ -0,0 +1,4 @@
+-- Re-priced residuals point back at the order whose position they fill
+
+ALTER TABLE orders ADD COLUMN parent_order_id VARCHAR(64) REFERENCES orders(client_order_id);
+ALTER TABLE orders ADD COLUMN reprice_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- Fees and slippage deducted from each test trade; profit is stored net of both
+
+ALTER TABLE test_results ADD COLUMN fees DOUBLE PRECISION NOT NULL DEFAULT 0;
+ALTER TABLE test_results ADD COLUMN slippage DOUBLE PRECISION NOT NULL DEFAULT 0;
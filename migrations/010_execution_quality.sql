@@ -0,0 +1,22 @@
+-- Order timing and per-order execution quality against the decision price
+
+ALTER TABLE orders ADD COLUMN submitted_at TIMESTAMPTZ;
+ALTER TABLE orders ADD COLUMN first_fill_at TIMESTAMPTZ;
+
+CREATE TABLE execution_quality (
+    client_order_id VARCHAR(64) PRIMARY KEY REFERENCES orders(client_order_id),
+    pattern_hash VARCHAR(64),
+    symbol VARCHAR(20) NOT NULL,
+    side VARCHAR(4) NOT NULL CHECK (side IN ('buy', 'sell')),
+    decision_price DOUBLE PRECISION NOT NULL,
+    avg_fill_price DOUBLE PRECISION NOT NULL,
+    filled_size DOUBLE PRECISION NOT NULL,
+    decision_to_submit_ms BIGINT NOT NULL,
+    submit_to_first_fill_ms BIGINT,
+    shortfall_bps DOUBLE PRECISION NOT NULL,
+    shortfall DOUBLE PRECISION NOT NULL,
+    decided_at TIMESTAMPTZ NOT NULL,
+    run_id UUID REFERENCES run_manifests(run_id)
+);
+
+CREATE INDEX idx_execution_quality_pattern ON execution_quality(pattern_hash, decided_at);
@@ -0,0 +1,5 @@
+-- Inputs for the selectable fitness functions (profit factor, Calmar, drawdown-penalized return)
+
+ALTER TABLE pattern_performance_snapshots ADD COLUMN gross_profit DOUBLE PRECISION NOT NULL DEFAULT 0;
+ALTER TABLE pattern_performance_snapshots ADD COLUMN gross_loss DOUBLE PRECISION NOT NULL DEFAULT 0;
+ALTER TABLE pattern_performance_snapshots ADD COLUMN avg_daily_return DOUBLE PRECISION NOT NULL DEFAULT 0;
@@ -0,0 +1,19 @@
+-- Best-ever patterns, kept with their full result history so the population can be rebuilt
+-- after a purge; deliberately no foreign key so archived patterns outlive discovered_patterns rows
+
+CREATE TABLE hall_of_fame (
+    pattern_hash VARCHAR(64) PRIMARY KEY,
+    entry_conditions JSONB NOT NULL,
+    exit_conditions JSONB NOT NULL,
+    timeframe_minutes INTEGER NOT NULL,
+    generation INTEGER NOT NULL DEFAULT 0,
+    parent_patterns TEXT[] NOT NULL DEFAULT '{}',
+    fitness DOUBLE PRECISION NOT NULL,
+    fitness_metric VARCHAR(32) NOT NULL,
+    performance JSONB NOT NULL,        -- the selection window it was inducted on
+    result_history JSONB NOT NULL,     -- every test_results row at induction time
+    inducted_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_hall_of_fame_metric ON hall_of_fame(fitness_metric, fitness DESC);
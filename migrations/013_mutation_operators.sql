@@ -0,0 +1,4 @@
+-- Per-generation mutation operator rates and how often each fired
+
+ALTER TABLE evolution_history ADD COLUMN mutation_rates JSONB;
+ALTER TABLE evolution_history ADD COLUMN mutation_operators JSONB;
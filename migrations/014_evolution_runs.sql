@@ -0,0 +1,21 @@
+-- One row per evolution cycle with population-level stats, so each cycle's effect is measurable
+
+CREATE TABLE evolution_runs (
+    id BIGSERIAL PRIMARY KEY,
+    generation INTEGER NOT NULL,
+    fitness_metric VARCHAR(32) NOT NULL,
+    population INTEGER NOT NULL,
+    mean_fitness DOUBLE PRECISION NOT NULL,
+    max_fitness DOUBLE PRECISION NOT NULL,
+    diversity_index DOUBLE PRECISION NOT NULL,  -- Shannon entropy over species sizes
+    species INTEGER NOT NULL,
+    survivors INTEGER NOT NULL,
+    culled INTEGER NOT NULL,
+    offspring INTEGER NOT NULL,
+    mutations INTEGER NOT NULL,
+    crossovers INTEGER NOT NULL,
+    run_id UUID REFERENCES run_manifests(run_id),
+    completed_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_evolution_runs_completed ON evolution_runs(completed_at);
@@ -0,0 +1,9 @@
+-- Patterns can be bound to one symbol, e.g. when crossbred from a correlated market,
+-- and each evolution run records how many offspring were bred that way
+
+ALTER TABLE discovered_patterns ADD COLUMN symbol VARCHAR(20);
+ALTER TABLE hall_of_fame ADD COLUMN symbol VARCHAR(20);
+
+CREATE INDEX idx_patterns_symbol ON discovered_patterns(symbol);
+
+ALTER TABLE evolution_runs ADD COLUMN crossbreeds INTEGER NOT NULL DEFAULT 0;
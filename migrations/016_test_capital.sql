@@ -0,0 +1,3 @@
+-- Capital committed per test trade, which now scales with equity; NULL on older rows means $5
+
+ALTER TABLE test_results ADD COLUMN capital DOUBLE PRECISION;
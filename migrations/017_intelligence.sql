@@ -0,0 +1,28 @@
+-- Native intelligence layer: token usage per LLM call, and the sentiment reports it produces
+
+CREATE TABLE llm_usage (
+    id BIGSERIAL PRIMARY KEY,
+    task VARCHAR(64) NOT NULL,           -- prompt name, e.g. market_sentiment
+    model VARCHAR(64) NOT NULL,
+    prompt_tokens INTEGER NOT NULL,
+    completion_tokens INTEGER NOT NULL,
+    total_tokens INTEGER NOT NULL,
+    attempts INTEGER NOT NULL,           -- tokens are summed over every attempt, failed ones included
+    succeeded BOOLEAN NOT NULL,
+    error TEXT,
+    latency_ms BIGINT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_llm_usage_created ON llm_usage(created_at);
+
+CREATE TABLE sentiment_reports (
+    id BIGSERIAL PRIMARY KEY,
+    overall_sentiment DOUBLE PRECISION NOT NULL,
+    fear_greed_index DOUBLE PRECISION NOT NULL,
+    report JSONB NOT NULL,
+    model VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_sentiment_reports_created ON sentiment_reports(created_at);
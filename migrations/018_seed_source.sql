@@ -0,0 +1,6 @@
+-- Which arm produced a fresh hypothesis: 'random' or 'llm' (template from the intelligence layer)
+-- NULL for evolved offspring and rows written before seeding was tracked
+
+ALTER TABLE discovered_patterns ADD COLUMN seed_source VARCHAR(16);
+
+CREATE INDEX idx_patterns_seed_source ON discovered_patterns(seed_source) WHERE seed_source IS NOT NULL;
@@ -0,0 +1,28 @@
+-- News and announcement headlines with their sentiment scores, and the metrics published from them
+
+CREATE TABLE news_items (
+    guid TEXT PRIMARY KEY,                 -- feed name + item guid (or link)
+    source VARCHAR(64) NOT NULL,
+    kind VARCHAR(16) NOT NULL,             -- news | announcement
+    title TEXT NOT NULL,
+    link TEXT,
+    published_at TIMESTAMPTZ NOT NULL,
+    symbols TEXT[] DEFAULT '{}',
+    score DOUBLE PRECISION NOT NULL,       -- -1.0 bearish to 1.0 bullish
+    scorer VARCHAR(16) NOT NULL,           -- llm | lexicon
+    ingested_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_news_items_published ON news_items(published_at);
+
+-- Condition vocabulary inputs: sentiment_1h and news_shock, per symbol and market-wide (symbol NULL)
+CREATE TABLE sentiment_metrics (
+    id BIGSERIAL PRIMARY KEY,
+    symbol VARCHAR(20),
+    sentiment_1h DOUBLE PRECISION NOT NULL,
+    news_shock DOUBLE PRECISION NOT NULL,
+    items_1h INTEGER NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_sentiment_metrics_symbol ON sentiment_metrics(symbol, computed_at);
@@ -0,0 +1,25 @@
+-- Ticker mentions picked up by the social scanners, and the metrics published from them
+
+CREATE TABLE social_mentions (
+    platform VARCHAR(16) NOT NULL,         -- reddit | x
+    post_id VARCHAR(64) NOT NULL,
+    ticker VARCHAR(20) NOT NULL,
+    posted_at TIMESTAMPTZ NOT NULL,
+    engagement DOUBLE PRECISION NOT NULL,  -- refreshed while the post stays in the feed
+    PRIMARY KEY (platform, post_id, ticker)
+);
+
+CREATE INDEX idx_social_mentions_ticker ON social_mentions(ticker, posted_at);
+
+-- Condition vocabulary inputs: social_velocity and social_engagement per ticker
+CREATE TABLE social_metrics (
+    id BIGSERIAL PRIMARY KEY,
+    ticker VARCHAR(20) NOT NULL,
+    mentions_1h INTEGER NOT NULL,
+    engagement_1h DOUBLE PRECISION NOT NULL,
+    social_velocity DOUBLE PRECISION NOT NULL,
+    social_engagement DOUBLE PRECISION NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_social_metrics_ticker ON social_metrics(ticker, computed_at);
@@ -0,0 +1,13 @@
+-- Structural embeddings of every hypothesis for nearest-neighbour search (requires pgvector)
+-- The dimension must match core::embedding::EMBEDDING_DIM
+
+CREATE EXTENSION IF NOT EXISTS vector;
+
+CREATE TABLE pattern_embeddings (
+    pattern_hash VARCHAR(64) PRIMARY KEY REFERENCES discovered_patterns(pattern_hash) ON DELETE CASCADE,
+    embedding vector(103) NOT NULL,
+    version INTEGER NOT NULL,              -- core::embedding::EMBEDDING_VERSION at write time
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_pattern_embeddings_hnsw ON pattern_embeddings USING hnsw (embedding vector_cosine_ops);
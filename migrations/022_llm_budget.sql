@@ -0,0 +1,12 @@
+-- Intelligence cost governor: what each call cost, and spend per UTC day
+
+ALTER TABLE llm_usage ADD COLUMN cost_usd DOUBLE PRECISION NOT NULL DEFAULT 0;  -- priced at the model's list rate
+
+CREATE VIEW llm_daily_spend AS
+SELECT (created_at AT TIME ZONE 'UTC')::date AS day,
+       model,
+       COUNT(*) AS calls,
+       SUM(total_tokens) AS total_tokens,
+       SUM(cost_usd) AS cost_usd
+FROM llm_usage
+GROUP BY 1, 2;
@@ -0,0 +1,15 @@
+-- Provider chain: which backend served each call (openai | anthropic | local)
+
+ALTER TABLE llm_usage ADD COLUMN provider VARCHAR(16) NOT NULL DEFAULT 'openai';
+
+DROP VIEW llm_daily_spend;
+
+CREATE VIEW llm_daily_spend AS
+SELECT (created_at AT TIME ZONE 'UTC')::date AS day,
+       provider,
+       model,
+       COUNT(*) AS calls,
+       SUM(total_tokens) AS total_tokens,
+       SUM(cost_usd) AS cost_usd
+FROM llm_usage
+GROUP BY 1, 2, 3;
@@ -0,0 +1,24 @@
+-- Versioned system prompts per intelligence task, and the version each LLM output came from
+
+CREATE TABLE prompt_versions (
+    task VARCHAR(64) NOT NULL,               -- StructuredPrompt::NAME
+    version INTEGER NOT NULL,
+    system_prompt TEXT NOT NULL,
+    content_hash VARCHAR(16) NOT NULL,
+    source VARCHAR(16) NOT NULL,             -- builtin (shipped in code) | operator
+    notes TEXT,
+    is_active BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    activated_at TIMESTAMPTZ,
+    PRIMARY KEY (task, version),
+    UNIQUE (task, content_hash)
+);
+
+-- At most one live version per task
+CREATE UNIQUE INDEX idx_prompt_versions_active ON prompt_versions(task) WHERE is_active;
+
+-- NULL where the built-in prompt ran unversioned (prompt store unavailable) or no LLM was involved
+ALTER TABLE llm_usage ADD COLUMN prompt_version INTEGER;
+ALTER TABLE sentiment_reports ADD COLUMN prompt_version INTEGER;
+ALTER TABLE news_items ADD COLUMN prompt_version INTEGER;
+ALTER TABLE discovered_patterns ADD COLUMN prompt_version INTEGER;  -- LLM-seeded hypotheses only
@@ -0,0 +1,8 @@
+-- Daily performance report: performance_metrics gets one row per UTC day, written at rollover,
+-- with the full report (including the trailing equity curve) alongside the summary columns
+
+ALTER TABLE performance_metrics ADD COLUMN total_patterns INTEGER;
+ALTER TABLE performance_metrics ADD COLUMN run_id UUID REFERENCES run_manifests(run_id);
+ALTER TABLE performance_metrics ADD COLUMN report JSONB;
+
+CREATE INDEX idx_trades_exit_time ON trades(exit_time) WHERE status = 'closed';
@@ -0,0 +1,27 @@
+-- Append-only record of every approval, rejection and activation, with reason codes
+
+CREATE TABLE audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    run_id UUID REFERENCES run_manifests(run_id),
+    component VARCHAR(16) NOT NULL,   -- discovery | risk | execution
+    action VARCHAR(16) NOT NULL,      -- approved | rejected | activated | skipped | halted | resumed
+    subject VARCHAR(64) NOT NULL,     -- pattern hash, position id, or 'system'
+    reason_code VARCHAR(64) NOT NULL,
+    detail JSONB
+);
+
+CREATE INDEX idx_audit_log_subject ON audit_log(subject, occurred_at);
+CREATE INDEX idx_audit_log_time ON audit_log(occurred_at);
+CREATE INDEX idx_audit_log_reason ON audit_log(component, reason_code, occurred_at);
+
+-- History is not editable
+CREATE OR REPLACE FUNCTION audit_log_append_only()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_no_update BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
@@ -0,0 +1,12 @@
+-- Minute-by-minute equity snapshots from the monitoring loop, for drawdown and CAGR analytics
+
+CREATE TABLE equity_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    taken_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    run_id UUID REFERENCES run_manifests(run_id),
+    equity DOUBLE PRECISION NOT NULL,
+    open_exposure DOUBLE PRECISION NOT NULL,
+    open_positions INTEGER NOT NULL
+);
+
+CREATE INDEX idx_equity_snapshots_time ON equity_snapshots(taken_at);
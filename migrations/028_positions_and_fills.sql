@@ -0,0 +1,44 @@
+-- The open book and every fill, written through as RiskManager and the execution engine
+-- change them, so positions survive a restart and P&L can be rebuilt from fills alone
+
+CREATE TABLE positions (
+    position_id VARCHAR(64) PRIMARY KEY,  -- client_order_id of the opening order
+    run_id UUID REFERENCES run_manifests(run_id),
+    pattern_hash VARCHAR(64) NOT NULL,
+    symbol VARCHAR(32) NOT NULL,
+    exchange VARCHAR(32) NOT NULL,
+    size DOUBLE PRECISION NOT NULL,        -- notional at entry; 0 once closed
+    entry_price DOUBLE PRECISION NOT NULL,
+    stop_loss DOUBLE PRECISION NOT NULL,
+    take_profit DOUBLE PRECISION NOT NULL,
+    realized_pnl DOUBLE PRECISION NOT NULL DEFAULT 0,
+    state VARCHAR(10) NOT NULL DEFAULT 'open' CHECK (state IN ('open', 'closed')),
+    opened_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    closed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_positions_open ON positions(state) WHERE state = 'open';
+CREATE INDEX idx_positions_pattern ON positions(pattern_hash);
+
+-- No foreign key to orders: an order row that failed to write must not also lose its fills
+CREATE TABLE fills (
+    id BIGSERIAL PRIMARY KEY,
+    client_order_id VARCHAR(64) NOT NULL,
+    position_id VARCHAR(64) NOT NULL,
+    run_id UUID REFERENCES run_manifests(run_id),
+    kind VARCHAR(5) NOT NULL CHECK (kind IN ('entry', 'exit')),
+    symbol VARCHAR(32) NOT NULL,
+    exchange VARCHAR(32) NOT NULL,
+    size DOUBLE PRECISION NOT NULL,
+    price DOUBLE PRECISION NOT NULL,
+    fees DOUBLE PRECISION NOT NULL,
+    pnl DOUBLE PRECISION,  -- exits only, net of fees
+    filled_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_fills_position ON fills(position_id);
+CREATE INDEX idx_fills_time ON fills(filled_at);
+
+-- Breaker trips and clears now land in risk_events with the numbers behind them
+ALTER TABLE risk_events ADD COLUMN detail JSONB;
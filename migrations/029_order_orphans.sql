@@ -0,0 +1,6 @@
+-- Orders still working when the process shut down, and could not be cancelled, are tagged
+-- here so the next start knows to sweep them before trading again
+
+ALTER TABLE orders ADD COLUMN orphaned_at TIMESTAMPTZ;
+
+CREATE INDEX idx_orders_orphaned ON orders(orphaned_at) WHERE orphaned_at IS NOT NULL;
@@ -0,0 +1,12 @@
+-- Batches of buffered inserts (test results, ticks) that their table rejected, parked as JSON
+-- and retried by the batch writer until they land or run out of attempts
+
+CREATE TABLE write_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    target VARCHAR(32) NOT NULL CHECK (target IN ('test_results', 'market_data')),
+    rows JSONB NOT NULL,  -- array of row objects, column name -> value
+    attempts INTEGER NOT NULL DEFAULT 1,
+    last_error TEXT,
+    parked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
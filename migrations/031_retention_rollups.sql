@@ -0,0 +1,27 @@
+-- Aggregates kept after retention prunes raw rows: hourly OHLC per symbol and venue for ticks,
+-- daily totals per pattern for test results of hypotheses nobody is testing any more
+
+CREATE TABLE market_data_hourly (
+    symbol VARCHAR(20) NOT NULL,
+    exchange VARCHAR(50) NOT NULL,
+    hour TIMESTAMPTZ NOT NULL,
+    open DOUBLE PRECISION NOT NULL,
+    high DOUBLE PRECISION NOT NULL,
+    low DOUBLE PRECISION NOT NULL,
+    close DOUBLE PRECISION NOT NULL,
+    volume DOUBLE PRECISION,  -- summed over ticks that reported one
+    ticks BIGINT NOT NULL,
+    PRIMARY KEY (symbol, exchange, hour)
+);
+
+CREATE TABLE test_result_daily (
+    pattern_hash VARCHAR(64) NOT NULL,
+    day DATE NOT NULL,
+    tests INTEGER NOT NULL,
+    wins INTEGER NOT NULL,
+    profit DOUBLE PRECISION NOT NULL,
+    fees DOUBLE PRECISION NOT NULL,
+    slippage DOUBLE PRECISION NOT NULL,
+    capital DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (pattern_hash, day)
+);
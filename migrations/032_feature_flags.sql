@@ -0,0 +1,10 @@
+-- Runtime feature flags, toggled through the control API and re-read by every process;
+-- a flag with no row here keeps its default
+
+CREATE TABLE feature_flags (
+    name VARCHAR(64) PRIMARY KEY,
+    enabled BOOLEAN NOT NULL,
+    rollout_pct DOUBLE PRECISION NOT NULL DEFAULT 1.0 CHECK (rollout_pct >= 0 AND rollout_pct <= 1),
+    updated_by VARCHAR(32) NOT NULL,  -- control API role that last changed it
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
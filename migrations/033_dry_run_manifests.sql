@@ -0,0 +1,5 @@
+-- `--dry-run` processes record their manifest with trading_mode 'dry_run'
+
+ALTER TABLE run_manifests DROP CONSTRAINT IF EXISTS run_manifests_trading_mode_check;
+ALTER TABLE run_manifests ADD CONSTRAINT run_manifests_trading_mode_check
+    CHECK (trading_mode IN ('live', 'paper', 'dry_run'));
@@ -0,0 +1,3 @@
+-- The seed a run drew its random numbers from; V26MEME_SEED=<seed> replays them
+
+ALTER TABLE run_manifests ADD COLUMN seed BIGINT;
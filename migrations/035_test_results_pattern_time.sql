@@ -0,0 +1,6 @@
+-- Discovery reads every test result of a pattern after each test trade, and backtests read a
+-- window of them, both oldest first. One index serves the lookup and the order, and covers what
+-- idx_test_results_pattern did
+
+CREATE INDEX idx_test_results_pattern_time ON test_results (pattern_hash, timestamp);
+DROP INDEX IF EXISTS idx_test_results_pattern;
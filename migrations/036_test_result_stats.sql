@@ -0,0 +1,24 @@
+-- Running aggregates of each hypothesis's test trades, so validation after a test reads one row
+-- instead of the whole history. Returns are profit over committed capital, NULL capital being
+-- the $5 every test used to commit
+
+CREATE TABLE test_result_stats (
+    pattern_hash VARCHAR(64) PRIMARY KEY,
+    tests INTEGER NOT NULL,
+    wins INTEGER NOT NULL,
+    profit DOUBLE PRECISION NOT NULL,
+    return_sum DOUBLE PRECISION NOT NULL,
+    return_sq_sum DOUBLE PRECISION NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO test_result_stats (pattern_hash, tests, wins, profit, return_sum, return_sq_sum)
+SELECT pattern_hash,
+       COUNT(*),
+       COUNT(*) FILTER (WHERE profitable),
+       SUM(profit),
+       SUM(profit / COALESCE(capital, 5.0)),
+       SUM((profit / COALESCE(capital, 5.0)) ^ 2)
+FROM test_results
+WHERE pattern_hash IS NOT NULL
+GROUP BY pattern_hash;
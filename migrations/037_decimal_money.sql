@@ -0,0 +1,34 @@
+-- Capital, sizes, prices, fees and P&L are rust_decimal::Decimal in the process now, so the
+-- columns that hold them are NUMERIC and keep every digit. Existing DOUBLE values convert as
+-- they are; equity snapshots and the statistics tables stay DOUBLE PRECISION
+
+ALTER TABLE orders
+    ALTER COLUMN size TYPE NUMERIC USING size::numeric,
+    ALTER COLUMN limit_price TYPE NUMERIC USING limit_price::numeric,
+    ALTER COLUMN filled_size TYPE NUMERIC USING filled_size::numeric,
+    ALTER COLUMN avg_fill_price TYPE NUMERIC USING avg_fill_price::numeric,
+    ALTER COLUMN fees TYPE NUMERIC USING fees::numeric;
+
+ALTER TABLE positions
+    ALTER COLUMN size TYPE NUMERIC USING size::numeric,
+    ALTER COLUMN entry_price TYPE NUMERIC USING entry_price::numeric,
+    ALTER COLUMN stop_loss TYPE NUMERIC USING stop_loss::numeric,
+    ALTER COLUMN take_profit TYPE NUMERIC USING take_profit::numeric,
+    ALTER COLUMN realized_pnl TYPE NUMERIC USING realized_pnl::numeric;
+
+ALTER TABLE fills
+    ALTER COLUMN size TYPE NUMERIC USING size::numeric,
+    ALTER COLUMN price TYPE NUMERIC USING price::numeric,
+    ALTER COLUMN fees TYPE NUMERIC USING fees::numeric,
+    ALTER COLUMN pnl TYPE NUMERIC USING pnl::numeric;
+
+ALTER TABLE test_results
+    ALTER COLUMN profit TYPE NUMERIC USING profit::numeric,
+    ALTER COLUMN entry_price TYPE NUMERIC USING entry_price::numeric,
+    ALTER COLUMN exit_price TYPE NUMERIC USING exit_price::numeric,
+    ALTER COLUMN fees TYPE NUMERIC USING fees::numeric,
+    ALTER COLUMN slippage TYPE NUMERIC USING slippage::numeric,
+    ALTER COLUMN capital TYPE NUMERIC USING capital::numeric;
+
+ALTER TABLE test_result_stats
+    ALTER COLUMN profit TYPE NUMERIC USING profit::numeric;
@@ -0,0 +1,339 @@
+// Risk Configuration
+// Every limit the RiskManager enforces, loaded as defaults -> TOML file -> the [risk] section
+// of the selected profile -> env vars, and validated before the manager is allowed to start
+
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+
+use crate::core::exchange::split_symbol;
+
+pub const DEFAULT_PATH: &str = "config/risk.toml";
+
+/// Limits that replace the base values once capital reaches `min_capital`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapitalTier {
+    pub min_capital: f64,
+    pub max_position_size_pct: f64,
+    pub kelly_fraction: f64,
+}
+
+/// Runtime change to the scalar limits; unset fields keep their current value
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitUpdate {
+    pub max_position_size_pct: Option<f64>,
+    pub max_daily_drawdown_pct: Option<f64>,
+    pub max_var_1d_pct: Option<f64>,
+    pub max_concurrent_positions: Option<u32>,
+    pub min_win_rate: Option<f64>,
+    pub kelly_fraction: Option<f64>,
+    pub max_order_pct: Option<f64>,
+    pub loss_15min_pct: Option<f64>,
+    pub loss_1hr_pct: Option<f64>,
+    pub max_asset_exposure_pct: Option<f64>,
+    pub max_venue_exposure_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskConfig {
+    pub max_position_size_pct: f64,      // 0.25 (25% of capital)
+    pub max_daily_drawdown_pct: f64,     // 0.30 (30% stop)
+    pub max_var_1d_pct: f64,             // 0.15 (1-day 95% VaR as share of capital)
+    pub max_concurrent_positions: u32,   // 10 per strategy type
+    pub min_win_rate: f64,               // 0.55 minimum to trade
+    pub kelly_fraction: f64,             // 0.25 (conservative)
+    pub min_position_size: f64,          // $5 - don't trade dust
+    pub max_order_pct: f64,              // 0.50 single order vs capital
+    pub max_portfolio_correlation: f64,  // 0.70
+
+    // Circuit breakers
+    pub loss_15min_pct: f64,             // 0.10 pauses for 1 hour
+    pub loss_1hr_pct: f64,               // 0.20 pauses for 6 hours
+
+    // Exposure caps as a fraction of current capital
+    pub max_asset_exposure_pct: f64,     // 0.40 in any single asset
+    pub max_venue_exposure_pct: f64,     // 0.60 on any single exchange
+    pub asset_exposure_caps: HashMap<String, f64>,  // by base asset; a symbol key ("DOGE-USD") caps its base
+    pub venue_exposure_caps: HashMap<String, f64>,
+
+    pub min_stop_distance_pct: f64,      // 0.01 at normal liquidity
+    pub degradation_grace_secs: i64,     // 120s of trouble before reduce-only
+
+    // Limits tighten automatically as capital grows
+    pub capital_tiers: Vec<CapitalTier>,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        RiskConfig {
+            max_position_size_pct: 0.25,
+            max_daily_drawdown_pct: 0.30,
+            max_var_1d_pct: 0.15,
+            max_concurrent_positions: 10,
+            min_win_rate: 0.55,
+            kelly_fraction: 0.25,
+            min_position_size: 5.0,
+            max_order_pct: 0.50,
+            max_portfolio_correlation: 0.70,
+            loss_15min_pct: 0.10,
+            loss_1hr_pct: 0.20,
+            max_asset_exposure_pct: 0.40,
+            max_venue_exposure_pct: 0.60,
+            asset_exposure_caps: HashMap::new(),
+            venue_exposure_caps: HashMap::new(),
+            min_stop_distance_pct: 0.01,
+            degradation_grace_secs: 120,
+            capital_tiers: vec![
+                CapitalTier { min_capital: 10_000.0, max_position_size_pct: 0.15, kelly_fraction: 0.20 },
+                CapitalTier { min_capital: 100_000.0, max_position_size_pct: 0.08, kelly_fraction: 0.15 },
+            ],
+        }
+    }
+}
+
+impl RiskConfig {
+    /// The cap on one base asset across every quote currency; the tightest matching key wins
+    pub fn asset_exposure_cap(&self, asset: &str) -> f64 {
+        self.asset_exposure_caps.iter()
+            .filter(|(key, _)| split_symbol(key).0 == asset)
+            .map(|(_, cap)| *cap)
+            .reduce(f64::min)
+            .unwrap_or(self.max_asset_exposure_pct)
+    }
+
+    /// Defaults, then the TOML file at RISK_CONFIG (or config/risk.toml if present), then env vars
+    pub fn load() -> Result<Self, String> {
+        Self::load_with(&toml::Table::new())
+    }
+
+    /// As `load`, with a profile's [risk] section between the file and the env vars
+    pub fn load_with(profile: &toml::Table) -> Result<Self, String> {
+        let path = Self::path();
+
+        let mut config = match std::fs::read_to_string(&path) {
+            Ok(text) => Self::from_toml(&text).map_err(|e| format!("{}: {}", path, e))?,
+            Err(_) if std::env::var("RISK_CONFIG").is_err() => RiskConfig::default(),
+            Err(e) => return Err(format!("cannot read {}: {}", path, e)),
+        };
+
+        config.apply_overlay(profile).map_err(|e| format!("profile [risk]: {}", e))?;
+        config.apply_env()?;
+
+        let errors = config.validate();
+        if !errors.is_empty() {
+            return Err(format!("invalid risk config: {}", errors.join("; ")));
+        }
+
+        Ok(config)
+    }
+
+    /// RISK_CONFIG, else config/risk.toml
+    pub fn path() -> String {
+        std::env::var("RISK_CONFIG").unwrap_or_else(|_| DEFAULT_PATH.to_string())
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+
+    /// Fields from `overlay` over these limits; arrays such as capital_tiers are replaced whole
+    pub fn apply_overlay(&mut self, overlay: &toml::Table) -> Result<(), String> {
+        if overlay.is_empty() {
+            return Ok(());
+        }
+        let mut tree = toml::Table::try_from(&*self).map_err(|e| e.to_string())?;
+        if let Some(unknown) = overlay.keys().find(|key| !tree.contains_key(*key)) {
+            return Err(format!("unknown field {}", unknown));
+        }
+        crate::config::merge(&mut tree, overlay);
+        *self = toml::Value::Table(tree).try_into().map_err(|e: toml::de::Error| e.to_string())?;
+        Ok(())
+    }
+
+    fn apply_env(&mut self) -> Result<(), String> {
+        fn read<T: std::str::FromStr>(key: &str, target: &mut T) -> Result<(), String> {
+            if let Ok(value) = std::env::var(key) {
+                *target = value.trim().parse()
+                    .map_err(|_| format!("{}={} is not a valid number", key, value))?;
+            }
+            Ok(())
+        }
+
+        read("MAX_POSITION_SIZE_PCT", &mut self.max_position_size_pct)?;
+        read("MAX_DAILY_DRAWDOWN_PCT", &mut self.max_daily_drawdown_pct)?;
+        read("MAX_VAR_1D_PCT", &mut self.max_var_1d_pct)?;
+        read("MAX_CONCURRENT_POSITIONS", &mut self.max_concurrent_positions)?;
+        read("MIN_WIN_RATE", &mut self.min_win_rate)?;
+        read("KELLY_FRACTION", &mut self.kelly_fraction)?;
+        read("MAX_ASSET_EXPOSURE_PCT", &mut self.max_asset_exposure_pct)?;
+        read("MAX_VENUE_EXPOSURE_PCT", &mut self.max_venue_exposure_pct)?;
+        Ok(())
+    }
+
+    /// Sanity checks - a config that fails any of these is refused outright
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        let mut fraction = |name: &str, value: f64| {
+            if !(value > 0.0 && value <= 1.0) {
+                errors.push(format!("{} must be in (0, 1], got {}", name, value));
+            }
+        };
+        fraction("max_position_size_pct", self.max_position_size_pct);
+        fraction("max_daily_drawdown_pct", self.max_daily_drawdown_pct);
+        fraction("max_var_1d_pct", self.max_var_1d_pct);
+        fraction("min_win_rate", self.min_win_rate);
+        fraction("kelly_fraction", self.kelly_fraction);
+        fraction("max_order_pct", self.max_order_pct);
+        fraction("max_portfolio_correlation", self.max_portfolio_correlation);
+        fraction("loss_15min_pct", self.loss_15min_pct);
+        fraction("loss_1hr_pct", self.loss_1hr_pct);
+        fraction("max_asset_exposure_pct", self.max_asset_exposure_pct);
+        fraction("max_venue_exposure_pct", self.max_venue_exposure_pct);
+        fraction("min_stop_distance_pct", self.min_stop_distance_pct);
+        for (symbol, cap) in &self.asset_exposure_caps {
+            fraction(&format!("asset_exposure_caps.{}", symbol), *cap);
+        }
+        for (venue, cap) in &self.venue_exposure_caps {
+            fraction(&format!("venue_exposure_caps.{}", venue), *cap);
+        }
+        for tier in &self.capital_tiers {
+            fraction(&format!("tier {} max_position_size_pct", tier.min_capital), tier.max_position_size_pct);
+            fraction(&format!("tier {} kelly_fraction", tier.min_capital), tier.kelly_fraction);
+        }
+
+        if self.max_concurrent_positions == 0 {
+            errors.push("max_concurrent_positions must be at least 1".to_string());
+        }
+        if self.min_position_size < 0.0 {
+            errors.push("min_position_size cannot be negative".to_string());
+        }
+        if self.loss_15min_pct > self.loss_1hr_pct {
+            errors.push("loss_15min_pct cannot exceed loss_1hr_pct".to_string());
+        }
+        if self.max_position_size_pct > self.max_asset_exposure_pct {
+            errors.push("max_position_size_pct cannot exceed max_asset_exposure_pct".to_string());
+        }
+
+        // Tiers must only ever tighten as capital grows
+        let mut previous = (self.max_position_size_pct, self.kelly_fraction, 0.0);
+        for tier in &self.capital_tiers {
+            if tier.min_capital <= previous.2 {
+                errors.push(format!("capital tier {} is out of order", tier.min_capital));
+            }
+            if tier.max_position_size_pct > previous.0 || tier.kelly_fraction > previous.1 {
+                errors.push(format!("capital tier {} loosens limits", tier.min_capital));
+            }
+            previous = (tier.max_position_size_pct, tier.kelly_fraction, tier.min_capital);
+        }
+
+        errors
+    }
+
+    /// `update` applied on top of these limits. A limit can be tightened freely but loosened
+    /// only as far as `ceiling`: the config the process launched with, or the limits already
+    /// in force for callers that may only tighten. The result must still pass `validate`. Capital tiers are capped at the new base so they keep tightening
+    pub fn with_update(&self, update: &LimitUpdate, ceiling: &RiskConfig) -> Result<RiskConfig, Vec<String>> {
+        fn upper(errors: &mut Vec<String>, name: &str, value: Option<f64>, ceiling: f64, target: &mut f64) {
+            match value {
+                Some(v) if v > ceiling => errors.push(format!("{} may not exceed {}, got {}", name, ceiling, v)),
+                Some(v) => *target = v,
+                None => {}
+            }
+        }
+
+        let mut next = self.clone();
+        let mut errors = Vec::new();
+        upper(&mut errors, "max_position_size_pct", update.max_position_size_pct, ceiling.max_position_size_pct, &mut next.max_position_size_pct);
+        upper(&mut errors, "max_daily_drawdown_pct", update.max_daily_drawdown_pct, ceiling.max_daily_drawdown_pct, &mut next.max_daily_drawdown_pct);
+        upper(&mut errors, "max_var_1d_pct", update.max_var_1d_pct, ceiling.max_var_1d_pct, &mut next.max_var_1d_pct);
+        upper(&mut errors, "kelly_fraction", update.kelly_fraction, ceiling.kelly_fraction, &mut next.kelly_fraction);
+        upper(&mut errors, "max_order_pct", update.max_order_pct, ceiling.max_order_pct, &mut next.max_order_pct);
+        upper(&mut errors, "loss_15min_pct", update.loss_15min_pct, ceiling.loss_15min_pct, &mut next.loss_15min_pct);
+        upper(&mut errors, "loss_1hr_pct", update.loss_1hr_pct, ceiling.loss_1hr_pct, &mut next.loss_1hr_pct);
+        upper(&mut errors, "max_asset_exposure_pct", update.max_asset_exposure_pct, ceiling.max_asset_exposure_pct, &mut next.max_asset_exposure_pct);
+        upper(&mut errors, "max_venue_exposure_pct", update.max_venue_exposure_pct, ceiling.max_venue_exposure_pct, &mut next.max_venue_exposure_pct);
+
+        // Fewer positions and a higher win-rate bar are the tighter directions
+        match update.max_concurrent_positions {
+            Some(n) if n > ceiling.max_concurrent_positions => errors.push(format!(
+                "max_concurrent_positions may not exceed {}, got {}", ceiling.max_concurrent_positions, n)),
+            Some(n) => next.max_concurrent_positions = n,
+            None => {}
+        }
+        match update.min_win_rate {
+            Some(v) if v < ceiling.min_win_rate => errors.push(format!(
+                "min_win_rate may not drop below {}, got {}", ceiling.min_win_rate, v)),
+            Some(v) => next.min_win_rate = v,
+            None => {}
+        }
+
+        for tier in &mut next.capital_tiers {
+            tier.max_position_size_pct = tier.max_position_size_pct.min(next.max_position_size_pct);
+            tier.kelly_fraction = tier.kelly_fraction.min(next.kelly_fraction);
+        }
+
+        errors.extend(next.validate());
+        if errors.is_empty() { Ok(next) } else { Err(errors) }
+    }
+
+    /// Position size cap and Kelly fraction in force at this capital level
+    pub fn limits_for_capital(&self, capital: f64) -> (f64, f64) {
+        self.capital_tiers
+            .iter()
+            .rev()
+            .find(|tier| capital >= tier.min_capital)
+            .map(|tier| (tier.max_position_size_pct, tier.kelly_fraction))
+            .unwrap_or((self.max_position_size_pct, self.kelly_fraction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_rejects_aggressive_kelly() {
+        let config = RiskConfig::from_toml("kelly_fraction = 1.5").unwrap();
+        let errors = config.validate();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("kelly_fraction"));
+        assert!(RiskConfig::default().validate().is_empty());
+    }
+
+    #[test]
+    fn test_shipped_config_matches_defaults() {
+        let shipped = RiskConfig::from_toml(include_str!("../config/risk.toml")).unwrap();
+        assert_eq!(shipped, RiskConfig::default());
+    }
+
+    #[test]
+    fn test_limits_tighten_with_capital() {
+        let config = RiskConfig::default();
+        assert_eq!(config.limits_for_capital(200.0), (0.25, 0.25));
+        assert_eq!(config.limits_for_capital(50_000.0), (0.15, 0.20));
+        assert_eq!(config.limits_for_capital(1_000_000.0), (0.08, 0.15));
+    }
+
+    #[test]
+    fn test_updates_tighten_freely_but_loosen_only_to_launch_values() {
+        let launch = RiskConfig::default();
+        let tight = launch.with_update(&LimitUpdate {
+            max_position_size_pct: Some(0.10),
+            min_win_rate: Some(0.60),
+            ..LimitUpdate::default()
+        }, &launch).unwrap();
+        assert_eq!(tight.limits_for_capital(50_000.0), (0.10, 0.20));  // tier capped at the new base
+
+        let back = tight.with_update(&LimitUpdate { max_position_size_pct: Some(0.25), ..LimitUpdate::default() }, &launch).unwrap();
+        assert_eq!(back.max_position_size_pct, 0.25);
+
+        let errors = tight.with_update(&LimitUpdate {
+            max_daily_drawdown_pct: Some(0.50),
+            min_win_rate: Some(0.50),
+            ..LimitUpdate::default()
+        }, &launch).unwrap_err();
+        assert_eq!(errors.len(), 2);
+    }
+}
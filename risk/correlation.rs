@@ -0,0 +1,119 @@
+// Empirical Pattern Correlations
+// Rolling pairwise Pearson correlations between pattern return series,
+// aligned on the days both patterns actually traded
+
+use std::collections::HashMap;
+use chrono::NaiveDate;
+use sqlx::{PgPool, Row};
+
+/// Pairs need at least this many overlapping days before we trust the estimate
+pub const MIN_OVERLAP_DAYS: usize = 5;
+
+pub fn pearson(a: &[f64], b: &[f64]) -> Option<f64> {
+    let n = a.len().min(b.len());
+    if n < 2 {
+        return None;
+    }
+
+    let mean_a = a[..n].iter().sum::<f64>() / n as f64;
+    let mean_b = b[..n].iter().sum::<f64>() / n as f64;
+
+    let mut cov = 0.0;
+    let mut var_a = 0.0;
+    let mut var_b = 0.0;
+    for i in 0..n {
+        let da = a[i] - mean_a;
+        let db = b[i] - mean_b;
+        cov += da * db;
+        var_a += da * da;
+        var_b += db * db;
+    }
+
+    if var_a == 0.0 || var_b == 0.0 {
+        return None;
+    }
+
+    Some(cov / (var_a.sqrt() * var_b.sqrt()))
+}
+
+/// Upper-triangle correlation matrix keyed by (pattern_a, pattern_b) with a < b
+pub fn correlation_matrix(
+    series: &HashMap<String, Vec<(NaiveDate, f64)>>,
+    min_overlap: usize,
+) -> HashMap<(String, String), f64> {
+    let mut hashes: Vec<&String> = series.keys().collect();
+    hashes.sort();
+
+    let by_day: HashMap<&String, HashMap<NaiveDate, f64>> = series
+        .iter()
+        .map(|(hash, points)| (hash, points.iter().copied().collect()))
+        .collect();
+
+    let mut matrix = HashMap::new();
+    for (i, a) in hashes.iter().enumerate() {
+        for b in &hashes[i + 1..] {
+            let (days_a, days_b) = (&by_day[a], &by_day[b]);
+
+            let (xs, ys): (Vec<f64>, Vec<f64>) = days_a
+                .iter()
+                .filter_map(|(day, ra)| days_b.get(day).map(|rb| (*ra, *rb)))
+                .unzip();
+
+            if xs.len() < min_overlap {
+                continue;
+            }
+
+            if let Some(corr) = pearson(&xs, &ys) {
+                matrix.insert(((*a).clone(), (*b).clone()), corr);
+            }
+        }
+    }
+
+    matrix
+}
+
+/// Patterns worth correlating: anything with a meaningful number of recent results
+pub async fn load_candidate_patterns(
+    db_pool: &PgPool,
+    days: i32,
+    min_results: i64,
+    limit: i64,
+) -> Result<Vec<String>, sqlx::Error> {
+    let query = "
+        SELECT pattern_hash
+        FROM test_results
+        WHERE timestamp > NOW() - make_interval(days => $1)
+        GROUP BY pattern_hash
+        HAVING COUNT(*) >= $2
+        ORDER BY COUNT(*) DESC
+        LIMIT $3
+    ";
+
+    let rows = sqlx::query(query)
+        .bind(days)
+        .bind(min_results)
+        .bind(limit)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| row.get("pattern_hash")).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correlation_matrix_aligns_days() {
+        let day = |d| NaiveDate::from_ymd_opt(2025, 1, d).unwrap();
+        let mut series = HashMap::new();
+        series.insert("a".to_string(), (1..=6).map(|d| (day(d), d as f64)).collect());
+        series.insert("b".to_string(), (1..=6).map(|d| (day(d), -2.0 * d as f64)).collect());
+        // Only two shared days with "a" - below the overlap threshold
+        series.insert("c".to_string(), vec![(day(1), 1.0), (day(2), 3.0), (day(20), 1.0)]);
+
+        let matrix = correlation_matrix(&series, MIN_OVERLAP_DAYS);
+        assert!((matrix[&("a".to_string(), "b".to_string())] + 1.0).abs() < 1e-12);
+        assert!(!matrix.contains_key(&("a".to_string(), "c".to_string())));
+    }
+}
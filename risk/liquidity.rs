@@ -0,0 +1,173 @@
+// Time-Bucketed Liquidity Profiles
+// Each symbol gets a relative liquidity score per UTC hour-of-week (1.0 = typical).
+// Thin hours shrink the allowed position size and demand wider stops,
+// instead of one static limit applied around the clock
+
+use std::collections::HashMap;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use sqlx::{PgPool, Row};
+
+pub const HOURS_PER_WEEK: usize = 168;
+
+#[derive(Debug, Clone)]
+pub struct LiquidityProfile {
+    pub symbol: String,
+    pub buckets: [f64; HOURS_PER_WEEK],  // index = weekday (Mon=0) * 24 + hour
+}
+
+/// Limits to apply for a symbol at a given moment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiquidityAdjustment {
+    pub score: f64,
+    pub size_multiplier: f64,  // applied to max position size
+    pub stop_multiplier: f64,  // applied to minimum stop distance
+}
+
+impl LiquidityProfile {
+    /// Heuristic profile for symbols without history: weekends are thin,
+    /// late weekend nights (Sat/Sun 20:00 - 06:00 UTC) thinnest
+    pub fn default_for(symbol: &str) -> Self {
+        let mut buckets = [1.0; HOURS_PER_WEEK];
+        for (i, bucket) in buckets.iter_mut().enumerate() {
+            let (weekday, hour) = (i / 24, i % 24);
+            let weekend = weekday >= 5;
+            let late_weekend = (weekday == 5 && hour >= 20)
+                || (weekday == 6 && !(6..20).contains(&hour))
+                || (weekday == 0 && hour < 6);
+
+            *bucket = if late_weekend {
+                0.4
+            } else if weekend {
+                0.6
+            } else if hour < 6 {
+                0.8
+            } else {
+                1.0
+            };
+        }
+
+        LiquidityProfile { symbol: symbol.to_string(), buckets }
+    }
+
+    /// Uniform liquidity - no time-of-day tightening
+    pub fn flat(symbol: &str) -> Self {
+        LiquidityProfile { symbol: symbol.to_string(), buckets: [1.0; HOURS_PER_WEEK] }
+    }
+
+    /// Build a profile from average volume per hour-of-week, normalized to the median bucket
+    pub fn from_hourly_volume(symbol: &str, volume: &[(usize, f64)]) -> Self {
+        let mut profile = Self::default_for(symbol);
+
+        let mut observed: Vec<f64> = volume.iter().map(|(_, v)| *v).filter(|v| *v > 0.0).collect();
+        if observed.is_empty() {
+            return profile;
+        }
+        observed.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let median = observed[observed.len() / 2];
+
+        for (bucket, avg_volume) in volume {
+            if *bucket < HOURS_PER_WEEK {
+                profile.buckets[*bucket] = (avg_volume / median).clamp(0.05, 3.0);
+            }
+        }
+
+        profile
+    }
+
+    pub fn score_at(&self, at: DateTime<Utc>) -> f64 {
+        self.buckets[bucket_index(at)]
+    }
+
+    pub fn adjustment_at(&self, at: DateTime<Utc>) -> LiquidityAdjustment {
+        let score = self.score_at(at);
+        if score >= 1.0 {
+            return LiquidityAdjustment { score, size_multiplier: 1.0, stop_multiplier: 1.0 };
+        }
+
+        LiquidityAdjustment {
+            score,
+            size_multiplier: score.clamp(0.25, 1.0),
+            stop_multiplier: (1.0 / score).min(3.0),
+        }
+    }
+}
+
+pub fn bucket_index(at: DateTime<Utc>) -> usize {
+    at.weekday().num_days_from_monday() as usize * 24 + at.hour() as usize
+}
+
+/// Profiles for the whole symbol universe, falling back to the heuristic default
+#[derive(Debug, Clone, Default)]
+pub struct LiquidityProfiles {
+    profiles: HashMap<String, LiquidityProfile>,
+}
+
+impl LiquidityProfiles {
+    pub fn insert(&mut self, profile: LiquidityProfile) {
+        self.profiles.insert(profile.symbol.clone(), profile);
+    }
+
+    pub fn adjustment(&self, symbol: &str, at: DateTime<Utc>) -> LiquidityAdjustment {
+        match self.profiles.get(symbol) {
+            Some(profile) => profile.adjustment_at(at),
+            None => LiquidityProfile::default_for(symbol).adjustment_at(at),
+        }
+    }
+}
+
+/// Learn hour-of-week volume profiles from the market_data table
+pub async fn load_profiles(db_pool: &PgPool, days: i32) -> Result<LiquidityProfiles, sqlx::Error> {
+    let query = "
+        SELECT symbol,
+               ((EXTRACT(ISODOW FROM timestamp AT TIME ZONE 'UTC')::int - 1) * 24
+                 + EXTRACT(HOUR FROM timestamp AT TIME ZONE 'UTC')::int) AS bucket,
+               AVG(volume)::float8 AS avg_volume
+        FROM market_data
+        WHERE timestamp > NOW() - make_interval(days => $1)
+          AND volume IS NOT NULL
+        GROUP BY symbol, bucket
+    ";
+
+    let rows = sqlx::query(query)
+        .bind(days)
+        .fetch_all(db_pool)
+        .await?;
+
+    let mut volume_by_symbol: HashMap<String, Vec<(usize, f64)>> = HashMap::new();
+    for row in rows {
+        let bucket: i32 = row.get("bucket");
+        let avg_volume: Option<f64> = row.get("avg_volume");
+        volume_by_symbol
+            .entry(row.get("symbol"))
+            .or_default()
+            .push((bucket as usize, avg_volume.unwrap_or(0.0)));
+    }
+
+    let mut profiles = LiquidityProfiles::default();
+    for (symbol, volume) in volume_by_symbol {
+        profiles.insert(LiquidityProfile::from_hourly_volume(&symbol, &volume));
+    }
+
+    Ok(profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_default_profile_tightens_late_weekend() {
+        let profile = LiquidityProfile::default_for("DOGE-USD");
+
+        // Wednesday 2025-01-15 14:00 UTC vs Sunday 2025-01-19 23:00 UTC
+        let weekday = Utc.with_ymd_and_hms(2025, 1, 15, 14, 0, 0).unwrap();
+        let late_sunday = Utc.with_ymd_and_hms(2025, 1, 19, 23, 0, 0).unwrap();
+
+        assert_eq!(profile.adjustment_at(weekday).size_multiplier, 1.0);
+
+        let thin = profile.adjustment_at(late_sunday);
+        assert!((thin.size_multiplier - 0.4).abs() < 1e-12);
+        assert!((thin.stop_multiplier - 2.5).abs() < 1e-12);
+    }
+}
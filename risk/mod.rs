@@ -0,0 +1,14 @@
+// Portfolio risk analytics
+// Pure measurement lives here; enforcement stays in core::risk_manager
+
+pub mod config;
+pub mod correlation;
+pub mod liquidity;
+pub mod var;
+
+#[cfg(test)]
+mod proptests;
+
+pub use config::{CapitalTier, LimitUpdate, RiskConfig};
+pub use liquidity::{LiquidityAdjustment, LiquidityProfile, LiquidityProfiles};
+pub use var::{VarReport, compute_var_report};
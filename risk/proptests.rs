@@ -0,0 +1,97 @@
+// Risk Properties - Sizing and Drawdown Invariants Under Random Inputs
+// The unit tests pin a handful of hand-picked cases; these throw randomized pattern stats,
+// limit configs and capital paths at RiskManager and check what must hold for all of them:
+// a position never exceeds the active max share of capital, a pattern with no edge is never
+// sized, any size is either zero or above the dust floor, and a drop from the daily high past
+// the drawdown limit always trips the emergency stop while a smaller one never does
+
+use proptest::prelude::*;
+
+use crate::core::money::{self, Decimal};
+use crate::core::risk_manager::{Pattern, RiskManager};
+use crate::risk::config::{CapitalTier, RiskConfig};
+
+fn pattern() -> impl Strategy<Value = Pattern> {
+    (0.0..=1.0f64, 0.0..1_000.0f64, -1_000.0..=0.0f64, -3.0..5.0f64).prop_map(|(win_rate, avg_win, avg_loss, sharpe)| Pattern {
+        hash: "prop".to_string(),
+        win_rate,
+        avg_win_amount: avg_win,
+        avg_loss_amount: avg_loss,
+        sharpe_ratio: sharpe,
+    })
+}
+
+/// Valid configs only: tiers tighten the base the way `validate` requires
+fn config() -> impl Strategy<Value = RiskConfig> {
+    (0.01..=0.40f64, 0.01..=0.50f64, 0.05..=0.50f64, 0.30..=0.70f64, 0.0..=50.0f64).prop_map(
+        |(max_pct, kelly, drawdown, min_win_rate, min_size)| {
+            let config = RiskConfig {
+                max_position_size_pct: max_pct,
+                kelly_fraction: kelly,
+                max_daily_drawdown_pct: drawdown,
+                min_win_rate,
+                min_position_size: min_size,
+                capital_tiers: vec![
+                    CapitalTier { min_capital: 10_000.0, max_position_size_pct: max_pct * 0.6, kelly_fraction: kelly * 0.8 },
+                    CapitalTier { min_capital: 100_000.0, max_position_size_pct: max_pct * 0.3, kelly_fraction: kelly * 0.6 },
+                ],
+                ..RiskConfig::default()
+            };
+            assert!(config.validate().is_empty(), "{:?}", config.validate());
+            config
+        },
+    )
+}
+
+/// $10 to $1M, to the cent
+fn capital() -> impl Strategy<Value = Decimal> {
+    (1_000i64..100_000_000).prop_map(|cents| Decimal::new(cents, 2))
+}
+
+proptest! {
+    #[test]
+    fn position_size_stays_within_the_active_cap(pattern in pattern(), config in config(), capital in capital(), share in 0.0..=1.0f64) {
+        let min_size = config.min_position_size;
+        let risk_manager = RiskManager::with_config(capital, config);
+        let available = money::share(capital, share);
+        let size = money::to_f64(risk_manager.calculate_position_size(&pattern, available));
+        let available = money::to_f64(available);
+        let (max_pct, _) = risk_manager.active_sizing_limits();
+
+        prop_assert!(size >= 0.0);
+        prop_assert!(size <= available * max_pct + 1e-9, "{} over {} * {}", size, available, max_pct);
+        prop_assert!(size == 0.0 || size >= min_size);
+    }
+
+    #[test]
+    fn no_edge_means_no_position(pattern in pattern(), config in config(), capital in capital()) {
+        let min_win_rate = config.min_win_rate;
+        let risk_manager = RiskManager::with_config(capital, config);
+        let avg_loss = pattern.avg_loss_amount.abs();
+        let edge = pattern.win_rate * pattern.avg_win_amount - (1.0 - pattern.win_rate) * avg_loss;
+
+        if edge <= 0.0 || pattern.win_rate < min_win_rate || avg_loss == 0.0 {
+            prop_assert_eq!(risk_manager.calculate_position_size(&pattern, capital), Decimal::ZERO);
+        }
+    }
+
+    #[test]
+    fn drawdown_past_the_limit_always_stops(config in config(), capital in capital(), gain in 0.0..2.0f64, drop in 0.0..=1.0f64) {
+        let limit = config.max_daily_drawdown_pct;
+        let risk_manager = RiskManager::with_config(capital, config);
+        let high = money::share(capital, 1.0 + gain);
+        risk_manager.update_capital(high);
+        risk_manager.update_capital(money::share(high, 1.0 - drop));
+        let high = risk_manager.daily_high();
+        let drawdown = money::ratio(high - risk_manager.current_capital(), high);
+
+        let allowed = risk_manager.check_risk_limits();
+        if drawdown > limit {
+            prop_assert!(!allowed);
+            prop_assert!(risk_manager.is_emergency_stopped());
+            prop_assert!(!risk_manager.check_risk_limits());
+        } else {
+            prop_assert!(!risk_manager.is_emergency_stopped(), "stopped at {} under {}", drawdown, limit);
+        }
+    }
+}
@@ -0,0 +1,221 @@
+// Value-at-Risk and Expected Shortfall
+// Historical and parametric (normal) estimates over the live portfolio's return series
+// All figures are positive loss fractions of capital: 0.12 = 12% of capital at risk
+
+use std::collections::{BTreeMap, HashMap};
+use chrono::NaiveDate;
+use serde::{Serialize, Deserialize};
+use sqlx::{PgPool, Row};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VarReport {
+    pub confidence: f64,
+    pub sample_size: usize,
+    pub historical_var: f64,
+    pub historical_es: f64,
+    pub parametric_var: f64,
+    pub parametric_es: f64,
+}
+
+impl VarReport {
+    /// The more pessimistic of the two VaR estimates, used for the circuit breaker
+    pub fn worst_var(&self) -> f64 {
+        self.historical_var.max(self.parametric_var)
+    }
+}
+
+pub fn compute_var_report(returns: &[f64], confidence: f64) -> VarReport {
+    VarReport {
+        confidence,
+        sample_size: returns.len(),
+        historical_var: historical_var(returns, confidence),
+        historical_es: historical_es(returns, confidence),
+        parametric_var: parametric_var(returns, confidence),
+        parametric_es: parametric_es(returns, confidence),
+    }
+}
+
+/// Loss at the (1 - confidence) quantile of the empirical distribution
+pub fn historical_var(returns: &[f64], confidence: f64) -> f64 {
+    if returns.is_empty() {
+        return 0.0;
+    }
+
+    let sorted = sorted(returns);
+    let index = tail_count(sorted.len(), confidence) - 1;
+    (-sorted[index]).max(0.0)
+}
+
+/// Average loss across the tail beyond the historical VaR
+pub fn historical_es(returns: &[f64], confidence: f64) -> f64 {
+    if returns.is_empty() {
+        return 0.0;
+    }
+
+    let sorted = sorted(returns);
+    let tail = &sorted[..tail_count(sorted.len(), confidence)];
+    let mean_tail = tail.iter().sum::<f64>() / tail.len() as f64;
+    (-mean_tail).max(0.0)
+}
+
+/// Normal-distribution VaR: -(mu - z * sigma)
+pub fn parametric_var(returns: &[f64], confidence: f64) -> f64 {
+    let Some((mean, std_dev)) = mean_std(returns) else {
+        return 0.0;
+    };
+
+    let z = inverse_normal_cdf(confidence);
+    (z * std_dev - mean).max(0.0)
+}
+
+/// Normal-distribution ES: -mu + sigma * phi(z) / (1 - confidence)
+pub fn parametric_es(returns: &[f64], confidence: f64) -> f64 {
+    let Some((mean, std_dev)) = mean_std(returns) else {
+        return 0.0;
+    };
+
+    let z = inverse_normal_cdf(confidence);
+    let density = (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt();
+    (std_dev * density / (1.0 - confidence) - mean).max(0.0)
+}
+
+/// Combine per-pattern daily returns into a portfolio series using position weights
+/// Days where a pattern has no result count as a flat day for that pattern
+pub fn portfolio_returns(
+    pattern_returns: &HashMap<String, Vec<(NaiveDate, f64)>>,
+    weights: &HashMap<String, f64>,
+) -> Vec<f64> {
+    let mut by_day: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+
+    for (hash, series) in pattern_returns {
+        let weight = weights.get(hash).copied().unwrap_or(0.0);
+        for (day, ret) in series {
+            *by_day.entry(*day).or_insert(0.0) += weight * ret;
+        }
+    }
+
+    by_day.into_values().collect()
+}
+
+/// Daily return series per pattern from stored test results
+/// Results without a recorded capital count as `test_capital` each
+pub async fn load_pattern_daily_returns(
+    db_pool: &PgPool,
+    pattern_hashes: &[String],
+    test_capital: f64,
+    days: i32,
+) -> Result<HashMap<String, Vec<(NaiveDate, f64)>>, sqlx::Error> {
+    let query = "
+        SELECT pattern_hash, DATE(timestamp) AS day, SUM(profit)::float8 AS profit,
+               SUM(COALESCE(capital, $3))::float8 AS deployed
+        FROM test_results
+        WHERE pattern_hash = ANY($1)
+          AND timestamp > NOW() - make_interval(days => $2)
+        GROUP BY pattern_hash, DATE(timestamp)
+        ORDER BY day
+    ";
+
+    let rows = sqlx::query(query)
+        .bind(pattern_hashes)
+        .bind(days)
+        .bind(test_capital)
+        .fetch_all(db_pool)
+        .await?;
+
+    let mut series: HashMap<String, Vec<(NaiveDate, f64)>> = HashMap::new();
+    for row in rows {
+        let profit: f64 = row.get("profit");
+        let deployed: f64 = row.get("deployed");
+        if deployed <= 0.0 {
+            continue;
+        }
+        series
+            .entry(row.get("pattern_hash"))
+            .or_default()
+            .push((row.get("day"), profit / deployed));
+    }
+
+    Ok(series)
+}
+
+fn sorted(returns: &[f64]) -> Vec<f64> {
+    let mut sorted = returns.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    sorted
+}
+
+/// Number of observations in the loss tail, never fewer than one
+fn tail_count(len: usize, confidence: f64) -> usize {
+    // Epsilon keeps float noise (1 - 0.95 = 0.05000000000000004) from adding an observation
+    (((1.0 - confidence) * len as f64 - 1e-9).ceil() as usize).clamp(1, len)
+}
+
+fn mean_std(returns: &[f64]) -> Option<(f64, f64)> {
+    if returns.len() < 2 {
+        return None;
+    }
+
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance = returns.iter()
+        .map(|r| (r - mean).powi(2))
+        .sum::<f64>() / (returns.len() - 1) as f64;
+
+    Some((mean, variance.sqrt()))
+}
+
+/// Acklam's rational approximation of the standard normal quantile (|error| < 1.2e-9)
+fn inverse_normal_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2,
+                         1.38357751867269e2, -3.066479806614716e1, 2.506628277459239e0];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2,
+                         6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838e0,
+                         -2.549732539343734e0, 4.374664141464968e0, 2.938163982698783e0];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996e0,
+                         3.754408661907416e0];
+
+    let p = p.clamp(1e-12, 1.0 - 1e-12);
+    let p_low = 0.02425;
+
+    if p < p_low {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - p_low {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -(((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_historical_var_and_es() {
+        // 20 observations: the worst 5% is the single -10% day
+        let mut returns = vec![0.01; 19];
+        returns.push(-0.10);
+
+        assert!((historical_var(&returns, 0.95) - 0.10).abs() < 1e-12);
+        assert!((historical_es(&returns, 0.95) - 0.10).abs() < 1e-12);
+        assert_eq!(historical_var(&[0.02, 0.03], 0.95), 0.0);
+    }
+
+    #[test]
+    fn test_parametric_var() {
+        assert!((inverse_normal_cdf(0.975) - 1.959964).abs() < 1e-5);
+
+        let returns = [-0.02, 0.0, 0.02, -0.02, 0.0, 0.02];
+        let var = parametric_var(&returns, 0.95);
+        let es = parametric_es(&returns, 0.95);
+        assert!(var > 0.0);
+        assert!(es > var);
+    }
+}